@@ -0,0 +1,763 @@
+//! A compact binary encoding of parsed programs, so a build cache can reuse the AST of a file whose
+//! source has not changed instead of lexing and parsing it again.
+//!
+//! [`Encode`] writes a value into a byte buffer and [`Decode`] reads it back. Decoding allocates the
+//! arena-backed parts of the tree (boxed sub-expressions, statement blocks) in the arena the
+//! [`Reader`] carries, so a decoded program has the same shape and lifetime as a freshly parsed one.
+//! Integers are LEB128 varints (signed ones zigzag-encoded), and enums a one-byte tag followed by
+//! their fields. Decoding malformed input fails with `None` rather than panicking.
+//!
+//! [`codec_struct!`](crate::codec_struct) and [`codec_enum!`](crate::codec_enum) implement both
+//! traits for a type from the list of its fields (or of its variants and their tags); the compiler
+//! uses them for its own cached data too.
+
+use crate::nodes::function::AccessorKind;
+use crate::nodes::{
+    AttributeNode, CatchClause, EnumDeclarationNode, EnumVariantNode, ExpressionNode, ExtendNode,
+    FunctionNode, GenericBound, GlobalVariableNode, ImportNode, InterfaceDeclarationNode,
+    LambdaBody, LambdaNode, LambdaParameter, ParameterNode, PatternNode, ProgramNode,
+    StatementNode, StructDeclarationNode, StructFieldNode, SwitchArm, SwitchArmBody, Type,
+};
+use crate::token::syntax_token::SyntaxToken;
+use crate::token::syntax_trivia::SyntaxTrivia;
+use crate::token::token_kind::TokenKind;
+use bumpalo::Bump;
+use dream_text::text_span::TextSpan;
+use std::convert::{TryFrom, TryInto};
+use std::rc::Rc;
+
+/// A value that can be written with [`encode`].
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// A value that can be read back with [`decode`], allocating into the arena of lifetime `'a`.
+pub trait Decode<'a>: Sized {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self>;
+}
+
+/// The bytes being decoded, and the arena decoded nodes are allocated in.
+pub struct Reader<'b, 'a> {
+    bytes: &'b [u8],
+    arena: &'a Bump,
+}
+
+impl<'b, 'a> Reader<'b, 'a> {
+    pub fn new(bytes: &'b [u8], arena: &'a Bump) -> Self {
+        Reader { bytes, arena }
+    }
+
+    pub fn arena(&self) -> &'a Bump {
+        self.arena
+    }
+
+    pub fn byte(&mut self) -> Option<u8> {
+        let (&first, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(first)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'b [u8]> {
+        if len > self.bytes.len() {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    /// A collection length, bounded by the bytes left so a corrupt length cannot make the decoder
+    /// reserve more memory than the input could describe.
+    fn len(&mut self) -> Option<usize> {
+        let len = usize::decode(self)?;
+        if len > self.bytes.len() {
+            return None;
+        }
+        Some(len)
+    }
+}
+
+/// Encodes `value` into a fresh buffer.
+pub fn encode<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// Decodes a `T` from the whole of `bytes`, allocating into `arena`. `None` when the bytes are not
+/// an encoded `T`, including when any are left over.
+pub fn decode<'a, T: Decode<'a>>(bytes: &[u8], arena: &'a Bump) -> Option<T> {
+    let mut r = Reader::new(bytes, arena);
+    let value = T::decode(&mut r)?;
+    if r.bytes.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Implements [`Encode`] and [`Decode`] for a struct from its field names, which are written in
+/// the order listed. A struct with a lifetime is written `Name<'a>`.
+#[macro_export]
+macro_rules! codec_struct {
+    ($name:ident<$lt:lifetime> { $($field:ident),* $(,)? }) => {
+        impl<$lt> $crate::codec::Encode for $name<$lt> {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $($crate::codec::Encode::encode(&self.$field, out);)*
+            }
+        }
+        impl<$lt> $crate::codec::Decode<$lt> for $name<$lt> {
+            fn decode(r: &mut $crate::codec::Reader<'_, $lt>) -> Option<Self> {
+                Some($name {
+                    $($field: $crate::codec::Decode::decode(r)?,)*
+                })
+            }
+        }
+    };
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::codec::Encode for $name {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $($crate::codec::Encode::encode(&self.$field, out);)*
+            }
+        }
+        impl<'a> $crate::codec::Decode<'a> for $name {
+            fn decode(r: &mut $crate::codec::Reader<'_, 'a>) -> Option<Self> {
+                Some($name {
+                    $($field: $crate::codec::Decode::decode(r)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implements [`Encode`] and [`Decode`] for an enum from its variants, each given the tag byte it
+/// is written with and names for its fields (`3 => Pair(left, right)`, `4 => Point { x, y }`, or
+/// a bare `5 => Empty`). Tags are part of the format: a variant keeps its tag when others are added.
+#[macro_export]
+macro_rules! codec_enum {
+    ($name:ident<$lt:lifetime> { $($body:tt)* }) => {
+        impl<$lt> $crate::codec::Encode for $name<$lt> {
+            $crate::codec_enum!(@encode $name { $($body)* });
+        }
+        impl<$lt> $crate::codec::Decode<$lt> for $name<$lt> {
+            $crate::codec_enum!(@decode $lt $name { $($body)* });
+        }
+    };
+    ($name:ident { $($body:tt)* }) => {
+        impl $crate::codec::Encode for $name {
+            $crate::codec_enum!(@encode $name { $($body)* });
+        }
+        impl<'a> $crate::codec::Decode<'a> for $name {
+            $crate::codec_enum!(@decode 'a $name { $($body)* });
+        }
+    };
+    (@encode $name:ident {
+        $($tag:literal => $variant:ident $(($($field:ident),*))? $({ $($named:ident),* })?),* $(,)?
+    }) => {
+        fn encode(&self, out: &mut Vec<u8>) {
+            match self {
+                $($name::$variant $(($($field),*))? $({ $($named),* })? => {
+                    out.push($tag);
+                    $($($crate::codec::Encode::encode($field, out);)*)?
+                    $($($crate::codec::Encode::encode($named, out);)*)?
+                })*
+            }
+        }
+    };
+    (@decode $lt:lifetime $name:ident {
+        $($tag:literal => $variant:ident $(($($field:ident),*))? $({ $($named:ident),* })?),* $(,)?
+    }) => {
+        fn decode(r: &mut $crate::codec::Reader<'_, $lt>) -> Option<Self> {
+            Some(match r.byte()? {
+                $($tag => $name::$variant
+                    $(($({
+                        let $field = $crate::codec::Decode::decode(r)?;
+                        $field
+                    }),*))?
+                    $({ $($named: $crate::codec::Decode::decode(r)?),* })?,)*
+                _ => return None,
+            })
+        }
+    };
+}
+
+impl Encode for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+}
+
+impl<'a> Decode<'a> for u8 {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        r.byte()
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut value = *self;
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+}
+
+impl<'a> Decode<'a> for u64 {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = r.byte()?;
+            if shift > 63 {
+                return None;
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+            shift += 7;
+        }
+    }
+}
+
+impl Encode for i64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (((*self << 1) ^ (*self >> 63)) as u64).encode(out);
+    }
+}
+
+impl<'a> Decode<'a> for i64 {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let value = u64::decode(r)?;
+        Some((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+}
+
+/// The narrower integers go through their 64-bit counterpart and fail to decode when out of range.
+macro_rules! codec_int {
+    ($($int:ty => $wide:ty),*) => {
+        $(
+            impl Encode for $int {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    (*self as $wide).encode(out);
+                }
+            }
+
+            impl<'a> Decode<'a> for $int {
+                fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+                    <$int>::try_from(<$wide>::decode(r)?).ok()
+                }
+            }
+        )*
+    };
+}
+
+codec_int!(u32 => u64, usize => u64, i32 => i64);
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl<'a> Decode<'a> for bool {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        match r.byte()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Encode for f64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl<'a> Decode<'a> for f64 {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        Some(f64::from_le_bytes(r.take(8)?.try_into().ok()?))
+    }
+}
+
+impl Encode for f32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl<'a> Decode<'a> for f32 {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        Some(f32::from_le_bytes(r.take(4)?.try_into().ok()?))
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+}
+
+impl<'a> Decode<'a> for char {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        char::from_u32(u32::decode(r)?)
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl<'a> Decode<'a> for String {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let len = r.len()?;
+        String::from_utf8(r.take(len)?.to_vec()).ok()
+    }
+}
+
+impl Encode for Rc<str> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl<'a> Decode<'a> for Rc<str> {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        Some(Rc::from(String::decode(r)?))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for Option<T> {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        match r.byte()? {
+            0 => Some(None),
+            1 => Some(Some(T::decode(r)?)),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for Vec<T> {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let len = r.len()?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::decode(r)?);
+        }
+        Some(items)
+    }
+}
+
+impl<T: Encode> Encode for Box<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for Box<T> {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        Some(Box::new(T::decode(r)?))
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for &'a T {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let value = T::decode(r)?;
+        Some(r.arena().alloc(value))
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for &'a [T] {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let items = Vec::<T>::decode(r)?;
+        Some(r.arena().alloc_slice_fill_iter(items))
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+}
+
+impl<'a, A: Decode<'a>, B: Decode<'a>> Decode<'a> for (A, B) {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        let first = A::decode(r)?;
+        let second = B::decode(r)?;
+        Some((first, second))
+    }
+}
+
+/// Every [`TokenKind`] in declaration order, so a kind is written as its discriminant and read back
+/// by indexing. A kind missing from the end of this list fails to decode, which a cache treats as a
+/// miss.
+const TOKEN_KINDS: [TokenKind; 87] = [
+    TokenKind::EndOfFileToken,
+    TokenKind::WhiteSpaceToken,
+    TokenKind::BadToken,
+    TokenKind::IdentifierToken,
+    TokenKind::NumberToken,
+    TokenKind::StringToken,
+    TokenKind::InterpolatedStringToken,
+    TokenKind::CharToken,
+    TokenKind::BooleanToken,
+    TokenKind::PlusToken,
+    TokenKind::MinusToken,
+    TokenKind::SlashToken,
+    TokenKind::StarToken,
+    TokenKind::BangToken,
+    TokenKind::ModulusToken,
+    TokenKind::PlusEqualToken,
+    TokenKind::MinusEqualToken,
+    TokenKind::StarEqualToken,
+    TokenKind::SlashEqualToken,
+    TokenKind::ModulusEqualToken,
+    TokenKind::PlusPlusToken,
+    TokenKind::MinusMinusToken,
+    TokenKind::EqualEqualToken,
+    TokenKind::NotEqualToken,
+    TokenKind::AmpersandAmpersandToken,
+    TokenKind::PipePipeToken,
+    TokenKind::BitWisePipeToken,
+    TokenKind::BitWiseAmpersandToken,
+    TokenKind::BitWiseXorToken,
+    TokenKind::ShiftLeftToken,
+    TokenKind::ShiftRightToken,
+    TokenKind::QuestionQuestionToken,
+    TokenKind::FatArrowToken,
+    TokenKind::EqualToken,
+    TokenKind::GreaterThanEqualToken,
+    TokenKind::GreaterThanToken,
+    TokenKind::SmallerThanToken,
+    TokenKind::SmallerThanEqualToken,
+    TokenKind::SemicolonToken,
+    TokenKind::ColonToken,
+    TokenKind::CommaToken,
+    TokenKind::DotToken,
+    TokenKind::DotDotEqualToken,
+    TokenKind::QuestionMarkToken,
+    TokenKind::OpenParenthesisToken,
+    TokenKind::CloseParenthesisToken,
+    TokenKind::CurlyOpenBracketToken,
+    TokenKind::CurlyCloseBracketToken,
+    TokenKind::OpenBracketToken,
+    TokenKind::CloseBracketToken,
+    TokenKind::IfToken,
+    TokenKind::ElseToken,
+    TokenKind::ForToken,
+    TokenKind::WhileToken,
+    TokenKind::DoToken,
+    TokenKind::ReturnToken,
+    TokenKind::BreakToken,
+    TokenKind::ContinueToken,
+    TokenKind::ThrowToken,
+    TokenKind::TryToken,
+    TokenKind::CatchToken,
+    TokenKind::FinallyToken,
+    TokenKind::LetToken,
+    TokenKind::ConstToken,
+    TokenKind::FunToken,
+    TokenKind::AsyncToken,
+    TokenKind::AwaitToken,
+    TokenKind::StaticToken,
+    TokenKind::VirtualToken,
+    TokenKind::ImportToken,
+    TokenKind::PublicToken,
+    TokenKind::ExternToken,
+    TokenKind::ClassToken,
+    TokenKind::InterfaceToken,
+    TokenKind::ExtendToken,
+    TokenKind::NullToken,
+    TokenKind::IsToken,
+    TokenKind::InToken,
+    TokenKind::EnumToken,
+    TokenKind::TypeToken,
+    TokenKind::SwitchToken,
+    TokenKind::CaseToken,
+    TokenKind::DefaultToken,
+    TokenKind::AtToken,
+    TokenKind::DataTypeToken,
+    TokenKind::LineCommentToken,
+    TokenKind::BlockCommentToken,
+];
+
+impl Encode for TokenKind {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl<'a> Decode<'a> for TokenKind {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        TOKEN_KINDS.get(r.byte()? as usize).copied()
+    }
+}
+
+codec_struct!(TextSpan {
+    start,
+    end,
+    line_no,
+    col_no
+});
+codec_struct!(SyntaxTrivia {
+    kind,
+    position,
+    text
+});
+codec_struct!(SyntaxToken {
+    kind,
+    position,
+    text,
+    leading_trivia,
+    trailing_trivia
+});
+
+codec_enum!(Type {
+    0 => Integer(token),
+    1 => Float(token),
+    2 => Double(token),
+    3 => String(token),
+    4 => Boolean(token),
+    5 => Char(token),
+    6 => Long(token),
+    7 => UInt(token),
+    8 => ULong(token),
+    9 => Byte(token),
+    10 => Object(token),
+    11 => Array(element),
+    12 => Struct(name, arguments),
+    13 => Generic(name),
+    14 => Nullable(inner),
+    15 => Function(parameters, ret),
+    16 => Void,
+    17 => Unknown,
+});
+
+codec_enum!(PatternNode {
+    0 => Wildcard(token),
+    1 => Binding(name),
+    2 => Literal(value),
+    3 => Variant(qualifier, name, fields),
+    4 => Tuple(paren, elements),
+    5 => Range(low, high),
+    6 => Or(alternatives),
+    7 => TypeTest(ty, name),
+    8 => Class(ty, fields),
+});
+
+codec_enum!(ExpressionNode<'a> {
+    0 => Literal(value),
+    1 => ArrayLiteral(elements),
+    2 => Tuple(paren, elements),
+    3 => Binary(left, operator, right),
+    4 => Unary(operator, operand),
+    5 => Identifier(name),
+    6 => Parenthesized(inner),
+    7 => FunctionCall(name, type_arguments, arguments),
+    8 => IndexAccess(array, index),
+    9 => Cast(ty, value),
+    10 => MemberAccess(receiver, member),
+    11 => IsExpression(value, ty, binding),
+    12 => MethodCall(receiver, name, type_arguments, arguments),
+    13 => Ternary(condition, then_value, else_value),
+    14 => Await(future),
+    15 => Propagate(value, question),
+    16 => Switch(subject, arms),
+    17 => Lambda(lambda),
+});
+
+codec_struct!(LambdaNode<'a> {
+    arrow,
+    parameters,
+    return_type,
+    body
+});
+codec_struct!(LambdaParameter { name, type_ });
+codec_enum!(LambdaBody<'a> {
+    0 => Expr(value),
+    1 => Block(body),
+});
+codec_struct!(SwitchArm<'a> {
+    pattern,
+    guard,
+    body
+});
+codec_enum!(SwitchArmBody<'a> {
+    0 => Expr(value),
+    1 => Block(body),
+});
+
+codec_enum!(StatementNode<'a> {
+    0 => Assignment(name, value),
+    1 => IndexAssignment(target, index, value),
+    2 => MemberAssignment(target, member, value),
+    3 => Declaration(name, ty, value, is_const),
+    4 => DestructuringDeclaration(pattern, value, is_const),
+    5 => FunctionInvocation(name, type_arguments, arguments),
+    6 => MethodInvocation(receiver, name, type_arguments, arguments),
+    7 => Return(value),
+    8 => IfElse(condition, then_body, else_ifs, else_body),
+    9 => While(condition, body),
+    10 => DoWhile(body, condition),
+    11 => For(init, condition, step, body),
+    12 => Labeled(label, inner),
+    13 => Break(label),
+    14 => Continue(label),
+    15 => ExpressionStatement(value),
+    16 => AwaitStmt(future),
+    17 => ForEach(element, iterable, index, array, body),
+    18 => Switch(subject, cases, default),
+    19 => Throw(keyword, value),
+    20 => Try(keyword, body, catches, finally),
+});
+
+codec_struct!(CatchClause<'a> { name, type_, body });
+
+codec_struct!(AttributeNode { name, args });
+codec_struct!(GenericBound { parameter, bounds });
+codec_struct!(ParameterNode {
+    name,
+    type_,
+    default
+});
+codec_enum!(AccessorKind {
+    0 => Get,
+    1 => Set,
+});
+codec_struct!(FunctionNode<'a> {
+    attributes,
+    name,
+    generic_parameters,
+    generic_bounds,
+    return_type,
+    parameters,
+    body,
+    is_public,
+    is_extern,
+    is_static,
+    is_async,
+    is_virtual,
+    file_path,
+    accessor,
+    has_default_body,
+});
+codec_struct!(StructFieldNode {
+    attributes,
+    name,
+    is_public,
+    type_token,
+    field_type,
+});
+codec_struct!(StructDeclarationNode<'a> {
+    attributes,
+    name,
+    generic_parameters,
+    generic_bounds,
+    fields,
+    methods,
+    implements,
+    is_public,
+    file_path,
+});
+codec_struct!(InterfaceDeclarationNode<'a> {
+    attributes,
+    name,
+    generic_parameters,
+    generic_bounds,
+    methods,
+    is_public,
+    file_path,
+});
+codec_struct!(GlobalVariableNode<'a> {
+    name,
+    declared_type,
+    initializer,
+    is_const,
+    is_public,
+    is_static,
+    file_path,
+});
+codec_struct!(ImportNode {
+    module_name,
+    alias,
+    names
+});
+codec_struct!(EnumVariantNode {
+    name,
+    fields,
+    value
+});
+codec_struct!(EnumDeclarationNode {
+    attributes,
+    name,
+    generic_parameters,
+    variants,
+    is_public,
+    file_path,
+});
+codec_struct!(ExtendNode<'a> {
+    target,
+    generic_parameters,
+    methods,
+    file_path
+});
+codec_struct!(ProgramNode<'a> {
+    imports,
+    structs,
+    interfaces,
+    functions,
+    enums,
+    extends,
+    globals,
+});
+
+#[cfg(test)]
+#[path = "tests/codec_tests.rs"]
+mod tests;
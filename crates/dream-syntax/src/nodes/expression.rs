@@ -9,6 +9,8 @@ use crate::token::syntax_token::SyntaxToken;
 pub enum ExpressionNode<'a> {
     Literal(Type),
     ArrayLiteral(Vec<ExpressionNode<'a>>),
+    /// `(a, b, ...)` — a tuple literal of two or more elements. The token is the opening `(`.
+    Tuple(SyntaxToken, Vec<ExpressionNode<'a>>),
     Binary(&'a ExpressionNode<'a>, SyntaxToken, &'a ExpressionNode<'a>),
     Unary(SyntaxToken, &'a ExpressionNode<'a>),
     Identifier(SyntaxToken),
@@ -36,11 +38,45 @@ pub enum ExpressionNode<'a> {
     /// `await <future-expr>`: suspends the enclosing `async` function until the awaited
     /// `Future<T>` resolves, then yields its `T`. The inner expression produces the future.
     Await(&'a ExpressionNode<'a>),
+    /// `expr?` — unwraps an `Option`/`Result`, returning its `None`/`Err` from the enclosing
+    /// function instead. The token is the `?`.
+    Propagate(&'a ExpressionNode<'a>, SyntaxToken),
     /// `switch (subject) { pattern [if guard] => body, ... }` in its pattern-matching form. Used
     /// both as an expression (every arm yields a value of a common type) and, when wrapped in an
     /// `ExpressionStatement`, as a statement (arms may be blocks yielding `void`). The first field
     /// is the subject. (The C-style `switch` with `case`/`default` is `StatementNode::Switch`.)
     Switch(&'a ExpressionNode<'a>, Vec<SwitchArm<'a>>),
+    /// `(params) => body` (or `x => body`) — an anonymous function value that may capture the
+    /// locals of the enclosing function(s).
+    Lambda(&'a LambdaNode<'a>),
+}
+
+/// A lambda expression: `(a, b: int): int => expr` or `(a) => { stmts }`.
+#[derive(Debug, Clone)]
+pub struct LambdaNode<'a> {
+    /// The `=>` token, used as the lambda's source position.
+    pub arrow: SyntaxToken,
+    pub parameters: Vec<LambdaParameter>,
+    /// The `: Type` annotation after the parameter list, if written.
+    pub return_type: Option<Type>,
+    pub body: LambdaBody<'a>,
+}
+
+/// One lambda parameter. Unlike a function parameter, the type annotation is optional: an
+/// unannotated parameter takes its type from the function type the lambda is expected to have.
+#[derive(Debug, Clone)]
+pub struct LambdaParameter {
+    pub name: SyntaxToken,
+    pub type_: Option<Type>,
+}
+
+/// The body of a lambda.
+#[derive(Debug, Clone)]
+pub enum LambdaBody<'a> {
+    /// `=> expr` - the expression's value is returned.
+    Expr(&'a ExpressionNode<'a>),
+    /// `=> { stmts }` - an ordinary function body.
+    Block(&'a [StatementNode<'a>]),
 }
 
 /// One arm of a pattern-matching `switch`: a pattern, an optional `if` guard, and a body.
@@ -56,7 +92,7 @@ pub struct SwitchArm<'a> {
 #[derive(Debug, Clone)]
 pub enum SwitchArmBody<'a> {
     /// `=> expr` - yields the expression's value (the only form allowed in expression position).
-    Expr(ExpressionNode<'a>),
+    Expr(&'a ExpressionNode<'a>),
     /// `=> { stmts }` - a statement block yielding `void` (only allowed in statement position).
     Block(&'a [StatementNode<'a>]),
 }
@@ -74,7 +110,9 @@ impl<'a> ExpressionNode<'a> {
             | ExpressionNode::MemberAccess(_, token)
             | ExpressionNode::MethodCall(_, token, _, _)
             | ExpressionNode::Binary(_, token, _)
-            | ExpressionNode::Unary(token, _) => Some(token.position),
+            | ExpressionNode::Unary(token, _)
+            | ExpressionNode::Tuple(token, _)
+            | ExpressionNode::Propagate(_, token) => Some(token.position),
             ExpressionNode::Parenthesized(inner)
             | ExpressionNode::Await(inner)
             | ExpressionNode::IsExpression(inner, _, _) => inner.position(),
@@ -85,6 +123,7 @@ impl<'a> ExpressionNode<'a> {
                 target_type.get_span().or_else(|| expr.position())
             }
             ExpressionNode::ArrayLiteral(elements) => elements.first().and_then(|e| e.position()),
+            ExpressionNode::Lambda(lambda) => Some(lambda.arrow.position),
         }
     }
 
@@ -106,9 +145,15 @@ impl<'a> ExpressionNode<'a> {
             | ExpressionNode::IsExpression(inner, _, _) => inner.start_position(),
             ExpressionNode::Switch(subject, _) => subject.start_position(),
             ExpressionNode::Ternary(cond, _, _) => cond.start_position(),
+            ExpressionNode::Propagate(inner, _) => inner.start_position(),
             ExpressionNode::ArrayLiteral(elements) => {
                 elements.first().and_then(|e| e.start_position())
             }
+            ExpressionNode::Lambda(lambda) => lambda
+                .parameters
+                .first()
+                .map(|p| p.name.position)
+                .or_else(|| self.position()),
             // Token-led forms (identifier, call name, unary operator, cast type, literal) already
             // start at the token `position` returns.
             _ => self.position(),
@@ -76,6 +76,9 @@ pub struct FunctionNode<'a> {
     pub attributes: Vec<crate::nodes::AttributeNode>,
     pub name: SyntaxToken,
     pub generic_parameters: Option<Vec<SyntaxToken>>,
+    /// Interface bounds on the generic parameters, inline or from a `where` clause. Empty when
+    /// every parameter is unbounded.
+    pub generic_bounds: Vec<crate::nodes::GenericBound>,
     pub return_type: Option<Type>,
     pub parameters: Vec<ParameterNode>,
     pub body: &'a [StatementNode<'a>],
@@ -92,12 +95,19 @@ pub struct FunctionNode<'a> {
     /// True for `async fun` declarations: calling the function eagerly starts a task and yields a
     /// `Future<T>` handle. The body is lowered to a resumable state machine driven by the scheduler.
     pub is_async: bool,
+    /// True for `virtual fun` class methods: calls on a receiver of the declaring class (or a
+    /// subclass) dispatch through the class vtable, so a subclass `@override` replaces the body.
+    pub is_virtual: bool,
     /// Source file this declaration came from; set during multi-file merge so semantic
     /// diagnostics can report the correct file. `None` for synthesized nodes.
     pub file_path: Option<Rc<str>>,
     /// `Some` when this is a TypeScript-style property accessor (`get`/`set`) rather than an
     /// ordinary method; `name` then holds the property name. `None` for normal methods/functions.
     pub accessor: Option<AccessorKind>,
+    /// True for an interface method declared with a `{ ... }` body, which implementing classes
+    /// inherit unless they define the method themselves. False for bare interface signatures and
+    /// every non-interface function (an empty default body is still a default).
+    pub has_default_body: bool,
 }
 
 impl<'a> FunctionNode<'a> {
@@ -115,6 +125,7 @@ impl<'a> FunctionNode<'a> {
             attributes,
             name,
             generic_parameters,
+            generic_bounds: Vec::new(),
             return_type,
             parameters,
             body,
@@ -122,8 +133,10 @@ impl<'a> FunctionNode<'a> {
             is_extern: false,
             is_static: false,
             is_async: false,
+            is_virtual: false,
             file_path: None,
             accessor: None,
+            has_default_body: false,
         }
     }
 }
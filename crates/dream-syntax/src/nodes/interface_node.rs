@@ -2,8 +2,8 @@ use crate::token::syntax_token::SyntaxToken;
 use std::rc::Rc;
 
 /// An `interface` declaration: a named set of method signatures a class can implement. Interfaces
-/// declare method signatures only (no instance fields, no default bodies in v1); a class satisfies
-/// an interface by providing a matching method for each signature. Interfaces cannot be
+/// have no instance fields; a class satisfies an interface by providing a matching method for each
+/// signature, or by inheriting the interface's default body for it. Interfaces cannot be
 /// instantiated; an interface-typed value is a tagged object pointer whose method calls dispatch
 /// dynamically through the object's runtime tag (see itable dispatch in codegen).
 #[derive(Debug, Clone)]
@@ -11,8 +11,10 @@ pub struct InterfaceDeclarationNode<'a> {
     pub attributes: Vec<crate::nodes::AttributeNode>,
     pub name: SyntaxToken,
     pub generic_parameters: Option<Vec<SyntaxToken>>,
-    /// The interface's method signatures. Each is a body-less [`FunctionNode`] (parsed like an
-    /// `extern fun ...;`); only the name/params/return type are meaningful.
+    /// Interface bounds on the generic parameters; empty when every parameter is unbounded.
+    pub generic_bounds: Vec<crate::nodes::GenericBound>,
+    /// The interface's methods. A bare signature has an empty body; a method with a default body
+    /// is marked [`FunctionNode::has_default_body`].
     pub methods: Vec<crate::nodes::function::FunctionNode<'a>>,
     /// True when the interface is marked `public`.
     pub is_public: bool,
@@ -33,6 +35,7 @@ impl<'a> InterfaceDeclarationNode<'a> {
             attributes,
             name,
             generic_parameters,
+            generic_bounds: Vec::new(),
             methods,
             is_public,
             file_path: None,
@@ -7,16 +7,18 @@ pub mod statement;
 pub mod struct_node;
 pub mod types;
 
-pub use expression::{ExpressionNode, SwitchArm, SwitchArmBody};
+pub use expression::{
+    ExpressionNode, LambdaBody, LambdaNode, LambdaParameter, SwitchArm, SwitchArmBody,
+};
 pub use function::{FunctionNode, ParameterNode};
 pub use interface_node::InterfaceDeclarationNode;
 pub use pattern::PatternNode;
 pub use program::{
     EnumDeclarationNode, EnumVariantNode, ExtendNode, GlobalVariableNode, ImportNode, ProgramNode,
 };
-pub use statement::StatementNode;
+pub use statement::{CatchClause, StatementNode};
 pub use struct_node::{StructDeclarationNode, StructFieldNode};
-pub use types::Type;
+pub use types::{GenericBound, Type};
 
 use crate::token::syntax_token::SyntaxToken;
 
@@ -2,8 +2,9 @@ use super::types::Type;
 use dream_text::text_span::TextSpan;
 use crate::token::syntax_token::SyntaxToken;
 
-/// A pattern matched by a `match` arm. Patterns may nest (a variant's sub-patterns are themselves
-/// patterns), enabling forms like `Pair(Some(x), None)`.
+/// A pattern matched by a `switch` arm. Patterns may nest (a variant's sub-patterns are themselves
+/// patterns), enabling forms like `Pair(Some(x), None)` or `Line { from: Point { x: 0 } }`.
+/// Tuple patterns also destructure `let (a, b) = ...;` declarations.
 #[derive(Debug, Clone)]
 pub enum PatternNode {
     /// `_` - matches anything and binds nothing.
@@ -16,15 +17,38 @@ pub enum PatternNode {
     /// name, and the sub-patterns for its payload fields (positional, in declaration order).
     /// A unit variant has no sub-patterns.
     Variant(Option<SyntaxToken>, SyntaxToken, Vec<PatternNode>),
+    /// A tuple pattern `(p0, p1, ...)`: matches a tuple of the same arity whose elements match the
+    /// sub-patterns positionally. The token is the opening `(`.
+    Tuple(SyntaxToken, Vec<PatternNode>),
+    /// An inclusive range `lo..=hi` of integer or char literals - matches when the subject lies
+    /// between the two bounds.
+    Range(Type, Type),
+    /// An or-pattern `p1 | p2 | ...` - matches when any alternative does. Alternatives may not
+    /// bind names, since a binding would be unset whenever a different alternative matched.
+    Or(Vec<PatternNode>),
+    /// A type pattern `Circle c` (or `Circle _`): matches when the subject's runtime type is the
+    /// named type, binding the narrowed value to the name.
+    TypeTest(Type, SyntaxToken),
+    /// A class pattern `Point { x: 0, y }`: matches a value of the named class whose listed fields
+    /// match their sub-patterns. A bare field name is shorthand for `field: field`, binding it.
+    Class(Type, Vec<(SyntaxToken, PatternNode)>),
 }
 
 impl PatternNode {
     /// A representative source span for diagnostics.
     pub fn position(&self) -> Option<TextSpan> {
         match self {
-            PatternNode::Wildcard(t) | PatternNode::Binding(t) => Some(t.position),
+            PatternNode::Wildcard(t) | PatternNode::Binding(t) | PatternNode::Tuple(t, _) => {
+                Some(t.position)
+            }
             PatternNode::Literal(ty) => ty.get_span(),
             PatternNode::Variant(_, name, _) => Some(name.position),
+            PatternNode::Range(lo, _) => lo.get_span(),
+            PatternNode::Or(alternatives) => alternatives.first().and_then(|p| p.position()),
+            PatternNode::TypeTest(ty, binding) => ty.get_span().or(Some(binding.position)),
+            PatternNode::Class(ty, fields) => ty
+                .get_span()
+                .or_else(|| fields.first().map(|(name, _)| name.position)),
         }
     }
 }
@@ -27,16 +27,41 @@ pub struct GlobalVariableNode<'a> {
     pub file_path: Option<Rc<str>>,
 }
 
-/// Represents an import declaration in the AST
+/// Represents an import declaration in the AST. Three forms share this node:
+/// - `import geometry.shapes;` brings the module's public declarations into scope and lets them be
+///   qualified with the last path segment (`shapes.Circle`).
+/// - `import geometry.shapes as gs;` only binds the qualifier (`gs.Circle`).
+/// - `import { Circle, area } from geometry.shapes;` brings just the listed names into scope.
 #[derive(Debug, Clone)]
 pub struct ImportNode {
+    /// The module path with `.` already mapped to `/` (`geometry/shapes`).
     pub module_name: SyntaxToken,
+    /// The `as` qualifier, if written.
+    pub alias: Option<SyntaxToken>,
+    /// The names of a selective `import { ... } from` form; `None` for the other two forms.
+    pub names: Option<Vec<SyntaxToken>>,
 }
 
 impl ImportNode {
     /// Creates a new import node
     pub fn new(module_name: SyntaxToken) -> ImportNode {
-        ImportNode { module_name }
+        ImportNode {
+            module_name,
+            alias: None,
+            names: None,
+        }
+    }
+
+    /// The name the module's declarations are qualified with: the `as` alias, else the last path
+    /// segment. A selective import binds no qualifier.
+    pub fn qualifier(&self) -> Option<&str> {
+        if self.names.is_some() {
+            return None;
+        }
+        match &self.alias {
+            Some(alias) => Some(alias.text.as_str()),
+            None => self.module_name.text.rsplit('/').next(),
+        }
     }
 }
 
@@ -66,6 +91,11 @@ pub struct EnumDeclarationNode {
     /// Generic type parameters for a generic discriminated union (`enum Option<T> { ... }`).
     pub generic_parameters: Option<Vec<SyntaxToken>>,
     pub variants: Vec<EnumVariantNode>,
+    /// `public` lets other modules import the enum; private (the default) is module-internal.
+    pub is_public: bool,
+    /// Source file this declaration came from; set during multi-file merge. `None` for
+    /// synthesized nodes.
+    pub file_path: Option<Rc<str>>,
 }
 
 impl EnumDeclarationNode {
@@ -80,6 +110,8 @@ impl EnumDeclarationNode {
             name,
             generic_parameters,
             variants,
+            is_public: false,
+            file_path: None,
         }
     }
 
@@ -1,6 +1,8 @@
 use super::expression::ExpressionNode;
+use super::pattern::PatternNode;
 use super::types::Type;
 use crate::token::syntax_token::SyntaxToken;
+use dream_text::text_span::TextSpan;
 
 /// Represents a statement node in the AST
 #[derive(Debug, Clone)]
@@ -14,6 +16,9 @@ pub enum StatementNode<'a> {
     MemberAssignment(&'a ExpressionNode<'a>, SyntaxToken, ExpressionNode<'a>),
     /// `let`/`const` declaration. The final `bool` marks `const` (immutable) bindings.
     Declaration(SyntaxToken, Option<Type>, ExpressionNode<'a>, bool),
+    /// `let (a, b) = expr;` — binds the elements of a tuple through an irrefutable tuple pattern.
+    /// The final `bool` marks `const` bindings, as in [`StatementNode::Declaration`].
+    DestructuringDeclaration(PatternNode, ExpressionNode<'a>, bool),
     FunctionInvocation(SyntaxToken, Option<Vec<Type>>, Vec<ExpressionNode<'a>>),
     MethodInvocation(
         &'a ExpressionNode<'a>,
@@ -52,6 +57,8 @@ pub enum StatementNode<'a> {
     /// `for (let element in iterable) { body }`. Iterates the elements of an array. The two
     /// `String` fields are unique synthetic local names (index counter and array temp) generated
     /// by the parser so codegen can lower this to an index loop without re-evaluating `iterable`.
+    /// A destructuring loop `for (let (k, v) in pairs)` arrives here with a synthetic element name
+    /// and a body that opens with the matching [`StatementNode::DestructuringDeclaration`].
     ForEach(
         SyntaxToken,
         ExpressionNode<'a>,
@@ -67,4 +74,60 @@ pub enum StatementNode<'a> {
         Vec<(Vec<ExpressionNode<'a>>, &'a [StatementNode<'a>])>,
         Option<&'a [StatementNode<'a>]>,
     ),
+    /// `throw <expr>;`. The token is the `throw` keyword, kept for diagnostics.
+    Throw(SyntaxToken, ExpressionNode<'a>),
+    /// `try { body } catch (e: T) { ... } ... finally { ... }`: the `try` keyword, the protected
+    /// body, the catch clauses in source order, and the optional `finally` body. The parser
+    /// guarantees at least one catch clause or a `finally`.
+    Try(
+        SyntaxToken,
+        &'a [StatementNode<'a>],
+        Vec<CatchClause<'a>>,
+        Option<&'a [StatementNode<'a>]>,
+    ),
+}
+
+/// One `catch (name: T) { body }` clause of a [`StatementNode::Try`]. Without a type annotation
+/// (`catch (e) { ... }`) the clause catches every exception, binding it as `object`.
+#[derive(Debug, Clone)]
+pub struct CatchClause<'a> {
+    pub name: SyntaxToken,
+    pub type_: Option<Type>,
+    pub body: &'a [StatementNode<'a>],
+}
+
+impl<'a> StatementNode<'a> {
+    /// Returns the span of the first positioned token of this statement, used to attribute the
+    /// statement to a source line. Forms that begin with a keyword the AST does not keep start at
+    /// their first positioned part (an `if` at its condition, a `for` at its initializer); a bare
+    /// `return;`/`break;`/`continue;` has nothing positional and returns `None`.
+    pub fn start_position(&self) -> Option<TextSpan> {
+        match self {
+            StatementNode::Assignment(token, _)
+            | StatementNode::Declaration(token, _, _, _)
+            | StatementNode::FunctionInvocation(token, _, _)
+            | StatementNode::ForEach(token, _, _, _, _) => Some(token.position),
+            StatementNode::IndexAssignment(target, _, _)
+            | StatementNode::MemberAssignment(target, _, _) => target.start_position(),
+            StatementNode::MethodInvocation(receiver, name, _, _) => {
+                receiver.start_position().or(Some(name.position))
+            }
+            StatementNode::DestructuringDeclaration(pattern, _, _) => pattern.position(),
+            StatementNode::Return(value) => value.as_ref().and_then(|v| v.start_position()),
+            StatementNode::IfElse(expr, _, _, _)
+            | StatementNode::While(expr, _)
+            | StatementNode::DoWhile(_, expr)
+            | StatementNode::Switch(expr, _, _)
+            | StatementNode::ExpressionStatement(expr)
+            | StatementNode::AwaitStmt(expr) => expr.start_position(),
+            StatementNode::For(init, cond, _, _) => init
+                .and_then(|s| s.start_position())
+                .or_else(|| cond.as_ref().and_then(|c| c.start_position())),
+            StatementNode::Labeled(_, inner) => inner.start_position(),
+            StatementNode::Throw(keyword, _) | StatementNode::Try(keyword, _, _, _) => {
+                Some(keyword.position)
+            }
+            StatementNode::Break(_) | StatementNode::Continue(_) => None,
+        }
+    }
 }
@@ -24,12 +24,16 @@ pub struct StructDeclarationNode<'a> {
     pub attributes: Vec<crate::nodes::AttributeNode>,
     pub name: SyntaxToken,
     pub generic_parameters: Option<Vec<SyntaxToken>>,
+    /// Interface bounds on the generic parameters (`class SortedList<T: Comparable>`); empty when
+    /// every parameter is unbounded.
+    pub generic_bounds: Vec<crate::nodes::GenericBound>,
     pub fields: Vec<StructFieldNode>,
     pub methods: Vec<crate::nodes::function::FunctionNode<'a>>,
-    /// The interfaces this class declares it implements (`class Cat : Animal, Container<int>`).
-    /// Each entry is a (possibly generic) interface type; the class must provide a matching method
-    /// for every method of each listed interface (validated during semantic analysis). Empty when
-    /// no `:` clause is present.
+    /// The types named after the `:` (`class Cat : Animal, Container<int>`): at most one base
+    /// class followed by the interfaces the class implements. Each entry is a (possibly generic)
+    /// type; semantic analysis tells the base class apart and checks that the class provides a
+    /// matching method for every method of each listed interface. Empty when no `:` clause is
+    /// present.
     pub implements: Vec<Type>,
     /// True when the class is marked `public`: visible to other modules and emitted as a
     /// WebAssembly export. Private (the default) classes are module-internal.
@@ -52,6 +56,7 @@ impl<'a> StructDeclarationNode<'a> {
             attributes,
             name,
             generic_parameters,
+            generic_bounds: Vec::new(),
             fields,
             methods,
             implements: Vec::new(),
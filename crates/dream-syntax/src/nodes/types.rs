@@ -133,10 +133,37 @@ pub fn foreach_array_local(n: usize) -> String {
     format!("__foreach_arr_{}", n)
 }
 
+/// The synthetic element local of a destructuring `for (let (a, b) in ...)`, which the loop body
+/// then destructures. See [`foreach_index_local`].
+pub fn foreach_element_local(n: usize) -> String {
+    format!("__foreach_elem_{}", n)
+}
+
 /// The base type name of the async handle `Future<T>`. Single source of truth for the identifier
 /// the async machinery keys on (the structured `Future<T>` type and its `Future_<inner>` mangling).
 pub const FUTURE_TYPE: &str = "Future";
 
+/// Base-name prefix of the compiler-synthesized tuple classes: `(int, string)` is the generic
+/// instance `Tuple#2<int, string>`, with positional fields `0`, `1`, .... `#` cannot appear in an
+/// identifier, so user code can never name or shadow a tuple class, while WAT identifiers accept it
+/// and the mangled instance name (`Tuple#2_int_string`) is emitted unchanged.
+pub const TUPLE_TYPE_PREFIX: &str = "Tuple#";
+
+/// The largest supported tuple arity (the smallest is 2). The analyzer synthesizes one generic
+/// tuple class per arity up to this bound.
+pub const MAX_TUPLE_ARITY: usize = 8;
+
+/// The synthesized tuple class for `arity` elements, e.g. `Tuple#2`.
+pub fn tuple_type_name(arity: usize) -> String {
+    format!("{}{}", TUPLE_TYPE_PREFIX, arity)
+}
+
+/// True if `name` (a tuple base name or a mangled instance such as `Tuple#2_int_string`, optionally
+/// nullable) names a tuple class.
+pub fn is_tuple_type_name(name: &str) -> bool {
+    strip_nullable(name).starts_with(TUPLE_TYPE_PREFIX)
+}
+
 /// Returns true if a type name denotes a heap-allocated, reference-counted value
 /// (strings, arrays, and structs). `known_struct` decides whether a bare name is a struct.
 pub fn is_reference_type_name(type_name: &str, known_struct: impl Fn(&str) -> bool) -> bool {
@@ -144,6 +171,17 @@ pub fn is_reference_type_name(type_name: &str, known_struct: impl Fn(&str) -> bo
     base == "string" || base == "object" || base.ends_with("[]") || known_struct(base)
 }
 
+/// Contextual keyword introducing a trailing bound clause (`where T: Comparable, U: A + B`).
+pub const WHERE_CLAUSE: &str = "where";
+
+/// The bounds on one generic type parameter, written inline (`<T: Comparable + Printable>`) or in
+/// a `where` clause. Each bound is a (possibly generic) interface the type argument must implement.
+#[derive(Debug, Clone)]
+pub struct GenericBound {
+    pub parameter: SyntaxToken,
+    pub bounds: Vec<Type>,
+}
+
 /// Represents a data type in the language
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
@@ -175,7 +213,8 @@ pub enum Type {
     Generic(String),
     Nullable(Box<Type>),
     /// A first-class function value `fun(params...): ret`. Represented at runtime as an `i32`
-    /// index into the module's function table (used with `call_indirect`).
+    /// pointer to a refcounted closure block holding a function-table index (used with
+    /// `call_indirect`) followed by the captured values.
     Function(Vec<Type>, Box<Type>),
     Void,
     /// The "poison" type produced on a semantic error (e.g. an unresolved identifier or call).
@@ -246,7 +285,11 @@ impl Type {
                         .map(|a| a.display_name())
                         .collect::<Vec<_>>()
                         .join(", ");
-                    format!("{}<{}>", token.text, args_str)
+                    if is_tuple_type_name(&token.text) {
+                        format!("({})", args_str)
+                    } else {
+                        format!("{}<{}>", token.text, args_str)
+                    }
                 }
                 None => token.text.clone(),
             },
@@ -303,6 +346,26 @@ impl Type {
         matches!(self, Type::Array(_))
     }
 
+    /// Builds the tuple type `(elements...)`: an instance of the synthesized `Tuple#N` class whose
+    /// name token sits at `position` (the opening parenthesis).
+    pub fn tuple(position: dream_text::text_span::TextSpan, elements: Vec<Type>) -> Type {
+        let name = SyntaxToken::new(
+            crate::token::token_kind::TokenKind::IdentifierToken,
+            position,
+            tuple_type_name(elements.len()),
+        );
+        Type::Struct(name, Some(elements))
+    }
+
+    /// The element types of a tuple type (`(int, string)` gives `[int, string]`); `None` for any
+    /// other type, including a nullable tuple.
+    pub fn tuple_elements(&self) -> Option<&[Type]> {
+        match self {
+            Type::Struct(token, Some(args)) if is_tuple_type_name(&token.text) => Some(args),
+            _ => None,
+        }
+    }
+
     /// Returns the type name with any trailing nullable (`?`) suffix removed.
     pub fn base_name(&self) -> String {
         strip_nullable(&self.get_type()).to_string()
@@ -13,6 +13,7 @@ struct FunctionModifiers {
     is_public: bool,
     is_static: bool,
     is_extern: bool,
+    is_virtual: bool,
 }
 
 impl<'a, 'b> Parser<'a, 'b> {
@@ -76,6 +77,10 @@ impl<'a, 'b> Parser<'a, 'b> {
         // attribute. Recover it so the comment still reaches the enum name token for hover/LSP.
         let doc_trivia = Self::recover_doc_trivia(first_trivia, &attributes);
 
+        let is_public = self.current_token().kind == TokenKind::PublicToken;
+        if is_public {
+            self.match_token(TokenKind::PublicToken);
+        }
         self.match_token(TokenKind::EnumToken);
         let mut name = self.match_token(TokenKind::IdentifierToken);
         Self::splice_leading_trivia(&mut name, doc_trivia);
@@ -126,12 +131,14 @@ impl<'a, 'b> Parser<'a, 'b> {
             }
         }
         self.match_token(TokenKind::CurlyCloseBracketToken);
-        Ok(crate::nodes::EnumDeclarationNode::new(
+        let mut node = crate::nodes::EnumDeclarationNode::new(
             attributes,
             name,
             generic_parameters,
             variants,
-        ))
+        );
+        node.is_public = is_public;
+        Ok(node)
     }
 
     /// Parses a single discriminated-union variant payload field: `name: Type`.
@@ -174,7 +181,7 @@ impl<'a, 'b> Parser<'a, 'b> {
         let mut struct_name = self.match_token(TokenKind::IdentifierToken);
         Self::splice_leading_trivia(&mut struct_name, first_trivia);
 
-        let generic_parameters = self.parse_identifier_generic_params();
+        let (generic_parameters, mut generic_bounds) = self.parse_bounded_generic_params()?;
 
         // Optional `: Iface1, Container<int>, ...` implements clause. Each entry is a (possibly
         // generic) interface type the class declares it satisfies; the class must provide a matching
@@ -193,6 +200,7 @@ impl<'a, 'b> Parser<'a, 'b> {
                 self.ensure_progress(iter);
             }
         }
+        self.parse_where_clause(&mut generic_bounds)?;
 
         self.match_token(TokenKind::CurlyOpenBracketToken);
 
@@ -204,17 +212,16 @@ impl<'a, 'b> Parser<'a, 'b> {
             let iter = self.current_token_index;
             let field_attributes = self.parse_attributes();
 
-            // Classify the member by looking past any leading `public`/`static`/`async`: a
+            // Classify the member by looking past any leading `public`/`static`/`async`/`virtual`: a
             // method (`fun`, `static fun`, `constructor`/`del`, `extern fun`) is dispatched to
             // `parse_function` (which consumes its own modifiers), otherwise it is a field.
-            let mut m = 0;
-            while matches!(
-                self.peek_token(m).kind,
-                TokenKind::PublicToken | TokenKind::StaticToken | TokenKind::AsyncToken
-            ) {
-                m += 1;
-            }
+            let m = self.count_member_modifiers();
             let core = self.peek_token(m);
+            if self.report_misplaced_override(m) {
+                self.skip_member();
+                self.ensure_progress(iter);
+                continue;
+            }
             let is_ctor_dtor = core.kind == TokenKind::IdentifierToken
                 && crate::nodes::types::is_special_member_name(&core.text)
                 && self.peek_token(m + 1).kind == TokenKind::OpenParenthesisToken;
@@ -274,12 +281,77 @@ impl<'a, 'b> Parser<'a, 'b> {
             is_public,
         );
         decl.implements = implements;
+        decl.generic_bounds = generic_bounds;
         Ok(decl)
     }
 
-    /// Parses an `interface` declaration: `[public] interface Name [<T>] { method-signature* }`.
-    /// Interface members are body-less method signatures ending in `;` (default bodies are not
-    /// supported in v1).
+    /// How many `public`/`static`/`async`/`virtual` tokens open the member at the cursor.
+    fn count_member_modifiers(&self) -> usize {
+        let mut m = 0;
+        while matches!(
+            self.peek_token(m).kind,
+            TokenKind::PublicToken
+                | TokenKind::StaticToken
+                | TokenKind::AsyncToken
+                | TokenKind::VirtualToken
+        ) {
+            m += 1;
+        }
+        m
+    }
+
+    /// Reports an `override` written as a modifier (`override fun`, `public override fun`) or an
+    /// attribute after the modifiers (`public @override fun`), `modifiers` tokens into the member.
+    /// Returns whether it did, in which case the member should be skipped: parsing it as a field
+    /// would only pile up errors that say nothing about the mistake.
+    fn report_misplaced_override(&mut self, modifiers: usize) -> bool {
+        let core = self.peek_token(modifiers);
+        let message = match core.kind {
+            TokenKind::IdentifierToken
+                if core.text == "override"
+                    && self.peek_token(modifiers + 1).kind != TokenKind::ColonToken =>
+            {
+                "'override' is not a modifier; mark the method with the '@override' attribute"
+            }
+            TokenKind::AtToken if modifiers > 0 => {
+                "attributes such as '@override' go before a member's modifiers"
+            }
+            _ => return false,
+        };
+        let position = core.position;
+        self.diagnostics
+            .report_error(message.to_string(), Some(position));
+        true
+    }
+
+    /// Skips the rest of a class member: up to and including its `;` or its body's closing brace,
+    /// or up to the brace closing the class.
+    fn skip_member(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            match self.current_token().kind {
+                TokenKind::EndOfFileToken => return,
+                TokenKind::CurlyCloseBracketToken if depth == 0 => return,
+                TokenKind::CurlyOpenBracketToken => depth += 1,
+                TokenKind::CurlyCloseBracketToken => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.next_token();
+                        return;
+                    }
+                }
+                TokenKind::SemicolonToken if depth == 0 => {
+                    self.next_token();
+                    return;
+                }
+                _ => {}
+            }
+            self.next_token();
+        }
+    }
+
+    /// Parses an `interface` declaration: `[public] interface Name [<T>] { method* }`. Each member is
+    /// a method signature ending in `;` or a method with a default body.
     pub(super) fn parse_interface_declaration(
         &mut self,
     ) -> Result<crate::nodes::InterfaceDeclarationNode<'a>, Error> {
@@ -297,7 +369,8 @@ impl<'a, 'b> Parser<'a, 'b> {
         let mut name = self.match_token(TokenKind::IdentifierToken);
         Self::splice_leading_trivia(&mut name, doc_trivia);
 
-        let generic_parameters = self.parse_identifier_generic_params();
+        let (generic_parameters, mut generic_bounds) = self.parse_bounded_generic_params()?;
+        self.parse_where_clause(&mut generic_bounds)?;
 
         self.match_token(TokenKind::CurlyOpenBracketToken);
 
@@ -312,18 +385,20 @@ impl<'a, 'b> Parser<'a, 'b> {
         }
 
         self.match_token(TokenKind::CurlyCloseBracketToken);
-        Ok(crate::nodes::InterfaceDeclarationNode::new(
+        let mut decl = crate::nodes::InterfaceDeclarationNode::new(
             attributes,
             name,
             generic_parameters,
             methods,
             is_public,
-        ))
+        );
+        decl.generic_bounds = generic_bounds;
+        Ok(decl)
     }
 
-    /// Parses one interface method signature: `[public] [static] fun Name[<T>](params)[: ret] ;`.
-    /// The method has no body; a `{ ... }` default body is rejected (deferred to a later version)
-    /// but still consumed so parsing can continue.
+    /// Parses one interface method: `[public] [static] [async] fun Name[<T>](params)[: ret]`
+    /// followed by either `;` (a signature implementing classes must define) or a `{ ... }` default
+    /// body they inherit.
     fn parse_interface_method(
         &mut self,
         attributes: Vec<crate::nodes::AttributeNode>,
@@ -333,44 +408,42 @@ impl<'a, 'b> Parser<'a, 'b> {
             is_public,
             is_static,
             is_extern: _,
+            is_virtual,
         } = self.parse_function_modifiers();
 
         self.match_token(TokenKind::FunToken);
         let function_name = self.match_token(TokenKind::IdentifierToken);
-        let generic_parameters = self.parse_identifier_generic_params();
+        let (generic_parameters, mut generic_bounds) = self.parse_bounded_generic_params()?;
         let params = self.parse_formal_parameters()?;
         let mut return_type: Option<Type> = None;
         if self.current_token().kind == TokenKind::ColonToken {
             self.match_token(TokenKind::ColonToken);
             return_type = Some(self.parse_type()?);
         }
+        self.parse_where_clause(&mut generic_bounds)?;
 
-        if self.current_token().kind == TokenKind::CurlyOpenBracketToken {
-            self.diagnostics.report_error(
-                format!(
-                    "interface method '{}' must be a signature ending with ';' (default method bodies are not supported yet)",
-                    function_name.text
-                ),
-                Some(function_name.position),
-            );
-            // Consume the block so parsing can recover.
-            let _ = self.parse_block()?;
+        let has_default_body = self.current_token().kind == TokenKind::CurlyOpenBracketToken;
+        let body: &'a [StatementNode<'a>] = if has_default_body {
+            self.parse_block()?
         } else {
             self.match_token(TokenKind::SemicolonToken);
-        }
+            self.arena.alloc_slice_fill_iter(std::iter::empty())
+        };
 
-        let empty: &'a [StatementNode<'a>] = self.arena.alloc_slice_fill_iter(std::iter::empty());
         let mut node = FunctionNode::new(
             attributes,
             function_name,
             generic_parameters,
             return_type,
             params,
-            empty,
+            body,
             is_public,
         );
+        node.has_default_body = has_default_body;
+        node.generic_bounds = generic_bounds;
         node.is_static = is_static;
         node.is_async = is_async;
+        node.is_virtual = is_virtual;
         Ok(node)
     }
 
@@ -402,10 +475,16 @@ impl<'a, 'b> Parser<'a, 'b> {
         {
             let iter = self.current_token_index;
             let field_attributes = self.parse_attributes();
+            if self.report_misplaced_override(self.count_member_modifiers()) {
+                self.skip_member();
+                self.ensure_progress(iter);
+                continue;
+            }
             if self.current_token().kind == TokenKind::FunToken
                 || self.current_token().kind == TokenKind::PublicToken
                 || self.current_token().kind == TokenKind::StaticToken
                 || self.current_token().kind == TokenKind::AsyncToken
+                || self.current_token().kind == TokenKind::VirtualToken
             {
                 methods.push(self.parse_function(Some(field_attributes))?);
             } else {
@@ -435,6 +514,19 @@ impl<'a, 'b> Parser<'a, 'b> {
     pub(super) fn parse_import(&mut self) -> Result<ImportNode, Error> {
         self.match_token(TokenKind::ImportToken);
 
+        // Selective form: `import { Circle, area } from geometry.shapes;`. `from` and `as` are
+        // contextual, so they stay usable as ordinary identifiers elsewhere.
+        let names = if self.current_token().kind == TokenKind::CurlyOpenBracketToken {
+            self.match_token(TokenKind::CurlyOpenBracketToken);
+            let names = self.parse_delimited_list(TokenKind::CurlyCloseBracketToken, |p| {
+                Ok(p.match_token(TokenKind::IdentifierToken))
+            })?;
+            self.match_contextual_keyword("from");
+            Some(names)
+        } else {
+            None
+        };
+
         let first = self.match_token(TokenKind::IdentifierToken);
         let mut position = first.position;
         let mut path = first.text.clone();
@@ -447,10 +539,37 @@ impl<'a, 'b> Parser<'a, 'b> {
             path.push_str(&segment.text);
         }
 
+        let alias = if names.is_none()
+            && self.current_token().kind == TokenKind::IdentifierToken
+            && self.current_token().text == "as"
+        {
+            self.next_token();
+            Some(self.match_token(TokenKind::IdentifierToken))
+        } else {
+            None
+        };
+
         self.match_token(TokenKind::SemicolonToken);
 
         let module_name = SyntaxToken::new(TokenKind::IdentifierToken, position, path);
-        Ok(ImportNode::new(module_name))
+        let mut node = ImportNode::new(module_name);
+        node.alias = alias;
+        node.names = names;
+        Ok(node)
+    }
+
+    /// Matches an identifier spelled `keyword` (a contextual keyword such as `from`), reporting an
+    /// error like [`Parser::match_token`] when it is missing.
+    fn match_contextual_keyword(&mut self, keyword: &str) {
+        let token = self.current_token();
+        if token.kind == TokenKind::IdentifierToken && token.text == keyword {
+            self.next_token();
+        } else {
+            self.diagnostics.report_error(
+                format!("Expected '{}' but found {}", keyword, token.kind.friendly_name()),
+                Some(token.position),
+            );
+        }
     }
     /// Parses a Type from the token stream, including array types
     pub(super) fn parse_type(&mut self) -> Result<Type, Error> {
@@ -470,10 +589,32 @@ impl<'a, 'b> Parser<'a, 'b> {
             return Ok(Type::Function(params, Box::new(ret)));
         }
 
+        // Tuple type: `(int, string)`, which may itself take array/nullable suffixes.
+        if self.current_token().kind == TokenKind::OpenParenthesisToken {
+            let open = self.match_token(TokenKind::OpenParenthesisToken);
+            let elements =
+                self.parse_delimited_list(TokenKind::CloseParenthesisToken, |p| p.parse_type())?;
+            self.check_tuple_arity(&open, elements.len());
+            let tuple = Type::tuple(open.position, elements);
+            return Ok(self.parse_type_suffixes(tuple));
+        }
+
         let type_token = if self.current_token().kind == TokenKind::DataTypeToken {
             self.match_token(TokenKind::DataTypeToken)
         } else {
-            self.match_token(TokenKind::IdentifierToken)
+            let mut token = self.match_token(TokenKind::IdentifierToken);
+            // A module-qualified name (`shapes.Circle`) is kept as one dotted token; the module
+            // resolver maps it to the declaration it names.
+            while self.current_token().kind == TokenKind::DotToken
+                && self.peek_token(1).kind == TokenKind::IdentifierToken
+            {
+                self.match_token(TokenKind::DotToken);
+                let segment = self.match_token(TokenKind::IdentifierToken);
+                token.text.push('.');
+                token.text.push_str(&segment.text);
+                token.position.end = segment.position.end;
+            }
+            token
         };
         // `from_token` only rejects one syntactic shape: a non-reference nullable such as `int?`.
         // Route that through the diagnostics bag (syntax's single error channel) and recover with a
@@ -508,6 +649,11 @@ impl<'a, 'b> Parser<'a, 'b> {
             }
         }
 
+        Ok(self.parse_type_suffixes(parsed_type))
+    }
+
+    /// Applies the array (`[]`, repeatable) and nullable (`?`) suffixes that may follow a type.
+    fn parse_type_suffixes(&mut self, mut parsed_type: Type) -> Type {
         // Check for array suffix `[]`
         while self.current_token().kind == TokenKind::OpenBracketToken {
             self.match_token(TokenKind::OpenBracketToken);
@@ -521,7 +667,22 @@ impl<'a, 'b> Parser<'a, 'b> {
             parsed_type = Type::Nullable(Box::new(parsed_type));
         }
 
-        Ok(parsed_type)
+        parsed_type
+    }
+
+    /// Reports a tuple type, literal, or pattern whose element count is outside
+    /// `2..=MAX_TUPLE_ARITY`. `open` is the tuple's opening parenthesis.
+    pub(super) fn check_tuple_arity(&mut self, open: &SyntaxToken, arity: usize) {
+        use crate::nodes::types::MAX_TUPLE_ARITY;
+        if !(2..=MAX_TUPLE_ARITY).contains(&arity) {
+            self.diagnostics.report_error(
+                format!(
+                    "A tuple must have between 2 and {} elements, found {}",
+                    MAX_TUPLE_ARITY, arity
+                ),
+                Some(open.position),
+            );
+        }
     }
 
     pub(super) fn parse_attributes(&mut self) -> Vec<crate::nodes::AttributeNode> {
@@ -556,8 +717,8 @@ impl<'a, 'b> Parser<'a, 'b> {
         attributes
     }
 
-    /// Parses the flexible function-modifier prefix (`async`/`public`/`static`/`extern`, which may
-    /// appear in several orders) and reports the `public`+`extern` conflict. Consumes exactly the
+    /// Parses the flexible function-modifier prefix (`async`/`public`/`static`/`extern`/`virtual`,
+    /// which may appear in several orders) and reports the `public`+`extern` conflict. Consumes exactly the
     /// modifier tokens, leaving the cursor on the `fun`/constructor/`del` token.
     fn parse_function_modifiers(&mut self) -> FunctionModifiers {
         let mut m = FunctionModifiers::default();
@@ -579,6 +740,18 @@ impl<'a, 'b> Parser<'a, 'b> {
             m.is_async = true;
         }
 
+        // `public virtual fun ...`: a class method subclasses may `@override`. Whether the
+        // modifier is allowed on this declaration is checked during semantic analysis.
+        if self.current_token().kind == TokenKind::VirtualToken {
+            self.match_token(TokenKind::VirtualToken);
+            m.is_virtual = true;
+        }
+
+        if self.current_token().kind == TokenKind::AsyncToken {
+            self.match_token(TokenKind::AsyncToken);
+            m.is_async = true;
+        }
+
         // `static fun ...`: a method with no implicit `this`, called as `Type.method(...)`.
         if self.current_token().kind == TokenKind::StaticToken {
             self.match_token(TokenKind::StaticToken);
@@ -631,6 +804,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             is_public,
             is_static,
             is_extern,
+            is_virtual,
         } = self.parse_function_modifiers();
 
         // Constructor (`constructor`) / destructor (`del`) declarations omit the `fun` keyword and
@@ -649,9 +823,10 @@ impl<'a, 'b> Parser<'a, 'b> {
             }
             let params = self.parse_formal_parameters()?;
             let block = self.parse_block()?;
-            return Ok(FunctionNode::new(
-                attributes, ctor_name, None, None, params, block, false,
-            ));
+            let mut node =
+                FunctionNode::new(attributes, ctor_name, None, None, params, block, false);
+            node.is_virtual = is_virtual;
+            return Ok(node);
         }
 
         // TypeScript-style property accessor: `get name(): T { ... }` / `set name(value: T) { ... }`.
@@ -687,16 +862,17 @@ impl<'a, 'b> Parser<'a, 'b> {
             );
             node.is_static = is_static;
             node.is_async = is_async;
+            node.is_virtual = is_virtual;
             node.accessor = Some(accessor_kind);
             return Ok(node);
         }
 
         //eat the fun keyword
         self.match_token(TokenKind::FunToken);
-        let mut function_name = self.match_token(TokenKind::IdentifierToken);
+        let mut function_name = self.match_member_name();
         Self::splice_leading_trivia(&mut function_name, first_trivia);
 
-        let generic_parameters = self.parse_identifier_generic_params();
+        let (generic_parameters, mut generic_bounds) = self.parse_bounded_generic_params()?;
 
         let params = self.parse_formal_parameters()?;
         let mut return_type: Option<Type> = None;
@@ -705,6 +881,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             self.match_token(TokenKind::ColonToken);
             return_type = Some(self.parse_type()?);
         }
+        self.parse_where_clause(&mut generic_bounds)?;
 
         if is_extern {
             // Extern functions are lowered to WASM imports: no body, terminated by `;`.
@@ -731,8 +908,10 @@ impl<'a, 'b> Parser<'a, 'b> {
                 false,
             );
             node.is_extern = true;
+            node.generic_bounds = generic_bounds;
             node.is_static = is_static;
             node.is_async = is_async;
+            node.is_virtual = is_virtual;
             return Ok(node);
         }
 
@@ -746,8 +925,10 @@ impl<'a, 'b> Parser<'a, 'b> {
             block,
             is_public,
         );
+        node.generic_bounds = generic_bounds;
         node.is_static = is_static;
         node.is_async = is_async;
+        node.is_virtual = is_virtual;
         Ok(node)
     }
 
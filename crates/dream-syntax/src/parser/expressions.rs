@@ -1,6 +1,9 @@
 use super::Parser;
 use crate::lexer::Lexer;
-use crate::nodes::{ExpressionNode, PatternNode, SwitchArm, SwitchArmBody, Type};
+use crate::nodes::{
+    ExpressionNode, LambdaBody, LambdaNode, LambdaParameter, PatternNode, SwitchArm,
+    SwitchArmBody, Type,
+};
 use crate::token::syntax_token::SyntaxToken;
 use crate::token::token_kind::TokenKind;
 use crate::token::token_kind::TokenKind::{EndOfFileToken, IdentifierToken};
@@ -26,6 +29,9 @@ impl<'a, 'b> Parser<'a, 'b> {
         } else {
             left = self.parse_primary_expression()?;
         }
+        // Postfix `?` binds tighter than any prefix or binary operator: `-x?` is `-(x?)` and
+        // `await f()?` is `(await f())?`.
+        left = self.parse_propagation(left)?;
         loop {
             let precedence = self.current_token().kind.get_binary_precedence();
             if precedence == 0 || precedence <= parent_precedence {
@@ -69,12 +75,80 @@ impl<'a, 'b> Parser<'a, 'b> {
 
         Ok(left)
     }
+    /// Wraps `base` in any postfix `?` operators that follow it, each of which may be followed by
+    /// further member/index accesses (`parse()?.value`, `a.b()?.c()?`).
+    pub(super) fn parse_propagation(
+        &mut self,
+        base: ExpressionNode<'a>,
+    ) -> Result<ExpressionNode<'a>, Error> {
+        let mut expr = base;
+        while self.current_token().kind == TokenKind::QuestionMarkToken && self.propagation_ahead() {
+            let question = self.next_token();
+            expr = ExpressionNode::Propagate(self.arena.alloc(expr), question);
+            expr = self.parse_postfix_chain(expr)?;
+        }
+        Ok(expr)
+    }
+
+    /// Whether the `?` under the cursor is the postfix propagation operator rather than the start
+    /// of a ternary: it is when the next token cannot begin an expression (`x?;`, `f()?.len()`,
+    /// `g(x?, y)`), so `c ? [a] : [b]` remains a ternary. After a `+`/`-`, which may be binary or
+    /// unary, it is a ternary only if a `:` follows in the same expression (`c ? -1 : 1` vs `a? - b`).
+    fn propagation_ahead(&self) -> bool {
+        let next = self.peek_token(1).kind;
+        if matches!(next, TokenKind::PlusToken | TokenKind::MinusToken) {
+            return !self.ternary_colon_ahead(2);
+        }
+        next.get_unary_precedence() == 0
+            && !matches!(
+                next,
+                TokenKind::IdentifierToken
+                    | TokenKind::NumberToken
+                    | TokenKind::StringToken
+                    | TokenKind::InterpolatedStringToken
+                    | TokenKind::CharToken
+                    | TokenKind::BooleanToken
+                    | TokenKind::NullToken
+                    | TokenKind::DataTypeToken
+                    | TokenKind::OpenParenthesisToken
+                    | TokenKind::OpenBracketToken
+                    | TokenKind::SwitchToken
+                    | TokenKind::AwaitToken
+            )
+    }
+    /// Whether a `:` appears at the current nesting depth, starting `offset` tokens ahead, before
+    /// the enclosing expression ends (at a `;`, `,`, `=>`, `{`, or an unmatched closing bracket).
+    fn ternary_colon_ahead(&self, mut offset: usize) -> bool {
+        let mut depth = 0usize;
+        loop {
+            match self.peek_token(offset).kind {
+                TokenKind::ColonToken if depth == 0 => return true,
+                TokenKind::OpenParenthesisToken | TokenKind::OpenBracketToken => depth += 1,
+                TokenKind::CloseParenthesisToken | TokenKind::CloseBracketToken => {
+                    if depth == 0 {
+                        return false;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::SemicolonToken
+                | TokenKind::CurlyOpenBracketToken
+                | TokenKind::CurlyCloseBracketToken
+                | TokenKind::EndOfFileToken => return false,
+                TokenKind::CommaToken | TokenKind::FatArrowToken if depth == 0 => return false,
+                _ => {}
+            }
+            offset += 1;
+        }
+    }
     /// Parses a primary expression (literal, identifier, parenthesized expression, or function call)
     pub(super) fn parse_primary_expression(&mut self) -> Result<ExpressionNode<'a>, Error> {
         // `switch (subject) { pattern => body, ... }` in expression (pattern-matching) form.
         if self.current_token().kind == TokenKind::SwitchToken {
             return self.parse_switch_expr();
         }
+        if self.lambda_ahead() {
+            return self.parse_lambda();
+        }
         //parse parenthesized expressions or cast
         if self.current_token().kind == TokenKind::OpenParenthesisToken {
             return self.parse_paren_or_cast();
@@ -151,10 +225,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             // A char literal `'a'` is a `char` whose backing token text is the (ASCII/code point)
             // value, so codegen can emit `i32.const <value>`. Escapes like '\n', '\t', '\\', '\''
             // and '\0' are supported.
-            let tok = self.next_token();
-            let value = Self::char_literal_value(&tok.text);
-            let char_token =
-                SyntaxToken::new(TokenKind::CharToken, tok.position, value.to_string());
+            let char_token = self.char_literal();
             return Ok(ExpressionNode::Literal(Type::Char(char_token)));
         }
 
@@ -187,6 +258,14 @@ impl<'a, 'b> Parser<'a, 'b> {
             // Could be `(Node)0` or `(x) + 1`
             // Let's check token after `)`
             let mut i = 2;
+            // A module-qualified type (`(shapes.Circle)s`). Only an identifier may follow the `)`
+            // then: `(p.x) - 1` and `(p.f)(...)` stay parenthesized expressions.
+            let qualified = self.peek_token(i).kind == TokenKind::DotToken;
+            while self.peek_token(i).kind == TokenKind::DotToken
+                && self.peek_token(i + 1).kind == TokenKind::IdentifierToken
+            {
+                i += 2;
+            }
             // Skip a generic argument list so `(Container<int>)b` (and nested forms like
             // `(Pair<Box<int>, int>)x`) are recognized as casts. `scan_generic_args` tracks `<`/`>`
             // nesting (treating `>>` as two closes) and returns the peek offset after the matching
@@ -208,7 +287,10 @@ impl<'a, 'b> Parser<'a, 'b> {
                 while self.peek_token(i).kind == TokenKind::OpenBracketToken {
                     i += 2; // skip `[` and `]`
                 }
-                if self.peek_token(i).kind == TokenKind::CloseParenthesisToken {
+                if qualified {
+                    self.peek_token(i).kind == TokenKind::CloseParenthesisToken
+                        && self.peek_token(i + 1).kind == TokenKind::IdentifierToken
+                } else if self.peek_token(i).kind == TokenKind::CloseParenthesisToken {
                     let next_kind = self.peek_token(i + 1).kind;
                     // If the token after `)` is an expression starter, it's a cast
                     matches!(
@@ -242,8 +324,18 @@ impl<'a, 'b> Parser<'a, 'b> {
         }
 
         //eat the open parenthesis
-        self.match_token(TokenKind::OpenParenthesisToken);
+        let open = self.match_token(TokenKind::OpenParenthesisToken);
         let expression = self.parse_expression(0)?;
+        // A comma after the first expression makes this a tuple literal `(a, b, ...)`.
+        if self.current_token().kind == TokenKind::CommaToken {
+            self.match_token(TokenKind::CommaToken);
+            let mut elements = vec![expression];
+            elements.extend(
+                self.parse_delimited_list(TokenKind::CloseParenthesisToken, |p| p.parse_expression(0))?,
+            );
+            self.check_tuple_arity(&open, elements.len());
+            return self.parse_postfix_chain(ExpressionNode::Tuple(open, elements));
+        }
         //eat the close parenthesis
         self.match_token(TokenKind::CloseParenthesisToken);
         // Allow postfix access on a parenthesized expression, e.g. `(7).hash_code()`,
@@ -253,6 +345,115 @@ impl<'a, 'b> Parser<'a, 'b> {
         self.parse_postfix_chain(parenthesized)
     }
 
+    /// Whether the cursor starts a lambda: `x =>`, `(params) =>`, or `(params): Type =>`. A
+    /// parenthesized group is scanned to its matching `)`; with a return annotation, the tokens up
+    /// to the `=>` must all be type tokens, so `cond ? (a) : b` stays a ternary.
+    fn lambda_ahead(&self) -> bool {
+        if self.in_switch_guard {
+            return false;
+        }
+        let kind = self.current_token().kind;
+        if kind == IdentifierToken {
+            return self.peek_token(1).kind == TokenKind::FatArrowToken;
+        }
+        if kind != TokenKind::OpenParenthesisToken {
+            return false;
+        }
+        let Some(mut i) = self.scan_balanced_parens(1) else {
+            return false;
+        };
+        match self.peek_token(i).kind {
+            TokenKind::FatArrowToken => true,
+            TokenKind::ColonToken => {
+                i += 1;
+                loop {
+                    match self.peek_token(i).kind {
+                        TokenKind::FatArrowToken => return true,
+                        TokenKind::IdentifierToken
+                        | TokenKind::DataTypeToken
+                        | TokenKind::FunToken
+                        | TokenKind::ColonToken
+                        | TokenKind::CommaToken
+                        | TokenKind::QuestionMarkToken
+                        | TokenKind::SmallerThanToken
+                        | TokenKind::GreaterThanToken
+                        | TokenKind::ShiftRightToken
+                        | TokenKind::OpenBracketToken
+                        | TokenKind::CloseBracketToken => i += 1,
+                        TokenKind::OpenParenthesisToken => match self.scan_balanced_parens(i + 1) {
+                            Some(after) => i = after,
+                            None => return false,
+                        },
+                        _ => return false,
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Lookahead past a parenthesized group whose first inner token is at peek offset `start` (the
+    /// `(` was already seen). Returns the peek offset right after the matching `)`, or `None` if a
+    /// `;`, `{`, or end-of-file is hit first.
+    fn scan_balanced_parens(&self, mut i: usize) -> Option<usize> {
+        let mut depth = 1;
+        loop {
+            match self.peek_token(i).kind {
+                TokenKind::OpenParenthesisToken => depth += 1,
+                TokenKind::CloseParenthesisToken => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                TokenKind::SemicolonToken
+                | TokenKind::CurlyOpenBracketToken
+                | TokenKind::EndOfFileToken => return None,
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Parses a lambda (see [`Self::lambda_ahead`]): a bare `x` or a parenthesized list of
+    /// `name[: Type]` parameters, an optional `: Type` return annotation, `=>`, and either a block
+    /// or a single expression body.
+    fn parse_lambda(&mut self) -> Result<ExpressionNode<'a>, Error> {
+        let parameters = if self.current_token().kind == IdentifierToken {
+            vec![LambdaParameter { name: self.next_token(), type_: None }]
+        } else {
+            self.match_token(TokenKind::OpenParenthesisToken);
+            self.parse_delimited_list(TokenKind::CloseParenthesisToken, |p| {
+                let name = p.match_token(IdentifierToken);
+                let type_ = if p.current_token().kind == TokenKind::ColonToken {
+                    p.match_token(TokenKind::ColonToken);
+                    Some(p.parse_type()?)
+                } else {
+                    None
+                };
+                Ok(LambdaParameter { name, type_ })
+            })?
+        };
+        let return_type = if self.current_token().kind == TokenKind::ColonToken {
+            self.match_token(TokenKind::ColonToken);
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        let arrow = self.match_token(TokenKind::FatArrowToken);
+        let body = if self.current_token().kind == TokenKind::CurlyOpenBracketToken {
+            LambdaBody::Block(self.parse_block()?)
+        } else {
+            LambdaBody::Expr(self.arena.alloc(self.parse_expression(0)?))
+        };
+        Ok(ExpressionNode::Lambda(self.arena.alloc(LambdaNode {
+            arrow,
+            parameters,
+            return_type,
+            body,
+        })))
+    }
+
     /// Continues parsing index (`[...]`) and member/method (`.name` / `.name(...)`) accesses onto an
     /// already-parsed base expression. Used so a call on a bare identifier (e.g. a constructor like
     /// `HttpClient(url)`) can be chained: `HttpClient(url).set_header(...)`.
@@ -286,7 +487,10 @@ impl<'a, 'b> Parser<'a, 'b> {
         base: ExpressionNode<'a>,
     ) -> Result<ExpressionNode<'a>, Error> {
         self.match_token(TokenKind::DotToken);
-        let member = self.match_token(TokenKind::IdentifierToken);
+        if self.current_token().kind == TokenKind::NumberToken {
+            return Ok(self.parse_tuple_index(base));
+        }
+        let member = self.match_member_name();
 
         let mut generic_args = None;
         if self.current_token().kind == TokenKind::SmallerThanToken {
@@ -317,6 +521,32 @@ impl<'a, 'b> Parser<'a, 'b> {
         }
     }
 
+    /// Parses the positional element access after a `.` on a tuple (`pair.0`), as a
+    /// [`ExpressionNode::MemberAccess`] of the field named by the index. The lexer reads a chained
+    /// `t.0.1` as the single number `0.1`, so such a token is split into two successive accesses.
+    fn parse_tuple_index(&mut self, base: ExpressionNode<'a>) -> ExpressionNode<'a> {
+        let token = self.next_token();
+        let mut expr = base;
+        let mut start = token.position.start;
+        for part in token.text.split('.') {
+            let mut position = token.position;
+            position.col_no += start - token.position.start;
+            position.start = start;
+            position.end = start + part.len();
+            start = position.end + 1;
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                self.diagnostics.report_error(
+                    format!("Expected a tuple element index but found '{}'", token.text),
+                    Some(token.position),
+                );
+                break;
+            }
+            let member = SyntaxToken::new(TokenKind::IdentifierToken, position, part.to_string());
+            expr = ExpressionNode::MemberAccess(self.arena.alloc(expr), member);
+        }
+        expr
+    }
+
     /// Parses a function invocation expression
     pub(super) fn parse_invocation_expression(&mut self) -> Result<ExpressionNode<'a>, Error> {
         let function_name = self.match_token(TokenKind::IdentifierToken);
@@ -377,7 +607,10 @@ impl<'a, 'b> Parser<'a, 'b> {
             // Optional `if <guard>` after the pattern.
             let guard = if p.current_token().kind == TokenKind::IfToken {
                 p.match_token(TokenKind::IfToken);
-                Some(p.parse_expression(0)?)
+                let outer = std::mem::replace(&mut p.in_switch_guard, true);
+                let guard = p.parse_expression(0);
+                p.in_switch_guard = outer;
+                Some(guard?)
             } else {
                 None
             };
@@ -387,7 +620,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             let body = if p.current_token().kind == TokenKind::CurlyOpenBracketToken {
                 SwitchArmBody::Block(p.parse_block()?)
             } else {
-                SwitchArmBody::Expr(p.parse_expression(0)?)
+                SwitchArmBody::Expr(p.arena.alloc(p.parse_expression(0)?))
             };
 
             Ok(SwitchArm {
@@ -407,12 +640,76 @@ impl<'a, 'b> Parser<'a, 'b> {
         Ok(ExpressionNode::Switch(self.arena.alloc(subject), arms))
     }
 
-    /// Parses a single match pattern: `_` (wildcard), a literal, a bare identifier (a binding,
-    /// later reinterpreted as a unit variant by the analyzer when it names one), or a variant
-    /// pattern `Variant(sub, ...)` / `Enum.Variant(sub, ...)`.
+    /// Parses a match pattern: one or more `|`-separated alternatives (see
+    /// [`parse_primary_pattern`](Self::parse_primary_pattern)), folded into a
+    /// [`PatternNode::Or`] when there is more than one.
     pub(super) fn parse_pattern(&mut self) -> Result<PatternNode, Error> {
+        let first = self.parse_primary_pattern()?;
+        if self.current_token().kind != TokenKind::BitWisePipeToken {
+            return Ok(first);
+        }
+        let mut alternatives = vec![first];
+        while self.current_token().kind == TokenKind::BitWisePipeToken {
+            self.match_token(TokenKind::BitWisePipeToken);
+            alternatives.push(self.parse_primary_pattern()?);
+        }
+        Ok(PatternNode::Or(alternatives))
+    }
+
+    /// Parses a single pattern alternative: `_` (wildcard), a literal or literal range `lo..=hi`, a
+    /// bare identifier (a binding, later reinterpreted as a unit variant by the analyzer when it
+    /// names one), a variant pattern `Variant(sub, ...)` / `Enum.Variant(sub, ...)`, a tuple
+    /// pattern `(sub, ...)`, a type pattern `Circle c`, or a class pattern `Point { x: 0, y }`.
+    fn parse_primary_pattern(&mut self) -> Result<PatternNode, Error> {
         let cur = self.current_token();
+        // A type followed by a binding or a field list: `int n`, `Box<int> b`, `Point { x, y }`.
+        let starts_type = match cur.kind {
+            TokenKind::DataTypeToken => true,
+            TokenKind::IdentifierToken if cur.text != "_" => {
+                // Skip a module qualifier (`shapes.Circle c`); `Enum.Variant(...)` is followed by
+                // a `(` instead, so it still parses as a variant pattern.
+                let mut i = 1;
+                while self.peek_token(i).kind == TokenKind::DotToken
+                    && self.peek_token(i + 1).kind == TokenKind::IdentifierToken
+                {
+                    i += 2;
+                }
+                matches!(
+                    self.peek_token(i).kind,
+                    TokenKind::IdentifierToken
+                        | TokenKind::SmallerThanToken
+                        | TokenKind::OpenBracketToken
+                        | TokenKind::CurlyOpenBracketToken
+                )
+            }
+            _ => false,
+        };
+        if starts_type {
+            let ty = self.parse_type()?;
+            if self.current_token().kind == TokenKind::CurlyOpenBracketToken {
+                self.match_token(TokenKind::CurlyOpenBracketToken);
+                let fields = self.parse_delimited_list(TokenKind::CurlyCloseBracketToken, |p| {
+                    let name = p.match_token(TokenKind::IdentifierToken);
+                    if p.current_token().kind == TokenKind::ColonToken {
+                        p.match_token(TokenKind::ColonToken);
+                        Ok((name, p.parse_pattern()?))
+                    } else {
+                        Ok((name.clone(), PatternNode::Binding(name)))
+                    }
+                })?;
+                return Ok(PatternNode::Class(ty, fields));
+            }
+            let binding = self.match_token(TokenKind::IdentifierToken);
+            return Ok(PatternNode::TypeTest(ty, binding));
+        }
         match cur.kind {
+            TokenKind::OpenParenthesisToken => {
+                let open = self.match_token(TokenKind::OpenParenthesisToken);
+                let subs =
+                    self.parse_delimited_list(TokenKind::CloseParenthesisToken, |p| p.parse_pattern())?;
+                self.check_tuple_arity(&open, subs.len());
+                Ok(PatternNode::Tuple(open, subs))
+            }
             TokenKind::IdentifierToken => {
                 if cur.text == "_" {
                     let tok = self.next_token();
@@ -434,7 +731,15 @@ impl<'a, 'b> Parser<'a, 'b> {
                 // A bare identifier: a binding (or a unit variant, resolved during analysis).
                 Ok(PatternNode::Binding(first))
             }
-            _ => Ok(PatternNode::Literal(self.parse_literal_pattern()?)),
+            _ => {
+                let literal = self.parse_literal_pattern()?;
+                if self.current_token().kind != TokenKind::DotDotEqualToken {
+                    return Ok(PatternNode::Literal(literal));
+                }
+                self.match_token(TokenKind::DotDotEqualToken);
+                let upper = self.parse_literal_pattern()?;
+                Ok(PatternNode::Range(literal, upper))
+            }
         }
     }
 
@@ -488,13 +793,7 @@ impl<'a, 'b> Parser<'a, 'b> {
                 self.match_token(TokenKind::NullToken);
                 Ok(Type::Nullable(Box::new(Type::Void)))
             }
-            TokenKind::CharToken => {
-                let tok = self.next_token();
-                let value = Self::char_literal_value(&tok.text);
-                let char_token =
-                    SyntaxToken::new(TokenKind::CharToken, tok.position, value.to_string());
-                Ok(Type::Char(char_token))
-            }
+            TokenKind::CharToken => Ok(Type::Char(self.char_literal())),
             TokenKind::MinusToken | TokenKind::NumberToken => {
                 let negative = cur.kind == TokenKind::MinusToken;
                 if negative {
@@ -707,6 +1006,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             diagnostics: &mut *self.diagnostics,
             foreach_counter: 0,
             type_aliases: self.type_aliases.clone(),
+            in_switch_guard: false,
         };
         let expr = sub.parse_expression(0)?;
         if sub.current_token().kind != EndOfFileToken {
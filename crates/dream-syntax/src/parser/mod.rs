@@ -1,6 +1,7 @@
 use dream_diagnostics::DiagnosticBag;
 use crate::lexer::Lexer;
-use crate::nodes::{ProgramNode, Type};
+use crate::nodes::types::WHERE_CLAUSE;
+use crate::nodes::{GenericBound, ProgramNode, Type};
 use crate::syntax_tree::SyntaxTree;
 use dream_text::line_text::LineText;
 use dream_text::text_span::TextSpan;
@@ -27,6 +28,9 @@ pub struct Parser<'a, 'b> {
     /// Declared type aliases (`type Foo = Bar;`). Resolved (erased) at parse time so the rest of
     /// the compiler never sees the alias name.
     type_aliases: HashMap<String, Type>,
+    /// True while parsing a `switch` arm guard, where a `(...) =>` or `x =>` ends the guard (the
+    /// `=>` introduces the arm body) rather than starting a lambda.
+    in_switch_guard: bool,
 }
 
 impl<'a, 'b> Parser<'a, 'b> {
@@ -40,6 +44,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             diagnostics,
             foreach_counter: 0,
             type_aliases: HashMap::new(),
+            in_switch_guard: false,
         }
     }
     //returns the new eof token
@@ -118,6 +123,19 @@ impl<'a, 'b> Parser<'a, 'b> {
             SyntaxToken::new(kind, err_pos, "".to_string())
         }
     }
+    /// Matches a member name: an identifier, or one of the keywords `true`/`false`/`extend`, which
+    /// name members like `Assert.true` or `list.extend` unambiguously after a `.` or `fun`.
+    /// Returned as an identifier token either way.
+    fn match_member_name(&mut self) -> SyntaxToken {
+        if matches!(
+            self.current_token().kind,
+            TokenKind::BooleanToken | TokenKind::ExtendToken
+        ) {
+            let token = self.next_token();
+            return SyntaxToken { kind: TokenKind::IdentifierToken, ..token };
+        }
+        self.match_token(TokenKind::IdentifierToken)
+    }
     /// True if the current token can close a generic argument list: either a plain `>` or the
     /// first half of a `>>` (`ShiftRightToken`), which appears when two generic lists end
     /// together, e.g. the `>>` in `Box<Box<int>>`.
@@ -194,7 +212,8 @@ impl<'a, 'b> Parser<'a, 'b> {
 
     /// Parses an optional generic *parameter* declaration list `<T, U, ...>` of bare identifiers
     /// (the declaration side; [`parse_generic_args`] parses concrete type *arguments*). Returns
-    /// `None` when no `<` follows. Shared by enum/struct/extend/function declarations.
+    /// `None` when no `<` follows. Used by enum and extend declarations, whose parameters take no
+    /// bounds (see [`parse_bounded_generic_params`]).
     fn parse_identifier_generic_params(&mut self) -> Option<Vec<SyntaxToken>> {
         if self.current_token().kind != TokenKind::SmallerThanToken {
             return None;
@@ -215,6 +234,74 @@ impl<'a, 'b> Parser<'a, 'b> {
         Some(params)
     }
 
+    /// Parses an optional generic parameter list whose parameters may carry interface bounds,
+    /// `<T: Comparable, U: A + B, V>`, as accepted on functions, classes and interfaces. Returns the
+    /// bare parameter tokens (as [`parse_identifier_generic_params`] does) and the bounds.
+    fn parse_bounded_generic_params(
+        &mut self,
+    ) -> Result<(Option<Vec<SyntaxToken>>, Vec<GenericBound>), Error> {
+        if self.current_token().kind != TokenKind::SmallerThanToken {
+            return Ok((None, Vec::new()));
+        }
+        self.match_token(TokenKind::SmallerThanToken);
+        let mut params = Vec::new();
+        let mut bounds = Vec::new();
+        while !self.is_generic_close() && self.current_token().kind != TokenKind::EndOfFileToken {
+            let iter = self.current_token_index;
+            let param = self.match_token(TokenKind::IdentifierToken);
+            if self.current_token().kind == TokenKind::ColonToken {
+                self.match_token(TokenKind::ColonToken);
+                bounds.push(GenericBound {
+                    parameter: param.clone(),
+                    bounds: self.parse_bound_list()?,
+                });
+            }
+            params.push(param);
+            if self.current_token().kind == TokenKind::CommaToken {
+                self.match_token(TokenKind::CommaToken);
+            }
+            self.ensure_progress(iter);
+        }
+        self.match_generic_close();
+        Ok((Some(params), bounds))
+    }
+
+    /// Parses an optional trailing `where T: A + B, U: C` clause, appending its bounds. `where` is
+    /// a contextual keyword, recognized only where a declaration body or `;` would otherwise start.
+    fn parse_where_clause(&mut self, bounds: &mut Vec<GenericBound>) -> Result<(), Error> {
+        if self.current_token().kind != TokenKind::IdentifierToken
+            || self.current_token().text != WHERE_CLAUSE
+        {
+            return Ok(());
+        }
+        self.match_token(TokenKind::IdentifierToken);
+        loop {
+            let iter = self.current_token_index;
+            let parameter = self.match_token(TokenKind::IdentifierToken);
+            self.match_token(TokenKind::ColonToken);
+            bounds.push(GenericBound {
+                parameter,
+                bounds: self.parse_bound_list()?,
+            });
+            if self.current_token().kind != TokenKind::CommaToken {
+                break;
+            }
+            self.match_token(TokenKind::CommaToken);
+            self.ensure_progress(iter);
+        }
+        Ok(())
+    }
+
+    /// Parses the `+`-separated interfaces bounding one generic parameter (`A + B<int>`).
+    fn parse_bound_list(&mut self) -> Result<Vec<Type>, Error> {
+        let mut bounds = vec![self.parse_type()?];
+        while self.current_token().kind == TokenKind::PlusToken {
+            self.match_token(TokenKind::PlusToken);
+            bounds.push(self.parse_type()?);
+        }
+        Ok(bounds)
+    }
+
     /// Recovery guard for token-consuming loops: if no token has been consumed since `mark`,
     /// skip one token so malformed input surfaces an error (already reported by the failing
     /// `match_token`) instead of spinning forever. Never advances past end-of-file.
@@ -262,14 +349,17 @@ impl<'a, 'b> Parser<'a, 'b> {
     }
 
     /// Returns the kind of the first token at or after the cursor that is not a leading
-    /// declaration modifier (`public`, `static`, `async`). Used to classify a top-level
+    /// declaration modifier (`public`, `static`, `async`, `virtual`). Used to classify a top-level
     /// declaration regardless of the order/number of modifiers preceding its core keyword
     /// (e.g. `public static let`, `public async fun`).
     fn first_keyword_after_modifiers(&self) -> TokenKind {
         let mut i = 0;
         loop {
             match self.peek_token(i).kind {
-                TokenKind::PublicToken | TokenKind::StaticToken | TokenKind::AsyncToken => i += 1,
+                TokenKind::PublicToken
+                | TokenKind::StaticToken
+                | TokenKind::AsyncToken
+                | TokenKind::VirtualToken => i += 1,
                 other => return other,
             }
         }
@@ -282,7 +372,10 @@ impl<'a, 'b> Parser<'a, 'b> {
         let mut i = 0;
         loop {
             match self.peek_token(i).kind {
-                TokenKind::PublicToken | TokenKind::StaticToken | TokenKind::AsyncToken => i += 1,
+                TokenKind::PublicToken
+                | TokenKind::StaticToken
+                | TokenKind::AsyncToken
+                | TokenKind::VirtualToken => i += 1,
                 TokenKind::AtToken => {
                     i += 1; // `@`
                     if self.peek_token(i).kind == TokenKind::IdentifierToken {
@@ -349,7 +442,7 @@ impl<'a, 'b> Parser<'a, 'b> {
                     Ok(iface) => interfaces.push(iface),
                     Err(_) => self.recover_to_next_declaration(),
                 }
-            } else if cur == TokenKind::EnumToken
+            } else if core == TokenKind::EnumToken
                 || (cur == TokenKind::AtToken
                     && self.core_keyword_after_attrs() == TokenKind::EnumToken)
             {
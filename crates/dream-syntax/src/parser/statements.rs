@@ -1,5 +1,5 @@
 use super::Parser;
-use crate::nodes::{ExpressionNode, StatementNode, Type};
+use crate::nodes::{CatchClause, ExpressionNode, StatementNode, Type};
 use crate::token::syntax_token::SyntaxToken;
 use crate::token::token_kind::TokenKind;
 use std::io::Error;
@@ -41,6 +41,26 @@ impl<'a, 'b> Parser<'a, 'b> {
         }
     }
 
+    /// Consumes a char literal token, returning a `CharToken` whose text is its value. A `char` is
+    /// one byte of a string's UTF-8 encoding, so a literal outside ASCII (`'é'`, `'😀'`) cannot be
+    /// one: it is reported, pointing at the string or code-point alternatives.
+    pub(super) fn char_literal(&mut self) -> SyntaxToken {
+        let tok = self.next_token();
+        let value = Self::char_literal_value(&tok.text);
+        if value > 0x7F {
+            self.diagnostics.report_error(
+                format!(
+                    "Char literal {} is not a single byte: a char is one byte of UTF-8, not a code point; use a string (\"{}\") or its code point ({})",
+                    tok.text,
+                    tok.text.trim_matches('\''),
+                    value
+                ),
+                Some(tok.position),
+            );
+        }
+        SyntaxToken::new(TokenKind::CharToken, tok.position, value.to_string())
+    }
+
     /// Computes the integer code point of a char literal token (text still includes the
     /// surrounding single quotes), resolving common escape sequences.
     pub(super) fn char_literal_value(text: &str) -> i32 {
@@ -112,12 +132,16 @@ impl<'a, 'b> Parser<'a, 'b> {
             TokenKind::SwitchToken => Ok(self.parse_switch()?),
             TokenKind::BreakToken => Ok(self.parse_break()?),
             TokenKind::ContinueToken => Ok(self.parse_continue()?),
+            TokenKind::ThrowToken => Ok(self.parse_throw()?),
+            TokenKind::TryToken => Ok(self.parse_try()?),
             // `await <future-expr>;` as a statement, discarding the resolved value.
             TokenKind::AwaitToken => {
                 let expr = self.parse_expression(0)?;
                 self.match_token(TokenKind::SemicolonToken);
                 match expr {
                     ExpressionNode::Await(inner) => Ok(StatementNode::AwaitStmt(inner.clone())),
+                    // `await f()?;` propagates the awaited result's failure and discards its value.
+                    ExpressionNode::Propagate(..) => Ok(StatementNode::ExpressionStatement(expr)),
                     other => Ok(StatementNode::AwaitStmt(other)),
                 }
             }
@@ -132,6 +156,7 @@ impl<'a, 'b> Parser<'a, 'b> {
             TokenKind::IdentifierToken => {
                 // Parse an expression first
                 let expr = self.parse_primary_expression()?;
+                let expr = self.parse_propagation(expr)?;
 
                 if self.current_token().kind == TokenKind::EqualToken {
                     self.match_token(TokenKind::EqualToken);
@@ -187,6 +212,8 @@ impl<'a, 'b> Parser<'a, 'b> {
                         ExpressionNode::MethodCall(obj, member, generic_args, params) => Ok(
                             StatementNode::MethodInvocation(obj, member, generic_args, params),
                         ),
+                        // `check(x)?;` keeps only the propagation; the unwrapped value is discarded.
+                        ExpressionNode::Propagate(..) => Ok(StatementNode::ExpressionStatement(expr)),
                         _ => {
                             self.diagnostics.report_error(
                                 "Expected function call but found expression".to_string(),
@@ -237,6 +264,8 @@ impl<'a, 'b> Parser<'a, 'b> {
             | TokenKind::DoToken
             | TokenKind::ReturnToken
             | TokenKind::SwitchToken
+            | TokenKind::ThrowToken
+            | TokenKind::TryToken
             | TokenKind::CurlyCloseBracketToken
             | TokenKind::EndOfFileToken => {
                 return;
@@ -264,6 +293,8 @@ impl<'a, 'b> Parser<'a, 'b> {
                 | TokenKind::DoToken
                 | TokenKind::ReturnToken
                 | TokenKind::SwitchToken
+                | TokenKind::ThrowToken
+                | TokenKind::TryToken
                 | TokenKind::CurlyCloseBracketToken => {
                     return;
                 }
@@ -283,6 +314,14 @@ impl<'a, 'b> Parser<'a, 'b> {
         } else {
             self.match_token(TokenKind::LetToken);
         }
+        // `let (a, b) = expr;` destructures a tuple.
+        if self.current_token().kind == TokenKind::OpenParenthesisToken {
+            let pattern = self.parse_pattern()?;
+            self.match_token(TokenKind::EqualToken);
+            let expression = self.parse_expression(0)?;
+            self.match_token(TokenKind::SemicolonToken);
+            return Ok(StatementNode::DestructuringDeclaration(pattern, expression, is_const));
+        }
         let identifier = self.match_token(TokenKind::IdentifierToken);
 
         // Optional type annotation
@@ -382,6 +421,41 @@ impl<'a, 'b> Parser<'a, 'b> {
             ));
         }
 
+        // Destructuring for-each: `for (let (k, v) in pairs) { ... }` binds each element to a
+        // synthetic local and opens the body with `let (k, v) = <element>;`.
+        if self.current_token().kind == TokenKind::LetToken
+            && self.peek_token(1).kind == TokenKind::OpenParenthesisToken
+        {
+            self.match_token(TokenKind::LetToken);
+            let position = self.current_token().position;
+            let pattern = self.parse_pattern()?;
+            self.match_token(TokenKind::InToken);
+            let iterable = self.parse_expression(0)?;
+            self.match_token(TokenKind::CloseParenthesisToken);
+            let body = self.parse_block()?;
+
+            let n = self.foreach_counter;
+            self.foreach_counter += 1;
+            let element = SyntaxToken::new(
+                TokenKind::IdentifierToken,
+                position,
+                crate::nodes::types::foreach_element_local(n),
+            );
+            let destructure = StatementNode::DestructuringDeclaration(
+                pattern,
+                ExpressionNode::Identifier(element.clone()),
+                false,
+            );
+            let mut statements = vec![destructure];
+            statements.extend(body.iter().cloned());
+            let body = self.arena.alloc_slice_fill_iter(statements);
+            let index_name = crate::nodes::types::foreach_index_local(n);
+            let array_name = crate::nodes::types::foreach_array_local(n);
+            return Ok(StatementNode::ForEach(
+                element, iterable, index_name, array_name, body,
+            ));
+        }
+
         let mut init: Option<&'a StatementNode<'a>> = None;
         if self.current_token().kind != TokenKind::SemicolonToken {
             if self.current_token().kind == TokenKind::LetToken {
@@ -554,4 +628,46 @@ impl<'a, 'b> Parser<'a, 'b> {
         self.match_token(TokenKind::SemicolonToken);
         Ok(StatementNode::Continue(label))
     }
+
+    /// Parses `throw <expr>;`.
+    pub(super) fn parse_throw(&mut self) -> Result<StatementNode<'a>, Error> {
+        let keyword = self.match_token(TokenKind::ThrowToken);
+        let value = self.parse_expression(0)?;
+        self.match_token(TokenKind::SemicolonToken);
+        Ok(StatementNode::Throw(keyword, value))
+    }
+    /// Parses `try { ... }` followed by any number of `catch (name[: Type]) { ... }` clauses and an
+    /// optional `finally { ... }`. At least one `catch` or the `finally` is required.
+    pub(super) fn parse_try(&mut self) -> Result<StatementNode<'a>, Error> {
+        let keyword = self.match_token(TokenKind::TryToken);
+        let body = self.parse_block()?;
+        let mut catches = Vec::new();
+        while self.current_token().kind == TokenKind::CatchToken {
+            self.match_token(TokenKind::CatchToken);
+            self.match_token(TokenKind::OpenParenthesisToken);
+            let name = self.match_token(TokenKind::IdentifierToken);
+            let type_ = if self.current_token().kind == TokenKind::ColonToken {
+                self.match_token(TokenKind::ColonToken);
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            self.match_token(TokenKind::CloseParenthesisToken);
+            let body = self.parse_block()?;
+            catches.push(CatchClause { name, type_, body });
+        }
+        let finally = if self.current_token().kind == TokenKind::FinallyToken {
+            self.match_token(TokenKind::FinallyToken);
+            Some(self.parse_block()?)
+        } else {
+            None
+        };
+        if catches.is_empty() && finally.is_none() {
+            self.diagnostics.report_error(
+                "Expected 'catch' or 'finally' after 'try' block".to_string(),
+                Some(self.current_token().position),
+            );
+        }
+        Ok(StatementNode::Try(keyword, body, catches, finally))
+    }
 }
@@ -10,4 +10,7 @@ impl<'a> SyntaxTree<'a> {
     pub fn get_root(&self) -> &ProgramNode<'a> {
         &self.root
     }
+    pub fn into_root(self) -> ProgramNode<'a> {
+        self.root
+    }
 }
@@ -0,0 +1,77 @@
+use super::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use dream_diagnostics::DiagnosticBag;
+use pretty_assertions::assert_eq;
+
+fn parse<'a>(code: &str, arena: &'a Bump) -> Option<ProgramNode<'a>> {
+    let mut diagnostics = DiagnosticBag::new(None);
+    let lexer = Lexer::new(code.to_string());
+    let mut parser = Parser::new(lexer, arena, &mut diagnostics);
+    let tree = parser.parse().ok()?;
+    if diagnostics.has_errors() {
+        return None;
+    }
+    Some(tree.get_root().clone())
+}
+
+#[test]
+fn test_token_kinds_are_listed_in_declaration_order() {
+    for (i, kind) in TOKEN_KINDS.iter().enumerate() {
+        assert_eq!(*kind as usize, i, "{:?} is out of place", kind);
+    }
+}
+
+#[test]
+fn test_programs_round_trip() {
+    // Every end-to-end case that parses: together they use each kind of node.
+    let cases = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/cases");
+    let mut round_tripped = 0;
+    for entry in std::fs::read_dir(cases).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("dream") {
+            continue;
+        }
+        let code = std::fs::read_to_string(&path).unwrap();
+        let arena = Bump::new();
+        let program = match parse(&code, &arena) {
+            Some(program) => program,
+            None => continue,
+        };
+        let bytes = encode(&program);
+        let decoded_arena = Bump::new();
+        let decoded: ProgramNode = decode(&bytes, &decoded_arena)
+            .unwrap_or_else(|| panic!("{} did not decode", path.display()));
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", program));
+        round_tripped += 1;
+    }
+    assert!(round_tripped > 100);
+}
+
+#[test]
+fn test_malformed_input_does_not_decode() {
+    let arena = Bump::new();
+    let program = parse("fun main(): int { let x = [1, 2]; return x[0]; }", &arena).unwrap();
+    let bytes = encode(&program);
+    // Truncated, or with bytes left over.
+    assert!(decode::<ProgramNode>(&bytes[..bytes.len() - 1], &arena).is_none());
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert!(decode::<ProgramNode>(&longer, &arena).is_none());
+    // A collection length larger than the input.
+    assert!(decode::<Vec<u8>>(&[0xff, 0xff, 0x03], &arena).is_none());
+    // An unknown enum tag.
+    assert!(decode::<Type>(&[200], &arena).is_none());
+}
+
+#[test]
+fn test_integers_round_trip() {
+    let arena = Bump::new();
+    for value in [0i64, 1, -1, 63, -64, 64, i64::MAX, i64::MIN] {
+        assert_eq!(decode::<i64>(&encode(&value), &arena), Some(value));
+    }
+    for value in [0u64, 127, 128, u64::MAX] {
+        assert_eq!(decode::<u64>(&encode(&value), &arena), Some(value));
+    }
+    assert_eq!(decode::<u32>(&encode(&u64::MAX), &arena), None);
+}
@@ -227,6 +227,55 @@ fn test_parse_switch_arm_guard() {
     assert!(arms[1].guard.is_none());
 }
 
+#[test]
+fn test_parse_lambda_forms() {
+    let code = "fun f(): void { g((x) => x + 1); g(x => x); g((a: int, b): int => { return a; }); g(() => 0); }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let func = &program.functions[0];
+    let lambdas: Vec<_> = func
+        .body
+        .iter()
+        .map(|stmt| {
+            let StatementNode::FunctionInvocation(_, _, args) = stmt else {
+                panic!("expected a call statement");
+            };
+            let ExpressionNode::Lambda(lambda) = &args[0] else {
+                panic!("expected a lambda argument");
+            };
+            *lambda
+        })
+        .collect();
+
+    use crate::nodes::LambdaBody;
+    assert_eq!(lambdas[0].parameters.len(), 1);
+    assert!(lambdas[0].parameters[0].type_.is_none());
+    assert!(matches!(lambdas[0].body, LambdaBody::Expr(ExpressionNode::Binary(..))));
+    assert_eq!(lambdas[1].parameters[0].name.text, "x");
+    assert!(matches!(lambdas[2].parameters[0].type_, Some(Type::Integer(_))));
+    assert!(lambdas[2].parameters[1].type_.is_none());
+    assert!(matches!(lambdas[2].return_type, Some(Type::Integer(_))));
+    assert!(matches!(lambdas[2].body, LambdaBody::Block(_)));
+    assert!(lambdas[3].parameters.is_empty());
+}
+
+#[test]
+fn test_parse_lambda_does_not_capture_ternary_or_guard() {
+    // `(a) : b` in a ternary and `(n > 0) =>` after a guard are not lambdas.
+    let code = "fun f(c: bool, a: int, b: int, o: Option): int { let x = c ? (a) : b; return switch (o) { Some(n) if (n > 0) => n, _ => x }; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let func = &program.functions[0];
+    let StatementNode::Return(Some(ExpressionNode::Switch(_subject, arms))) = &func.body[1] else {
+        panic!("expected a return of a switch expression");
+    };
+    assert!(matches!(arms[0].guard, Some(ExpressionNode::Parenthesized(_))));
+}
+
 #[test]
 fn test_parse_switch_statement_pattern_arms() {
     // A pattern-arm `switch` used as a statement parses to an `ExpressionStatement` wrapping an
@@ -373,6 +422,35 @@ fn test_parse_const_and_labeled_break() {
     }
 }
 
+#[test]
+fn test_parse_throw_and_try_catch_finally() {
+    let code = "fun test(): void { try { throw Exception(\"x\"); } catch (e: Exception) { } catch (other) { } finally { } }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let func = &program.functions[0];
+    if let StatementNode::Try(_, body, catches, finally) = &func.body[0] {
+        assert!(matches!(&body[0], StatementNode::Throw(_, _)));
+        assert_eq!(catches.len(), 2);
+        assert_eq!(catches[0].name.text, "e");
+        assert!(catches[0].type_.is_some());
+        assert_eq!(catches[1].name.text, "other");
+        assert!(catches[1].type_.is_none());
+        assert!(finally.is_some());
+    } else {
+        panic!("Expected try statement");
+    }
+}
+
+#[test]
+fn test_parse_try_requires_catch_or_finally() {
+    let code = "fun test(): void { try { } }";
+    let arena = bumpalo::Bump::new();
+    let (_, diagnostics) = parse_code(code, &arena);
+    assert!(diagnostics.has_errors());
+}
+
 #[test]
 fn test_parse_char_literal() {
     let code = "fun test(): void { let c: char = 'A'; }";
@@ -390,6 +468,19 @@ fn test_parse_char_literal() {
     }
 }
 
+#[test]
+fn test_parse_non_ascii_char_literal_is_rejected() {
+    // A `char` is one UTF-8 byte, so a literal needing more than one is reported.
+    let code = "fun test(): void { let c: char = '\u{e9}'; }";
+    let arena = bumpalo::Bump::new();
+    let (_, diagnostics) = parse_code(code, &arena);
+
+    let message = &diagnostics.errors().next().expect("non-ASCII char literal").message;
+    assert!(message.contains("not a single byte"), "{}", message);
+    assert!(message.contains("not a code point"), "{}", message);
+    assert!(message.contains("(233)"), "{}", message);
+}
+
 #[test]
 fn test_parse_suffixed_number_literals() {
     // The suffix selects the literal's concrete numeric type and is stripped from the token text.
@@ -566,7 +657,7 @@ fn assert_parses_without_panic(code: &str) {
 
 #[test]
 fn fuzz_random_token_soup_never_panics() {
-    const TOKENS: [&str; 64] = [
+    const TOKENS: [&str; 68] = [
         "fun",
         "class",
         "enum",
@@ -587,6 +678,10 @@ fn fuzz_random_token_soup_never_panics() {
         "default",
         "break",
         "continue",
+        "throw",
+        "try",
+        "catch",
+        "finally",
         "import",
         "type",
         "constructor",
@@ -718,6 +813,23 @@ fn test_parse_interface_declaration() {
     assert!(iface.methods[0].body.is_empty());
 }
 
+#[test]
+fn test_parse_interface_default_method() {
+    let code = "interface Shape { fun area(): int; fun describe(): string { return \"shape\"; } fun reset(): void {} }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let methods = &program.interfaces[0].methods;
+    assert_eq!(methods.len(), 3);
+    assert!(!methods[0].has_default_body);
+    assert!(methods[1].has_default_body);
+    assert_eq!(methods[1].body.len(), 1);
+    // An empty block is still a default body.
+    assert!(methods[2].has_default_body);
+    assert!(methods[2].body.is_empty());
+}
+
 #[test]
 fn test_parse_class_implements_clause() {
     let code = "class Cat : Animal, Pet { fun call(): string { return \"meow\"; } }";
@@ -732,6 +844,51 @@ fn test_parse_class_implements_clause() {
     assert_eq!(implemented, vec!["Animal".to_string(), "Pet".to_string()]);
 }
 
+#[test]
+fn test_parse_virtual_method_and_base_class() {
+    let code = "class Dog : Animal, Pet { constructor() { super(); } public virtual fun speak(): string { return \"woof\"; } @override public fun name(): string { return \"dog\"; } }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let dog = &program.structs[0];
+    let named: Vec<String> = dog.implements.iter().map(|t| t.get_type()).collect();
+    assert_eq!(named, vec!["Animal".to_string(), "Pet".to_string()]);
+    let speak = dog.methods.iter().find(|m| m.name.text == "speak").unwrap();
+    assert!(speak.is_virtual);
+    assert!(speak.is_public);
+    let name = dog.methods.iter().find(|m| m.name.text == "name").unwrap();
+    assert!(!name.is_virtual);
+}
+
+#[test]
+fn test_parse_misplaced_override_reports_only_the_modifier_error() {
+    // The member is skipped once the mistake is reported, so nothing follows from reading
+    // `override` as a field name; the members after it still parse.
+    let cases = [
+        ("override fun speak", "'override' is not a modifier"),
+        ("public virtual override fun speak", "'override' is not a modifier"),
+        ("public @override fun speak", "go before a member's modifiers"),
+    ];
+    for (member, expected) in cases {
+        let code = format!(
+            "class Dog : Animal {{ {}(): string {{ return \"woof\"; }} fun name(): string {{ return \"dog\"; }} }} fun main(): void {{ }}",
+            member
+        );
+        let arena = bumpalo::Bump::new();
+        let (program, diagnostics) = parse_code(&code, &arena);
+
+        let errors: Vec<&str> = diagnostics.errors().map(|e| e.message.as_str()).collect();
+        assert_eq!(errors.len(), 1, "{}: {:?}", member, errors);
+        assert!(errors[0].contains(expected), "{}: {:?}", member, errors);
+        let dog = &program.structs[0];
+        let methods: Vec<&str> = dog.methods.iter().map(|m| m.name.text.as_str()).collect();
+        assert_eq!(methods, vec!["name"]);
+        assert!(dog.fields.is_empty());
+        assert_eq!(program.functions.len(), 1);
+    }
+}
+
 #[test]
 fn test_parse_class_implements_generic_interface() {
     let code = "class Box<T> : Container<int> { fun get(): int { return 0; } }";
@@ -745,6 +902,49 @@ fn test_parse_class_implements_generic_interface() {
     assert_eq!(implemented, vec!["Container<int>".to_string()]);
 }
 
+#[test]
+fn test_parse_inline_generic_bounds() {
+    let code = "fun max<T: Comparable<T>, U: Show + Hash, V>(a: T, b: T): T { return a; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let func = &program.functions[0];
+    let params: Vec<&str> = func.generic_parameters.as_ref().unwrap().iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(params, vec!["T", "U", "V"]);
+    let bounds: Vec<(String, Vec<String>)> = func
+        .generic_bounds
+        .iter()
+        .map(|b| (b.parameter.text.clone(), b.bounds.iter().map(|t| t.display_name()).collect()))
+        .collect();
+    assert_eq!(
+        bounds,
+        vec![
+            ("T".to_string(), vec!["Comparable<T>".to_string()]),
+            ("U".to_string(), vec!["Show".to_string(), "Hash".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_where_clause_bounds() {
+    let code = "class Sorted<T> : Container<T> where T: Comparable + Show { }
+fun pick<K, V>(k: K): V where K: Hash, V: Show { return k; }
+interface Index<T> where T: Hash { fun at(key: T): int; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let class = &program.structs[0];
+    assert_eq!(class.implements.len(), 1);
+    assert_eq!(class.generic_bounds.len(), 1);
+    assert_eq!(class.generic_bounds[0].bounds.len(), 2);
+    let func = &program.functions[0];
+    let bounded: Vec<&str> = func.generic_bounds.iter().map(|b| b.parameter.text.as_str()).collect();
+    assert_eq!(bounded, vec!["K", "V"]);
+    assert_eq!(program.interfaces[0].generic_bounds[0].parameter.text, "T");
+}
+
 #[test]
 fn test_parse_is_with_binding() {
     let code = "fun f(o: object): void { if (o is int a) { print(a); } }";
@@ -923,6 +1123,56 @@ fn test_parse_single_segment_import() {
     assert_eq!(program.imports[0].module_name.text, "math_lib");
 }
 
+#[test]
+fn test_parse_aliased_and_selective_imports() {
+    let code = "import geometry.shapes as gs;\nimport { Circle, area } from geometry.shapes;\nimport util;\nfun main(): void {}";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    assert_eq!(program.imports.len(), 3);
+    let aliased = &program.imports[0];
+    assert_eq!(aliased.module_name.text, "geometry/shapes");
+    assert_eq!(aliased.qualifier(), Some("gs"));
+    let selective = &program.imports[1];
+    assert_eq!(selective.module_name.text, "geometry/shapes");
+    let names: Vec<&str> = selective.names.as_ref().unwrap().iter().map(|n| n.text.as_str()).collect();
+    assert_eq!(names, vec!["Circle", "area"]);
+    assert_eq!(selective.qualifier(), None);
+    assert_eq!(program.imports[2].qualifier(), Some("util"));
+}
+
+#[test]
+fn test_parse_public_enum() {
+    let code = "public enum Kind { Round, Square }\nenum Hidden { A }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    assert!(program.enums[0].is_public);
+    assert!(!program.enums[1].is_public);
+}
+
+#[test]
+fn test_parse_module_qualified_types() {
+    let code = "fun f(c: gs.Circle): gs.Box<gs.Circle>[] { let x = (gs.Circle)c; let y = (c.r) - 1; return []; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let f = &program.functions[0];
+    assert_eq!(f.parameters[0].type_.get_type(), "gs.Circle");
+    assert_eq!(f.return_type.as_ref().unwrap().display_name(), "gs.Box<gs.Circle>[]");
+    let StatementNode::Declaration(_, _, ExpressionNode::Cast(ty, _), _) = &f.body[0] else {
+        panic!("expected a cast");
+    };
+    assert_eq!(ty.get_type(), "gs.Circle");
+    assert!(matches!(
+        &f.body[1],
+        StatementNode::Declaration(_, _, ExpressionNode::Binary(..), _)
+    ));
+}
+
 #[test]
 fn test_parenthesized_comparison_is_not_a_cast() {
     // `(x) < y` is a comparison, not a `(Type)expr` cast: the generic lookahead must not
@@ -937,3 +1187,206 @@ fn test_parenthesized_comparison_is_not_a_cast() {
         other => panic!("expected a `<` comparison, got {:?}", other),
     }
 }
+
+#[test]
+fn test_parse_postfix_propagation_vs_ternary() {
+    // `?` is postfix propagation when no operand follows it, and a ternary when one does; after a
+    // `+`/`-` it is a ternary only if a `:` follows.
+    let arena = bumpalo::Bump::new();
+    let cases = [
+        ("let v = f(x)?;", true),
+        ("let v = f(x)?.next()?.id;", true),
+        ("let v = a? - b;", true),
+        ("let v = c ? -1 : 1;", false),
+        ("let v = c ? x : y;", false),
+        ("let v = c ? [a] : [b];", false),
+    ];
+    for (stmt, propagates) in cases {
+        let code = format!("fun main(): void {{ {} }}", stmt);
+        let (program, diagnostics) = parse_code(&code, &arena);
+        assert_eq!(diagnostics.has_errors(), false, "{}", stmt);
+        match only_decl_expr(&program) {
+            ExpressionNode::Ternary(..) => assert!(!propagates, "{} parsed as a ternary", stmt),
+            _ => assert!(propagates, "{} did not parse as a ternary", stmt),
+        }
+    }
+}
+
+#[test]
+fn test_parse_propagation_statement_and_precedence() {
+    let code = "fun main(): void { check(x)?; let n = -f()?; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let body = &program.functions[0].body;
+    assert!(matches!(
+        &body[0],
+        StatementNode::ExpressionStatement(ExpressionNode::Propagate(..))
+    ));
+    match &body[1] {
+        StatementNode::Declaration(_, _, ExpressionNode::Unary(_, inner), _) => {
+            assert!(matches!(inner, ExpressionNode::Propagate(..)))
+        }
+        other => panic!("expected `-(f()?)`, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_boolean_keywords_as_member_names() {
+    // `true`/`false` name a method after `fun` and after `.` (`Assert.true(c)`), but stay literals
+    // everywhere else.
+    let code = "class Assert { static fun true(c: bool): void {} }\nfun main(): void { let r = Assert.true(false); }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    assert_eq!(program.structs[0].methods[0].name.text, "true");
+    let func = program.functions.iter().find(|f| f.name.text == "main").unwrap();
+    match &func.body[0] {
+        StatementNode::Declaration(_, _, ExpressionNode::MethodCall(_, method, _, args), _) => {
+            assert_eq!(method.kind, TokenKind::IdentifierToken);
+            assert_eq!(method.text, "true");
+            assert!(matches!(args[0], ExpressionNode::Literal(_)));
+        }
+        other => panic!("expected an `Assert.true(..)` call, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_extend_keyword_as_member_name() {
+    // `extend` names a method (`list.extend(other)`) without disturbing `extend string { .. }`.
+    let code = "class L { fun extend(o: L): void {} }\nextend string { fun f(): int { return 1; } }\nfun main(): void { let l = L(); l.extend(l); }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    assert_eq!(program.structs[0].methods[0].name.text, "extend");
+    assert_eq!(program.extends.len(), 1);
+    let func = program.functions.iter().find(|f| f.name.text == "main").unwrap();
+    match &func.body[1] {
+        StatementNode::MethodInvocation(_, method, _, _) => {
+            assert_eq!(method.kind, TokenKind::IdentifierToken);
+            assert_eq!(method.text, "extend");
+        }
+        other => panic!("expected an `l.extend(..)` call, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_tuple_type_literal_and_element_access() {
+    let code = "fun f(): (int, string[])? { let t = (1, (a, \"b\")); return t.1.0; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let func = &program.functions[0];
+    assert_eq!(
+        func.return_type.as_ref().map(|t| t.display_name()),
+        Some("(int, string[])?".to_string())
+    );
+    match only_decl_expr(&program) {
+        ExpressionNode::Tuple(_, elements) => {
+            assert_eq!(elements.len(), 2);
+            assert!(matches!(elements[1], ExpressionNode::Tuple(_, _)));
+        }
+        other => panic!("expected a tuple literal, got {:?}", other),
+    }
+    // The lexer reads `1.0` as one number; it becomes two element accesses.
+    let StatementNode::Return(Some(ExpressionNode::MemberAccess(inner, last))) = &func.body[1] else {
+        panic!("expected `return t.1.0;`");
+    };
+    assert_eq!(last.text, "0");
+    assert!(matches!(inner, ExpressionNode::MemberAccess(_, first) if first.text == "1"));
+}
+
+#[test]
+fn test_parse_range_or_type_and_class_patterns() {
+    let code = "fun f(o: object): int { return switch (o) { \
+        1..=9 | 'a'..='z' => 1, \
+        Circle c => 2, \
+        Box<int> _ => 3, \
+        Point { x: 0, y } => y, \
+        _ => 0 }; }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    let StatementNode::Return(Some(ExpressionNode::Switch(_, arms))) = &program.functions[0].body[0]
+    else {
+        panic!("expected a return of a switch expression");
+    };
+    assert_eq!(arms.len(), 5);
+
+    use crate::nodes::PatternNode;
+    match &arms[0].pattern {
+        PatternNode::Or(alternatives) => {
+            assert_eq!(alternatives.len(), 2);
+            assert!(matches!(alternatives[0], PatternNode::Range(Type::Integer(_), Type::Integer(_))));
+            assert!(matches!(alternatives[1], PatternNode::Range(Type::Char(_), Type::Char(_))));
+        }
+        other => panic!("expected an or-pattern, got {:?}", other),
+    }
+    assert!(matches!(&arms[1].pattern, PatternNode::TypeTest(_, name) if name.text == "c"));
+    assert!(
+        matches!(&arms[2].pattern, PatternNode::TypeTest(Type::Struct(_, Some(args)), name) if args.len() == 1 && name.text == "_")
+    );
+    match &arms[3].pattern {
+        PatternNode::Class(Type::Struct(name, None), fields) => {
+            assert_eq!(name.text, "Point");
+            assert_eq!(fields.len(), 2);
+            assert!(matches!(fields[0].1, PatternNode::Literal(_)));
+            // `y` is shorthand for `y: y`.
+            assert!(matches!(&fields[1].1, PatternNode::Binding(b) if b.text == "y"));
+        }
+        other => panic!("expected a class pattern, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_destructuring_let_and_foreach() {
+    let code = "fun main(): void { const (a, (b, _)) = f(); for (let (k, v) in m.entries()) { g(k); } }";
+    let arena = bumpalo::Bump::new();
+    let (program, diagnostics) = parse_code(code, &arena);
+
+    assert_eq!(diagnostics.has_errors(), false);
+    use crate::nodes::PatternNode;
+    let body = &program.functions[0].body;
+    match &body[0] {
+        StatementNode::DestructuringDeclaration(PatternNode::Tuple(_, subs), _, is_const) => {
+            assert!(*is_const);
+            assert!(matches!(subs[0], PatternNode::Binding(_)));
+            assert!(matches!(subs[1], PatternNode::Tuple(_, _)));
+        }
+        other => panic!("expected a destructuring declaration, got {:?}", other),
+    }
+    // The loop binds a synthetic element and destructures it at the top of the body.
+    match &body[1] {
+        StatementNode::ForEach(element, _, _, _, loop_body) => {
+            assert_eq!(loop_body.len(), 2);
+            match &loop_body[0] {
+                StatementNode::DestructuringDeclaration(_, ExpressionNode::Identifier(id), false) => {
+                    assert_eq!(id.text, element.text)
+                }
+                other => panic!("expected the element destructuring, got {:?}", other),
+            }
+        }
+        other => panic!("expected a for-each loop, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tuple_arity_out_of_range_is_rejected() {
+    let arena = bumpalo::Bump::new();
+    for code in [
+        "fun f(x: (int)): void {}",
+        "fun main(): void { let t = (1, 2, 3, 4, 5, 6, 7, 8, 9); }",
+    ] {
+        let (_, diagnostics) = parse_code(code, &arena);
+        assert!(
+            diagnostics.errors().any(|e| e.message.contains("between 2 and 8 elements")),
+            "{}",
+            code
+        );
+    }
+}
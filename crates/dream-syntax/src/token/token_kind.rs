@@ -96,6 +96,8 @@ pub enum TokenKind {
     CommaToken,
     #[token(".")]
     DotToken,
+    #[token("..=")]
+    DotDotEqualToken,
     #[token("?")]
     QuestionMarkToken,
 
@@ -128,6 +130,14 @@ pub enum TokenKind {
     BreakToken,
     #[token("continue")]
     ContinueToken,
+    #[token("throw")]
+    ThrowToken,
+    #[token("try")]
+    TryToken,
+    #[token("catch")]
+    CatchToken,
+    #[token("finally")]
+    FinallyToken,
     #[token("let")]
     LetToken,
     #[token("const")]
@@ -140,6 +150,8 @@ pub enum TokenKind {
     AwaitToken,
     #[token("static")]
     StaticToken,
+    #[token("virtual")]
+    VirtualToken,
     #[token("import")]
     ImportToken,
     #[token("public")]
@@ -233,6 +245,7 @@ impl TokenKind {
             TokenKind::ColonToken => "':'",
             TokenKind::CommaToken => "','",
             TokenKind::DotToken => "'.'",
+            TokenKind::DotDotEqualToken => "'..='",
             TokenKind::QuestionMarkToken => "'?'",
             TokenKind::OpenParenthesisToken => "'('",
             TokenKind::CloseParenthesisToken => "')'",
@@ -248,12 +261,17 @@ impl TokenKind {
             TokenKind::ReturnToken => "'return'",
             TokenKind::BreakToken => "'break'",
             TokenKind::ContinueToken => "'continue'",
+            TokenKind::ThrowToken => "'throw'",
+            TokenKind::TryToken => "'try'",
+            TokenKind::CatchToken => "'catch'",
+            TokenKind::FinallyToken => "'finally'",
             TokenKind::LetToken => "'let'",
             TokenKind::ConstToken => "'const'",
             TokenKind::FunToken => "'fun'",
             TokenKind::AsyncToken => "'async'",
             TokenKind::AwaitToken => "'await'",
             TokenKind::StaticToken => "'static'",
+            TokenKind::VirtualToken => "'virtual'",
             TokenKind::ImportToken => "'import'",
             TokenKind::PublicToken => "'public'",
             TokenKind::ExternToken => "'extern'",
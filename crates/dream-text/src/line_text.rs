@@ -1,12 +1,18 @@
 pub struct LineText {
-    line_width: Vec<usize>,
+    /// The offset just past each line's end (its newline included): a running sum of the line
+    /// widths, so a position's line is found by binary search.
+    line_ends: Vec<usize>,
 }
 
 impl LineText {
     pub fn new(text: String) -> LineText {
-        LineText {
-            line_width: LineText::calculate_line_width(text),
+        let mut line_ends = LineText::calculate_line_width(text);
+        let mut sum = 0;
+        for end in line_ends.iter_mut() {
+            sum += *end;
+            *end = sum;
         }
+        LineText { line_ends }
     }
     ///internally used to calculate the line widths of the text
     fn calculate_line_width(input: String) -> Vec<usize> {
@@ -25,17 +31,14 @@ impl LineText {
     }
     ///returns the line number,column number of the token at the given index
     pub fn get_point(&self, start: usize) -> (usize, usize) {
-        let mut line_number = 0;
-        let mut sum: usize = 0;
-        // visit each line width
-        for i in self.line_width.iter() {
-            //if the sum+current_line_size is greater than the start index, then we are in the line
-            if sum + *i > start {
-                break;
-            }
-            sum += *i;
-            line_number += 1;
-        }
+        // The line is the first whose end lies past `start`; a position past the last line
+        // counts from the end of the text.
+        let line_number = self.line_ends.partition_point(|&end| end <= start);
+        let sum = if line_number == 0 {
+            0
+        } else {
+            self.line_ends[line_number - 1]
+        };
         (
             line_number + 1,
             if start > sum {
@@ -37,6 +37,9 @@ export const TAGS = {
 // Allocated pointers point at `data` (block_start + HEAP_HEADER_SIZE).
 export const HEAP_HEADER_SIZE = 12;
 
+// Size of a WebAssembly linear-memory page; the heap limit is set in whole pages.
+const WASM_PAGE_SIZE = 65536;
+
 /** Byte size of a single element of the given Dream type (see utils.rs `element_size_of`). */
 function elementSize(typeName) {
   if (typeName === "bool" || typeName === "char" || typeName === "byte") return 1;
@@ -93,8 +96,11 @@ function wasmToJs(inst, t, raw) {
  * Dream's heap layout so you can read/write strings, arrays, lists, and structs.
  */
 export class DreamInstance {
-  constructor(instance) {
+  constructor(instance, module) {
     this.instance = instance;
+    // Function index -> { name, file, line, lines: [[offset, line], ...] }, from the module's
+    // `dream.srcmap` custom section (empty when the module has none).
+    this.sourceMap = module ? readSourceMap(module) : new Map();
     this.exports = instance.exports;
     this.memory = instance.exports.memory;
     // JS-object handle registry backing the Dream `JsRef` type. A `JsRef` crosses the boundary
@@ -155,11 +161,19 @@ export class DreamInstance {
 
   /** Reads a null-terminated UTF-8 string at `ptr` (a Dream string data pointer). */
   readString(ptr) {
-    if (!ptr) return "";
+    return new TextDecoder("utf-8").decode(this.readStringBytes(ptr));
+  }
+
+  /**
+   * The raw UTF-8 bytes of the Dream string at `ptr` (a view, copy it to keep it). Unlike
+   * `readString`, bytes that are not valid UTF-8 on their own (half of a split character) survive.
+   */
+  readStringBytes(ptr) {
+    if (!ptr) return new Uint8Array(0);
     const bytes = this.bytes;
     let end = ptr;
     while (end < bytes.length && bytes[end] !== 0) end++;
-    return new TextDecoder("utf-8").decode(bytes.subarray(ptr, end));
+    return bytes.subarray(ptr, end);
   }
 
   /**
@@ -315,34 +329,123 @@ export class DreamInstance {
   }
 
   /**
-   * Wraps a Dream function value (an `i32` index into the exported `__indirect_function_table`)
-   * as a JS callable, so a Dream function passed to a `fun(...)`-typed extern parameter can be
-   * invoked by the host. `typeStr` is the Dream function type (e.g. `fun(int):void`) used to
-   * marshal arguments in and the result out.
+   * Wraps a Dream function value (a pointer to a closure block whose first word is an index into
+   * the exported `__indirect_function_table`) as a JS callable, so a Dream function passed to a
+   * `fun(...)`-typed extern parameter can be invoked by the host. The block is passed back as the
+   * hidden first argument, and is pinned (its refcount bumped) since the host may hold the callable
+   * past the Dream value's lifetime. `typeStr` is the Dream function type (e.g. `fun(int):void`)
+   * used to marshal arguments in and the result out.
    */
-  callback(index, typeStr = "fun():void") {
-    if (index < 0) return null;
+  callback(closure, typeStr = "fun():void") {
+    if (!closure) return null;
     const table = this.exports.__indirect_function_table;
     if (!table) throw new Error("module does not export its function table; cannot build a callback");
+    const index = this.i32(closure);
     const fn = table.get(index);
     if (typeof fn !== "function") {
       throw new Error(`no Dream function at table index ${index}`);
     }
+    this.view.setInt32(closure - 4, this.i32(closure - 4) + 1, true);
     const { params, result } = parseFunType(typeStr);
     return (...jsArgs) => {
       const raw = params.map((p, i) => jsToWasm(this, p, jsArgs[i]));
-      const out = fn(...raw);
+      const out = fn(closure, ...raw);
       return wasmToJs(this, result, out);
     };
   }
 
-  /** Calls the exported `main`, if present. Returns its result (if any). */
-  run() {
+  /**
+   * Calls the exported `main`, if present, passing `args` to a `main(args: string[])`. Returns its
+   * result (if any).
+   */
+  run(args = []) {
     if (typeof this.exports.main === "function") {
-      return this.exports.main();
+      const slot = this.exports.__dream_main_args;
+      if (slot && args.length > 0) slot.value = this.writeArray(args, "string");
+      try {
+        return this.exports.main();
+      } catch (e) {
+        throw this.withStackTrace(this.runtimeError() ?? e, e);
+      }
     }
     throw new Error("module has no exported `main`");
   }
+
+  /** Caps how far the allocator may grow linear memory, in bytes (rounded down to whole pages). */
+  setMaxHeap(bytes) {
+    const limit = this.exports.__dream_heap_max_pages;
+    if (!limit) throw new Error("module does not export a heap limit");
+    limit.value = Math.min(Math.floor(bytes / WASM_PAGE_SIZE), 65536);
+  }
+
+  /**
+   * After a trap, an Error describing the failed allocation if the allocator ran out of memory
+   * (it records the request size just before trapping), or null for any other trap.
+   */
+  outOfMemoryError() {
+    const request = this.exports.__dream_oom_request;
+    if (!request || request.value === 0) return null;
+    const limit = this.exports.__dream_heap_max_pages.value * WASM_PAGE_SIZE;
+    return new Error(
+      `out of memory: could not allocate ${request.value >>> 0} bytes (heap limit ${limit} bytes)`,
+    );
+  }
+
+  /**
+   * After a trap, an Error carrying the failed runtime check's message (an out-of-range index or a
+   * null field access), or null for any other trap.
+   */
+  panicError() {
+    const message = this.exports.__dream_panic_message;
+    if (!message || message.value === 0) return null;
+    return new Error(this.readString(message.value));
+  }
+
+  /** After a trap, the Dream-level error it stands for (out of memory or a failed check), or null. */
+  runtimeError() {
+    return this.outOfMemoryError() ?? this.panicError();
+  }
+
+  /**
+   * The Dream frames of a trap, innermost first, as `at Point.move (src/app.dream:42)` strings.
+   * Resolves the engine's `wasm-function[N]:0xOFFSET` stack entries through the source map; frames in
+   * runtime helpers have no source and are skipped.
+   */
+  stackTrace(trap) {
+    const frames = [];
+    const stack = trap && typeof trap.stack === "string" ? trap.stack : "";
+    for (const [, index, offset] of stack.matchAll(/wasm-function\[(\d+)\]:0x([0-9a-fA-F]+)/g)) {
+      const fn = this.sourceMap.get(Number(index));
+      if (!fn) continue;
+      const at = parseInt(offset, 16);
+      let line = fn.line;
+      for (const [marker, markerLine] of fn.lines) {
+        if (marker > at) break;
+        line = markerLine;
+      }
+      frames.push(`at ${fn.name} (${fn.file}:${line})`);
+    }
+    return frames;
+  }
+
+  /** `error` with the Dream stack trace of `trap` appended to its message (unchanged if none). */
+  withStackTrace(error, trap) {
+    const frames = this.stackTrace(trap);
+    if (frames.length === 0) return error;
+    const traced = new Error(`${error.message}\n${frames.map((f) => `    ${f}`).join("\n")}`);
+    traced.dreamStack = frames;
+    return traced;
+  }
+}
+
+/** Reads a module's `dream.srcmap` custom section (written by the compiler) into a Map. */
+function readSourceMap(module) {
+  const map = new Map();
+  for (const section of WebAssembly.Module.customSections(module, "dream.srcmap")) {
+    const { functions } = JSON.parse(new TextDecoder().decode(section));
+    for (const fn of functions) map.set(fn.index, fn);
+  }
+  return map;
 }
 
 /** Marshals raw WASM argument values into JS values per the parameter type names. */
@@ -350,7 +453,7 @@ function marshalArgs(inst, params, rawArgs) {
   if (!params) return rawArgs;
   return rawArgs.map((arg, i) => {
     const rawType = params[i] || "int";
-    if (isFunType(rawType)) return inst.callback(arg, rawType); // Dream fn index -> JS callable
+    if (isFunType(rawType)) return inst.callback(arg, rawType); // Dream closure -> JS callable
     const t = stripSuffix(rawType);
     if (t === "string") return inst.readString(arg);
     if (t === "JsRef") return inst.derefHandle(arg); // i32 handle id -> live JS value
@@ -690,13 +793,18 @@ function defaultEnv(getInstance, options) {
     ? (s) => options.stdout(s + "\n")
     : (s) => console.log(s);
 
+  // Strings and chars are UTF-8 bytes (a `char` is one byte), so output goes through one streaming
+  // decoder: a character printed a byte at a time, or split across two prints, comes out whole.
+  const decoder = new TextDecoder("utf-8");
+  const text = (bytes) => decoder.decode(bytes, { stream: true });
+
   return {
-    print_string: (ptr) => writeOut(getInstance().readString(ptr)),
-    println: (ptr) => writeLine(getInstance().readString(ptr)),
+    print_string: (ptr) => writeOut(text(getInstance().readStringBytes(ptr))),
+    println: (ptr) => writeLine(text(getInstance().readStringBytes(ptr)) + decoder.decode()),
     print_int: (v) => writeOut(String(v)),
     print_float: (v) => writeOut(String(v)),
     print_double: (v) => writeOut(String(v)),
-    print_char: (v) => writeOut(String.fromCharCode(v)),
+    print_char: (v) => writeOut(text(Uint8Array.of(v & 0xff))),
     sin: Math.sin,
     cos: Math.cos,
     abs: Math.abs,
@@ -738,6 +846,8 @@ async function loadAbi(abi) {
  * @param {object} [options.imports] - JS implementations keyed by extern function name.
  * @param {string|object} [options.abi] - URL/path to (or parsed) `.abi.json` for auto-marshaling.
  * @param {function} [options.stdout] - Custom output sink for print builtins.
+ * @param {number} [options.maxHeapBytes] - Upper bound on the heap; exceeding it is an
+ *   out-of-memory error. Unbounded (up to the 4 GiB wasm32 limit) by default.
  * @returns {Promise<DreamInstance>}
  */
 export async function load(source, options = {}) {
@@ -796,8 +906,9 @@ export async function load(source, options = {}) {
     }
   }
 
-  const { instance: wasmInstance } = await WebAssembly.instantiate(wasmBytes, importObject);
-  instance = new DreamInstance(wasmInstance);
+  const { instance: wasmInstance, module } = await WebAssembly.instantiate(wasmBytes, importObject);
+  instance = new DreamInstance(wasmInstance, module);
+  if (options.maxHeapBytes != null) instance.setMaxHeap(options.maxHeapBytes);
   return instance;
 }
 
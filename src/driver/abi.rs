@@ -1,35 +1,39 @@
-use std::fs;
-use std::io::Error;
-use std::path::Path;
-use tracing::{error, info};
-
+use crate::mir::srcmap::{append_custom_section, strip_line_markers, SourceMap, SRCMAP_SECTION};
 use crate::syntax::nodes::ProgramNode;
 
-/// Emits a binary `.wasm` next to the `.wat`, plus an `.abi.json` describing the module's
-/// extern imports (for JS interop marshaling) and exported functions.
-pub(crate) fn emit_wasm_and_abi(
-    wat_path: &str,
-    wat_text: &str,
-    program: &ProgramNode,
-) -> Result<(), Error> {
-    let base = Path::new(wat_path);
-
-    let wasm_path = base.with_extension("wasm");
-    match wat::parse_str(wat_text) {
-        Ok(bytes) => {
-            fs::write(&wasm_path, bytes)?;
-            info!("created file: {}", wasm_path.display());
-        }
-        Err(e) => {
-            // Non-fatal: the `.wat` is still valid output; just warn.
-            error!("could not assemble binary wasm: {}", e);
-        }
+/// Assembles the emitted `.wat` into a binary module with its line markers stripped, carrying its
+/// source map as a `dream.srcmap` custom section, which the JS runtime uses to render Dream stack
+/// traces for runtime errors.
+pub(crate) fn assemble(wat_text: &str) -> Result<Vec<u8>, wat::Error> {
+    let mut bytes = strip_line_markers(&wat::parse_str(wat_text)?);
+    let map = SourceMap::from_wasm(&bytes);
+    if !map.is_empty() {
+        append_custom_section(&mut bytes, SRCMAP_SECTION, build_srcmap_json(&map).as_bytes());
     }
+    Ok(bytes)
+}
 
-    let abi_path = base.with_extension("abi.json");
-    fs::write(&abi_path, build_abi_json(program))?;
-    info!("created file: {}", abi_path.display());
-    Ok(())
+/// Serializes a source map for the JS runtime:
+/// `{"functions":[{"index":N,"name":..,"file":..,"line":..,"lines":[[offset,line],...]},...]}`, where
+/// `index` is the module function index and `offset` a module byte offset (as reported in a wasm
+/// stack frame's `wasm-function[N]:0xOFFSET`).
+fn build_srcmap_json(map: &SourceMap) -> String {
+    let functions: Vec<String> = map
+        .functions()
+        .map(|(index, f)| {
+            let lines: Vec<String> =
+                f.lines.iter().map(|(offset, line)| format!("[{},{}]", offset, line)).collect();
+            format!(
+                "{{\"index\":{},\"name\":\"{}\",\"file\":\"{}\",\"line\":{},\"lines\":[{}]}}",
+                index,
+                json_escape(&f.name),
+                json_escape(&f.file),
+                f.line,
+                lines.join(",")
+            )
+        })
+        .collect();
+    format!("{{\"functions\":[{}]}}", functions.join(","))
 }
 
 /// Escapes a string for embedding in a JSON document.
@@ -0,0 +1,497 @@
+//! The opt-in build cache (`--cache`) under `.dream-cache/`, in the package root or next to the
+//! entry file outside a package.
+//!
+//! Every entry is keyed by a SHA-256 hash of what it was computed from, the cache's
+//! [`FORMAT_VERSION`], and the compiler binary. There are three kinds:
+//!
+//! - **Parsed files.** The AST of each source file (the user's modules and the embedded prelude) is
+//!   stored under the hash of its text, so a file that did not change is decoded instead of lexed
+//!   and parsed again.
+//! - **Lowered functions.** The analyzer works on the whole program at once, numbering definitions
+//!   and interning types as it goes, so every build analyzes again. The MIR each function lowers to
+//!   is kept with the build, under the hash of its HIR and of the type interner, and the next build
+//!   of the same slot reuses it while both are the same. A function is lowered again when its own
+//!   module changed, or when a module it imports changed something it uses; a change that interns
+//!   new types lowers every function again.
+//! - **Builds.** For every module the cache records the hash of its source and the files its
+//!   imports resolved to, which is enough to walk the import graph again without parsing anything.
+//!   When every module reached from the entry still hashes the same (and the options and package
+//!   manifests are unchanged), the artifacts the last such build wrote are copied out and the
+//!   pipeline is skipped. A module whose source changed invalidates itself and every module that
+//!   imports it, directly or through others; those are logged and the program is compiled again,
+//!   reusing what parsed files and lowered functions still match.
+//!
+//! Storing a build removes the parsed files that no build in the index used.
+
+use indexmap::{IndexMap, IndexSet};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::hir::HFunction;
+use crate::mir::MirFunction;
+use crate::syntax::codec::{self, Encode};
+use crate::syntax::nodes::ProgramNode;
+use crate::types::TypeInterner;
+
+pub const CACHE_DIR: &str = ".dream-cache";
+
+/// The version of the cache's layout, keys, and encodings. Every key includes it, and an index
+/// written under another version is ignored, so bumping it turns the whole cache into misses.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The cache's table of contents, next to the `builds/` directory holding the artifacts.
+const INDEX_FILE: &str = "index.toml";
+
+/// The directory holding the encoded ASTs, one file per key.
+const PARSED_DIR: &str = "parsed";
+
+/// Lists, in a build's directory, the parsed files the build used.
+const USES_FILE: &str = "uses";
+
+/// Holds, in a build's directory, the bodies the build lowered: a list of (key, function) pairs.
+const LOWERED_FILE: &str = "lowered";
+
+/// One file a build writes: the path it goes to (`-` for stdout) and its contents.
+#[derive(Debug)]
+pub struct Artifact {
+    pub path: String,
+    pub bytes: Vec<u8>,
+}
+
+/// What the cache knows about one module from the last build that loaded it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRecord {
+    /// [`content_hash`] of the source.
+    pub hash: String,
+    /// The files its imports resolved to.
+    pub imports: Vec<String>,
+}
+
+/// The outcome of looking a build up.
+#[derive(Debug)]
+pub enum Lookup {
+    /// Nothing the build depends on changed: these are the artifacts to write.
+    Fresh(Vec<Artifact>),
+    /// The build must run. Lists the modules it invalidated (empty when the cache has no build
+    /// with these options, or the compiler or a manifest changed).
+    Stale(Vec<String>),
+}
+
+#[derive(Debug)]
+pub struct BuildCache {
+    dir: PathBuf,
+    /// [`compiler_fingerprint`], part of every key.
+    compiler: String,
+    modules: IndexMap<String, ModuleRecord>,
+    /// Each build slot (entry file, options, and output path) mapped to the key of its last
+    /// successful build, whose artifacts live in `builds/<key>/`.
+    builds: IndexMap<String, String>,
+    /// The parsed files (`parsed/<key>`) this build read or wrote.
+    used: IndexSet<String>,
+    /// The key of the slot's previous build, whose lowered bodies this build may reuse; and those
+    /// bodies by key, once loaded.
+    previous: Option<String>,
+    reusable: Option<HashMap<String, MirFunction>>,
+    /// The bodies this build lowered or reused, encoded as (key, function) pairs, and their count.
+    bodies: Vec<u8>,
+    body_count: usize,
+    /// How many files were parsed and functions lowered, and how many of each came from the cache.
+    parsed: (usize, usize),
+    lowered: (usize, usize),
+}
+
+impl BuildCache {
+    /// Opens the cache in `dir`. A missing or unreadable index, or one written by another
+    /// [`FORMAT_VERSION`], is an empty cache.
+    pub fn open(dir: &Path) -> BuildCache {
+        let mut cache = BuildCache {
+            dir: dir.to_path_buf(),
+            compiler: compiler_fingerprint(),
+            modules: IndexMap::new(),
+            builds: IndexMap::new(),
+            used: IndexSet::new(),
+            previous: None,
+            reusable: None,
+            bodies: Vec::new(),
+            body_count: 0,
+            parsed: (0, 0),
+            lowered: (0, 0),
+        };
+        let index = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|text| text.parse::<toml::Table>().ok())
+            .filter(|index| {
+                index.get("version").and_then(toml::Value::as_integer)
+                    == Some(i64::from(FORMAT_VERSION))
+            });
+        let index = match index {
+            Some(index) => index,
+            None => return cache,
+        };
+        if let Some(modules) = index.get("modules").and_then(toml::Value::as_table) {
+            for (file, record) in modules {
+                let hash = record.get("hash").and_then(toml::Value::as_str);
+                let imports = record.get("imports").and_then(toml::Value::as_array);
+                if let (Some(hash), Some(imports)) = (hash, imports) {
+                    let imports = imports
+                        .iter()
+                        .filter_map(|i| i.as_str().map(str::to_string))
+                        .collect();
+                    cache.modules.insert(
+                        file.clone(),
+                        ModuleRecord {
+                            hash: hash.to_string(),
+                            imports,
+                        },
+                    );
+                }
+            }
+        }
+        if let Some(builds) = index.get("builds").and_then(toml::Value::as_table) {
+            for (slot, key) in builds {
+                if let Some(key) = key.as_str() {
+                    cache.builds.insert(slot.clone(), key.to_string());
+                }
+            }
+        }
+        cache
+    }
+
+    /// Looks up the build of `slot` starting at the canonical `entry`. `inputs` fingerprints
+    /// everything besides the compiler and the modules the build depends on. When the build must
+    /// run, it can reuse the bodies the slot's previous build lowered.
+    pub fn lookup(&mut self, slot: &str, entry: &str, inputs: &str) -> Lookup {
+        self.previous = self.builds.get(slot).cloned();
+        let modules = match self.current_modules(entry) {
+            Ok(modules) => modules,
+            Err(changed) => return Lookup::Stale(self.importers_of(changed)),
+        };
+        let key = self.build_key(slot, inputs, &modules);
+        if self.builds.get(slot) != Some(&key) {
+            return Lookup::Stale(Vec::new());
+        }
+        let dir = self.dir.join("builds").join(&key);
+        let listing = match fs::read_to_string(dir.join("artifacts")) {
+            Ok(listing) => listing,
+            Err(_) => return Lookup::Stale(Vec::new()),
+        };
+        let mut artifacts = Vec::new();
+        for (i, path) in listing.lines().enumerate() {
+            match fs::read(dir.join(i.to_string())) {
+                Ok(bytes) => artifacts.push(Artifact {
+                    path: path.to_string(),
+                    bytes,
+                }),
+                Err(_) => return Lookup::Stale(Vec::new()),
+            }
+        }
+        Lookup::Fresh(artifacts)
+    }
+
+    /// Records a successful build of `slot`: the modules it loaded (file to record), the
+    /// artifacts it wrote, and the entries it used, replacing the slot's previous build. Then
+    /// drops the entries no recorded build uses.
+    pub fn store(
+        &mut self,
+        slot: &str,
+        inputs: &str,
+        modules: IndexMap<String, ModuleRecord>,
+        artifacts: &[Artifact],
+    ) -> std::io::Result<()> {
+        let hashes: Vec<(String, String)> = modules
+            .iter()
+            .map(|(file, record)| (file.clone(), record.hash.clone()))
+            .collect();
+        let key = self.build_key(slot, inputs, &hashes);
+        self.modules.extend(modules);
+
+        let builds = self.dir.join("builds");
+        let dir = builds.join(&key);
+        fs::create_dir_all(&dir)?;
+        let mut listing = String::new();
+        for (i, artifact) in artifacts.iter().enumerate() {
+            fs::write(dir.join(i.to_string()), &artifact.bytes)?;
+            listing.push_str(&artifact.path);
+            listing.push('\n');
+        }
+        fs::write(dir.join("artifacts"), listing)?;
+        let mut uses = String::new();
+        for entry in &self.used {
+            uses.push_str(entry);
+            uses.push('\n');
+        }
+        fs::write(dir.join(USES_FILE), uses)?;
+        let mut lowered = Vec::new();
+        self.body_count.encode(&mut lowered);
+        lowered.extend_from_slice(&self.bodies);
+        fs::write(dir.join(LOWERED_FILE), lowered)?;
+
+        if let Some(old) = self.builds.insert(slot.to_string(), key.clone()) {
+            if old != key && !self.builds.values().any(|k| *k == old) {
+                let _ = fs::remove_dir_all(builds.join(old));
+            }
+        }
+        fs::write(self.dir.join(INDEX_FILE), self.render_index())?;
+        self.prune();
+        Ok(())
+    }
+
+    /// The key the AST of a source file with this `text` is cached under.
+    pub fn parse_key(&self, text: &str) -> String {
+        let mut hasher = self.hasher();
+        feed(&mut hasher, text.as_bytes());
+        format!("{}/{}", PARSED_DIR, hex(hasher))
+    }
+
+    /// The program a build parsed from the source `key` was made from, decoded into `arena`.
+    pub fn parsed<'a>(&mut self, key: &str, arena: &'a bumpalo::Bump) -> Option<ProgramNode<'a>> {
+        self.parsed.0 += 1;
+        let bytes = fs::read(self.dir.join(key)).ok()?;
+        let program = codec::decode(&bytes, arena)?;
+        self.parsed.1 += 1;
+        self.used.insert(key.to_string());
+        Some(program)
+    }
+
+    /// Caches `program`, parsed from the source `key` was made from.
+    pub fn store_parsed(&mut self, key: &str, program: &ProgramNode) {
+        if self.write_entry(key, &codec::encode(program)) {
+            self.used.insert(key.to_string());
+        }
+    }
+
+    /// Lowers `func` with `lower`, or takes the body the slot's previous build lowered from the
+    /// same HIR in the same `context` (see [`lowering_context`]). An async function keeps its HIR
+    /// in its body, which is not encoded, so it is always lowered.
+    pub fn lower_function(
+        &mut self,
+        func: &HFunction,
+        context: &str,
+        lower: impl FnOnce(&HFunction) -> MirFunction,
+    ) -> MirFunction {
+        if func.is_async {
+            return lower(func);
+        }
+        self.lowered.0 += 1;
+        let mut hasher = self.hasher();
+        feed(&mut hasher, context.as_bytes());
+        let _ = write!(hasher, "{:?}", func);
+        let key = hex(hasher);
+
+        let body = match self.reusable().remove(&key) {
+            Some(body) => {
+                self.lowered.1 += 1;
+                body
+            }
+            None => lower(func),
+        };
+        key.encode(&mut self.bodies);
+        body.encode(&mut self.bodies);
+        self.body_count += 1;
+        body
+    }
+
+    /// How much of this build the cache supplied, for the log.
+    pub fn summary(&self) -> String {
+        format!(
+            "reused {} of {} parsed files and {} of {} lowered functions",
+            self.parsed.1, self.parsed.0, self.lowered.1, self.lowered.0
+        )
+    }
+
+    /// The bodies of the previous build not yet reused, read on first use. A missing or unreadable
+    /// file reuses nothing.
+    fn reusable(&mut self) -> &mut HashMap<String, MirFunction> {
+        let file = match &self.previous {
+            Some(previous) => self.dir.join("builds").join(previous).join(LOWERED_FILE),
+            None => PathBuf::new(),
+        };
+        self.reusable.get_or_insert_with(|| {
+            let arena = bumpalo::Bump::new();
+            let bodies: Option<Vec<(String, MirFunction)>> = fs::read(file)
+                .ok()
+                .and_then(|bytes| codec::decode(&bytes, &arena));
+            bodies.unwrap_or_default().into_iter().collect()
+        })
+    }
+
+    /// A hasher already fed what every key depends on: the format version and the compiler.
+    fn hasher(&self) -> Sha256 {
+        let mut hasher = Sha256::new();
+        hasher.update(FORMAT_VERSION.to_le_bytes());
+        feed(&mut hasher, self.compiler.as_bytes());
+        hasher
+    }
+
+    /// Writes an entry, reporting whether it was. A cache that cannot be written to only costs
+    /// the next build the reuse.
+    fn write_entry(&self, key: &str, bytes: &[u8]) -> bool {
+        let path = self.dir.join(key);
+        let written = match path.parent() {
+            Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::write(&path, bytes)),
+            None => fs::write(&path, bytes),
+        };
+        written.is_ok()
+    }
+
+    /// Removes the parsed files that no build in the index lists as used.
+    fn prune(&self) {
+        let mut live = HashSet::new();
+        for key in self.builds.values() {
+            let uses = self.dir.join("builds").join(key).join(USES_FILE);
+            if let Ok(uses) = fs::read_to_string(uses) {
+                live.extend(uses.lines().map(str::to_string));
+            }
+        }
+        let entries = match fs::read_dir(self.dir.join(PARSED_DIR)) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            let key = format!("{}/{}", PARSED_DIR, entry.file_name().to_string_lossy());
+            if !live.contains(&key) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// The `(file, hash)` of every module reachable from `entry`, in walk order, following the
+    /// recorded imports. Fails with the modules whose source no longer matches their record (or
+    /// that have none), since their imports may have changed too.
+    fn current_modules(&self, entry: &str) -> Result<Vec<(String, String)>, Vec<String>> {
+        let mut modules = Vec::new();
+        let mut changed = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![entry.to_string()];
+        while let Some(file) = pending.pop() {
+            if !seen.insert(file.clone()) {
+                continue;
+            }
+            let hash = fs::read_to_string(&file).ok().map(|text| content_hash(&text));
+            match (self.modules.get(&file), hash) {
+                (Some(record), Some(hash)) if record.hash == hash => {
+                    pending.extend(record.imports.iter().rev().cloned());
+                    modules.push((file, hash));
+                }
+                _ => changed.push(file),
+            }
+        }
+        if changed.is_empty() {
+            Ok(modules)
+        } else {
+            Err(changed)
+        }
+    }
+
+    /// `changed` plus every recorded module that imports one of them, directly or not.
+    fn importers_of(&self, changed: Vec<String>) -> Vec<String> {
+        let mut invalid: Vec<String> = changed;
+        let mut i = 0;
+        while i < invalid.len() {
+            let file = invalid[i].clone();
+            for (importer, record) in &self.modules {
+                if record.imports.contains(&file) && !invalid.contains(importer) {
+                    invalid.push(importer.clone());
+                }
+            }
+            i += 1;
+        }
+        invalid
+    }
+
+    /// The key of a build of `slot`: a hash of the compiler, its other inputs, and every module
+    /// it loads.
+    fn build_key(&self, slot: &str, inputs: &str, modules: &[(String, String)]) -> String {
+        let mut sorted: Vec<&(String, String)> = modules.iter().collect();
+        sorted.sort();
+        let mut hasher = self.hasher();
+        feed(&mut hasher, slot.as_bytes());
+        feed(&mut hasher, inputs.as_bytes());
+        for (file, hash) in sorted {
+            feed(&mut hasher, file.as_bytes());
+            feed(&mut hasher, hash.as_bytes());
+        }
+        hex(hasher)
+    }
+
+    fn render_index(&self) -> String {
+        let mut modules = toml::Table::new();
+        for (file, record) in &self.modules {
+            let mut entry = toml::Table::new();
+            entry.insert("hash".to_string(), toml::Value::String(record.hash.clone()));
+            entry.insert(
+                "imports".to_string(),
+                toml::Value::Array(
+                    record
+                        .imports
+                        .iter()
+                        .cloned()
+                        .map(toml::Value::String)
+                        .collect(),
+                ),
+            );
+            modules.insert(file.clone(), toml::Value::Table(entry));
+        }
+        let mut builds = toml::Table::new();
+        for (slot, key) in &self.builds {
+            builds.insert(slot.clone(), toml::Value::String(key.clone()));
+        }
+        let mut index = toml::Table::new();
+        index.insert(
+            "version".to_string(),
+            toml::Value::Integer(i64::from(FORMAT_VERSION)),
+        );
+        index.insert("modules".to_string(), toml::Value::Table(modules));
+        index.insert("builds".to_string(), toml::Value::Table(builds));
+        index.to_string()
+    }
+}
+
+/// The hash a module's source is recorded under.
+pub fn content_hash(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(FORMAT_VERSION.to_le_bytes());
+    feed(&mut hasher, text.as_bytes());
+    hex(hasher)
+}
+
+/// What lowering reads besides the function itself: every interned type, and whether runtime
+/// checks are on. Computed once per build and passed to [`BuildCache::lower_function`].
+pub fn lowering_context(interner: &TypeInterner, runtime_checks: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([runtime_checks as u8]);
+    for (id, kind) in interner.iter_kinds() {
+        let _ = write!(hasher, "{:?}={:?};", id, kind);
+    }
+    hex(hasher)
+}
+
+/// Identifies the compiler binary, so a rebuilt compiler never reuses its predecessor's output.
+pub fn compiler_fingerprint() -> String {
+    let exe = std::env::current_exe().ok();
+    let metadata = exe.as_ref().and_then(|exe| fs::metadata(exe).ok());
+    format!(
+        "{} {:?} {:?} {:?}",
+        env!("CARGO_PKG_VERSION"),
+        exe,
+        metadata.as_ref().map(|m| m.len()),
+        metadata.and_then(|m| m.modified().ok())
+    )
+}
+
+/// Feeds one part of a key, prefixed with its length so consecutive parts cannot run together.
+fn feed(hasher: &mut Sha256, part: &[u8]) {
+    hasher.update((part.len() as u64).to_le_bytes());
+    hasher.update(part);
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
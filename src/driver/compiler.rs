@@ -1,19 +1,47 @@
 use bumpalo::Bump;
+use indexmap::IndexMap;
 use std::fs;
-use tracing::info;
+use std::io::Write;
+use std::path::Path;
+use tracing::{error, info};
 
 use crate::diagnostics::{render, DiagnosticBag};
-use crate::driver::abi::emit_wasm_and_abi;
+use crate::driver::abi::{assemble, build_abi_json};
+use crate::driver::cache::{
+    content_hash, lowering_context, Artifact, BuildCache, Lookup, ModuleRecord, CACHE_DIR,
+};
 use crate::driver::error::CompileError;
 use crate::driver::json_derive::generate_json_derives;
+use crate::driver::package::PackageGraph;
 use crate::driver::prelude::merge_prelude;
 use crate::driver::source_loader::{parse_file_recursive, ProgramAccumulator};
 use crate::semantics::analyzer::Analyzer;
+use crate::semantics::modules::resolve_modules;
 use crate::syntax::nodes::ProgramNode;
 use crate::syntax::syntax_tree::SyntaxTree;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Target {
+    /// A module importing the `env`/`Dream` host functions that the `dream` runner and
+    /// `runtime/dream.js` provide.
     Wasm,
+    /// A WASI command (`_start`, `wasi_snapshot_preview1` imports only) for any WASI runtime.
+    Wasi,
+}
+
+/// Which artifact a compilation writes to its output path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// The `.wat` text, plus the assembled `.wasm` next to it (the default).
+    WatAndWasm,
+    /// Only the `.wat` text.
+    Wat,
+    /// Only the binary module; failing to assemble it fails the compilation.
+    Wasm,
+    /// The optimized MIR, one function after another (see [`crate::mir::print`]).
+    Mir,
+    /// The analyzer's HIR, as its `Debug` dump.
+    Hir,
 }
 
 /// Orchestrates the compilation pipeline: source loading (delegated to `source_loader`/`prelude`),
@@ -25,6 +53,28 @@ pub struct Compiler {
     /// `Debug.total_allocations()` probes report real values. Off by default (release builds pay
     /// no per-allocation cost); enabled via the CLI `--debug` flag or [`Compiler::with_debug_alloc`].
     debug_alloc: bool,
+    /// When `true` (the default), array/string indexing is bounds-checked, field access on a class
+    /// instance is null-checked and a downcast is class-checked, each failing with a readable
+    /// runtime error. Disabled via the
+    /// CLI `--unchecked` flag or [`Compiler::with_runtime_checks`].
+    runtime_checks: bool,
+    /// The artifact [`Compiler::compile`] writes. Defaults to [`Emit::WatAndWasm`].
+    emit: Emit,
+    /// When `true` (the default), a `.wat`/`.wasm` build also writes the `.abi.json` sidecar the JS
+    /// runtime marshals extern calls with. Disabled via the CLI `--no-abi` flag.
+    abi: bool,
+    /// When `true`, the module also exports every `@test` function for `dream test` (see
+    /// [`crate::execution::wasm_runner::run_tests`]). Off by default: tests are dropped as dead code.
+    tests: bool,
+    /// When `true`, codegen emits the cycle collector, which reclaims reference cycles ARC alone
+    /// leaks. Off by default (plain ARC code is unchanged); enabled via the CLI `--collect-cycles`
+    /// flag or [`Compiler::with_cycle_collection`].
+    collect_cycles: bool,
+    /// When `true`, builds go through the `.dream-cache/` build cache (see [`crate::driver::cache`]),
+    /// reusing the ASTs of unchanged files and the lowered bodies of unchanged functions, and
+    /// replaying the last build's artifacts when no module it loaded changed. Off by default;
+    /// enabled via the CLI `--cache` flag.
+    cache: bool,
 }
 
 impl Compiler {
@@ -32,6 +82,12 @@ impl Compiler {
         Self {
             target,
             debug_alloc: false,
+            runtime_checks: true,
+            emit: Emit::WatAndWasm,
+            abi: true,
+            tests: false,
+            collect_cycles: false,
+            cache: false,
         }
     }
 
@@ -41,15 +97,120 @@ impl Compiler {
         self
     }
 
+    /// Builder: keep (the default) or drop the runtime bounds, null and cast checks.
+    pub fn with_runtime_checks(mut self, on: bool) -> Self {
+        self.runtime_checks = on;
+        self
+    }
+
+    /// Builder: choose the artifact to write.
+    pub fn with_emit(mut self, emit: Emit) -> Self {
+        self.emit = emit;
+        self
+    }
+
+    /// Builder: write (the default) or skip the `.abi.json` sidecar.
+    pub fn with_abi(mut self, on: bool) -> Self {
+        self.abi = on;
+        self
+    }
+
+    /// Builder: export the program's `@test` functions.
+    pub fn with_tests(mut self, on: bool) -> Self {
+        self.tests = on;
+        self
+    }
+
+    /// Builder: emit the cycle collector.
+    pub fn with_cycle_collection(mut self, on: bool) -> Self {
+        self.collect_cycles = on;
+        self
+    }
+
+    /// Builder: use the `.dream-cache/` build cache.
+    pub fn with_cache(mut self, on: bool) -> Self {
+        self.cache = on;
+        self
+    }
+
+    /// Compiles `main_file_path` and writes the chosen artifact to `out_path` (`-` for stdout).
+    /// Sidecars (`.wasm`, `.abi.json`) are written next to `out_path`.
     pub fn compile(&self, main_file_path: &String, out_path: &String) -> Result<(), CompileError> {
+        self.run(main_file_path, Some(out_path))
+    }
+
+    /// Parses and analyzes `main_file_path`, rendering any diagnostics, without generating code.
+    pub fn check(&self, main_file_path: &String) -> Result<(), CompileError> {
+        self.run(main_file_path, None)
+    }
+
+    fn run(&self, main_file_path: &String, out_path: Option<&String>) -> Result<(), CompileError> {
         info!("starting parsing and multi-file resolution");
         let mut acc = ProgramAccumulator::default();
 
         let arena = Bump::new();
         let mut diagnostics = DiagnosticBag::new(None);
 
+        // A `dream.toml` in the entry file's directory or above makes the program a package whose
+        // dependencies its imports can name; loading it also brings `dream.lock` up to date.
+        let entry_dir = Path::new(main_file_path)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        let entry_dir = if entry_dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            entry_dir
+        };
+        if let Some(root) = PackageGraph::find_root(entry_dir) {
+            let packages = PackageGraph::load(&root)?;
+            packages.sync_lockfile()?;
+            acc.packages = Some(packages);
+        }
+
+        // Only builds are cached; `check` always parses and analyzes.
+        let mut slot = None;
+        if let (true, Some(out_path)) = (self.cache, out_path) {
+            let entry = Path::new(main_file_path).canonicalize()?;
+            let entry = entry.to_string_lossy();
+            let (mut build_cache, build_slot, inputs) = self.open_cache(&entry, out_path, &acc)?;
+            match build_cache.lookup(&build_slot, &entry, &inputs) {
+                Lookup::Fresh(artifacts) => {
+                    info!("no module changed since the last build; reusing its output");
+                    for artifact in &artifacts {
+                        write_output(&artifact.path, &artifact.bytes)?;
+                    }
+                    return Ok(());
+                }
+                Lookup::Stale(invalidated) => {
+                    for file in &invalidated {
+                        info!("invalidated: {}", file);
+                    }
+                }
+            }
+            acc.cache = Some(build_cache);
+            slot = Some((build_slot, inputs));
+        }
+
         parse_file_recursive(main_file_path, &mut acc, &arena, &mut diagnostics)?;
 
+        // Errors from here on are name-resolution (semantic) errors rather than syntax errors.
+        let syntax_failed = diagnostics.has_errors();
+
+        // Give each file its own namespace: rewrite cross-module references to the declarations
+        // they name (renaming same-named declarations apart) before the prelude and `@json`
+        // derives join the program-wide namespace.
+        resolve_modules(
+            &acc.modules,
+            &arena,
+            &mut acc.all_functions,
+            &mut acc.all_structs,
+            &mut acc.all_interfaces,
+            &mut acc.all_enums,
+            &mut acc.all_extends,
+            &mut acc.all_globals,
+            &mut diagnostics,
+        );
+
         // The standard collections (List<T>, Map<K, V>) are embedded in the compiler and merged
         // into every program as a prelude. They are generic templates, so they emit no code unless
         // the program actually instantiates them.
@@ -62,6 +223,7 @@ impl Compiler {
             &mut acc.all_extends,
             &mut diagnostics,
             &mut acc.file_contents,
+            acc.cache.as_mut(),
         )?;
 
         // Auto-derive `to_json`/`from_json` converters for every `@json` class (must run after
@@ -77,9 +239,29 @@ impl Compiler {
 
         if diagnostics.has_errors() {
             render(&diagnostics, &acc.file_contents);
-            return Err(CompileError::Syntax);
+            return Err(if syntax_failed {
+                CompileError::Syntax
+            } else {
+                CompileError::Semantic
+            });
         }
 
+        // What the cache records about each module once the build succeeds.
+        let modules: IndexMap<String, ModuleRecord> = acc
+            .modules
+            .iter()
+            .filter(|_| acc.cache.is_some())
+            .filter_map(|(file, module)| {
+                let text = acc.file_contents.get(file)?;
+                let record = ModuleRecord {
+                    hash: content_hash(text),
+                    imports: module.imports.iter().map(|i| i.file.clone()).collect(),
+                };
+                Some((file.clone(), record))
+            })
+            .collect();
+
+        let mut cache = acc.cache.take();
         let combined_program = ProgramNode::new(
             vec![],
             acc.all_structs,
@@ -106,19 +288,114 @@ impl Compiler {
         };
 
         info!("finished semantic analysis");
+        let mut artifacts = Vec::new();
+        if let Some(out_path) = out_path {
+            // Destructuring moves the owned `hir` out and drops `symbol_info`'s borrowing
+            // references, releasing the `&mut analyzer` borrow so the shared interner can be read
+            // (the HIR references its `TypeId`s, so both must come from this same analyzer).
+            let crate::semantics::analyzer::SemanticInfo { hir, .. } = symbol_info;
+            self.generate(
+                &ast,
+                hir,
+                analyzer.interner(),
+                out_path,
+                cache.as_mut(),
+                &mut artifacts,
+            )?;
+        }
+        for artifact in &artifacts {
+            write_output(&artifact.path, &artifact.bytes)?;
+        }
+
+        if let (Some(mut cache), Some((slot, inputs))) = (cache, slot) {
+            info!("{}", cache.summary());
+            if let Err(e) = cache.store(&slot, &inputs, modules, &artifacts) {
+                error!("could not update the build cache: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the build cache (in the package root, or next to the canonical `entry` file) and
+    /// returns it with this build's slot and the fingerprint of its inputs other than the compiler
+    /// and the modules: the manifests of the packages involved.
+    fn open_cache(
+        &self,
+        entry: &str,
+        out_path: &str,
+        acc: &ProgramAccumulator,
+    ) -> Result<(BuildCache, String, String), CompileError> {
+        let dir = match &acc.packages {
+            Some(packages) => packages.root().root.join(CACHE_DIR),
+            None => Path::new(entry)
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(CACHE_DIR),
+        };
+        let slot = format!(
+            "{} {:?} {:?} debug={} checks={} abi={} tests={} cycles={} -> {}",
+            entry,
+            self.target,
+            self.emit,
+            self.debug_alloc,
+            self.runtime_checks,
+            self.abi,
+            self.tests,
+            self.collect_cycles,
+            out_path
+        );
+        let mut inputs = String::new();
+        if let Some(packages) = &acc.packages {
+            for package in packages.packages() {
+                let manifest = package.root.join(crate::driver::package::MANIFEST_FILE);
+                inputs.push_str(&fs::read_to_string(manifest)?);
+            }
+        }
+        Ok((BuildCache::open(&dir), slot, inputs))
+    }
+
+    /// Lowers the analyzed program (reusing the bodies `cache` holds) and generates the artifacts
+    /// `self.emit` asks for, destined for `out_path` (sidecars next to it).
+    fn generate(
+        &self,
+        ast: &SyntaxTree,
+        hir: crate::hir::Hir,
+        interner: &crate::types::TypeInterner,
+        out_path: &str,
+        cache: Option<&mut BuildCache>,
+        artifacts: &mut Vec<Artifact>,
+    ) -> Result<(), CompileError> {
         info!("starting code generation");
 
         // Lower the analyzer-emitted HIR to MIR, optimize, and emit a self-contained module.
-        // Destructuring moves the owned `hir` out and drops `symbol_info`'s borrowing references,
-        // releasing the `&mut analyzer` borrow so the shared interner can be read (the HIR references
-        // its `TypeId`s, so both must come from this same analyzer instance).
         let text = {
-            let crate::semantics::analyzer::SemanticInfo { hir, .. } = symbol_info;
-            let interner = analyzer.interner();
-            let mut mir = crate::mir::lower::lower_program(&hir, interner);
+            if self.emit == Emit::Hir {
+                artifacts.push(Artifact {
+                    path: out_path.to_string(),
+                    bytes: format!("{:#?}\n", hir).into_bytes(),
+                });
+                return Ok(());
+            }
+            let checks = self.runtime_checks;
+            let mut mir = match cache {
+                Some(cache) => {
+                    let context = lowering_context(interner, checks);
+                    crate::mir::lower::lower_program_using(&hir, interner, checks, |f| {
+                        cache.lower_function(f, &context, |f| {
+                            crate::mir::lower::lower_function_with(f, interner, checks)
+                        })
+                    })
+                }
+                None => crate::mir::lower::lower_program_with(&hir, interner, checks),
+            };
+            if !self.tests {
+                mir.tests.clear();
+            }
+            mir.collect_cycles = self.collect_cycles;
             // Drop unused prelude helpers before optimizing/emitting so the module only carries code
-            // reachable from `main` (see `mir::prune_unreachable`).
+            // reachable from `main` and the exported tests (see `mir::prune_unreachable`).
             crate::mir::prune_unreachable(&mut mir);
+            crate::mir::unwind::insert_unwind_cleanup(&mut mir, interner);
             let rc = crate::mir::passes::RcInsertion;
             let pipeline = crate::mir::passes::PassManager::default_pipeline();
             for f in &mut mir.functions {
@@ -126,19 +403,78 @@ impl Compiler {
                 rc.run(f, interner);
                 pipeline.run(f, interner);
             }
+            if self.emit == Emit::Mir {
+                artifacts.push(Artifact {
+                    path: out_path.to_string(),
+                    bytes: crate::mir::print::print_program(&mir).into_bytes(),
+                });
+                return Ok(());
+            }
             match self.target {
                 Target::Wasm => crate::mir::emit::emit_module(&mir, interner, self.debug_alloc),
+                Target::Wasi => crate::mir::emit::emit_wasi_module(&mir, interner, self.debug_alloc),
             }
         };
 
         info!("finished code generation");
-        fs::write(out_path, &text)?;
-        info!("created file: {}", out_path);
-
-        // Also emit a binary `.wasm` (what browsers/Node load) and an `.abi.json` sidecar
-        // describing extern imports and exports so the JS runtime can auto-marshal values.
-        emit_wasm_and_abi(out_path, &text, ast.get_root())?;
+        let base = Path::new(out_path);
+        match self.emit {
+            Emit::Wasm => {
+                let bytes = assemble(&text).map_err(|e| CompileError::Assemble(e.to_string()))?;
+                artifacts.push(Artifact {
+                    path: out_path.to_string(),
+                    bytes,
+                });
+            }
+            _ => {
+                // Alongside the text, the binary `.wasm` is what browsers/Node load. Failing to
+                // assemble it is non-fatal here: the `.wat` is still valid output.
+                let wasm = if self.emit == Emit::WatAndWasm && out_path != "-" {
+                    match assemble(&text) {
+                        Ok(bytes) => Some(bytes),
+                        Err(e) => {
+                            error!("could not assemble binary wasm: {}", e);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                artifacts.push(Artifact {
+                    path: out_path.to_string(),
+                    bytes: text.into_bytes(),
+                });
+                if let Some(bytes) = wasm {
+                    artifacts.push(Artifact {
+                        path: base.with_extension("wasm").to_string_lossy().into_owned(),
+                        bytes,
+                    });
+                }
+            }
+        }
 
+        // The `.abi.json` sidecar describes extern imports and exports so the JS runtime can
+        // auto-marshal values.
+        if self.abi && out_path != "-" {
+            artifacts.push(Artifact {
+                path: base.with_extension("abi.json").to_string_lossy().into_owned(),
+                bytes: build_abi_json(ast.get_root()).into_bytes(),
+            });
+        }
         Ok(())
     }
 }
+
+/// Writes an artifact to `path`, or to stdout when `path` is `-`.
+fn write_output(path: &str, bytes: &[u8]) -> std::io::Result<()> {
+    if path == "-" {
+        // A reader that stops early (`dream emit-mir app.dream | head`) is not a failure.
+        return match std::io::stdout().write_all(bytes) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        };
+    }
+    fs::write(path, bytes)?;
+    info!("created file: {}", path);
+    Ok(())
+}
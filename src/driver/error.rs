@@ -4,12 +4,18 @@
 
 use std::fmt;
 
+use crate::driver::package::PackageError;
+
 #[derive(Debug)]
 pub enum CompileError {
     /// One or more syntax errors were reported during parsing/import resolution.
     Syntax,
     /// One or more semantic errors were reported during analysis.
     Semantic,
+    /// The generated module failed to assemble into a binary `.wasm`.
+    Assemble(String),
+    /// The `dream.toml` manifest, a dependency, or the `dream.lock` lockfile could not be loaded.
+    Package(PackageError),
     /// An I/O failure during the pipeline (reading sources, writing artifacts).
     Io(std::io::Error),
 }
@@ -19,6 +25,8 @@ impl fmt::Display for CompileError {
         match self {
             CompileError::Syntax => write!(f, "Syntax errors found during parsing"),
             CompileError::Semantic => write!(f, "Semantic errors found"),
+            CompileError::Assemble(e) => write!(f, "could not assemble binary wasm: {}", e),
+            CompileError::Package(e) => write!(f, "{}", e),
             CompileError::Io(e) => write!(f, "{}", e),
         }
     }
@@ -31,3 +39,9 @@ impl From<std::io::Error> for CompileError {
         CompileError::Io(e)
     }
 }
+
+impl From<PackageError> for CompileError {
+    fn from(e: PackageError) -> Self {
+        CompileError::Package(e)
+    }
+}
@@ -1,6 +1,8 @@
 pub mod abi;
+pub mod cache;
 pub mod compiler;
 pub mod error;
 pub mod json_derive;
+pub mod package;
 pub mod prelude;
 pub mod source_loader;
@@ -0,0 +1,642 @@
+//! Packages: the `dream.toml` manifest, dependency resolution, and the `dream.lock` lockfile.
+//!
+//! A manifest sits in a package's root directory:
+//!
+//! ```toml
+//! [package]
+//! name = "app"
+//! version = "0.1.0"
+//! entry = "main.dream"
+//!
+//! [dependencies]
+//! geometry = { path = "../geometry" }
+//! json = { git = "https://example.com/dream-json.git", rev = "3f2a9c1" }
+//! ```
+//!
+//! A path dependency is used where it is. A git dependency is never fetched by the compiler: it must
+//! already be checked out on disk, under `vendor/<name>` unless its `path` says otherwise. Either
+//! kind is itself a package with its own `dream.toml`, so dependencies are resolved transitively.
+//!
+//! A file of a package that depends on `geometry` resolves `import geometry.shapes;` against the
+//! root of that dependency (`shapes.dream` next to its manifest) and `import geometry;` to its entry
+//! file. `dream.lock` lists every package of the graph and pins each git dependency to the commit its
+//! checkout is at; a checkout that later moves is an error until `dream update` re-pins it.
+
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::driver::source_loader::resolve_import_path;
+
+pub const MANIFEST_FILE: &str = "dream.toml";
+pub const LOCK_FILE: &str = "dream.lock";
+
+/// The entry file of a package whose manifest names none.
+const DEFAULT_ENTRY: &str = "main.dream";
+/// Where a git dependency is expected to be checked out, relative to the manifest that declares it.
+const VENDOR_DIR: &str = "vendor";
+/// The `version` key of the lockfile format this compiler writes.
+const LOCK_VERSION: i64 = 1;
+
+/// A manifest, lockfile, or dependency that could not be loaded, with the file it concerns.
+#[derive(Debug)]
+pub struct PackageError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl PackageError {
+    fn new(path: &Path, message: impl Into<String>) -> Self {
+        PackageError {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PackageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for PackageError {}
+
+/// Where a package of the graph comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// The package being built.
+    Root,
+    /// A `path` dependency.
+    Path,
+    /// A `git` dependency, with the commit its checkout is at when it could be read.
+    Git { url: String, commit: Option<String> },
+}
+
+/// One package of the graph.
+#[derive(Debug)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// The canonical directory holding the package's `dream.toml`.
+    pub root: PathBuf,
+    /// The canonical path of the package's entry file.
+    pub entry: PathBuf,
+    pub source: Source,
+    /// Each dependency's import name, mapped to its index in the graph.
+    pub dependencies: IndexMap<String, usize>,
+}
+
+/// A root package and everything it depends on, transitively.
+#[derive(Debug)]
+pub struct PackageGraph {
+    /// Every package once, the root first.
+    packages: Vec<Package>,
+}
+
+/// A `[dependencies]` entry as written in a manifest.
+enum Dependency {
+    Path(String),
+    Git {
+        url: String,
+        rev: Option<String>,
+        path: Option<String>,
+    },
+}
+
+/// The parts of a `dream.toml` the compiler reads.
+struct Manifest {
+    name: String,
+    version: String,
+    entry: String,
+    dependencies: Vec<(String, Dependency)>,
+}
+
+impl PackageGraph {
+    /// Finds the root directory of the package `dir` belongs to: the nearest of `dir` and its
+    /// ancestors that holds a `dream.toml`.
+    pub fn find_root(dir: &Path) -> Option<PathBuf> {
+        let dir = dir.canonicalize().ok()?;
+        dir.ancestors()
+            .find(|candidate| candidate.join(MANIFEST_FILE).is_file())
+            .map(Path::to_path_buf)
+    }
+
+    /// Loads the package rooted at `root` and, transitively, its dependencies.
+    pub fn load(root: &Path) -> Result<PackageGraph, PackageError> {
+        let mut graph = PackageGraph {
+            packages: Vec::new(),
+        };
+        let root = root
+            .canonicalize()
+            .map_err(|e| PackageError::new(root, e.to_string()))?;
+        graph.load_package(&root, Source::Root, &mut HashMap::new())?;
+        Ok(graph)
+    }
+
+    /// Loads the package at the canonical directory `root` unless it already is in the graph, and
+    /// returns its index.
+    fn load_package(
+        &mut self,
+        root: &Path,
+        source: Source,
+        loaded: &mut HashMap<PathBuf, usize>,
+    ) -> Result<usize, PackageError> {
+        if let Some(&index) = loaded.get(root) {
+            return Ok(index);
+        }
+        let manifest_path = root.join(MANIFEST_FILE);
+        let manifest = read_manifest(&manifest_path)?;
+        if let Some(other) = self.packages.iter().find(|p| p.name == manifest.name) {
+            return Err(PackageError::new(
+                &manifest_path,
+                format!(
+                    "package '{}' is also loaded from {}; a graph can hold only one package of each name",
+                    manifest.name,
+                    other.root.display()
+                ),
+            ));
+        }
+        let entry = root.join(&manifest.entry);
+        let entry = entry.canonicalize().map_err(|_| {
+            PackageError::new(
+                &manifest_path,
+                format!("entry file '{}' not found", manifest.entry),
+            )
+        })?;
+
+        let index = self.packages.len();
+        loaded.insert(root.to_path_buf(), index);
+        self.packages.push(Package {
+            name: manifest.name,
+            version: manifest.version,
+            root: root.to_path_buf(),
+            entry,
+            source,
+            dependencies: IndexMap::new(),
+        });
+
+        for (name, dependency) in manifest.dependencies {
+            let (dir, source) = match &dependency {
+                Dependency::Path(path) => (root.join(path), Source::Path),
+                Dependency::Git { url, path, .. } => {
+                    let dir = match path {
+                        Some(path) => root.join(path),
+                        None => root.join(VENDOR_DIR).join(&name),
+                    };
+                    let commit = git_head(&dir);
+                    (dir, Source::Git { url: url.clone(), commit })
+                }
+            };
+            let dir = match dir.canonicalize() {
+                Ok(dir) if dir.join(MANIFEST_FILE).is_file() => dir,
+                Ok(dir) => {
+                    return Err(PackageError::new(
+                        &manifest_path,
+                        format!(
+                            "dependency '{}' has no {} in {}",
+                            name,
+                            MANIFEST_FILE,
+                            dir.display()
+                        ),
+                    ))
+                }
+                Err(_) => {
+                    let message = match &dependency {
+                        Dependency::Git { url, .. } => format!(
+                            "git dependency '{}' is not checked out: clone {} into {}",
+                            name,
+                            url,
+                            dir.display()
+                        ),
+                        Dependency::Path(_) => {
+                            format!("dependency '{}' not found at {}", name, dir.display())
+                        }
+                    };
+                    return Err(PackageError::new(&manifest_path, message));
+                }
+            };
+            if let (Dependency::Git { rev: Some(rev), .. }, Source::Git { commit, .. }) =
+                (&dependency, &source)
+            {
+                let message = match commit {
+                    Some(commit) if commit.starts_with(rev.as_str()) => None,
+                    Some(commit) => Some(format!(
+                        "the checkout of '{}' in {} is at commit {}, not at rev '{}'",
+                        name,
+                        dir.display(),
+                        commit,
+                        rev
+                    )),
+                    None => Some(format!(
+                        "cannot tell which commit the checkout of '{}' in {} is at, so rev '{}' cannot be checked",
+                        name,
+                        dir.display(),
+                        rev
+                    )),
+                };
+                if let Some(message) = message {
+                    return Err(PackageError::new(&manifest_path, message));
+                }
+            }
+
+            let dependency_index = self.load_package(&dir, source, loaded)?;
+            let found = &self.packages[dependency_index].name;
+            if *found != name {
+                return Err(PackageError::new(
+                    &manifest_path,
+                    format!(
+                        "dependency '{}' points at package '{}' ({})",
+                        name,
+                        found,
+                        dir.display()
+                    ),
+                ));
+            }
+            self.packages[index]
+                .dependencies
+                .insert(name, dependency_index);
+        }
+        Ok(index)
+    }
+
+    /// The package being built.
+    pub fn root(&self) -> &Package {
+        &self.packages[0]
+    }
+
+    /// Every package of the graph, the root first.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// The package `file` belongs to: the one whose root is the innermost directory containing it
+    /// (a vendored dependency lives inside the package that uses it).
+    pub fn package_of(&self, file: &Path) -> Option<&Package> {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        self.packages
+            .iter()
+            .filter(|package| file.starts_with(&package.root))
+            .max_by_key(|package| package.root.components().count())
+    }
+
+    /// Resolves an import of `module_path` (slash-joined, as in
+    /// [`crate::syntax::nodes::ImportNode`]) made by `file` when its first segment names a
+    /// dependency of `file`'s package. Returns `None` for every other import, which resolves
+    /// relative to the importing file.
+    pub fn resolve_import(&self, file: &Path, module_path: &str) -> Option<PathBuf> {
+        let (head, rest) = match module_path.split_once('/') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (module_path, None),
+        };
+        let index = *self.package_of(file)?.dependencies.get(head)?;
+        let dependency = &self.packages[index];
+        Some(match rest {
+            Some(rest) => resolve_import_path(&dependency.root, rest),
+            None => dependency.entry.clone(),
+        })
+    }
+
+    /// The module name of a file that belongs to a dependency: the package name followed by the
+    /// file's path inside the package (`geometry.shapes`). `None` for files of the root package.
+    pub fn module_name(&self, file: &Path) -> Option<String> {
+        let package = self.package_of(file)?;
+        if package.source == Source::Root {
+            return None;
+        }
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        let inner = crate::semantics::modules::module_name(&package.root, &file);
+        Some(format!("{}.{}", package.name, inner))
+    }
+
+    /// Brings `dream.lock` in line with the graph, writing it when it is missing or out of date.
+    /// A git dependency whose checkout is no longer at the commit the lockfile pins is an error:
+    /// the pins only move with [`PackageGraph::update_lockfile`].
+    pub fn sync_lockfile(&self) -> Result<(), PackageError> {
+        let lock_path = self.root().root.join(LOCK_FILE);
+        let existing = fs::read_to_string(&lock_path).ok();
+        let pins = read_existing_pins(&lock_path, existing.as_deref())?;
+        for package in &self.packages {
+            let (url, commit) = match &package.source {
+                Source::Git {
+                    url,
+                    commit: Some(commit),
+                } => (url, commit),
+                _ => continue,
+            };
+            if let Some((pinned_url, pinned)) = pins.get(&package.name) {
+                if pinned_url == url && pinned != commit {
+                    return Err(PackageError::new(
+                        &lock_path,
+                        format!(
+                            "the checkout of '{}' in {} is at commit {}, but the lockfile pins {}; check out the pinned commit or run `dream update`",
+                            package.name,
+                            package.root.display(),
+                            commit,
+                            pinned
+                        ),
+                    ));
+                }
+            }
+        }
+        self.write_lockfile(&lock_path, existing.as_deref(), &pins)
+    }
+
+    /// Rewrites `dream.lock` from the graph, pinning every git dependency to the commit its
+    /// checkout is at now.
+    pub fn update_lockfile(&self) -> Result<(), PackageError> {
+        let lock_path = self.root().root.join(LOCK_FILE);
+        let existing = fs::read_to_string(&lock_path).ok();
+        let pins = read_existing_pins(&lock_path, existing.as_deref())?;
+        self.write_lockfile(&lock_path, existing.as_deref(), &pins)
+    }
+
+    /// Writes the lockfile unless it already reads `existing`. A git dependency `pins` pins but
+    /// whose checkout's commit cannot be read is an error rather than a pin silently dropped.
+    fn write_lockfile(
+        &self,
+        lock_path: &Path,
+        existing: Option<&str>,
+        pins: &HashMap<String, (String, String)>,
+    ) -> Result<(), PackageError> {
+        for package in &self.packages {
+            if let Source::Git { url, commit: None } = &package.source {
+                if let Some((pinned_url, pinned)) = pins.get(&package.name) {
+                    if pinned_url == url {
+                        return Err(PackageError::new(
+                            lock_path,
+                            format!(
+                                "cannot tell which commit the checkout of '{}' in {} is at, so the lockfile's pin {} cannot be checked or kept",
+                                package.name,
+                                package.root.display(),
+                                pinned
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        let text = self.render_lockfile();
+        if existing == Some(text.as_str()) {
+            return Ok(());
+        }
+        fs::write(lock_path, text).map_err(|e| PackageError::new(lock_path, e.to_string()))
+    }
+
+    /// The lockfile text for the graph: one `[[package]]` table per package, the root first and the
+    /// rest by name, so the file only changes when the graph does.
+    fn render_lockfile(&self) -> String {
+        let root = &self.root().root;
+        let mut packages: Vec<&Package> = self.packages.iter().collect();
+        packages[1..].sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut out = String::from(
+            "# Generated by `dream` from dream.toml; run `dream update` to re-pin git dependencies.\n",
+        );
+        out.push_str(&format!("version = {}\n", LOCK_VERSION));
+        for package in packages {
+            out.push_str("\n[[package]]\n");
+            out.push_str(&format!("name = {}\n", quote(&package.name)));
+            out.push_str(&format!("version = {}\n", quote(&package.version)));
+            let source = match &package.source {
+                Source::Root => None,
+                Source::Path => Some(format!("path+{}", relative_path(root, &package.root))),
+                Source::Git {
+                    url,
+                    commit: Some(commit),
+                } => Some(format!("git+{}#{}", url, commit)),
+                Source::Git { url, commit: None } => Some(format!("git+{}", url)),
+            };
+            if let Some(source) = source {
+                out.push_str(&format!("source = {}\n", quote(&source)));
+            }
+            if !package.dependencies.is_empty() {
+                let names: Vec<String> = package.dependencies.keys().map(|n| quote(n)).collect();
+                out.push_str(&format!("dependencies = [{}]\n", names.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+/// Reads and validates the manifest at `path`.
+fn read_manifest(path: &Path) -> Result<Manifest, PackageError> {
+    let text = fs::read_to_string(path).map_err(|e| PackageError::new(path, e.to_string()))?;
+    let table = parse_toml(path, &text)?;
+    let error = |message: String| PackageError::new(path, message);
+
+    for key in table.keys() {
+        if key != "package" && key != "dependencies" {
+            return Err(error(format!("unknown section '{}'", key)));
+        }
+    }
+    let package = table
+        .get("package")
+        .and_then(toml::Value::as_table)
+        .ok_or_else(|| error("missing [package] section".to_string()))?;
+    let mut name = None;
+    let mut version = "0.0.0".to_string();
+    let mut entry = DEFAULT_ENTRY.to_string();
+    for (key, value) in package {
+        let text = value
+            .as_str()
+            .ok_or_else(|| error(format!("package.{} must be a string", key)))?;
+        match key.as_str() {
+            "name" => name = Some(text.to_string()),
+            "version" => version = text.to_string(),
+            "entry" => entry = text.to_string(),
+            _ => return Err(error(format!("unknown key 'package.{}'", key))),
+        }
+    }
+    let name = name.ok_or_else(|| error("missing package.name".to_string()))?;
+    if !is_identifier(&name) {
+        return Err(error(format!(
+            "package name '{}' is not an identifier, so it could not be imported",
+            name
+        )));
+    }
+
+    let mut dependencies = Vec::new();
+    if let Some(value) = table.get("dependencies") {
+        let entries = value
+            .as_table()
+            .ok_or_else(|| error("[dependencies] must be a table".to_string()))?;
+        for (dependency, spec) in entries {
+            if !is_identifier(dependency) {
+                return Err(error(format!(
+                    "dependency name '{}' is not an identifier",
+                    dependency
+                )));
+            }
+            let spec = spec.as_table().ok_or_else(|| {
+                error(format!(
+                    "dependency '{}' must be a table with a `path` or a `git` key",
+                    dependency
+                ))
+            })?;
+            let mut fields = HashMap::new();
+            for (key, value) in spec {
+                if !matches!(key.as_str(), "path" | "git" | "rev") {
+                    return Err(error(format!(
+                        "unknown key '{}' in dependency '{}'",
+                        key, dependency
+                    )));
+                }
+                let text = value.as_str().ok_or_else(|| {
+                    error(format!("{} of dependency '{}' must be a string", key, dependency))
+                })?;
+                fields.insert(key.as_str(), text.to_string());
+            }
+            let spec = match (fields.remove("git"), fields.remove("path"), fields.remove("rev")) {
+                (Some(url), path, rev) => Dependency::Git { url, rev, path },
+                (None, Some(_), Some(_)) => {
+                    return Err(error(format!(
+                        "dependency '{}' has a `rev` but no `git` url",
+                        dependency
+                    )))
+                }
+                (None, Some(path), None) => Dependency::Path(path),
+                (None, None, _) => {
+                    return Err(error(format!(
+                        "dependency '{}' needs a `path` or a `git` key",
+                        dependency
+                    )))
+                }
+            };
+            dependencies.push((dependency.clone(), spec));
+        }
+    }
+
+    Ok(Manifest {
+        name,
+        version,
+        entry,
+        dependencies,
+    })
+}
+
+/// The pins of the lockfile at `path` whose text is `existing`; none when there is no lockfile.
+fn read_existing_pins(
+    path: &Path,
+    existing: Option<&str>,
+) -> Result<HashMap<String, (String, String)>, PackageError> {
+    match existing {
+        Some(text) => read_lock_pins(path, text),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Reads the git pins of an existing lockfile: package name to (url, commit).
+fn read_lock_pins(
+    path: &Path,
+    text: &str,
+) -> Result<HashMap<String, (String, String)>, PackageError> {
+    let table = parse_toml(path, text)?;
+    let mut pins = HashMap::new();
+    let packages = match table.get("package").and_then(toml::Value::as_array) {
+        Some(packages) => packages,
+        None => return Ok(pins),
+    };
+    for package in packages {
+        let name = package.get("name").and_then(toml::Value::as_str);
+        let source = package.get("source").and_then(toml::Value::as_str);
+        if let (Some(name), Some(source)) = (name, source) {
+            let pin = source
+                .strip_prefix("git+")
+                .and_then(|source| source.rsplit_once('#'));
+            if let Some((url, commit)) = pin {
+                pins.insert(name.to_string(), (url.to_string(), commit.to_string()));
+            }
+        }
+    }
+    Ok(pins)
+}
+
+fn parse_toml(path: &Path, text: &str) -> Result<toml::Table, PackageError> {
+    text.parse::<toml::Table>().map_err(|e| {
+        let message = match e.span() {
+            Some(span) => format!(
+                "line {}: {}",
+                text[..span.start].matches('\n').count() + 1,
+                e.message()
+            ),
+            None => e.message().to_string(),
+        };
+        PackageError::new(path, message)
+    })
+}
+
+/// The commit a git checkout's `HEAD` is at, read straight from its git directory (a `.git` file,
+/// as in a submodule or worktree, points at it). `None` when `dir` is not a checkout, or its `HEAD`
+/// names a ref that cannot be found.
+fn git_head(dir: &Path) -> Option<String> {
+    let mut git_dir = dir.join(".git");
+    if git_dir.is_file() {
+        let link = fs::read_to_string(&git_dir).ok()?;
+        git_dir = dir.join(link.trim().strip_prefix("gitdir:")?.trim());
+    }
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let reference = match head.trim().strip_prefix("ref:") {
+        Some(reference) => reference.trim(),
+        None => return Some(head.trim().to_string()),
+    };
+    // A worktree's git directory holds its own `HEAD`, but the branches live in the repository's,
+    // which its `commondir` file names (relative to the worktree's git directory).
+    let mut dirs = vec![git_dir.clone()];
+    if let Ok(common) = fs::read_to_string(git_dir.join("commondir")) {
+        dirs.push(git_dir.join(common.trim()));
+    }
+    dirs.iter().find_map(|dir| read_ref(dir, reference))
+}
+
+/// The commit `reference` names in the git directory `git_dir`: a loose ref file, or a
+/// `<commit> <ref>` line of `packed-refs` once the ref has been packed.
+fn read_ref(git_dir: &Path, reference: &str) -> Option<String> {
+    if let Ok(commit) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        let (commit, name) = line.split_once(' ')?;
+        if name == reference {
+            Some(commit.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// `target` relative to `base` (both canonical), with `/` separators so lockfiles are the same on
+/// every platform.
+fn relative_path(base: &Path, target: &Path) -> String {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(&target)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts: Vec<String> = vec!["..".to_string(); base.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+fn quote(text: &str) -> String {
+    toml::Value::String(text.to_string()).to_string()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -7,12 +7,13 @@ use std::collections::HashMap;
 use std::io::Error;
 
 use crate::diagnostics::DiagnosticBag;
-use crate::driver::source_loader::collect_declarations;
-use crate::syntax::lexer::Lexer;
-use crate::syntax::parser::Parser;
+use crate::driver::cache::BuildCache;
+use crate::driver::source_loader::{collect_declarations, parse_source};
 
 /// Parses the embedded standard-collections prelude and merges its declarations into the
-/// program. Uses the same arena as the user's files so all AST nodes share a lifetime.
+/// program. Uses the same arena as the user's files so all AST nodes share a lifetime, and the
+/// build `cache`, when given, for the files it has already parsed.
+#[allow(clippy::too_many_arguments)]
 pub fn merge_prelude<'a>(
     arena: &'a Bump,
     all_functions: &mut Vec<crate::syntax::nodes::FunctionNode<'a>>,
@@ -22,6 +23,7 @@ pub fn merge_prelude<'a>(
     all_extends: &mut Vec<crate::syntax::nodes::ExtendNode<'a>>,
     diagnostics: &mut DiagnosticBag,
     file_contents: &mut HashMap<String, String>,
+    mut cache: Option<&mut BuildCache>,
 ) -> Result<(), Error> {
     // Each standard type lives in its own prelude file. The primitive files (int/char/string/...)
     // make the built-in types real, extensible classes via `extend` blocks. The list itself lives
@@ -30,22 +32,18 @@ pub fn merge_prelude<'a>(
         let prelude_name = prelude_name.to_string();
         file_contents.insert(prelude_name.clone(), prelude_src.to_string());
 
-        let mut prelude_diagnostics = DiagnosticBag::new(Some(prelude_name.clone()));
-        let lexer = Lexer::new(prelude_src.to_string());
-        let mut parser = Parser::new(lexer, arena, &mut prelude_diagnostics);
-        let ast = match parser.parse() {
-            Ok(ast) => ast,
-            Err(e) => {
-                diagnostics.extend(&prelude_diagnostics);
-                return Err(e);
-            }
-        };
-        diagnostics.extend(&prelude_diagnostics);
+        let program = parse_source(
+            prelude_src,
+            &prelude_name,
+            arena,
+            cache.as_deref_mut(),
+            diagnostics,
+        )?;
 
         // Preludes declare no globals; a throwaway sink keeps the shared collector signature.
         let mut globals = Vec::new();
         collect_declarations(
-            ast.get_root(),
+            &program,
             &prelude_name,
             all_functions,
             all_structs,
@@ -6,10 +6,13 @@ use bumpalo::Bump;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{Error, ErrorKind, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::diagnostics::DiagnosticBag;
+use crate::driver::cache::BuildCache;
+use crate::driver::package::PackageGraph;
+use crate::semantics::modules::{module_name, ModuleGraph, ModuleImport};
 use crate::syntax::lexer::Lexer;
 use crate::syntax::nodes::struct_node::StructDeclarationNode;
 use crate::syntax::nodes::{
@@ -31,6 +34,17 @@ pub struct ProgramAccumulator<'a> {
     pub all_extends: Vec<ExtendNode<'a>>,
     pub all_globals: Vec<GlobalVariableNode<'a>>,
     pub file_contents: HashMap<String, String>,
+    /// Every loaded user file as a module with its resolved imports, entry file first. Drives
+    /// [`crate::semantics::modules::resolve_modules`].
+    pub modules: ModuleGraph,
+    /// The entry file's directory; module names are file paths relative to it.
+    pub root_dir: Option<PathBuf>,
+    /// The package the entry file belongs to, when a `dream.toml` declares one. Its dependencies
+    /// are what `import <dependency>.<module>;` resolves against.
+    pub packages: Option<PackageGraph>,
+    /// The build cache, when the build uses one: parsing a file whose AST it holds decodes that
+    /// instead.
+    pub cache: Option<BuildCache>,
 }
 
 /// Resolves an `import a.b.c;` reference (passed here as the slash-joined path `a/b/c`) relative to
@@ -43,9 +57,22 @@ pub fn resolve_import_path(base_dir: &Path, module_name: &str) -> std::path::Pat
     import_path
 }
 
+/// Finds the file an `import` of `module_name` (slash-joined) made by `file` names: a module of one
+/// of the package's dependencies when the first segment names one, otherwise a file relative to
+/// `file`'s directory. The compiler and the language server both resolve imports through here.
+pub fn locate_import(file: &Path, module_name: &str, packages: Option<&PackageGraph>) -> PathBuf {
+    packages
+        .and_then(|packages| packages.resolve_import(file, module_name))
+        .unwrap_or_else(|| {
+            let parent_dir = file.parent().unwrap_or_else(|| Path::new(""));
+            resolve_import_path(parent_dir, module_name)
+        })
+}
+
 /// Clones every top-level declaration of `program` into the accumulators, tagging each with
 /// `file_tag` so semantic diagnostics can be attributed to the right source file. Shared by the
 /// recursive loader, the prelude merge, and the LSP front-end so the tagging logic never drifts.
+#[allow(clippy::too_many_arguments)]
 pub fn collect_declarations<'a>(
     program: &ProgramNode<'a>,
     file_tag: &str,
@@ -80,6 +107,8 @@ pub fn collect_declarations<'a>(
         all_interfaces.push(interface_decl);
     }
     for enum_decl in program.enums.iter().cloned() {
+        let mut enum_decl = enum_decl;
+        enum_decl.file_path = Some(tag.clone());
         all_enums.push(enum_decl);
     }
     for extend_decl in program.extends.iter().cloned() {
@@ -97,6 +126,77 @@ pub fn collect_declarations<'a>(
     }
 }
 
+/// Resolves each of the imports of `program` (parsed from `file`) to the canonical path of the file
+/// it names, reporting the ones that do not exist.
+pub fn resolve_imports(
+    program: &ProgramNode,
+    file: &Path,
+    packages: Option<&PackageGraph>,
+    diagnostics: &mut DiagnosticBag,
+) -> Vec<ModuleImport> {
+    let mut imports = Vec::new();
+    for import in &program.imports {
+        let module_name = import.module_name.text.as_str();
+        let import_path = locate_import(file, module_name, packages);
+
+        let import_path_str = match import_path.canonicalize().ok().and_then(|p| {
+            p.to_str().map(str::to_string)
+        }) {
+            Some(s) => s,
+            None if !import_path.exists() => {
+                diagnostics.report_error(
+                    format!("Imported file not found: {}", import_path.display()),
+                    Some(import.module_name.position),
+                );
+                continue;
+            }
+            None => {
+                diagnostics.report_error(
+                    format!("Non-UTF-8 import path: {:?}", import_path),
+                    Some(import.module_name.position),
+                );
+                continue;
+            }
+        };
+        imports.push(ModuleImport {
+            node: import.clone(),
+            file: import_path_str,
+        });
+    }
+    imports
+}
+
+/// Parses `text`, the source of `file`, or decodes the program `cache` kept for the same text.
+/// Only a parse that reported no diagnostics is cached, so reusing one never hides a warning.
+pub fn parse_source<'a>(
+    text: &str,
+    file: &str,
+    arena: &'a Bump,
+    mut cache: Option<&mut BuildCache>,
+    diagnostics: &mut DiagnosticBag,
+) -> Result<ProgramNode<'a>, Error> {
+    let key = cache.as_ref().map(|cache| cache.parse_key(text));
+    if let (Some(cache), Some(key)) = (cache.as_mut(), &key) {
+        if let Some(program) = cache.parsed(key, arena) {
+            return Ok(program);
+        }
+    }
+
+    let mut file_diagnostics = DiagnosticBag::new(Some(file.to_string()));
+    let lexer = Lexer::new(text.to_string());
+    let mut parser = Parser::new(lexer, arena, &mut file_diagnostics);
+    let parsed = parser.parse();
+    diagnostics.extend(&file_diagnostics);
+    let program = parsed?.into_root();
+
+    if let (Some(cache), Some(key)) = (cache, key) {
+        if !file_diagnostics.has_errors() && !file_diagnostics.has_warnings() {
+            cache.store_parsed(&key, &program);
+        }
+    }
+    Ok(program)
+}
+
 /// Recursively parses `file_path` and every file it imports, merging all declarations into the
 /// `acc` accumulators. Each declaration is tagged with its originating file so semantic
 /// diagnostics (which run on the merged program) can attribute errors correctly.
@@ -131,53 +231,28 @@ pub fn parse_file_recursive<'a>(
 
     acc.file_contents.insert(path_str.clone(), text.clone());
 
-    let mut file_diagnostics = DiagnosticBag::new(Some(path_str.clone()));
-
-    let lexer = Lexer::new(text);
-    let mut parser = Parser::new(lexer, arena, &mut file_diagnostics);
-
-    let ast = match parser.parse() {
-        Ok(ast) => ast,
-        Err(e) => {
-            diagnostics.extend(&file_diagnostics);
-            return Err(e);
-        }
-    };
-
-    diagnostics.extend(&file_diagnostics);
+    let program = parse_source(&text, &path_str, arena, acc.cache.as_mut(), diagnostics)?;
 
-    let program = ast.get_root();
     let parent_dir = path.parent().unwrap_or_else(|| Path::new(""));
 
-    for import in &program.imports {
-        let module_name = import.module_name.text.as_str();
-        let import_path = resolve_import_path(parent_dir, module_name);
-
-        let import_path_str = match import_path.to_str() {
-            Some(s) => s.to_string(),
-            None => {
-                diagnostics.report_error(
-                    format!("Non-UTF-8 import path: {:?}", import_path),
-                    Some(import.module_name.position),
-                );
-                continue;
-            }
-        };
-        if !import_path.exists() {
-            diagnostics.report_error(
-                format!("Imported file not found: {}", import_path_str),
-                Some(import.module_name.position),
-            );
-            continue;
-        }
+    let root = acc.root_dir.get_or_insert_with(|| parent_dir.to_path_buf());
+    let name = acc
+        .packages
+        .as_ref()
+        .and_then(|packages| packages.module_name(&path))
+        .unwrap_or_else(|| module_name(root, &path));
+    let imports = resolve_imports(&program, &path, acc.packages.as_ref(), diagnostics);
+    let files: Vec<String> = imports.iter().map(|i| i.file.clone()).collect();
+    acc.modules.add_module(&path_str, name, imports);
 
-        parse_file_recursive(&import_path_str, acc, arena, diagnostics)?;
+    for import_path_str in &files {
+        parse_file_recursive(import_path_str, acc, arena, diagnostics)?;
     }
 
     // Tag every declaration with its source file so semantic diagnostics (which run on the
     // merged program) can report the correct file name.
     collect_declarations(
-        program,
+        &program,
         &path_str,
         &mut acc.all_functions,
         &mut acc.all_structs,
@@ -1,6 +1,7 @@
 //! Synchronous console host functions (the `Dream` module behind `src/stdlib/system/system.dream`'s
 //! `readLine`/`readKey`/`exit`). Browser/Node hosts implement the same names in `runtime/dream.js`.
 
+use std::fmt;
 use std::io::{self, BufRead, Read, Write};
 use wasmtime::*;
 
@@ -19,6 +20,20 @@ pub fn enable_ansi_support() {
     }
 }
 
+/// The error `System.exit(code)` traps with. `consoleExit` never ends the process itself:
+/// `execute_wasm` exits with the code once the module has unwound, while `dream test` reports it
+/// as the running test's failure and moves on to the next test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitRequest(pub i32);
+
+impl fmt::Display for ExitRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "System.exit({}) called", self.0)
+    }
+}
+
+impl std::error::Error for ExitRequest {}
+
 /// Registers the synchronous console host functions on `linker`. Shared by the CLI runner and the
 /// E2E test harness so the native behavior can never drift.
 pub fn link_console_functions(linker: &mut Linker<()>) -> Result<()> {
@@ -66,9 +81,11 @@ pub fn link_console_functions(linker: &mut Linker<()>) -> Result<()> {
         code
     })?;
 
-    linker.func_wrap("Dream", "consoleExit", |code: i32| -> () {
+    // Unwinds the module instead of ending the process here: the embedder decides what exiting
+    // means (see [`ExitRequest`]).
+    linker.func_wrap("Dream", "consoleExit", |code: i32| -> Result<()> {
         let _ = io::stdout().flush();
-        std::process::exit(code);
+        Err(Error::new(ExitRequest(code)))
     })?;
 
     Ok(())
@@ -25,9 +25,9 @@ pub fn link_datetime_functions(linker: &mut Linker<()>) -> Result<()> {
         |millis: i64| -> i32 {
             use chrono::{Local, TimeZone};
             match Local.timestamp_millis_opt(millis) {
-                chrono::LocalResult::Single(dt) => (dt.offset().local_minus_utc() / 60) as i32,
+                chrono::LocalResult::Single(dt) => dt.offset().local_minus_utc() / 60,
                 chrono::LocalResult::Ambiguous(dt, _) => {
-                    (dt.offset().local_minus_utc() / 60) as i32
+                    dt.offset().local_minus_utc() / 60
                 }
                 chrono::LocalResult::None => 0,
             }
@@ -7,7 +7,8 @@ use std::path::Path;
 use wasmtime::*;
 
 use super::memory::{
-    read_arg_bytes, read_arg_string, write_bytes_to_memory, write_string_to_memory,
+    read_arg_bytes, read_arg_string, read_arg_string_bytes, write_bytes_to_memory,
+    write_string_bytes_to_memory, write_string_to_memory,
 };
 
 /// Registers the synchronous filesystem host functions on `linker`. Shared by the CLI runner and
@@ -18,8 +19,8 @@ pub fn link_file_functions(linker: &mut Linker<()>) -> Result<()> {
         "fileRead",
         |mut caller: Caller<'_, ()>, path_ptr: i32| -> i32 {
             let path = read_arg_string(&mut caller, path_ptr);
-            let content = fs::read_to_string(&path).unwrap_or_default();
-            write_string_to_memory(&mut caller, &content)
+            let content = fs::read(&path).unwrap_or_default();
+            write_string_bytes_to_memory(&mut caller, &content)
         },
     )?;
 
@@ -28,8 +29,8 @@ pub fn link_file_functions(linker: &mut Linker<()>) -> Result<()> {
         "fileWrite",
         |mut caller: Caller<'_, ()>, path_ptr: i32, content_ptr: i32| -> i64 {
             let path = read_arg_string(&mut caller, path_ptr);
-            let content = read_arg_string(&mut caller, content_ptr);
-            match fs::write(&path, content.as_bytes()) {
+            let content = read_arg_string_bytes(&mut caller, content_ptr);
+            match fs::write(&path, &content) {
                 Ok(()) => content.len() as i64,
                 Err(_) => -1,
             }
@@ -41,12 +42,12 @@ pub fn link_file_functions(linker: &mut Linker<()>) -> Result<()> {
         "fileAppend",
         |mut caller: Caller<'_, ()>, path_ptr: i32, content_ptr: i32| -> i64 {
             let path = read_arg_string(&mut caller, path_ptr);
-            let content = read_arg_string(&mut caller, content_ptr);
+            let content = read_arg_string_bytes(&mut caller, content_ptr);
             let result = fs::OpenOptions::new()
                 .create(true)
                 .append(true)
                 .open(&path)
-                .and_then(|mut f| f.write_all(content.as_bytes()));
+                .and_then(|mut f| f.write_all(&content));
             match result {
                 Ok(()) => content.len() as i64,
                 Err(_) => -1,
@@ -4,7 +4,7 @@
 
 use wasmtime::*;
 
-use super::memory::{read_arg_bytes, read_arg_string, write_bytes_to_memory};
+use super::memory::{read_arg_bytes, read_arg_string, read_arg_string_bytes, write_bytes_to_memory};
 
 /// Future heap-block sizing/kind, mirroring `mir::async_emit` (`F_SLOTS` = 56) and
 /// `runtime/dream.js` (`FUTURE_KIND_HOST` = 1). A host future saves no locals, so its block is
@@ -114,7 +114,7 @@ pub fn link_http_functions(linker: &mut Linker<()>) -> Result<()> {
             let url = read_arg_string(&mut caller, url_ptr);
             let method = read_arg_string(&mut caller, method_ptr);
             let headers = read_arg_string(&mut caller, headers_ptr);
-            let body = read_arg_string(&mut caller, body_ptr).into_bytes();
+            let body = read_arg_string_bytes(&mut caller, body_ptr);
             let response = perform_http(&method, &url, &headers, body);
             resolve_host_future_bytes(&mut caller, &response)
         },
@@ -1,6 +1,10 @@
 //! Linear-memory marshaling shared by every host-function module: reading/writing Dream strings
 //! and `char[]` byte arrays across the WASM boundary. These mirror `DreamInstance`'s helpers in
 //! `runtime/dream.js` so the native and JS hosts lay out values identically.
+//!
+//! A Dream string is UTF-8: its bytes cross the boundary unchanged in both directions. Text that
+//! is not valid UTF-8 (e.g. a `substring` that splits a multi-byte character) only becomes lossy
+//! where a host API needs a Rust `String`; output and file contents go through the raw bytes.
 
 use wasmtime::*;
 
@@ -13,14 +17,20 @@ const TAG_STRING: i32 = 5;
 /// `char[]` (byte array) is laid out as `[count: i32][bytes...]` at the data pointer.
 const TAG_ARRAY: i32 = 6;
 
-/// Reads a NUL-terminated UTF-8 string from `memory` starting at `ptr`.
-pub fn read_string_from_memory(memory: &Memory, store: impl AsContext, ptr: i32) -> String {
+/// Reads the bytes of the NUL-terminated Dream string at `ptr` in `memory`, exactly as stored.
+pub fn read_string_bytes_from_memory(memory: &Memory, store: impl AsContext, ptr: i32) -> Vec<u8> {
     let data = memory.data(&store);
     let mut end = ptr as usize;
     while end < data.len() && data[end] != 0 {
         end += 1;
     }
-    String::from_utf8_lossy(&data[ptr as usize..end]).into_owned()
+    data[ptr as usize..end].to_vec()
+}
+
+/// Reads a NUL-terminated UTF-8 string from `memory` starting at `ptr`. Malformed UTF-8 is
+/// replaced with U+FFFD; use [`read_string_bytes_from_memory`] where the bytes must survive.
+pub fn read_string_from_memory(memory: &Memory, store: impl AsContext, ptr: i32) -> String {
+    String::from_utf8_lossy(&read_string_bytes_from_memory(memory, store, ptr)).into_owned()
 }
 
 /// Reads the caller's exported `memory` and returns the NUL-terminated string at `ptr`.
@@ -32,17 +42,31 @@ pub(crate) fn read_arg_string(caller: &mut Caller<'_, ()>, ptr: i32) -> String {
     read_string_from_memory(&memory, &*caller, ptr)
 }
 
+/// Like [`read_arg_string`], but returns the string's raw UTF-8 bytes (lossless).
+pub(crate) fn read_arg_string_bytes(caller: &mut Caller<'_, ()>, ptr: i32) -> Vec<u8> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .expect("module must export `memory`");
+    read_string_bytes_from_memory(&memory, &*caller, ptr)
+}
+
 /// Allocates `s` as a Dream `string` inside the module's linear memory by calling its exported
 /// `malloc`, copying the UTF-8 bytes, and NUL-terminating. Returns the data pointer (mirrors
 /// `DreamInstance.writeString` in `runtime/dream.js`). Used by host functions that return strings.
 pub fn write_string_to_memory(caller: &mut Caller<'_, ()>, s: &str) -> i32 {
+    write_string_bytes_to_memory(caller, s.as_bytes())
+}
+
+/// Allocates a Dream `string` holding `bytes` unchanged (they should be UTF-8, but are not
+/// checked), NUL-terminated. Returns the data pointer.
+pub(crate) fn write_string_bytes_to_memory(caller: &mut Caller<'_, ()>, bytes: &[u8]) -> i32 {
     let malloc = caller
         .get_export("malloc")
         .and_then(Extern::into_func)
         .expect("module must export `malloc`")
         .typed::<(i32, i32), i32>(&*caller)
         .expect("unexpected `malloc` signature");
-    let bytes = s.as_bytes();
     let ptr = malloc
         .call(&mut *caller, (bytes.len() as i32 + 1, TAG_STRING))
         .expect("malloc call failed");
@@ -1,20 +1,128 @@
 use super::host::{
-    enable_ansi_support, link_console_functions, link_datetime_functions, link_file_functions,
-    link_http_functions, link_math_functions, link_regex_functions, read_string_from_memory,
+    enable_ansi_support, ExitRequest, link_console_functions, link_datetime_functions, link_file_functions,
+    link_http_functions, link_math_functions, link_regex_functions, read_string_bytes_from_memory,
+    read_string_from_memory,
 };
+use crate::mir::abi::{
+    EXPORT_HEAP_MAX_PAGES, EXPORT_MAIN_ARGS, EXPORT_OOM_REQUEST, EXPORT_PANIC_MESSAGE,
+    EXPORT_TEST_PREFIX, TAG_ARRAY, TAG_STRING, WASM_PAGE_SIZE,
+};
+use crate::mir::srcmap::{strip_line_markers, Frame, SourceMap};
 use std::fs;
+use std::io::Write;
 use wasmtime::*;
 
-pub fn execute_wasm(wat_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Host-side settings for running a compiled module.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Upper bound on linear memory, in bytes (rounded down to whole 64 KiB pages). `None` lets the
+    /// heap grow to the 4 GiB wasm32 limit.
+    pub max_heap_bytes: Option<u64>,
+    /// Arguments passed to `main(args: string[])`; ignored by a `main` that takes none.
+    pub args: Vec<String>,
+}
+
+/// The engine compiled modules run on: the default configuration plus the exception-handling
+/// proposal, which `throw`/`try` compile to.
+pub fn new_engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config.wasm_exceptions(true);
+    Engine::new(&config)
+}
+
+pub fn execute_wasm(wat_path: &str, options: &RunOptions) -> Result<(), Box<dyn std::error::Error>> {
     enable_ansi_support();
     let wat_content = fs::read_to_string(wat_path)?;
-    let wasm_bytes = wat::parse_str(&wat_content)?;
+    let wasm_bytes = strip_line_markers(&wat::parse_str(&wat_content)?);
 
-    let engine = Engine::default();
+    let engine = new_engine()?;
     let module = Module::new(&engine, &wasm_bytes)?;
 
     let mut store = Store::new(&engine, ());
-    let mut linker = Linker::new(&engine);
+    let linker = host_linker(&engine, &module)?;
+
+    let instance = linker.instantiate(&mut store, &module)?;
+    if let Some(max) = options.max_heap_bytes {
+        set_max_heap(&instance, &mut store, max)?;
+    }
+    set_main_args(&instance, &mut store, &options.args)?;
+
+    if let Ok(main_func) = instance.get_typed_func::<(), ()>(&mut store, "main") {
+        if let Err(trap) = main_func.call(&mut store, ()) {
+            if let Some(ExitRequest(code)) = trap.downcast_ref::<ExitRequest>() {
+                std::process::exit(*code);
+            }
+            let map = SourceMap::from_wasm(&wasm_bytes);
+            return Err(trap_error(&instance, &mut store, trap, &map).into());
+        }
+    } else {
+        println!("No main function found in module");
+    }
+
+    Ok(())
+}
+
+/// The outcome of one `@test` function run by [`run_tests`].
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// The test function's name.
+    pub name: String,
+    /// `None` if the test passed; otherwise what failed, followed by its Dream stack trace.
+    pub failure: Option<String>,
+    /// Where a failed test stopped: the innermost frame outside the standard library, i.e. the
+    /// failing `Assert` call or the statement that trapped.
+    pub location: Option<Frame>,
+}
+
+/// Runs every `@test` export of a test build (`wasm_path` holds its text or binary form), in
+/// declaration order. Each test gets a fresh instance, so one test's globals, heap, or trap cannot
+/// affect the next; a test calling `System.exit` fails instead of ending the run. `report` sees
+/// each result as soon as its test finishes.
+pub fn run_tests(
+    wasm_path: &str,
+    options: &RunOptions,
+    mut report: impl FnMut(&TestResult),
+) -> Result<Vec<TestResult>, Box<dyn std::error::Error>> {
+    enable_ansi_support();
+    let wasm_bytes = strip_line_markers(&wat::parse_file(wasm_path)?);
+    let engine = new_engine()?;
+    let module = Module::new(&engine, &wasm_bytes)?;
+    let linker = host_linker(&engine, &module)?;
+    let map = SourceMap::from_wasm(&wasm_bytes);
+
+    let tests: Vec<String> = module
+        .exports()
+        .filter_map(|export| export.name().strip_prefix(EXPORT_TEST_PREFIX))
+        .map(str::to_string)
+        .collect();
+    let mut results = Vec::with_capacity(tests.len());
+    for name in tests {
+        let mut store = Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &module)?;
+        if let Some(max) = options.max_heap_bytes {
+            set_max_heap(&instance, &mut store, max)?;
+        }
+        let test = instance
+            .get_typed_func::<(), ()>(&mut store, &format!("{}{}", EXPORT_TEST_PREFIX, name))?;
+        let result = match test.call(&mut store, ()) {
+            Ok(()) => TestResult { name, failure: None, location: None },
+            Err(trap) => {
+                let location = stack_trace(&trap, &map)
+                    .into_iter()
+                    .find(|frame| !frame.file.starts_with("<std>/"));
+                let failure = trap_error(&instance, &mut store, trap, &map).to_string();
+                TestResult { name, failure: Some(failure), location }
+            }
+        };
+        report(&result);
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// A linker providing every host function a module built for the wasm target may import.
+fn host_linker(engine: &Engine, module: &Module) -> Result<Linker<()>> {
+    let mut linker = Linker::new(engine);
 
     linker.func_wrap("env", "print_int", |v: i32| {
         print!("{}", v);
@@ -28,10 +136,10 @@ pub fn execute_wasm(wat_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         print!("{}", v);
     })?;
 
+    // Strings and chars are written as raw UTF-8 bytes: a `char` is one byte of a multi-byte
+    // sequence, so printing a string char by char reassembles it on the terminal.
     linker.func_wrap("env", "print_char", |v: i32| {
-        if let Some(c) = char::from_u32(v as u32) {
-            print!("{}", c);
-        }
+        let _ = std::io::stdout().write_all(&[v as u8]);
     })?;
 
     linker.func_wrap(
@@ -39,15 +147,16 @@ pub fn execute_wasm(wat_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         "print_string",
         |mut caller: Caller<'_, ()>, ptr: i32| {
             let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
-            let s = read_string_from_memory(&memory, &caller, ptr);
-            print!("{}", s);
+            let bytes = read_string_bytes_from_memory(&memory, &caller, ptr);
+            let _ = std::io::stdout().write_all(&bytes);
         },
     )?;
 
     linker.func_wrap("env", "println", |mut caller: Caller<'_, ()>, ptr: i32| {
         let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
-        let s = read_string_from_memory(&memory, &caller, ptr);
-        println!("{}", s);
+        let mut bytes = read_string_bytes_from_memory(&memory, &caller, ptr);
+        bytes.push(b'\n');
+        let _ = std::io::stdout().write_all(&bytes);
     })?;
 
     link_math_functions(&mut linker)?;
@@ -63,15 +172,135 @@ pub fn execute_wasm(wat_path: &str) -> Result<(), Box<dyn std::error::Error>> {
     // user `@js(...)` import) have no native implementation. Stub every still-unresolved import
     // as a trap so modules that merely *declare* them still instantiate and run under wasmtime;
     // calling one without a JS host traps, matching `runtime/dream.js`'s thrower stubs.
-    linker.define_unknown_imports_as_traps(&module)?;
+    linker.define_unknown_imports_as_traps(module)?;
+    Ok(linker)
+}
 
-    let instance = linker.instantiate(&mut store, &module)?;
+/// The error a trap stands for: a Dream runtime error or plain trap, followed by its stack trace.
+fn trap_error<T>(instance: &Instance, store: &mut Store<T>, trap: Error, map: &SourceMap) -> Error {
+    if let Some(error) = traced_runtime_error(instance, store, &trap, map) {
+        return error;
+    }
+    let frames = stack_trace(&trap, map);
+    if frames.is_empty() {
+        return trap;
+    }
+    with_stack_trace(trap, &frames)
+}
 
-    if let Ok(main_func) = instance.get_typed_func::<(), ()>(&mut store, "main") {
-        main_func.call(&mut store, ())?;
+/// The Dream frames of a trap's backtrace, innermost first, resolved through the module's source map.
+/// Frames in runtime helpers (allocator, string runtime, `$panic`) have no source and are skipped.
+pub fn stack_trace(trap: &Error, map: &SourceMap) -> Vec<Frame> {
+    let Some(backtrace) = trap.downcast_ref::<WasmBacktrace>() else {
+        return Vec::new();
+    };
+    backtrace
+        .frames()
+        .iter()
+        .filter_map(|frame| map.frame(frame.func_index(), frame.module_offset()))
+        .collect()
+}
+
+/// [`runtime_error`] followed by the trap's Dream stack trace, e.g.
+///
+/// ```text
+/// index out of bounds: index 3 is out of range for int[] of length 3
+///     at Point.get (src/app.dream:10)
+///     at main (src/app.dream:17)
+/// ```
+pub fn traced_runtime_error<T>(
+    instance: &Instance,
+    store: &mut Store<T>,
+    trap: &Error,
+    map: &SourceMap,
+) -> Option<Error> {
+    let error = runtime_error(instance, store)?;
+    Some(with_stack_trace(error, &stack_trace(trap, map)))
+}
+
+/// Renders a runtime error as its message followed by one indented `at ...` line per frame. For a
+/// plain trap the message is the trap's own description without wasmtime's raw backtrace.
+fn with_stack_trace(error: Error, frames: &[Frame]) -> Error {
+    let message = if let Some(trap) = error.downcast_ref::<Trap>() {
+        trap.to_string()
+    } else if let Some(exit) = error.downcast_ref::<ExitRequest>() {
+        exit.to_string()
     } else {
-        println!("No main function found in module");
+        error.to_string()
+    };
+    let mut out = message;
+    for frame in frames {
+        out.push_str("\n    ");
+        out.push_str(&frame.to_string());
     }
+    Error::msg(out)
+}
 
-    Ok(())
+/// Caps how far the module's allocator may grow linear memory, in bytes (rounded down to whole
+/// pages). Allocations made by module initializers run at instantiation, before this can be applied.
+pub fn set_max_heap<T>(instance: &Instance, store: &mut Store<T>, bytes: u64) -> Result<()> {
+    let global = instance
+        .get_global(&mut *store, EXPORT_HEAP_MAX_PAGES)
+        .ok_or_else(|| Error::msg("module does not export a heap limit"))?;
+    let pages = (bytes / WASM_PAGE_SIZE).min(u64::from(u16::MAX) + 1) as i32;
+    global.set(&mut *store, Val::I32(pages))
+}
+
+/// Allocates `args` as a `string[]` and hands it to `main(args: string[])` through the module's
+/// [`EXPORT_MAIN_ARGS`] slot. A module whose `main` takes no arguments has no slot and ignores them.
+pub fn set_main_args<T>(instance: &Instance, store: &mut Store<T>, args: &[String]) -> Result<()> {
+    let slot = match instance.get_global(&mut *store, EXPORT_MAIN_ARGS) {
+        Some(slot) if !args.is_empty() => slot,
+        _ => return Ok(()),
+    };
+    let malloc = instance.get_typed_func::<(i32, i32), i32>(&mut *store, "malloc")?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| Error::msg("module does not export `memory`"))?;
+    let array = malloc.call(&mut *store, (4 + 4 * args.len() as i32, TAG_ARRAY))?;
+    memory.write(&mut *store, array as usize, &(args.len() as i32).to_le_bytes())?;
+    for (i, arg) in args.iter().enumerate() {
+        let ptr = malloc.call(&mut *store, (arg.len() as i32 + 1, TAG_STRING))?;
+        memory.write(&mut *store, ptr as usize, arg.as_bytes())?;
+        memory.write(&mut *store, ptr as usize + arg.len(), &[0])?;
+        memory.write(&mut *store, array as usize + 4 + 4 * i, &ptr.to_le_bytes())?;
+    }
+    slot.set(&mut *store, Val::I32(array))
+}
+
+/// After a trap, returns an out-of-memory error describing the failed allocation if that is what
+/// the trap was (see `$out_of_memory` in the allocator runtime), or `None` for any other trap.
+pub fn out_of_memory_error<T>(instance: &Instance, store: &mut Store<T>) -> Option<Error> {
+    let requested = instance
+        .get_global(&mut *store, EXPORT_OOM_REQUEST)?
+        .get(&mut *store)
+        .i32()
+        .filter(|&n| n != 0)? as u32;
+    let limit = instance
+        .get_global(&mut *store, EXPORT_HEAP_MAX_PAGES)
+        .and_then(|g| g.get(&mut *store).i32())
+        .map(|pages| pages as u32 as u64 * WASM_PAGE_SIZE)
+        .unwrap_or(0);
+    Some(Error::msg(format!(
+        "out of memory: could not allocate {} bytes (heap limit {} bytes)",
+        requested, limit
+    )))
+}
+
+/// After a trap, returns the failed runtime check's message (see `$panic` in the runtime) if that is
+/// what the trap was, or `None` for any other trap.
+pub fn panic_error<T>(instance: &Instance, store: &mut Store<T>) -> Option<Error> {
+    let message = instance
+        .get_global(&mut *store, EXPORT_PANIC_MESSAGE)?
+        .get(&mut *store)
+        .i32()
+        .filter(|&ptr| ptr != 0)?;
+    let memory = instance.get_memory(&mut *store, "memory")?;
+    Some(Error::msg(read_string_from_memory(&memory, &*store, message)))
+}
+
+/// After a trap, the Dream-level error it stands for — running out of memory or a failed runtime
+/// check — or `None` for a plain WebAssembly trap.
+pub fn runtime_error<T>(instance: &Instance, store: &mut Store<T>) -> Option<Error> {
+    out_of_memory_error(instance, store).or_else(|| panic_error(instance, store))
 }
@@ -6,6 +6,10 @@
 //! always agree. Fields are kept in **declaration order**, which coincides with offset order (a
 //! struct lays its fields out sequentially), so the resolved field index used in
 //! [`super::HPlace::Field`] indexes straight into [`TypeLayout::fields`].
+//!
+//! A subclass layout starts with its base class's layout verbatim (same fields, same offsets), so
+//! a pointer to a subclass instance is also a valid pointer to its base: base methods, field reads
+//! and releases work on it unchanged.
 
 use crate::types::{PrimTy, TyKind, TypeId, TypeInterner};
 use indexmap::IndexMap;
@@ -38,6 +42,8 @@ pub struct TypeLayout {
     pub fields: Vec<FieldLayout>,
     /// Total allocated size in bytes (data only; the allocator adds its own header).
     pub size: u32,
+    /// The base class (`class Dog : Animal`) whose layout prefixes this one, if any.
+    pub base: Option<TypeId>,
 }
 
 impl TypeLayout {
@@ -58,7 +64,35 @@ impl TypeLayout {
             offset += size;
             max_align = max_align.max(align);
         }
-        TypeLayout { name: name.into(), fields, size: align_up(offset, max_align) }
+        TypeLayout { name: name.into(), fields, size: align_up(offset, max_align), base: None }
+    }
+
+    /// Builds a subclass layout: `base`'s fields at their existing offsets, then the subclass's own
+    /// `field_defs` laid out from the end of the base (its trailing padding is not reused, so the
+    /// prefix is exactly a base instance). `base_ty` is recorded as [`TypeLayout::base`].
+    pub fn extending(
+        interner: &TypeInterner,
+        name: impl Into<String>,
+        base_ty: TypeId,
+        base: &TypeLayout,
+        field_defs: impl IntoIterator<Item = (String, TypeId)>,
+    ) -> Self {
+        let mut offset = base.size;
+        let mut max_align = base.fields.iter().map(|f| scalar_size(interner, f.ty).1).fold(4, u32::max);
+        let mut fields = base.fields.clone();
+        for (field_name, ty) in field_defs {
+            let (size, align) = scalar_size(interner, ty);
+            offset = align_up(offset, align);
+            fields.push(FieldLayout { offset, ty, name: field_name });
+            offset += size;
+            max_align = max_align.max(align);
+        }
+        TypeLayout {
+            name: name.into(),
+            fields,
+            size: align_up(offset, max_align),
+            base: Some(base_ty),
+        }
     }
 }
 
@@ -117,6 +151,33 @@ impl LayoutTable {
     pub fn insert_union(&mut self, ty: TypeId, layout: UnionLayout) {
         self.unions.insert(ty, layout);
     }
+
+    /// True when `ty` is `ancestor` or (transitively) extends it.
+    pub fn is_subclass_of(&self, ty: TypeId, ancestor: TypeId) -> bool {
+        let mut cur = Some(ty);
+        while let Some(t) = cur {
+            if t == ancestor {
+                return true;
+            }
+            cur = self.structs.get(&t).and_then(|l| l.base);
+        }
+        false
+    }
+
+    /// The strict subclasses of `ty` (direct and indirect), in table order.
+    pub fn subclasses(&self, ty: TypeId) -> Vec<TypeId> {
+        self.structs
+            .keys()
+            .copied()
+            .filter(|t| *t != ty && self.is_subclass_of(*t, ty))
+            .collect()
+    }
+
+    /// True when some class extends `ty`, so a value statically typed `ty` may be an instance of a
+    /// larger subclass and its concrete type must be read from the runtime tag.
+    pub fn has_subclasses(&self, ty: TypeId) -> bool {
+        self.structs.values().any(|l| l.base == Some(ty))
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +201,20 @@ mod tests {
         assert_eq!(l.fields[2].offset, 16);
         assert_eq!(l.size, 24);
     }
+
+    #[test]
+    fn subclass_layout_keeps_base_prefix() {
+        let mut i = TypeInterner::new();
+        let dbl = i.prim(PrimTy::Double);
+        let by = i.prim(PrimTy::Byte);
+        let int = i.int();
+        let a = i.struct_ty(crate::types::DefId(0), vec![]);
+        // Base: int @0, byte @4, size 8. The subclass's fields continue past the base: double @8.
+        let base = TypeLayout::from_fields(&i, "A", [("n".into(), int), ("b".into(), by)]);
+        let l = TypeLayout::extending(&i, "B", a, &base, [("d".into(), dbl), ("c".into(), by)]);
+        let offsets: Vec<u32> = l.fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, vec![0, 4, 8, 16]);
+        assert_eq!(l.size, 24);
+        assert_eq!(l.base, Some(a));
+    }
 }
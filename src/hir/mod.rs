@@ -15,6 +15,10 @@ pub use ops::{BinOp, UnOp};
 
 use crate::types::{DefId, TypeId};
 
+/// Name of the hidden first parameter of a lambda body or function-reference thunk: the closure
+/// block being called, through which captures are read.
+pub const CLOSURE_ENV_PARAM: &str = "__env";
+
 /// A local variable slot within a function (parameters and `let`-bindings), unique per function.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LocalId(pub u32);
@@ -53,13 +57,18 @@ pub struct Hir {
     /// itable data + dispatch trampolines emitted by the backend, and keeps concrete interface
     /// method implementations reachable through dead-code elimination.
     pub interfaces: InterfaceTable,
+    /// The `@test` functions, in declaration order. Only a test build exports them (see
+    /// `Compiler::with_tests`).
+    pub tests: Vec<DefId>,
 }
 
 /// Interface dispatch metadata carried from analysis into codegen.
 #[derive(Debug, Clone, Default)]
 pub struct InterfaceTable {
     /// The program's interfaces in registration order; the index into this vector is the stable
-    /// `iface_id` referenced by [`HExprKind::InterfaceCall`].
+    /// `iface_id` referenced by [`HExprKind::InterfaceCall`]. Class vtables reuse the same shape:
+    /// each class declaring virtual methods takes one of the leading ids (named after the class),
+    /// implemented by the class itself and every subclass, ahead of the real interfaces.
     pub interfaces: Vec<InterfaceInfo>,
     /// Every class that implements at least one interface, with the concrete method symbols it
     /// supplies for each implemented interface.
@@ -130,6 +139,19 @@ pub struct HFunction {
     pub locals: Vec<HLocal>,
     pub body: Vec<HStmt>,
     pub is_async: bool,
+    /// Where the function was declared, for runtime stack traces. `None` for synthesized functions
+    /// (thunks, the module initializer, derived converters).
+    pub source: Option<SourceInfo>,
+}
+
+/// A function's place in the source: the name a stack trace shows for it (`Point.move`, `main`,
+/// `main.<lambda>`) and the file and line it is declared at. Statement lines within the body are
+/// carried by [`HStmt::Line`] markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceInfo {
+    pub name: String,
+    pub file: String,
+    pub line: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -148,12 +170,11 @@ pub struct HLocal {
     pub ty: TypeId,
 }
 
-/// A resolved reference to a variable or function.
+/// A resolved reference to a variable. (Function values are [`HExprKind::Closure`]s.)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Binding {
     Local(LocalId),
     Global(GlobalId),
-    Func(Callee),
 }
 
 /// A fully resolved call target: the def, the monomorphization type-args (empty when non-generic),
@@ -224,6 +245,28 @@ pub enum HStmt {
     Continue(Option<String>),
     /// `await e;` at statement position (the only legal await position).
     Await(HExpr),
+    /// `throw value;` — the value is `object`-typed (primitives arrive boxed).
+    Throw(HExpr),
+    /// `try { body } catch ... finally { ... }`. A caught exception is stored in the `object` local
+    /// `exception`, then the first clause whose type test passes runs (an untyped clause always
+    /// matches); with no match it propagates after `finally`. `finally` runs on every exit.
+    Try {
+        body: Vec<HStmt>,
+        exception: LocalId,
+        catches: Vec<HCatch>,
+        finally: Vec<HStmt>,
+    },
+    /// Source-line marker: the statements after it, up to the next marker, were written on this
+    /// line of the function's file. Has no runtime effect; it feeds the stack-trace line table.
+    Line(u32),
+}
+
+/// One `catch` clause of a [`HStmt::Try`]: `ty` is the type it catches (`None` catches anything).
+/// The body starts by binding the clause's variable from the `try`'s exception local.
+#[derive(Debug, Clone)]
+pub struct HCatch {
+    pub ty: Option<TypeId>,
+    pub body: Vec<HStmt>,
 }
 
 /// One arm of a `switch`.
@@ -303,7 +346,17 @@ pub enum HExprKind {
         callee: Callee,
         args: Vec<HExpr>,
     },
-    /// An indirect call through a function-typed value.
+    /// A function value: a heap closure block `[fn: table index][captures...]` whose layout is the
+    /// struct `env`. `func` takes the block itself as a hidden first parameter, followed by the
+    /// function type's declared parameters; `captures` initialize the block's fields after `fn`, in
+    /// order. A plain function used as a value is a capture-free closure over a forwarding thunk.
+    Closure {
+        func: Callee,
+        env: TypeId,
+        captures: Vec<HExpr>,
+    },
+    /// An indirect call through a function-typed value (a closure pointer; the block is passed as
+    /// the callee's hidden first argument).
     IndirectCall {
         target: Box<HExpr>,
         args: Vec<HExpr>,
@@ -322,8 +375,9 @@ pub enum HExprKind {
     },
     /// Constructor `Type(args)`; `instance` records the monomorphization type-args when generic.
     /// When `ctor` is `Some`, `args` are the user `constructor(){}`'s arguments (the backend
-    /// allocates, then calls that constructor with `this` + args); when `None`, the implicit zero-arg
-    /// default constructor takes no args and every field is zero-initialized.
+    /// allocates, then calls that constructor with `this` + args); when `None`, `args` initialize the
+    /// leading fields in order and the rest are zero-initialized. Only tuple literals supply such
+    /// args; the implicit zero-arg default constructor takes none.
     New {
         def: DefId,
         instance: Vec<TypeId>,
@@ -390,6 +444,13 @@ pub enum HExprKind {
         lhs: Box<HExpr>,
         rhs: Box<HExpr>,
     },
+    /// Evaluates `value` once into the hidden `local`, then yields `body`, which reads it. Lets a
+    /// desugaring use an operand more than once without evaluating it twice.
+    Let {
+        local: LocalId,
+        value: Box<HExpr>,
+        body: Box<HExpr>,
+    },
     /// `await e` used as a value (only valid in the limited await positions; carries the awaited
     /// future's inner type as `ty`).
     Await(Box<HExpr>),
@@ -453,6 +514,7 @@ mod tests {
             locals: vec![],
             body,
             is_async: false,
+            source: None,
         };
 
         let hir = Hir {
@@ -463,6 +525,7 @@ mod tests {
             imports: vec![],
             intrinsics: vec![],
             interfaces: InterfaceTable::default(),
+            tests: vec![],
         };
         assert_eq!(hir.functions.len(), 1);
         assert_eq!(hir.functions[0].params.len(), 2);
@@ -98,6 +98,10 @@ pub const ATTR_SLEEP: &str = "sleep";
 /// `String.alloc(n)` / `String.set(s, i, c)` — low-level string buffer primitives.
 pub const ATTR_STRING_ALLOC: &str = "string_alloc";
 pub const ATTR_STRING_SET: &str = "string_set";
+/// `String.copy(dst, at, src, start, count)` — overlap-safe byte copy between string buffers.
+pub const ATTR_STRING_COPY: &str = "string_copy";
+/// `double.to_fixed(digits)` — fixed-point decimal formatting.
+pub const ATTR_FORMAT_FIXED: &str = "format_fixed";
 /// `Debug.free_list_head()` — allocator introspection for tests.
 pub const ATTR_DEBUG_FREE_LIST: &str = "debug_get_free_list_head";
 /// `Debug.heap_ptr()` — current bump-pointer (heap high-water mark).
@@ -108,6 +112,8 @@ pub const ATTR_DEBUG_LIVE_OBJECTS: &str = "debug_get_live_objects";
 pub const ATTR_DEBUG_TOTAL_ALLOCATIONS: &str = "debug_get_total_allocations";
 /// `Debug.ref_count(o)` — live reference count of a heap value.
 pub const ATTR_DEBUG_REF_COUNT: &str = "debug_get_ref_count";
+/// `Debug.collect_cycles()` — run the cycle collector now.
+pub const ATTR_DEBUG_COLLECT_CYCLES: &str = "debug_collect_cycles";
 
 /// The operation a `@intrinsic("…")`-tagged static method lowers to. Derived once from the
 /// attribute key via [`IntrinsicOp::from_key`], so every layer dispatches off the same enum
@@ -136,6 +142,10 @@ pub enum IntrinsicOp {
     StringAlloc,
     /// `String.set(s, i, c)` — write char `c` at index `i` of buffer `s`.
     StringSet,
+    /// `String.copy(dst, at, src, start, count)` — copy `count` bytes of `src` into `dst`.
+    StringCopy,
+    /// `double.to_fixed(digits)` — format with exactly `digits` decimal places.
+    FormatFixed,
     /// `Debug.free_list_head()` — head of the allocator free list.
     DebugFreeList,
     /// `Debug.heap_ptr()` — current bump-pointer value.
@@ -146,6 +156,8 @@ pub enum IntrinsicOp {
     DebugTotalAllocations,
     /// `Debug.ref_count(o)` — live reference count of a heap value.
     DebugRefCount,
+    /// `Debug.collect_cycles()` — run the cycle collector now.
+    DebugCollectCycles,
 }
 
 impl IntrinsicOp {
@@ -163,11 +175,14 @@ impl IntrinsicOp {
             ATTR_SLEEP => IntrinsicOp::Sleep,
             ATTR_STRING_ALLOC => IntrinsicOp::StringAlloc,
             ATTR_STRING_SET => IntrinsicOp::StringSet,
+            ATTR_STRING_COPY => IntrinsicOp::StringCopy,
+            ATTR_FORMAT_FIXED => IntrinsicOp::FormatFixed,
             ATTR_DEBUG_FREE_LIST => IntrinsicOp::DebugFreeList,
             ATTR_DEBUG_HEAP_PTR => IntrinsicOp::DebugHeapPtr,
             ATTR_DEBUG_LIVE_OBJECTS => IntrinsicOp::DebugLiveObjects,
             ATTR_DEBUG_TOTAL_ALLOCATIONS => IntrinsicOp::DebugTotalAllocations,
             ATTR_DEBUG_REF_COUNT => IntrinsicOp::DebugRefCount,
+            ATTR_DEBUG_COLLECT_CYCLES => IntrinsicOp::DebugCollectCycles,
             _ => return None,
         })
     }
@@ -1,32 +1,80 @@
-use dream::driver::compiler::{Compiler, Target};
-use dream::execution::wasm_runner::execute_wasm;
+use dream::driver::compiler::{Compiler, Emit, Target};
+use dream::driver::package::PackageGraph;
+use dream::execution::wasm_runner::{execute_wasm, run_tests, RunOptions, TestResult};
+use std::fs;
 use std::path::Path;
+use std::process::exit;
 use tracing::{error, info, Level};
 use tracing_subscriber::FmtSubscriber;
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
+const USAGE: &str = "\
+Usage: dream <command> [options] [<file>] [-- <args>...]
 
-    let mut verbose = false;
-    let mut run_after_compile = false;
-    let mut debug_alloc = false;
-    let mut file_name = None;
+Commands:
+  build      Compile to WebAssembly (the default when no command is given)
+  run        Compile and run with wasmtime; arguments after `--` are passed to `main(args: string[])`
+  check      Parse and type-check without generating code
+  test       Run the `@test` functions of each file, each in a fresh instance
+  emit-mir   Print the optimized MIR (same as `build --emit=mir -o -`)
+  fmt        Reformat source files in place
+  update     Rewrite dream.lock, re-pinning git dependencies to their checked-out commits
+  help       Print this message (same as -h / --help)
 
-    for arg in args.iter().skip(1) {
-        if arg == "-v" || arg == "--verbose" {
-            verbose = true;
-        } else if arg == "-d" || arg == "--debug" {
-            // Enable allocator instrumentation so the `Debug.live_objects()` /
-            // `Debug.total_allocations()` probes report real values. Off by default so normal
-            // builds carry zero per-allocation overhead.
-            debug_alloc = true;
-        } else if arg == "run" {
-            run_after_compile = true;
-        } else if !arg.starts_with("-") {
-            file_name = Some(arg);
-        }
-    }
+Without a file, `build`, `run`, `check`, and `emit-mir` compile the entry file named by the
+dream.toml in the current directory or the nearest parent.
+
+Options:
+  -o <path>                  Output path (`-` for stdout); defaults to the source path with the
+                             artifact's extension
+  --emit=wat|wasm|mir|hir    Write only this artifact (default: the .wat plus its assembled .wasm)
+  --no-abi                   Do not write the .abi.json sidecar
+  --target=wasm|wasi         Target the Dream host (default) or any WASI runtime
+  --unchecked                Compile without runtime bounds, null and cast checks
+  --max-heap=<size>          Cap the heap for `run` (e.g. 64M, 1G, or a byte count)
+  --check                    With `fmt`: list files that need formatting instead of rewriting them
+  --collect-cycles           Reclaim unreachable reference cycles with a cycle collector
+  --cache                    Keep build work in .dream-cache/ and reuse what is unchanged
+  -d, --debug                Instrument the allocator for the `Debug.*` probes
+  -v, --verbose              Log each compilation phase
+  -h, --help                 Print this message and exit
+
+Exit status: 0 on success, 1 when compilation, formatting checks, tests, or the program fail, 2
+for invalid usage. A program that calls `System.exit(code)` exits with `code`.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Build,
+    Run,
+    Check,
+    Test,
+    EmitMir,
+    Fmt,
+    Update,
+    Help,
+}
+
+/// A parsed command line.
+struct Cli {
+    command: Command,
+    files: Vec<String>,
+    output: Option<String>,
+    emit: Emit,
+    abi: bool,
+    target: Target,
+    runtime_checks: bool,
+    debug_alloc: bool,
+    collect_cycles: bool,
+    cache: bool,
+    verbose: bool,
+    fmt_check: bool,
+    run_options: RunOptions,
+}
 
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = parse_args(&args);
+
+    let verbose = cli.as_ref().is_ok_and(|cli| cli.verbose);
     let subscriber = FmtSubscriber::builder()
         .with_max_level(if verbose { Level::INFO } else { Level::WARN })
         .without_time()
@@ -34,54 +82,402 @@ fn main() {
         .finish();
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
-    if file_name.is_none() {
-        error!("Expected a source file (*.dream) as argument");
-        error!(
-            "Usage: {} [-v|--verbose] [-d|--debug] [run] <file>",
-            args[0]
+    let cli = match cli {
+        Ok(cli) => cli,
+        Err(message) => {
+            error!("{}", message);
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+
+    let ok = match cli.command {
+        Command::Help => {
+            println!("{}", USAGE);
+            true
+        }
+        Command::Fmt => format_files(&cli.files, cli.fmt_check),
+        Command::Update => update_lockfile(&cli.files),
+        Command::Test => test_files(&cli),
+        _ => compile_and_run(&cli),
+    };
+    if !ok {
+        exit(1);
+    }
+}
+
+/// Whether a positional argument that is not a command names a source file or directory.
+fn looks_like_path(arg: &str) -> bool {
+    arg.contains(['.', '/', '\\']) || Path::new(arg).exists()
+}
+
+/// Parses the arguments after the program name. The first positional argument names the command
+/// when it is one (`dream hello.dream` is `dream build hello.dream`); one that is neither a command
+/// nor a path (no extension or separator, and nothing of that name on disk) is a misspelled
+/// command. Everything after `--` is passed to the program by `run`.
+fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let mut cli = Cli {
+        command: Command::Build,
+        files: Vec::new(),
+        output: None,
+        emit: Emit::WatAndWasm,
+        abi: true,
+        target: Target::Wasm,
+        runtime_checks: true,
+        debug_alloc: false,
+        collect_cycles: false,
+        cache: false,
+        verbose: false,
+        fmt_check: false,
+        run_options: RunOptions::default(),
+    };
+    let mut command = None;
+    let mut program_args = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--" => {
+                program_args = Some(iter.by_ref().cloned().collect::<Vec<_>>());
+            }
+            "-v" | "--verbose" => cli.verbose = true,
+            "-h" | "--help" => command = Some(Command::Help),
+            // Enable allocator instrumentation so the `Debug.live_objects()` /
+            // `Debug.total_allocations()` probes report real values. Off by default so normal
+            // builds carry zero per-allocation overhead.
+            "-d" | "--debug" => cli.debug_alloc = true,
+            // Drop the runtime bounds/null/cast checks (release builds that trust their indexing).
+            "--unchecked" => cli.runtime_checks = false,
+            // Add the cycle collector, which frees reference cycles plain ARC would leak.
+            "--collect-cycles" => cli.collect_cycles = true,
+            "--no-abi" => cli.abi = false,
+            // Build through the `.dream-cache/` build cache.
+            "--cache" => cli.cache = true,
+            "--check" => cli.fmt_check = true,
+            "-o" => {
+                let path = iter.next().ok_or("-o expects an output path")?;
+                cli.output = Some(path.clone());
+            }
+            _ => {
+                if let Some(size) = arg.strip_prefix("--max-heap=") {
+                    let bytes = parse_size(size).ok_or_else(|| {
+                        format!("Invalid --max-heap size '{}' (expected e.g. 64M, 1G, or a byte count)", size)
+                    })?;
+                    cli.run_options.max_heap_bytes = Some(bytes);
+                } else if let Some(name) = arg.strip_prefix("--target=") {
+                    cli.target = match name {
+                        "wasm" => Target::Wasm,
+                        "wasi" => Target::Wasi,
+                        _ => return Err(format!("Unknown --target '{}' (expected 'wasm' or 'wasi')", name)),
+                    };
+                } else if let Some(kind) = arg.strip_prefix("--emit=") {
+                    cli.emit = match kind {
+                        "wat" => Emit::Wat,
+                        "wasm" => Emit::Wasm,
+                        "mir" => Emit::Mir,
+                        "hir" => Emit::Hir,
+                        _ => return Err(format!("Unknown --emit '{}' (expected wat, wasm, mir, or hir)", kind)),
+                    };
+                } else if arg.starts_with('-') && arg.len() > 1 {
+                    return Err(format!("Unknown option '{}'", arg));
+                } else if command.is_none() && cli.files.is_empty() {
+                    command = Some(match arg.as_str() {
+                        "build" => Command::Build,
+                        "run" => Command::Run,
+                        "check" => Command::Check,
+                        "test" => Command::Test,
+                        "emit-mir" => Command::EmitMir,
+                        "fmt" => Command::Fmt,
+                        "update" => Command::Update,
+                        "help" => Command::Help,
+                        _ if !looks_like_path(arg) => {
+                            return Err(format!(
+                                "Unknown command '{}' (expected build, run, check, test, emit-mir, fmt, update, or help)",
+                                arg
+                            ))
+                        }
+                        _ => {
+                            cli.files.push(arg.clone());
+                            Command::Build
+                        }
+                    });
+                } else {
+                    cli.files.push(arg.clone());
+                }
+            }
+        }
+    }
+    cli.command = command.unwrap_or(Command::Build);
+    // Help wins over everything else on the line: no file or option checks apply.
+    if cli.command == Command::Help {
+        return Ok(cli);
+    }
+
+    if cli.files.is_empty() && matches!(cli.command, Command::Fmt | Command::Test) {
+        return Err("Expected a source file (*.dream) as argument".to_string());
+    }
+    if cli.files.is_empty() && package_root(None).is_none() {
+        return Err(
+            "Expected a source file (*.dream) as argument, or a dream.toml in this directory or a parent"
+                .to_string(),
         );
-        error!(r"Example: {} run src/sample/test_arrays.dream", args[0]);
-        return;
     }
+    if !matches!(cli.command, Command::Fmt | Command::Test) && cli.files.len() > 1 {
+        return Err(format!("Expected one source file, found {}", cli.files.len()));
+    }
+    if let Some(args) = program_args {
+        if cli.command != Command::Run {
+            return Err("Only `run` takes program arguments after `--`".to_string());
+        }
+        cli.run_options.args = args;
+    }
+    if cli.command == Command::EmitMir {
+        cli.emit = Emit::Mir;
+        cli.output.get_or_insert_with(|| "-".to_string());
+    }
+    if cli.command == Command::Test
+        && (cli.target == Target::Wasi || cli.emit != Emit::WatAndWasm || cli.output.is_some())
+    {
+        return Err("`test` runs on the wasm target and takes no --target, --emit, or -o".to_string());
+    }
+    if cli.command == Command::Run {
+        if matches!(cli.target, Target::Wasi) {
+            return Err("`run` executes the wasm target; run a --target=wasi build with a WASI runtime (e.g. `wasmtime run <file>.wasm`)".to_string());
+        }
+        if cli.emit != Emit::WatAndWasm {
+            return Err("`run` does not take --emit".to_string());
+        }
+    }
+    Ok(cli)
+}
 
-    let file_name = file_name.unwrap();
+/// Runs `build`, `run`, `check`, or `emit-mir` on the single source file. Returns whether it
+/// succeeded; a program that calls `System.exit` ends the process itself.
+fn compile_and_run(cli: &Cli) -> bool {
+    let file_name = match cli.files.first() {
+        Some(file) => file.clone(),
+        // No file: build the entry of the package the current directory belongs to.
+        None => match package_root(None).map(|root| PackageGraph::load(&root)) {
+            Some(Ok(packages)) => packages.root().entry.to_string_lossy().into_owned(),
+            Some(Err(e)) => {
+                error!("{}", e);
+                return false;
+            }
+            None => return false,
+        },
+    };
+    let file_name = &file_name;
 
     info!("Dream Compiler Tools");
     info!("========================");
     info!("Compiling file: {}", file_name);
 
-    let compiler = Compiler::new(Target::Wasm).with_debug_alloc(debug_alloc);
-    let out_path = match get_path_from_file_path(file_name) {
-        Some(path) => path,
-        None => {
-            error!("Invalid source file path: {}", file_name);
-            return;
-        }
+    let compiler = Compiler::new(cli.target)
+        .with_debug_alloc(cli.debug_alloc)
+        .with_cycle_collection(cli.collect_cycles)
+        .with_runtime_checks(cli.runtime_checks)
+        .with_emit(cli.emit)
+        .with_abi(cli.abi)
+        .with_cache(cli.cache);
+
+    if cli.command == Command::Check {
+        return match compiler.check(file_name) {
+            Ok(()) => {
+                info!("No errors found");
+                true
+            }
+            Err(e) => {
+                error!("Check failed: {}", e);
+                false
+            }
+        };
+    }
+
+    let out_path = match &cli.output {
+        Some(path) => path.clone(),
+        None => match default_output_path(file_name, cli.emit) {
+            Some(path) => path,
+            None => {
+                error!("Invalid source file path: {}", file_name);
+                return false;
+            }
+        },
     };
+    if cli.command == Command::Run && out_path == "-" {
+        error!("`run` needs an output file, not stdout");
+        return false;
+    }
 
-    match compiler.compile(file_name, &out_path) {
-        Ok(_) => {
-            info!("Compilation successful");
+    if let Err(e) = compiler.compile(file_name, &out_path) {
+        error!("Compilation failed: {}", e);
+        return false;
+    }
+    info!("Compilation successful");
 
-            if run_after_compile {
-                info!("Executing via Wasmtime...");
-                if let Err(e) = execute_wasm(&out_path) {
-                    error!("Execution failed: {}", e);
-                }
+    if cli.command == Command::Run {
+        info!("Executing via Wasmtime...");
+        if let Err(e) = execute_wasm(&out_path, &cli.run_options) {
+            error!("Execution failed: {}", e);
+            return false;
+        }
+    }
+    true
+}
+
+/// Compiles each file as a test build and runs its `@test` functions, printing one line per test and
+/// the details of every failure. Returns `false` if a file fails to compile or any test fails.
+fn test_files(cli: &Cli) -> bool {
+    let compiler = Compiler::new(Target::Wasm)
+        .with_debug_alloc(cli.debug_alloc)
+        .with_cycle_collection(cli.collect_cycles)
+        .with_runtime_checks(cli.runtime_checks)
+        .with_emit(Emit::Wasm)
+        .with_abi(false)
+        .with_tests(true);
+
+    let (mut passed, mut failed) = (0, 0);
+    let mut ok = true;
+    for (index, file) in cli.files.iter().enumerate() {
+        let stem = Path::new(file).file_stem().and_then(|s| s.to_str()).unwrap_or("test");
+        let wasm = std::env::temp_dir().join(format!("dream_test_{}_{}_{}.wasm", std::process::id(), index, stem));
+        let wasm = wasm.to_string_lossy().to_string();
+        if let Err(e) = compiler.compile(file, &wasm) {
+            error!("Compilation failed: {}", e);
+            ok = false;
+            continue;
+        }
+
+        println!("running tests in {}", file);
+        let results = run_tests(&wasm, &cli.run_options, |result: &TestResult| {
+            match (&result.failure, &result.location) {
+                (None, _) => println!("test {} ... ok", result.name),
+                (Some(_), Some(at)) => println!("test {} ... FAILED at {}:{}", result.name, at.file, at.line),
+                (Some(_), None) => println!("test {} ... FAILED", result.name),
             }
+        });
+        let _ = fs::remove_file(&wasm);
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => {
+                error!("Could not run the tests in {}: {}", file, e);
+                ok = false;
+                continue;
+            }
+        };
+
+        let failures: Vec<&TestResult> = results.iter().filter(|r| r.failure.is_some()).collect();
+        if !failures.is_empty() {
+            println!("\nfailures:");
+            for result in &failures {
+                println!("\n---- {} ----\n{}", result.name, result.failure.as_deref().unwrap_or(""));
+            }
+        }
+        println!();
+        passed += results.len() - failures.len();
+        failed += failures.len();
+    }
+
+    ok &= failed == 0;
+    println!(
+        "test result: {}. {} passed; {} failed",
+        if ok { "ok" } else { "FAILED" },
+        passed,
+        failed
+    );
+    ok
+}
+
+/// Reformats each file in place, or with `check` only lists the ones whose formatting differs.
+/// Returns `false` if a file could not be read or written, or (with `check`) needs formatting.
+fn format_files(files: &[String], check: bool) -> bool {
+    let mut ok = true;
+    for file in files {
+        let text = match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(e) => {
+                error!("Could not read {}: {}", file, e);
+                ok = false;
+                continue;
+            }
+        };
+        let formatted = dream::syntax::format::format(&text);
+        if formatted == text {
+            continue;
+        }
+        if check {
+            println!("{}", file);
+            ok = false;
+        } else if let Err(e) = fs::write(file, formatted) {
+            error!("Could not write {}: {}", file, e);
+            ok = false;
+        } else {
+            info!("formatted {}", file);
+        }
+    }
+    ok
+}
+
+/// The root of the package `path` (a file or a directory; the current directory when `None`)
+/// belongs to, if a `dream.toml` is found there or above.
+fn package_root(path: Option<&str>) -> Option<std::path::PathBuf> {
+    let dir = match path {
+        Some(path) if Path::new(path).is_dir() => Path::new(path).to_path_buf(),
+        Some(path) => match Path::new(path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => Path::new(".").to_path_buf(),
+        },
+        None => std::env::current_dir().ok()?,
+    };
+    PackageGraph::find_root(&dir)
+}
+
+/// Rewrites the lockfile of the package `files[0]` (or the current directory) belongs to, pinning
+/// its git dependencies to the commits they are checked out at. Returns whether it succeeded.
+fn update_lockfile(files: &[String]) -> bool {
+    let root = match package_root(files.first().map(String::as_str)) {
+        Some(root) => root,
+        None => {
+            error!("No dream.toml found");
+            return false;
+        }
+    };
+    match PackageGraph::load(&root).and_then(|packages| packages.update_lockfile()) {
+        Ok(()) => {
+            info!("updated {}", root.join(dream::driver::package::LOCK_FILE).display());
+            true
         }
         Err(e) => {
-            error!("Compilation failed: {}", e.to_string());
+            error!("Could not update the lockfile: {}", e);
+            false
         }
     }
 }
 
-/// Derives the output `.wat` path that sits next to the given source file.
-/// Returns `None` if the path has no file stem or contains non-UTF-8 components.
-fn get_path_from_file_path(file_path: &str) -> Option<String> {
+/// Derives the output path that sits next to the given source file, with the extension of the
+/// artifact `emit` writes. Returns `None` if the path has no file stem or contains non-UTF-8
+/// components.
+fn default_output_path(file_path: &str, emit: Emit) -> Option<String> {
+    let extension = match emit {
+        Emit::WatAndWasm | Emit::Wat => "wat",
+        Emit::Wasm => "wasm",
+        Emit::Mir => "mir",
+        Emit::Hir => "hir",
+    };
     let path = Path::new(file_path);
     let file_stem = path.file_stem()?.to_str()?;
     let parent = path.parent().unwrap_or_else(|| Path::new(""));
-    let result = parent.join(format!("{}.wat", file_stem));
+    let result = parent.join(format!("{}.{}", file_stem, extension));
     Some(result.to_str()?.to_string())
 }
+
+/// Parses a byte size with an optional binary `K`/`M`/`G` suffix (`512K`, `64M`, `1G`, `1048576`).
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (digits, scale) = match text.chars().last()?.to_ascii_uppercase() {
+        'K' => (&text[..text.len() - 1], 1u64 << 10),
+        'M' => (&text[..text.len() - 1], 1u64 << 20),
+        'G' => (&text[..text.len() - 1], 1u64 << 30),
+        _ => (text, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(scale)
+}
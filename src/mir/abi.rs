@@ -19,3 +19,27 @@ pub const TAG_ULONG: i32 = 10;
 pub const TAG_BYTE: i32 = 11;
 /// Structs/unions are assigned consecutive tags starting here, ordered by sorted type name.
 pub const TAG_STRUCT_BASE: i32 = 12;
+
+/// Size of a WebAssembly linear-memory page.
+pub const WASM_PAGE_SIZE: u64 = 65536;
+
+/// Exported mutable global capping how many pages the allocator may grow linear memory to. A host
+/// lowers it (before calling into the module) to bound the heap; the default is the full 4 GiB.
+pub const EXPORT_HEAP_MAX_PAGES: &str = "__dream_heap_max_pages";
+
+/// Exported global the allocator sets to the size of the request it could not satisfy just before
+/// trapping, so a host can turn that trap into an out-of-memory error. Zero until then.
+pub const EXPORT_OOM_REQUEST: &str = "__dream_oom_request";
+
+/// Exported mutable global holding the `string[]` a host passes to `main(args: string[])`: the host
+/// allocates the array (through the exported `malloc`) and stores its pointer here before calling
+/// `main`. Zero means no arguments. Only present when `main` takes arguments.
+pub const EXPORT_MAIN_ARGS: &str = "__dream_main_args";
+
+/// Prefix of the export a test build adds for each `@test` function (`__dream_test_<name>`), in
+/// declaration order. Each takes and returns nothing; a failing test traps.
+pub const EXPORT_TEST_PREFIX: &str = "__dream_test_";
+
+/// Exported global a failed runtime check (array/string bounds, null field access) sets to a
+/// pointer to its message string just before trapping. Zero until then.
+pub const EXPORT_PANIC_MESSAGE: &str = "__dream_panic_message";
@@ -5,8 +5,8 @@
 //! `await` points). The cooperative scheduler runtime lives in `mir/runtime/async.wat`.
 
 use super::emit::{
-    emit_expr_to_scratch, emit_straight_line_segment, func_symbol, poll_symbol, release_call_for_ty,
-    wasm_ty_of,
+    emit_expr_to_scratch, emit_straight_line_segment, func_symbol, main_args_prologue, poll_symbol,
+    release_call_for_ty, wasm_ty_of,
 };
 use super::lower::{lower_async_segment, lower_expr_value};
 use super::MirFunction;
@@ -215,6 +215,26 @@ fn save_locals_to_frame(out: &mut String, slots: &AsyncSlots) {
     }
 }
 
+/// The MIR the backend lowers an async function's body to: one function per poll segment plus one
+/// per awaited operand. Lets module-level tables (e.g. interned strings) see what those bodies use.
+pub(crate) fn segment_mirs(
+    func: &MirFunction,
+    interner: &TypeInterner,
+    runtime_checks: bool,
+) -> Vec<MirFunction> {
+    let Some(hir) = func.hir_fn.as_ref() else { return Vec::new() };
+    let mut out = Vec::new();
+    for seg in split_async_segments(&hir.body) {
+        if !seg.plain.is_empty() {
+            out.push(lower_async_segment(hir, &seg.plain, interner, runtime_checks));
+        }
+        if let SegmentEnd::Suspend(child) = &seg.end {
+            out.push(lower_expr_value(hir, child, interner, runtime_checks).0);
+        }
+    }
+    out
+}
+
 /// Emits the constructor + poll WAT for one async function.
 #[allow(clippy::too_many_arguments)]
 pub fn emit_async_function(
@@ -226,6 +246,7 @@ pub fn emit_async_function(
     tags: &HashMap<TypeId, i32>,
     ftable: &HashMap<(crate::types::DefId, Vec<TypeId>), usize>,
     poll_idx: usize,
+    runtime_checks: bool,
 ) -> String {
     let hir = func.hir_fn.as_ref().expect("async function missing hir_fn snapshot");
     let slots = async_slots(func, interner);
@@ -236,10 +257,12 @@ pub fn emit_async_function(
     let mut max_locals = func.locals.len();
     for seg in &segments {
         if !seg.plain.is_empty() {
-            max_locals = max_locals.max(lower_async_segment(hir, &seg.plain, interner).locals.len());
+            max_locals = max_locals
+                .max(lower_async_segment(hir, &seg.plain, interner, runtime_checks).locals.len());
         }
         if let SegmentEnd::Suspend(child) = &seg.end {
-            max_locals = max_locals.max(lower_expr_value(hir, child, interner).0.locals.len());
+            max_locals = max_locals
+                .max(lower_expr_value(hir, child, interner, runtime_checks).0.locals.len());
         }
     }
     let mut out = String::new();
@@ -326,7 +349,7 @@ pub fn emit_async_function(
         }
 
         if !seg.plain.is_empty() {
-            let seg_mir = lower_async_segment(hir, &seg.plain, interner);
+            let seg_mir = lower_async_segment(hir, &seg.plain, interner, runtime_checks);
             out.push_str(&emit_straight_line_segment(
                 &seg_mir, interner, symbols, layouts, strings, tags, ftable, func,
             ));
@@ -336,6 +359,7 @@ pub fn emit_async_function(
             SegmentEnd::Suspend(child) => {
                 out.push_str(&emit_expr_to_scratch(
                     hir, child, interner, symbols, layouts, strings, tags, ftable, func,
+                    runtime_checks,
                 ));
                 let next = seg_idx + 1;
                 out.push_str(" local.get $self\n local.get $__scratch\n");
@@ -358,10 +382,8 @@ pub fn emit_async_function(
 pub fn emit_async_main_wrapper(entry_sym: &str, has_args_param: bool) -> String {
     let mut out = String::from("(func (export \"main\")");
     if has_args_param {
-        out.push_str("\n (local $args i32)");
-        out.push_str("\n i32.const 4");
-        out.push_str(&format!("\n i32.const {}", super::abi::TAG_ARRAY));
-        out.push_str("\n call $malloc\n local.set $args\n local.get $args\n i32.const 0\n i32.store\n local.get $args");
+        out.push_str("\n (local $args i32)\n");
+        out.push_str(main_args_prologue().trim_end());
     }
     let _ = writeln!(out, "\n call ${entry_sym}\n drop\n call $dream_run_loop\n)\n");
     out
@@ -16,6 +16,12 @@ pub struct FunctionBuilder {
     locals: Vec<LocalDecl>,
     blocks: Vec<BasicBlock>,
     current: BlockId,
+    /// The source line of the code being built, re-marked at the top of each block switched to so
+    /// every block is attributed to a line regardless of where the backend places it.
+    line: Option<u32>,
+    /// The unwind target stamped on each new block: the landing block of the innermost enclosing
+    /// `try` while one is being built.
+    unwind: Option<BlockId>,
 }
 
 impl FunctionBuilder {
@@ -32,6 +38,8 @@ impl FunctionBuilder {
             locals: Vec::new(),
             blocks: vec![BasicBlock::default()],
             current: BlockId(0),
+            line: None,
+            unwind: None,
         }
     }
 
@@ -64,19 +72,60 @@ impl FunctionBuilder {
         l
     }
 
-    /// Allocates a fresh, empty block (terminator defaults to `Unreachable` until set).
+    /// Allocates a fresh, empty block (terminator defaults to `Unreachable` until set), unwinding
+    /// to the current unwind target.
     pub fn new_block(&mut self) -> BlockId {
         let id = BlockId(self.blocks.len() as u32);
-        self.blocks.push(BasicBlock::default());
+        self.blocks.push(BasicBlock {
+            unwind: self.unwind,
+            ..BasicBlock::default()
+        });
         id
     }
 
+    /// The unwind target blocks allocated from here on get (see [`BasicBlock::unwind`]).
+    pub fn unwind(&self) -> Option<BlockId> {
+        self.unwind
+    }
+
+    /// Sets the unwind target for blocks allocated from here on; existing blocks keep theirs.
+    pub fn set_unwind(&mut self, unwind: Option<BlockId>) {
+        self.unwind = unwind;
+    }
+
     pub fn current(&self) -> BlockId {
         self.current
     }
 
     pub fn switch_to(&mut self, block: BlockId) {
         self.current = block;
+        if let Some(line) = self.line {
+            if self.blocks[block.0 as usize].stmts.is_empty() {
+                self.push(Statement::Line(line));
+            }
+        }
+    }
+
+    /// The source line currently attributed to new code.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// Attributes the code built from here on to source `line`, marking it in the current block. A
+    /// marker directly after another replaces it.
+    pub fn set_line(&mut self, line: u32) {
+        self.line = Some(line);
+        let stmts = &mut self.blocks[self.current.0 as usize].stmts;
+        match stmts.last_mut() {
+            Some(Statement::Line(prev)) => *prev = line,
+            _ => stmts.push(Statement::Line(line)),
+        }
+    }
+
+    /// Restores the attributed line without marking it; the next block switched to picks it up.
+    /// Used to hand a compound statement's line back to the code after its nested bodies.
+    pub fn restore_line(&mut self, line: Option<u32>) {
+        self.line = line;
     }
 
     /// Appends a statement to the current block.
@@ -107,6 +156,10 @@ impl FunctionBuilder {
         self.locals[local.0 as usize].ty
     }
 
+    pub fn local_name(&self, local: Local) -> Option<&str> {
+        self.locals[local.0 as usize].name.as_deref()
+    }
+
     pub fn finish(self) -> MirFunction {
         MirFunction {
             def: self.def,
@@ -119,6 +172,7 @@ impl FunctionBuilder {
             blocks: self.blocks,
             entry: BlockId(0),
             hir_fn: None,
+            source: None,
         }
     }
 }
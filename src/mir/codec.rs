@@ -0,0 +1,185 @@
+//! The binary encoding of lowered function bodies, which the build cache keeps so a function whose
+//! HIR did not change is not lowered again (see [`crate::driver::cache`]). Built on the AST codec
+//! in [`crate::syntax::codec`].
+//!
+//! Only a body's own data is encoded. [`MirFunction::hir_fn`], which an async function keeps for the
+//! coroutine transform, is not: an encoded function always decodes without it, so async functions
+//! are never cached.
+
+use super::{
+    BasicBlock, BlockId, Callee, Const, Global, Local, LocalDecl, MirFunction, Operand, Place,
+    RuntimeCheck, Rvalue, Statement, Terminator,
+};
+use crate::hir::{BinOp, SourceInfo, UnOp};
+use crate::syntax::codec::{Decode, Encode, Reader};
+use crate::syntax::{codec_enum, codec_struct};
+use crate::types::{DefId, TypeId};
+
+/// The `u32` newtypes are written as their index.
+macro_rules! codec_index {
+    ($($index:ident),*) => {
+        $(
+            impl Encode for $index {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    self.0.encode(out);
+                }
+            }
+
+            impl<'a> Decode<'a> for $index {
+                fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+                    Some($index(u32::decode(r)?))
+                }
+            }
+        )*
+    };
+}
+
+codec_index!(BlockId, Local, Global, DefId, TypeId);
+
+impl Encode for MirFunction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        debug_assert!(self.hir_fn.is_none(), "an async function's HIR is not encoded");
+        self.def.encode(out);
+        self.instance.encode(out);
+        self.name.encode(out);
+        self.params.encode(out);
+        self.ret.encode(out);
+        self.locals.encode(out);
+        self.blocks.encode(out);
+        self.entry.encode(out);
+        self.is_async.encode(out);
+        self.source.encode(out);
+    }
+}
+
+impl<'a> Decode<'a> for MirFunction {
+    fn decode(r: &mut Reader<'_, 'a>) -> Option<Self> {
+        Some(MirFunction {
+            def: Decode::decode(r)?,
+            instance: Decode::decode(r)?,
+            name: Decode::decode(r)?,
+            params: Decode::decode(r)?,
+            ret: Decode::decode(r)?,
+            locals: Decode::decode(r)?,
+            blocks: Decode::decode(r)?,
+            entry: Decode::decode(r)?,
+            is_async: Decode::decode(r)?,
+            hir_fn: None,
+            source: Decode::decode(r)?,
+        })
+    }
+}
+
+codec_struct!(SourceInfo { name, file, line });
+codec_struct!(LocalDecl { ty, name });
+codec_struct!(BasicBlock {
+    stmts,
+    terminator,
+    unwind
+});
+codec_struct!(Callee { def, args, ret });
+
+codec_enum!(Statement {
+    0 => Assign(place, value),
+    1 => Retain(value),
+    2 => Release(value),
+    3 => Call { callee, args },
+    4 => IndirectCall { target, args },
+    5 => InterfaceCall { receiver, iface_id, method_slot, sig, args },
+    6 => Print { arg, ty, newline },
+    7 => Check(check),
+    8 => Line(line),
+    9 => Nop,
+});
+
+codec_enum!(RuntimeCheck {
+    0 => Bounds { base, index },
+    1 => StrBounds { base, index },
+    2 => NotNull { base, field },
+    3 => Downcast { value, from, target },
+});
+
+codec_enum!(Terminator {
+    0 => Goto(target),
+    1 => If { cond, then_blk, else_blk },
+    2 => Switch { value, targets, default },
+    3 => Return(value),
+    4 => AsyncComplete(value),
+    5 => Throw(value),
+    6 => Unreachable,
+});
+
+codec_enum!(Place {
+    0 => Local(local),
+    1 => Global(global),
+    2 => Field { base, field },
+    3 => Index { base, index },
+});
+
+codec_enum!(Operand {
+    0 => Copy(place),
+    1 => Const(value),
+});
+
+codec_enum!(Const {
+    0 => Int(value),
+    1 => Long(value),
+    2 => Float(value),
+    3 => F32(value),
+    4 => Bool(value),
+    5 => Char(value),
+    6 => Str(value),
+    7 => Null,
+});
+
+codec_enum!(Rvalue {
+    0 => Use(value),
+    1 => Binary(op, lhs, rhs),
+    2 => Unary(op, value),
+    3 => StrLen(value),
+    4 => CharAt(string, index),
+    5 => ArrayNew { elem_ty, len },
+    6 => HashCode(value),
+    7 => ToString(value),
+    8 => Concat(lhs, rhs),
+    9 => EnumName { value, arms },
+    10 => Call { callee, args },
+    11 => IndirectCall { target, args },
+    12 => InterfaceCall { receiver, iface_id, method_slot, sig, args, ret },
+    13 => Closure { func, env, captures },
+    14 => New { def, ty, ctor, args },
+    15 => UnionNew { def, ty, variant, args },
+    16 => ArrayLit { elem_ty, elems },
+    17 => ArrayLen(value),
+    18 => Cast(value, from, to),
+    19 => Discriminant(value),
+    20 => UnionField { base, ty, variant, field },
+    21 => IsType(value, ty),
+    22 => Exception,
+});
+
+codec_enum!(BinOp {
+    0 => Add,
+    1 => Sub,
+    2 => Mul,
+    3 => Div,
+    4 => Rem,
+    5 => Eq,
+    6 => Ne,
+    7 => Lt,
+    8 => Le,
+    9 => Gt,
+    10 => Ge,
+    11 => And,
+    12 => Or,
+    13 => BitAnd,
+    14 => BitOr,
+    15 => BitXor,
+    16 => Shl,
+    17 => Shr,
+});
+
+codec_enum!(UnOp {
+    0 => Neg,
+    1 => Not,
+});
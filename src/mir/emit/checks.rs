@@ -0,0 +1,70 @@
+use super::*;
+use crate::mir::RuntimeCheck;
+
+/// The constant message pieces `$panic_index`/`$panic_array_index` splice around the dynamic parts,
+/// keyed by their placeholder in [`RUNTIME_PANIC`]. Interned only into modules lowered with checks.
+pub(super) const PANIC_STR_CONSTS: [(&str, &str); 4] = [
+    ("index_out_of_bounds", "index out of bounds: index "),
+    ("out_of_range_for", " is out of range for "),
+    ("of_length", " of length "),
+    ("null_index", "null dereference: indexing a null "),
+];
+
+/// The runtime-check failure layer with its message placeholders resolved from the interned string
+/// table, reporting each failure on stderr under WASI. Depends on the string runtime and
+/// `$int_to_string`.
+pub(super) fn panic_runtime(strings: &IndexMap<String, u32>, wasi: bool) -> String {
+    let report = if wasi { "local.get $msg\n    call $wasi_report" } else { "" };
+    let mut out = RUNTIME_PANIC.replace(";;@PANIC_REPORT@", report);
+    for (key, text) in PANIC_STR_CONSTS {
+        out = out.replace(&format!("{{{}}}", key), &strings[text].to_string());
+    }
+    out
+}
+
+/// The interned string a check passes to the failure path: the indexed type's name for a bounds
+/// check, or the whole message for a null or downcast check.
+pub(super) fn check_string(
+    func: &MirFunction,
+    check: &RuntimeCheck,
+    interner: &TypeInterner,
+    layouts: &LayoutTable,
+) -> String {
+    match check {
+        RuntimeCheck::Bounds { base, .. } => type_label(interner, layouts, func.local_ty(*base)),
+        RuntimeCheck::StrBounds { .. } => "string".to_string(),
+        RuntimeCheck::NotNull { base, field } => {
+            let ty = interner.strip_nullable(func.local_ty(*base));
+            let (name, field) = match layouts.get(ty) {
+                Some(l) => (
+                    l.name.as_str(),
+                    l.fields.get(*field).map(|f| f.name.as_str()).unwrap_or("?"),
+                ),
+                None => ("object", "?"),
+            };
+            format!("null dereference: field '{}' of a null {}", field, name)
+        }
+        RuntimeCheck::Downcast { from, target, .. } => format!(
+            "invalid cast from {} to {}",
+            type_label(interner, layouts, *from),
+            type_label(interner, layouts, *target)
+        ),
+    }
+}
+
+/// A type's source spelling for runtime messages (`int[]`, `Point`, `string?`). Nominal types take
+/// their layout's display name; types without one fall back to a generic description.
+fn type_label(interner: &TypeInterner, layouts: &LayoutTable, ty: TypeId) -> String {
+    match interner.kind(ty) {
+        TyKind::Prim(p) => p.name().to_string(),
+        TyKind::Array(e) => format!("{}[]", type_label(interner, layouts, *e)),
+        TyKind::Nullable(inner) => format!("{}?", type_label(interner, layouts, *inner)),
+        TyKind::Struct(..) => layouts.get(ty).map(|l| l.name.clone()).unwrap_or_else(|| "object".into()),
+        TyKind::Union(..) => {
+            layouts.unions.get(&ty).map(|l| l.name.clone()).unwrap_or_else(|| "object".into())
+        }
+        TyKind::Func(..) => "function".to_string(),
+        TyKind::Enum(_) => "enum".to_string(),
+        _ => "object".to_string(),
+    }
+}
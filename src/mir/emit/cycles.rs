@@ -0,0 +1,187 @@
+use super::*;
+use std::collections::HashSet;
+
+/// Initial roots buffer capacity (entries); filling it doubles the buffer and schedules a collection.
+const CC_ROOTS_CAPACITY: u32 = 4096;
+
+/// Allocations between automatic collections of a non-empty roots buffer.
+const CC_ALLOC_THRESHOLD: u32 = 10_000;
+
+/// The `$cc_children` op that frees garbage (see `runtime/cycles.wat`); under it, leaf children are
+/// released through their statically typed release instead of being traced.
+const CC_COLLECT: i32 = 3;
+
+/// Refcount-word bit marking an array whose elements are traceable references.
+pub(super) const RC_REF_ARRAY: u32 = 0x8000_0000;
+
+/// The cycle collector runtime, with its capacity/threshold/tag placeholders resolved.
+pub(super) fn cycles_runtime() -> String {
+    RUNTIME_CYCLES
+        .replace("{ROOTS_CAPACITY}", &CC_ROOTS_CAPACITY.to_string())
+        .replace("{ALLOC_THRESHOLD}", &CC_ALLOC_THRESHOLD.to_string())
+}
+
+/// The struct/union types whose instances can sit on a reference cycle: those with a field that can
+/// (transitively) point back at an instance. `object`, interface and function values may hold
+/// anything, so they count as traceable; a type reaching itself through its own fields is traceable
+/// too, and so is a class with a traceable subclass. Everything else is acyclic and the collector
+/// treats its instances as leaves.
+pub(super) fn traced_types(mir: &crate::mir::Mir, interner: &TypeInterner) -> HashSet<TypeId> {
+    let mut memo: HashMap<TypeId, bool> = HashMap::new();
+    let nominal = mir.layouts.structs.keys().chain(mir.layouts.unions.keys());
+    nominal.copied().filter(|ty| traceable(interner, &mir.layouts, *ty, &mut memo)).collect()
+}
+
+/// Whether a value of static type `ty` may reference (directly or through a container) a traced
+/// object. A type currently being visited counts as traceable, which is what makes self-reaching
+/// types traceable.
+fn traceable(
+    interner: &TypeInterner,
+    layouts: &LayoutTable,
+    ty: TypeId,
+    memo: &mut HashMap<TypeId, bool>,
+) -> bool {
+    let ty = interner.strip_nullable(ty);
+    if let Some(&known) = memo.get(&ty) {
+        return known;
+    }
+    match interner.kind(ty) {
+        TyKind::Object | TyKind::Interface(..) | TyKind::Func(..) => true,
+        TyKind::Array(e) => traceable(interner, layouts, *e, memo),
+        TyKind::Struct(..) | TyKind::Union(..) => {
+            // A class value may be any of its subclasses, whose extra fields count too.
+            let fields: Vec<TypeId> = if let Some(l) = layouts.structs.get(&ty) {
+                l.fields.iter().map(|f| f.ty).chain(layouts.subclasses(ty)).collect()
+            } else if let Some(l) = layouts.unions.get(&ty) {
+                l.variants.iter().flat_map(|v| v.fields.iter().map(|f| f.ty)).collect()
+            } else {
+                return true;
+            };
+            memo.insert(ty, true);
+            let on = fields.into_iter().any(|f| traceable(interner, layouts, f, memo));
+            memo.insert(ty, on);
+            on
+        }
+        _ => false,
+    }
+}
+
+/// Whether a field of static type `ty` is an edge the collector follows, i.e. whether it may point
+/// at a traced object (see [`traced_types`]).
+pub(super) fn traced_edge(interner: &TypeInterner, mir: &crate::mir::Mir, traced: &HashSet<TypeId>, ty: TypeId) -> bool {
+    let ty = interner.strip_nullable(ty);
+    match interner.kind(ty) {
+        TyKind::Object | TyKind::Interface(..) | TyKind::Func(..) => true,
+        TyKind::Array(e) => traced_edge(interner, mir, traced, *e),
+        TyKind::Struct(..) | TyKind::Union(..) => {
+            traced.contains(&ty) || (!mir.layouts.structs.contains_key(&ty) && !mir.layouts.unions.contains_key(&ty))
+        }
+        _ => false,
+    }
+}
+
+/// Emits the per-module half of the cycle collector: `$cc_traced(ptr)`, which tells traced objects
+/// (instances of [`traced_types`], and flagged arrays) from leaves, and `$cc_children(ptr, op)`,
+/// which visits a traced object's reference fields by tag. Structs and union variants come from the
+/// layout table; arrays visit every element.
+pub(super) fn emit_cycle_funcs(
+    out: &mut String,
+    mir: &crate::mir::Mir,
+    interner: &TypeInterner,
+    tags: &HashMap<TypeId, i32>,
+) {
+    let traced = traced_types(mir, interner);
+    let traced_tags: Vec<(TypeId, i32)> = mir
+        .layouts
+        .structs
+        .keys()
+        .chain(mir.layouts.unions.keys())
+        .filter(|ty| traced.contains(ty))
+        .filter_map(|ty| tags.get(ty).map(|t| (*ty, *t)))
+        .collect();
+
+    out.push_str("(func $cc_traced (param $ptr i32) (result i32)\n  (local $tag i32)\n");
+    out.push_str("  (local.get $ptr) (call $object_tag) (local.set $tag)\n");
+    let _ = writeln!(
+        out,
+        "  (local.get $tag) (i32.const {}) (i32.eq) (if (then (local.get $ptr) (i32.const 4) (i32.sub) (i32.load) (i32.const {:#x}) (i32.and) (i32.const 0) (i32.ne) (return)))",
+        ARRAY_TAG, RC_REF_ARRAY
+    );
+    for (_, tag) in &traced_tags {
+        let _ = writeln!(out, "  (local.get $tag) (i32.const {}) (i32.eq) (if (then (i32.const 1) (return)))", tag);
+    }
+    out.push_str("  (i32.const 0)\n)\n");
+
+    out.push_str("(func $cc_children (param $ptr i32) (param $op i32)\n");
+    out.push_str("  (local $tag i32) (local $d i32) (local $len i32) (local $i i32) (local $elem i32)\n");
+    out.push_str("  (local.get $ptr) (call $object_tag) (local.set $tag)\n");
+    out.push_str("  (local.get $tag) (i32.const ");
+    let _ = writeln!(out, "{}) (i32.eq) (if (then", ARRAY_TAG);
+    out.push_str("    (local.get $ptr) (i32.load) (local.set $len)\n");
+    out.push_str("    (block $done (loop $scan\n");
+    out.push_str("      (local.get $i) (local.get $len) (i32.ge_s) (br_if $done)\n");
+    out.push_str("      (local.get $ptr) (i32.const 4) (i32.add) (local.get $i) (i32.const 4) (i32.mul) (i32.add) (i32.load) (local.set $elem)\n");
+    out.push_str("      (local.get $elem) (local.get $op) (call $cc_edge) (if (then (local.get $elem) (call $release_object)))\n");
+    out.push_str("      (local.get $i) (i32.const 1) (i32.add) (local.set $i) (br $scan)))\n");
+    out.push_str("    (return)))\n");
+    for (ty, tag) in &traced_tags {
+        let _ = writeln!(out, "  (local.get $tag) (i32.const {}) (i32.eq) (if (then", tag);
+        if let Some(layout) = mir.layouts.structs.get(ty) {
+            for f in &layout.fields {
+                emit_field_edge(out, mir, interner, &traced, f, "    ");
+            }
+        } else if let Some(layout) = mir.layouts.unions.get(ty) {
+            out.push_str("    (local.get $ptr) (i32.load) (local.set $d)\n");
+            for v in &layout.variants {
+                if !v.fields.iter().any(|f| interner.is_reference(f.ty)) {
+                    continue;
+                }
+                let _ = writeln!(out, "    (local.get $d) (i32.const {}) (i32.eq) (if (then", v.discriminant);
+                for f in &v.fields {
+                    emit_field_edge(out, mir, interner, &traced, f, "      ");
+                }
+                out.push_str("    ))\n");
+            }
+        }
+        out.push_str("    (return)))\n");
+    }
+    out.push_str(")\n");
+}
+
+/// Emits one reference field's edge for `$cc_children`: a traced edge goes through `$cc_edge`
+/// (`$cc_edge_array` for an array of traceable references, which flags the array), and a leaf
+/// field is only released, under the collect op.
+fn emit_field_edge(
+    out: &mut String,
+    mir: &crate::mir::Mir,
+    interner: &TypeInterner,
+    traced: &HashSet<TypeId>,
+    f: &crate::hir::FieldLayout,
+    indent: &str,
+) {
+    if !interner.is_reference(f.ty) {
+        return;
+    }
+    let load = if f.offset > 0 {
+        format!("(local.get $ptr) (i32.const {}) (i32.add) (i32.load)", f.offset)
+    } else {
+        "(local.get $ptr) (i32.load)".to_string()
+    };
+    let release = release_call(interner, &mir.layouts, f.ty);
+    if traced_edge(interner, mir, traced, f.ty) {
+        let edge = if matches!(interner.kind(interner.strip_nullable(f.ty)), TyKind::Array(_)) {
+            "$cc_edge_array"
+        } else {
+            "$cc_edge"
+        };
+        let _ = writeln!(
+            out,
+            "{indent}{load} (local.get $op) (call {edge}) (if (then {load} (call {release})))"
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "{indent}(local.get $op) (i32.const {CC_COLLECT}) (i32.eq) (if (then {load} (call {release})))"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::syntax::nodes::types::is_tuple_type_name;
 
 /// Emits one function as WAT (calls fall back to `$def{N}`, and field/index access has no layout, so
 /// this is for layout-free unit tests; the pipeline uses [`emit_program`]/[`emit_module`]).
@@ -85,8 +86,9 @@ pub(crate) fn emit_expr_to_scratch(
     tags: &HashMap<TypeId, i32>,
     ftable: &HashMap<(DefId, Vec<TypeId>), usize>,
     parent: &MirFunction,
+    runtime_checks: bool,
 ) -> String {
-    let (mir, temp) = crate::mir::lower::lower_expr_value(hir, expr, interner);
+    let (mir, temp) = crate::mir::lower::lower_expr_value(hir, expr, interner, runtime_checks);
     let mut out = emit_straight_line_segment(
         &mir, interner, symbols, layouts, strings, tags, ftable, parent,
     );
@@ -157,6 +159,10 @@ impl Emitter<'_> {
         // it can be released *after* the new value is stored (deferred release keeps a self-referential
         // `obj.f = g(obj.f)` sound).
         self.line("  (local $__rel i32)");
+        // The exception caught by a protected block, read by its landing block's `Rvalue::Exception`.
+        if self.func.blocks.iter().any(|b| b.unwind.is_some()) {
+            self.line("  (local $__exn i32)");
+        }
 
         self.emit_dispatch();
         self.line(")");
@@ -202,8 +208,27 @@ impl Emitter<'_> {
 
     fn emit_block(&mut self, id: crate::mir::BlockId) {
         let block = self.func.block(id);
-        for stmt in &block.stmts {
-            self.emit_stmt(stmt);
+        match block.unwind {
+            // A protected block runs its statements in a `try_table`; an exception leaves it with the
+            // thrown pointer, which is stashed in `$__exn` before jumping to the landing block.
+            Some(unwind) => {
+                self.line("     (block $__ok");
+                self.line("      (block $__unw (result i32)");
+                self.line(&format!("       (try_table (catch {} $__unw)", EXCEPTION_TAG));
+                for stmt in &block.stmts {
+                    self.emit_stmt(stmt);
+                }
+                self.line("       )");
+                self.line("       (br $__ok))");
+                self.line("      (local.set $__exn)");
+                self.goto(unwind);
+                self.line("     )");
+            }
+            None => {
+                for stmt in &block.stmts {
+                    self.emit_stmt(stmt);
+                }
+            }
         }
         self.emit_terminator(&block.terminator);
     }
@@ -235,6 +260,11 @@ impl Emitter<'_> {
                     self.line("     (drop)");
                 }
             }
+            Statement::IndirectCall { target, args } => {
+                if self.emit_indirect_call(target, args) {
+                    self.line("     (drop)");
+                }
+            }
             Statement::InterfaceCall { receiver, iface_id, method_slot, sig, args } => {
                 self.emit_interface_call(receiver, *iface_id, *method_slot, *sig, args);
                 let ret = match self.interner.kind(*sig) {
@@ -293,10 +323,83 @@ impl Emitter<'_> {
                     self.line("     (call $print_char)");
                 }
             }
+            Statement::Check(check) => self.emit_check(check),
+            // A line marker: stack-neutral, and its `global.get` of the marker global stays in the
+            // code section for `srcmap` to find and strip once the module is assembled.
+            Statement::Line(line) => self.line(&format!(
+                "     (drop (global.get ${})) (drop (i32.const {}))",
+                crate::mir::srcmap::MARKER_GLOBAL,
+                line
+            )),
             Statement::Nop => {}
         }
     }
 
+    /// A runtime check: falls through when it holds, otherwise calls the `$panic*` runtime, which
+    /// records a message for the host and traps.
+    fn emit_check(&mut self, check: &crate::mir::RuntimeCheck) {
+        use crate::mir::RuntimeCheck;
+        let what = self.string_addr(&check_string(self.func, check, self.interner, self.layouts));
+        match check {
+            RuntimeCheck::Bounds { base, index } => {
+                // Unsigned compare, so a negative index fails too. A null array reads the (always
+                // zero) word at address 0 and is selected to length 0, so every index fails.
+                self.emit_operand(index);
+                self.line(&format!("     (local.get ${})", base.0));
+                self.line("     (i32.load)");
+                self.line("     (i32.const 0)");
+                self.line(&format!("     (local.get ${})", base.0));
+                self.line("     (select)");
+                self.line("     (i32.ge_u)");
+                self.line("     (if (then");
+                self.emit_operand(index);
+                self.line(&format!("     (local.get ${})", base.0));
+                self.line(&format!("     (i32.const {})", what));
+                self.line("     (call $panic_array_index)))");
+            }
+            RuntimeCheck::StrBounds { base, index } => {
+                self.emit_operand(index);
+                self.emit_operand(base);
+                self.line("     (call $strlen)");
+                self.line("     (i32.ge_u)");
+                self.line("     (if (then");
+                self.emit_operand(index);
+                self.emit_operand(base);
+                self.line("     (call $strlen)");
+                self.line(&format!("     (i32.const {})", what));
+                self.line("     (call $panic_index)))");
+            }
+            RuntimeCheck::NotNull { base, .. } => {
+                self.line(&format!("     (local.get ${})", base.0));
+                self.line("     (i32.eqz)");
+                self.line("     (if (then");
+                self.line(&format!("     (i32.const {})", what));
+                self.line("     (call $panic)))");
+            }
+            RuntimeCheck::Downcast { value, from, target } => {
+                // An upcast, or a cast to a type without a class layout (an interface), is not
+                // checked. Otherwise a null passes (tag 0), as does any tag `is` accepts; `$__obj`
+                // holds the tag, as no allocation is in flight.
+                let (from, target) =
+                    (self.interner.strip_nullable(*from), self.interner.strip_nullable(*target));
+                if self.layouts.get(target).is_none() || self.layouts.is_subclass_of(from, target) {
+                    return;
+                }
+                self.emit_operand(value);
+                self.line("     (call $object_tag)");
+                self.line("     (local.tee $__obj)");
+                self.line("     (i32.const 0) (i32.ne)");
+                for tag in self.instance_tags(target) {
+                    self.line(&format!("     (local.get $__obj) (i32.const {}) (i32.ne)", tag));
+                    self.line("     (i32.and)");
+                }
+                self.line("     (if (then");
+                self.line(&format!("     (i32.const {})", what));
+                self.line("     (call $panic)))");
+            }
+        }
+    }
+
     fn emit_assign(&mut self, place: &Place, rvalue: &Rvalue) {
         match place {
             Place::Local(l) => {
@@ -504,9 +607,23 @@ impl Emitter<'_> {
                         self.interner.kind(self.interner.strip_nullable(ty)),
                         TyKind::Prim(PrimTy::String)
                     );
+                // Tuples are values: `==` compares them element-wise via the generated `$<Tuple>_equals`.
+                let tuple_eq = if matches!(op, BinOp::Eq | BinOp::Ne) {
+                    self.layouts
+                        .get(self.interner.strip_nullable(ty))
+                        .filter(|l| is_tuple_type_name(&l.name))
+                        .map(|l| format!("${}_equals", l.name))
+                } else {
+                    None
+                };
                 self.emit_operand(a);
                 self.emit_operand(b);
-                if str_eq {
+                if let Some(equals) = tuple_eq {
+                    self.line(&format!("     (call {})", equals));
+                    if matches!(op, BinOp::Ne) {
+                        self.line("     (i32.eqz)");
+                    }
+                } else if str_eq {
                     self.line("     (call $string_eq)");
                     if matches!(op, BinOp::Ne) {
                         self.line("     (i32.eqz)");
@@ -545,28 +662,38 @@ impl Emitter<'_> {
                 }
             }
             Rvalue::IndirectCall { target, args } => {
-                for a in args {
-                    self.emit_operand(a);
-                }
-                self.emit_operand(target);
-                // The table index (target) is on top of the stack; dispatch through `$__ft` with the
-                // signature derived from the target's function type.
-                let sig = func_sig(self.interner, self.operand_ty(target))
-                    .map(|(name, _, _)| name)
-                    .unwrap_or_else(|| "$sig___v".to_string());
-                self.line(&format!("     (call_indirect $__ft (type {}))", sig));
+                self.emit_indirect_call(target, args);
             }
             Rvalue::InterfaceCall { receiver, iface_id, method_slot, sig, args, .. } => {
                 self.emit_interface_call(receiver, *iface_id, *method_slot, *sig, args);
             }
-            Rvalue::FuncRef(callee) => {
-                // A function value is its slot index in the module function table.
+            Rvalue::Closure { func, env, captures } => {
+                // A closure block `[fn][captures...]` laid out as the `env` struct: `$malloc` returns
+                // it with refcount 1, the function's table slot goes in word 0, and each capture is
+                // stored (retained if borrowed) into the field after it.
                 let idx = self
                     .func_table
-                    .get(&(callee.def, callee.args.clone()))
+                    .get(&(func.def, func.args.clone()))
                     .copied()
                     .unwrap_or(0);
-                self.line(&format!("     (i32.const {}) ;; funcref def{}", idx, callee.def.0));
+                let info = self
+                    .layouts
+                    .get(*env)
+                    .map(|l| (l.size, l.fields.iter().map(|f| (f.offset, f.ty)).collect::<Vec<_>>()));
+                let (size, fields) = info.unwrap_or((4, Vec::new()));
+                self.line(&format!("     (i32.const {})", size));
+                self.line(&format!("     (i32.const {}) ;; tag", self.type_tag(*env, func.def)));
+                self.line("     (call $malloc)");
+                self.line("     (local.set $__obj)");
+                self.line("     (local.get $__obj)");
+                self.line(&format!("     (i32.const {}) ;; closure fn def{}", idx, func.def.0));
+                self.line("     (i32.store)");
+                for (i, c) in captures.iter().enumerate() {
+                    if let Some(&(off, fty)) = fields.get(i + 1) {
+                        self.store_at_obj(off, fty, c);
+                    }
+                }
+                self.line("     (local.get $__obj)");
             }
             Rvalue::New { def, ty, ctor, args } => {
                 // `$malloc(data_size, tag)` returns a data pointer with refcount 1.
@@ -598,11 +725,14 @@ impl Emitter<'_> {
                         self.line(&format!("     (call ${})", sym));
                         self.line("     (local.get $__obj)");
                     } else {
-                        // Implicit zero-arg default constructor: leave every field at its zero
-                        // value. Reused heap blocks are not zeroed, so zero each field explicitly.
-                        let _ = args;
-                        for &(off, fty) in &fields {
-                            self.zero_at_obj(off, fty);
+                        // No user constructor: a tuple literal's args fill the leading fields in
+                        // order (retained if borrowed); the rest, and every field of an implicit
+                        // default constructor, are zeroed since reused heap blocks are not.
+                        for (i, &(off, fty)) in fields.iter().enumerate() {
+                            match args.get(i) {
+                                Some(arg) => self.store_at_obj(off, fty, arg),
+                                None => self.zero_at_obj(off, fty),
+                            }
                         }
                         self.line("     (local.get $__obj)");
                     }
@@ -707,9 +837,18 @@ impl Emitter<'_> {
             }
             Rvalue::ToString(o) => {
                 self.emit_operand(o);
-                // A `string` is already its own `to_string`; every other type has a formatter.
-                if let Some(call) = value_to_string_call(self.interner, self.operand_ty(o)) {
-                    self.line(&format!("     (call {})", call));
+                // A `string` is already its own `to_string`; every other type has a formatter. The
+                // result is owned, so a shared string (the operand itself, or a string behind an
+                // `object`) gets its own reference.
+                match value_to_string_call(self.interner, self.operand_ty(o)) {
+                    Some(call) if call == "$object_to_string" => {
+                        self.line("     (call $object_to_string_owned)")
+                    }
+                    Some(call) => self.line(&format!("     (call {})", call)),
+                    None => {
+                        self.emit_operand(o);
+                        self.line("     (call $retain)");
+                    }
                 }
             }
             Rvalue::EnumName { value, arms } => {
@@ -751,12 +890,26 @@ impl Emitter<'_> {
                 self.line("     (call $strlen) ;; strings are NUL-terminated");
             }
             Rvalue::Cast(o, from, to) => self.emit_cast(o, *from, *to),
+            Rvalue::Exception => self.line("     (local.get $__exn)"),
             Rvalue::IsType(o, target) => {
                 self.emit_operand(o);
                 self.line("     (call $object_tag)");
-                let tag = runtime_tag_for(self.interner, self.tags, *target).unwrap_or(0);
-                self.line(&format!("     (i32.const {})", tag));
-                self.line("     (i32.eq)");
+                // An instance of any subclass is also an instance of `target`.
+                let subclasses = self.layouts.subclasses(self.interner.strip_nullable(*target));
+                if subclasses.is_empty() {
+                    let tag = runtime_tag_for(self.interner, self.tags, *target).unwrap_or(0);
+                    self.line(&format!("     (i32.const {})", tag));
+                    self.line("     (i32.eq)");
+                } else {
+                    // No allocation is in flight, so `$__obj` can hold the tag across the compares.
+                    self.line("     (local.set $__obj)");
+                    for (i, tag) in self.instance_tags(*target).into_iter().enumerate() {
+                        self.line(&format!("     (local.get $__obj) (i32.const {}) (i32.eq)", tag));
+                        if i > 0 {
+                            self.line("     (i32.or)");
+                        }
+                    }
+                }
             }
             Rvalue::Discriminant(o) => {
                 // The discriminant is the `i32` at offset 0 of the union block.
@@ -785,6 +938,16 @@ impl Emitter<'_> {
         }
     }
 
+    /// The runtime tags of `target` and all its subclasses: an object carrying any of them passes
+    /// `is target`.
+    fn instance_tags(&self, target: TypeId) -> Vec<i32> {
+        let subclasses = self.layouts.subclasses(self.interner.strip_nullable(target));
+        std::iter::once(target)
+            .chain(subclasses)
+            .filter_map(|ty| runtime_tag_for(self.interner, self.tags, ty))
+            .collect()
+    }
+
     fn emit_cast(&mut self, o: &Operand, from: TypeId, to: TypeId) {
         let from_prim = prim_of(self.interner, from);
         let to_prim = prim_of(self.interner, to);
@@ -835,6 +998,24 @@ impl Emitter<'_> {
         }
     }
 
+    /// Emits a call through the closure `target`, returning whether it leaves a result on the stack.
+    /// The closure block is the callee's hidden first argument, followed by the declared ones; its
+    /// first word (the table index) goes on top of the stack to dispatch through `$__ft` with the
+    /// env-taking signature derived from the target's function type.
+    fn emit_indirect_call(&mut self, target: &Operand, args: &[Operand]) -> bool {
+        self.emit_operand(target);
+        for a in args {
+            self.emit_operand(a);
+        }
+        self.emit_operand(target);
+        self.line("     (i32.load) ;; closure fn index");
+        let (sig, has_result) = closure_sig(self.interner, self.operand_ty(target))
+            .map(|(name, _, result)| (name, result.is_some()))
+            .unwrap_or_else(|| ("$sig_i32__v".to_string(), false));
+        self.line(&format!("     (call_indirect $__ft (type {}))", sig));
+        has_result
+    }
+
     /// Emits a dynamic interface method call. The receiver is pushed as argument 0, then the real
     /// arguments (widened to the interface method's declared parameter types), then control transfers
     /// to the per-`(interface, method)` dispatch trampoline which looks the concrete implementation up
@@ -929,6 +1110,10 @@ impl Emitter<'_> {
                 self.line("     (return)");
             }
             Terminator::Return(None) => self.line("     (return)"),
+            Terminator::Throw(o) => {
+                self.emit_operand(o);
+                self.line(&format!("     (throw {})", EXCEPTION_TAG));
+            }
             Terminator::Unreachable => self.line("     (unreachable)"),
             Terminator::AsyncComplete(_) => self.line("     (unreachable) ;; async in sync fn"),
         }
@@ -1025,9 +1210,10 @@ impl Emitter<'_> {
     /// fall-through/`unreachable` tail exits the dispatch loop so the segment's trailing code runs.
     fn emit_poll_cfg_terminator(&mut self, t: &Terminator) {
         match t {
-            Terminator::Goto(_) | Terminator::If { .. } | Terminator::Switch { .. } => {
-                self.emit_terminator(t)
-            }
+            Terminator::Goto(_)
+            | Terminator::If { .. }
+            | Terminator::Switch { .. }
+            | Terminator::Throw(_) => self.emit_terminator(t),
             Terminator::AsyncComplete(_) | Terminator::Return(Some(_)) => {
                 self.emit_poll_terminator(t)
             }
@@ -1106,8 +1292,8 @@ impl Emitter<'_> {
         match c {
             Const::Int(v) => self.line(&format!("     (i32.const {})", v)),
             Const::Long(v) => self.line(&format!("     (i64.const {})", v)),
-            Const::Float(v) => self.line(&format!("     (f64.const {})", v)),
-            Const::F32(v) => self.line(&format!("     (f32.const {})", v)),
+            Const::Float(v) => self.line(&format!("     (f64.const {})", wat_float(v.to_string()))),
+            Const::F32(v) => self.line(&format!("     (f32.const {})", wat_float(v.to_string()))),
             Const::Bool(v) => self.line(&format!("     (i32.const {})", *v as i32)),
             Const::Char(v) => self.line(&format!("     (i32.const {})", *v as u32)),
             Const::Null => self.line("     (i32.const 0)"),
@@ -1188,4 +1374,13 @@ impl Emitter<'_> {
             BinOp::Shr => format!("{}.shr{}", w, s),
         }
     }
-}
\ No newline at end of file
+}
+/// A float literal as WAT spells it. Rust already prints the infinities as `inf`/`-inf`, but NaN
+/// (which constant folding produces for `0.0d / 0.0d`) has to be lowercase.
+fn wat_float(text: String) -> String {
+    if text == "NaN" {
+        "nan".to_string()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+/// The exception tag every Dream `throw` raises; its payload is the thrown `object` pointer.
+pub(super) const EXCEPTION_TAG: &str = "$__dream_exception";
+
+/// The prefix of the message an exception that escapes `main` is reported with.
+pub(super) const UNCAUGHT_PREFIX: &str = "uncaught exception: ";
+
+/// True if any function throws or handles an exception. Only such modules declare the exception tag
+/// and guard `main`, so programs without exceptions do not depend on the exception-handling proposal.
+pub(super) fn module_uses_exceptions(mir: &crate::mir::Mir) -> bool {
+    mir.functions.iter().any(|f| {
+        f.blocks
+            .iter()
+            .any(|b| b.unwind.is_some() || matches!(b.terminator, Terminator::Throw(_)))
+    })
+}
+
+/// The exported entry for a module that uses exceptions: runs `body` (which calls the real `main`,
+/// using the extra `locals`) inside a handler, and turns an exception that escapes it into a
+/// runtime panic whose message is [`UNCAUGHT_PREFIX`] followed by the value's `to_string`. Under
+/// WASI, where no host reads that message, it is also written to stderr.
+pub(super) fn guarded_main(
+    export: &str,
+    locals: &str,
+    body: &str,
+    strings: &IndexMap<String, u32>,
+    wasi: bool,
+) -> String {
+    let report = if wasi {
+        "  local.tee $__exn\n  call $wasi_report\n  local.get $__exn\n"
+    } else {
+        ""
+    };
+    format!(
+        "(func (export \"{export}\")\n (local $__exn i32)\n{locals} (block $__ok\n  (block $__unw (result i32)\n   (try_table (catch {tag} $__unw)\n{body}   )\n   (br $__ok))\n  local.set $__exn\n  i32.const {prefix}\n  local.get $__exn\n  call $object_to_string\n  call $concat_strings\n{report}  global.set $panic_message\n  unreachable)\n)\n",
+        tag = EXCEPTION_TAG,
+        prefix = strings[UNCAUGHT_PREFIX],
+    )
+}
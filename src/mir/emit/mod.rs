@@ -38,7 +38,7 @@ const HEAP_HEADER_SIZE: u32 = 12;
 /// (block start + header), matching the runtime's null-terminated string ABI. The heap starts above.
 const STRING_BASE: u32 = 1024;
 
-/// Linear-memory size, in 64 KiB WASM pages.
+/// Initial linear-memory size, in 64 KiB WASM pages. The allocator grows memory past this on demand.
 const MEMORY_PAGES: u32 = 16;
 
 /// The fixed allocator runtime (`$malloc`/`$free`/`$retain`/`$release_generic`/`$object_tag`), the
@@ -54,16 +54,35 @@ const RUNTIME_STRINGS: &str = include_str!("../runtime/strings.wat");
 /// (`$int_to_string`/`$long_to_string`/`$byte_to_string`/…). `{TAG_*}` placeholders are substituted.
 const RUNTIME_OBJECT: &str = include_str!("../runtime/object.wat");
 
-/// The decimal `float`/`double` formatter (`$float_to_string`/`$double_to_string`). `{minus}` (the
-/// data pointer of the interned `"-"`) and `{TAG_STRING}` are substituted.
+/// The decimal `float`/`double` formatters (`$float_to_string`/`$double_to_string`, and the
+/// fixed-point `$format_fixed` behind `String.format`). `{minus}` (the data pointer of the
+/// interned `"-"`) and `{TAG_STRING}` are substituted.
 const RUNTIME_FORMAT: &str = include_str!("../runtime/format.wat");
 
+/// The runtime-check failure layer (`$panic`/`$panic_index`/`$panic_array_index`), present only in
+/// modules lowered with runtime checks. Its message-piece placeholders are resolved by
+/// [`panic_runtime`].
+const RUNTIME_PANIC: &str = include_str!("../runtime/panic.wat");
+
+/// The WASI host layer (`print_*` and the `Dream` host bridges over `wasi_snapshot_preview1`),
+/// present only in modules built for the WASI target. Its placeholders are resolved by
+/// [`wasi_runtime`].
+const RUNTIME_WASI: &str = include_str!("../runtime/wasi.wat");
+
+/// The opt-in cycle collector (`$collect_cycles` and its trial-deletion passes), present only in
+/// modules built with cycle collection. The per-module tracing it calls is generated by
+/// [`emit_cycle_funcs`]; its capacity placeholders are resolved by [`cycles_runtime`].
+const RUNTIME_CYCLES: &str = include_str!("../runtime/cycles.wat");
+
 /// String constants the `*_to_string` runtime references by address (`bool` renders to `"true"`/
 /// `"false"`; the `double` formatter prepends `"-"`). Interned into every module so the runtime is
 /// always self-contained.
 const RUNTIME_STR_CONSTS: [&str; 3] = ["true", "false", "-"];
 
+mod checks;
+mod cycles;
 mod emitter;
+mod exceptions;
 mod module;
 mod protocol;
 mod release;
@@ -71,20 +90,26 @@ mod runtime;
 mod strings;
 mod tables;
 mod types;
+mod wasi;
 
 // Flat internal re-exports so each submodule can `use super::*` and call sibling helpers
 // exactly as it did when this was one file. Kept private (not part of the crate API).
+use checks::*;
+use cycles::*;
 use emitter::*;
+use exceptions::*;
 use protocol::*;
 use release::*;
 use runtime::*;
 use strings::*;
 use tables::*;
 use types::*;
+use wasi::*;
 
 // The external API of the backend, at the historical `crate::mir::emit::…` paths.
 pub use emitter::emit_function;
-pub use module::{emit_module, emit_program};
+pub use module::{emit_module, emit_program, emit_wasi_module};
+pub(crate) use module::main_args_prologue;
 pub(crate) use emitter::{emit_expr_to_scratch, emit_straight_line_segment};
 pub(crate) use tables::{func_symbol, poll_symbol, release_call_for_ty};
 pub(crate) use types::wasm_ty_of;
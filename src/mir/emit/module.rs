@@ -5,7 +5,7 @@ use super::*;
 pub fn emit_program(mir: &crate::mir::Mir, interner: &TypeInterner) -> String {
     let symbols = symbol_table(mir);
     let sigs = signature_table(mir);
-    let strings = string_table(mir);
+    let strings = string_table(mir, interner);
     let tags = struct_tags(mir);
     let ftable = func_table(mir);
     let mut out = String::new();
@@ -22,16 +22,42 @@ pub fn emit_program(mir: &crate::mir::Mir, interner: &TypeInterner) -> String {
 /// under its source name. This is the self-contained unit the driver will hand to the WASM
 /// assembler once the runtime layers are wired in.
 pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc: bool) -> String {
+    emit_module_for(mir, interner, debug_alloc, false)
+}
+
+/// Emits a whole MIR program as a WASI command: a module whose only imports are
+/// `wasi_snapshot_preview1` functions (console output, the `Dream` host bridges, and runtime error
+/// reports go through the WASI layer instead of the `env`/`Dream` hosts) and whose entry is
+/// `_start`.
+pub fn emit_wasi_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc: bool) -> String {
+    emit_module_for(mir, interner, debug_alloc, true)
+}
+
+fn emit_module_for(
+    mir: &crate::mir::Mir,
+    interner: &TypeInterner,
+    debug_alloc: bool,
+    wasi: bool,
+) -> String {
     let symbols = symbol_table(mir);
     let sigs = signature_table(mir);
-    let strings = string_table(mir);
+    let mut strings = string_table(mir, interner);
+    if wasi {
+        for text in wasi_strings(mir) {
+            intern(&mut strings, text);
+        }
+    }
     let tags = struct_tags(mir);
     let ftable = func_table(mir);
+    let exceptions = module_uses_exceptions(mir);
     let mut out = String::new();
     out.push_str("(module\n");
 
     // Imports come first (WASM requires imported funcs before defined ones).
-    emit_imports(&mut out, mir, interner);
+    if wasi {
+        emit_wasi_imports(&mut out);
+    }
+    emit_imports(&mut out, mir, interner, wasi);
 
     // `call_indirect` signature types (declared before use), plus the function table + its export.
     emit_func_signatures(&mut out, interner);
@@ -41,12 +67,18 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
     // pointer then starts past those. Its trampolines/data are emitted below.
     let iface = emit_interface_dispatch(mir, interner, heap_base(&strings));
 
-    // Linear memory + allocator runtime state. The heap bump pointer starts above the itable region.
+    // Linear memory + allocator runtime state. The heap bump pointer starts above the itable region;
+    // memory starts at `MEMORY_PAGES` and `$malloc` grows it on demand up to `$heap_max_pages`.
     let _ = writeln!(out, "(memory {})", MEMORY_PAGES);
     let _ = writeln!(out, "(global $heap_ptr (mut i32) (i32.const {}))", iface.heap_start);
     out.push_str("(global $free_list_head (mut i32) (i32.const 0))\n");
+    out.push_str("(global $heap_max_pages (mut i32) (i32.const 65536))\n");
+    out.push_str("(global $oom_request (mut i32) (i32.const 0))\n");
+    out.push_str("(global $panic_message (mut i32) (i32.const 0))\n");
     out.push_str("(global $live_objects (mut i32) (i32.const 0))\n");
     out.push_str("(global $total_allocations (mut i32) (i32.const 0))\n");
+    // Read (and dropped) by statement line markers until they are stripped; see `crate::mir::srcmap`.
+    let _ = writeln!(out, "(global ${} i32 (i32.const 0))", crate::mir::srcmap::MARKER_GLOBAL);
 
     // Module-level user variables. They start zeroed; any initializer runs in `$__dream_init`
     // (emitted as a normal function below and wired to `(start ...)`).
@@ -55,7 +87,11 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
         let _ = writeln!(out, "(global $g{} (mut {}) {})", g.id.0, wasm_ty_of(interner, g.ty), zero);
     }
 
-    out.push_str(&runtime_prelude(debug_alloc));
+    if exceptions {
+        let _ = writeln!(out, "(tag {} (param i32))", EXCEPTION_TAG);
+    }
+
+    out.push_str(&runtime_prelude(debug_alloc, wasi, mir.collect_cycles));
     out.push('\n');
     if crate::mir::async_emit::module_has_async(&mir.functions) {
         out.push_str(&crate::mir::async_emit::async_runtime_wat());
@@ -63,10 +99,24 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
     }
     out.push_str(&to_string_runtime(&strings));
     out.push('\n');
+    if mir.runtime_checks {
+        out.push_str(&panic_runtime(&strings, wasi));
+        out.push('\n');
+    }
+    if wasi {
+        out.push_str(&wasi_runtime(&strings));
+        out.push('\n');
+        out.push_str(&wasi_host_bridges(mir, interner, &strings));
+        out.push('\n');
+    }
     emit_object_protocol(&mut out, mir, interner, &strings, &tags);
     out.push('\n');
     emit_release_funcs(&mut out, mir, interner, &tags);
     out.push('\n');
+    if mir.collect_cycles {
+        emit_cycle_funcs(&mut out, mir, interner, &tags);
+        out.push('\n');
+    }
 
     // Interface dispatch trampolines (reference `$object_tag` + `$__ft`, both defined above).
     out.push_str(&iface.trampolines);
@@ -84,12 +134,13 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
     out.push_str(&iface.data);
 
     let polls = crate::mir::async_emit::poll_indices(&mir.functions);
-    let mut has_init = false;
+    let has_init = mir.functions.iter().any(|f| f.name == crate::mir::lower::INIT_FN_NAME);
     for f in &mir.functions {
         if f.is_async {
             out.push_str(&crate::mir::async_emit::emit_async_function(
                 f, interner, &symbols, &mir.layouts, &strings, &tags, &ftable,
                 *polls.get(&(f.def, f.instance.clone())).unwrap_or(&0),
+                mir.runtime_checks,
             ));
         } else {
             out.push_str(&emit_function_with(
@@ -97,7 +148,9 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
             ));
         }
         if f.name == crate::mir::lower::INIT_FN_NAME {
-            has_init = true;
+            // Not exported: it runs as the start function, or first thing in a WASI `_start`.
+        } else if wasi && f.instance.is_empty() && f.name == "main" {
+            out.push_str(&wasi_start(f, interner, has_init, exceptions, &strings));
         } else if f.instance.is_empty() && f.name == "main" && f.is_async {
             out.push_str(&crate::mir::async_emit::emit_async_main_wrapper(
                 &func_symbol(f),
@@ -105,21 +158,55 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
             ));
         } else if f.instance.is_empty() && f.name == "main" && !f.params.is_empty() {
             // `main(args: string[])`: the exported entry takes no args, so wrap the real `main` with a
-            // `()` shim that passes an empty `string[]` (a zero-length, TAG_ARRAY block).
-            let _ = writeln!(
-                out,
-                "(func (export \"main\")\n (local $args i32)\n i32.const 4\n i32.const {}\n call $malloc\n local.set $args\n local.get $args\n i32.const 0\n i32.store\n local.get $args\n call ${}\n)",
-                crate::mir::abi::TAG_ARRAY,
-                func_symbol(f),
-            );
+            // `()` shim that passes the host's arguments (see `main_args_prologue`).
+            let call = format!("{} call ${}\n", main_args_prologue(), func_symbol(f));
+            if exceptions {
+                out.push_str(&guarded_main("main", " (local $args i32)\n", &call, &strings, false));
+            } else {
+                let _ = writeln!(out, "(func (export \"main\")\n (local $args i32)\n{})", call);
+            }
+        } else if f.instance.is_empty() && f.name == "main" && exceptions {
+            let mut call = format!(" call ${}\n", func_symbol(f));
+            if !matches!(interner.kind(f.ret), TyKind::Void) {
+                call.push_str(" drop\n");
+            }
+            out.push_str(&guarded_main("main", "", &call, &strings, false));
         } else if f.instance.is_empty() {
             let _ = writeln!(out, "(export \"{}\" (func ${}))", f.name, func_symbol(f));
         }
         out.push('\n');
     }
 
-    // Run global initializers before any entry point.
-    if has_init {
+    // One `()` export per `@test` function (a test build only), in declaration order. An async test
+    // drives the scheduler until it settles; with exceptions, an escaping one fails the test.
+    for def in &mir.tests {
+        let Some(f) = mir.functions.iter().find(|f| f.def == *def && f.instance.is_empty()) else {
+            continue;
+        };
+        let export = format!("{}{}", crate::mir::abi::EXPORT_TEST_PREFIX, f.name);
+        let mut call = format!(" call ${}\n", func_symbol(f));
+        if f.is_async || !matches!(interner.kind(f.ret), TyKind::Void) {
+            call.push_str(" drop\n");
+        }
+        if f.is_async {
+            call.push_str(" call $dream_run_loop\n");
+        }
+        if exceptions {
+            out.push_str(&guarded_main(&export, "", &call, &strings, false));
+        } else {
+            let _ = writeln!(out, "(func (export \"{}\")\n{})", export, call);
+        }
+    }
+
+    // The slot a host stores `main`'s `string[]` argument in before calling the `main` export.
+    let takes_args = mir.functions.iter().any(|f| f.instance.is_empty() && f.name == "main" && !f.params.is_empty());
+    if takes_args && !wasi {
+        out.push_str("(global $main_args (mut i32) (i32.const 0))\n");
+        let _ = writeln!(out, "(export \"{}\" (global $main_args))", crate::mir::abi::EXPORT_MAIN_ARGS);
+    }
+
+    // Run global initializers before any entry point (a WASI `_start` runs them itself).
+    if has_init && !wasi {
         let _ = writeln!(out, "(start ${})", crate::mir::lower::INIT_FN_NAME);
     }
 
@@ -127,29 +214,60 @@ pub fn emit_module(mir: &crate::mir::Mir, interner: &TypeInterner, debug_alloc:
     out.push_str("(export \"memory\" (memory 0))\n");
     out.push_str("(export \"malloc\" (func $malloc))\n");
     out.push_str("(export \"free\" (func $free))\n");
+    // Heap-limit controls: the host may lower the growth cap, and reads the failed request size
+    // after an out-of-memory trap.
+    let _ = writeln!(out, "(export \"{}\" (global $heap_max_pages))", crate::mir::abi::EXPORT_HEAP_MAX_PAGES);
+    let _ = writeln!(out, "(export \"{}\" (global $oom_request))", crate::mir::abi::EXPORT_OOM_REQUEST);
+    // The message of a failed runtime check (a string pointer; 0 until a check fails).
+    let _ = writeln!(out, "(export \"{}\" (global $panic_message))", crate::mir::abi::EXPORT_PANIC_MESSAGE);
     if crate::mir::async_emit::module_has_async(&mir.functions) {
         out.push_str("(export \"__dream_run_loop\" (func $dream_run_loop))\n");
         out.push_str("(export \"__dream_resolve\" (func $dream_resolve))\n");
         out.push_str("(export \"__dream_new_future\" (func $dream_new_future))\n");
     }
+    emit_source_section(&mut out, mir);
     out.push_str(")\n");
     out
 }
 
+/// Emits the `dream.funcs` custom section: `symbol \t name \t file \t line` per function with a
+/// known source. An async function's entries cover both its constructor and its `poll` body, where
+/// the markers of its statements land.
+fn emit_source_section(out: &mut String, mir: &crate::mir::Mir) {
+    let mut entries = String::new();
+    for f in &mir.functions {
+        let Some(src) = &f.source else { continue };
+        let mut symbols = vec![func_symbol(f)];
+        if f.is_async {
+            symbols.push(poll_symbol(f));
+        }
+        for symbol in symbols {
+            let _ = writeln!(entries, "{}\t{}\t{}\t{}", symbol, src.name, src.file, src.line);
+        }
+    }
+    if !entries.is_empty() {
+        let bytes: String = entries.bytes().map(|b| format!("\\{:02x}", b)).collect();
+        let _ = writeln!(out, "(@custom \"{}\" \"{}\")", crate::mir::srcmap::FUNCS_SECTION, bytes);
+    }
+}
+
 /// Emits the module's `(import ...)` declarations: the fixed host `print_*` builtins (which
 /// `print`/`println` lower to) followed by user `extern fun` interop imports. Call sites reference
-/// each import's internal `$name`; the `module`/`field` pair names the host binding.
-pub(super) fn emit_imports(out: &mut String, mir: &crate::mir::Mir, interner: &TypeInterner) {
-    for (name, param) in [
+/// each import's internal `$name`; the `module`/`field` pair names the host binding. A WASI module
+/// defines the builtins and the `Dream` host functions itself (see [`wasi_host_bridges`]), so it
+/// imports only the user's own externs.
+pub(super) fn emit_imports(out: &mut String, mir: &crate::mir::Mir, interner: &TypeInterner, wasi: bool) {
+    let builtins = if wasi { &[][..] } else { &[
         ("print_string", "i32"),
         ("print_int", "i32"),
         ("print_float", "f32"),
         ("print_double", "f64"),
         ("print_char", "i32"),
-    ] {
+    ][..] };
+    for (name, param) in builtins {
         let _ = writeln!(out, "(import \"env\" \"{name}\" (func ${name} (param {param})))");
     }
-    for imp in &mir.imports {
+    for imp in mir.imports.iter().filter(|imp| !(wasi && imp.module == "Dream")) {
         let params: String = imp
             .params
             .iter()
@@ -170,4 +288,13 @@ pub(super) fn emit_imports(out: &mut String, mir: &crate::mir::Mir, interner: &T
             imp.module, imp.field, imp.name, params, result
         );
     }
-}
\ No newline at end of file
+}
+
+/// Leaves `main`'s `string[]` argument in `$args`: the array a host stored in `$main_args` before
+/// calling the `main` export, or else an empty one (a zero-length, TAG_ARRAY block).
+pub(crate) fn main_args_prologue() -> String {
+    format!(
+        " global.get $main_args\n local.tee $args\n i32.eqz\n if\n  i32.const 4\n  i32.const {}\n  call $malloc\n  local.set $args\n  local.get $args\n  i32.const 0\n  i32.store\n end\n local.get $args\n",
+        crate::mir::abi::TAG_ARRAY,
+    )
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::syntax::nodes::types::is_tuple_type_name;
 
 /// Emits the object-protocol runtime that depends on the user's types: one default `$<Type>_to_string`
 /// per struct, plus the tag-dispatching `$object_to_string` and `$print_object` routers. Struct
@@ -45,6 +46,42 @@ pub(super) fn emit_object_protocol(
         }
     }
     emit_object_hash_code(out, mir, tags);
+    for layout in mir.layouts.structs.values() {
+        if is_tuple_type_name(&layout.name) {
+            emit_tuple_equals(out, layout, mir, interner);
+        }
+    }
+}
+
+/// Emits a tuple's `$<Tuple>_equals(a, b)`, the target of `==`/`!=` on tuple values. Tuples compare
+/// structurally: the same pointer (or both null) is equal, one null side is not, and otherwise every
+/// element must match — primitives by value, strings by content, nested tuples recursively, and any
+/// other reference by identity (as `==` on it would).
+pub(super) fn emit_tuple_equals(
+    out: &mut String,
+    layout: &crate::hir::TypeLayout,
+    mir: &crate::mir::Mir,
+    interner: &TypeInterner,
+) {
+    let _ = writeln!(out, "(func ${}_equals (param $a i32) (param $b i32) (result i32)", layout.name);
+    out.push_str("  (local.get $a) (local.get $b) (i32.eq) (if (then (i32.const 1) (return)))\n");
+    out.push_str(
+        "  (local.get $a) (i32.eqz) (local.get $b) (i32.eqz) (i32.or) (if (then (i32.const 0) (return)))\n",
+    );
+    for f in &layout.fields {
+        let add = if f.offset > 0 { format!(" (i32.const {}) (i32.add)", f.offset) } else { String::new() };
+        let load = load_instr_for(interner, f.ty);
+        let compare = match mir.layouts.get(interner.strip_nullable(f.ty)) {
+            Some(nested) if is_tuple_type_name(&nested.name) => format!("(call ${}_equals)", nested.name),
+            _ if prim_of(interner, f.ty) == Some(PrimTy::String) => "(call $string_eq)".to_string(),
+            _ => format!("({}.eq)", wasm_ty_of(interner, f.ty)),
+        };
+        let _ = writeln!(
+            out,
+            "  (local.get $a){add} ({load}) (local.get $b){add} ({load}) {compare} (i32.eqz) (if (then (i32.const 0) (return)))"
+        );
+    }
+    out.push_str("  (i32.const 1)\n)\n");
 }
 
 /// The instructions that turn a loaded value of `ty` (already on the stack) into its `i32` hash.
@@ -152,25 +189,26 @@ pub(super) fn emit_object_hash_code(out: &mut String, mir: &crate::mir::Mir, tag
     out.push_str("  (local.get $ptr)\n)\n");
 }
 
-/// Emits one struct's default `$<Type>_to_string`, concatenating the interned label pieces with each
-/// field's rendered value (in offset order).
+/// Emits one struct's default `$<Type>_to_string`, concatenating the interned label pieces (see
+/// [`struct_to_string_pieces`]) with each field's rendered value (in offset order).
 pub(super) fn emit_struct_to_string(
     out: &mut String,
     layout: &crate::hir::TypeLayout,
     interner: &TypeInterner,
     strings: &IndexMap<String, u32>,
 ) {
-    let prefix = format!("{} {{ ", layout.name);
+    let (prefix, labels, suffix) = struct_to_string_pieces(layout);
     let _ = writeln!(out, "(func ${}_to_string (param $this i32) (result i32)", layout.name);
     out.push_str("  (local $res i32)\n");
     let _ = writeln!(out, "  (i32.const {}) (local.set $res)", strings[&prefix]);
-    for (i, f) in layout.fields.iter().enumerate() {
-        let label = if i == 0 { format!("{}: ", f.name) } else { format!(", {}: ", f.name) };
-        let _ = writeln!(
-            out,
-            "  (local.get $res) (i32.const {}) (call $concat_strings) (local.set $res)",
-            strings[&label]
-        );
+    for (f, label) in layout.fields.iter().zip(&labels) {
+        if !label.is_empty() {
+            let _ = writeln!(
+                out,
+                "  (local.get $res) (i32.const {}) (call $concat_strings) (local.set $res)",
+                strings[label]
+            );
+        }
         out.push_str("  (local.get $res)\n  (local.get $this)\n");
         if f.offset > 0 {
             let _ = writeln!(out, "  (i32.const {}) (i32.add)", f.offset);
@@ -181,7 +219,7 @@ pub(super) fn emit_struct_to_string(
         }
         out.push_str("  (call $concat_strings) (local.set $res)\n");
     }
-    let _ = writeln!(out, "  (local.get $res) (i32.const {}) (call $concat_strings)", strings[" }"]);
+    let _ = writeln!(out, "  (local.get $res) (i32.const {}) (call $concat_strings)", strings[&suffix]);
     out.push_str(")\n");
 }
 
@@ -328,7 +366,8 @@ pub(super) fn emit_array_to_string(
 }
 
 /// Emits `$object_to_string`: null → `"null"`, boxed primitives → unbox + `*_to_string`, strings →
-/// identity, each struct/union tag → its `$<Type>_to_string`, everything else → `"<object>"`.
+/// identity, each struct/union tag → its `$<Type>_to_string`, everything else → `"<object>"`; plus
+/// its owned-result wrapper `$object_to_string_owned`.
 pub(super) fn emit_object_to_string(
     out: &mut String,
     mir: &crate::mir::Mir,
@@ -370,6 +409,22 @@ pub(super) fn emit_object_to_string(
         }
     }
     let _ = writeln!(out, "  (i32.const {})\n)", strings["<object>"]);
+    // `$object_to_string_owned`: the same rendering as an owned reference, for a `to_string()` whose
+    // result the caller releases. Strings and the fixed `"null"`/`"<object>"` fallbacks come back
+    // shared, so they take a reference; every other arm already builds a fresh string.
+    out.push_str("(func $object_to_string_owned (param $ptr i32) (result i32)\n  (local $res i32)\n");
+    out.push_str("  (local.get $ptr) (call $object_to_string) (local.set $res)\n");
+    let _ = writeln!(
+        out,
+        "  (local.get $ptr) (i32.eqz) (local.get $ptr) (call $object_tag) (i32.const {}) (i32.eq) (i32.or)",
+        t::TAG_STRING
+    );
+    let _ = writeln!(
+        out,
+        "  (local.get $res) (i32.const {}) (i32.eq) (i32.or) (if (then (local.get $res) (call $retain)))",
+        strings["<object>"]
+    );
+    out.push_str("  (local.get $res)\n)\n");
 }
 
 /// Writes one `if (tag == n) {{ <body>; return }}` dispatch arm into `$object_to_string`.
@@ -10,7 +10,10 @@ pub(super) fn release_call(interner: &TypeInterner, layouts: &LayoutTable, ty: T
     let ty = interner.strip_nullable(ty);
     match interner.kind(ty) {
         TyKind::Struct(..) | TyKind::Union(..) => {
-            if let Some(l) = layouts.structs.get(&ty) {
+            // A class with subclasses may hold any of them, so release by the runtime tag.
+            if layouts.has_subclasses(ty) {
+                "$release_object".to_string()
+            } else if let Some(l) = layouts.structs.get(&ty) {
                 format!("$release_{}", l.name)
             } else if let Some(l) = layouts.unions.get(&ty) {
                 format!("$release_{}", l.name)
@@ -20,45 +23,76 @@ pub(super) fn release_call(interner: &TypeInterner, layouts: &LayoutTable, ty: T
         }
         TyKind::Array(e) if interner.is_reference(*e) => format!("$release_array_t{}", e.0),
         // An interface-typed value is a concrete tagged object; release it through the
-        // tag-dispatching `$release_object` so the concrete type's deep release runs.
-        TyKind::Object | TyKind::Interface(..) => "$release_object".to_string(),
+        // tag-dispatching `$release_object` so the concrete type's deep release runs. A function
+        // value is likewise tagged with its closure's env layout, which owns the captures.
+        TyKind::Object | TyKind::Interface(..) | TyKind::Func(..) => "$release_object".to_string(),
         _ => "$release_generic".to_string(),
     }
 }
 
 /// Emits the null check + refcount decrement shared by every per-type release, opening the
-/// `if (new_count == 0) (then` block that the caller fills with the deep-release + `$free`. Uses only
-/// the `$rc`/`$nc` locals, which every release function declares. Matches `$release_generic`'s ABI
-/// (refcount word at `ptr - 4`).
-pub(super) fn emit_release_prologue(out: &mut String) {
+/// `if (new_count == 0) (then` block that the caller fills with the deep-release before closing it
+/// with [`emit_release_epilogue`]. Uses only the `$rc`/`$nc` locals, which every release function
+/// declares. Matches `$release_generic`'s ABI (refcount word at `ptr - 4`). With the cycle collector,
+/// the count is the low bits of the word, and a dying object is pinned (see `$cc_pin`) while its
+/// fields are released.
+pub(super) fn emit_release_prologue(out: &mut String, cycles: bool) {
     out.push_str("  (local.get $ptr) (i32.eqz) (if (then (return)))\n");
     out.push_str("  (local.get $ptr) (i32.const 4) (i32.sub) (local.set $rc)\n");
     out.push_str("  (local.get $rc) (i32.load) (i32.const 1) (i32.sub) (local.set $nc)\n");
     out.push_str("  (local.get $rc) (local.get $nc) (i32.store)\n");
-    out.push_str("  (local.get $nc) (i32.eqz) (if (then\n");
+    if cycles {
+        out.push_str("  (local.get $nc) (i32.const 0x0fffffff) (i32.and) (i32.eqz) (if (then\n");
+        out.push_str("    (local.get $ptr) (call $cc_pin)\n");
+    } else {
+        out.push_str("  (local.get $nc) (i32.eqz) (if (then\n");
+    }
+}
+
+/// Closes the block opened by [`emit_release_prologue`] with the `$free`. `root`, when given, runs on
+/// a decrement that leaves the count nonzero: the cycle collector records a candidate root there.
+pub(super) fn emit_release_epilogue(out: &mut String, root: Option<&str>) {
+    out.push_str("    (local.get $ptr) (call $free)\n  )");
+    if let Some(root) = root {
+        let _ = write!(out, "\n  (else\n{}  )", root);
+    }
+    out.push_str(")\n)\n");
 }
 
 /// Emits the `del()` destructor invocation (when the type declares one): the refcount is first pinned
 /// to 1 so the destructor body's own `this` retain/release cannot re-enter this release at zero, then
 /// `$<Type>_del(ptr)` runs while the fields are still live. `del` is the destructor's function symbol
-/// or `None`.
-pub(super) fn emit_del_call(out: &mut String, del: Option<&str>) {
+/// or `None`. The cycle collector's prologue has pinned already; it re-pins after `del`, which may
+/// have made the object a candidate root.
+pub(super) fn emit_del_call(out: &mut String, del: Option<&str>, cycles: bool) {
     if let Some(d) = del {
-        out.push_str("    (local.get $rc) (i32.const 1) (i32.store)\n");
+        if !cycles {
+            out.push_str("    (local.get $rc) (i32.const 1) (i32.store)\n");
+        }
         let _ = writeln!(out, "    (local.get $ptr) (call ${})", d);
+        if cycles {
+            out.push_str("    (local.get $ptr) (call $cc_pin)\n");
+        }
     }
 }
 
 /// Emits the deep-release runtime: a per-struct/union `$release_<Type>` (run `del()` if present,
 /// release reference fields, then `$free`), a `$release_array_t<E>` for each reference-element array
 /// type, and the tag-dispatching `$release_object`. Non-reference fields and scalar arrays never need
-/// releasing; strings/boxed primitives fall through to `$release_generic`.
+/// releasing; strings/boxed primitives fall through to `$release_generic`. With the cycle collector
+/// (`mir.collect_cycles`), a nonzero decrement of a type that can sit on a cycle records a candidate
+/// root instead of doing nothing.
 pub(super) fn emit_release_funcs(
     out: &mut String,
     mir: &crate::mir::Mir,
     interner: &TypeInterner,
     tags: &HashMap<TypeId, i32>,
 ) {
+    let cycles = mir.collect_cycles;
+    let traced = if cycles { traced_types(mir, interner) } else { Default::default() };
+    let root_of = |ty: &TypeId| -> Option<&str> {
+        traced.contains(ty).then_some("    (local.get $ptr) (call $cc_possible_root)\n")
+    };
     let fn_names: std::collections::HashSet<&str> =
         mir.functions.iter().map(|f| f.name.as_str()).collect();
     let del_of = |name: &str| -> Option<String> {
@@ -66,12 +100,16 @@ pub(super) fn emit_release_funcs(
         fn_names.contains(sym.as_str()).then_some(sym)
     };
 
-    for layout in mir.layouts.structs.values() {
-        let del = del_of(&layout.name);
+    for (ty, layout) in &mir.layouts.structs {
         let _ = writeln!(out, "(func $release_{} (param $ptr i32)", layout.name);
         out.push_str("  (local $rc i32) (local $nc i32)\n");
-        emit_release_prologue(out);
-        emit_del_call(out, del.as_deref());
+        emit_release_prologue(out, cycles);
+        // A subclass runs its own `del` and then each base class's, most-derived first.
+        let mut class = Some(layout);
+        while let Some(l) = class {
+            emit_del_call(out, del_of(&l.name).as_deref(), cycles);
+            class = l.base.and_then(|b| mir.layouts.structs.get(&b));
+        }
         for f in layout.fields.iter().filter(|f| interner.is_reference(f.ty)) {
             out.push_str("    (local.get $ptr)\n");
             if f.offset > 0 {
@@ -83,15 +121,15 @@ pub(super) fn emit_release_funcs(
                 release_call(interner, &mir.layouts, f.ty)
             );
         }
-        out.push_str("    (local.get $ptr) (call $free)\n  ))\n)\n");
+        emit_release_epilogue(out, root_of(ty));
     }
 
-    for layout in mir.layouts.unions.values() {
+    for (ty, layout) in &mir.layouts.unions {
         let del = del_of(&layout.name);
         let _ = writeln!(out, "(func $release_{} (param $ptr i32)", layout.name);
         out.push_str("  (local $rc i32) (local $nc i32) (local $d i32)\n");
-        emit_release_prologue(out);
-        emit_del_call(out, del.as_deref());
+        emit_release_prologue(out, cycles);
+        emit_del_call(out, del.as_deref(), cycles);
         // Only the active variant's payload is valid, so switch on the discriminant (offset 0).
         out.push_str("    (local.get $ptr) (i32.load) (local.set $d)\n");
         for v in &layout.variants {
@@ -118,7 +156,7 @@ pub(super) fn emit_release_funcs(
             }
             out.push_str("    ))\n");
         }
-        out.push_str("    (local.get $ptr) (call $free)\n  ))\n)\n");
+        emit_release_epilogue(out, root_of(ty));
     }
 
     // One array release per reference-element array type; the element type is known statically at the
@@ -129,7 +167,7 @@ pub(super) fn emit_release_funcs(
         }
         let _ = writeln!(out, "(func $release_array_t{} (param $ptr i32)", elem.0);
         out.push_str("  (local $rc i32) (local $nc i32) (local $len i32) (local $i i32) (local $elem i32)\n");
-        emit_release_prologue(out);
+        emit_release_prologue(out, cycles);
         out.push_str("    (local.get $ptr) (i32.load) (local.set $len)\n");
         out.push_str("    (i32.const 0) (local.set $i)\n");
         out.push_str("    (block $done (loop $scan\n");
@@ -141,7 +179,14 @@ pub(super) fn emit_release_funcs(
             release_call(interner, &mir.layouts, elem)
         );
         out.push_str("      (local.get $i) (i32.const 1) (i32.add) (local.set $i) (br $scan)))\n");
-        out.push_str("    (local.get $ptr) (call $free)\n  ))\n)\n");
+        // The element type is only known here, so an array of traceable references is flagged for
+        // the collector's tag-based tracing as it becomes a candidate root.
+        let root = format!(
+            "    (local.get $rc) (local.get $nc) (i32.const {:#x}) (i32.or) (i32.store)\n    (local.get $ptr) (call $cc_possible_root)\n",
+            RC_REF_ARRAY
+        );
+        let traced_elems = cycles && traced_edge(interner, mir, &traced, elem);
+        emit_release_epilogue(out, traced_elems.then_some(root.as_str()));
     }
 
     // `$release_object`: tag dispatch for reference values whose static type is `object`. Strings,
@@ -3,8 +3,10 @@ use super::*;
 /// The allocator + string runtime. When `debug_alloc` is on, `$malloc` bumps
 /// `$live_objects`/`$total_allocations` and `$free` decrements `$live_objects` (backing the
 /// `Debug.*` probes); otherwise the placeholders expand to nothing so the hot allocation path
-/// carries no extra instructions.
-pub(super) fn runtime_prelude(debug_alloc: bool) -> String {
+/// carries no extra instructions. Under WASI, running out of memory is also reported on stderr.
+/// With `cycles`, the allocator hooks into the cycle collector (see [`cycles_runtime`], appended
+/// here); without it, `Debug.collect_cycles()` is a stub that collects nothing.
+pub(super) fn runtime_prelude(debug_alloc: bool, wasi: bool, cycles: bool) -> String {
     let (malloc_count, free_count) = if debug_alloc {
         (
             "global.get $live_objects\n    i32.const 1\n    i32.add\n    global.set $live_objects\n    \
@@ -14,11 +16,30 @@ pub(super) fn runtime_prelude(debug_alloc: bool) -> String {
     } else {
         ("", "")
     };
+    let (alloc_hook, free_hook, count_mask) = if cycles {
+        (
+            "call $cc_on_alloc",
+            "local.get $ptr\n    i32.const 4\n    i32.sub\n    i32.const 0x10000000\n    i32.store",
+            "i32.const 0x0fffffff\n    i32.and",
+        )
+    } else {
+        ("", "", "")
+    };
     let mut out = RUNTIME_ALLOCATOR
         .replace(";;@DEBUG_ALLOC_COUNT@", malloc_count)
-        .replace(";;@DEBUG_FREE_COUNT@", free_count);
+        .replace(";;@DEBUG_FREE_COUNT@", free_count)
+        .replace(";;@CC_ALLOC_HOOK@", alloc_hook)
+        .replace(";;@CC_FREE_HOOK@", free_hook)
+        .replace(";;@RC_COUNT_MASK@", count_mask)
+        .replace(";;@OOM_REPORT@", if wasi { "call $wasi_report_oom" } else { "" });
     out.push('\n');
     out.push_str(RUNTIME_STRINGS);
+    out.push('\n');
+    if cycles {
+        out.push_str(&cycles_runtime());
+    } else {
+        out.push_str("(func $debug_collect_cycles (result i32)\n    i32.const 0\n)\n");
+    }
     out
 }
 
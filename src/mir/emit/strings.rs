@@ -1,4 +1,5 @@
 use super::*;
+use crate::syntax::nodes::types::is_tuple_type_name;
 
 /// The fixed runtime strings the object protocol references: the `null`/`<object>` fallbacks plus
 /// each struct's default `to_string` pieces (`"Point { "`, `"x: "`, `", y: "`, `" }"`). Interned
@@ -7,11 +8,10 @@ pub(super) fn protocol_strings(mir: &crate::mir::Mir) -> Vec<String> {
     let mut v =
         vec!["null".to_string(), "<object>".to_string(), "[".to_string(), "]".to_string(), ", ".to_string()];
     for layout in mir.layouts.structs.values() {
-        v.push(format!("{} {{ ", layout.name));
-        for (i, f) in layout.fields.iter().enumerate() {
-            v.push(if i == 0 { format!("{}: ", f.name) } else { format!(", {}: ", f.name) });
-        }
-        v.push(" }".to_string());
+        let (prefix, labels, suffix) = struct_to_string_pieces(layout);
+        v.push(prefix);
+        v.extend(labels.into_iter().filter(|l| !l.is_empty()));
+        v.push(suffix);
     }
     for layout in mir.layouts.unions.values() {
         for variant in &layout.variants {
@@ -24,6 +24,24 @@ pub(super) fn protocol_strings(mir: &crate::mir::Mir) -> Vec<String> {
     v
 }
 
+/// The `(prefix, field-labels, suffix)` literal pieces of a struct's default `to_string`: classes
+/// render as `Point { x: <x>, y: <y> }`, tuples positionally as `(<0>, <1>)` (the first element's
+/// label is empty).
+pub(super) fn struct_to_string_pieces(layout: &crate::hir::TypeLayout) -> (String, Vec<String>, String) {
+    if is_tuple_type_name(&layout.name) {
+        let labels =
+            (0..layout.fields.len()).map(|i| if i == 0 { String::new() } else { ", ".to_string() }).collect();
+        return ("(".to_string(), labels, ")".to_string());
+    }
+    let labels = layout
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| if i == 0 { format!("{}: ", f.name) } else { format!(", {}: ", f.name) })
+        .collect();
+    (format!("{} {{ ", layout.name), labels, " }".to_string())
+}
+
 /// The `(prefix, field-labels, suffix)` literal pieces of a union variant's `to_string`. Data
 /// variants render as `Variant(a: <a>, b: <b>)`; unit variants render as just `Variant`.
 pub(super) fn union_variant_pieces(v: &crate::hir::UnionVariant) -> (String, Vec<String>, String) {
@@ -44,18 +62,29 @@ pub(super) fn union_variant_pieces(v: &crate::hir::UnionVariant) -> (String, Vec
 /// (deterministic). Each string is a heap-object block `[size=0][tag=STRING][ref_count=1][utf8][\0]`;
 /// the mapped address points at the utf8 bytes (block start + [`HEAP_HEADER_SIZE`]), so it is a valid
 /// runtime string pointer. Blocks are laid out consecutively, 4-byte aligned.
-pub(super) fn string_table(mir: &crate::mir::Mir) -> IndexMap<String, u32> {
+pub(super) fn string_table(mir: &crate::mir::Mir, interner: &TypeInterner) -> IndexMap<String, u32> {
     let mut found = Vec::new();
     for f in &mir.functions {
-        for b in &f.blocks {
-            for s in &b.stmts {
-                strings_in_stmt(s, &mut found);
+        // An async function's MIR body is a stub: the coroutine transform lowers its poll segments
+        // from the preserved HIR snapshot, so scan those lowered bodies as well.
+        let segments = if f.is_async {
+            crate::mir::async_emit::segment_mirs(f, interner, mir.runtime_checks)
+        } else {
+            Vec::new()
+        };
+        for body in std::iter::once(f).chain(&segments) {
+            for b in &body.blocks {
+                for s in &b.stmts {
+                    strings_in_stmt(s, &mut found);
+                    if let Statement::Check(check) = s {
+                        found.push(check_string(body, check, interner, &mir.layouts));
+                    }
+                }
+                strings_in_terminator(&b.terminator, &mut found);
             }
-            strings_in_terminator(&b.terminator, &mut found);
         }
-        // An async function's MIR body is a stub; its literals live in the preserved HIR snapshot
-        // (emitted later by the coroutine transform), so harvest them here too — otherwise those
-        // string constants get no data segment and lower to a null pointer.
+        // Harvest the snapshot's literals directly too, so none is left without a data segment
+        // (a missing one lowers to a null pointer).
         if f.is_async {
             if let Some(hir_fn) = &f.hir_fn {
                 let mut edges = crate::mir::HirEdges::default();
@@ -64,8 +93,14 @@ pub(super) fn string_table(mir: &crate::mir::Mir) -> IndexMap<String, u32> {
             }
         }
     }
+    // The runtime-check failure messages' fixed pieces, after the program's own literals.
+    if mir.runtime_checks {
+        found.extend(PANIC_STR_CONSTS.iter().map(|(_, text)| text.to_string()));
+    }
+    if module_uses_exceptions(mir) {
+        found.push(UNCAUGHT_PREFIX.to_string());
+    }
     let mut map: IndexMap<String, u32> = IndexMap::new();
-    let mut block = STRING_BASE;
     // Seed the constants the `*_to_string`/object-protocol runtime references so they always have
     // stable addresses, regardless of which literals the program itself uses.
     let found = RUNTIME_STR_CONSTS
@@ -74,15 +109,23 @@ pub(super) fn string_table(mir: &crate::mir::Mir) -> IndexMap<String, u32> {
         .chain(protocol_strings(mir))
         .chain(found);
     for s in found {
-        if !map.contains_key(&s) {
-            let total = HEAP_HEADER_SIZE + s.len() as u32 + 1;
-            map.insert(s, block + HEAP_HEADER_SIZE);
-            block += (total + 3) & !3;
-        }
+        intern(&mut map, s);
     }
     map
 }
 
+/// Adds `s` to the string table, in a block just past the last one, unless it is already there.
+pub(super) fn intern(map: &mut IndexMap<String, u32>, s: String) {
+    if map.contains_key(&s) {
+        return;
+    }
+    let block = match map.last() {
+        Some((last, addr)) => addr - HEAP_HEADER_SIZE + ((HEAP_HEADER_SIZE + last.len() as u32 + 1 + 3) & !3),
+        None => STRING_BASE,
+    };
+    map.insert(s, block + HEAP_HEADER_SIZE);
+}
+
 pub(super) fn strings_in_operand(op: &Operand, out: &mut Vec<String>) {
     match op {
         Operand::Const(Const::Str(s)) => out.push(s.clone()),
@@ -115,7 +158,8 @@ pub(super) fn strings_in_rvalue(rv: &Rvalue, out: &mut Vec<String>) {
         Rvalue::Call { args, .. }
         | Rvalue::New { args, .. }
         | Rvalue::UnionNew { args, .. }
-        | Rvalue::ArrayLit { elems: args, .. } => {
+        | Rvalue::ArrayLit { elems: args, .. }
+        | Rvalue::Closure { captures: args, .. } => {
             args.iter().for_each(|a| strings_in_operand(a, out))
         }
         Rvalue::IndirectCall { target, args } => {
@@ -126,7 +170,7 @@ pub(super) fn strings_in_rvalue(rv: &Rvalue, out: &mut Vec<String>) {
             strings_in_operand(receiver, out);
             args.iter().for_each(|a| strings_in_operand(a, out));
         }
-        Rvalue::FuncRef(_) => {}
+        Rvalue::Exception => {}
     }
 }
 
@@ -140,12 +184,25 @@ pub(super) fn strings_in_stmt(s: &Statement, out: &mut Vec<String>) {
         }
         Statement::Retain(o) | Statement::Release(o) => strings_in_operand(o, out),
         Statement::Call { args, .. } => args.iter().for_each(|a| strings_in_operand(a, out)),
-        Statement::InterfaceCall { receiver, args, .. } => {
+        Statement::IndirectCall { target: receiver, args }
+        | Statement::InterfaceCall { receiver, args, .. } => {
             strings_in_operand(receiver, out);
             args.iter().for_each(|a| strings_in_operand(a, out));
         }
         Statement::Print { arg, .. } => strings_in_operand(arg, out),
-        Statement::Nop => {}
+        Statement::Check(crate::mir::RuntimeCheck::Bounds { index, .. }) => {
+            strings_in_operand(index, out)
+        }
+        Statement::Check(crate::mir::RuntimeCheck::StrBounds { base, index }) => {
+            strings_in_operand(base, out);
+            strings_in_operand(index, out);
+        }
+        Statement::Check(crate::mir::RuntimeCheck::Downcast { value, .. }) => {
+            strings_in_operand(value, out)
+        }
+        Statement::Check(crate::mir::RuntimeCheck::NotNull { .. })
+        | Statement::Line(_)
+        | Statement::Nop => {}
     }
 }
 
@@ -153,7 +210,7 @@ pub(super) fn strings_in_terminator(t: &Terminator, out: &mut Vec<String>) {
     match t {
         Terminator::If { cond, .. } => strings_in_operand(cond, out),
         Terminator::Switch { value, .. } => strings_in_operand(value, out),
-        Terminator::Return(Some(o)) => strings_in_operand(o, out),
+        Terminator::Return(Some(o)) | Terminator::Throw(o) => strings_in_operand(o, out),
         Terminator::AsyncComplete(Some(o)) => strings_in_operand(o, out),
         _ => {}
     }
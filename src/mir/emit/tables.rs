@@ -1,13 +1,23 @@
 use super::*;
 
 /// The emitted symbol for a function (or generic instance): the source name, suffixed with the
-/// instance's interned type-arg ids so each monomorphization stays distinct.
+/// instance's interned type-arg ids so each monomorphization stays distinct. An overload's
+/// signature-mangled name can spell parameter types WAT identifiers reject (`fmt.object[]`,
+/// `apply.fun(int):int`); those characters are replaced so the symbol stays a valid `$id`.
 pub(crate) fn func_symbol(func: &MirFunction) -> String {
+    let name: String = func
+        .name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | '(' | ')' | ',' | ' ' | '{' | '}' | '"' | ';' => '\'',
+            c => c,
+        })
+        .collect();
     if func.instance.is_empty() {
-        func.name.clone()
+        name
     } else {
         let args: Vec<String> = func.instance.iter().map(|t| t.0.to_string()).collect();
-        format!("{}__{}", func.name, args.join("_"))
+        format!("{}__{}", name, args.join("_"))
     }
 }
 
@@ -48,8 +58,8 @@ pub(super) fn signature_table(mir: &crate::mir::Mir) -> HashMap<(DefId, Vec<Type
 }
 
 /// Maps each function's `(DefId, instance args)` to its slot in the module's function table, in
-/// `mir.functions` order (so the slot index matches the `(elem ...)` position below). A `FuncRef`
-/// resolves to this index; `call_indirect` uses it as the table entry.
+/// `mir.functions` order (so the slot index matches the `(elem ...)` position below). A `Closure`
+/// stores this index in its block; `call_indirect` uses it as the table entry.
 pub(super) fn func_table(mir: &crate::mir::Mir) -> HashMap<(DefId, Vec<TypeId>), usize> {
     mir.functions
         .iter()
@@ -76,14 +86,25 @@ pub(super) fn func_sig(interner: &TypeInterner, ty: TypeId) -> Option<(String, V
     }
 }
 
+/// The `call_indirect` type for invoking a closure of function type `ty`: [`func_sig`] with the
+/// closure block prepended as a hidden `i32` first parameter (every closure function takes its own
+/// block, so captures are reachable). `None` if `ty` is not a function type.
+pub(super) fn closure_sig(interner: &TypeInterner, ty: TypeId) -> Option<(String, Vec<&'static str>, Option<&'static str>)> {
+    let (_, mut ptys, rty) = func_sig(interner, ty)?;
+    ptys.insert(0, "i32");
+    let name = format!("$sig_{}__{}", ptys.join("_"), rty.unwrap_or("v"));
+    Some((name, ptys, rty))
+}
+
 /// Emits a `(type …)` declaration for every distinct function signature in the program (one per WASM
-/// shape), so `call_indirect` can name its expected type. Over-approximates from all interned function
+/// shape), so `call_indirect` can name its expected type. Each function type declares both its plain
+/// shape (interface dispatch) and its closure shape. Over-approximates from all interned function
 /// types — spare declarations are harmless.
 pub(super) fn emit_func_signatures(out: &mut String, interner: &TypeInterner) {
     let mut seen: IndexMap<String, (Vec<&'static str>, Option<&'static str>)> = IndexMap::new();
     for (id, kind) in interner.iter_kinds() {
         if matches!(kind, TyKind::Func(..)) {
-            if let Some((name, ptys, rty)) = func_sig(interner, id) {
+            for (name, ptys, rty) in func_sig(interner, id).into_iter().chain(closure_sig(interner, id)) {
                 seen.entry(name).or_insert((ptys, rty));
             }
         }
@@ -163,6 +184,8 @@ pub(super) struct InterfaceDispatch {
 /// index of the concrete `{Class}_{method}` that the class with runtime `tag` supplies for that
 /// interface method slot (0 for tags that do not implement the interface). At a call site the
 /// trampoline computes `tag = $object_tag(this)`, loads that entry, and `call_indirect`s it.
+/// A class vtable is just another table here: its virtual methods are the slots and each subclass
+/// tag supplies its override (or the inherited method).
 pub(super) fn emit_interface_dispatch(
     mir: &crate::mir::Mir,
     interner: &TypeInterner,
@@ -70,6 +70,7 @@
                     FieldLayout { offset: 8, ty: dbl, name: "b".into() },
                 ],
                 size: 16,
+                base: None,
             },
         );
 
@@ -110,6 +111,7 @@
                     FieldLayout { offset: 4, ty: int, name: "b".into() },
                 ],
                 size: 8,
+                base: None,
             },
         );
 
@@ -173,6 +175,7 @@
                 name: "S".into(),
                 fields: vec![FieldLayout { offset: 0, ty: int, name: "a".into() }],
                 size: 4,
+                base: None,
             },
         );
 
@@ -254,10 +257,40 @@
         );
     }
 
+    /// The allocator grows memory on demand, and the module exposes the heap cap and the
+    /// out-of-memory record hosts use to bound the heap and report exhaustion.
+    #[test]
+    fn module_exports_heap_growth_controls() {
+        let wat = emit_module(&crate::mir::Mir::default(), &TypeInterner::new(), false);
+        assert!(wat.contains("memory.grow"), "allocator never grows memory:\n{}", wat);
+        assert!(wat.contains("(export \"__dream_heap_max_pages\" (global $heap_max_pages))"));
+        assert!(wat.contains("(export \"__dream_oom_request\" (global $oom_request))"));
+        wat::parse_str(&wat).unwrap_or_else(|e| panic!("module failed to assemble: {}\n{}", e, wat));
+    }
+
     /// `--debug` must actually instrument the allocator under the MIR backend: with it on, `$malloc`
     /// bumps the live/total counters; with it off the hot path stays clean.
     #[test]
     fn debug_alloc_toggles_allocator_instrumentation() {
-        assert!(runtime_prelude(true).contains("global.set $live_objects"));
-        assert!(!runtime_prelude(false).contains("global.set $live_objects"));
+        assert!(runtime_prelude(true, false, false).contains("global.set $live_objects"));
+        assert!(!runtime_prelude(false, false, false).contains("global.set $live_objects"));
+    }
+
+    /// The cycle collector is opt-in: without it the allocator carries none of its hooks and
+    /// `Debug.collect_cycles()` is a stub; with it the module still assembles.
+    #[test]
+    fn cycle_collection_is_emitted_only_on_request() {
+        let plain = runtime_prelude(false, false, false);
+        assert!(!plain.contains("$cc_on_alloc"));
+        assert!(!plain.contains("(func $collect_cycles"));
+        assert!(plain.contains("(func $debug_collect_cycles (result i32)\n    i32.const 0\n)"));
+
+        let cc = runtime_prelude(false, false, true);
+        assert!(cc.contains("call $cc_on_alloc"));
+        assert!(cc.contains("(func $collect_cycles"));
+
+        let mir = crate::mir::Mir { collect_cycles: true, ..Default::default() };
+        let wat = emit_module(&mir, &TypeInterner::new(), false);
+        assert!(wat.contains("(func $cc_children"), "per-module tracer missing:\n{}", wat);
+        wat::parse_str(&wat).unwrap_or_else(|e| panic!("module failed to assemble: {}\n{}", e, wat));
     }
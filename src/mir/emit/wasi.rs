@@ -0,0 +1,138 @@
+use super::*;
+
+/// The `wasi_snapshot_preview1` functions the WASI layer ([`RUNTIME_WASI`]) calls, with their
+/// signatures. Each is imported as `$wasi_<name>`.
+const WASI_IMPORTS: [(&str, &str); 13] = [
+    ("fd_write", "(param i32 i32 i32 i32) (result i32)"),
+    ("fd_read", "(param i32 i32 i32 i32) (result i32)"),
+    ("fd_close", "(param i32) (result i32)"),
+    ("fd_readdir", "(param i32 i32 i32 i64 i32) (result i32)"),
+    ("fd_prestat_get", "(param i32 i32) (result i32)"),
+    ("fd_prestat_dir_name", "(param i32 i32 i32) (result i32)"),
+    ("path_open", "(param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)"),
+    ("path_filestat_get", "(param i32 i32 i32 i32 i32) (result i32)"),
+    ("path_unlink_file", "(param i32 i32 i32) (result i32)"),
+    ("args_sizes_get", "(param i32 i32) (result i32)"),
+    ("args_get", "(param i32 i32) (result i32)"),
+    ("clock_time_get", "(param i32 i64 i32) (result i32)"),
+    ("proc_exit", "(param i32)"),
+];
+
+/// The `Dream` host functions the WASI layer implements, each as `$host_<field>`. The rest (HTTP,
+/// regular expressions, JS interop) have no WASI counterpart.
+const WASI_HOST_FUNCTIONS: [&str; 15] = [
+    "consoleReadLine",
+    "consoleReadKey",
+    "consoleExit",
+    "dateNowMillis",
+    "dateLocalOffsetMinutes",
+    "fileRead",
+    "fileReadBytes",
+    "fileWrite",
+    "fileAppend",
+    "fileWriteBytes",
+    "fileExists",
+    "fileSize",
+    "fileIsDir",
+    "fileDelete",
+    "dirList",
+];
+
+/// Strings the WASI layer references by address, keyed by their placeholder in [`RUNTIME_WASI`].
+const WASI_STR_CONSTS: [(&str, &str); 2] = [("dot", "."), ("out_of_memory", "out of memory")];
+
+/// What calling the host function `field` reports when WASI has no counterpart for it.
+fn unavailable_message(field: &str) -> String {
+    format!("host function '{}' is not available on the WASI target", field)
+}
+
+/// Every string a WASI module needs interned on top of its own literals: the layer's constants
+/// and one [`unavailable_message`] per host import it cannot provide.
+pub(super) fn wasi_strings(mir: &crate::mir::Mir) -> Vec<String> {
+    let mut out: Vec<String> = WASI_STR_CONSTS.iter().map(|(_, text)| text.to_string()).collect();
+    for imp in mir.imports.iter().filter(|imp| imp.module == "Dream") {
+        if !WASI_HOST_FUNCTIONS.contains(&imp.field.as_str()) {
+            out.push(unavailable_message(&imp.field));
+        }
+    }
+    out
+}
+
+/// Emits the `wasi_snapshot_preview1` imports, which replace the `env` print builtins.
+pub(super) fn emit_wasi_imports(out: &mut String) {
+    for (name, sig) in WASI_IMPORTS {
+        let _ = writeln!(out, "(import \"wasi_snapshot_preview1\" \"{name}\" (func $wasi_{name} {sig}))");
+    }
+}
+
+/// The WASI layer with its placeholders resolved. Depends on the allocator, string, and
+/// `*_to_string` runtime.
+pub(super) fn wasi_runtime(strings: &IndexMap<String, u32>) -> String {
+    let mut out = RUNTIME_WASI.replace("{TAG_ARRAY}", &crate::mir::abi::TAG_ARRAY.to_string());
+    for (key, text) in WASI_STR_CONSTS {
+        out = out.replace(&format!("{{{}}}", key), &strings[text].to_string());
+    }
+    out
+}
+
+/// Defines the module's `Dream` host imports, which a WASI module cannot import: each one calls its
+/// `$host_*` implementation, or, when WASI has none, reports that on stderr and traps.
+pub(super) fn wasi_host_bridges(
+    mir: &crate::mir::Mir,
+    interner: &TypeInterner,
+    strings: &IndexMap<String, u32>,
+) -> String {
+    let mut out = String::new();
+    for imp in mir.imports.iter().filter(|imp| imp.module == "Dream") {
+        let mut sig = String::new();
+        for (i, t) in imp.params.iter().enumerate() {
+            let _ = write!(sig, " (param $p{} {})", i, wasm_ty_of(interner, *t));
+        }
+        if let Some(t) = imp.ret {
+            let _ = write!(sig, " (result {})", wasm_ty_of(interner, t));
+        }
+        let _ = writeln!(out, "(func ${}{}", imp.name, sig);
+        if WASI_HOST_FUNCTIONS.contains(&imp.field.as_str()) {
+            for i in 0..imp.params.len() {
+                let _ = writeln!(out, "    local.get $p{}", i);
+            }
+            let _ = writeln!(out, "    call $host_{}", imp.field);
+        } else {
+            let _ = writeln!(out, "    i32.const {}", strings[&unavailable_message(&imp.field)]);
+            out.push_str("    call $wasi_report\n    unreachable\n");
+        }
+        out.push_str(")\n");
+    }
+    out
+}
+
+/// The `_start` entry of a WASI command: runs the global initializers (which a WASI host must not
+/// see run as the module's start function, before it is set up), then `main` — passing it the
+/// command-line arguments if it takes them — and then, for an async `main`, the scheduler.
+pub(super) fn wasi_start(
+    f: &MirFunction,
+    interner: &TypeInterner,
+    has_init: bool,
+    exceptions: bool,
+    strings: &IndexMap<String, u32>,
+) -> String {
+    let mut body = String::new();
+    if has_init {
+        let _ = writeln!(body, " call ${}", crate::mir::lower::INIT_FN_NAME);
+    }
+    if !f.params.is_empty() {
+        body.push_str(" call $wasi_args\n");
+    }
+    let _ = writeln!(body, " call ${}", func_symbol(f));
+    if f.is_async || !matches!(interner.kind(f.ret), TyKind::Void) {
+        body.push_str(" drop\n");
+    }
+    if f.is_async {
+        body.push_str(" call $dream_run_loop\n");
+    }
+    if exceptions {
+        guarded_main("_start", "", &body, strings, true)
+    } else {
+        format!("(func (export \"_start\")\n{})\n", body)
+    }
+}
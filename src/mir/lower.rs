@@ -8,7 +8,9 @@
 //! produces the data/control skeleton.
 
 use super::build::FunctionBuilder;
-use super::{Const, Local, Mir, MirFunction, Operand, Place, Rvalue, Statement, Terminator};
+use super::{
+    Const, Local, Mir, MirFunction, Operand, Place, Rvalue, RuntimeCheck, Statement, Terminator,
+};
 use crate::hir::{
     Binding, HExpr, HExprKind, HFunction, HParam, HPlace, HStmt, Hir,
 };
@@ -18,11 +20,32 @@ use std::collections::HashMap;
 /// Symbol/name of the synthesized module-init function; the backend wires it to `(start ...)`.
 pub const INIT_FN_NAME: &str = "__dream_init";
 
-/// Lowers a whole HIR program to MIR.
+/// Lowers a whole HIR program to MIR, with runtime bounds, null and cast checks.
 pub fn lower_program(hir: &Hir, interner: &TypeInterner) -> Mir {
+    lower_program_with(hir, interner, true)
+}
+
+/// Lowers a whole HIR program to MIR. `runtime_checks` guards every array/string index with a
+/// bounds check, every field access with a null check and every downcast with a class check (see
+/// [`RuntimeCheck`]); without it they compile to bare loads, stores and casts (the CLI's
+/// `--unchecked`).
+pub fn lower_program_with(hir: &Hir, interner: &TypeInterner, runtime_checks: bool) -> Mir {
+    lower_program_using(hir, interner, runtime_checks, |f| {
+        lower_function_with(f, interner, runtime_checks)
+    })
+}
+
+/// [`lower_program_with`], taking each function's body from `lower_function`, which may reuse a
+/// body an earlier build lowered instead of lowering it again (see [`crate::driver::cache`]).
+pub fn lower_program_using(
+    hir: &Hir,
+    interner: &TypeInterner,
+    runtime_checks: bool,
+    mut lower_function: impl FnMut(&HFunction) -> MirFunction,
+) -> Mir {
     let mut functions = Vec::new();
     for f in &hir.functions {
-        functions.push(lower_function(f, interner));
+        functions.push(lower_function(f));
     }
     // Synthesize a module-init function from the global initializers, so a `(start ...)` can run
     // them before `main`. Reserves a sentinel `DefId` that no real declaration uses.
@@ -46,8 +69,9 @@ pub fn lower_program(hir: &Hir, interner: &TypeInterner) -> Mir {
             locals: vec![],
             body: init_body,
             is_async: false,
+            source: None,
         };
-        functions.push(lower_function(&init_fn, interner));
+        functions.push(lower_function(&init_fn));
     }
     let globals = hir
         .globals
@@ -61,15 +85,23 @@ pub fn lower_program(hir: &Hir, interner: &TypeInterner) -> Mir {
         imports: hir.imports.clone(),
         intrinsics: hir.intrinsics.clone(),
         interfaces: hir.interfaces.clone(),
+        tests: hir.tests.clone(),
+        runtime_checks,
+        collect_cycles: false,
     }
 }
 
-/// Lowers a single function.
+/// Lowers a single function (checked).
 pub fn lower_function(func: &HFunction, interner: &TypeInterner) -> MirFunction {
+    lower_function_with(func, interner, true)
+}
+
+/// Lowers a single function, with or without runtime checks.
+pub fn lower_function_with(func: &HFunction, interner: &TypeInterner, runtime_checks: bool) -> MirFunction {
     if func.is_async {
         return lower_async_stub(func, interner);
     }
-    lower_sync_function(func, interner)
+    lower_sync_function(func, interner, runtime_checks)
 }
 
 /// Preserves the HIR body for the async coroutine transform; the poll/constructor are emitted
@@ -88,10 +120,11 @@ fn lower_async_stub(func: &HFunction, interner: &TypeInterner) -> MirFunction {
     let mut f = b.finish();
     f.ret = func.ret;
     f.hir_fn = Some(func.clone());
+    f.source = func.source.clone();
     f
 }
 
-fn lower_sync_function(func: &HFunction, interner: &TypeInterner) -> MirFunction {
+fn lower_sync_function(func: &HFunction, interner: &TypeInterner, runtime_checks: bool) -> MirFunction {
     let mut b = FunctionBuilder::new(func.name.clone(), func.ret);
     b.set_async(func.is_async);
     b.set_def(func.def, func.instance.clone());
@@ -111,7 +144,10 @@ fn lower_sync_function(func: &HFunction, interner: &TypeInterner) -> MirFunction
         interner,
         locals,
         loops: Vec::new(),
+        handlers: Vec::new(),
+        finallies: Vec::new(),
         async_segment: false,
+        runtime_checks,
     };
     lo.lower_block(&func.body);
 
@@ -119,12 +155,19 @@ fn lower_sync_function(func: &HFunction, interner: &TypeInterner) -> MirFunction
     if !lo.b.is_terminated() {
         lo.b.terminate(Terminator::Return(None));
     }
-    lo.b.finish()
+    let mut f = lo.b.finish();
+    f.source = func.source.clone();
+    f
 }
 
 /// Lowers a straight-line slice of an async function body (one poll segment). `Return` becomes
 /// [`Terminator::AsyncComplete`] so the async emitter can finish the task with `$dream_complete`.
-pub fn lower_async_segment(func: &HFunction, stmts: &[HStmt], interner: &TypeInterner) -> MirFunction {
+pub fn lower_async_segment(
+    func: &HFunction,
+    stmts: &[HStmt],
+    interner: &TypeInterner,
+    runtime_checks: bool,
+) -> MirFunction {
     let mut b = FunctionBuilder::new(format!("{}__seg", func.name), func.ret);
     b.set_def(func.def, func.instance.clone());
     let mut locals: HashMap<u32, Local> = HashMap::new();
@@ -141,11 +184,16 @@ pub fn lower_async_segment(func: &HFunction, stmts: &[HStmt], interner: &TypeInt
         interner,
         locals,
         loops: Vec::new(),
+        handlers: Vec::new(),
+        finallies: Vec::new(),
         async_segment: true,
+        runtime_checks,
     };
     lo.lower_block(stmts);
+    // Falling off the end is not a completion: the segment's suspend (or the task's completion)
+    // follows it, which the emitter reaches through a void `Return`.
     if !lo.b.is_terminated() {
-        lo.b.terminate(Terminator::AsyncComplete(None));
+        lo.b.terminate(Terminator::Return(None));
     }
     lo.b.finish()
 }
@@ -156,6 +204,7 @@ pub fn lower_expr_value(
     func: &HFunction,
     expr: &crate::hir::HExpr,
     interner: &TypeInterner,
+    runtime_checks: bool,
 ) -> (MirFunction, Local) {
     let mut b = FunctionBuilder::new(format!("{}__expr", func.name), expr.ty);
     b.set_def(func.def, func.instance.clone());
@@ -173,7 +222,10 @@ pub fn lower_expr_value(
         interner,
         locals,
         loops: Vec::new(),
+        handlers: Vec::new(),
+        finallies: Vec::new(),
         async_segment: false,
+        runtime_checks,
     };
     let t = lo.b.new_temp(expr.ty);
     let rv = lo.lower_rvalue(expr);
@@ -186,6 +238,26 @@ struct LoopCtx {
     break_blk: super::BlockId,
     continue_blk: super::BlockId,
     label: Option<String>,
+    /// How many `finally` bodies enclose the loop; `break`/`continue` run the ones above this depth.
+    finallies: usize,
+}
+
+/// An active exception handler: the protected code's blocks unwind to `landing`, and a `throw`
+/// lowered inside it stores into `exception` and jumps straight to `dispatch`.
+#[derive(Clone, Copy)]
+struct Handler {
+    landing: super::BlockId,
+    dispatch: super::BlockId,
+    exception: Local,
+}
+
+/// A `finally` body that every early exit (`return`, `break`, `continue`) from its `try` or catch
+/// clauses must run first. `handlers` is the handler depth outside the `try`, which the body runs
+/// under.
+#[derive(Clone)]
+struct FinallyCtx {
+    body: Vec<HStmt>,
+    handlers: usize,
 }
 
 struct Lowerer<'a> {
@@ -193,8 +265,12 @@ struct Lowerer<'a> {
     interner: &'a TypeInterner,
     locals: HashMap<u32, Local>,
     loops: Vec<LoopCtx>,
+    handlers: Vec<Handler>,
+    finallies: Vec<FinallyCtx>,
     /// When set, `return` completes the async task instead of returning from a WASM function.
     async_segment: bool,
+    /// When set, index and field accesses and downcasts are preceded by a [`Statement::Check`].
+    runtime_checks: bool,
 }
 
 impl Lowerer<'_> {
@@ -203,12 +279,16 @@ impl Lowerer<'_> {
     }
 
     fn lower_block(&mut self, stmts: &[HStmt]) {
+        // Code after a nested body (a loop's step and exit, an `if`'s join) belongs to the enclosing
+        // statement's line, not to the body's last statement.
+        let line = self.b.line();
         for s in stmts {
             if self.b.is_terminated() {
                 break; // unreachable tail
             }
             self.lower_stmt(s);
         }
+        self.b.restore_line(line);
     }
 
     fn lower_stmt(&mut self, stmt: &HStmt) {
@@ -246,6 +326,11 @@ impl Lowerer<'_> {
                         args: lowered,
                     });
                 }
+                HExprKind::IndirectCall { target, args } if !self.interner.is_reference(e.ty) => {
+                    let t = self.lower_operand(target);
+                    let lowered = args.iter().map(|a| self.lower_operand(a)).collect();
+                    self.b.push(Statement::IndirectCall { target: t, args: lowered });
+                }
                 HExprKind::InterfaceCall { receiver, iface_id, method_slot, sig, args }
                     if !self.interner.is_reference(e.ty) =>
                 {
@@ -271,7 +356,18 @@ impl Lowerer<'_> {
                 }
             },
             HStmt::Return(e) => {
-                let op = e.as_ref().map(|e| self.lower_operand(e));
+                let mut op = e.as_ref().map(|e| self.lower_operand(e));
+                if !self.finallies.is_empty() {
+                    // Pin the value before the `finally` bodies run: they may reassign its local.
+                    if let (Some(value), Some(e)) = (op.take(), e) {
+                        let t = self.b.new_temp(e.ty);
+                        self.b.assign(Place::Local(t), Rvalue::Use(value));
+                        op = Some(Operand::Copy(Place::Local(t)));
+                    }
+                    if !self.run_finallies(0) {
+                        return;
+                    }
+                }
                 if self.async_segment {
                     self.b.terminate(Terminator::AsyncComplete(op));
                 } else {
@@ -303,8 +399,19 @@ impl Lowerer<'_> {
                 arms,
                 default,
             } => self.lower_switch(scrutinee, arms, default),
+            HStmt::Throw(e) => {
+                let op = self.lower_operand(e);
+                self.lower_throw(op);
+            }
+            HStmt::Try {
+                body,
+                exception,
+                catches,
+                finally,
+            } => self.lower_try(body, *exception, catches, finally),
             HStmt::Break(label) => self.lower_break(label.as_deref()),
             HStmt::Continue(label) => self.lower_continue(label.as_deref()),
+            HStmt::Line(line) => self.b.set_line(*line),
         }
     }
 
@@ -352,6 +459,7 @@ impl Lowerer<'_> {
             break_blk: after_blk,
             continue_blk: cond_blk,
             label: label.map(str::to_string),
+            finallies: self.finallies.len(),
         });
         self.b.switch_to(body_blk);
         self.lower_block(body);
@@ -375,6 +483,7 @@ impl Lowerer<'_> {
             break_blk: after_blk,
             continue_blk: cond_blk,
             label: label.map(str::to_string),
+            finallies: self.finallies.len(),
         });
         self.b.switch_to(body_blk);
         self.lower_block(body);
@@ -421,6 +530,7 @@ impl Lowerer<'_> {
             break_blk: after_blk,
             continue_blk: step_blk,
             label: label.map(str::to_string),
+            finallies: self.finallies.len(),
         });
         self.b.switch_to(body_blk);
         self.lower_block(body);
@@ -483,6 +593,7 @@ impl Lowerer<'_> {
             break_blk: after_blk,
             continue_blk: step_blk,
             label: label.map(str::to_string),
+            finallies: self.finallies.len(),
         });
         self.b.switch_to(body_blk);
         let elem_local = self.mir_local(elem);
@@ -659,27 +770,195 @@ impl Lowerer<'_> {
     }
 
     fn lower_break(&mut self, label: Option<&str>) {
-        if let Some(target) = self.loop_target(label, true) {
-            self.b.terminate(Terminator::Goto(target));
+        if let Some((target, finallies)) = self.loop_target(label, true) {
+            if self.run_finallies(finallies) {
+                self.b.terminate(Terminator::Goto(target));
+            }
         }
     }
 
     fn lower_continue(&mut self, label: Option<&str>) {
-        if let Some(target) = self.loop_target(label, false) {
-            self.b.terminate(Terminator::Goto(target));
+        if let Some((target, finallies)) = self.loop_target(label, false) {
+            if self.run_finallies(finallies) {
+                self.b.terminate(Terminator::Goto(target));
+            }
         }
     }
 
-    fn loop_target(&self, label: Option<&str>, is_break: bool) -> Option<super::BlockId> {
+    /// The jump target of a `break`/`continue` and the `finally` depth of its loop.
+    fn loop_target(&self, label: Option<&str>, is_break: bool) -> Option<(super::BlockId, usize)> {
         let ctx = match label {
             Some(l) => self.loops.iter().rev().find(|c| c.label.as_deref() == Some(l)),
             None => self.loops.last(),
         }?;
-        Some(if is_break {
+        let target = if is_break {
             ctx.break_blk
         } else {
             ctx.continue_blk
-        })
+        };
+        Some((target, ctx.finallies))
+    }
+
+    /// Throws `value`: to the innermost handler of this function by a plain jump, or out of the
+    /// function when there is none.
+    fn lower_throw(&mut self, value: Operand) {
+        match self.handlers.last().copied() {
+            Some(handler) => {
+                self.b.assign(Place::Local(handler.exception), Rvalue::Use(value));
+                self.b.terminate(Terminator::Goto(handler.dispatch));
+            }
+            None => self.b.terminate(Terminator::Throw(value)),
+        }
+    }
+
+    /// Lowers `try`/`catch`/`finally`. The body's blocks unwind to a landing block that stores the
+    /// exception and falls into a chain of type tests, one per clause; a value no clause takes is
+    /// rethrown after `finally`. When there is a `finally`, the catch bodies are protected too, so an
+    /// exception escaping one still runs it. Early exits run `finally` inline (see
+    /// [`Self::run_finallies`]); the normal exits of the body and of every clause share one copy.
+    fn lower_try(
+        &mut self,
+        body: &[HStmt],
+        exception: crate::hir::LocalId,
+        catches: &[crate::hir::HCatch],
+        finally: &[HStmt],
+    ) {
+        let exception = self.mir_local(exception);
+        let outer_unwind = self.b.unwind();
+        let rethrow = self.b.new_block();
+        let finally_normal = self.b.new_block();
+        let after = self.b.new_block();
+        let dispatch = self.b.new_block();
+        let landing = self.b.new_block();
+        let catch_landing = self.b.new_block();
+        if !finally.is_empty() {
+            self.finallies.push(FinallyCtx {
+                body: finally.to_vec(),
+                handlers: self.handlers.len(),
+            });
+        }
+
+        // The protected body.
+        self.handlers.push(Handler { landing, dispatch, exception });
+        self.b.set_unwind(Some(landing));
+        let body_blk = self.b.new_block();
+        self.b.terminate(Terminator::Goto(body_blk));
+        self.b.switch_to(body_blk);
+        self.lower_block(body);
+        if !self.b.is_terminated() {
+            self.b.terminate(Terminator::Goto(finally_normal));
+        }
+        self.handlers.pop();
+        self.b.set_unwind(outer_unwind);
+
+        self.b.switch_to(landing);
+        self.b.assign(Place::Local(exception), Rvalue::Exception);
+        self.b.terminate(Terminator::Goto(dispatch));
+
+        // Clause selection, then the clause bodies. With a `finally`, an exception escaping a clause
+        // lands in `catch_landing` and leaves through `rethrow`.
+        let protect_catches = !finally.is_empty();
+        if protect_catches {
+            self.handlers.push(Handler {
+                landing: catch_landing,
+                dispatch: rethrow,
+                exception,
+            });
+            self.b.set_unwind(Some(catch_landing));
+        }
+        self.b.switch_to(dispatch);
+        for catch in catches {
+            let catch_blk = self.b.new_block();
+            // A clause for `object` (or untyped) takes anything and ends the chain.
+            let test_ty = catch
+                .ty
+                .filter(|ty| !matches!(self.interner.kind(*ty), TyKind::Object));
+            let next = match test_ty {
+                Some(ty) => {
+                    let test = self.b.new_temp(self.interner.bool());
+                    self.b.assign(
+                        Place::Local(test),
+                        Rvalue::IsType(Operand::Copy(Place::Local(exception)), ty),
+                    );
+                    let next = self.b.new_block();
+                    self.b.terminate(Terminator::If {
+                        cond: Operand::Copy(Place::Local(test)),
+                        then_blk: catch_blk,
+                        else_blk: next,
+                    });
+                    Some(next)
+                }
+                None => {
+                    self.b.terminate(Terminator::Goto(catch_blk));
+                    None
+                }
+            };
+            self.b.switch_to(catch_blk);
+            self.lower_block(&catch.body);
+            if !self.b.is_terminated() {
+                self.b.terminate(Terminator::Goto(finally_normal));
+            }
+            match next {
+                Some(next) => self.b.switch_to(next),
+                None => break,
+            }
+        }
+        if !self.b.is_terminated() {
+            self.b.terminate(Terminator::Goto(rethrow));
+        }
+        if protect_catches {
+            self.handlers.pop();
+            self.b.set_unwind(outer_unwind);
+        }
+        if !finally.is_empty() {
+            self.finallies.pop();
+        }
+
+        self.b.switch_to(catch_landing);
+        self.b.assign(Place::Local(exception), Rvalue::Exception);
+        self.b.terminate(Terminator::Goto(rethrow));
+
+        // Propagation: `finally`, then on to the enclosing handler or the caller.
+        self.b.switch_to(rethrow);
+        self.lower_block(finally);
+        if !self.b.is_terminated() {
+            self.lower_throw(Operand::Copy(Place::Local(exception)));
+        }
+
+        self.b.switch_to(finally_normal);
+        self.lower_block(finally);
+        if !self.b.is_terminated() {
+            self.b.terminate(Terminator::Goto(after));
+        }
+        self.b.switch_to(after);
+    }
+
+    /// Runs, innermost first, the `finally` bodies above `depth` ahead of a jump out of them. Each
+    /// body starts a fresh block under the handlers outside its own `try`, so an exception it raises
+    /// is not caught by that `try`'s clauses. False if a body threw, leaving nothing to jump from.
+    fn run_finallies(&mut self, depth: usize) -> bool {
+        if self.finallies.len() <= depth {
+            return true;
+        }
+        let handlers = self.handlers.clone();
+        let finallies = self.finallies.clone();
+        let unwind = self.b.unwind();
+        for (i, ctx) in finallies.iter().enumerate().skip(depth).rev() {
+            self.handlers.truncate(ctx.handlers);
+            self.finallies.truncate(i);
+            self.b.set_unwind(self.handlers.last().map(|h| h.landing));
+            let blk = self.b.new_block();
+            self.b.terminate(Terminator::Goto(blk));
+            self.b.switch_to(blk);
+            self.lower_block(&ctx.body);
+            if self.b.is_terminated() {
+                break;
+            }
+        }
+        self.handlers = handlers;
+        self.finallies = finallies;
+        self.b.set_unwind(unwind);
+        !self.b.is_terminated()
     }
 
     /// Selects the integer constant width from the literal's static type: `long`/`ulong` lower to a
@@ -715,6 +994,12 @@ impl Lowerer<'_> {
             HExprKind::Binary { op, .. } if op.is_logical() => self.lower_short_circuit(e),
             HExprKind::Ternary { .. } => self.lower_ternary(e),
             HExprKind::Coalesce { .. } => self.lower_coalesce(e),
+            HExprKind::Let { local, value, body } => {
+                let rv = self.lower_rvalue(value);
+                let dest = self.mir_local(*local);
+                self.b.assign(Place::Local(dest), rv);
+                self.lower_operand(body)
+            }
             _ => {
                 let rv = self.lower_rvalue(e);
                 let temp = self.b.new_temp(e.ty);
@@ -772,8 +1057,10 @@ impl Lowerer<'_> {
                     ret: e.ty,
                 }
             }
-            // A function name used as a value becomes its function-table index.
-            HExprKind::Var(Binding::Func(callee)) => Rvalue::FuncRef(self.lower_callee(callee)),
+            HExprKind::Closure { func, env, captures } => {
+                let lowered = captures.iter().map(|c| self.lower_operand(c)).collect();
+                Rvalue::Closure { func: self.lower_callee(func), env: *env, captures: lowered }
+            }
             HExprKind::New { def, ctor, args, .. } => {
                 let lowered = args.iter().map(|a| self.lower_operand(a)).collect();
                 Rvalue::New { def: *def, ty: e.ty, ctor: *ctor, args: lowered }
@@ -789,11 +1076,22 @@ impl Lowerer<'_> {
             }
             HExprKind::Field { obj, field } => {
                 let base = self.operand_into_local(obj);
+                self.check_not_null(base, *field);
                 Rvalue::Use(Operand::Copy(Place::Field { base, field: *field }))
             }
+            // `s[i]` on a string reads one character, bounds-checked against the string's length.
+            HExprKind::Index { array, index }
+                if self.interner.strip_nullable(array.ty) == self.interner.string() =>
+            {
+                let s = self.lower_operand(array);
+                let idx = self.lower_operand(index);
+                self.check(RuntimeCheck::StrBounds { base: s.clone(), index: idx.clone() });
+                Rvalue::CharAt(s, idx)
+            }
             HExprKind::Index { array, index } => {
                 let base = self.operand_into_local(array);
                 let idx = self.lower_operand(index);
+                self.check(RuntimeCheck::Bounds { base, index: idx.clone() });
                 Rvalue::Use(Operand::Copy(Place::Index { base, index: Box::new(idx) }))
             }
             HExprKind::Discriminant(v) => Rvalue::Discriminant(self.lower_operand(v)),
@@ -816,6 +1114,11 @@ impl Lowerer<'_> {
                 len: self.lower_operand(len),
             },
             HExprKind::HashCode(e) => Rvalue::HashCode(self.lower_operand(e)),
+            // A string is its own `to_string`: the result is the receiver itself, a borrow the RC
+            // pass must retain like any other copy, not a fresh string.
+            HExprKind::ToString(e) if self.interner.strip_nullable(e.ty) == self.interner.string() => {
+                Rvalue::Use(self.lower_operand(e))
+            }
             HExprKind::ToString(e) => Rvalue::ToString(self.lower_operand(e)),
             HExprKind::Concat(a, b) => {
                 Rvalue::Concat(self.lower_operand(a), self.lower_operand(b))
@@ -833,7 +1136,11 @@ impl Lowerer<'_> {
             }
             HExprKind::Cast(inner) => {
                 let from = inner.ty;
-                Rvalue::Cast(self.lower_operand(inner), from, e.ty)
+                let value = self.lower_operand(inner);
+                if self.is_downcast(from, e.ty) {
+                    self.check(RuntimeCheck::Downcast { value: value.clone(), from, target: e.ty });
+                }
+                Rvalue::Cast(value, from, e.ty)
             }
             HExprKind::Await(inner) => {
                 // `await` lowers to a call into the runtime poll/await machinery; modeled here as
@@ -989,15 +1296,49 @@ impl Lowerer<'_> {
             HPlace::Global(g) => Place::Global(super::Global(g.0)),
             HPlace::Field { obj, field } => {
                 let base = self.operand_into_local(obj);
+                self.check_not_null(base, *field);
                 Place::Field { base, field: *field }
             }
             HPlace::Index { array, index } => {
                 let base = self.operand_into_local(array);
                 let idx = self.lower_operand(index);
+                self.check(RuntimeCheck::Bounds { base, index: idx.clone() });
                 Place::Index { base, index: Box::new(idx) }
             }
         }
     }
+
+    /// Emits `check` ahead of the access it guards, unless lowering unchecked.
+    fn check(&mut self, check: RuntimeCheck) {
+        if self.runtime_checks {
+            self.b.push(Statement::Check(check));
+        }
+    }
+
+    /// Whether a cast from `from` to `to` may narrow an object to a class it is not an instance of:
+    /// the target is a class and the source `object`, an interface, or another class. The emitter,
+    /// which has the layouts, drops the check again for an upcast or a target without a layout.
+    fn is_downcast(&self, from: TypeId, to: TypeId) -> bool {
+        let (from, to) = (self.interner.strip_nullable(from), self.interner.strip_nullable(to));
+        from != to
+            && matches!(self.interner.kind(to), TyKind::Struct(..))
+            && matches!(
+                self.interner.kind(from),
+                TyKind::Object | TyKind::Interface(..) | TyKind::Struct(..)
+            )
+    }
+
+    /// Null-checks a class instance before a field access. Closure environments are never null
+    /// (the hidden `__env` parameter is the closure block being called), so they are not checked.
+    fn check_not_null(&mut self, base: Local, field: usize) {
+        let is_struct = matches!(
+            self.interner.kind(self.interner.strip_nullable(self.b.local_ty(base))),
+            TyKind::Struct(..)
+        );
+        if is_struct && self.b.local_name(base) != Some(crate::hir::CLOSURE_ENV_PARAM) {
+            self.check(RuntimeCheck::NotNull { base, field });
+        }
+    }
 }
 
 fn const_int_value(e: &HExpr) -> Option<i64> {
@@ -1036,6 +1377,7 @@ mod tests {
             ret: int,
             locals: vec![],
             is_async: false,
+            source: None,
             body: vec![
                 HStmt::If {
                     cond: HExpr::new(boolean, HExprKind::Var(Binding::Local(LocalId(0)))),
@@ -1055,4 +1397,96 @@ mod tests {
             .iter()
             .any(|b| matches!(b.terminator, Terminator::Return(_))));
     }
+
+    #[test]
+    fn index_is_bounds_checked_unless_unchecked() {
+        let mut ctx = TypeCtx::new();
+        let def = ctx.register(DefKind::Function, "f", vec![]);
+        let int = ctx.interner.int();
+        let ints = ctx.interner.array(int);
+
+        // fun f(xs: int[]): int { return xs[2]; }
+        let func = HFunction {
+            def,
+            name: "f".into(),
+            instance: vec![],
+            params: vec![crate::hir::HParam { local: LocalId(0), name: "xs".into(), ty: ints }],
+            ret: int,
+            locals: vec![],
+            is_async: false,
+            source: None,
+            body: vec![HStmt::Return(Some(HExpr::new(
+                int,
+                HExprKind::Index {
+                    array: Box::new(HExpr::new(ints, HExprKind::Var(Binding::Local(LocalId(0))))),
+                    index: Box::new(HExpr::new(int, HExprKind::IntLit(2))),
+                },
+            )))],
+        };
+        let checks = |f: &MirFunction| {
+            f.blocks
+                .iter()
+                .flat_map(|b| &b.stmts)
+                .filter(|s| matches!(s, Statement::Check(RuntimeCheck::Bounds { .. })))
+                .count()
+        };
+
+        assert_eq!(checks(&lower_function_with(&func, &ctx.interner, true)), 1);
+        assert_eq!(checks(&lower_function_with(&func, &ctx.interner, false)), 0);
+    }
+
+    #[test]
+    fn lowers_try_to_landing_dispatch_and_inline_finally() {
+        let mut ctx = TypeCtx::new();
+        let def = ctx.register(DefKind::Function, "f", vec![]);
+        let int = ctx.interner.int();
+        let object = ctx.interner.object();
+        let local = |id: u32, name: &str, ty| crate::hir::HLocal { id: LocalId(id), name: name.into(), ty };
+
+        // fun f(x: object): int { try { throw x; } catch { return 1; } finally { y = 5; } return 0; }
+        let func = HFunction {
+            def,
+            name: "f".into(),
+            instance: vec![],
+            params: vec![crate::hir::HParam { local: LocalId(0), name: "x".into(), ty: object }],
+            ret: int,
+            locals: vec![local(1, "$exception", object), local(2, "y", int)],
+            is_async: false,
+            source: None,
+            body: vec![
+                HStmt::Try {
+                    body: vec![HStmt::Throw(HExpr::new(object, HExprKind::Var(Binding::Local(LocalId(0)))))],
+                    exception: LocalId(1),
+                    catches: vec![crate::hir::HCatch {
+                        ty: None,
+                        body: vec![HStmt::Return(Some(HExpr::new(int, HExprKind::IntLit(1))))],
+                    }],
+                    finally: vec![HStmt::Assign {
+                        place: crate::hir::HPlace::Local(LocalId(2)),
+                        value: HExpr::new(int, HExprKind::IntLit(5)),
+                    }],
+                },
+                HStmt::Return(Some(HExpr::new(int, HExprKind::IntLit(0)))),
+            ],
+        };
+
+        let mir = lower_function(&func, &ctx.interner);
+        // The `throw` inside the `try` jumps to its handler; only the rethrow path leaves the function.
+        let throws = mir.blocks.iter().filter(|b| matches!(b.terminator, Terminator::Throw(_))).count();
+        assert_eq!(throws, 1);
+        // The protected body unwinds to a landing block that picks up the exception.
+        let landing = mir.blocks.iter().find_map(|b| b.unwind).expect("a protected block");
+        assert!(matches!(
+            mir.block(landing).stmts.last(),
+            Some(Statement::Assign(_, Rvalue::Exception))
+        ));
+        // `finally` is copied onto the catch's early return, the normal exit, and the rethrow path.
+        let finally_copies = mir
+            .blocks
+            .iter()
+            .flat_map(|b| &b.stmts)
+            .filter(|s| matches!(s, Statement::Assign(_, Rvalue::Use(Operand::Const(Const::Int(5))))))
+            .count();
+        assert_eq!(finally_copies, 3);
+    }
 }
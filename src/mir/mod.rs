@@ -4,17 +4,21 @@
 //! Where HIR keeps structured control flow, MIR desugars everything (if/while/for/foreach/switch/
 //! match/ternary/`&&`/`||`/`??`/async) into blocks joined by [`Terminator`]s. Reference-counting
 //! (`Retain`/`Release`) and allocation are explicit [`Statement`]s, which lets the optimization
-//! passes reason about them with ordinary dataflow. The backend  reconstructs
-//! structured WASM control flow from this CFG via a relooper.
+//! passes reason about them with ordinary dataflow. Exceptions add one implicit edge per block: a
+//! statement that throws transfers control to the block's [`BasicBlock::unwind`] target. The
+//! backend  reconstructs structured WASM control flow from this CFG via a relooper.
 
 pub mod abi;
 pub mod async_emit;
 pub mod build;
+pub mod codec;
 pub mod emit;
 pub mod lower;
 pub mod passes;
 pub mod print;
 pub mod relooper;
+pub mod srcmap;
+pub mod unwind;
 
 pub use crate::hir::{BinOp, UnOp};
 use crate::types::{DefId, TypeId};
@@ -50,6 +54,15 @@ pub struct Mir {
     /// Interface dispatch metadata: ordered interfaces (index = `iface_id`) + per-class concrete
     /// method symbols. Drives the itable data + dispatch trampolines emitted by the backend.
     pub interfaces: crate::hir::InterfaceTable,
+    /// The `@test` functions, carried from HIR. Each is a reachability root and gets a test export;
+    /// a normal build clears the list so tests cost nothing outside `dream test`.
+    pub tests: Vec<DefId>,
+    /// Whether lowering inserted [`Statement::Check`]s. Async bodies are lowered again by the
+    /// backend (from their HIR snapshot), which must make the same choice.
+    pub runtime_checks: bool,
+    /// Whether the backend emits the opt-in cycle collector (see `mir/runtime/cycles.wat`). Set by
+    /// the driver after lowering; off by default, leaving plain ARC code byte-for-byte unchanged.
+    pub collect_cycles: bool,
 }
 
 /// A module-level variable slot (declared as one mutable WASM global `$g{id}`).
@@ -76,6 +89,8 @@ pub struct MirFunction {
     pub is_async: bool,
     /// When `is_async`, the full typed HIR function preserved for the coroutine transform.
     pub hir_fn: Option<crate::hir::HFunction>,
+    /// Where the function is declared, for stack traces (see [`crate::hir::SourceInfo`]).
+    pub source: Option<crate::hir::SourceInfo>,
 }
 
 impl MirFunction {
@@ -103,6 +118,19 @@ pub struct LocalDecl {
 pub struct BasicBlock {
     pub stmts: Vec<Statement>,
     pub terminator: Terminator,
+    /// Where control goes when a statement of this block throws: a landing block that picks the
+    /// exception up with [`Rvalue::Exception`]. `None` propagates it to the caller. The terminator
+    /// is outside the protected range (a [`Terminator::Throw`] always leaves the function).
+    pub unwind: Option<BlockId>,
+}
+
+impl BasicBlock {
+    /// The terminator's successors plus the unwind edge, for CFG traversal.
+    pub fn successors(&self) -> Vec<BlockId> {
+        let mut s = self.terminator.successors();
+        s.extend(self.unwind);
+        s
+    }
 }
 
 /// A straight-line operation with no control-flow effect.
@@ -119,6 +147,12 @@ pub enum Statement {
         callee: Callee,
         args: Vec<Operand>,
     },
+    /// A call through a closure evaluated for effect only (result dropped if any). See
+    /// [`Rvalue::IndirectCall`].
+    IndirectCall {
+        target: Operand,
+        args: Vec<Operand>,
+    },
     /// An interface method call evaluated for effect only (result dropped if any). See
     /// [`Rvalue::InterfaceCall`].
     InterfaceCall {
@@ -135,10 +169,31 @@ pub enum Statement {
         ty: TypeId,
         newline: bool,
     },
+    /// A runtime safety check guarding the memory access that follows it; traps with a readable
+    /// message when it fails. Only emitted by checked lowering (the default; `--unchecked` omits it).
+    Check(RuntimeCheck),
+    /// The code that follows, up to the next marker, comes from this source line (see
+    /// [`crate::hir::HStmt::Line`]). Emitted as a marker the stack-trace line table is built from,
+    /// then stripped from the assembled module (see [`srcmap::strip_line_markers`]).
+    Line(u32),
     /// No-op; left behind by passes that delete statements without renumbering.
     Nop,
 }
 
+/// The condition a [`Statement::Check`] asserts.
+#[derive(Debug, Clone)]
+pub enum RuntimeCheck {
+    /// `index` lies in `0..len` of the array `base`. A null `base` fails as a null dereference.
+    Bounds { base: Local, index: Operand },
+    /// `index` lies in `0..strlen` of the string `base`.
+    StrBounds { base: Operand, index: Operand },
+    /// The class instance `base` is non-null before its field `field` is read or written.
+    NotNull { base: Local, field: usize },
+    /// The object `value`, cast from `from` down to the class `target`, is null or an instance of
+    /// `target` or one of its subclasses (the test `is` makes).
+    Downcast { value: Operand, from: TypeId, target: TypeId },
+}
+
 /// How a block transfers control. Every block ends in exactly one terminator.
 #[derive(Debug, Clone, Default)]
 pub enum Terminator {
@@ -160,6 +215,9 @@ pub enum Terminator {
     /// Completes the enclosing async task (`$dream_complete`) in a poll function. Used only by the
     /// async coroutine transform; synchronous functions use [`Terminator::Return`].
     AsyncComplete(Option<Operand>),
+    /// Throws the (`object`) operand to the caller. A `throw` inside a `try` instead jumps to the
+    /// handler; this terminator is what leaves the function with an exception.
+    Throw(Operand),
     /// Statically unreachable (e.g. after a diverging call); the placeholder default.
     #[default]
     Unreachable,
@@ -176,7 +234,10 @@ impl Terminator {
                 s.push(*default);
                 s
             }
-            Terminator::Return(_) | Terminator::AsyncComplete(_) | Terminator::Unreachable => vec![],
+            Terminator::Return(_)
+            | Terminator::AsyncComplete(_)
+            | Terminator::Throw(_)
+            | Terminator::Unreachable => vec![],
         }
     }
 }
@@ -246,7 +307,8 @@ pub enum Rvalue {
     },
     /// A direct call returning a value.
     Call { callee: Callee, args: Vec<Operand> },
-    /// An indirect call through a function-pointer operand.
+    /// An indirect call through a closure operand: the block's `fn` word selects the table entry,
+    /// and the block itself is passed as the hidden first argument.
     IndirectCall { target: Operand, args: Vec<Operand> },
     /// A dynamically-dispatched interface method call. Lowered to a call to the generated dispatch
     /// trampoline for `(iface_id, method_slot)`, which reads the receiver's tag, indexes the
@@ -260,9 +322,13 @@ pub enum Rvalue {
         args: Vec<Operand>,
         ret: TypeId,
     },
-    /// A first-class reference to a (possibly monomorphized) function, materialized as its index in
-    /// the module's function table. Used when a function name is taken as a value (`let f = foo;`).
-    FuncRef(Callee),
+    /// Allocate a closure block of the env struct `env`: word 0 is `func`'s function-table index and
+    /// `captures` fill the remaining fields in order. Produces an owned (`+1`) function value.
+    Closure {
+        func: Callee,
+        env: TypeId,
+        captures: Vec<Operand>,
+    },
     /// Allocate and construct a struct instance. `ty` is the constructed value's interned type (the
     /// layout key, distinguishing generic instances); `def` tags the allocation. When `ctor` is
     /// `Some`, `args` are the user constructor's arguments (the backend allocates, zeroes, then calls
@@ -306,6 +372,10 @@ pub enum Rvalue {
     /// A runtime type test `value is T`: compares the boxed value's `$object_tag` against the tag of
     /// `TypeId`. Yields `bool`.
     IsType(Operand, TypeId),
+    /// The exception that unwound into this landing block, as an owned (`+1`) `object`: the
+    /// thrower's reference is handed over. Only valid at the start of a block that is some block's
+    /// [`BasicBlock::unwind`] target.
+    Exception,
 }
 
 /// A resolved call target carried into MIR. The backend derives the emitted symbol from
@@ -322,11 +392,11 @@ pub struct Callee {
 /// and `Callee::{def, args}`.
 type FnKey = (DefId, Vec<TypeId>);
 
-/// Records every callable this rvalue statically references (direct calls, first-class function
-/// refs, and user constructors) into `out`.
+/// Records every callable this rvalue statically references (direct calls, closure functions, and
+/// user constructors) into `out`.
 fn rvalue_callees(rv: &Rvalue, out: &mut Vec<FnKey>) {
     match rv {
-        Rvalue::Call { callee, .. } | Rvalue::FuncRef(callee) => {
+        Rvalue::Call { callee, .. } | Rvalue::Closure { func: callee, .. } => {
             out.push((callee.def, callee.args.clone()))
         }
         Rvalue::New { ctor: Some(ctor), .. } => out.push((*ctor, vec![])),
@@ -360,9 +430,10 @@ pub(crate) fn hir_body_edges(body: &[crate::hir::HStmt], out: &mut HirEdges) {
 fn hir_stmt_edges(stmt: &crate::hir::HStmt, out: &mut HirEdges) {
     use crate::hir::{HPlace, HStmt};
     match stmt {
-        HStmt::Let { value, .. } | HStmt::Expr(value) | HStmt::Await(value) => {
-            hir_expr_edges(value, out)
-        }
+        HStmt::Let { value, .. }
+        | HStmt::Expr(value)
+        | HStmt::Await(value)
+        | HStmt::Throw(value) => hir_expr_edges(value, out),
         HStmt::Assign { place, value } => {
             match place {
                 HPlace::Field { obj, .. } => hir_expr_edges(obj, out),
@@ -408,7 +479,14 @@ fn hir_stmt_edges(stmt: &crate::hir::HStmt, out: &mut HirEdges) {
             }
             hir_body_edges(default, out);
         }
-        HStmt::Break(_) | HStmt::Continue(_) => {}
+        HStmt::Try { body, catches, finally, .. } => {
+            hir_body_edges(body, out);
+            for catch in catches {
+                hir_body_edges(&catch.body, out);
+            }
+            hir_body_edges(finally, out);
+        }
+        HStmt::Break(_) | HStmt::Continue(_) | HStmt::Line(_) => {}
     }
 }
 
@@ -434,6 +512,13 @@ fn hir_expr_edges(e: &crate::hir::HExpr, out: &mut HirEdges) {
                 hir_expr_edges(a, out);
             }
         }
+        K::Closure { func, env, captures } => {
+            out.callees.push((func.def, func.instance.clone()));
+            out.types.push(*env);
+            for c in captures {
+                hir_expr_edges(c, out);
+            }
+        }
         K::InterfaceCall { receiver, iface_id, method_slot, args, .. } => {
             out.iface_calls.push((*iface_id, *method_slot));
             hir_expr_edges(receiver, out);
@@ -456,7 +541,10 @@ fn hir_expr_edges(e: &crate::hir::HExpr, out: &mut HirEdges) {
                 hir_expr_edges(a, out);
             }
         }
-        K::Binary { lhs, rhs, .. } | K::Concat(lhs, rhs) | K::Coalesce { lhs, rhs } => {
+        K::Binary { lhs, rhs, .. }
+        | K::Concat(lhs, rhs)
+        | K::Coalesce { lhs, rhs }
+        | K::Let { value: lhs, body: rhs, .. } => {
             hir_expr_edges(lhs, out);
             hir_expr_edges(rhs, out);
         }
@@ -504,10 +592,10 @@ fn hir_expr_edges(e: &crate::hir::HExpr, out: &mut HirEdges) {
 /// `JsonValue`, …) that are merged into every program; carrying them into the module would force the
 /// assembler to resolve dead code that may reference runtime pieces the MIR backend has not wired yet.
 ///
-/// Reachability starts from `main` and the synthesized global initializer and follows direct calls,
-/// `FuncRef`s, and constructors. An `IndirectCall` has no static target, but its only possible
-/// targets are functions whose address was taken by a `FuncRef` in reachable code — which the
-/// `FuncRef` edges already keep — so the result stays sound.
+/// Reachability starts from `main`, the synthesized global initializer, and the `@test` functions of
+/// a test build, and follows direct calls, `Closure`s, and constructors. An `IndirectCall` has no
+/// static target, but its only possible targets are functions wrapped by a `Closure` in reachable
+/// code — which the `Closure` edges already keep — so the result stays sound.
 pub fn prune_unreachable(mir: &mut Mir) {
     use std::collections::{HashMap, HashSet};
 
@@ -532,7 +620,11 @@ pub fn prune_unreachable(mir: &mut Mir) {
         .functions
         .iter()
         .enumerate()
-        .filter(|(_, f)| f.name == "main" || f.name == lower::INIT_FN_NAME)
+        .filter(|(_, f)| {
+            f.name == "main"
+                || f.name == lower::INIT_FN_NAME
+                || (f.instance.is_empty() && mir.tests.contains(&f.def))
+        })
         .map(|(i, _)| i)
         .collect();
 
@@ -558,9 +650,9 @@ pub fn prune_unreachable(mir: &mut Mir) {
                                 iface_uses.push((*iface_id, *method_slot));
                             }
                             match rv {
-                                Rvalue::New { ty, .. } | Rvalue::UnionNew { ty, .. } => {
-                                    type_worklist.push(*ty)
-                                }
+                                Rvalue::New { ty, .. }
+                                | Rvalue::UnionNew { ty, .. }
+                                | Rvalue::Closure { env: ty, .. } => type_worklist.push(*ty),
                                 _ => {}
                             }
                         }
@@ -620,6 +712,8 @@ pub fn prune_unreachable(mir: &mut Mir) {
             if let Some(l) = mir.layouts.structs.get(&ty) {
                 names.push(l.name.clone());
                 field_tys.extend(l.fields.iter().map(|f| f.ty));
+                // A subclass's release chains into its base classes' destructors.
+                field_tys.extend(l.base);
             }
             if let Some(l) = mir.layouts.unions.get(&ty) {
                 names.push(l.name.clone());
@@ -680,6 +774,7 @@ mod tests {
             ret: int,
             locals: vec![],
             is_async: false,
+            source: None,
             body: vec![HStmt::Return(Some(HExpr::new(
                 int,
                 HExprKind::Binary {
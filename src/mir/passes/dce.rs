@@ -4,7 +4,7 @@
 
 use super::MirPass;
 use crate::mir::{
-    BlockId, Local, MirFunction, Operand, Place, Rvalue, Statement, Terminator,
+    BlockId, Local, MirFunction, Operand, Place, Rvalue, RuntimeCheck, Statement, Terminator,
 };
 use crate::types::TypeInterner;
 use std::collections::HashSet;
@@ -45,7 +45,7 @@ fn reachable_blocks(func: &MirFunction) -> HashSet<BlockId> {
         if !seen.insert(b) {
             continue;
         }
-        for s in func.block(b).terminator.successors() {
+        for s in func.block(b).successors() {
             stack.push(s);
         }
     }
@@ -88,7 +88,6 @@ fn is_pure(rvalue: &Rvalue) -> bool {
             | Rvalue::IsType(..)
             | Rvalue::Discriminant(_)
             | Rvalue::UnionField { .. }
-            | Rvalue::FuncRef(_)
     )
 }
 
@@ -111,12 +110,25 @@ fn read_stmt(stmt: &Statement, read: &mut HashSet<Local>) {
         }
         Statement::Retain(o) | Statement::Release(o) => read_operand(o, read),
         Statement::Call { args, .. } => args.iter().for_each(|a| read_operand(a, read)),
-        Statement::InterfaceCall { receiver, args, .. } => {
+        Statement::IndirectCall { target: receiver, args }
+        | Statement::InterfaceCall { receiver, args, .. } => {
             read_operand(receiver, read);
             args.iter().for_each(|a| read_operand(a, read));
         }
         Statement::Print { arg, .. } => read_operand(arg, read),
-        Statement::Nop => {}
+        Statement::Check(RuntimeCheck::Bounds { base, index }) => {
+            read.insert(*base);
+            read_operand(index, read);
+        }
+        Statement::Check(RuntimeCheck::StrBounds { base, index }) => {
+            read_operand(base, read);
+            read_operand(index, read);
+        }
+        Statement::Check(RuntimeCheck::NotNull { base, .. }) => {
+            read.insert(*base);
+        }
+        Statement::Check(RuntimeCheck::Downcast { value, .. }) => read_operand(value, read),
+        Statement::Line(_) | Statement::Nop => {}
     }
 }
 
@@ -154,7 +166,8 @@ fn read_rvalue(rvalue: &Rvalue, read: &mut HashSet<Local>) {
         Rvalue::ArrayNew { len, .. } => read_operand(len, read),
         Rvalue::Unary(_, a) => read_operand(a, read),
         Rvalue::Call { args, .. } | Rvalue::New { args, .. } | Rvalue::UnionNew { args, .. }
-        | Rvalue::ArrayLit { elems: args, .. } => args.iter().for_each(|a| read_operand(a, read)),
+        | Rvalue::ArrayLit { elems: args, .. }
+        | Rvalue::Closure { captures: args, .. } => args.iter().for_each(|a| read_operand(a, read)),
         Rvalue::IndirectCall { target, args } => {
             read_operand(target, read);
             args.iter().for_each(|a| read_operand(a, read));
@@ -163,7 +176,7 @@ fn read_rvalue(rvalue: &Rvalue, read: &mut HashSet<Local>) {
             read_operand(receiver, read);
             args.iter().for_each(|a| read_operand(a, read));
         }
-        Rvalue::FuncRef(_) => {}
+        Rvalue::Exception => {}
     }
 }
 
@@ -171,7 +184,7 @@ fn read_terminator(t: &Terminator, read: &mut HashSet<Local>) {
     match t {
         Terminator::If { cond, .. } => read_operand(cond, read),
         Terminator::Switch { value, .. } => read_operand(value, read),
-        Terminator::Return(Some(o)) => read_operand(o, read),
+        Terminator::Return(Some(o)) | Terminator::Throw(o) => read_operand(o, read),
         Terminator::AsyncComplete(Some(o)) => read_operand(o, read),
         _ => {}
     }
@@ -4,7 +4,7 @@
 //! without SSA phi handling.
 
 use super::MirPass;
-use crate::mir::{Local, MirFunction, Operand, Place, Rvalue, Statement, Terminator};
+use crate::mir::{Local, MirFunction, Operand, Place, Rvalue, RuntimeCheck, Statement, Terminator};
 use crate::types::TypeInterner;
 use std::collections::HashMap;
 
@@ -65,7 +65,8 @@ fn subst_stmt_reads(stmt: &mut Statement, known: &HashMap<Local, Operand>) -> bo
         }
         Statement::Retain(o) | Statement::Release(o) => subst_operand(o, known),
         Statement::Call { args, .. } => args.iter_mut().fold(false, |c, a| c | subst_operand(a, known)),
-        Statement::InterfaceCall { receiver, args, .. } => {
+        Statement::IndirectCall { target: receiver, args }
+        | Statement::InterfaceCall { receiver, args, .. } => {
             let mut c = subst_operand(receiver, known);
             for a in args {
                 c |= subst_operand(a, known);
@@ -73,7 +74,15 @@ fn subst_stmt_reads(stmt: &mut Statement, known: &HashMap<Local, Operand>) -> bo
             c
         }
         Statement::Print { arg, .. } => subst_operand(arg, known),
-        Statement::Nop => false,
+        // A checked array/field base stays a local, like the place it guards.
+        Statement::Check(RuntimeCheck::Bounds { index, .. }) => subst_operand(index, known),
+        Statement::Check(RuntimeCheck::StrBounds { base, index }) => {
+            subst_operand(base, known) | subst_operand(index, known)
+        }
+        Statement::Check(RuntimeCheck::Downcast { value, .. }) => subst_operand(value, known),
+        Statement::Check(RuntimeCheck::NotNull { .. }) | Statement::Line(_) | Statement::Nop => {
+            false
+        }
     }
 }
 
@@ -95,7 +104,8 @@ fn subst_rvalue_reads(rvalue: &mut Rvalue, known: &HashMap<Local, Operand>) -> b
         Rvalue::ArrayNew { len, .. } => subst_operand(len, known),
         Rvalue::Unary(_, a) => subst_operand(a, known),
         Rvalue::Call { args, .. } | Rvalue::New { args, .. } | Rvalue::UnionNew { args, .. }
-        | Rvalue::ArrayLit { elems: args, .. } => {
+        | Rvalue::ArrayLit { elems: args, .. }
+        | Rvalue::Closure { captures: args, .. } => {
             args.iter_mut().fold(false, |c, a| c | subst_operand(a, known))
         }
         Rvalue::IndirectCall { target, args } => {
@@ -112,7 +122,7 @@ fn subst_rvalue_reads(rvalue: &mut Rvalue, known: &HashMap<Local, Operand>) -> b
             }
             c
         }
-        Rvalue::FuncRef(_) => false,
+        Rvalue::Exception => false,
     }
 }
 
@@ -120,7 +130,7 @@ fn subst_terminator_reads(t: &mut Terminator, known: &HashMap<Local, Operand>) -
     match t {
         Terminator::If { cond, .. } => subst_operand(cond, known),
         Terminator::Switch { value, .. } => subst_operand(value, known),
-        Terminator::Return(Some(o)) => subst_operand(o, known),
+        Terminator::Return(Some(o)) | Terminator::Throw(o) => subst_operand(o, known),
         Terminator::AsyncComplete(Some(o)) => subst_operand(o, known),
         _ => false,
     }
@@ -6,15 +6,16 @@
 //! 1. *Local assignment* — when a borrowed reference is copied into a reference local it inserts a
 //!    `Retain` (the local becomes a new owner); before a reference local is overwritten it inserts a
 //!    `Release` of the previous value (releasing the zero-initialized null on first assignment is a
-//!    runtime no-op). Owned producers (call results, `new`, array literals) already carry their
-//!    `+1`, so they are not retained.
+//!    runtime no-op). Owned producers (call results, `new`, array literals, closures) already carry
+//!    their `+1`, so they are not retained.
 //! 2. *Container stores* (handled in the emitter, not here) retain a borrowed reference written into
 //!    a struct field / array element / union payload, so the container owns its own count and the
 //!    source local keeps its own.
 //! 3. *Scope exit* — at every `Return`, release each non-parameter reference local. The returned
 //!    value is excluded: an owned local transfers its `+1` to the caller, and a borrowed return
 //!    (parameter, field, or element read) is spilled to a fresh temporary and retained so it
-//!    survives the releases and hands the caller a `+1`.
+//!    survives the releases and hands the caller a `+1`. A `Throw` is treated the same way, its
+//!    value going to the catching frame (see [`crate::mir::unwind`]).
 //!
 //! Parameters are borrowed (the caller owns them), so they are never released at scope exit and call
 //! arguments are not retained — a self-consistent ABI: callee-owns-none-of-its-params,
@@ -56,12 +57,17 @@ impl MirPass for RcInsertion {
         let mut extra_locals: Vec<LocalDecl> = Vec::new();
         let temp_base = func.locals.len() as u32;
         for block in &mut func.blocks {
+            let protected = block.unwind.is_some();
             let mut out: Vec<Statement> = Vec::with_capacity(block.stmts.len());
             for stmt in block.stmts.drain(..) {
+                // In a block that can unwind, a call that throws must leave its destination's old
+                // value owned (the unwind path releases it), so it is released only afterwards too.
                 let ref_dest = match &stmt {
-                    Statement::Assign(Place::Local(dest), rvalue) if is_owned_ref(dest.0) => {
-                        Some((*dest, is_borrowed_copy(rvalue, interner), rvalue_reads_local(rvalue, dest.0)))
-                    }
+                    Statement::Assign(Place::Local(dest), rvalue) if is_owned_ref(dest.0) => Some((
+                        *dest,
+                        is_borrowed_copy(rvalue, interner),
+                        rvalue_reads_local(rvalue, dest.0) || (protected && may_throw(rvalue)),
+                    )),
                     _ => None,
                 };
                 match ref_dest {
@@ -98,25 +104,38 @@ impl MirPass for RcInsertion {
         // excludes them from Rule 3 below).
         func.locals.extend(extra_locals);
 
-        // Rule 3: scope-exit release at every `Return`.
+        // Rule 3: scope-exit release at every `Return` and every `Throw` (which hands its value to
+        // the catching frame the same way).
         let owned_locals: Vec<u32> = (0..func.locals.len() as u32).filter(|i| is_owned_ref(*i)).collect();
         let ret_is_ref = interner.is_reference(func.ret);
         let mut spills: Vec<LocalDecl> = Vec::new();
         let next_local = func.locals.len() as u32;
         for block in &mut func.blocks {
-            let Terminator::Return(ret) = &block.terminator else { continue };
+            let (ret, ty) = match &block.terminator {
+                Terminator::Return(ret) => (ret.clone(), func.ret),
+                Terminator::Throw(value) => (Some(value.clone()), interner.object()),
+                _ => continue,
+            };
+            let is_ref = match block.terminator {
+                Terminator::Return(_) => ret_is_ref,
+                _ => true,
+            };
             // Decide whether the return value transfers (owned local) or must be spilled + retained.
             let (skip, spill_from): (Option<u32>, Option<Operand>) = match ret {
                 Some(Operand::Copy(Place::Local(l))) if is_owned_ref(l.0) => (Some(l.0), None),
-                Some(op) if ret_is_ref => (None, Some(op.clone())),
+                Some(op) if is_ref => (None, Some(op)),
                 _ => (None, None),
             };
             let skip = if let Some(op) = spill_from {
                 let temp = Local(next_local + spills.len() as u32);
-                spills.push(LocalDecl { ty: func.ret, name: None });
+                spills.push(LocalDecl { ty, name: None });
                 block.stmts.push(Statement::Assign(Place::Local(temp), Rvalue::Use(op)));
                 block.stmts.push(Statement::Retain(Operand::Copy(Place::Local(temp))));
-                block.terminator = Terminator::Return(Some(Operand::Copy(Place::Local(temp))));
+                let temp_op = Operand::Copy(Place::Local(temp));
+                block.terminator = match block.terminator {
+                    Terminator::Throw(_) => Terminator::Throw(temp_op),
+                    _ => Terminator::Return(Some(temp_op)),
+                };
                 changed = true;
                 Some(temp.0)
             } else {
@@ -185,7 +204,8 @@ fn rvalue_reads_local(rvalue: &Rvalue, local: u32) -> bool {
         Rvalue::Call { args, .. }
         | Rvalue::New { args, .. }
         | Rvalue::UnionNew { args, .. }
-        | Rvalue::ArrayLit { elems: args, .. } => args.iter().for_each(&mut check),
+        | Rvalue::ArrayLit { elems: args, .. }
+        | Rvalue::Closure { captures: args, .. } => args.iter().for_each(&mut check),
         Rvalue::IndirectCall { target, args } => {
             check(target);
             args.iter().for_each(&mut check);
@@ -194,11 +214,24 @@ fn rvalue_reads_local(rvalue: &Rvalue, local: u32) -> bool {
             check(receiver);
             args.iter().for_each(&mut check);
         }
-        Rvalue::FuncRef(_) => {}
+        Rvalue::Exception => {}
     }
     hit
 }
 
+/// True if evaluating the rvalue can raise an exception (it runs a call).
+fn may_throw(rvalue: &Rvalue) -> bool {
+    matches!(
+        rvalue,
+        Rvalue::Call { .. }
+            | Rvalue::IndirectCall { .. }
+            | Rvalue::InterfaceCall { .. }
+            | Rvalue::New { ctor: Some(_), .. }
+            | Rvalue::ToString(_)
+            | Rvalue::HashCode(_)
+    )
+}
+
 fn is_borrowed_copy(rvalue: &Rvalue, interner: &TypeInterner) -> bool {
     match rvalue {
         Rvalue::Use(Operand::Copy(_))
@@ -209,8 +242,10 @@ fn is_borrowed_copy(rvalue: &Rvalue, interner: &TypeInterner) -> bool {
         // A reference-to-reference cast (e.g. `(Animal)cat`, an interface up/downcast) is pointer
         // identity: the destination local aliases the source's reference, so it is a borrow and must
         // be retained to balance its scope-exit release — otherwise the shared pointer is
-        // double-freed. Reference→primitive unboxes and numeric casts produce fresh values.
-        Rvalue::Cast(Operand::Copy(_), from, to) => {
+        // double-freed. The same holds for a string literal viewed as `object` (`let o: object =
+        // "x"`), which shares the literal's static block. Reference→primitive unboxes and numeric
+        // casts produce fresh values.
+        Rvalue::Cast(Operand::Copy(_) | Operand::Const(crate::mir::Const::Str(_)), from, to) => {
             interner.is_reference(*from) && interner.is_reference(*to)
         }
         _ => false,
@@ -3,7 +3,7 @@
 //! `is`-folding the old backend did inline.
 
 use super::MirPass;
-use crate::mir::{BlockId, Const, MirFunction, Operand, Terminator};
+use crate::mir::{BlockId, Const, MirFunction, Operand, Statement, Terminator};
 use crate::types::TypeInterner;
 
 pub struct SimplifyCfg;
@@ -45,14 +45,16 @@ fn fold_constant_branches(func: &mut MirFunction) -> bool {
     changed
 }
 
-/// Replaces `goto t` with `t`'s terminator when `t` is an empty forwarding block, collapsing chains
-/// of trivial jumps. Self-targets are left alone to avoid spinning on empty self-loops.
+/// Replaces `goto t` with `t`'s terminator when `t` is an empty forwarding block (source-line markers
+/// aside), collapsing chains of trivial jumps. Self-targets are left alone to avoid spinning on empty
+/// self-loops.
 fn thread_empty_jumps(func: &mut MirFunction) -> bool {
     let mut changed = false;
     for i in 0..func.blocks.len() {
         let here = BlockId(i as u32);
         if let Terminator::Goto(t) = func.blocks[i].terminator {
-            if t != here && func.block(t).stmts.is_empty() {
+            let forwards = func.block(t).stmts.iter().all(|s| matches!(s, Statement::Line(_)));
+            if t != here && forwards {
                 let forwarded = func.block(t).terminator.clone();
                 // Only thread when it actually changes the target (avoid no-op churn / cycles).
                 if !matches!(&forwarded, Terminator::Goto(u) if *u == t) {
@@ -2,10 +2,16 @@
 //! order with their statements and terminator.
 
 use super::{
-    BasicBlock, Const, MirFunction, Operand, Place, Rvalue, Statement, Terminator,
+    BasicBlock, Const, Mir, MirFunction, Operand, Place, Rvalue, RuntimeCheck, Statement, Terminator,
 };
 use std::fmt::Write;
 
+/// Dumps every function of a lowered program, separated by blank lines.
+pub fn print_program(mir: &Mir) -> String {
+    let funcs: Vec<String> = mir.functions.iter().map(print_function).collect();
+    funcs.join("\n")
+}
+
 pub fn print_function(func: &MirFunction) -> String {
     let mut out = String::new();
     let params: Vec<String> = func.params.iter().map(|l| format!("_{}", l.0)).collect();
@@ -19,6 +25,9 @@ pub fn print_function(func: &MirFunction) -> String {
 }
 
 fn print_block(out: &mut String, block: &BasicBlock) {
+    if let Some(unwind) = block.unwind {
+        let _ = writeln!(out, "    unwind bb{}", unwind.0);
+    }
     for s in &block.stmts {
         let _ = writeln!(out, "    {}", stmt(s));
     }
@@ -33,6 +42,9 @@ fn stmt(s: &Statement) -> String {
         Statement::Call { callee, args } => {
             format!("call def{}({})", callee.def.0, ops(args))
         }
+        Statement::IndirectCall { target, args } => {
+            format!("call_indirect {}({})", operand(target), ops(args))
+        }
         Statement::InterfaceCall { receiver, iface_id, method_slot, args, .. } => {
             format!(
                 "iface_call I{}#{} {}({})",
@@ -46,6 +58,19 @@ fn stmt(s: &Statement) -> String {
             let f = if *newline { "println" } else { "print" };
             format!("{}({})", f, operand(arg))
         }
+        Statement::Check(RuntimeCheck::Bounds { base, index }) => {
+            format!("check _{}[{}]", base.0, operand(index))
+        }
+        Statement::Check(RuntimeCheck::StrBounds { base, index }) => {
+            format!("check {}[{}]", operand(base), operand(index))
+        }
+        Statement::Check(RuntimeCheck::NotNull { base, field }) => {
+            format!("check _{}.{} not null", base.0, field)
+        }
+        Statement::Check(RuntimeCheck::Downcast { value, target, .. }) => {
+            format!("check {} is ty{}", operand(value), target.0)
+        }
+        Statement::Line(line) => format!("line {}", line),
         Statement::Nop => "nop".to_string(),
     }
 }
@@ -69,6 +94,7 @@ fn terminator(t: &Terminator) -> String {
             "async_complete{}",
             v.as_ref().map(operand).unwrap_or_default()
         ),
+        Terminator::Throw(o) => format!("throw {}", operand(o)),
         Terminator::Unreachable => "unreachable".to_string(),
     }
 }
@@ -109,10 +135,13 @@ fn rvalue(r: &Rvalue) -> String {
         Rvalue::Cast(o, from, ty) => format!("{} as ty{} (from ty{})", operand(o), ty.0, from.0),
         Rvalue::Discriminant(o) => format!("discriminant({})", operand(o)),
         Rvalue::IsType(o, ty) => format!("{} is ty{}", operand(o), ty.0),
+        Rvalue::Exception => "exception".to_string(),
         Rvalue::UnionField { base, variant, field, .. } => {
             format!("{}#{}.{}", operand(base), variant, field)
         }
-        Rvalue::FuncRef(callee) => format!("funcref def{}", callee.def.0),
+        Rvalue::Closure { func, captures, .. } => {
+            format!("closure def{}[{}]", func.def.0, ops(captures))
+        }
     }
 }
 
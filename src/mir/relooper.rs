@@ -46,7 +46,6 @@ impl Relooper<'_> {
     fn succs(&self, b: BlockId, headers: &BTreeSet<BlockId>) -> Vec<BlockId> {
         self.func
             .block(b)
-            .terminator
             .successors()
             .into_iter()
             .filter(|s| !headers.contains(s))
@@ -5,6 +5,15 @@
     (local $block_size i32)
     (local $new_ptr i32)
     ;;@DEBUG_ALLOC_COUNT@
+    ;;@CC_ALLOC_HOOK@
+    ;; a request this large (or a negative size) can never be satisfied
+    local.get $size
+    i32.const 0x7ffffff0
+    i32.gt_u
+    (if (then
+        local.get $size
+        call $out_of_memory
+    ))
     ;; round size up to a multiple of 4, then reserve 12 bytes for the header
     local.get $size
     i32.const 3
@@ -84,7 +93,10 @@
             br $scan_freelist
         )
     )
-    ;; no free block fit: bump-allocate fresh memory
+    ;; no free block fit: bump-allocate fresh memory, growing linear memory first if needed
+    global.get $heap_ptr
+    local.get $size
+    call $ensure_heap
     global.get $heap_ptr
     local.set $new_ptr
     global.get $heap_ptr
@@ -109,11 +121,88 @@
     i32.add
 )
 
+;; Grows linear memory so `[0, base + size)` is addressable. Growth is geometric (at least doubling
+;; the current size) to amortize `memory.grow`, but never past `$heap_max_pages`; a request that
+;; cannot fit under that cap, or a failed `memory.grow`, traps as out of memory.
+(func $ensure_heap (param $base i32) (param $size i32)
+    (local $need i64)
+    (local $have i32)
+    (local $grow i32)
+    ;; pages needed to cover the end of the block (computed in i64 so it cannot wrap)
+    local.get $base
+    i64.extend_i32_u
+    local.get $size
+    i64.extend_i32_u
+    i64.add
+    i64.const 65535
+    i64.add
+    i64.const 16
+    i64.shr_u
+    local.set $need
+    memory.size
+    local.set $have
+    local.get $need
+    local.get $have
+    i64.extend_i32_u
+    i64.le_u
+    br_if 0
+    local.get $need
+    global.get $heap_max_pages
+    i64.extend_i32_u
+    i64.gt_u
+    (if (then
+        local.get $size
+        call $out_of_memory
+    ))
+    ;; grow by max(need - have, have), capped at the maximum
+    local.get $need
+    i32.wrap_i64
+    local.get $have
+    i32.sub
+    local.set $grow
+    local.get $have
+    local.get $grow
+    i32.gt_u
+    (if (then
+        local.get $have
+        local.set $grow
+    ))
+    local.get $have
+    local.get $grow
+    i32.add
+    global.get $heap_max_pages
+    i32.gt_u
+    (if (then
+        global.get $heap_max_pages
+        local.get $have
+        i32.sub
+        local.set $grow
+    ))
+    local.get $grow
+    memory.grow
+    i32.const -1
+    i32.eq
+    (if (then
+        local.get $size
+        call $out_of_memory
+    ))
+)
+
+;; Records the failed request size (never 0, so the host can tell an out-of-memory trap from any
+;; other) and traps.
+(func $out_of_memory (param $size i32)
+    local.get $size
+    global.set $oom_request
+    ;;@OOM_REPORT@
+    unreachable
+)
+
 (func $free (param $ptr i32)
     (local $block_start i32)
     local.get $ptr
     i32.eqz
     br_if 0
+    ;;@CC_FREE_HOOK@
     ;;@DEBUG_FREE_COUNT@
     local.get $ptr
     i32.const 12
@@ -178,6 +267,7 @@
     local.get $new_count
     i32.store
     local.get $new_count
+    ;;@RC_COUNT_MASK@
     i32.eqz
     (if (then
         local.get $ptr
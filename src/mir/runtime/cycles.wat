@@ -0,0 +1,721 @@
+;; Synchronous trial-deletion cycle collector (Bacon & Rajan, "Concurrent Cycle Collection in
+;; Reference Counted Systems", section 3), emitted only into modules built with cycle collection.
+;;
+;; The collector keeps its per-object state in the high bits of the refcount word at `ptr - 4`:
+;;
+;;   bits 0-27   reference count
+;;   bits 28-29  color: 0 black (in use), 1 gray (trial-deleted), 2 white (garbage), 3 purple (root)
+;;   bit  30     buffered: the object sits in the roots buffer
+;;   bit  31     the array holds traceable references (set by its typed release / a typed field)
+;;
+;; A *traced* object is a struct/union whose type can reach a cycle, or a flagged array; everything
+;; else (strings, boxed primitives, scalar arrays, acyclic structs) is a leaf the collector never
+;; trial-deletes. `$cc_traced`/`$cc_children` are generated per module from the type layouts.
+;; `$cc_children(ptr, op)` applies `$cc_edge(child, op)` to every traced child of `ptr`, where `op`
+;; is 0 mark-gray, 1 scan, 2 scan-black or 3 collect; under collect, a leaf child it returns 1 for is
+;; released by the caller through its statically typed release.
+;;
+;; Every freed block gets the DEAD word (gray with count 0, a state no live object holds outside
+;; the mark/scan passes), so a roots-buffer entry left behind by a freed object is recognized and
+;; dropped; a block reused since holds a fresh count and is handled like any other entry.
+;;
+;; The passes are iterative over a work stack carved from the bump region (never from the freelist),
+;; so neither deep structures nor a `del()` allocating mid-collection can disturb them. Garbage is
+;; marked DEAD and chained through its tag word, then freed after the collect pass; collection
+;; never runs `del()` on it (see docs/memory.md and the cycle tests in tests/cli_tests.rs).
+
+(global $cc_roots (mut i32) (i32.const 0))
+(global $cc_roots_len (mut i32) (i32.const 0))
+(global $cc_roots_cap (mut i32) (i32.const 0))
+(global $cc_stack (mut i32) (i32.const 0))
+(global $cc_stack_cap (mut i32) (i32.const 0))
+(global $cc_stack_len (mut i32) (i32.const 0))
+(global $cc_garbage (mut i32) (i32.const 0))
+(global $cc_allocations (mut i32) (i32.const 0))
+(global $cc_running (mut i32) (i32.const 0))
+(global $cc_freed (mut i32) (i32.const 0))
+
+;; Records a nonzero decrement of a traced object: it may now be the only thing keeping a cycle
+;; alive, so it turns purple and (once) joins the roots buffer. A full buffer doubles and schedules
+;; a collection for the next allocation.
+(func $cc_possible_root (param $ptr i32)
+    (local $rc i32)
+    (local $cap i32)
+    (local $data i32)
+    global.get $cc_running
+    br_if 0
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    local.set $rc
+    local.get $rc
+    i32.load
+    i32.const 0x40000000
+    i32.and
+    (if (then
+        local.get $rc
+        local.get $rc
+        i32.load
+        i32.const 0x30000000
+        i32.or
+        i32.store
+        return
+    ))
+    global.get $cc_roots_len
+    global.get $cc_roots_cap
+    i32.eq
+    (if (then
+        ;; grow the buffer, and if it was full, collect at the next allocation rather than here:
+        ;; this may be deep inside a release cascade whose pinned parents still hold counted
+        ;; references into the structure being decremented
+        global.get $cc_roots_cap
+        i32.const 1
+        i32.shl
+        i32.const {ROOTS_CAPACITY}
+        global.get $cc_roots_cap
+        select
+        local.set $cap
+        local.get $cap
+        i32.const 4
+        i32.mul
+        i32.const 12
+        i32.add
+        call $cc_carve
+        local.set $data
+        local.get $data
+        global.get $cc_roots
+        global.get $cc_roots_len
+        i32.const 4
+        i32.mul
+        memory.copy
+        global.get $cc_roots
+        (if (then
+            global.get $cc_roots
+            i32.const 8
+            i32.sub
+            global.get $free_list_head
+            i32.store
+            global.get $cc_roots
+            i32.const 12
+            i32.sub
+            global.set $free_list_head
+            i32.const {ALLOC_THRESHOLD}
+            global.set $cc_allocations
+        ))
+        local.get $data
+        global.set $cc_roots
+        local.get $cap
+        global.set $cc_roots_cap
+    ))
+    local.get $rc
+    local.get $rc
+    i32.load
+    i32.const 0x70000000
+    i32.or
+    i32.store
+    global.get $cc_roots
+    global.get $cc_roots_len
+    i32.const 4
+    i32.mul
+    i32.add
+    local.get $ptr
+    i32.store
+    global.get $cc_roots_len
+    i32.const 1
+    i32.add
+    global.set $cc_roots_len
+)
+
+;; Pins a traced object whose count reached zero at count 1 and black while its deep release runs,
+;; so neither `del()` nor a collection triggered meanwhile can free or trace it.
+(func $cc_pin (param $ptr i32)
+    (local $rc i32)
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    local.tee $rc
+    local.get $rc
+    i32.load
+    i32.const 0xc0000000
+    i32.and
+    i32.const 1
+    i32.or
+    i32.store
+)
+
+;; Counts an allocation; every {ALLOC_THRESHOLD} of them, a pending roots buffer is collected.
+(func $cc_on_alloc
+    global.get $cc_allocations
+    i32.const 1
+    i32.add
+    global.set $cc_allocations
+    global.get $cc_allocations
+    i32.const {ALLOC_THRESHOLD}
+    i32.lt_u
+    br_if 0
+    i32.const 0
+    global.set $cc_allocations
+    global.get $cc_roots_len
+    (if (then
+        call $collect_cycles
+        drop
+    ))
+)
+
+;; Carves a `size`-byte block (header included) off the bump region, bypassing the freelist, and
+;; returns its data pointer.
+(func $cc_carve (param $size i32) (result i32)
+    (local $block i32)
+    global.get $heap_ptr
+    local.get $size
+    call $ensure_heap
+    global.get $heap_ptr
+    local.tee $block
+    local.get $size
+    i32.add
+    global.set $heap_ptr
+    local.get $block
+    local.get $size
+    i32.store
+    local.get $block
+    i32.const 12
+    i32.add
+)
+
+(func $cc_push (param $v i32)
+    (local $cap i32)
+    (local $data i32)
+    global.get $cc_stack_len
+    global.get $cc_stack_cap
+    i32.eq
+    (if (then
+        ;; double the stack into a fresh block; the old one goes to the freelist
+        global.get $cc_stack_cap
+        i32.const 1
+        i32.shl
+        i32.const 1024
+        global.get $cc_stack_cap
+        select
+        local.set $cap
+        local.get $cap
+        i32.const 4
+        i32.mul
+        i32.const 12
+        i32.add
+        call $cc_carve
+        local.set $data
+        local.get $data
+        global.get $cc_stack
+        global.get $cc_stack_len
+        i32.const 4
+        i32.mul
+        memory.copy
+        global.get $cc_stack
+        (if (then
+            global.get $cc_stack
+            i32.const 8
+            i32.sub
+            global.get $free_list_head
+            i32.store
+            global.get $cc_stack
+            i32.const 12
+            i32.sub
+            global.set $free_list_head
+        ))
+        local.get $data
+        global.set $cc_stack
+        local.get $cap
+        global.set $cc_stack_cap
+    ))
+    global.get $cc_stack
+    global.get $cc_stack_len
+    i32.const 4
+    i32.mul
+    i32.add
+    local.get $v
+    i32.store
+    global.get $cc_stack_len
+    i32.const 1
+    i32.add
+    global.set $cc_stack_len
+)
+
+;; Pops the work stack; 0 when it is empty.
+(func $cc_pop (result i32)
+    global.get $cc_stack_len
+    i32.eqz
+    (if (then
+        i32.const 0
+        return
+    ))
+    global.get $cc_stack_len
+    i32.const 1
+    i32.sub
+    global.set $cc_stack_len
+    global.get $cc_stack
+    global.get $cc_stack_len
+    i32.const 4
+    i32.mul
+    i32.add
+    i32.load
+)
+
+(func $cc_color (param $ptr i32) (result i32)
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    i32.load
+    i32.const 0x30000000
+    i32.and
+)
+
+(func $cc_count (param $ptr i32) (result i32)
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    i32.load
+    i32.const 0x0fffffff
+    i32.and
+)
+
+;; Sets the color bits of `ptr`'s refcount word.
+(func $cc_set_color (param $ptr i32) (param $color i32)
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    i32.load
+    i32.const 0xcfffffff
+    i32.and
+    local.get $color
+    i32.or
+    i32.store
+)
+
+;; Adds `delta` to the count of `ptr`.
+(func $cc_adjust (param $ptr i32) (param $delta i32)
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    i32.load
+    local.get $delta
+    i32.add
+    i32.store
+)
+
+;; Visits one edge `parent -> child` for `$cc_children`, queueing the child's own visit on the work
+;; stack. Returns 1 only under collect for a live leaf child, which the caller releases.
+(func $cc_edge (param $child i32) (param $op i32) (result i32)
+    local.get $child
+    i32.eqz
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $op
+    i32.const 3
+    i32.eq
+    (if (then
+        local.get $child
+        i32.const 4
+        i32.sub
+        i32.load
+        i32.const 0x10000000
+        i32.eq
+        (if (then
+            i32.const 0
+            return
+        ))
+    ))
+    local.get $child
+    call $cc_traced
+    i32.eqz
+    (if (then
+        local.get $op
+        i32.const 3
+        i32.eq
+        return
+    ))
+    (block $done
+        (block $collect
+            (block $scan_black
+                (block $scan
+                    (block $mark_gray
+                        local.get $op
+                        br_table $mark_gray $scan $scan_black $collect
+                    )
+                    ;; trial-delete the edge; a child not yet gray has its own edges to remove
+                    local.get $child
+                    i32.const -1
+                    call $cc_adjust
+                    local.get $child
+                    call $cc_color
+                    i32.const 0x10000000
+                    i32.ne
+                    (if (then
+                        local.get $child
+                        i32.const 0x10000000
+                        call $cc_set_color
+                        local.get $child
+                        call $cc_push
+                    ))
+                    br $done
+                )
+                local.get $child
+                call $cc_push
+                br $done
+            )
+            ;; restore the edge; a child not yet black is live too
+            local.get $child
+            i32.const 1
+            call $cc_adjust
+            local.get $child
+            call $cc_color
+            (if (then
+                local.get $child
+                i32.const 0
+                call $cc_set_color
+                local.get $child
+                i32.const 1
+                i32.or
+                call $cc_push
+            ))
+            br $done
+        )
+        local.get $child
+        call $cc_mark_dead
+    )
+    i32.const 0
+)
+
+;; Like `$cc_edge`, for a child statically typed as an array of traceable references: the child is
+;; flagged first, so it is traced even if it was never released through its typed release. Collect
+;; leaves the word alone (it may be DEAD); any array it reaches white was flagged while marking.
+(func $cc_edge_array (param $child i32) (param $op i32) (result i32)
+    local.get $child
+    i32.const 0
+    i32.ne
+    local.get $op
+    i32.const 3
+    i32.ne
+    i32.and
+    (if (then
+        local.get $child
+        i32.const 4
+        i32.sub
+        local.get $child
+        i32.const 4
+        i32.sub
+        i32.load
+        i32.const 0x80000000
+        i32.or
+        i32.store
+    ))
+    local.get $child
+    local.get $op
+    call $cc_edge
+)
+
+;; Trial deletion: removes the references internal to the subgraph reachable from `root`.
+(func $cc_mark_gray (param $root i32)
+    (local $s i32)
+    local.get $root
+    call $cc_color
+    i32.const 0x10000000
+    i32.eq
+    br_if 0
+    local.get $root
+    i32.const 0x10000000
+    call $cc_set_color
+    local.get $root
+    call $cc_push
+    (block $done
+        (loop $next
+            call $cc_pop
+            local.tee $s
+            i32.eqz
+            br_if $done
+            local.get $s
+            i32.const 0
+            call $cc_children
+            br $next
+        )
+    )
+)
+
+;; Decides the gray subgraph under `root`: an object still referenced from outside it is live, and
+;; turns black with everything it reaches (restoring their counts); one left at count 0 is garbage
+;; and turns white. Stack entries with bit 0 set are black objects whose children need restoring.
+(func $cc_scan (param $root i32)
+    (local $s i32)
+    local.get $root
+    call $cc_push
+    (block $done
+        (loop $next
+            call $cc_pop
+            local.tee $s
+            i32.eqz
+            br_if $done
+            local.get $s
+            i32.const 1
+            i32.and
+            (if (then
+                local.get $s
+                i32.const -2
+                i32.and
+                i32.const 2
+                call $cc_children
+                br $next
+            ))
+            local.get $s
+            call $cc_color
+            i32.const 0x10000000
+            i32.ne
+            br_if $next
+            local.get $s
+            call $cc_count
+            (if
+                (then
+                    local.get $s
+                    i32.const 0
+                    call $cc_set_color
+                    local.get $s
+                    i32.const 1
+                    i32.or
+                    call $cc_push
+                )
+                (else
+                    local.get $s
+                    i32.const 0x20000000
+                    call $cc_set_color
+                    local.get $s
+                    i32.const 1
+                    call $cc_children
+                )
+            )
+            br $next
+        )
+    )
+)
+
+;; Marks a white object DEAD and queues it; others are left alone.
+(func $cc_mark_dead (param $ptr i32)
+    local.get $ptr
+    call $cc_color
+    i32.const 0x20000000
+    i32.ne
+    br_if 0
+    local.get $ptr
+    i32.const 4
+    i32.sub
+    i32.const 0x10000000
+    i32.store
+    local.get $ptr
+    call $cc_push
+)
+
+;; Gathers the white subgraph under `root` onto the garbage chain: traced children are gathered in
+;; turn and leaf children released. A DEAD object's tag is no longer read, so it holds the chain.
+(func $cc_collect_white (param $root i32)
+    (local $s i32)
+    local.get $root
+    call $cc_mark_dead
+    (block $done
+        (loop $next
+            call $cc_pop
+            local.tee $s
+            i32.eqz
+            br_if $done
+            local.get $s
+            i32.const 3
+            call $cc_children
+            local.get $s
+            i32.const 8
+            i32.sub
+            global.get $cc_garbage
+            i32.store
+            local.get $s
+            global.set $cc_garbage
+            br $next
+        )
+    )
+)
+
+;; Runs one collection over the roots buffer and returns the number of objects it freed. Roots
+;; that are no longer purple (including freed ones) leave the buffer; the rest are trial-deleted,
+;; scanned, and their garbage freed. The buffer is empty afterwards.
+(func $collect_cycles (result i32)
+    (local $i i32)
+    (local $kept i32)
+    (local $s i32)
+    (local $word i32)
+    global.get $cc_running
+    (if (then
+        i32.const 0
+        return
+    ))
+    i32.const 1
+    global.set $cc_running
+    i32.const 0
+    global.set $cc_freed
+    ;; mark roots
+    (block $marked
+        (loop $mark
+            local.get $i
+            global.get $cc_roots_len
+            i32.ge_u
+            br_if $marked
+            global.get $cc_roots
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load
+            local.tee $s
+            i32.const 4
+            i32.sub
+            i32.load
+            local.set $word
+            local.get $word
+            i32.const 0x30000000
+            i32.and
+            i32.const 0x30000000
+            i32.eq
+            local.get $word
+            i32.const 0x0fffffff
+            i32.and
+            i32.const 0
+            i32.ne
+            i32.and
+            (if
+                (then
+                    local.get $s
+                    call $cc_mark_gray
+                    global.get $cc_roots
+                    local.get $kept
+                    i32.const 4
+                    i32.mul
+                    i32.add
+                    local.get $s
+                    i32.store
+                    local.get $kept
+                    i32.const 1
+                    i32.add
+                    local.set $kept
+                )
+                (else
+                    local.get $s
+                    i32.const 4
+                    i32.sub
+                    local.get $word
+                    i32.const 0xbfffffff
+                    i32.and
+                    i32.store
+                )
+            )
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $mark
+        )
+    )
+    ;; scan roots
+    i32.const 0
+    local.set $i
+    (block $scanned
+        (loop $scan
+            local.get $i
+            local.get $kept
+            i32.ge_u
+            br_if $scanned
+            global.get $cc_roots
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load
+            call $cc_scan
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $scan
+        )
+    )
+    ;; collect roots
+    i32.const 0
+    global.set $cc_roots_len
+    i32.const 0
+    local.set $i
+    (block $collected
+        (loop $collect
+            local.get $i
+            local.get $kept
+            i32.ge_u
+            br_if $collected
+            global.get $cc_roots
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load
+            local.tee $s
+            i32.const 4
+            i32.sub
+            i32.load
+            local.tee $word
+            i32.const 0x10000000
+            i32.ne
+            (if (then
+                local.get $s
+                i32.const 4
+                i32.sub
+                local.get $word
+                i32.const 0xbfffffff
+                i32.and
+                i32.store
+                local.get $s
+                call $cc_collect_white
+            ))
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $collect
+        )
+    )
+    ;; free the garbage
+    (block $freed
+        (loop $free
+            global.get $cc_garbage
+            local.tee $s
+            i32.eqz
+            br_if $freed
+            local.get $s
+            i32.const 8
+            i32.sub
+            i32.load
+            global.set $cc_garbage
+            local.get $s
+            call $free
+            global.get $cc_freed
+            i32.const 1
+            i32.add
+            global.set $cc_freed
+            br $free
+        )
+    )
+    i32.const 0
+    global.set $cc_running
+    global.get $cc_freed
+)
+
+(func $debug_collect_cycles (result i32)
+    call $collect_cycles
+)
@@ -128,3 +128,481 @@
     f64.promote_f32
     call $double_to_string
 )
+;; Fixed-point formatting for `double.to_fixed` and the `0.00` / `F2` item formats of
+;; `String.format`: `$v` rounded half away from zero to `$digits` decimal places (clamped to 0-15),
+;; written with exactly that many. A value that rounds to zero drops its sign. NaN and the
+;; infinities print as `NaN`, `Infinity` and `-Infinity`; magnitudes past the i64 range go through
+;; `$format_fixed_big`. The string is built in a single allocation, right to left.
+(func $format_fixed (param $v f64) (param $digits i32) (result i32)
+    (local $neg i32)
+    (local $pow i64)
+    (local $ip i64)
+    (local $fr i64)
+    (local $t i64)
+    (local $ilen i32)
+    (local $len i32)
+    (local $res i32)
+    (local $i i32)
+    ;; NaN is the only value unequal to itself
+    local.get $v
+    local.get $v
+    f64.ne
+    (if (then
+        i32.const 3
+        call $string_alloc
+        local.set $res
+        ;; 'N' 'a' as one little-endian halfword, then the final 'N'
+        local.get $res
+        i32.const 24910
+        i32.store16
+        local.get $res
+        i32.const 78
+        i32.store8 offset=2
+        local.get $res
+        return
+    ))
+    ;; clamp digits to [0, 15] so 10^digits stays exact in both f64 and i64
+    local.get $digits
+    i32.const 0
+    i32.lt_s
+    (if (then i32.const 0 local.set $digits))
+    local.get $digits
+    i32.const 15
+    i32.gt_s
+    (if (then i32.const 15 local.set $digits))
+    ;; pow = 10^digits
+    i64.const 1
+    local.set $pow
+    i32.const 0
+    local.set $i
+    (block $pdone
+        (loop $pgen
+            local.get $i
+            local.get $digits
+            i32.ge_s
+            br_if $pdone
+            local.get $pow
+            i64.const 10
+            i64.mul
+            local.set $pow
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $pgen
+        )
+    )
+    local.get $v
+    f64.const 0
+    f64.lt
+    (if (then
+        i32.const 1
+        local.set $neg
+        local.get $v
+        f64.neg
+        local.set $v
+    ))
+    local.get $v
+    f64.const inf
+    f64.eq
+    (if (then
+        local.get $neg
+        i32.const 8
+        i32.add
+        call $string_alloc
+        local.set $res
+        local.get $res
+        i32.const 45
+        i32.store8
+        ;; "Infinity" as one little-endian i64, after the '-' when there is one
+        local.get $res
+        local.get $neg
+        i32.add
+        i64.const 0x7974696e69666e49
+        i64.store
+        local.get $res
+        return
+    ))
+    ;; from 2^63 up the value is a whole number that no longer fits an i64
+    local.get $v
+    f64.const 0x1p63
+    f64.ge
+    (if (then
+        local.get $v
+        local.get $neg
+        local.get $digits
+        call $format_fixed_big
+        return
+    ))
+    ;; split off the integer part (exact below 2^63) and round only the fraction, so the scaling
+    ;; never overflows: fr = round((v - ip) * 10^digits), carrying into ip when it reaches 10^digits
+    local.get $v
+    i64.trunc_f64_s
+    local.set $ip
+    local.get $v
+    local.get $ip
+    f64.convert_i64_s
+    f64.sub
+    local.get $pow
+    f64.convert_i64_s
+    f64.mul
+    f64.const 0.5
+    f64.add
+    i64.trunc_f64_s
+    local.set $fr
+    local.get $fr
+    local.get $pow
+    i64.ge_s
+    (if (then
+        local.get $fr
+        local.get $pow
+        i64.sub
+        local.set $fr
+        local.get $ip
+        i64.const 1
+        i64.add
+        local.set $ip
+    ))
+    local.get $ip
+    local.get $fr
+    i64.or
+    i64.eqz
+    (if (then i32.const 0 local.set $neg))
+    ;; ilen = number of integer digits (at least one)
+    i32.const 1
+    local.set $ilen
+    local.get $ip
+    local.set $t
+    (block $cdone
+        (loop $cgen
+            local.get $t
+            i64.const 10
+            i64.lt_s
+            br_if $cdone
+            local.get $t
+            i64.const 10
+            i64.div_s
+            local.set $t
+            local.get $ilen
+            i32.const 1
+            i32.add
+            local.set $ilen
+            br $cgen
+        )
+    )
+    ;; len = sign + integer digits + ('.' + fraction digits, when there are any)
+    local.get $neg
+    local.get $ilen
+    i32.add
+    local.set $len
+    local.get $digits
+    (if (then
+        local.get $len
+        local.get $digits
+        i32.const 1
+        i32.add
+        i32.add
+        local.set $len
+    ))
+    local.get $len
+    call $string_alloc
+    local.set $res
+    local.get $len
+    local.set $i
+    ;; fraction digits, least significant last, then the '.'
+    local.get $digits
+    (if (then
+        (block $fdone
+            (loop $fgen
+                local.get $i
+                local.get $len
+                local.get $digits
+                i32.sub
+                i32.le_s
+                br_if $fdone
+                local.get $i
+                i32.const 1
+                i32.sub
+                local.set $i
+                local.get $res
+                local.get $i
+                i32.add
+                local.get $fr
+                i64.const 10
+                i64.rem_s
+                i32.wrap_i64
+                i32.const 48
+                i32.add
+                i32.store8
+                local.get $fr
+                i64.const 10
+                i64.div_s
+                local.set $fr
+                br $fgen
+            )
+        )
+        local.get $i
+        i32.const 1
+        i32.sub
+        local.set $i
+        local.get $res
+        local.get $i
+        i32.add
+        i32.const 46
+        i32.store8
+    ))
+    ;; integer digits, least significant last
+    (block $idone
+        (loop $igen
+            local.get $i
+            i32.const 1
+            i32.sub
+            local.set $i
+            local.get $res
+            local.get $i
+            i32.add
+            local.get $ip
+            i64.const 10
+            i64.rem_s
+            i32.wrap_i64
+            i32.const 48
+            i32.add
+            i32.store8
+            local.get $ip
+            i64.const 10
+            i64.div_s
+            local.set $ip
+            local.get $ip
+            i64.eqz
+            br_if $idone
+            br $igen
+        )
+    )
+    local.get $neg
+    (if (then
+        local.get $res
+        i32.const 45
+        i32.store8
+    ))
+    local.get $res
+)
+;; `$format_fixed` for a magnitude `$v` of at least 2^63, with its sign already split off into
+;; `$neg`. Such a double is a whole number, mantissa * 2^exp, so its exact decimal digits come from
+;; doubling the mantissa's digits in a scratch buffer (least significant first) up to 32 bits at a
+;; time; the `$digits` fraction digits are all zeros.
+(func $format_fixed_big (param $v f64) (param $neg i32) (param $digits i32) (result i32)
+    (local $bits i64)
+    (local $mant i64)
+    (local $exp i32)
+    (local $k i32)
+    (local $buf i32)
+    (local $n i32)
+    (local $i i32)
+    (local $t i64)
+    (local $carry i64)
+    (local $len i32)
+    (local $res i32)
+    local.get $v
+    i64.reinterpret_f64
+    local.set $bits
+    local.get $bits
+    i64.const 52
+    i64.shr_u
+    i64.const 2047
+    i64.and
+    i32.wrap_i64
+    i32.const 1075
+    i32.sub
+    local.set $exp
+    local.get $bits
+    i64.const 0xfffffffffffff
+    i64.and
+    i64.const 0x10000000000000
+    i64.or
+    local.set $mant
+    ;; the largest double has 309 integer digits
+    i32.const 320
+    i32.const {TAG_STRING}
+    call $malloc
+    local.set $buf
+    (block $mdone
+        (loop $mgen
+            local.get $buf
+            local.get $n
+            i32.add
+            local.get $mant
+            i64.const 10
+            i64.rem_u
+            i64.store8
+            local.get $n
+            i32.const 1
+            i32.add
+            local.set $n
+            local.get $mant
+            i64.const 10
+            i64.div_u
+            local.set $mant
+            local.get $mant
+            i64.eqz
+            br_if $mdone
+            br $mgen
+        )
+    )
+    ;; digits = digits * 2^k until exp is used up; each step's carry stays below 2^k
+    (block $edone
+        (loop $egen
+            local.get $exp
+            i32.const 0
+            i32.le_s
+            br_if $edone
+            i32.const 32
+            local.get $exp
+            local.get $exp
+            i32.const 32
+            i32.gt_s
+            select
+            local.set $k
+            local.get $exp
+            local.get $k
+            i32.sub
+            local.set $exp
+            i64.const 0
+            local.set $carry
+            i32.const 0
+            local.set $i
+            (block $ddone
+                (loop $dgen
+                    local.get $i
+                    local.get $n
+                    i32.ge_s
+                    br_if $ddone
+                    local.get $buf
+                    local.get $i
+                    i32.add
+                    i64.load8_u
+                    local.get $k
+                    i64.extend_i32_u
+                    i64.shl
+                    local.get $carry
+                    i64.add
+                    local.set $t
+                    local.get $buf
+                    local.get $i
+                    i32.add
+                    local.get $t
+                    i64.const 10
+                    i64.rem_u
+                    i64.store8
+                    local.get $t
+                    i64.const 10
+                    i64.div_u
+                    local.set $carry
+                    local.get $i
+                    i32.const 1
+                    i32.add
+                    local.set $i
+                    br $dgen
+                )
+            )
+            (block $cdone
+                (loop $cgen
+                    local.get $carry
+                    i64.eqz
+                    br_if $cdone
+                    local.get $buf
+                    local.get $n
+                    i32.add
+                    local.get $carry
+                    i64.const 10
+                    i64.rem_u
+                    i64.store8
+                    local.get $n
+                    i32.const 1
+                    i32.add
+                    local.set $n
+                    local.get $carry
+                    i64.const 10
+                    i64.div_u
+                    local.set $carry
+                    br $cgen
+                )
+            )
+            br $egen
+        )
+    )
+    ;; len = sign + integer digits + ('.' + fraction digits, when there are any)
+    local.get $neg
+    local.get $n
+    i32.add
+    local.set $len
+    local.get $digits
+    (if (then
+        local.get $len
+        local.get $digits
+        i32.const 1
+        i32.add
+        i32.add
+        local.set $len
+    ))
+    local.get $len
+    call $string_alloc
+    local.set $res
+    local.get $neg
+    (if (then
+        local.get $res
+        i32.const 45
+        i32.store8
+    ))
+    ;; integer digits, most significant first
+    i32.const 0
+    local.set $i
+    (block $idone
+        (loop $igen
+            local.get $i
+            local.get $n
+            i32.ge_s
+            br_if $idone
+            local.get $res
+            local.get $neg
+            i32.add
+            local.get $i
+            i32.add
+            local.get $buf
+            local.get $n
+            i32.const 1
+            i32.sub
+            local.get $i
+            i32.sub
+            i32.add
+            i32.load8_u
+            i32.const 48
+            i32.add
+            i32.store8
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $igen
+        )
+    )
+    local.get $digits
+    (if (then
+        local.get $res
+        local.get $neg
+        i32.add
+        local.get $n
+        i32.add
+        local.tee $i
+        i32.const 46
+        i32.store8
+        local.get $i
+        i32.const 1
+        i32.add
+        i32.const 48
+        local.get $digits
+        memory.fill
+    ))
+    local.get $buf
+    call $free
+    local.get $res
+)
@@ -0,0 +1,47 @@
+;; Runtime-check failures. Each builds a readable message, records it in `$panic_message` (exported
+;; so the host can report it after the trap; a WASI module writes it to stderr itself), and traps. `{...}` placeholders are the data pointers
+;; of the interned message pieces.
+
+(func $panic (param $msg i32)
+    local.get $msg
+    global.set $panic_message
+    ;;@PANIC_REPORT@
+    unreachable
+)
+
+;; "index out of bounds: index <index> is out of range for <what> of length <length>", where `what`
+;; names the indexed type (e.g. "int[]").
+(func $panic_index (param $index i32) (param $length i32) (param $what i32)
+    i32.const {index_out_of_bounds}
+    local.get $index
+    call $int_to_string
+    call $concat_strings
+    i32.const {out_of_range_for}
+    call $concat_strings
+    local.get $what
+    call $concat_strings
+    i32.const {of_length}
+    call $concat_strings
+    local.get $length
+    call $int_to_string
+    call $concat_strings
+    call $panic
+)
+
+;; A failed array bounds check: a null array is reported as a null dereference, anything else as an
+;; out-of-range index.
+(func $panic_array_index (param $index i32) (param $array i32) (param $what i32)
+    local.get $array
+    i32.eqz
+    (if (then
+        i32.const {null_index}
+        local.get $what
+        call $concat_strings
+        call $panic
+    ))
+    local.get $index
+    local.get $array
+    i32.load
+    local.get $what
+    call $panic_index
+)
@@ -122,10 +122,13 @@
     (if (result i32)
         (then i32.const 0)
         (else
+            ;; the count is the low bits; the cycle collector keeps its state above them
             local.get $ptr
             i32.const 4
             i32.sub
             i32.load
+            i32.const 0x0fffffff
+            i32.and
         )
     )
 )
@@ -221,3 +224,17 @@
     local.get $c
     i32.store8
 )
+
+;; Copies `count` bytes of `src` starting at `start` into `dst` at offset `at`. The ranges may
+;; overlap (`memory.copy` has `memmove` semantics), so a buffer can shift its own bytes. The caller
+;; keeps both ranges in bounds; no null terminator is written.
+(func $string_copy (param $dst i32) (param $at i32) (param $src i32) (param $start i32) (param $count i32)
+    local.get $dst
+    local.get $at
+    i32.add
+    local.get $src
+    local.get $start
+    i32.add
+    local.get $count
+    memory.copy
+)
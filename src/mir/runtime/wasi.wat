@@ -0,0 +1,1407 @@
+;; The WASI host layer, present only in modules built for the WASI target. It implements the
+;; `print_*` builtins and the `Dream` host bridges (console, files, clock) over the
+;; `wasi_snapshot_preview1` imports (`$wasi_*`), so the module runs on any WASI host with no
+;; Dream-specific glue. `{TAG_ARRAY}` is substituted, and `{dot}`/`{out_of_memory}` are the data
+;; pointers of interned strings.
+;;
+;; Scratch memory below the interned strings (which start at 1024):
+;;   16..24   one iovec `[ptr, len]`
+;;   24..28   bytes written/read, or directory-buffer bytes used
+;;   32..40   an 8-byte result: clock time, prestat, argument sizes, or an opened fd
+;;   40..44   one UTF-8 encoded character
+;;   64..128  a `filestat`
+
+;; The path `$wasi_resolve` leaves relative to the preopened directory it found.
+(global $wasi_path (mut i32) (i32.const 0))
+;; The byte length of the block `$wasi_read_to_end` returned.
+(global $wasi_len (mut i32) (i32.const 0))
+
+;; Writes `len` bytes at `ptr` to `fd`, continuing after short writes. Returns 0, or the errno of the
+;; failed write.
+(func $wasi_write (param $fd i32) (param $ptr i32) (param $len i32) (result i32)
+    (local $errno i32)
+    (block $done
+        (loop $more
+            local.get $len
+            i32.eqz
+            br_if $done
+            i32.const 16
+            local.get $ptr
+            i32.store
+            i32.const 20
+            local.get $len
+            i32.store
+            local.get $fd
+            i32.const 16
+            i32.const 1
+            i32.const 24
+            call $wasi_fd_write
+            local.tee $errno
+            br_if $done
+            ;; a write that makes no progress is an I/O error (EIO), not a reason to spin
+            i32.const 24
+            i32.load
+            i32.eqz
+            (if (then
+                i32.const 29
+                return
+            ))
+            local.get $ptr
+            i32.const 24
+            i32.load
+            i32.add
+            local.set $ptr
+            local.get $len
+            i32.const 24
+            i32.load
+            i32.sub
+            local.set $len
+            br $more
+        )
+    )
+    local.get $errno
+)
+
+(func $print_string (param $s i32)
+    i32.const 1
+    local.get $s
+    local.get $s
+    call $strlen
+    call $wasi_write
+    drop
+)
+
+(func $print_int (param $v i32)
+    (local $s i32)
+    local.get $v
+    call $int_to_string
+    local.set $s
+    local.get $s
+    call $print_string
+    local.get $s
+    call $free
+)
+
+(func $print_char (param $c i32)
+    i32.const 1
+    i32.const 40
+    i32.const 40
+    local.get $c
+    call $wasi_encode_char
+    call $wasi_write
+    drop
+)
+
+;; Encodes code point `c` as UTF-8 at `ptr`; returns the number of bytes written.
+(func $wasi_encode_char (param $ptr i32) (param $c i32) (result i32)
+    local.get $c
+    i32.const 0x80
+    i32.lt_u
+    (if (then
+        local.get $ptr
+        local.get $c
+        i32.store8
+        i32.const 1
+        return
+    ))
+    local.get $c
+    i32.const 0x800
+    i32.lt_u
+    (if (then
+        local.get $ptr
+        local.get $c
+        i32.const 6
+        i32.shr_u
+        i32.const 0xC0
+        i32.or
+        i32.store8
+        local.get $ptr
+        local.get $c
+        i32.const 0x3F
+        i32.and
+        i32.const 0x80
+        i32.or
+        i32.store8 offset=1
+        i32.const 2
+        return
+    ))
+    local.get $c
+    i32.const 0x10000
+    i32.lt_u
+    (if (then
+        local.get $ptr
+        local.get $c
+        i32.const 12
+        i32.shr_u
+        i32.const 0xE0
+        i32.or
+        i32.store8
+        local.get $ptr
+        local.get $c
+        i32.const 6
+        i32.shr_u
+        i32.const 0x3F
+        i32.and
+        i32.const 0x80
+        i32.or
+        i32.store8 offset=1
+        local.get $ptr
+        local.get $c
+        i32.const 0x3F
+        i32.and
+        i32.const 0x80
+        i32.or
+        i32.store8 offset=2
+        i32.const 3
+        return
+    ))
+    local.get $ptr
+    local.get $c
+    i32.const 18
+    i32.shr_u
+    i32.const 0xF0
+    i32.or
+    i32.store8
+    local.get $ptr
+    local.get $c
+    i32.const 12
+    i32.shr_u
+    i32.const 0x3F
+    i32.and
+    i32.const 0x80
+    i32.or
+    i32.store8 offset=1
+    local.get $ptr
+    local.get $c
+    i32.const 6
+    i32.shr_u
+    i32.const 0x3F
+    i32.and
+    i32.const 0x80
+    i32.or
+    i32.store8 offset=2
+    local.get $ptr
+    local.get $c
+    i32.const 0x3F
+    i32.and
+    i32.const 0x80
+    i32.or
+    i32.store8 offset=3
+    i32.const 4
+)
+
+;; Writes a runtime error's message and a newline to stderr; the caller traps afterwards.
+(func $wasi_report (param $msg i32)
+    i32.const 2
+    local.get $msg
+    local.get $msg
+    call $strlen
+    call $wasi_write
+    drop
+    i32.const 40
+    i32.const 10
+    i32.store8
+    i32.const 2
+    i32.const 40
+    i32.const 1
+    call $wasi_write
+    drop
+)
+
+;; Reports running out of memory without allocating.
+(func $wasi_report_oom
+    i32.const {out_of_memory}
+    call $wasi_report
+)
+
+;; The `string[]` passed to `main(args)`: the command-line arguments after the program name.
+(func $wasi_args (result i32)
+    (local $argc i32)
+    (local $argv i32)
+    (local $buf i32)
+    (local $arr i32)
+    (local $i i32)
+    (local $arg i32)
+    (local $len i32)
+    (local $copy i32)
+    i32.const 32
+    i32.const 36
+    call $wasi_args_sizes_get
+    drop
+    i32.const 32
+    i32.load
+    local.set $argc
+    local.get $argc
+    i32.const 4
+    i32.mul
+    i32.const 0
+    call $malloc
+    local.set $argv
+    i32.const 36
+    i32.load
+    i32.const 0
+    call $malloc
+    local.set $buf
+    local.get $argv
+    local.get $buf
+    call $wasi_args_get
+    drop
+    ;; drop the program name
+    local.get $argc
+    (if (then
+        local.get $argc
+        i32.const 1
+        i32.sub
+        local.set $argc
+    ))
+    local.get $argc
+    i32.const 4
+    i32.mul
+    i32.const 4
+    i32.add
+    i32.const {TAG_ARRAY}
+    call $malloc
+    local.set $arr
+    local.get $arr
+    local.get $argc
+    i32.store
+    (block $done
+        (loop $next
+            local.get $i
+            local.get $argc
+            i32.ge_u
+            br_if $done
+            local.get $argv
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load offset=4
+            local.set $arg
+            local.get $arg
+            call $strlen
+            local.set $len
+            local.get $len
+            call $string_alloc
+            local.set $copy
+            local.get $copy
+            local.get $arg
+            local.get $len
+            memory.copy
+            local.get $arr
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            local.get $copy
+            i32.store offset=4
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $next
+        )
+    )
+    local.get $argv
+    call $free
+    local.get $buf
+    call $free
+    local.get $arr
+)
+
+;; Copies the `len` bytes of `buf` into a fresh raw block of `cap` bytes and frees `buf`.
+(func $wasi_grow (param $buf i32) (param $len i32) (param $cap i32) (result i32)
+    (local $grown i32)
+    local.get $cap
+    i32.const 0
+    call $malloc
+    local.set $grown
+    local.get $grown
+    local.get $buf
+    local.get $len
+    memory.copy
+    local.get $buf
+    call $free
+    local.get $grown
+)
+
+;; Reads one byte of stdin; -1 at the end of input or on an error.
+(func $wasi_read_byte (result i32)
+    i32.const 16
+    i32.const 40
+    i32.store
+    i32.const 20
+    i32.const 1
+    i32.store
+    i32.const 0
+    i32.const 16
+    i32.const 1
+    i32.const 24
+    call $wasi_fd_read
+    (if (then
+        i32.const -1
+        return
+    ))
+    i32.const 24
+    i32.load
+    i32.eqz
+    (if (then
+        i32.const -1
+        return
+    ))
+    i32.const 40
+    i32.load8_u
+)
+
+;; Reads `fd` to its end into a fresh raw block and returns it, leaving its length in `$wasi_len`.
+(func $wasi_read_to_end (param $fd i32) (result i32)
+    (local $buf i32)
+    (local $cap i32)
+    (local $len i32)
+    i32.const 4096
+    local.set $cap
+    local.get $cap
+    i32.const 0
+    call $malloc
+    local.set $buf
+    (block $done
+        (loop $more
+            local.get $len
+            local.get $cap
+            i32.eq
+            (if (then
+                local.get $cap
+                i32.const 2
+                i32.mul
+                local.set $cap
+                local.get $buf
+                local.get $len
+                local.get $cap
+                call $wasi_grow
+                local.set $buf
+            ))
+            i32.const 16
+            local.get $buf
+            local.get $len
+            i32.add
+            i32.store
+            i32.const 20
+            local.get $cap
+            local.get $len
+            i32.sub
+            i32.store
+            local.get $fd
+            i32.const 16
+            i32.const 1
+            i32.const 24
+            call $wasi_fd_read
+            br_if $done
+            i32.const 24
+            i32.load
+            i32.eqz
+            br_if $done
+            local.get $len
+            i32.const 24
+            i32.load
+            i32.add
+            local.set $len
+            br $more
+        )
+    )
+    local.get $len
+    global.set $wasi_len
+    local.get $buf
+)
+
+;; How much of `path` the preopen name at `name` (`len` bytes) covers: its length when `path` lies
+;; under it, 0 for the "." preopen and a relative path, or -1 when it does not apply.
+(func $wasi_prefix (param $path i32) (param $name i32) (param $len i32) (result i32)
+    (local $i i32)
+    ;; "dir/" names the same preopen as "dir" (but "/" stays the root)
+    (block $trimmed
+        (loop $trim
+            local.get $len
+            i32.const 1
+            i32.le_u
+            br_if $trimmed
+            local.get $name
+            local.get $len
+            i32.add
+            i32.const 1
+            i32.sub
+            i32.load8_u
+            i32.const 47
+            i32.ne
+            br_if $trimmed
+            local.get $len
+            i32.const 1
+            i32.sub
+            local.set $len
+            br $trim
+        )
+    )
+    local.get $len
+    i32.const 1
+    i32.eq
+    local.get $name
+    i32.load8_u
+    i32.const 46
+    i32.eq
+    i32.and
+    (if (then
+        local.get $path
+        i32.load8_u
+        i32.const 47
+        i32.eq
+        (if (result i32) (then i32.const -1) (else i32.const 0))
+        return
+    ))
+    (block $matched
+        (loop $next
+            local.get $i
+            local.get $len
+            i32.eq
+            br_if $matched
+            local.get $path
+            local.get $i
+            i32.add
+            i32.load8_u
+            local.get $name
+            local.get $i
+            i32.add
+            i32.load8_u
+            i32.ne
+            (if (then
+                i32.const -1
+                return
+            ))
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $next
+        )
+    )
+    ;; the match must end at a path separator: "/data" covers "/data/x" but not "/database"
+    local.get $name
+    local.get $len
+    i32.add
+    i32.const 1
+    i32.sub
+    i32.load8_u
+    i32.const 47
+    i32.eq
+    local.get $path
+    local.get $len
+    i32.add
+    i32.load8_u
+    local.tee $i
+    i32.const 47
+    i32.eq
+    i32.or
+    local.get $i
+    i32.eqz
+    i32.or
+    (if (result i32) (then local.get $len) (else i32.const -1))
+)
+
+;; Finds the preopened directory `path` lives under (the longest matching preopen) and returns its
+;; fd, leaving `path` relative to it in `$wasi_path`. Returns -1 when no preopen covers `path`.
+(func $wasi_resolve (param $path i32) (result i32)
+    (local $fd i32)
+    (local $best i32)
+    (local $best_len i32)
+    (local $name i32)
+    (local $len i32)
+    (local $matched i32)
+    ;; "./a" is "a"
+    (block $stripped
+        (loop $strip
+            local.get $path
+            i32.load8_u
+            i32.const 46
+            i32.ne
+            br_if $stripped
+            local.get $path
+            i32.load8_u offset=1
+            i32.const 47
+            i32.ne
+            br_if $stripped
+            local.get $path
+            i32.const 2
+            i32.add
+            local.set $path
+            br $strip
+        )
+    )
+    i32.const -1
+    local.set $best
+    i32.const -1
+    local.set $best_len
+    ;; preopens are numbered from 3 up to the first fd `fd_prestat_get` rejects
+    i32.const 3
+    local.set $fd
+    (block $end
+        (loop $next
+            local.get $fd
+            i32.const 32
+            call $wasi_fd_prestat_get
+            br_if $end
+            i32.const 32
+            i32.load8_u
+            i32.eqz
+            (if (then
+                i32.const 36
+                i32.load
+                local.set $len
+                local.get $len
+                i32.const 1
+                i32.add
+                i32.const 0
+                call $malloc
+                local.set $name
+                local.get $fd
+                local.get $name
+                local.get $len
+                call $wasi_fd_prestat_dir_name
+                drop
+                local.get $path
+                local.get $name
+                local.get $len
+                call $wasi_prefix
+                local.tee $matched
+                local.get $best_len
+                i32.gt_s
+                (if (then
+                    local.get $fd
+                    local.set $best
+                    local.get $matched
+                    local.set $best_len
+                ))
+                local.get $name
+                call $free
+            ))
+            local.get $fd
+            i32.const 1
+            i32.add
+            local.set $fd
+            br $next
+        )
+    )
+    local.get $best
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const -1
+        return
+    ))
+    local.get $path
+    local.get $best_len
+    i32.add
+    local.set $path
+    (block $bare
+        (loop $skip
+            local.get $path
+            i32.load8_u
+            i32.const 47
+            i32.ne
+            br_if $bare
+            local.get $path
+            i32.const 1
+            i32.add
+            local.set $path
+            br $skip
+        )
+    )
+    ;; the preopen itself
+    local.get $path
+    i32.load8_u
+    i32.eqz
+    (if (then
+        i32.const {dot}
+        local.set $path
+    ))
+    local.get $path
+    global.set $wasi_path
+    local.get $best
+)
+
+;; Opens `path` with the given `path_open` flags and rights; returns the new fd, or -1.
+(func $wasi_open (param $path i32) (param $oflags i32) (param $rights i64) (param $fdflags i32) (result i32)
+    (local $dir i32)
+    local.get $path
+    call $wasi_resolve
+    local.tee $dir
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const -1
+        return
+    ))
+    local.get $dir
+    ;; follow symlinks
+    i32.const 1
+    global.get $wasi_path
+    global.get $wasi_path
+    call $strlen
+    local.get $oflags
+    local.get $rights
+    i64.const 0
+    local.get $fdflags
+    i32.const 32
+    call $wasi_path_open
+    (if (then
+        i32.const -1
+        return
+    ))
+    i32.const 32
+    i32.load
+)
+
+;; Stats `path` into the `filestat` at 64; returns whether that succeeded.
+(func $wasi_stat (param $path i32) (result i32)
+    (local $dir i32)
+    local.get $path
+    call $wasi_resolve
+    local.tee $dir
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $dir
+    i32.const 1
+    global.get $wasi_path
+    global.get $wasi_path
+    call $strlen
+    i32.const 64
+    call $wasi_path_filestat_get
+    i32.eqz
+)
+
+;; Opens `path` for writing with the given flags and writes `len` bytes at `ptr`. Returns the byte
+;; count, or -1.
+(func $wasi_write_file (param $path i32) (param $oflags i32) (param $fdflags i32) (param $ptr i32) (param $len i32) (result i64)
+    (local $fd i32)
+    (local $errno i32)
+    local.get $path
+    local.get $oflags
+    ;; fd_write | fd_seek | fd_tell | fd_filestat_get
+    i64.const 0x200064
+    local.get $fdflags
+    call $wasi_open
+    local.tee $fd
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i64.const -1
+        return
+    ))
+    local.get $fd
+    local.get $ptr
+    local.get $len
+    call $wasi_write
+    local.set $errno
+    local.get $fd
+    call $wasi_fd_close
+    drop
+    local.get $errno
+    (if (result i64)
+        (then i64.const -1)
+        (else
+            local.get $len
+            i64.extend_i32_u
+        )
+    )
+)
+
+;; Opens `path` for reading and reads all of it (see `$wasi_read_to_end`); 0 when it cannot be
+;; opened.
+(func $wasi_read_file (param $path i32) (result i32)
+    (local $fd i32)
+    (local $data i32)
+    local.get $path
+    i32.const 0
+    ;; fd_read | fd_seek | fd_tell | fd_filestat_get
+    i64.const 0x200026
+    i32.const 0
+    call $wasi_open
+    local.tee $fd
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $fd
+    call $wasi_read_to_end
+    local.set $data
+    local.get $fd
+    call $wasi_fd_close
+    drop
+    local.get $data
+)
+
+(func $host_consoleReadLine (result i32)
+    (local $buf i32)
+    (local $cap i32)
+    (local $len i32)
+    (local $byte i32)
+    (local $line i32)
+    i32.const 64
+    local.set $cap
+    local.get $cap
+    i32.const 0
+    call $malloc
+    local.set $buf
+    (block $done
+        (loop $next
+            call $wasi_read_byte
+            local.tee $byte
+            i32.const 0
+            i32.lt_s
+            br_if $done
+            local.get $byte
+            i32.const 10
+            i32.eq
+            br_if $done
+            ;; "\r\n" line endings drop the "\r"
+            local.get $byte
+            i32.const 13
+            i32.ne
+            (if (then
+                local.get $len
+                local.get $cap
+                i32.eq
+                (if (then
+                    local.get $cap
+                    i32.const 2
+                    i32.mul
+                    local.set $cap
+                    local.get $buf
+                    local.get $len
+                    local.get $cap
+                    call $wasi_grow
+                    local.set $buf
+                ))
+                local.get $buf
+                local.get $len
+                i32.add
+                local.get $byte
+                i32.store8
+                local.get $len
+                i32.const 1
+                i32.add
+                local.set $len
+            ))
+            br $next
+        )
+    )
+    local.get $len
+    call $string_alloc
+    local.set $line
+    local.get $line
+    local.get $buf
+    local.get $len
+    memory.copy
+    local.get $buf
+    call $free
+    local.get $line
+)
+
+;; WASI has no raw terminal mode: this is the next character of stdin, decoded from UTF-8, once the
+;; terminal hands the line over. 0 at the end of input.
+(func $host_consoleReadKey (result i32)
+    (local $byte i32)
+    (local $code i32)
+    (local $more i32)
+    call $wasi_read_byte
+    local.tee $byte
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $byte
+    i32.const 0x80
+    i32.lt_u
+    (if (then
+        local.get $byte
+        return
+    ))
+    ;; the leading byte's payload bits, and how many continuation bytes follow it
+    local.get $byte
+    i32.const 0xE0
+    i32.lt_u
+    (if
+        (then
+            local.get $byte
+            i32.const 0x1F
+            i32.and
+            local.set $code
+            i32.const 1
+            local.set $more
+        )
+        (else
+            local.get $byte
+            i32.const 0xF0
+            i32.lt_u
+            (if
+                (then
+                    local.get $byte
+                    i32.const 0x0F
+                    i32.and
+                    local.set $code
+                    i32.const 2
+                    local.set $more
+                )
+                (else
+                    local.get $byte
+                    i32.const 0x07
+                    i32.and
+                    local.set $code
+                    i32.const 3
+                    local.set $more
+                )
+            )
+        )
+    )
+    (block $done
+        (loop $next
+            local.get $more
+            i32.eqz
+            br_if $done
+            call $wasi_read_byte
+            local.tee $byte
+            i32.const 0
+            i32.lt_s
+            br_if $done
+            local.get $code
+            i32.const 6
+            i32.shl
+            local.get $byte
+            i32.const 0x3F
+            i32.and
+            i32.or
+            local.set $code
+            local.get $more
+            i32.const 1
+            i32.sub
+            local.set $more
+            br $next
+        )
+    )
+    local.get $code
+)
+
+(func $host_consoleExit (param $code i32)
+    local.get $code
+    call $wasi_proc_exit
+)
+
+(func $host_dateNowMillis (result i64)
+    ;; the realtime clock, in nanoseconds
+    i32.const 0
+    i64.const 1000
+    i32.const 32
+    call $wasi_clock_time_get
+    drop
+    i32.const 32
+    i64.load
+    i64.const 1000000
+    i64.div_u
+)
+
+;; WASI has no time zones, so local time is UTC.
+(func $host_dateLocalOffsetMinutes (param $millis i64) (result i32)
+    i32.const 0
+)
+
+;; The file as a string; "" when it cannot be read.
+(func $host_fileRead (param $path i32) (result i32)
+    (local $data i32)
+    (local $s i32)
+    local.get $path
+    call $wasi_read_file
+    local.tee $data
+    i32.eqz
+    (if (then
+        i32.const 0
+        call $string_alloc
+        return
+    ))
+    global.get $wasi_len
+    call $string_alloc
+    local.set $s
+    local.get $s
+    local.get $data
+    global.get $wasi_len
+    memory.copy
+    local.get $data
+    call $free
+    local.get $s
+)
+
+;; The file as a `byte[]`; empty when it cannot be read.
+(func $host_fileReadBytes (param $path i32) (result i32)
+    (local $data i32)
+    (local $arr i32)
+    local.get $path
+    call $wasi_read_file
+    local.tee $data
+    i32.eqz
+    (if (then
+        i32.const 0
+        global.set $wasi_len
+    ))
+    global.get $wasi_len
+    i32.const 4
+    i32.add
+    i32.const {TAG_ARRAY}
+    call $malloc
+    local.set $arr
+    local.get $arr
+    global.get $wasi_len
+    i32.store
+    local.get $arr
+    i32.const 4
+    i32.add
+    local.get $data
+    global.get $wasi_len
+    memory.copy
+    local.get $data
+    call $free
+    local.get $arr
+)
+
+(func $host_fileWrite (param $path i32) (param $content i32) (result i64)
+    local.get $path
+    ;; creat | trunc
+    i32.const 9
+    i32.const 0
+    local.get $content
+    local.get $content
+    call $strlen
+    call $wasi_write_file
+)
+
+(func $host_fileAppend (param $path i32) (param $content i32) (result i64)
+    local.get $path
+    ;; creat, with the append fd flag
+    i32.const 1
+    i32.const 1
+    local.get $content
+    local.get $content
+    call $strlen
+    call $wasi_write_file
+)
+
+(func $host_fileWriteBytes (param $path i32) (param $data i32) (result i64)
+    local.get $path
+    i32.const 9
+    i32.const 0
+    local.get $data
+    i32.const 4
+    i32.add
+    local.get $data
+    i32.load
+    call $wasi_write_file
+)
+
+(func $host_fileExists (param $path i32) (result i32)
+    local.get $path
+    call $wasi_stat
+)
+
+(func $host_fileSize (param $path i32) (result i64)
+    local.get $path
+    call $wasi_stat
+    (if (result i64)
+        (then
+            i32.const 64
+            i64.load offset=32
+        )
+        (else i64.const -1)
+    )
+)
+
+(func $host_fileIsDir (param $path i32) (result i32)
+    local.get $path
+    call $wasi_stat
+    (if (result i32)
+        (then
+            ;; filetype 3 is a directory
+            i32.const 64
+            i32.load8_u offset=16
+            i32.const 3
+            i32.eq
+        )
+        (else i32.const 0)
+    )
+)
+
+(func $host_fileDelete (param $path i32) (result i32)
+    (local $dir i32)
+    local.get $path
+    call $wasi_resolve
+    local.tee $dir
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $dir
+    global.get $wasi_path
+    global.get $wasi_path
+    call $strlen
+    call $wasi_path_unlink_file
+    i32.eqz
+)
+
+;; Whether the name of directory entry `a` sorts before that of `b`, bytewise like the native host.
+(func $wasi_name_lt (param $a i32) (param $b i32) (result i32)
+    (local $la i32)
+    (local $lb i32)
+    (local $i i32)
+    (local $ca i32)
+    (local $cb i32)
+    local.get $a
+    i32.load offset=16
+    local.set $la
+    local.get $b
+    i32.load offset=16
+    local.set $lb
+    (block $done
+        (loop $next
+            local.get $i
+            local.get $la
+            i32.eq
+            br_if $done
+            local.get $i
+            local.get $lb
+            i32.eq
+            br_if $done
+            local.get $a
+            local.get $i
+            i32.add
+            i32.load8_u offset=24
+            local.set $ca
+            local.get $b
+            local.get $i
+            i32.add
+            i32.load8_u offset=24
+            local.set $cb
+            local.get $ca
+            local.get $cb
+            i32.ne
+            (if (then
+                local.get $ca
+                local.get $cb
+                i32.lt_u
+                return
+            ))
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $next
+        )
+    )
+    local.get $la
+    local.get $lb
+    i32.lt_u
+)
+
+;; Whether directory entry `e` is "." or "..".
+(func $wasi_dot_entry (param $e i32) (result i32)
+    (local $len i32)
+    local.get $e
+    i32.load offset=16
+    local.set $len
+    local.get $e
+    i32.load8_u offset=24
+    i32.const 46
+    i32.ne
+    (if (then
+        i32.const 0
+        return
+    ))
+    local.get $len
+    i32.const 1
+    i32.eq
+    local.get $len
+    i32.const 2
+    i32.eq
+    local.get $e
+    i32.load8_u offset=25
+    i32.const 46
+    i32.eq
+    i32.and
+    i32.or
+)
+
+;; The directory's entry names, sorted and joined by "\n"; "" when it is empty or not a directory.
+(func $host_dirList (param $path i32) (result i32)
+    (local $fd i32)
+    (local $buf i32)
+    (local $cap i32)
+    (local $used i32)
+    (local $entries i32)
+    (local $count i32)
+    (local $p i32)
+    (local $i i32)
+    (local $j i32)
+    (local $key i32)
+    (local $total i32)
+    (local $out i32)
+    (local $at i32)
+    (local $len i32)
+    local.get $path
+    ;; directory
+    i32.const 2
+    ;; fd_readdir | fd_filestat_get
+    i64.const 0x204000
+    i32.const 0
+    call $wasi_open
+    local.tee $fd
+    i32.const 0
+    i32.lt_s
+    (if (then
+        i32.const 0
+        call $string_alloc
+        return
+    ))
+    ;; a full buffer may have cut the listing short: retry with a bigger one until it fits
+    i32.const 2048
+    local.set $cap
+    (loop $retry
+        local.get $cap
+        i32.const 0
+        call $malloc
+        local.set $buf
+        local.get $fd
+        local.get $buf
+        local.get $cap
+        i64.const 0
+        i32.const 24
+        call $wasi_fd_readdir
+        (if
+            (then
+                i32.const 0
+                local.set $used
+            )
+            (else
+                i32.const 24
+                i32.load
+                local.set $used
+            )
+        )
+        local.get $used
+        local.get $cap
+        i32.eq
+        (if (then
+            local.get $buf
+            call $free
+            local.get $cap
+            i32.const 2
+            i32.mul
+            local.set $cap
+            br $retry
+        ))
+    )
+    local.get $fd
+    call $wasi_fd_close
+    drop
+    ;; every entry is a 24-byte header followed by its name, so this bounds the entry count
+    local.get $used
+    i32.const 24
+    i32.div_u
+    i32.const 4
+    i32.mul
+    i32.const 4
+    i32.add
+    i32.const 0
+    call $malloc
+    local.set $entries
+    local.get $buf
+    local.set $p
+    (block $listed
+        (loop $next
+            local.get $p
+            i32.const 24
+            i32.add
+            local.get $buf
+            local.get $used
+            i32.add
+            i32.gt_u
+            br_if $listed
+            local.get $p
+            call $wasi_dot_entry
+            i32.eqz
+            (if (then
+                local.get $entries
+                local.get $count
+                i32.const 4
+                i32.mul
+                i32.add
+                local.get $p
+                i32.store
+                local.get $count
+                i32.const 1
+                i32.add
+                local.set $count
+                local.get $total
+                local.get $p
+                i32.load offset=16
+                i32.add
+                i32.const 1
+                i32.add
+                local.set $total
+            ))
+            local.get $p
+            local.get $p
+            i32.load offset=16
+            i32.add
+            i32.const 24
+            i32.add
+            local.set $p
+            br $next
+        )
+    )
+    ;; insertion sort of the entry pointers by name
+    i32.const 1
+    local.set $i
+    (block $sorted
+        (loop $outer
+            local.get $i
+            local.get $count
+            i32.ge_u
+            br_if $sorted
+            local.get $entries
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load
+            local.set $key
+            local.get $i
+            local.set $j
+            (block $placed
+                (loop $inner
+                    local.get $j
+                    i32.eqz
+                    br_if $placed
+                    local.get $key
+                    local.get $entries
+                    local.get $j
+                    i32.const 4
+                    i32.mul
+                    i32.add
+                    i32.const 4
+                    i32.sub
+                    i32.load
+                    call $wasi_name_lt
+                    i32.eqz
+                    br_if $placed
+                    local.get $entries
+                    local.get $j
+                    i32.const 4
+                    i32.mul
+                    i32.add
+                    local.get $entries
+                    local.get $j
+                    i32.const 4
+                    i32.mul
+                    i32.add
+                    i32.const 4
+                    i32.sub
+                    i32.load
+                    i32.store
+                    local.get $j
+                    i32.const 1
+                    i32.sub
+                    local.set $j
+                    br $inner
+                )
+            )
+            local.get $entries
+            local.get $j
+            i32.const 4
+            i32.mul
+            i32.add
+            local.get $key
+            i32.store
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $outer
+        )
+    )
+    ;; the names with a "\n" between each (`total` counted one after each)
+    local.get $total
+    (if (then
+        local.get $total
+        i32.const 1
+        i32.sub
+        local.set $total
+    ))
+    local.get $total
+    call $string_alloc
+    local.set $out
+    local.get $out
+    local.set $at
+    i32.const 0
+    local.set $i
+    (block $joined
+        (loop $next
+            local.get $i
+            local.get $count
+            i32.ge_u
+            br_if $joined
+            local.get $i
+            (if (then
+                local.get $at
+                i32.const 10
+                i32.store8
+                local.get $at
+                i32.const 1
+                i32.add
+                local.set $at
+            ))
+            local.get $entries
+            local.get $i
+            i32.const 4
+            i32.mul
+            i32.add
+            i32.load
+            local.set $p
+            local.get $p
+            i32.load offset=16
+            local.set $len
+            local.get $at
+            local.get $p
+            i32.const 24
+            i32.add
+            local.get $len
+            memory.copy
+            local.get $at
+            local.get $len
+            i32.add
+            local.set $at
+            local.get $i
+            i32.const 1
+            i32.add
+            local.set $i
+            br $next
+        )
+    )
+    local.get $entries
+    call $free
+    local.get $buf
+    call $free
+    local.get $out
+)
@@ -0,0 +1,392 @@
+//! Source maps for runtime stack traces.
+//!
+//! The backend leaves two records in the module that survive assembly:
+//!
+//! - a `dream.funcs` custom section with one `symbol \t name \t file \t line` entry per emitted
+//!   function whose source is known, keyed by the same `$symbol` the name section carries;
+//! - a marker per [`Statement::Line`](super::Statement::Line) —
+//!   `(drop (global.get $__dream_srcloc)) (drop (i32.const LINE))` — which is stack-neutral but
+//!   stays visible in the code section.
+//!
+//! [`strip_line_markers`] then removes the markers from the assembled module, recording where each
+//! one was in a `dream.lines` custom section instead, so the code that runs carries none of them.
+//! [`SourceMap::from_wasm`] joins those records with the name section into a table from code offset
+//! to Dream function, file, and line. The runner resolves trap frames against it directly; the
+//! driver also appends it to the `.wasm` as a `dream.srcmap` custom section for the JS runtime (see
+//! `driver::abi`).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+use wasmparser::{KnownCustom, Name, Operator, Parser, Payload, TypeRef};
+
+/// Custom section the backend writes, mapping emitted function symbols to their source.
+pub const FUNCS_SECTION: &str = "dream.funcs";
+
+/// Custom section the driver appends to the `.wasm`: the resolved source map, as JSON.
+pub const SRCMAP_SECTION: &str = "dream.srcmap";
+
+/// Custom section [`strip_line_markers`] writes in place of the markers: one
+/// `function index \t offset:line offset:line ...` entry per function, each offset relative to the
+/// start of the function's body.
+pub const LINES_SECTION: &str = "dream.lines";
+
+/// WASM section id of the code section.
+const CODE_SECTION: u8 = 10;
+
+/// The immutable global that line markers read. Nothing else references it, so a `global.get` of it
+/// unambiguously starts a marker.
+pub const MARKER_GLOBAL: &str = "__dream_srcloc";
+
+/// The source of one function in the module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSource {
+    /// Source-level name (`Point.move`, `main.<lambda>`).
+    pub name: String,
+    pub file: String,
+    /// The declaration line, for code before the first marker.
+    pub line: u32,
+    /// `(module byte offset of a marker, line)`, ascending by offset.
+    pub lines: Vec<(usize, u32)>,
+}
+
+/// One resolved stack frame, rendered as `at Point.move (src/app.dream:42)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub name: String,
+    pub file: String,
+    pub line: u32,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {} ({}:{})", self.name, self.file, self.line)
+    }
+}
+
+/// The code-offset → source table of an assembled module, keyed by module function index (imports
+/// included, matching the index hosts report for a frame).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    functions: BTreeMap<u32, FunctionSource>,
+}
+
+impl SourceMap {
+    /// Builds the source map of an assembled module. A module without `dream.funcs` (or one that does
+    /// not parse) yields an empty map.
+    pub fn from_wasm(wasm: &[u8]) -> SourceMap {
+        Self::parse(wasm).unwrap_or_default()
+    }
+
+    fn parse(wasm: &[u8]) -> wasmparser::Result<SourceMap> {
+        let mut imported_funcs = 0u32;
+        let mut defined_funcs = 0u32;
+        // Where each function's body starts, and its markers relative to that: the `dream.lines`
+        // section follows the code, so the two are joined at the end.
+        let mut body_starts: HashMap<u32, usize> = HashMap::new();
+        let mut markers: HashMap<u32, Vec<(usize, u32)>> = HashMap::new();
+        let mut func_names: HashMap<u32, String> = HashMap::new();
+        let mut sources: HashMap<String, (String, String, u32)> = HashMap::new();
+
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload? {
+                Payload::ImportSection(reader) => imported_funcs += count_imported_funcs(reader)?,
+                Payload::CodeSectionEntry(body) => {
+                    body_starts.insert(imported_funcs + defined_funcs, body.range().start);
+                    defined_funcs += 1;
+                }
+                Payload::CustomSection(section) => match section.as_known() {
+                    KnownCustom::Name(names) => {
+                        for name in names {
+                            if let Name::Function(map) = name? {
+                                for naming in map {
+                                    let naming = naming?;
+                                    func_names.insert(naming.index, naming.name.to_string());
+                                }
+                            }
+                        }
+                    }
+                    _ if section.name() == FUNCS_SECTION => {
+                        let text = String::from_utf8_lossy(section.data());
+                        for entry in text.lines() {
+                            let fields: Vec<&str> = entry.split('\t').collect();
+                            if let [symbol, name, file, line] = fields.as_slice() {
+                                sources.insert(
+                                    symbol.to_string(),
+                                    (name.to_string(), file.to_string(), line.parse().unwrap_or(0)),
+                                );
+                            }
+                        }
+                    }
+                    _ if section.name() == LINES_SECTION => {
+                        let text = String::from_utf8_lossy(section.data());
+                        for entry in text.lines() {
+                            let Some((func, lines)) = entry.split_once('\t') else { continue };
+                            let Ok(func) = func.parse() else { continue };
+                            let lines = lines
+                                .split(' ')
+                                .filter_map(|pair| pair.split_once(':'))
+                                .filter_map(|(offset, line)| Some((offset.parse().ok()?, line.parse().ok()?)));
+                            markers.entry(func).or_default().extend(lines);
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        let mut functions = BTreeMap::new();
+        for (index, symbol) in func_names {
+            if let Some((name, file, line)) = sources.remove(&symbol) {
+                let start = body_starts.get(&index).copied().unwrap_or(0);
+                let lines = markers
+                    .remove(&index)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(offset, line)| (start + offset, line))
+                    .collect();
+                functions.insert(index, FunctionSource { name, file, line, lines });
+            }
+        }
+        Ok(SourceMap { functions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// The functions with a known source, by module function index.
+    pub fn functions(&self) -> impl Iterator<Item = (u32, &FunctionSource)> {
+        self.functions.iter().map(|(index, source)| (*index, source))
+    }
+
+    /// Resolves a frame executing function `func` at module byte `offset` (the trapping instruction,
+    /// or the call in a caller frame). The line is that of the nearest marker at or before `offset`,
+    /// or the declaration line when there is none. `None` for runtime helpers and other functions
+    /// without a Dream source.
+    pub fn frame(&self, func: u32, offset: Option<usize>) -> Option<Frame> {
+        let source = self.functions.get(&func)?;
+        let line = offset
+            .and_then(|offset| {
+                let i = source.lines.partition_point(|(marker, _)| *marker <= offset);
+                i.checked_sub(1).map(|i| source.lines[i].1)
+            })
+            .unwrap_or(source.line);
+        Some(Frame { name: source.name.clone(), file: source.file.clone(), line })
+    }
+}
+
+/// A line marker found in a function body: its byte range in the module, the global it reads, and
+/// its line.
+struct Marker {
+    span: Range<usize>,
+    global: u32,
+    line: u32,
+}
+
+/// Removes the line markers from an assembled module, recording each one's position and line in a
+/// `dream.lines` custom section instead. Every runner and the driver's `.wasm` go through this, so
+/// no marker is ever executed. A module without markers (already stripped, or not built by the
+/// backend) or one that does not parse comes back unchanged.
+pub fn strip_line_markers(wasm: &[u8]) -> Vec<u8> {
+    strip(wasm).unwrap_or_else(|_| wasm.to_vec())
+}
+
+fn strip(wasm: &[u8]) -> wasmparser::Result<Vec<u8>> {
+    let mut imported_funcs = 0u32;
+    let mut sections: Vec<(u8, Range<usize>)> = Vec::new();
+    let mut bodies: Vec<(Range<usize>, Vec<Marker>)> = Vec::new();
+    // Which global is the marker global is only known once the name section (after the code) has
+    // been read.
+    let mut marker_global = None;
+
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        match &payload {
+            Payload::ImportSection(reader) => imported_funcs += count_imported_funcs(reader.clone())?,
+            Payload::CodeSectionEntry(body) => {
+                let mut markers = Vec::new();
+                let mut ops = body.get_operators_reader()?;
+                let mut window: Vec<(Operator, usize)> = Vec::with_capacity(4);
+                while !ops.eof() {
+                    window.push(ops.read_with_offset()?);
+                    if window.len() > 4 {
+                        window.remove(0);
+                    }
+                    if let [(Operator::GlobalGet { global_index }, start), (Operator::Drop, _), (Operator::I32Const { value }, _), (Operator::Drop, last)] =
+                        window.as_slice()
+                    {
+                        // `drop` is a single byte.
+                        markers.push(Marker { span: *start..*last + 1, global: *global_index, line: *value as u32 });
+                    }
+                }
+                bodies.push((body.range(), markers));
+            }
+            Payload::CustomSection(section) => {
+                if let KnownCustom::Name(names) = section.as_known() {
+                    for name in names {
+                        if let Name::Global(map) = name? {
+                            for naming in map {
+                                let naming = naming?;
+                                if naming.name == MARKER_GLOBAL {
+                                    marker_global = Some(naming.index);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(section) = payload.as_section() {
+            sections.push(section);
+        }
+    }
+
+    let Some(marker_global) = marker_global else { return Ok(wasm.to_vec()) };
+    for (_, markers) in &mut bodies {
+        markers.retain(|m| m.global == marker_global);
+    }
+    if bodies.iter().all(|(_, markers)| markers.is_empty()) {
+        return Ok(wasm.to_vec());
+    }
+
+    let mut out = wasm[..8].to_vec(); // magic and version
+    let mut lines = String::new();
+    for (id, range) in sections {
+        if id != CODE_SECTION {
+            push_section(&mut out, id, &wasm[range]);
+            continue;
+        }
+        let mut code = Vec::with_capacity(range.len());
+        write_leb128(&mut code, bodies.len() as u32);
+        for (i, (range, markers)) in bodies.iter().enumerate() {
+            let mut body = Vec::with_capacity(range.len());
+            let mut entry = Vec::with_capacity(markers.len());
+            let mut from = range.start;
+            for marker in markers {
+                body.extend_from_slice(&wasm[from..marker.span.start]);
+                from = marker.span.end;
+                entry.push(format!("{}:{}", body.len(), marker.line));
+            }
+            body.extend_from_slice(&wasm[from..range.end]);
+            write_leb128(&mut code, body.len() as u32);
+            code.extend_from_slice(&body);
+            if !entry.is_empty() {
+                lines.push_str(&format!("{}\t{}\n", imported_funcs + i as u32, entry.join(" ")));
+            }
+        }
+        push_section(&mut out, id, &code);
+    }
+    append_custom_section(&mut out, LINES_SECTION, lines.as_bytes());
+    Ok(out)
+}
+
+fn count_imported_funcs(reader: wasmparser::ImportSectionReader) -> wasmparser::Result<u32> {
+    let mut count = 0;
+    for import in reader.into_imports() {
+        if matches!(import?.ty, TypeRef::Func(_) | TypeRef::FuncExact(_)) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn push_section(out: &mut Vec<u8>, id: u8, content: &[u8]) {
+    out.push(id);
+    write_leb128(out, content.len() as u32);
+    out.extend_from_slice(content);
+}
+
+/// Appends a custom section `name` carrying `data` to an assembled module.
+pub fn append_custom_section(wasm: &mut Vec<u8>, name: &str, data: &[u8]) {
+    let mut payload = Vec::with_capacity(name.len() + data.len() + 5);
+    write_leb128(&mut payload, name.len() as u32);
+    payload.extend_from_slice(name.as_bytes());
+    payload.extend_from_slice(data);
+    push_section(wasm, 0, &payload); // custom section id
+}
+
+fn write_leb128(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"(module
+        (import "env" "print_int" (func $print_int (param i32)))
+        (global $__dream_srcloc i32 (i32.const 0))
+        (func $helper (result i32) (i32.const 7))
+        (func $Point_move
+            (drop (global.get $__dream_srcloc)) (drop (i32.const 12))
+            (call $print_int (call $helper))
+            (drop (global.get $__dream_srcloc)) (drop (i32.const 13))
+            (unreachable))
+        (@custom "dream.funcs" "Point_move\tPoint.move\tsrc/app.dream\t11\n"))"#;
+
+    #[test]
+    fn maps_offsets_to_the_nearest_preceding_line_marker() {
+        let wasm = strip_line_markers(&wat::parse_str(MODULE).unwrap());
+        let map = SourceMap::from_wasm(&wasm);
+        let (index, source) = map.functions().next().expect("Point.move has a source");
+        // The import and `$helper` precede it in the function index space.
+        assert_eq!(index, 2);
+        assert_eq!(source.name, "Point.move");
+        assert_eq!(source.lines.len(), 2);
+
+        // The markers are gone: the second one sits right after the first statement's call.
+        let (first, _) = source.lines[0];
+        let (second, _) = source.lines[1];
+        assert_eq!(second - first, 4);
+        let frame = map.frame(index, Some(second)).unwrap();
+        assert_eq!(frame.to_string(), "at Point.move (src/app.dream:13)");
+        assert_eq!(map.frame(index, Some(second - 1)).unwrap().line, 12);
+        assert_eq!(map.frame(index, None).unwrap().line, 11);
+        // Runtime helpers carry no source.
+        assert_eq!(map.frame(1, Some(0)), None);
+    }
+
+    #[test]
+    fn stripping_removes_every_marker_and_keeps_the_module_valid() {
+        let wasm = strip_line_markers(&wat::parse_str(MODULE).unwrap());
+        wasmparser::Validator::new().validate_all(&wasm).unwrap();
+        let reads_marker = Parser::new(0).parse_all(&wasm).any(|p| match p {
+            Ok(Payload::CodeSectionEntry(body)) => body
+                .get_operators_reader()
+                .unwrap()
+                .into_iter()
+                .any(|op| matches!(op, Ok(Operator::GlobalGet { .. }))),
+            _ => false,
+        });
+        assert!(!reads_marker);
+        // Stripping is idempotent.
+        assert_eq!(strip_line_markers(&wasm), wasm);
+    }
+
+    #[test]
+    fn module_without_source_records_has_an_empty_map() {
+        let wasm = wat::parse_str("(module (func $f))").unwrap();
+        assert!(SourceMap::from_wasm(&wasm).is_empty());
+    }
+
+    #[test]
+    fn appended_custom_section_keeps_the_module_valid() {
+        let mut wasm = wat::parse_str(MODULE).unwrap();
+        append_custom_section(&mut wasm, SRCMAP_SECTION, b"{}");
+        let found = Parser::new(0).parse_all(&wasm).any(|p| {
+            matches!(p, Ok(Payload::CustomSection(c)) if c.name() == SRCMAP_SECTION && c.data() == b"{}")
+        });
+        assert!(found);
+    }
+}
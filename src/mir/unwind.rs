@@ -0,0 +1,143 @@
+//! Whole-program exception edges. Lowering stamps every block built inside a `try` with that
+//! `try`'s landing block ([`BasicBlock::unwind`]), whether or not anything in it can throw. This step
+//! runs once the program is complete, before reference counting:
+//!
+//! 1. It works out which functions may throw: a function may throw if it ends a block in
+//!    [`Terminator::Throw`], or if one of its unprotected blocks contains a call that may throw.
+//!    Direct calls and constructors resolve to their callee. Calls whose target is only known at
+//!    run time (closures, interface methods, and the `to_string`/`hash_code` of an object) are
+//!    assumed to throw whenever the program throws anywhere.
+//! 2. It clears the unwind edge of every block with no statement that may throw, so a program
+//!    without `throw` or `try` compiles exactly as it would without exceptions.
+//! 3. It gives the unprotected throwing blocks of a function that owns reference locals a cleanup
+//!    block, `tmp = exception; throw tmp`. RC insertion releases the function's locals before that
+//!    `throw` like before any other, so an exception passing through a frame does not leak it.
+//!
+//! `del()` destructors run by `Release` are assumed not to throw. Async functions are left alone:
+//! they may not contain `throw` or `try`, and an exception raised by a callee leaves their poll
+//! function without cleanup.
+
+use super::{
+    BasicBlock, BlockId, FnKey, LocalDecl, Mir, MirFunction, Operand, Place, Rvalue, Statement,
+    Terminator,
+};
+use crate::types::{DefId, PrimTy, TyKind, TypeInterner};
+use std::collections::HashSet;
+
+/// Computes which functions may throw, then clears and adds unwind edges as described above.
+pub fn insert_unwind_cleanup(mir: &mut Mir, interner: &TypeInterner) {
+    let program_throws = mir
+        .functions
+        .iter()
+        .any(|f| f.blocks.iter().any(|b| matches!(b.terminator, Terminator::Throw(_))));
+    let throwing = if program_throws {
+        throwing_functions(mir, interner)
+    } else {
+        Throwing::default()
+    };
+
+    for f in &mut mir.functions {
+        if f.is_async {
+            continue;
+        }
+        let mut uncovered = Vec::new();
+        for (i, block) in f.blocks.iter_mut().enumerate() {
+            let throws = block_may_throw(block, &f.locals, &throwing, program_throws, interner);
+            if !throws {
+                block.unwind = None;
+            } else if block.unwind.is_none() {
+                uncovered.push(i);
+            }
+        }
+        let owns_references = f
+            .locals
+            .iter()
+            .enumerate()
+            .any(|(i, d)| interner.is_reference(d.ty) && !f.params.iter().any(|p| p.0 as usize == i));
+        if uncovered.is_empty() || !owns_references {
+            continue;
+        }
+        let cleanup = add_cleanup_block(f, interner);
+        for i in uncovered {
+            f.blocks[i].unwind = Some(cleanup);
+        }
+    }
+}
+
+/// The functions that may throw: concrete instances for direct calls, and constructor defs (a `New`
+/// names its constructor by def alone).
+#[derive(Default)]
+struct Throwing {
+    functions: HashSet<FnKey>,
+    constructors: HashSet<DefId>,
+}
+
+fn throwing_functions(mir: &Mir, interner: &TypeInterner) -> Throwing {
+    let mut throwing = Throwing::default();
+    loop {
+        let mut changed = false;
+        for f in &mir.functions {
+            let key = (f.def, f.instance.clone());
+            if f.is_async || throwing.functions.contains(&key) {
+                continue;
+            }
+            let throws = f.blocks.iter().any(|b| {
+                matches!(b.terminator, Terminator::Throw(_))
+                    || (b.unwind.is_none() && block_may_throw(b, &f.locals, &throwing, true, interner))
+            });
+            if throws {
+                throwing.functions.insert(key);
+                throwing.constructors.insert(f.def);
+                changed = true;
+            }
+        }
+        if !changed {
+            return throwing;
+        }
+    }
+}
+
+fn block_may_throw(
+    block: &BasicBlock,
+    locals: &[LocalDecl],
+    throwing: &Throwing,
+    program_throws: bool,
+    interner: &TypeInterner,
+) -> bool {
+    let calls = |callee: &super::Callee| throwing.functions.contains(&(callee.def, callee.args.clone()));
+    // The `to_string`/`hash_code` of an object dispatches to user code.
+    let dispatches = |op: &Operand| {
+        let ty = match op {
+            Operand::Copy(Place::Local(l)) => locals[l.0 as usize].ty,
+            _ => return false,
+        };
+        interner.is_reference(ty)
+            && !matches!(interner.kind(interner.strip_nullable(ty)), TyKind::Prim(PrimTy::String))
+    };
+    block.stmts.iter().any(|s| match s {
+        Statement::Call { callee, .. } => calls(callee),
+        Statement::IndirectCall { .. } | Statement::InterfaceCall { .. } => program_throws,
+        Statement::Print { arg, .. } => program_throws && dispatches(arg),
+        Statement::Assign(_, rvalue) => match rvalue {
+            Rvalue::Call { callee, .. } => calls(callee),
+            Rvalue::New { ctor: Some(ctor), .. } => throwing.constructors.contains(ctor),
+            Rvalue::IndirectCall { .. } | Rvalue::InterfaceCall { .. } => program_throws,
+            Rvalue::ToString(o) | Rvalue::HashCode(o) => program_throws && dispatches(o),
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Appends `tmp = exception; throw tmp` (with a fresh `object` temp) and returns its block.
+fn add_cleanup_block(f: &mut MirFunction, interner: &TypeInterner) -> BlockId {
+    let tmp = super::Local(f.locals.len() as u32);
+    f.locals.push(LocalDecl { ty: interner.object(), name: None });
+    let id = BlockId(f.blocks.len() as u32);
+    f.blocks.push(BasicBlock {
+        stmts: vec![Statement::Assign(Place::Local(tmp), Rvalue::Exception)],
+        terminator: Terminator::Throw(Operand::Copy(Place::Local(tmp))),
+        unwind: None,
+    });
+    id
+}
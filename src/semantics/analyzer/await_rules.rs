@@ -7,6 +7,8 @@ use super::Analyzer;
 use crate::diagnostics::DiagnosticBag;
 use crate::syntax::nodes::{ExpressionNode, FunctionNode, StatementNode};
 
+const LAMBDA_AWAIT_MESSAGE: &str = "'await' cannot be used inside a lambda";
+
 impl<'a> Analyzer<'a> {
     /// Awaits nested in sub-expressions, loops, branches, or non-async functions are rejected.
     pub(super) fn check_await_positions(
@@ -26,11 +28,15 @@ impl<'a> Analyzer<'a> {
         }
         for stmt in function.body.iter() {
             match stmt {
-                StatementNode::Declaration(_, _, ExpressionNode::Await(inner), _) => {
-                    self.forbid_await_in_expr(inner, diagnostics);
+                StatementNode::Declaration(_, _, e, _) | StatementNode::Return(Some(e))
+                    if Self::awaited_operand(e).is_some() =>
+                {
+                    self.forbid_await_in_expr(Self::awaited_operand(e).unwrap(), diagnostics);
                 }
-                StatementNode::Return(Some(ExpressionNode::Await(inner))) => {
-                    self.forbid_await_in_expr(inner, diagnostics);
+                StatementNode::ExpressionStatement(e @ ExpressionNode::Propagate(..))
+                    if Self::awaited_operand(e).is_some() =>
+                {
+                    self.forbid_await_in_expr(Self::awaited_operand(e).unwrap(), diagnostics);
                 }
                 StatementNode::AwaitStmt(inner) => {
                     self.forbid_await_in_expr(inner, diagnostics);
@@ -42,6 +48,36 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// The future of a statement-level `await e` or `(await e)?`, the shapes the async lowering
+    /// suspends on.
+    fn awaited_operand<'e>(expr: &'e ExpressionNode<'a>) -> Option<&'e ExpressionNode<'a>> {
+        match expr {
+            ExpressionNode::Await(inner) => Some(inner),
+            ExpressionNode::Propagate(ExpressionNode::Await(inner), _) => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// A lambda is never async, so an `await` anywhere in its block body is rejected.
+    pub(super) fn forbid_await_in_lambda(
+        &self,
+        body: &[StatementNode<'a>],
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        for stmt in body.iter() {
+            self.forbid_await_in_stmt(stmt, LAMBDA_AWAIT_MESSAGE, diagnostics);
+        }
+    }
+
+    /// Like [`Self::forbid_await_in_lambda`], for an expression-bodied lambda.
+    pub(super) fn forbid_await_in_lambda_expr(
+        &self,
+        body: &ExpressionNode<'a>,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        self.scan_expr_await(body, LAMBDA_AWAIT_MESSAGE, diagnostics);
+    }
+
     /// Reports `message` at every `await` found anywhere inside `stmt` (including nested bodies).
     fn forbid_await_in_stmt(
         &self,
@@ -55,13 +91,16 @@ impl<'a> Analyzer<'a> {
                 self.scan_expr_await(inner, message, diagnostics);
             }
             StatementNode::Declaration(_, _, e, _)
+            | StatementNode::DestructuringDeclaration(_, e, _)
             | StatementNode::Assignment(_, e)
             | StatementNode::IndexAssignment(_, _, e)
             | StatementNode::ExpressionStatement(e)
             | StatementNode::MemberAssignment(_, _, e) => {
                 self.scan_expr_await(e, message, diagnostics);
             }
-            StatementNode::Return(Some(e)) => self.scan_expr_await(e, message, diagnostics),
+            StatementNode::Return(Some(e)) | StatementNode::Throw(_, e) => {
+                self.scan_expr_await(e, message, diagnostics)
+            }
             StatementNode::FunctionInvocation(_, _, args) => {
                 for a in args {
                     self.scan_expr_await(a, message, diagnostics);
@@ -128,6 +167,16 @@ impl<'a> Analyzer<'a> {
                     }
                 }
             }
+            StatementNode::Try(_, body, catches, finally) => {
+                let bodies = std::iter::once(*body)
+                    .chain(catches.iter().map(|c| c.body))
+                    .chain(finally.iter().copied());
+                for b in bodies {
+                    for s in b.iter() {
+                        self.forbid_await_in_stmt(s, message, diagnostics);
+                    }
+                }
+            }
             StatementNode::Labeled(_, inner) => {
                 self.forbid_await_in_stmt(inner, message, diagnostics)
             }
@@ -161,6 +210,7 @@ impl<'a> Analyzer<'a> {
             ExpressionNode::Unary(_, e)
             | ExpressionNode::Parenthesized(e)
             | ExpressionNode::Cast(_, e)
+            | ExpressionNode::Propagate(e, _)
             | ExpressionNode::IsExpression(e, _, _) => self.scan_expr_await(e, message, diagnostics),
             ExpressionNode::FunctionCall(_, _, args) => {
                 for a in args {
@@ -173,7 +223,7 @@ impl<'a> Analyzer<'a> {
                     self.scan_expr_await(a, message, diagnostics);
                 }
             }
-            ExpressionNode::ArrayLiteral(elems) => {
+            ExpressionNode::ArrayLiteral(elems) | ExpressionNode::Tuple(_, elems) => {
                 for e in elems {
                     self.scan_expr_await(e, message, diagnostics);
                 }
@@ -10,10 +10,11 @@ use crate::semantics::symbol_table::SymbolTable;
 use crate::syntax::nodes::types::{
     canonical_type_name, is_numeric_primitive, is_unknown_type_name, mangle_generic, strip_nullable,
 };
-use crate::types::{constructor_fn, method_fn};
+use crate::syntax::nodes::struct_node::StructDeclarationNode;
 use crate::syntax::nodes::{ExpressionNode, FunctionNode, Type};
 use crate::syntax::token::syntax_token::SyntaxToken;
 use crate::syntax::token::token_kind::TokenKind;
+use crate::types::{constructor_fn, method_fn};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -26,7 +27,18 @@ pub(super) enum HookResolution {
     /// (it is static, async, or has the wrong number of parameters).
     Ineligible(String),
     /// A usable hook: an accessible instance, non-async method with the requested declared arity.
-    Eligible(FunctionTableInfo),
+    Eligible(Box<FunctionTableInfo>),
+}
+
+/// The generic method a method call resolves to (see `Analyzer::analyze_generic_method_call`).
+struct GenericMethodCall<'a> {
+    /// The method's `{Type}_{method}` name.
+    base: String,
+    template: &'a FunctionNode<'a>,
+    /// The bindings of the receiver's generic class.
+    outer: GenericBindings,
+    /// The receiver type of an instance method; `None` for a static `Array` method.
+    this: Option<String>,
 }
 
 impl<'a> Analyzer<'a> {
@@ -100,7 +112,7 @@ impl<'a> Analyzer<'a> {
                 });
                 continue;
             }
-            return HookResolution::Eligible(info);
+            return HookResolution::Eligible(Box::new(info));
         }
         match ineligible_reason {
             Some(reason) => HookResolution::Ineligible(reason),
@@ -239,12 +251,10 @@ impl<'a> Analyzer<'a> {
             store_sig.return_type.unwrap_or(Type::Void)
         };
         // A static method is a free function under its mangled `{Type}_{method}` name (no receiver);
-        // overloaded names are ambiguous for a single `DefId` lookup, so defer those.
-        if self.function_table.is_overloaded(&base) {
-            self.hir_none();
-        } else {
-            self.hir_set_call(&base, arg_hirs, &ret_type);
-        }
+        // like overloaded free functions, an overload resolves to the selected overload's emitted
+        // name (each is a distinct `DefId`).
+        self.hir_box_object_args(&expected_params, &mut arg_hirs);
+        self.hir_set_call(&store_sig.name, arg_hirs, &ret_type);
         Ok(ret_type)
     }
 
@@ -256,6 +266,7 @@ impl<'a> Analyzer<'a> {
         parent_function: &FunctionNode<'a>,
         base_name: &str,
     ) -> bool {
+        let parent_function = self.closure_hosts.first().unwrap_or(parent_function);
         // A `static` method belongs to its declaring type, so it may access that type's private
         // members even though it has no `this` receiver. Static methods are registered under the
         // mangled name `{Type}_{method}`, so a name prefixed with `{base_name}_` identifies one.
@@ -274,9 +285,11 @@ impl<'a> Analyzer<'a> {
         let this_base = Self::resolve_struct_parts(&first.type_)
             .map(|(b, _)| b)
             .unwrap_or_else(|| strip_nullable(&first.type_.get_type()).to_string());
+        // A subclass's methods see its base classes' private members, which it inherits.
         this_base == base_name
             || this_base.starts_with(&format!("{}_", base_name))
             || base_name.starts_with(&format!("{}_", this_base))
+            || self.is_subclass_of(&this_base, base_name)
     }
 
     /// Appends the default values of any omitted trailing parameters to a call's argument lists.
@@ -305,6 +318,64 @@ impl<'a> Analyzer<'a> {
         Ok(())
     }
 
+    /// The declared parameter types of the function `name` calls (a function-typed variable, a
+    /// non-overloaded free function, or a class constructor), used as the expected types of lambda
+    /// arguments. Empty when the callee is unknown or overloaded.
+    fn call_param_hints(
+        &self,
+        name: &SyntaxToken,
+        generic_args: &Option<Vec<Type>>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+    ) -> Vec<Type> {
+        if let Ok(Type::Function(params, _)) = (*symbol_table).as_ref().borrow().get_symbol(name) {
+            return params;
+        }
+        if let Some(template) = self.generic_structs.get(&name.text) {
+            return self.constructor_param_hints(template, generic_args).unwrap_or_default();
+        }
+        let init_name = constructor_fn(&name.text);
+        if self.struct_table.get_struct(&name.text).is_some() && !self.function_table.is_overloaded(&init_name) {
+            return self
+                .function_table
+                .get_function(&init_name)
+                .map(|f| f.parameters.iter().skip(1).map(|p| Self::type_from_name(p)).collect())
+                .unwrap_or_default();
+        }
+        if self.function_table.is_overloaded(&name.text) {
+            return vec![];
+        }
+        self.function_table
+            .get_function(&name.text)
+            .map(|f| f.parameters.iter().map(|p| Self::type_from_name(p)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The parameter types of generic class `template`'s constructor with the class's type
+    /// parameters replaced by `generic_args`, or `None` when the call names no type arguments.
+    fn constructor_param_hints(
+        &self,
+        template: &StructDeclarationNode<'a>,
+        generic_args: &Option<Vec<Type>>,
+    ) -> Option<Vec<Type>> {
+        let params = template.generic_parameters.as_deref()?;
+        let bindings: GenericBindings = params
+            .iter()
+            .map(|p| p.text.clone())
+            .zip(generic_args.as_ref()?.iter().cloned())
+            .collect();
+        let constructor = template
+            .methods
+            .iter()
+            .find(|m| m.name.text == crate::syntax::nodes::types::CONSTRUCTOR_NAME)?;
+        Some(
+            constructor
+                .parameters
+                .iter()
+                .map(|p| Self::monomorphize_type(&p.type_, &bindings))
+                .collect(),
+        )
+    }
+
     pub(super) fn analyze_function_call(
         &mut self,
         name: &SyntaxToken,
@@ -314,11 +385,41 @@ impl<'a> Analyzer<'a> {
         symbol_table: &Rc<RefCell<SymbolTable>>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Type, SemanticError> {
+        if name.text == SUPER
+            && (*symbol_table).as_ref().borrow().get_symbol(name).is_err()
+            && self.function_table.get_function(&name.text).is_err()
+        {
+            return self.analyze_super_constructor_call(
+                name,
+                params,
+                parent_function,
+                symbol_table,
+                diagnostics,
+            );
+        }
         let mut function_name = name.text.clone();
         let mut params_types = vec![];
         let mut arg_hirs = vec![];
-        for param in params.iter() {
-            let t = self.analyze_expression(param, parent_function, symbol_table, diagnostics)?;
+        let hints = self.call_param_hints(name, generic_args, symbol_table);
+        let generic_template = self
+            .generic_functions
+            .get(&name.text)
+            .copied()
+            .filter(|_| (*symbol_table).as_ref().borrow().get_symbol(name).is_err());
+        for (i, param) in params.iter().enumerate() {
+            let hint = match generic_template {
+                Some(template) => Self::generic_arg_hint(
+                    template,
+                    &GenericBindings::new(),
+                    generic_args,
+                    i,
+                    &params_types,
+                    self.current_expected_type.as_ref(),
+                ),
+                None => hints.get(i).cloned(),
+            };
+            let t =
+                self.analyze_call_arg(param, hint, parent_function, symbol_table, diagnostics)?;
             arg_hirs.push(self.hir_take());
             params_types.push(t.get_type());
         }
@@ -439,10 +540,19 @@ impl<'a> Analyzer<'a> {
                 template,
                 generic_args,
                 &params_types,
+                self.current_expected_type.as_ref(),
                 &name.position,
                 diagnostics,
             );
             let mangled_name = mangle_bindings(&function_name, &bindings);
+            self.check_instance_bounds(
+                &template.name.text,
+                &template.generic_bounds,
+                &bindings,
+                &mangled_name,
+                &name.position,
+                diagnostics,
+            );
             generic_instance = Some((
                 function_name.clone(),
                 bindings.values().cloned().collect(),
@@ -562,6 +672,9 @@ impl<'a> Analyzer<'a> {
                 {
                     continue;
                 }
+                if self.class_upcastable(expected, given) {
+                    continue;
+                }
                 diagnostics.report_error(
                     format!(
                         "Function {} has param {} of type {:?} but param {} of type {:?} is given",
@@ -590,6 +703,7 @@ impl<'a> Analyzer<'a> {
         // the monomorphization args (so it targets the emitted instance); a plain non-overloaded
         // free function resolves by name. Overloads would collide on the base name's single `DefId`,
         // so they stay on the legacy path for now.
+        self.hir_box_object_args(&store_sig.parameters, &mut arg_hirs);
         if let Some((base_name, instance_types)) = generic_instance {
             let instance = instance_types
                 .iter()
@@ -714,12 +828,14 @@ impl<'a> Analyzer<'a> {
             let given_class = crate::syntax::nodes::types::strip_nullable(given);
             return self.class_implements(given_class, iface);
         }
-        false
+        // Implicit upcast to a base class parameter.
+        self.class_upcastable(expected, given)
     }
 
     /// Type-checks a constructor call `Struct(args)`. When the struct defines a custom `constructor`
     /// the call is checked against `init`'s parameters; otherwise the class has an implicit zero-arg
     /// default constructor (`Struct()`) that leaves every field at its zero value.
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn analyze_constructor_call(
         &mut self,
         name: &SyntaxToken,
@@ -794,6 +910,7 @@ impl<'a> Analyzer<'a> {
                 symbol_table,
                 diagnostics,
             )?;
+            self.hir_box_object_args(&expected, arg_hirs);
             for i in 0..expected.len() {
                 let e = expected[i].as_str();
                 let g = params_types[i].as_str();
@@ -823,14 +940,17 @@ impl<'a> Analyzer<'a> {
         &mut self,
         obj: &ExpressionNode<'a>,
         method: &SyntaxToken,
-        _generic_args: &Option<Vec<Type>>,
+        generic_args: &Option<Vec<Type>>,
         params: &Vec<ExpressionNode<'a>>,
         ctx: &super::AnalyzerContext<'a, '_>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Type, SemanticError> {
         if let ExpressionNode::Identifier(id) = obj {
+            if id.text == SUPER && (*ctx.symbol_table).as_ref().borrow().get_symbol(id).is_err() {
+                return self.analyze_super_method_call(id, method, params, ctx, diagnostics);
+            }
             if let Some(t) =
-                self.try_analyze_static_method(id, method, _generic_args, params, ctx, diagnostics)?
+                self.try_analyze_static_method(id, method, generic_args, params, ctx, diagnostics)?
             {
                 return Ok(t);
             }
@@ -865,7 +985,120 @@ impl<'a> Analyzer<'a> {
             return Ok(t);
         }
 
-        self.analyze_instance_method(&obj_type, method, params, ctx, recv, diagnostics)
+        self.analyze_instance_method(&obj_type, method, generic_args, params, ctx, recv, diagnostics)
+    }
+
+    /// `super.method(args)`: calls the base class's implementation of `method` on `this`. Only
+    /// valid in the instance methods of a subclass.
+    fn analyze_super_method_call(
+        &mut self,
+        super_token: &SyntaxToken,
+        method: &SyntaxToken,
+        params: &Vec<ExpressionNode<'a>>,
+        ctx: &super::AnalyzerContext<'a, '_>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let Some(base) = self.enclosing_base_class(ctx.parent_function) else {
+            return Err(report(
+                diagnostics,
+                "'super' can only be used in the instance methods of a class that extends another class"
+                    .to_string(),
+                Some(super_token.position),
+            ));
+        };
+        self.analyze_super_call(super_token, &base, method, params, ctx, diagnostics)
+    }
+
+    /// `super(args)`: runs the base class's constructor on `this`. Only valid as the first
+    /// statement of a subclass constructor; a constructor without one chains to the base's
+    /// zero-argument constructor implicitly (see `implicit_super_body`).
+    fn analyze_super_constructor_call(
+        &mut self,
+        name: &SyntaxToken,
+        params: &Vec<ExpressionNode<'a>>,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        self.hir_none();
+        let leads_body = matches!(
+            parent_function.body.first(),
+            Some(crate::syntax::nodes::StatementNode::FunctionInvocation(first, ..))
+                if std::ptr::eq(first, name)
+        );
+        let base = self
+            .method_names
+            .get(&parent_function.name.text)
+            .filter(|(_, member)| member == crate::syntax::nodes::types::CONSTRUCTOR_NAME)
+            .and_then(|(class, _)| self.base_classes.get(class))
+            .filter(|_| leads_body && self.closure_hosts.is_empty())
+            .cloned();
+        let Some(base) = base else {
+            return Err(report(
+                diagnostics,
+                "'super(...)' can only be called as the first statement of a subclass constructor"
+                    .to_string(),
+                Some(name.position),
+            ));
+        };
+        if self.function_table.get_function(&constructor_fn(&base)).is_err() {
+            // The base only has the implicit zero-argument constructor, which does nothing.
+            if !params.is_empty() {
+                diagnostics.report_error(
+                    format!(
+                        "Constructor for '{}' expects 0 argument(s), but {} were given",
+                        base,
+                        params.len()
+                    ),
+                    Some(name.position),
+                );
+            }
+            return Ok(Type::Void);
+        }
+        let mut constructor = name.clone();
+        constructor.text = crate::syntax::nodes::types::CONSTRUCTOR_NAME.to_string();
+        let ctx = super::AnalyzerContext {
+            parent_function,
+            symbol_table,
+        };
+        self.analyze_super_call(name, &base, &constructor, params, &ctx, diagnostics)
+    }
+
+    /// Calls class `base`'s own `method` on `this`, bypassing virtual dispatch.
+    fn analyze_super_call(
+        &mut self,
+        super_token: &SyntaxToken,
+        base: &str,
+        method: &SyntaxToken,
+        params: &Vec<ExpressionNode<'a>>,
+        ctx: &super::AnalyzerContext<'a, '_>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let mut this = super_token.clone();
+        this.text = "this".to_string();
+        self.analyze_expression(
+            &ExpressionNode::Identifier(this),
+            ctx.parent_function,
+            ctx.symbol_table,
+            diagnostics,
+        )?;
+        let receiver = self.hir_take();
+        let base_ty = Type::Struct(synthetic_token(TokenKind::IdentifierToken, base), None);
+        self.dispatch_statically = true;
+        self.analyze_instance_method(&base_ty, method, &None, params, ctx, receiver, diagnostics)
+    }
+
+    /// The base class of the class whose instance method (or a lambda inside one) is being
+    /// analyzed, if that class extends another.
+    pub(super) fn enclosing_base_class(&self, parent_function: &FunctionNode<'a>) -> Option<String> {
+        let host = self.closure_hosts.first().unwrap_or(parent_function);
+        if host.is_static {
+            return None;
+        }
+        let this = host.parameters.first().filter(|p| p.name.text == "this")?;
+        self.base_classes
+            .get(strip_nullable(&this.type_.get_type()))
+            .cloned()
     }
 
     /// Handles `Type.method(args)` static dispatch when the receiver `id` names a type rather than
@@ -911,9 +1144,18 @@ impl<'a> Analyzer<'a> {
             let template = *self.generic_functions.get(&base).unwrap();
             let mut params_types = vec![];
             let mut arg_hirs = vec![];
-            for param in params.iter() {
-                let t = self.analyze_expression(
+            for (i, param) in params.iter().enumerate() {
+                let hint = Self::generic_arg_hint(
+                    template,
+                    &GenericBindings::new(),
+                    generic_args,
+                    i,
+                    &params_types,
+                    self.current_expected_type.as_ref(),
+                );
+                let t = self.analyze_call_arg(
                     param,
+                    hint,
                     ctx.parent_function,
                     ctx.symbol_table,
                     diagnostics,
@@ -942,8 +1184,7 @@ impl<'a> Analyzer<'a> {
                 }
                 return Ok(Some(Type::Void));
             }
-            // Generic static calls / intrinsics need an `InstanceId` (a later slice); stay out of
-            // HIR coverage regardless of which sub-branch handles the call.
+            // The intrinsic sub-branches below set their own HIR; default to none.
             self.hir_none();
             // `Array.new<T>(len)`: a generic intrinsic that allocates a zero-initialized
             // `T[]`. The element type comes from the explicit type argument (resolved
@@ -985,10 +1226,19 @@ impl<'a> Analyzer<'a> {
                 template,
                 generic_args,
                 &params_types,
+                self.current_expected_type.as_ref(),
                 &method.position,
                 diagnostics,
             );
             let mangled_name = mangle_bindings(&base, &bindings);
+            self.check_instance_bounds(
+                &template.name.text,
+                &template.generic_bounds,
+                &bindings,
+                &mangled_name,
+                &method.position,
+                diagnostics,
+            );
 
             // Promise combinators (`Promise.all/any/race`) are typed by the shared async
             // intrinsic logic; classify via the registry and delegate when applicable.
@@ -1060,22 +1310,19 @@ impl<'a> Analyzer<'a> {
                 return Ok(Some(t_type));
             }
 
-            if self.function_table.get_function(&mangled_name).is_err() {
-                let mut specialized = template.clone();
-                Self::substitute_generic_signature(&mut specialized, &bindings);
-                let specialized_ref: &'a FunctionNode<'a> = self.arena.alloc(specialized);
-                let info = FunctionTableInfo::from(specialized_ref);
-                self.function_table
-                    .add_function(mangled_name.clone(), info)
-                    .unwrap();
-                self.instantiated_generics
-                    .insert(mangled_name.clone(), (bindings, specialized_ref));
-            }
+            let mangled_name = self.instantiate_generic_method(&base, template, &bindings, None);
+            self.record_method_call(&mangled_name, method.position, diagnostics);
             let info = self.function_table.get_function(&mangled_name).unwrap();
-            if info.is_async {
-                return Ok(Some(Self::future_type(info.return_type.unwrap_or(Type::Void))));
-            }
-            return Ok(Some(info.return_type.unwrap_or(Type::Void)));
+            let ret = if info.is_async {
+                Self::future_type(info.return_type.unwrap_or(Type::Void))
+            } else {
+                info.return_type.unwrap_or(Type::Void)
+            };
+            // An ordinary generic static method resolves like a generic free function: the
+            // template's base `DefId` plus the monomorphization args.
+            let instance = bindings.values().map(|t| self.type_ctx.lower(t)).collect();
+            self.hir_set_generic_call(&base, instance, arg_hirs, &ret);
+            return Ok(Some(ret));
         }
 
         if self.function_table.is_overloaded(&base)
@@ -1308,10 +1555,15 @@ impl<'a> Analyzer<'a> {
             }
         }
 
-        let iface_id = self
-            .interface_methods
-            .get_index_of(iface_name)
-            .unwrap_or(0);
+        // Ids index the emitted interfaces, which follow the class vtables and leave out the
+        // archetypes (see `hir_build_interfaces`).
+        let iface_id = self.vtables.len()
+            + self
+                .interface_methods
+                .keys()
+                .filter(|name| !is_archetype_derived(name))
+                .position(|name| name == iface_name)
+                .unwrap_or(0);
         // The `call_indirect` signature is `fun(this, params...): ret`, with `this` typed as
         // `object` (an `i32` pointer, matching every concrete implementation's receiver).
         let sig = self.interface_dispatch_sig(im);
@@ -1342,15 +1594,42 @@ impl<'a> Analyzer<'a> {
         self.type_ctx.interner.func(params, ret)
     }
 
-    fn analyze_instance_method(
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn analyze_instance_method(
         &mut self,
         obj_type: &Type,
         method: &SyntaxToken,
+        generic_args: &Option<Vec<Type>>,
         params: &Vec<ExpressionNode<'a>>,
         ctx: &super::AnalyzerContext<'a, '_>,
         receiver: Option<crate::hir::HExpr>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Type, SemanticError> {
+        // Consumed up front so calls nested in the arguments dispatch normally.
+        let statically = std::mem::take(&mut self.dispatch_statically);
+        // An array has no methods of its own besides the builtins: `xs.m(args)` calls the generic
+        // `Array.m(xs, args)` of `stdlib/core/array.dream`.
+        if matches!(obj_type, Type::Array(_)) {
+            let base = method_fn("Array", &method.text);
+            if let Some(template) = self.generic_functions.get(&base).copied().filter(|t| t.is_static) {
+                let call = GenericMethodCall {
+                    base,
+                    template,
+                    outer: GenericBindings::new(),
+                    this: None,
+                };
+                return self.analyze_generic_method_call(
+                    call,
+                    obj_type,
+                    receiver,
+                    method,
+                    generic_args,
+                    params,
+                    ctx,
+                    diagnostics,
+                );
+            }
+        }
         // A generic interface receiver (e.g. `Container<int>`) must be monomorphized before dispatch
         // so its concrete method slots exist, even if no implementing class was instantiated earlier
         // in analysis order.
@@ -1391,13 +1670,58 @@ impl<'a> Analyzer<'a> {
 
         let mangled_name = method_fn(&struct_name, &method.text);
 
+        // A method with type parameters of its own is instantiated per call, alongside the
+        // receiver's class bindings.
+        if let Some(template) = self
+            .generic_functions
+            .get(&mangled_name)
+            .copied()
+            .filter(|t| !t.is_static)
+        {
+            let outer = self
+                .class_methods
+                .get(&struct_name)
+                .and_then(|methods| methods.iter().find(|(m, _)| std::ptr::eq(*m, template)))
+                .map(|(_, bindings)| bindings.clone())
+                .unwrap_or_default();
+            let call = GenericMethodCall {
+                base: mangled_name,
+                template,
+                outer,
+                this: Some(struct_name),
+            };
+            return self.analyze_generic_method_call(
+                call,
+                obj_type,
+                receiver,
+                method,
+                generic_args,
+                params,
+                ctx,
+                diagnostics,
+            );
+        }
+
         // Analyze the explicit arguments once, then resolve the method (overloaded methods select
         // by argument types, with the receiver supplied as the implicit `this` argument).
+        // A lambda argument takes its parameter types from the method's declared ones (past `this`),
+        // unless the method is overloaded.
+        let hints: Vec<Type> = match self.function_table.get_function(&mangled_name) {
+            Ok(info) if !self.function_table.is_overloaded(&mangled_name) => {
+                info.parameters.iter().skip(1).map(|p| Self::type_from_name(p)).collect()
+            }
+            _ => Vec::new(),
+        };
         let mut arg_types = Vec::new();
         let mut arg_hirs = Vec::new();
-        for param in params.iter() {
-            let t =
-                self.analyze_expression(param, ctx.parent_function, ctx.symbol_table, diagnostics)?;
+        for (i, param) in params.iter().enumerate() {
+            let t = self.analyze_call_arg(
+                param,
+                hints.get(i).cloned(),
+                ctx.parent_function,
+                ctx.symbol_table,
+                diagnostics,
+            )?;
             arg_hirs.push(self.hir_take());
             arg_types.push(t.get_type());
         }
@@ -1494,7 +1818,9 @@ impl<'a> Analyzer<'a> {
                 continue;
             }
 
-            if given_type != expected_type_str {
+            if given_type != expected_type_str
+                && !self.class_upcastable(expected_type_str, given_type)
+            {
                 diagnostics.report_error(
                     format!(
                         "function {} expects parameter {} to be {}, got {}",
@@ -1517,10 +1843,185 @@ impl<'a> Analyzer<'a> {
         } else {
             store_sig.return_type.unwrap_or(Type::Void)
         };
+        self.hir_box_object_args(&expected_params, &mut arg_hirs);
+        // A virtual method dispatches on the receiver's runtime class through the static class's
+        // vtable, unless this is a `super.m()` call naming the base implementation.
+        if let Some((vtable_id, slot, declared)) = self
+            .virtual_slot(&struct_name, &method.text)
+            .filter(|_| !statically)
+        {
+            let sig = self.interface_dispatch_sig(declared);
+            self.hir_set_interface_call(receiver, vtable_id, slot, sig, arg_hirs, &ret_type);
+            return Ok(ret_type);
+        }
         // Overloaded methods each register a distinct `DefId` under their emitted (signature-mangled)
         // name; resolve to the selected overload's name so the call targets the right instance.
         // Non-overloaded methods keep their base-mangled name.
+        self.record_method_call(&store_sig.name, method.position, diagnostics);
         self.hir_set_method_call(receiver, &store_sig.name, arg_hirs, &ret_type);
         Ok(ret_type)
     }
+
+    /// Calls a generic method on `receiver` (of type `obj_type`): an instance method, or for an
+    /// array receiver a static `Array` method taking the array as its first parameter. Each lambda
+    /// argument is analyzed against its parameter type with the type parameters fixed so far, then
+    /// the remaining ones are inferred from all the arguments and the instance is registered.
+    #[allow(clippy::too_many_arguments)]
+    fn analyze_generic_method_call(
+        &mut self,
+        call: GenericMethodCall<'a>,
+        obj_type: &Type,
+        receiver: Option<crate::hir::HExpr>,
+        method: &SyntaxToken,
+        generic_args: &Option<Vec<Type>>,
+        params: &Vec<ExpressionNode<'a>>,
+        ctx: &super::AnalyzerContext<'a, '_>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let GenericMethodCall { base, template, outer, this } = call;
+        // A static method takes the receiver as its first declared parameter; an instance method
+        // takes it as the implicit `this`, which the template does not declare.
+        let mut arg_types = Vec::new();
+        if this.is_none() {
+            arg_types.push(obj_type.get_type());
+        }
+        let leading = arg_types.len();
+        let mut arg_hirs = vec![receiver];
+        for (i, param) in params.iter().enumerate() {
+            let hint = Self::generic_arg_hint(
+                template,
+                &outer,
+                generic_args,
+                leading + i,
+                &arg_types,
+                self.current_expected_type.as_ref(),
+            );
+            let t = self.analyze_call_arg(
+                param,
+                hint,
+                ctx.parent_function,
+                ctx.symbol_table,
+                diagnostics,
+            )?;
+            arg_hirs.push(self.hir_take());
+            arg_types.push(t.get_type());
+        }
+
+        // Infer against the signature with the declaring class's type arguments already in place,
+        // so a formal like `fun(T): Result<U, E>` only leaves the method's own `U` to read off.
+        let mut formal = template.clone();
+        Self::substitute_generic_signature(&mut formal, &outer);
+        let mut bindings = outer;
+        bindings.extend(self.infer_generic_bindings(
+            &formal,
+            generic_args,
+            &arg_types,
+            self.current_expected_type.as_ref(),
+            &method.position,
+            diagnostics,
+        ));
+        let mangled_name = self.instantiate_generic_method(&base, template, &bindings, this.as_deref());
+        self.record_method_call(&mangled_name, method.position, diagnostics);
+        self.check_instance_bounds(
+            &template.name.text,
+            &template.generic_bounds,
+            &bindings,
+            &mangled_name,
+            &method.position,
+            diagnostics,
+        );
+        let info = self.function_table.get_function(&mangled_name).unwrap().clone();
+
+        if !info.is_public {
+            let owner = this.as_deref().unwrap_or("Array");
+            let owner = self.demangle_generic_struct(owner).map(|(b, _)| b).unwrap_or(owner.to_string());
+            if !self.in_methods_of(ctx.parent_function, &owner) {
+                diagnostics.report_error(
+                    format!("'{}' is private to '{}'", method.text, owner),
+                    Some(method.position),
+                );
+            }
+        }
+
+        // Line the declared parameters up with `arg_types`, which hold the receiver only when it
+        // is a declared parameter.
+        let expected = &info.parameters[1 - leading..];
+        if arg_types.len() != expected.len() {
+            diagnostics.report_error(
+                format!(
+                    "function {} expects {} parameters, got {}",
+                    base,
+                    expected.len() - leading,
+                    arg_types.len() - leading
+                ),
+                Some(method.position),
+            );
+            self.hir_none();
+            return Ok(info.return_type.unwrap_or(Type::Void));
+        }
+        for (i, (given, expected)) in arg_types.iter().zip(expected).enumerate().skip(leading) {
+            if expected == "object"
+                || is_unknown_type_name(given)
+                || (is_numeric_primitive(expected) && is_numeric_primitive(given))
+                || given == expected
+                || self.class_upcastable(expected, given)
+            {
+                continue;
+            }
+            diagnostics.report_error(
+                format!(
+                    "function {} expects parameter {} to be {}, got {}",
+                    base,
+                    i + 1 - leading,
+                    expected,
+                    given
+                ),
+                Some(method.position),
+            );
+        }
+
+        let ret = info.return_type.unwrap_or(Type::Void);
+        let instance = bindings.values().map(|t| self.type_ctx.lower(t)).collect();
+        self.hir_set_generic_call(&base, instance, arg_hirs, &ret);
+        Ok(ret)
+    }
+
+    /// Registers (once) the instance of generic method `base` (`{Type}_{method}`) for `bindings`,
+    /// which also carry the type arguments of a generic declaring class, and returns its mangled
+    /// name. An instance method (`this` is its receiver type) gets the implicit `this` parameter.
+    fn instantiate_generic_method(
+        &mut self,
+        base: &str,
+        template: &'a FunctionNode<'a>,
+        bindings: &GenericBindings,
+        this: Option<&str>,
+    ) -> String {
+        let mangled_name = mangle_bindings(base, bindings);
+        if self.function_table.get_function(&mangled_name).is_err() {
+            let mut specialized = template.clone();
+            Self::substitute_generic_signature(&mut specialized, bindings);
+            // The template keeps its bare method name; the instance is emitted under the
+            // `{Type}_{method}` def the call site resolves to.
+            specialized.name = synthetic_token(TokenKind::IdentifierToken, base);
+            if let Some(this) = this {
+                specialized.parameters.insert(0, Self::make_this_param(this));
+            }
+            let specialized_ref: &'a FunctionNode<'a> = self.arena.alloc(specialized);
+            let info = FunctionTableInfo::from(specialized_ref);
+            self.function_table
+                .add_function(mangled_name.clone(), info)
+                .unwrap();
+            self.instantiated_generics
+                .insert(mangled_name.clone(), (bindings.clone(), specialized_ref));
+        }
+        mangled_name
+    }
+
+    /// The vtable id, slot and declaring method of virtual method `method` on class `class`, if it
+    /// is one.
+    fn virtual_slot(&self, class: &str, method: &str) -> Option<(usize, usize, &'a FunctionNode<'a>)> {
+        let (vtable_id, _, slots) = self.vtables.get_full(class)?;
+        let slot = slots.iter().position(|m| m.name.text == method)?;
+        Some((vtable_id, slot, slots[slot]))
+    }
 }
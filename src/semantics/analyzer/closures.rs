@@ -0,0 +1,185 @@
+//! Analysis of lambda expressions. A lambda is type-checked as a nested function: its parameters
+//! live in a closure-boundary scope parented off the enclosing scope (so outer locals resolve, and
+//! are captured, through ordinary lookup), and its body is analyzed against a synthesized
+//! [`FunctionNode`] carrying the lambda's signature. Parameter and return types come from the
+//! annotations when written, otherwise from the function type the context expects.
+
+use super::*;
+use crate::semantics::function_control_flow::FunctionControlGraph;
+use crate::syntax::nodes::function::ParameterNode;
+use crate::syntax::nodes::types::UNKNOWN_TYPE_NAME;
+use crate::syntax::nodes::{ExpressionNode, LambdaBody, LambdaNode};
+
+impl<'a> Analyzer<'a> {
+    pub(super) fn analyze_lambda(
+        &mut self,
+        lambda: &'a LambdaNode<'a>,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let position = lambda.arrow.position;
+        let expected = match self.current_expected_type.clone() {
+            Some(Type::Function(params, ret)) => Some((params, *ret)),
+            Some(Type::Nullable(inner)) => match *inner {
+                Type::Function(params, ret) => Some((params, *ret)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((params, _)) = &expected {
+            if params.len() != lambda.parameters.len() {
+                self.hir_fail();
+                return Err(report(
+                    diagnostics,
+                    format!(
+                        "lambda takes {} parameters but a function with {} parameters is expected",
+                        lambda.parameters.len(),
+                        params.len()
+                    ),
+                    Some(position),
+                ));
+            }
+        }
+
+        let mut params = Vec::with_capacity(lambda.parameters.len());
+        for (i, param) in lambda.parameters.iter().enumerate() {
+            let ty = match (&param.type_, &expected) {
+                (Some(t), _) => Self::monomorphize_type(t, &self.current_generic_bindings),
+                // An unknown expected parameter is a type parameter of a generic callee that
+                // nothing before the lambda fixed (see `generic_arg_hint`).
+                (None, Some((expected_params, _))) if !expected_params[i].is_unknown() => {
+                    expected_params[i].clone()
+                }
+                (None, _) => {
+                    diagnostics.report_error(
+                        format!(
+                            "cannot infer the type of lambda parameter '{}'; annotate it (e.g. `({}: int) => ...`)",
+                            param.name.text, param.name.text
+                        ),
+                        Some(param.name.position),
+                    );
+                    Type::Unknown
+                }
+            };
+            params.push((param.name.clone(), ty));
+        }
+
+        let param_table = Rc::new(RefCell::new(SymbolTable::new_closure(symbol_table.clone())));
+        for (name, ty) in params.iter() {
+            self.check_reserved_name(name, "parameter", diagnostics);
+            if let Err(e) = param_table.borrow_mut().add_symbol(name.text.clone(), ty.clone()) {
+                diagnostics.report_error(e.to_string(), Some(name.position));
+            }
+        }
+        symbol_table.borrow_mut().add_child(param_table.clone());
+
+        let hir_params: Vec<(String, Type)> =
+            params.iter().map(|(n, t)| (n.text.clone(), t.clone())).collect();
+        self.hir_lambda_begin(&hir_params, position.line_no);
+        self.closure_hosts.push(parent_function.clone());
+        let saved_expected = self.current_expected_type.take();
+        let saved_labels = std::mem::take(&mut self.loop_labels);
+        let saved_pending = self.pending_loop_label.take();
+
+        let annotated = lambda
+            .return_type
+            .as_ref()
+            .map(|t| Self::monomorphize_type(t, &self.current_generic_bindings))
+            .or_else(|| expected.as_ref().map(|(_, ret)| ret.clone()))
+            .filter(|ret| !ret.is_unknown());
+        // An expected result only partly known (`Result<U, string>` with `U` not yet inferred)
+        // still guides the body, but an expression body's own type is the lambda's result.
+        let partial = annotated
+            .as_ref()
+            .is_some_and(|ret| ret.get_type().contains(UNKNOWN_TYPE_NAME));
+        // The lambda's body is its own function: a `?` in it returns from the lambda.
+        let ret = self.with_propagate_barrier(None, |s| s.with_async_flag(false, |s| match lambda.body {
+            LambdaBody::Expr(body) => {
+                s.current_expected_type = annotated.clone();
+                let annotated = annotated.clone().filter(|_| !partial);
+                let func = s.lambda_function(lambda, &params, annotated.clone(), &[]);
+                let t = s
+                    .analyze_expression(body, func, &param_table, diagnostics)
+                    .unwrap_or(Type::Unknown);
+                let value = s.hir_take();
+                s.forbid_await_in_lambda_expr(body, diagnostics);
+                let ret = annotated.clone().unwrap_or_else(|| t.clone());
+                if annotated.is_some() && !matches!(ret, Type::Void) {
+                    let _ = s.compare_data_type(&ret, &t, &position, diagnostics);
+                }
+                if matches!(ret, Type::Void) {
+                    s.hir_expr_stmt(value);
+                    s.hir_return_void();
+                } else {
+                    s.hir_return_value(value);
+                }
+                ret
+            }
+            LambdaBody::Block(body) => {
+                let ret = annotated.clone().unwrap_or(Type::Void);
+                let declared = (!matches!(ret, Type::Void)).then(|| ret.clone());
+                let func = s.lambda_function(lambda, &params, declared, body);
+                let _ = s.analyze_body(body, func, Some(&param_table), false, diagnostics);
+                s.forbid_await_in_lambda(body, diagnostics);
+                if let Err(e) = FunctionControlGraph::new(func).build() {
+                    diagnostics.report_error(e.to_string(), Some(position));
+                }
+                ret
+            }
+        }));
+
+        self.current_expected_type = saved_expected;
+        self.loop_labels = saved_labels;
+        self.pending_loop_label = saved_pending;
+        self.closure_hosts.pop();
+        let func_ty = Type::Function(
+            params.into_iter().map(|(_, t)| t).collect(),
+            Box::new(ret.clone()),
+        );
+        self.hir_lambda_finish(&func_ty, &ret);
+        Ok(func_ty)
+    }
+
+    /// Synthesizes the function node a lambda body is analyzed against: `return` statements are
+    /// checked against its return type and the control-flow check runs over its body.
+    fn lambda_function(
+        &self,
+        lambda: &LambdaNode<'a>,
+        params: &[(SyntaxToken, Type)],
+        return_type: Option<Type>,
+        body: &'a [crate::syntax::nodes::StatementNode<'a>],
+    ) -> &'a FunctionNode<'a> {
+        let name = SyntaxToken::new(
+            TokenKind::IdentifierToken,
+            lambda.arrow.position,
+            "lambda".to_string(),
+        );
+        let parameters = params
+            .iter()
+            .map(|(n, t)| ParameterNode::new(n.clone(), t.clone()))
+            .collect();
+        self.arena
+            .alloc(FunctionNode::new(vec![], name, None, return_type, parameters, body, false))
+    }
+
+    /// Analyzes one call argument. A lambda argument is given `expected` (the callee's parameter
+    /// type, when known) as its expected type, so its parameter types can be inferred from the
+    /// callee; other arguments are analyzed as usual.
+    pub(super) fn analyze_call_arg(
+        &mut self,
+        arg: &ExpressionNode<'a>,
+        expected: Option<Type>,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        if !matches!(arg, ExpressionNode::Lambda(_)) {
+            return self.analyze_expression(arg, parent_function, symbol_table, diagnostics);
+        }
+        let saved = std::mem::replace(&mut self.current_expected_type, expected);
+        let result = self.analyze_expression(arg, parent_function, symbol_table, diagnostics);
+        self.current_expected_type = saved;
+        result
+    }
+}
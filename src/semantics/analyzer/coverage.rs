@@ -0,0 +1,289 @@
+//! Coverage analysis for pattern `switch`es. Each checked arm pattern is reduced to a [`Space`] -
+//! the set of values it matches, with bindings and types stripped away - and both exhaustiveness
+//! and unreachable-arm detection then come down to one question: is a pattern *useful* after the
+//! rows of earlier arms, i.e. does it match some value none of them do? That is answered by the
+//! usual specialize/default recursion over the pattern matrix, splitting integer ranges into the
+//! pieces the arms distinguish.
+
+use std::rc::Rc;
+
+/// The most uncovered patterns collected for one "missing pattern(s)" message.
+const MISSING_LIMIT: usize = 5;
+
+/// How a constructor is spelled when it appears in a missing-pattern message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum CtorKind {
+    /// A union variant: `Circle(_)`, or a bare `Empty`.
+    Variant,
+    /// A tuple: `(_, true)`.
+    Tuple,
+    /// A class, from a type or class pattern: `Circle _`, `Point { x: 0 }`.
+    Class,
+    /// An argument-less value: `true`, `null`.
+    Literal,
+}
+
+/// One constructor of a [`Family`]. Its arity is the number of `fields`.
+#[derive(Debug)]
+pub(super) struct Member {
+    pub(super) name: String,
+    pub(super) kind: CtorKind,
+    pub(super) fields: Vec<String>,
+}
+
+/// The constructors a value of one type can be built from.
+#[derive(Debug)]
+pub(super) struct Family {
+    pub(super) members: Vec<Member>,
+    /// True when `members` lists every way to build the value (a union's variants, `true` and
+    /// `false`, a sealed set of interface implementors). An open family (strings, classes seen
+    /// through `object`) can only be covered by a catch-all.
+    pub(super) complete: bool,
+}
+
+/// The values of an integer or `char` type, as an inclusive range of code points/numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct IntDomain {
+    pub(super) min: i128,
+    pub(super) max: i128,
+    pub(super) is_char: bool,
+}
+
+/// The values a pattern matches, as far as coverage is concerned.
+#[derive(Clone, Debug)]
+pub(super) enum Space {
+    /// Everything: `_`, a binding, an irrefutable type pattern's subject type.
+    Wild,
+    /// Values built by the constructor `name` whose fields match `args` positionally.
+    Ctor {
+        name: String,
+        args: Vec<Space>,
+        family: Rc<Family>,
+    },
+    /// The integers (or chars) `lo..=hi`; a single literal is a one-value range.
+    Range {
+        lo: i128,
+        hi: i128,
+        domain: IntDomain,
+    },
+    /// Values matching any of the alternatives.
+    Or(Vec<Space>),
+}
+
+type Row = Vec<Space>;
+
+/// True when `q` matches a value that no row of `rows` matches.
+pub(super) fn is_useful(rows: &[Row], q: &[Space]) -> bool {
+    let Some((head, rest)) = q.split_first() else {
+        return rows.is_empty();
+    };
+    let rows = expand_or_heads(rows);
+    match head {
+        Space::Or(alternatives) => alternatives
+            .iter()
+            .any(|alt| is_useful(&rows, &prepend(std::slice::from_ref(alt), rest))),
+        Space::Ctor { name, args, .. } => {
+            is_useful(&specialize_ctor(&rows, name, args.len()), &prepend(args, rest))
+        }
+        Space::Range { lo, hi, .. } => split_range(&rows, *lo, *hi)
+            .into_iter()
+            .any(|(a, b)| is_useful(&specialize_range(&rows, a, b), rest)),
+        Space::Wild => match column_shape(&rows) {
+            Shape::Complete(family) => family.members.iter().any(|m| {
+                let arity = m.fields.len();
+                is_useful(
+                    &specialize_ctor(&rows, &m.name, arity),
+                    &prepend(&vec![Space::Wild; arity], rest),
+                )
+            }),
+            Shape::Ints(domain) => split_range(&rows, domain.min, domain.max)
+                .into_iter()
+                .any(|(a, b)| is_useful(&specialize_range(&rows, a, b), rest)),
+            Shape::Open => is_useful(&default_rows(&rows), rest),
+        },
+    }
+}
+
+/// Up to [`MISSING_LIMIT`] patterns (one rendered string per column) matching values that no row
+/// of `rows` matches, each row being `width` columns wide. Empty when the rows are exhaustive.
+pub(super) fn missing_patterns(rows: &[Row], width: usize) -> Vec<Vec<String>> {
+    if width == 0 {
+        return if rows.is_empty() { vec![vec![]] } else { vec![] };
+    }
+    let rows = expand_or_heads(rows);
+    let mut out = Vec::new();
+    match column_shape(&rows) {
+        Shape::Complete(family) => {
+            for member in &family.members {
+                let arity = member.fields.len();
+                let specialized = specialize_ctor(&rows, &member.name, arity);
+                for mut witness in missing_patterns(&specialized, arity + width - 1) {
+                    let rest = witness.split_off(arity);
+                    let mut row = vec![render_ctor(member, &witness)];
+                    row.extend(rest);
+                    out.push(row);
+                    if out.len() == MISSING_LIMIT {
+                        return out;
+                    }
+                }
+            }
+        }
+        Shape::Ints(domain) => {
+            for (lo, hi) in split_range(&rows, domain.min, domain.max) {
+                for witness in missing_patterns(&specialize_range(&rows, lo, hi), width - 1) {
+                    let mut row = vec![render_range(lo, hi, domain)];
+                    row.extend(witness);
+                    out.push(row);
+                    if out.len() == MISSING_LIMIT {
+                        return out;
+                    }
+                }
+            }
+        }
+        Shape::Open => {
+            for witness in missing_patterns(&default_rows(&rows), width - 1) {
+                let mut row = vec!["_".to_string()];
+                row.extend(witness);
+                out.push(row);
+                if out.len() == MISSING_LIMIT {
+                    return out;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// What the first column of a matrix tells us about the values it can hold.
+enum Shape {
+    /// Constructors of a complete family appear: split on each of them.
+    Complete(Rc<Family>),
+    /// Integer ranges appear: split the domain into the pieces the rows distinguish.
+    Ints(IntDomain),
+    /// Only catch-alls, or constructors of an open family: fall back to the catch-all rows.
+    Open,
+}
+
+fn column_shape(rows: &[Row]) -> Shape {
+    for row in rows {
+        match row.first() {
+            Some(Space::Ctor { family, .. }) if family.complete => {
+                return Shape::Complete(family.clone())
+            }
+            Some(Space::Range { domain, .. }) => return Shape::Ints(*domain),
+            _ => {}
+        }
+    }
+    Shape::Open
+}
+
+/// Replaces every row whose head is an or-pattern by one row per alternative.
+fn expand_or_heads(rows: &[Row]) -> Vec<Row> {
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        match row.first() {
+            Some(Space::Or(alternatives)) => {
+                let alt_rows: Vec<Row> = alternatives
+                    .iter()
+                    .map(|alt| prepend(std::slice::from_ref(alt), &row[1..]))
+                    .collect();
+                out.extend(expand_or_heads(&alt_rows));
+            }
+            _ => out.push(row.clone()),
+        }
+    }
+    out
+}
+
+/// The rows that can match a value built by `name` (with `arity` fields), with the head replaced
+/// by the constructor's field patterns.
+fn specialize_ctor(rows: &[Row], name: &str, arity: usize) -> Vec<Row> {
+    rows.iter()
+        .filter_map(|row| match &row[0] {
+            Space::Ctor { name: n, args, .. } if n == name => Some(prepend(args, &row[1..])),
+            Space::Wild => Some(prepend(&vec![Space::Wild; arity], &row[1..])),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The rows whose head matches every value in `lo..=hi`, with the head removed. `lo..=hi` comes
+/// from [`split_range`], so each head range either contains it or misses it entirely.
+fn specialize_range(rows: &[Row], lo: i128, hi: i128) -> Vec<Row> {
+    rows.iter()
+        .filter(|row| match &row[0] {
+            Space::Range { lo: a, hi: b, .. } => *a <= lo && hi <= *b,
+            Space::Wild => true,
+            _ => false,
+        })
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// The rows whose head is a catch-all, with the head removed.
+fn default_rows(rows: &[Row]) -> Vec<Row> {
+    rows.iter()
+        .filter(|row| matches!(row[0], Space::Wild))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// Splits `lo..=hi` at every boundary of the head ranges of `rows`, so that no head range
+/// partially overlaps a piece.
+fn split_range(rows: &[Row], lo: i128, hi: i128) -> Vec<(i128, i128)> {
+    let mut cuts = vec![lo, hi + 1];
+    for row in rows {
+        if let Space::Range { lo: a, hi: b, .. } = &row[0] {
+            for cut in [*a, *b + 1] {
+                if lo < cut && cut <= hi {
+                    cuts.push(cut);
+                }
+            }
+        }
+    }
+    cuts.sort_unstable();
+    cuts.dedup();
+    cuts.windows(2).map(|w| (w[0], w[1] - 1)).collect()
+}
+
+fn prepend(head: &[Space], rest: &[Space]) -> Row {
+    head.iter().chain(rest).cloned().collect()
+}
+
+fn render_ctor(member: &Member, args: &[String]) -> String {
+    match member.kind {
+        CtorKind::Literal => member.name.clone(),
+        CtorKind::Variant if args.is_empty() => member.name.clone(),
+        CtorKind::Variant => format!("{}({})", member.name, args.join(", ")),
+        CtorKind::Tuple => format!("({})", args.join(", ")),
+        CtorKind::Class => {
+            let listed: Vec<String> = member
+                .fields
+                .iter()
+                .zip(args)
+                .filter(|(_, arg)| arg.as_str() != "_")
+                .map(|(field, arg)| format!("{}: {}", field, arg))
+                .collect();
+            if listed.is_empty() {
+                format!("{} _", member.name)
+            } else {
+                format!("{} {{ {} }}", member.name, listed.join(", "))
+            }
+        }
+    }
+}
+
+fn render_range(lo: i128, hi: i128, domain: IntDomain) -> String {
+    if lo == domain.min && hi == domain.max {
+        return "_".to_string();
+    }
+    let value = |v: i128| match char::from_u32(v as u32).filter(|_| domain.is_char) {
+        Some(c) => format!("'{}'", c.escape_default()),
+        None => v.to_string(),
+    };
+    if lo == hi {
+        value(lo)
+    } else {
+        format!("{}..={}", value(lo), value(hi))
+    }
+}
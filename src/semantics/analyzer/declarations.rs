@@ -13,7 +13,7 @@ use crate::syntax::nodes::{EnumVariantNode, FunctionNode, ProgramNode, Type};
 use crate::text::text_span::TextSpan;
 use crate::syntax::token::token_kind::TokenKind;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 impl<'a> Analyzer<'a> {
@@ -269,6 +269,15 @@ impl<'a> Analyzer<'a> {
             // dispatched instance methods). `async` interface methods are supported: they dispatch
             // to a concrete async implementation that returns a `Future<T>`.
             for method in iface.methods.iter() {
+                if method.is_virtual {
+                    diagnostics.report_error(
+                        format!(
+                            "Interface method '{}' cannot be 'virtual' (interface methods are always dynamically dispatched)",
+                            method.name.text
+                        ),
+                        Some(method.name.position),
+                    );
+                }
                 if method.is_static {
                     diagnostics.report_error(
                         format!(
@@ -348,6 +357,7 @@ impl<'a> Analyzer<'a> {
             );
         }
         let bindings = generic_bindings(params, args);
+        self.check_instance_bounds(base_name, &template.generic_bounds, &bindings, &mangled, position, diagnostics);
         let mut methods: Vec<&'a FunctionNode<'a>> = Vec::new();
         for method in template.methods.iter().filter(|m| !m.is_static) {
             let mut m = method.clone();
@@ -360,19 +370,47 @@ impl<'a> Analyzer<'a> {
     /// Builds the interface dispatch metadata carried into codegen: the ordered interfaces (index =
     /// `iface_id`) with each method slot's `call_indirect` signature, and, per implementing class,
     /// the concrete method symbol filling each `(interface, slot)`.
+    ///
+    /// Class vtables ride on the same machinery: each class with virtual methods is an entry ahead
+    /// of the interfaces, implemented by the class itself and every subclass.
     pub(super) fn hir_build_interfaces(&mut self) -> crate::hir::InterfaceTable {
         use crate::hir::{InterfaceImpl, InterfaceInfo, InterfaceTable};
 
+        let mut interfaces = Vec::new();
+        let mut impls = Vec::new();
+        let vtables: Vec<(String, Vec<&'a FunctionNode<'a>>)> = self
+            .vtables
+            .iter()
+            .map(|(class, slots)| (class.clone(), slots.clone()))
+            .collect();
+        for (id, (class, slots)) in vtables.into_iter().enumerate() {
+            let sigs = slots.iter().map(|m| self.interface_dispatch_sig(m)).collect();
+            interfaces.push(InterfaceInfo {
+                name: class.clone(),
+                method_count: slots.len(),
+                sigs,
+            });
+            let members = std::iter::once(class.clone()).chain(self.subclasses_of(&class));
+            for member in members {
+                let symbols = slots.iter().map(|m| method_fn(&member, &m.name.text)).collect();
+                impls.push(InterfaceImpl {
+                    class_ty: self.type_ctx.lower_str(&member),
+                    entries: vec![(id, symbols)],
+                });
+            }
+        }
+        let first_iface_id = interfaces.len();
+
         let iface_order: Vec<(String, Vec<&'a FunctionNode<'a>>)> = self
             .interface_methods
             .iter()
+            .filter(|(name, _)| !is_archetype_derived(name))
             .map(|(name, methods)| (name.clone(), methods.clone()))
             .collect();
 
         let mut name_to_id: HashMap<String, usize> = HashMap::new();
-        let mut interfaces = Vec::with_capacity(iface_order.len());
-        for (id, (name, methods)) in iface_order.iter().enumerate() {
-            name_to_id.insert(name.clone(), id);
+        for (i, (name, methods)) in iface_order.iter().enumerate() {
+            name_to_id.insert(name.clone(), first_iface_id + i);
             let sigs: Vec<crate::types::TypeId> = methods
                 .iter()
                 .map(|m| self.interface_dispatch_sig(m))
@@ -387,9 +425,9 @@ impl<'a> Analyzer<'a> {
         let class_impls: Vec<(String, Vec<String>)> = self
             .implements
             .iter()
+            .filter(|(class, _)| !is_archetype_derived(class))
             .map(|(class, ifaces)| (class.clone(), ifaces.clone()))
             .collect();
-        let mut impls = Vec::new();
         for (class, ifaces) in class_impls {
             let class_ty = self.type_ctx.lower_str(&class);
             let mut entries = Vec::new();
@@ -437,13 +475,47 @@ impl<'a> Analyzer<'a> {
         None
     }
 
-    /// True when class `class_name` was validated as implementing interface `iface_name`.
+    /// True when class `class_name` was validated as implementing interface `iface_name`, or
+    /// `class_name` is an archetype bounded by it.
     pub(super) fn class_implements(&self, class_name: &str, iface_name: &str) -> bool {
         self.implements
             .get(class_name)
+            .or_else(|| self.archetypes.get(class_name))
             .is_some_and(|ifaces| ifaces.iter().any(|i| i == iface_name))
     }
 
+    /// True when class `derived` extends `ancestor`, directly or through intermediate bases. A
+    /// class is not its own subclass.
+    pub(super) fn is_subclass_of(&self, derived: &str, ancestor: &str) -> bool {
+        let mut current = derived;
+        while let Some(base) = self.base_classes.get(current) {
+            if base == ancestor {
+                return true;
+            }
+            current = base;
+        }
+        false
+    }
+
+    /// True when a value of type `value` may be implicitly converted to class-typed `target` (an
+    /// upcast to one of its base classes). A nullable value only converts to a nullable target.
+    pub(super) fn class_upcastable(&self, target: &str, value: &str) -> bool {
+        if value.ends_with('?') && !target.ends_with('?') {
+            return false;
+        }
+        self.is_subclass_of(strip_nullable(value), strip_nullable(target))
+    }
+
+    /// Every class extending `class` directly or indirectly, in registration order (bases before
+    /// their subclasses).
+    pub(super) fn subclasses_of(&self, class: &str) -> Vec<String> {
+        self.base_classes
+            .keys()
+            .filter(|sub| self.is_subclass_of(sub, class))
+            .cloned()
+            .collect()
+    }
+
     /// True when a value of type `value` may be implicitly converted to interface-typed `target`
     /// (an upcast): `target` names an interface and `value`'s concrete class implements it.
     /// Nullable wrappers on either side are ignored.
@@ -487,14 +559,19 @@ impl<'a> Analyzer<'a> {
     }
 
     /// Validates a class's `implements` clause: every listed type must name an interface, and the
-    /// class must provide an instance method with a matching signature for each interface method.
-    /// Records the validated (mangled) interface list in `self.implements` under `class_name`.
+    /// class must provide an instance method with a matching signature for each interface method
+    /// without a default body. Records the validated (mangled) interface list in `self.implements`
+    /// under `class_name`.
     ///
     /// Works uniformly for non-generic classes (`bindings` empty) and monomorphized generic classes
     /// (`bindings` maps the class's type parameters to concrete types). For a monomorphized class,
     /// the `implements` entries are expected to already be substituted (e.g. `Container<int>`) while
     /// `methods` are the unsubstituted template methods, substituted here for signature comparison.
     /// Generic interfaces named in the clause are instantiated on demand.
+    ///
+    /// Each default method the class does not define is registered as a method of the class, so
+    /// the class gets its own monomorphized copy and its itable slot points at `{Class}_{method}`
+    /// like any other. Two interfaces supplying a default for the same unoverridden method conflict.
     fn validate_implements(
         &mut self,
         class_name: &str,
@@ -507,7 +584,7 @@ impl<'a> Analyzer<'a> {
         if implements.is_empty() {
             return;
         }
-        let mut validated: Vec<String> = Vec::new();
+        let mut resolved: Vec<(String, Vec<&'a FunctionNode<'a>>, GenericBindings)> = Vec::new();
         for iface_ty in implements {
             let span = iface_ty.get_span().unwrap_or(class_pos);
             let (base, args) = match Self::resolve_struct_parts(iface_ty) {
@@ -524,64 +601,150 @@ impl<'a> Analyzer<'a> {
                 );
                 continue;
             }
-            let iface_name = if args.is_empty() {
-                base.clone()
+            let (iface_name, iface_bindings) = if args.is_empty() {
+                (base.clone(), GenericBindings::new())
             } else {
                 self.ensure_interface_instantiated(&base, &args, &span, diagnostics);
-                mangle_generic(&base, &args)
-            };
-            let iface_methods = match self.interface_methods.get(&iface_name) {
-                Some(m) => m.clone(),
-                None => continue,
+                let params = self
+                    .generic_interfaces
+                    .get(&base)
+                    .and_then(|t| t.generic_parameters.as_deref())
+                    .unwrap_or(&[]);
+                (mangle_generic(&base, &args), generic_bindings(params, &args))
             };
-            for im in &iface_methods {
-                match methods
+            if resolved.iter().any(|(name, _, _)| *name == iface_name) {
+                continue;
+            }
+            if let Some(iface_methods) = self.interface_methods.get(&iface_name) {
+                resolved.push((iface_name, iface_methods.clone(), iface_bindings));
+            }
+        }
+
+        let defines = |name: &str| methods.iter().any(|cm| cm.name.text == name && !cm.is_static);
+        let mut inherited: Vec<(&'a FunctionNode<'a>, GenericBindings, String)> = Vec::new();
+        for (iface_name, iface_methods, iface_bindings) in &resolved {
+            for im in iface_methods.iter().filter(|im| im.has_default_body && !defines(&im.name.text)) {
+                match inherited.iter().find(|(m, _, _)| m.name.text == im.name.text) {
+                    Some((_, _, first)) => diagnostics.report_error(
+                        format!(
+                            "class '{}' inherits default method '{}' from both '{}' and '{}'; define it in the class",
+                            class_name, im.name.text, first, iface_name
+                        ),
+                        Some(class_pos),
+                    ),
+                    None => inherited.push((im, iface_bindings.clone(), iface_name.clone())),
+                }
+            }
+        }
+
+        for (iface_name, iface_methods, _) in &resolved {
+            for im in iface_methods {
+                if let Some(cm) = methods
                     .iter()
                     .find(|cm| cm.name.text == im.name.text && !cm.is_static)
                 {
-                    Some(cm) => {
-                        let matches = if bindings.is_empty() {
-                            Self::interface_method_matches(im, cm)
-                        } else {
-                            let mut sub = cm.clone();
-                            Self::substitute_generic_signature(&mut sub, bindings);
-                            Self::interface_method_matches(im, &sub)
-                        };
-                        if !matches {
-                            diagnostics.report_error(
-                                format!(
-                                    "class '{}' method '{}' does not match the signature required by interface '{}'",
-                                    class_name, im.name.text, iface_name
-                                ),
-                                Some(cm.name.position),
-                            );
-                        }
-                    }
-                    None => {
+                    let matches = if bindings.is_empty() {
+                        Self::interface_method_matches(im, cm)
+                    } else {
+                        let mut sub = cm.clone();
+                        Self::substitute_generic_signature(&mut sub, bindings);
+                        Self::interface_method_matches(im, &sub)
+                    };
+                    if !matches {
                         diagnostics.report_error(
                             format!(
-                                "class '{}' does not implement method '{}' required by interface '{}'",
+                                "class '{}' method '{}' does not match the signature required by interface '{}'",
                                 class_name, im.name.text, iface_name
                             ),
-                            Some(class_pos),
+                            Some(cm.name.position),
                         );
                     }
+                    continue;
+                }
+                match inherited.iter().find(|(m, _, _)| m.name.text == im.name.text) {
+                    Some((default, _, source)) if source != iface_name => {
+                        if !Self::interface_method_matches(im, default) {
+                            diagnostics.report_error(
+                                format!(
+                                    "default method '{}' inherited by class '{}' from '{}' does not match the signature required by interface '{}'",
+                                    im.name.text, class_name, source, iface_name
+                                ),
+                                Some(class_pos),
+                            );
+                        }
+                    }
+                    Some(_) => {}
+                    None => diagnostics.report_error(
+                        format!(
+                            "class '{}' does not implement method '{}' required by interface '{}'",
+                            class_name, im.name.text, iface_name
+                        ),
+                        Some(class_pos),
+                    ),
                 }
             }
-            if !validated.contains(&iface_name) {
-                validated.push(iface_name);
-            }
         }
+
+        for (default, iface_bindings, _) in inherited {
+            // Interface members are public whether or not the declaration says so.
+            let mut copy = default.clone();
+            copy.is_public = true;
+            let copy: &'a [FunctionNode<'a>] = self.arena.alloc_slice_fill_iter(std::iter::once(copy));
+            self.register_methods_for(class_name, copy, &iface_bindings, diagnostics);
+        }
+        let validated = resolved.into_iter().map(|(name, _, _)| name).collect();
         self.implements.insert(class_name.to_string(), validated);
     }
 
+    /// Registers the synthesized generic tuple classes `Tuple#2` .. `Tuple#MAX_TUPLE_ARITY` as
+    /// templates, so a tuple type `(int, string)` monomorphizes like any generic class instance
+    /// (layout, ARC release, `to_string`/`hash_code`). Each has public positional fields `0`, `1`,
+    /// ... typed by its parameters `T0`, `T1`, ... and no methods. Runs before every other
+    /// registration pass, since any field or signature may mention a tuple.
+    pub(super) fn register_tuple_templates(&mut self) {
+        use crate::syntax::nodes::types::{tuple_type_name, MAX_TUPLE_ARITY};
+        for arity in 2..=MAX_TUPLE_ARITY {
+            let name = tuple_type_name(arity);
+            let params: Vec<SyntaxToken> = (0..arity)
+                .map(|i| synthetic_token(TokenKind::IdentifierToken, &format!("T{}", i)))
+                .collect();
+            let fields = params
+                .iter()
+                .enumerate()
+                .map(|(i, param)| StructFieldNode {
+                    attributes: vec![],
+                    name: synthetic_token(TokenKind::IdentifierToken, &i.to_string()),
+                    is_public: true,
+                    type_token: param.clone(),
+                    field_type: Type::Struct(param.clone(), None),
+                })
+                .collect();
+            let template: &'a StructDeclarationNode<'a> = self.arena.alloc(StructDeclarationNode::new(
+                vec![],
+                synthetic_token(TokenKind::IdentifierToken, &name),
+                Some(params),
+                fields,
+                vec![],
+                true,
+            ));
+            self.type_ctx.register(
+                DefKind::Struct,
+                &name,
+                generic_param_names(&template.generic_parameters),
+            );
+            self.generic_structs.insert(name, template);
+        }
+    }
+
     /// Pass 0: register every (non-generic) struct and its methods; stash generic templates.
+    /// Base classes register before their subclasses, so a subclass can inherit the base's
+    /// fields, methods, vtable slots and interfaces.
     pub(super) fn register_structs(
         &mut self,
         node: &'a ProgramNode<'a>,
         diagnostics: &mut DiagnosticBag,
     ) {
-        for struct_decl in node.structs.iter() {
+        for (struct_decl, base, interfaces) in self.order_by_inheritance(node, diagnostics) {
             diagnostics.file_path = file_path_string(&struct_decl.file_path);
             self.type_ctx.register(
                 DefKind::Struct,
@@ -589,42 +752,417 @@ impl<'a> Analyzer<'a> {
                 generic_param_names(&struct_decl.generic_parameters),
             );
             if struct_decl.generic_parameters.is_some() {
+                for method in struct_decl.methods.iter().filter(|m| m.is_virtual) {
+                    diagnostics.report_error(
+                        format!(
+                            "Generic class '{}' cannot declare virtual method '{}'",
+                            struct_decl.name.text, method.name.text
+                        ),
+                        Some(method.name.position),
+                    );
+                }
                 // A generic class may implement a (generic or non-generic) interface; the
                 // `implements` clause is validated per monomorphization in `ensure_struct_instantiated`.
-                // v1 restriction: async methods on generic classes are not supported (the async
-                // state machine would have to be re-generated per monomorphization).
-                for method in struct_decl.methods.iter() {
-                    if method.is_async {
-                        diagnostics.report_error(
-                            format!("Async methods are not supported on generic class '{}' (method '{}')", struct_decl.name.text, method.name.text),
-                            Some(method.name.position),
-                        );
-                    }
-                }
                 self.generic_structs
                     .insert(struct_decl.name.text.clone(), struct_decl);
                 continue;
             }
-            if let Err(e) = self.struct_table.add_struct(struct_decl) {
+            let added = match &base {
+                Some(base) => self.struct_table.add_subclass(struct_decl, base),
+                None => self.struct_table.add_struct(struct_decl),
+            };
+            if let Err(e) = added {
                 diagnostics.report_error(e, Some(struct_decl.name.position));
             }
+            if let Some(base) = &base {
+                self.base_classes
+                    .insert(struct_decl.name.text.clone(), base.clone());
+            }
             self.register_struct_methods(
                 struct_decl,
                 &struct_decl.name.text,
                 &GenericBindings::new(),
                 diagnostics,
             );
+            let Some(base) = base else {
+                self.register_vtable(struct_decl, None, diagnostics);
+                self.validate_implements(
+                    &struct_decl.name.text,
+                    &interfaces,
+                    &struct_decl.methods,
+                    &GenericBindings::new(),
+                    struct_decl.name.position,
+                    diagnostics,
+                );
+                continue;
+            };
+            self.check_constructor_chaining(struct_decl, &base, diagnostics);
+            let inherited = self.inherit_methods(struct_decl, &base, diagnostics);
+            self.register_vtable(struct_decl, Some(&base), diagnostics);
+            // Inherited methods satisfy the subclass's own interfaces too; the base's interfaces
+            // were validated on the base and carry over as-is.
+            let methods: Vec<FunctionNode<'a>> =
+                struct_decl.methods.iter().chain(inherited).cloned().collect();
             self.validate_implements(
                 &struct_decl.name.text,
-                &struct_decl.implements,
-                &struct_decl.methods,
+                &interfaces,
+                &methods,
                 &GenericBindings::new(),
                 struct_decl.name.position,
                 diagnostics,
             );
+            let base_ifaces = self.implements.get(&base).cloned().unwrap_or_default();
+            if !base_ifaces.is_empty() {
+                let ifaces = self.implements.entry(struct_decl.name.text.clone()).or_default();
+                for iface in base_ifaces {
+                    if !ifaces.contains(&iface) {
+                        ifaces.push(iface);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Orders the class declarations so every base class precedes its subclasses (otherwise
+    /// keeping declaration order), pairing each with its base class and the interfaces left in its
+    /// `:` clause. The base is the clause entry naming a class. Reports a second base class, a
+    /// generic class on either side of the clause, and inheritance cycles; a rejected base is
+    /// dropped so the class registers without one.
+    #[allow(clippy::type_complexity)]
+    fn order_by_inheritance(
+        &self,
+        node: &'a ProgramNode<'a>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Vec<(&'a StructDeclarationNode<'a>, Option<String>, Vec<Type>)> {
+        let classes: HashMap<&str, &'a StructDeclarationNode<'a>> = node
+            .structs
+            .iter()
+            .map(|s| (s.name.text.as_str(), s))
+            .collect();
+        let names_class = |ty: &Type| match ty {
+            Type::Struct(token, _) => classes.get(token.text.as_str()).copied(),
+            _ => None,
+        };
+
+        let mut bases: HashMap<&str, String> = HashMap::new();
+        for decl in node.structs.iter() {
+            let mut named = decl
+                .implements
+                .iter()
+                .filter_map(|ty| names_class(ty).map(|base| (ty, base)));
+            let Some((base_ty, base)) = named.next() else {
+                continue;
+            };
+            diagnostics.file_path = file_path_string(&decl.file_path);
+            let span = base_ty.get_span().unwrap_or(decl.name.position);
+            if let Some((extra, _)) = named.next() {
+                diagnostics.report_error(
+                    format!(
+                        "Class '{}' can only extend one class, but also names class '{}'",
+                        decl.name.text,
+                        extra.get_type()
+                    ),
+                    Some(extra.get_span().unwrap_or(span)),
+                );
+            }
+            if decl.generic_parameters.is_some() {
+                diagnostics.report_error(
+                    format!(
+                        "Generic class '{}' cannot extend class '{}'",
+                        decl.name.text, base.name.text
+                    ),
+                    Some(span),
+                );
+                continue;
+            }
+            if base.generic_parameters.is_some() {
+                diagnostics.report_error(
+                    format!(
+                        "Class '{}' cannot extend generic class '{}'",
+                        decl.name.text, base.name.text
+                    ),
+                    Some(span),
+                );
+                continue;
+            }
+            bases.insert(decl.name.text.as_str(), base.name.text.clone());
+        }
+
+        // Place each class after its chain of bases. Declarations are tracked by address so a
+        // duplicate class name still reaches `add_struct` (which reports it).
+        let mut placed: HashSet<*const StructDeclarationNode<'a>> = HashSet::new();
+        let mut order = Vec::with_capacity(node.structs.len());
+        for decl in node.structs.iter() {
+            let mut chain = vec![decl];
+            while let Some(base) = chain.last().and_then(|d| bases.get(d.name.text.as_str())) {
+                let base_decl = classes[base.as_str()];
+                if placed.contains(&(base_decl as *const _)) {
+                    break;
+                }
+                if chain.iter().any(|d| std::ptr::eq(*d, base_decl)) {
+                    let last = *chain.last().unwrap();
+                    diagnostics.file_path = file_path_string(&last.file_path);
+                    diagnostics.report_error(
+                        format!(
+                            "Class '{}' cannot extend '{}': the inheritance chain cycles back to '{}'",
+                            last.name.text, base, last.name.text
+                        ),
+                        Some(last.name.position),
+                    );
+                    bases.remove(last.name.text.as_str());
+                    break;
+                }
+                chain.push(base_decl);
+            }
+            for decl in chain.into_iter().rev() {
+                if placed.insert(decl as *const _) {
+                    let base = bases.get(decl.name.text.as_str()).cloned();
+                    let interfaces = decl
+                        .implements
+                        .iter()
+                        .filter(|ty| names_class(ty).is_none())
+                        .cloned()
+                        .collect();
+                    order.push((decl, base, interfaces));
+                }
+            }
+        }
+        order
+    }
+
+    /// Checks that a subclass's constructors can chain to its base class's constructor: one that
+    /// does not begin with `super(...)` calls the base's with no arguments, which must be allowed.
+    /// A subclass declaring no constructor gets an empty one (when the base has a constructor), so
+    /// constructing it still runs the base's.
+    fn check_constructor_chaining(
+        &mut self,
+        decl: &'a StructDeclarationNode<'a>,
+        base: &str,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        use crate::syntax::nodes::types::CONSTRUCTOR_NAME;
+        let Ok(base_ctor) = self
+            .function_table
+            .get_function(&crate::types::constructor_fn(base))
+        else {
+            return;
+        };
+        // Parameter 0 of the registered constructor is the implicit `this`.
+        let needs_args = base_ctor.defaults.iter().skip(1).any(|d| d.is_none());
+        let mut constructors = decl
+            .methods
+            .iter()
+            .filter(|m| m.name.text == CONSTRUCTOR_NAME)
+            .peekable();
+        if constructors.peek().is_none() {
+            if needs_args {
+                diagnostics.report_error(
+                    format!(
+                        "class '{}' must declare a constructor that calls 'super(...)': the constructor of '{}' takes arguments",
+                        decl.name.text, base
+                    ),
+                    Some(decl.name.position),
+                );
+                return;
+            }
+            let mut constructor = FunctionNode::new(
+                vec![],
+                synthetic_token(TokenKind::IdentifierToken, CONSTRUCTOR_NAME),
+                None,
+                None,
+                vec![],
+                &[],
+                false,
+            );
+            constructor.file_path = decl.file_path.clone();
+            let constructor: &'a [FunctionNode<'a>] =
+                self.arena.alloc_slice_fill_iter(std::iter::once(constructor));
+            self.register_methods_for(&decl.name.text, constructor, &GenericBindings::new(), diagnostics);
+            return;
+        }
+        for constructor in constructors {
+            if needs_args && !begins_with_super_call(constructor) {
+                diagnostics.report_error(
+                    format!(
+                        "constructor of '{}' must begin with 'super(...)': the constructor of '{}' takes arguments",
+                        decl.name.text, base
+                    ),
+                    Some(constructor.name.position),
+                );
+            }
         }
     }
 
+    /// Copies into subclass `decl` every instance method of `base` that `decl` does not redefine
+    /// (by name, so redefining one overload hides the whole set). Each copy is registered as
+    /// `{Subclass}_{method}` with a `this` of the subclass type, exactly like an interface default
+    /// method. Returns the copied declarations.
+    fn inherit_methods(
+        &mut self,
+        decl: &'a StructDeclarationNode<'a>,
+        base: &str,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Vec<&'a FunctionNode<'a>> {
+        let own: HashSet<String> = decl.methods.iter().map(accessor_member_name).collect();
+        let inherited: Vec<(&'a FunctionNode<'a>, GenericBindings)> = self
+            .class_methods
+            .get(base)
+            .into_iter()
+            .flatten()
+            .filter(|(m, _)| !own.contains(&accessor_member_name(m)))
+            .cloned()
+            .collect();
+        // Methods sharing bindings register together, so an inherited overload set is known in
+        // full when its emitted names are assigned.
+        let mut copied = Vec::with_capacity(inherited.len());
+        let mut rest = inherited.as_slice();
+        while let Some((_, bindings)) = rest.first() {
+            let len = rest.iter().take_while(|(_, b)| b == bindings).count();
+            let group: &'a [FunctionNode<'a>] = self
+                .arena
+                .alloc_slice_fill_iter(rest[..len].iter().map(|(m, _)| (*m).clone()));
+            self.register_methods_for(&decl.name.text, group, bindings, diagnostics);
+            copied.extend(group.iter());
+            rest = &rest[len..];
+        }
+        copied
+    }
+
+    /// Validates `virtual` and `@override` on a class's own methods and records its vtable: the
+    /// base class's slots followed by the class's new virtual methods. Slots are keyed by method
+    /// name; each class's dispatch entry for a slot is its own `{Class}_{method}`, which is the
+    /// override when the class declares one and the inherited copy otherwise.
+    fn register_vtable(
+        &mut self,
+        decl: &'a StructDeclarationNode<'a>,
+        base: Option<&str>,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        let mut slots = base
+            .and_then(|b| self.vtables.get(b))
+            .cloned()
+            .unwrap_or_default();
+        let inherited = slots.len();
+        for method in decl.methods.iter() {
+            let name = method.name.text.as_str();
+            let position = Some(method.name.position);
+            let is_override = method.attributes.iter().any(|a| a.name.text == "override");
+            if name == crate::intrinsics::TO_STRING || name == crate::intrinsics::HASH_CODE {
+                // Object-protocol methods already dispatch on the runtime class.
+                if method.is_virtual {
+                    diagnostics.report_error(
+                        format!("'virtual' cannot be applied to object-protocol method '{}'", name),
+                        position,
+                    );
+                }
+                continue;
+            }
+            let non_virtual_kind = if crate::syntax::nodes::types::is_special_member_name(name) {
+                Some("special member")
+            } else if method.is_static {
+                Some("static method")
+            } else if method.accessor.is_some() {
+                Some("property accessor")
+            } else if method.generic_parameters.is_some() {
+                Some("generic method")
+            } else {
+                None
+            };
+            if let Some(kind) = non_virtual_kind {
+                if method.is_virtual {
+                    diagnostics.report_error(
+                        format!("'virtual' cannot be applied to {} '{}'", kind, name),
+                        position,
+                    );
+                } else if is_override && base.is_some() {
+                    diagnostics.report_error(
+                        format!("'@override' cannot be applied to {} '{}'", kind, name),
+                        position,
+                    );
+                }
+                continue;
+            }
+
+            let Some(base) = base else {
+                if method.is_virtual {
+                    Self::push_virtual_slot(&mut slots, method, diagnostics);
+                }
+                continue;
+            };
+            if let Some(slot) = slots[..inherited].iter().find(|s| s.name.text == name) {
+                if !is_override {
+                    diagnostics.report_error(
+                        format!(
+                            "method '{}' overrides a virtual method of '{}'; mark it with '@override'",
+                            name, base
+                        ),
+                        position,
+                    );
+                } else if method.is_virtual {
+                    diagnostics.report_error(
+                        format!(
+                            "method '{}' is already virtual in '{}'; an override takes '@override', not 'virtual'",
+                            name, base
+                        ),
+                        position,
+                    );
+                } else if !Self::interface_method_matches(slot, method) {
+                    diagnostics.report_error(
+                        format!(
+                            "method '{}' does not match the signature of the virtual method it overrides in '{}'",
+                            name, base
+                        ),
+                        position,
+                    );
+                }
+                continue;
+            }
+            let hides = self
+                .class_methods
+                .get(base)
+                .is_some_and(|ms| ms.iter().any(|(m, _)| m.name.text == name));
+            if is_override {
+                diagnostics.report_error(
+                    format!(
+                        "method '{}' is marked '@override' but '{}' has no virtual method '{}'",
+                        name, base, name
+                    ),
+                    position,
+                );
+            } else if hides {
+                diagnostics.report_error(
+                    format!(
+                        "method '{}' hides non-virtual method '{}' of base class '{}'; declare it 'virtual' in '{}' to override it",
+                        name, name, base, base
+                    ),
+                    position,
+                );
+            } else if method.is_virtual {
+                Self::push_virtual_slot(&mut slots, method, diagnostics);
+            }
+        }
+        if !slots.is_empty() {
+            self.vtables.insert(decl.name.text.clone(), slots);
+        }
+    }
+
+    /// Appends a newly declared virtual method to a vtable under construction. Slots are keyed by
+    /// name, so a virtual method cannot be overloaded.
+    fn push_virtual_slot(
+        slots: &mut Vec<&'a FunctionNode<'a>>,
+        method: &'a FunctionNode<'a>,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        if slots.iter().any(|s| s.name.text == method.name.text) {
+            diagnostics.report_error(
+                format!("virtual method '{}' cannot be overloaded", method.name.text),
+                Some(method.name.position),
+            );
+            return;
+        }
+        slots.push(method);
+    }
+
     /// Pass: analyze and register every top-level variable. Each initializer is type-checked in
     /// declaration order against the globals declared so far (forward references to later globals
     /// are not allowed) plus all already-registered functions/types. The resolved type is recorded
@@ -731,6 +1269,18 @@ impl<'a> Analyzer<'a> {
         for function in node.functions.iter() {
             diagnostics.file_path = file_path_string(&function.file_path);
             self.check_reserved_name(&function.name, "function", diagnostics);
+            if function.is_virtual {
+                diagnostics.report_error(
+                    format!(
+                        "'virtual' can only be applied to class methods, not function '{}'",
+                        function.name.text
+                    ),
+                    Some(function.name.position),
+                );
+            }
+            if is_test(function) {
+                self.validate_test_function(function, diagnostics);
+            }
             if function.generic_parameters.is_some() {
                 self.type_ctx.register(
                     DefKind::Function,
@@ -877,9 +1427,8 @@ impl<'a> Analyzer<'a> {
                     None => continue,
                 };
                 diagnostics.file_path = file_path_string(&template.file_path);
-                let table =
-                    self.with_generic_bindings(bindings, |s| s.analyze_function(template, diagnostics))?;
-                symbol_table_map.insert(mangled_name, table);
+                let muted = self.instance_is_muted(template, &mangled_name);
+                self.analyze_instance_body(template, bindings, mangled_name, muted, symbol_table_map, diagnostics)?;
                 progressed = true;
             }
 
@@ -888,8 +1437,8 @@ impl<'a> Analyzer<'a> {
                 let (method, bindings) = self.struct_methods[method_index].clone();
                 method_index += 1;
                 diagnostics.file_path = file_path_string(&method.file_path);
-                let table =
-                    self.with_generic_bindings(bindings, |s| s.analyze_function(method, diagnostics))?;
+                let instance = self.method_names.get(&method.name.text).map(|(target, _)| target.clone());
+                let muted = self.instance_is_muted(method, instance.as_deref().unwrap_or_default());
                 // Key by the emitted name so overloaded methods each get a distinct entry (the
                 // parameter list includes the implicit `this`).
                 let param_types: Vec<String> = method
@@ -900,7 +1449,7 @@ impl<'a> Analyzer<'a> {
                 let key = self
                     .function_table
                     .resolve_emitted_name(&method.name.text, &param_types);
-                symbol_table_map.insert(key, table);
+                self.analyze_instance_body(method, bindings, key, muted, symbol_table_map, diagnostics)?;
                 progressed = true;
             }
 
@@ -910,6 +1459,50 @@ impl<'a> Analyzer<'a> {
         }
         Ok(())
     }
+
+    /// Analyzes the body of the generic instance `function` (emitted as `key`) under `bindings`,
+    /// reporting nothing when `muted`. A standard-library instance is analyzed leniently: one that
+    /// needs more of its type arguments than they offer (`List<T>.sort` compares elements with `<`)
+    /// is recorded in `unavailable_methods` instead, and is only an error where it is called.
+    fn analyze_instance_body(
+        &mut self,
+        function: &'a FunctionNode<'a>,
+        bindings: GenericBindings,
+        key: String,
+        muted: bool,
+        symbol_table_map: &mut HashMap<String, Rc<RefCell<SymbolTable>>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        let mut scratch = DiagnosticBag::new(diagnostics.file_path.clone());
+        if muted {
+            let table = self.with_generic_bindings(bindings, |s| s.analyze_function(function, &mut scratch))?;
+            symbol_table_map.insert(key, table);
+            return Ok(());
+        }
+        let lenient = function.file_path.as_deref().is_some_and(|file| file.starts_with("<std>/"));
+        if !lenient || bindings.is_empty() {
+            let table = self.with_generic_bindings(bindings, |s| s.analyze_function(function, diagnostics))?;
+            symbol_table_map.insert(key, table);
+            return Ok(());
+        }
+        let saved = self.lenient_method.replace(key.clone());
+        let table = self.with_generic_bindings(bindings, |s| s.analyze_function(function, &mut scratch));
+        self.lenient_method = saved;
+        let table = table?;
+        let error = scratch.errors().next().map(|error| error.message.clone());
+        match error {
+            Some(error) => {
+                let message = format!("'{}' is unavailable: {}", self.source_name(function), error);
+                self.unavailable_methods.insert(key, message);
+            }
+            None => {
+                diagnostics.extend(&scratch);
+                symbol_table_map.insert(key, table);
+            }
+        }
+        Ok(())
+    }
+
     pub(super) fn ensure_struct_instantiated(
         &mut self,
         base_name: &str,
@@ -944,6 +1537,7 @@ impl<'a> Analyzer<'a> {
             );
         }
         let bindings = generic_bindings(params, args);
+        self.check_instance_bounds(base_name, &template.generic_bounds, &bindings, &mangled_name, position, diagnostics);
 
         let new_fields: Vec<StructFieldNode> = template
             .fields
@@ -981,9 +1575,11 @@ impl<'a> Analyzer<'a> {
         // through each listed interface (`Container<T>` -> `Container<int>`) and match the (also
         // substituted) method signatures. Records `implements[Box_int] = [Container_int]`.
         if !template.implements.is_empty() {
+            // A base class named in the clause was rejected when the template was registered.
             let sub_impls: Vec<Type> = template
                 .implements
                 .iter()
+                .filter(|t| !matches!(t, Type::Struct(tok, _) if self.struct_table.get_struct(&tok.text).is_some()))
                 .map(|t| substitute_generic_type(t, &bindings))
                 .collect();
             self.validate_implements(
@@ -1026,14 +1622,28 @@ impl<'a> Analyzer<'a> {
         for method in methods {
             // Validate object-protocol overrides once (on the non-monomorphized declaration).
             if bindings.is_empty() {
-                self.validate_protocol_override(method, diagnostics);
+                self.validate_protocol_override(target_type_str, method, diagnostics);
                 self.validate_accessor(method, diagnostics);
+                if is_test(method) {
+                    diagnostics.report_error(
+                        format!("'@test' can only be applied to free functions, not method '{}'", method.name.text),
+                        Some(method.name.position),
+                    );
+                }
             }
             // Property accessors (`get`/`set`) are registered under a `$`-tagged internal name that a
             // user identifier can never spell, so `obj.prop`/`obj.prop = v` resolve to them without a
             // regular method (or the indexer `get`/`set` hooks) ever colliding.
             let member_name = accessor_member_name(method);
             let mangled_name = method_fn(target_type_str, &member_name);
+            if !method.is_static
+                && !crate::syntax::nodes::types::is_special_member_name(&method.name.text)
+            {
+                self.class_methods
+                    .entry(target_type_str.to_string())
+                    .or_default()
+                    .push((method, bindings.clone()));
+            }
             self.type_ctx.register(
                 DefKind::Function,
                 &mangled_name,
@@ -1044,6 +1654,11 @@ impl<'a> Analyzer<'a> {
                 self.generic_functions.insert(mangled_name.clone(), method);
             }
 
+            self.method_names.insert(
+                mangled_name.clone(),
+                (target_type_str.to_string(), method.name.text.clone()),
+            );
+
             let mut new_method = method.clone();
             new_method.name = synthetic_token(TokenKind::IdentifierToken, &mangled_name);
 
@@ -1061,7 +1676,11 @@ impl<'a> Analyzer<'a> {
             let param_types: Vec<String> =
                 new_method.parameters.iter().map(|p| p.type_.get_type()).collect();
             let method_ref = self.arena.alloc(new_method);
-            self.struct_methods.push((method_ref, bindings.clone()));
+            // A method with type parameters of its own has its body analyzed per instantiation
+            // (see `instantiate_generic_method`), never as a bare template.
+            if method.generic_parameters.is_none() {
+                self.struct_methods.push((method_ref, bindings.clone()));
+            }
 
             if let Err(e) = self
                 .function_table
@@ -1119,6 +1738,15 @@ impl<'a> Analyzer<'a> {
     ) {
         for ext in node.extends.iter() {
             diagnostics.file_path = file_path_string(&ext.file_path);
+            for method in ext.methods.iter().filter(|m| m.is_virtual) {
+                diagnostics.report_error(
+                    format!(
+                        "Extension method '{}' cannot be 'virtual' (only class methods can be overridden)",
+                        method.name.text
+                    ),
+                    Some(method.name.position),
+                );
+            }
             let target = ext.target.text.clone();
             if ext.generic_parameters.is_some() {
                 // Generic extend blocks were stashed by `stash_generic_extensions` and are attached
@@ -1145,6 +1773,25 @@ impl<'a> Analyzer<'a> {
                 continue;
             }
             self.register_methods_for(&target, &ext.methods, &GenericBindings::new(), diagnostics);
+            // Subclasses were registered before any extension; each inherits the methods it does
+            // not define itself.
+            for sub in self.subclasses_of(&target) {
+                let defined: HashSet<String> = self
+                    .class_methods
+                    .get(&sub)
+                    .into_iter()
+                    .flatten()
+                    .map(|(m, _)| accessor_member_name(m))
+                    .collect();
+                let inherited: &'a [FunctionNode<'a>] = self.arena.alloc_slice_fill_iter(
+                    ext.methods
+                        .iter()
+                        .filter(|m| !m.is_static && !defined.contains(&accessor_member_name(m)))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                );
+                self.register_methods_for(&sub, inherited, &GenericBindings::new(), diagnostics);
+            }
         }
     }
 
@@ -1162,9 +1809,11 @@ impl<'a> Analyzer<'a> {
 
     /// Validates an `@override` object-protocol method: `@override` may only mark `to_string`
     /// / `hash_code`, those must be exported with the exact protocol signature, and a method
-    /// that shadows a protocol name must carry `@override`.
+    /// that shadows a protocol name must carry `@override`. In a subclass (`target` has a base
+    /// class) `@override` may also mark a virtual-method override, checked by `register_vtable`.
     pub(super) fn validate_protocol_override(
         &self,
+        target: &str,
         method: &FunctionNode<'a>,
         diagnostics: &mut DiagnosticBag,
     ) {
@@ -1191,6 +1840,9 @@ impl<'a> Analyzer<'a> {
         let is_override = method.attributes.iter().any(|a| a.name.text == "override");
 
         if is_override && !is_protocol {
+            if self.base_classes.contains_key(target) {
+                return;
+            }
             diagnostics.report_error(
                 format!("'@override' can only be applied to object-protocol methods (to_string, hash_code), not '{}'", name),
                 Some(method.name.position),
@@ -1239,6 +1891,31 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// Validates an `@test` function: the runner calls it with no arguments and ignores any result,
+    /// so it must be a plain, non-generic `fun name()` (optionally `async`) with a body.
+    fn validate_test_function(&self, function: &FunctionNode<'a>, diagnostics: &mut DiagnosticBag) {
+        let name = &function.name.text;
+        let problem = if function.is_extern {
+            Some("must have a body")
+        } else if function.generic_parameters.is_some() {
+            Some("cannot be generic")
+        } else if !function.parameters.is_empty() {
+            Some("must not declare parameters")
+        } else if !matches!(function.return_type, None | Some(Type::Void)) {
+            Some("must not return a value")
+        } else if name == "main" {
+            Some("cannot be 'main'")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            diagnostics.report_error(
+                format!("test function '{}' {}", name, problem),
+                Some(function.name.position),
+            );
+        }
+    }
+
     /// Validates a TypeScript-style property accessor (`get`/`set`): a getter takes no parameters
     /// and returns a non-`void` value; a setter takes exactly one parameter; neither may be `static`
     /// or `async`. Non-accessor methods are ignored.
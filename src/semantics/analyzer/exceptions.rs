@@ -0,0 +1,186 @@
+//! Analysis of `throw` and `try`/`catch`/`finally`. Any value may be thrown; it travels as an
+//! `object` (primitives are boxed), so a typed `catch (e: T)` is an `is T` test on the caught value
+//! followed by the same narrowing an `if (x is T e)` binding performs. A `try` keeps the caught value
+//! in a synthetic `object` local that the catch clauses bind from.
+
+use super::*;
+use crate::syntax::nodes::{CatchClause, ExpressionNode, StatementNode};
+
+const ASYNC_EXCEPTIONS_MESSAGE: &str =
+    "'throw' and 'try' are not supported inside an 'async' function yet";
+
+impl<'a> Analyzer<'a> {
+    pub(super) fn analyze_throw(
+        &mut self,
+        keyword: &SyntaxToken,
+        value: &ExpressionNode<'a>,
+        ctx: &AnalyzerContext<'a, '_>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        if self.current_function_is_async {
+            self.hir_fail();
+            diagnostics.report_error(ASYNC_EXCEPTIONS_MESSAGE.to_string(), Some(keyword.position));
+        }
+        let value_type = self
+            .analyze_expression(value, ctx.parent_function, ctx.symbol_table, diagnostics)
+            .unwrap_or(Type::Unknown);
+        let value_hir = self.hir_take();
+        if matches!(value_type, Type::Void) {
+            self.hir_fail();
+            return Err(report(
+                diagnostics,
+                "cannot throw a value of type void".to_string(),
+                value.position().or(Some(keyword.position)),
+            ));
+        }
+        self.hir_throw(value_hir);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn analyze_try(
+        &mut self,
+        keyword: &SyntaxToken,
+        body: &'a [StatementNode<'a>],
+        catches: &[CatchClause<'a>],
+        finally: &Option<&'a [StatementNode<'a>]>,
+        ctx: &AnalyzerContext<'a, '_>,
+        has_parent_while: bool,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        if self.current_function_is_async {
+            self.hir_fail();
+            diagnostics.report_error(ASYNC_EXCEPTIONS_MESSAGE.to_string(), Some(keyword.position));
+        }
+
+        self.hir_open_block();
+        self.analyze_body(body, ctx.parent_function, Some(ctx.symbol_table), has_parent_while, diagnostics)?;
+        let body_hir = self.hir_close_block();
+
+        // The caught value lives in a synthetic `object` local; `$` keeps its name out of the
+        // source namespace. Each clause gets its own scope holding that local and its binding.
+        let object_type = Type::Object(synthetic_token(TokenKind::DataTypeToken, "object"));
+        let exception_name = format!("$exception{}", keyword.position.start);
+        let exception = synthetic_token(TokenKind::IdentifierToken, &exception_name);
+        let exception_slot = self.hir_alloc_local(&exception_name, &object_type);
+        let mut hir_catches = Vec::with_capacity(catches.len());
+        for clause in catches.iter() {
+            let scope = Rc::new(RefCell::new(SymbolTable::new(Some(ctx.symbol_table.clone()))));
+            ctx.symbol_table.borrow_mut().add_child(scope.clone());
+            let _ = scope.borrow_mut().add_symbol(exception_name.clone(), object_type.clone());
+            let clause_ctx = AnalyzerContext {
+                parent_function: ctx.parent_function,
+                symbol_table: &scope,
+            };
+            let operand = ExpressionNode::Identifier(exception.clone());
+            let target = clause
+                .type_
+                .as_ref()
+                .map(|t| Self::monomorphize_type(t, &self.current_generic_bindings));
+            self.hir_open_block();
+            match &target {
+                Some(target) => {
+                    self.declare_is_binding(
+                        &Some((&clause.name, target, &operand)),
+                        &scope,
+                        &clause_ctx,
+                        diagnostics,
+                    )?;
+                }
+                None => {
+                    self.check_reserved_name(&clause.name, "variable", diagnostics);
+                    let _ = self.analyze_expression(&operand, ctx.parent_function, &scope, diagnostics);
+                    let init = self.hir_take();
+                    self.hir_declare_local(&clause.name.text, &object_type, init);
+                    if let Err(e) = scope
+                        .borrow_mut()
+                        .add_symbol(clause.name.text.clone(), object_type.clone())
+                    {
+                        diagnostics.report_error(e.to_string(), Some(clause.name.position));
+                    }
+                }
+            }
+            self.analyze_body(clause.body, ctx.parent_function, Some(&scope), has_parent_while, diagnostics)?;
+            let catch_hir = self.hir_close_block();
+            hir_catches.push((target, catch_hir));
+        }
+
+        let mut finally_hir = Vec::new();
+        if let Some(finally) = finally {
+            for statement in finally.iter() {
+                self.forbid_finally_exit(statement, false, keyword, diagnostics);
+            }
+            self.hir_open_block();
+            self.with_propagate_barrier(Some("a 'finally' block"), |s| {
+                s.analyze_body(finally, ctx.parent_function, Some(ctx.symbol_table), has_parent_while, diagnostics)
+            })?;
+            finally_hir = self.hir_close_block();
+        }
+        self.hir_try(body_hir, exception_slot, hir_catches, finally_hir);
+        Ok(())
+    }
+
+    /// Rejects control flow that would leave a `finally` body early: `return` anywhere in it, and
+    /// `break`/`continue` outside a loop nested in it. `in_loop` is set once inside such a loop;
+    /// statements without a position of their own are reported at the `try` keyword.
+    fn forbid_finally_exit(
+        &self,
+        statement: &StatementNode<'a>,
+        in_loop: bool,
+        keyword: &SyntaxToken,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        let nested: Vec<&[StatementNode<'a>]> = match statement {
+            StatementNode::Return(value) => {
+                diagnostics.report_error(
+                    "'return' cannot be used inside a 'finally' block".to_string(),
+                    value.as_ref().and_then(|v| v.position()).or(Some(keyword.position)),
+                );
+                Vec::new()
+            }
+            StatementNode::Break(_) | StatementNode::Continue(_) if !in_loop => {
+                diagnostics.report_error(
+                    "'break' and 'continue' cannot leave a 'finally' block".to_string(),
+                    Some(keyword.position),
+                );
+                Vec::new()
+            }
+            StatementNode::IfElse(_, then_body, else_ifs, else_body) => {
+                let mut bodies = vec![*then_body];
+                bodies.extend(else_ifs.iter().map(|(_, body)| *body));
+                bodies.extend(else_body.iter().copied());
+                bodies
+            }
+            StatementNode::Switch(_, cases, default_body) => {
+                let mut bodies: Vec<&[StatementNode<'a>]> = cases.iter().map(|(_, body)| *body).collect();
+                bodies.extend(default_body.iter().copied());
+                bodies
+            }
+            StatementNode::Try(_, body, catches, finally) => {
+                let mut bodies = vec![*body];
+                bodies.extend(catches.iter().map(|c| c.body));
+                bodies.extend(finally.iter().copied());
+                bodies
+            }
+            StatementNode::While(_, body)
+            | StatementNode::DoWhile(body, _)
+            | StatementNode::For(_, _, _, body)
+            | StatementNode::ForEach(_, _, _, _, body) => {
+                for s in body.iter() {
+                    self.forbid_finally_exit(s, true, keyword, diagnostics);
+                }
+                Vec::new()
+            }
+            StatementNode::Labeled(_, inner) => {
+                self.forbid_finally_exit(inner, in_loop, keyword, diagnostics);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
+        for body in nested {
+            for s in body.iter() {
+                self.forbid_finally_exit(s, in_loop, keyword, diagnostics);
+            }
+        }
+    }
+}
@@ -5,7 +5,10 @@ use super::*;
 use crate::diagnostics::DiagnosticBag;
 use crate::semantics::errors::SemanticError;
 use crate::semantics::symbol_table::SymbolTable;
-use crate::syntax::nodes::types::{is_numeric_primitive, mangle_generic, strip_nullable};
+use crate::syntax::nodes::types::{
+    is_numeric_primitive, is_tuple_type_name, mangle_generic, strip_nullable, tuple_type_name,
+    MAX_TUPLE_ARITY,
+};
 use crate::types::method_fn;
 use crate::syntax::nodes::{ExpressionNode, FunctionNode, Type};
 use crate::text::text_span::TextSpan;
@@ -23,6 +26,9 @@ impl<'a> Analyzer<'a> {
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Type, SemanticError> {
         match expression {
+            ExpressionNode::Lambda(lambda) => {
+                self.analyze_lambda(lambda, parent_function, symbol_table, diagnostics)
+            }
             ExpressionNode::Literal(number) => {
                 self.hir_set_literal(number);
                 Ok(number.clone())
@@ -62,6 +68,9 @@ impl<'a> Analyzer<'a> {
                 self.hir_set_array_lit(elem_hirs, &array_type);
                 Ok(array_type)
             }
+            ExpressionNode::Tuple(open, elements) => {
+                self.analyze_tuple_literal(open, elements, parent_function, symbol_table, diagnostics)
+            }
             ExpressionNode::IndexAccess(array_expr, index_expr) => {
                 let array_type = self.analyze_expression(
                     array_expr,
@@ -71,19 +80,18 @@ impl<'a> Analyzer<'a> {
                 )?;
                 let array_hir = self.hir_take();
 
-                // Class/string indexer: `obj[i]` on a struct or `string` receiver desugars to
-                // `obj.get(i)` when an eligible `get` exists (`string` exposes one via `extend
-                // string`, yielding a `char`). Arrays keep the built-in index path; `Unknown` is a
-                // poison carried from an earlier error and must not cascade.
-                if !matches!(array_type, Type::Array(_) | Type::Unknown)
-                    && (Self::resolve_struct_parts(&array_type).is_some()
-                        || matches!(array_type, Type::String(_)))
+                // Class indexer: `obj[i]` on a struct receiver desugars to `obj.get(i)` when an
+                // eligible `get` exists. Arrays and strings keep the built-in (bounds-checked) index
+                // path, a string yielding a `char`; `Unknown` is a poison carried from an earlier
+                // error and must not cascade.
+                if !matches!(array_type, Type::Array(_) | Type::String(_) | Type::Unknown)
+                    && Self::resolve_struct_parts(&array_type).is_some()
                 {
                     // The synthesized call re-evaluates the receiver, so drop the base HIR taken above.
                     let _ = array_hir;
                     return self.analyze_index_get(
-                        *array_expr,
-                        *index_expr,
+                        array_expr,
+                        index_expr,
                         &array_type,
                         parent_function,
                         symbol_table,
@@ -93,6 +101,7 @@ impl<'a> Analyzer<'a> {
 
                 let inner_type = match array_type {
                     Type::Array(inner) => *inner,
+                    Type::String(_) => Type::Char(synthetic_token(TokenKind::DataTypeToken, "char")),
                     // Don't cascade if the base was already poisoned by an earlier error.
                     Type::Unknown => Type::Unknown,
                     _ => {
@@ -128,6 +137,29 @@ impl<'a> Analyzer<'a> {
                 let right_type =
                     self.analyze_expression(right, parent_function, symbol_table, diagnostics)?;
                 let operand = self.hir_take();
+                // `-v` on a class is `v.op_neg()`.
+                if opr.kind == TokenKind::MinusToken {
+                    match self.resolve_operator(&right_type, opr, 0, diagnostics) {
+                        OperatorUse::Builtin => {}
+                        OperatorUse::Method(method) => {
+                            return self.analyze_operator_call(
+                                &right_type,
+                                operand,
+                                opr,
+                                method,
+                                vec![],
+                                parent_function,
+                                symbol_table,
+                                diagnostics,
+                            );
+                        }
+                        OperatorUse::Rejected => {
+                            self.hir_fail();
+                            self.hir_none();
+                            return Ok(Type::Unknown);
+                        }
+                    }
+                }
                 match opr.kind {
                     TokenKind::BangToken => {
                         if !right_type.is_unknown() && !right_type.is_bool() {
@@ -209,12 +241,17 @@ impl<'a> Analyzer<'a> {
                 let left_name = left_type.get_type();
                 let right_name = right_type.get_type();
                 let stripped = strip_nullable(&left_name);
+                let target = strip_nullable(&right_name);
                 if left_type.is_unknown() {
                     self.hir_none();
-                } else if stripped == "object" || self.is_interface_name(&stripped) {
+                } else if stripped == "object"
+                    || self.is_interface_name(stripped)
+                    || self.is_subclass_of(target, stripped)
+                {
+                    // Testing a class operand against one of its subclasses needs the runtime tag.
                     self.hir_set_is_type(left_hir, right_type);
                 } else {
-                    self.hir_set_bool(stripped == strip_nullable(&right_name));
+                    self.hir_set_bool(stripped == target || self.is_subclass_of(stripped, target));
                 }
                 Ok(Type::Boolean(synthetic_token(
                     TokenKind::BooleanToken,
@@ -237,11 +274,14 @@ impl<'a> Analyzer<'a> {
                         condition.position(),
                     );
                 }
-                let then_type =
-                    self.analyze_expression(then_expr, parent_function, symbol_table, diagnostics)?;
+                let branch = Some("a ternary branch");
+                let then_type = self.with_propagate_barrier(branch, |s| {
+                    s.analyze_expression(then_expr, parent_function, symbol_table, diagnostics)
+                })?;
                 let then_hir = self.hir_take();
-                let else_type =
-                    self.analyze_expression(else_expr, parent_function, symbol_table, diagnostics)?;
+                let else_type = self.with_propagate_barrier(branch, |s| {
+                    s.analyze_expression(else_expr, parent_function, symbol_table, diagnostics)
+                })?;
                 let else_hir = self.hir_take();
                 // Both branches must agree; reuse the standard compatibility check.
                 self.compare_data_type(&then_type, &else_type, &empty_span(), diagnostics)?;
@@ -287,6 +327,9 @@ impl<'a> Analyzer<'a> {
                 // `analyze_method_call` records the `MethodCall`/`Call` (or clears `last`) itself.
                 Ok(t)
             }
+            ExpressionNode::Propagate(inner, question) => {
+                self.analyze_propagate(inner, question, parent_function, symbol_table, diagnostics)
+            }
             ExpressionNode::Await(inner) => {
                 let fut =
                     self.analyze_expression(inner, parent_function, symbol_table, diagnostics)?;
@@ -313,9 +356,138 @@ impl<'a> Analyzer<'a> {
         }
     }
 
-    /// Desugars a class indexer read `obj[index]` to `obj.get(index)` when `obj_type` exposes an
-    /// eligible `get` (see [`Analyzer::resolve_hook_method`]): an accessible instance, non-async
-    /// method taking one argument and returning a (non-`void`) value. Any other same-named `get`
+    /// Types `operand?`. The operand must be an `Option<T>` or a `Result<T, E>`, and the enclosing
+    /// function must return the same kind of union — for a `Result`, one whose error type accepts
+    /// `E` — so its `None`/`Err` can be returned as is. The value is the `T`. It desugars in front of
+    /// the enclosing statement to
+    ///
+    /// ```text
+    /// let $p = <operand>;
+    /// if (discriminant($p) != Some/Ok) { return Option.None / Result.Err($p.error); }
+    /// ```
+    ///
+    /// leaving `$p.value` as the expression, so `let x = await f()?;` in an `async fun` still binds
+    /// the awaited value first, the shape the async lowering suspends on.
+    fn analyze_propagate(
+        &mut self,
+        operand: &ExpressionNode<'a>,
+        question: &SyntaxToken,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let operand_type =
+            self.analyze_expression(operand, parent_function, symbol_table, diagnostics)?;
+        let operand_hir = self.hir_take();
+        if operand_type.is_unknown() {
+            self.hir_none();
+            return Ok(Type::Unknown);
+        }
+        let fail = |s: &mut Self, diagnostics: &mut DiagnosticBag, message: String| {
+            s.hir_fail();
+            s.hir_none();
+            Err(report(diagnostics, message, Some(question.position)))
+        };
+        let (union, args) = match &operand_type {
+            Type::Struct(base, Some(args))
+                if (base.text == "Option" && args.len() == 1)
+                    || (base.text == "Result" && args.len() == 2) =>
+            {
+                (base.text.clone(), args.clone())
+            }
+            other => {
+                return fail(
+                    self,
+                    diagnostics,
+                    format!("the '?' operator needs an Option or Result value, got {}", other.display_name()),
+                );
+            }
+        };
+        if let Some(context) = self.propagate_barrier {
+            return fail(self, diagnostics, format!("the '?' operator cannot be used in {}", context));
+        }
+
+        let return_type = parent_function
+            .return_type
+            .as_ref()
+            .map(|t| Self::monomorphize_type(t, &self.current_generic_bindings));
+        let return_args = match &return_type {
+            Some(Type::Struct(base, Some(ret_args))) if base.text == union => Some(ret_args.clone()),
+            _ => None,
+        };
+        let returns = return_type.as_ref().map_or("void".to_string(), |t| t.display_name());
+        let Some(return_args) = return_args else {
+            return fail(
+                self,
+                diagnostics,
+                format!(
+                    "the '?' operator on {} can only be used in a function that returns {}, but '{}' returns {}",
+                    operand_type.display_name(),
+                    if union == "Option" { "an Option" } else { "a Result" },
+                    parent_function.name.text,
+                    returns
+                ),
+            );
+        };
+        let return_error = match (args.get(1), return_args.get(1)) {
+            (Some(error), Some(return_error)) => {
+                if !self.type_str_assignable(&return_error.get_type(), &error.get_type()) {
+                    return fail(
+                        self,
+                        diagnostics,
+                        format!(
+                            "the '?' operator cannot return the {} error of {} from '{}', which returns {}",
+                            error.display_name(),
+                            operand_type.display_name(),
+                            parent_function.name.text,
+                            returns
+                        ),
+                    );
+                }
+                Some(return_error.clone())
+            }
+            _ => None,
+        };
+
+        let return_type = return_type.unwrap_or(Type::Unknown);
+        self.ensure_union_instantiated(&union, &args, &question.position, diagnostics);
+        self.ensure_union_instantiated(&union, &return_args, &question.position, diagnostics);
+        let def = self.type_ctx.defs.lookup(crate::types::DefKind::Union, &union);
+        let discriminant = |s: &Self, variant: &str| {
+            s.union_table
+                .get(&mangle_generic(&union, &args))
+                .and_then(|info| info.variant(variant))
+                .map(|v| v.discriminant as usize)
+        };
+        let (success, failure) = if union == "Option" {
+            (discriminant(self, "Some"), discriminant(self, "None"))
+        } else {
+            (discriminant(self, "Ok"), discriminant(self, "Err"))
+        };
+        match (def, success, failure) {
+            (Some(def), Some(success), Some(failure)) => {
+                let slot_name = format!("$propagate{}", question.position.start);
+                self.hir_set_propagate(
+                    &slot_name,
+                    operand_hir,
+                    &operand_type,
+                    (def, success, failure),
+                    return_error.as_ref(),
+                    &return_type,
+                );
+            }
+            _ => {
+                self.hir_fail();
+                self.hir_none();
+            }
+        }
+        Ok(args[0].clone())
+    }
+
+    /// Desugars a class indexer read `obj[index]` to `obj.op_index(index)`, or to `obj.get(index)`
+    /// when there is no `op_index`, if `obj_type` exposes an eligible one (see
+    /// [`Analyzer::resolve_hook_method`]): an accessible instance, non-async method taking one
+    /// argument and returning a (non-`void`) value. Any other same-named method
     /// (static/async/void/wrong arity) is left as an ordinary method and this site reports why the
     /// value cannot be indexed, rather than silently rewriting the call.
     fn analyze_index_get(
@@ -327,21 +499,27 @@ impl<'a> Analyzer<'a> {
         symbol_table: &Rc<RefCell<SymbolTable>>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Type, SemanticError> {
-        match self.resolve_hook_method(obj_type, "get", 1, diagnostics) {
+        // `op_index`, the operator-method spelling of the indexer, takes precedence over `get`.
+        let hook = match self.resolve_hook_method(obj_type, "op_index", 1, diagnostics) {
+            super::calls::HookResolution::Absent => "get",
+            _ => "op_index",
+        };
+        match self.resolve_hook_method(obj_type, hook, 1, diagnostics) {
             super::calls::HookResolution::Eligible(info) => {
                 if matches!(info.return_type, None | Some(Type::Void)) {
                     self.hir_fail();
                     self.hir_none();
                     diagnostics.report_error(
                         format!(
-                            "type '{}' has no indexer: its 'get' must return a value",
-                            obj_type.get_type()
+                            "type '{}' has no indexer: its '{}' must return a value",
+                            obj_type.get_type(),
+                            hook
                         ),
                         array_expr.position(),
                     );
                     return Ok(Type::Unknown);
                 }
-                let get_tok = synthetic_token(TokenKind::IdentifierToken, "get");
+                let get_tok = synthetic_token(TokenKind::IdentifierToken, hook);
                 let call = ExpressionNode::MethodCall(
                     array_expr,
                     get_tok,
@@ -483,11 +661,13 @@ impl<'a> Analyzer<'a> {
                 .map(|f| (f.type_.clone(), f.is_public)),
             None => {
                 self.hir_none();
-                return Err(report(
-                    diagnostics,
-                    format!("Struct '{}' not found", struct_name),
-                    Some(member.position),
-                ));
+                // Interfaces (including a bounded type parameter's archetype) expose methods only.
+                let message = if self.is_interface_name(&struct_name) {
+                    format!("interface '{}' has no field '{}'", struct_name, member.text)
+                } else {
+                    format!("Struct '{}' not found", struct_name)
+                };
+                return Err(report(diagnostics, message, Some(member.position)));
             }
         };
 
@@ -509,6 +689,17 @@ impl<'a> Analyzer<'a> {
                     );
                 }
                 self.hir_none();
+                if is_tuple_type_name(&base_name) {
+                    return Err(report(
+                        diagnostics,
+                        format!(
+                            "Tuple type '{}' has no element {}",
+                            obj_type.display_name(),
+                            member.text
+                        ),
+                        Some(member.position),
+                    ));
+                }
                 return Err(report(
                     diagnostics,
                     format!(
@@ -536,6 +727,88 @@ impl<'a> Analyzer<'a> {
         Ok(field_type)
     }
 
+    /// Types a tuple literal `(a, b, ...)` as an instance of the synthesized `Tuple#N` class. An
+    /// expected tuple type of the same arity (a `let` annotation or the function's return type) is
+    /// pushed down element by element, so `let p: (double, string?) = (1, null);` widens the `1`
+    /// and types the `null`; without one each element keeps its own type, and a bare `null` has
+    /// nothing to take its type from.
+    fn analyze_tuple_literal(
+        &mut self,
+        open: &SyntaxToken,
+        elements: &[ExpressionNode<'a>],
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let expected: Option<Vec<Type>> = self
+            .current_expected_type
+            .as_ref()
+            .and_then(|t| match t {
+                Type::Nullable(inner) => inner.tuple_elements(),
+                t => t.tuple_elements(),
+            })
+            .filter(|elems| elems.len() == elements.len())
+            .map(|elems| elems.to_vec());
+        let saved_expected = self.current_expected_type.take();
+
+        let mut element_types = Vec::with_capacity(elements.len());
+        let mut element_hirs = Vec::with_capacity(elements.len());
+        let mut failed = None;
+        for (i, element) in elements.iter().enumerate() {
+            let want = expected.as_ref().map(|elems| elems[i].clone());
+            self.current_expected_type = want.clone();
+            let actual =
+                match self.analyze_expression(element, parent_function, symbol_table, diagnostics) {
+                    Ok(t) => t,
+                    Err(e) => {
+                        failed = Some(e);
+                        break;
+                    }
+                };
+            element_hirs.push(self.hir_take());
+            let position = element.position().unwrap_or(open.position);
+            let element_type = match want {
+                Some(want) => {
+                    if let Err(e) =
+                        self.compare_data_type(&want, &actual, &position, diagnostics)
+                    {
+                        failed = Some(e);
+                        break;
+                    }
+                    want
+                }
+                None if actual.get_type() == "void?" => {
+                    diagnostics.report_error(
+                        "Cannot infer the type of a 'null' tuple element; annotate the tuple type"
+                            .to_string(),
+                        Some(position),
+                    );
+                    Type::Unknown
+                }
+                None => actual,
+            };
+            element_types.push(element_type);
+        }
+        self.current_expected_type = saved_expected;
+        if let Some(e) = failed {
+            self.hir_none();
+            return Err(e);
+        }
+
+        // The parser already reported an out-of-range arity; there is no template to instantiate.
+        let arity = elements.len();
+        if !(2..=MAX_TUPLE_ARITY).contains(&arity) || element_types.iter().any(Type::is_unknown) {
+            self.hir_none();
+            return Ok(Type::Unknown);
+        }
+        let base_name = tuple_type_name(arity);
+        self.ensure_struct_instantiated(&base_name, &element_types, &open.position, diagnostics);
+        let tuple_type = Type::tuple(open.position, element_types);
+        let element_types = tuple_type.tuple_elements().unwrap_or_default();
+        self.hir_set_tuple(&base_name, element_hirs, element_types, &tuple_type);
+        Ok(tuple_type)
+    }
+
     /// Types a cast `expr as T`: instantiates a generic target struct if needed, then validates the
     /// conversion (identity, numeric<->numeric, `char`<->`int`/`byte`, boxing/unboxing via `object`,
     /// and `int`->pointer for null literals). Always yields the target type, reporting an error for
@@ -612,7 +885,13 @@ impl<'a> Analyzer<'a> {
             }
         } else if self.is_interface_name(strip_nullable(&expr_type_str)) {
             // Downcast from an interface to a concrete class or another interface: permitted
-            // (identity at runtime; like unboxing `object`, a wrong downcast is the caller's risk).
+            // (identity at runtime; a downcast to a class is checked against the runtime tag).
+            Ok(target_type.clone())
+        } else if self.is_subclass_of(strip_nullable(&expr_type_str), strip_nullable(&target_type_str))
+            || self.is_subclass_of(strip_nullable(&target_type_str), strip_nullable(&expr_type_str))
+        {
+            // Up- or downcast within a class hierarchy: identity at runtime, with a downcast checked
+            // against the runtime tag like `is` (see `RuntimeCheck::Downcast`).
             Ok(target_type.clone())
         } else {
             diagnostics.report_error(
@@ -635,8 +914,63 @@ impl<'a> Analyzer<'a> {
         let left_value =
             self.analyze_expression(left, parent_function, symbol_table, diagnostics)?;
         let left_hir = self.hir_take();
-        let right_value =
-            self.analyze_expression(right, parent_function, symbol_table, diagnostics)?;
+
+        // A class operand overloads the operator with one of its `op_*` methods: `a + b` is
+        // `a.op_add(b)`, `a != b` is `!a.op_eq(b)`. The left operand's HIR becomes the receiver,
+        // so neither side is analyzed twice. `==`/`!=` also reach `op_eq` through a nullable
+        // class operand (see `analyze_equality_call`); `x == null` keeps comparing references.
+        let is_null_literal =
+            matches!(right, ExpressionNode::Literal(Type::Nullable(inner)) if matches!(**inner, Type::Void));
+        if !is_null_literal {
+            let is_equality = matches!(opr.kind, TokenKind::EqualEqualToken | TokenKind::NotEqualToken);
+            let operand = match &left_value {
+                Type::Nullable(inner) if is_equality => (**inner).clone(),
+                other => other.clone(),
+            };
+            match self.resolve_operator(&operand, opr, 1, diagnostics) {
+                OperatorUse::Builtin => {}
+                OperatorUse::Method(_) if is_equality => {
+                    return self.analyze_equality_call(
+                        &operand,
+                        &left_value,
+                        left_hir,
+                        opr,
+                        right,
+                        parent_function,
+                        symbol_table,
+                        diagnostics,
+                    );
+                }
+                OperatorUse::Method(method) => {
+                    return self.analyze_operator_call(
+                        &left_value,
+                        left_hir,
+                        opr,
+                        method,
+                        vec![right.clone()],
+                        parent_function,
+                        symbol_table,
+                        diagnostics,
+                    );
+                }
+                OperatorUse::Rejected => {
+                    let _ = self.analyze_expression(right, parent_function, symbol_table, diagnostics)?;
+                    self.hir_fail();
+                    self.hir_none();
+                    return Ok(Type::Unknown);
+                }
+            }
+        }
+
+        let barrier = match opr.kind {
+            TokenKind::AmpersandAmpersandToken => Some("the right operand of '&&'"),
+            TokenKind::PipePipeToken => Some("the right operand of '||'"),
+            TokenKind::QuestionQuestionToken => Some("the right operand of '??'"),
+            _ => self.propagate_barrier,
+        };
+        let right_value = self.with_propagate_barrier(barrier, |s| {
+            s.analyze_expression(right, parent_function, symbol_table, diagnostics)
+        })?;
         let right_hir = self.hir_take();
 
         // Null-coalescing `a ?? b`: `a` should be nullable; the result is the unwrapped element
@@ -705,6 +1039,186 @@ impl<'a> Analyzer<'a> {
         self.hir_set_binary(left_hir, opr, right_hir, &result_type);
         Ok(result_type)
     }
+
+    /// Decides whether `opr`, applied to an `operand` with `arity` further operands, is a call to
+    /// an operator method. Only a (non-null) class value overloads operators; on one, an operator
+    /// without a usable method is an error, except `==`/`!=`, which fall back to comparing
+    /// references. A `string` is ordered by the `op_lt`..`op_ge` methods of `extend string`.
+    fn resolve_operator(
+        &mut self,
+        operand: &Type,
+        opr: &SyntaxToken,
+        arity: usize,
+        diagnostics: &mut DiagnosticBag,
+    ) -> OperatorUse {
+        let Some(method) = operator_method(opr.kind, arity) else {
+            return OperatorUse::Builtin;
+        };
+        let string_order = matches!(operand, Type::String(_))
+            && matches!(
+                opr.kind,
+                TokenKind::SmallerThanToken
+                    | TokenKind::SmallerThanEqualToken
+                    | TokenKind::GreaterThanToken
+                    | TokenKind::GreaterThanEqualToken
+            );
+        if !self.is_class_value(operand) && !string_order {
+            return OperatorUse::Builtin;
+        }
+        let is_equality = matches!(opr.kind, TokenKind::EqualEqualToken | TokenKind::NotEqualToken);
+        match self.resolve_hook_method(operand, method, arity, diagnostics) {
+            super::calls::HookResolution::Eligible(info) => {
+                let returns_bool = is_equality
+                    || matches!(
+                        opr.kind,
+                        TokenKind::SmallerThanToken
+                            | TokenKind::SmallerThanEqualToken
+                            | TokenKind::GreaterThanToken
+                            | TokenKind::GreaterThanEqualToken
+                    );
+                match &info.return_type {
+                    None | Some(Type::Void) => diagnostics.report_error(
+                        format!("'{}' must return a value to overload '{}'", method, opr.text),
+                        Some(opr.position),
+                    ),
+                    Some(ret) if returns_bool && !ret.is_bool() => diagnostics.report_error(
+                        format!(
+                            "'{}' must return bool to overload '{}', not '{}'",
+                            method,
+                            opr.text,
+                            ret.display_name()
+                        ),
+                        Some(opr.position),
+                    ),
+                    _ => return OperatorUse::Method(method),
+                }
+            }
+            super::calls::HookResolution::Ineligible(reason) => diagnostics.report_error(
+                format!(
+                    "Operator '{}' on '{}' cannot use its '{}': {}",
+                    opr.text,
+                    operand.display_name(),
+                    method,
+                    reason
+                ),
+                Some(opr.position),
+            ),
+            super::calls::HookResolution::Absent if is_equality => return OperatorUse::Builtin,
+            super::calls::HookResolution::Absent => diagnostics.report_error(
+                format!(
+                    "Operator '{}' is not defined for '{}' (define '{}' to overload it)",
+                    opr.text,
+                    operand.display_name(),
+                    method
+                ),
+                Some(opr.position),
+            ),
+        }
+        OperatorUse::Rejected
+    }
+
+    /// Types an overloaded operator as the method call `receiver.method(args)`, reusing the
+    /// already-analyzed operand as the receiver. `!=` negates the `op_eq` call.
+    #[allow(clippy::too_many_arguments)]
+    fn analyze_operator_call(
+        &mut self,
+        operand: &Type,
+        receiver: Option<crate::hir::HExpr>,
+        opr: &SyntaxToken,
+        method: &str,
+        args: Vec<ExpressionNode<'a>>,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        let mut method_tok = synthetic_token(TokenKind::IdentifierToken, method);
+        method_tok.position = opr.position;
+        let ctx = super::AnalyzerContext {
+            parent_function,
+            symbol_table,
+        };
+        let result =
+            self.analyze_instance_method(operand, &method_tok, &None, &args, &ctx, receiver, diagnostics)?;
+        if opr.kind == TokenKind::NotEqualToken && !result.is_unknown() {
+            let call = self.hir_take();
+            let result = Type::Boolean(opr.clone());
+            self.hir_set_unary(&synthetic_token(TokenKind::BangToken, "!"), call, &result);
+            return Ok(result);
+        }
+        Ok(result)
+    }
+
+    /// Types `left == right` (or `!=`) for a `class` with `op_eq`, where either operand may be
+    /// nullable. Each operand is evaluated once, in order, into a hidden local; two nulls are
+    /// equal, a null never equals a value, and two values compare with `left.op_eq(right)`, so
+    /// `a == b` and `b == a` agree whichever side is nullable.
+    #[allow(clippy::too_many_arguments)]
+    fn analyze_equality_call(
+        &mut self,
+        class: &Type,
+        left_value: &Type,
+        left_hir: Option<crate::hir::HExpr>,
+        opr: &SyntaxToken,
+        right: &ExpressionNode<'a>,
+        parent_function: &FunctionNode<'a>,
+        symbol_table: &Rc<RefCell<SymbolTable>>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<Type, SemanticError> {
+        const LHS: &str = "__eq_lhs";
+        const RHS: &str = "__eq_rhs";
+        let right_value = self.analyze_expression(right, parent_function, symbol_table, diagnostics)?;
+        let right_hir = self.hir_take();
+        let right_nullable = matches!(&right_value, Type::Nullable(inner) if !matches!(**inner, Type::Void));
+        let right_class = match &right_value {
+            Type::Nullable(inner) if right_nullable => (**inner).clone(),
+            other => other.clone(),
+        };
+
+        // `op_eq` is typed as a call on the non-null values: the receiver reads the left local and
+        // the argument names the right one, declared in a scope of its own.
+        let lhs_local = self.hir_alloc_local(LHS, left_value);
+        let rhs_local = self.hir_alloc_local(RHS, &right_value);
+        let scope = Rc::new(RefCell::new(SymbolTable::new(Some(symbol_table.clone()))));
+        let _ = scope.borrow_mut().add_symbol(RHS.to_string(), right_class);
+        let class_ty = self.type_ctx.lower(class);
+        let receiver = lhs_local.map(|local| self.hx_local(local, class_ty));
+        let mut eq = synthetic_token(TokenKind::EqualEqualToken, "==");
+        eq.position = opr.position;
+        let mut arg = synthetic_token(TokenKind::IdentifierToken, RHS);
+        arg.position = opr.position;
+        let result = self.analyze_operator_call(
+            class,
+            receiver,
+            &eq,
+            "op_eq",
+            vec![ExpressionNode::Identifier(arg)],
+            parent_function,
+            &scope,
+            diagnostics,
+        )?;
+        if result.is_unknown() {
+            self.hir_none();
+            return Ok(result);
+        }
+        let call = self.hir_take();
+        self.hir_set_equality(
+            (lhs_local, left_hir, matches!(left_value, Type::Nullable(_))),
+            (rhs_local, right_hir, right_nullable),
+            call,
+            opr.kind == TokenKind::NotEqualToken,
+        );
+        Ok(Type::Boolean(opr.clone()))
+    }
+
+    /// True for a (non-null) value of a user class: not a tuple, union, enum or interface.
+    fn is_class_value(&self, ty: &Type) -> bool {
+        let Type::Struct(token, _) = ty else {
+            return false;
+        };
+        !is_tuple_type_name(&token.text)
+            && (self.type_ctx.nominal_kind(&token.text) == Some(crate::types::DefKind::Struct)
+                || self.demangle_generic_struct(&token.text).is_some())
+    }
     pub(super) fn compare_data_type(
         &mut self,
         left: &Type,
@@ -740,6 +1254,10 @@ impl<'a> Analyzer<'a> {
         if self.value_assignable_to_interface(left, right) {
             return Ok(());
         }
+        // Implicit upcast to a base class (`let a: Animal = dog;`).
+        if self.class_upcastable(&left.get_type(), &right.get_type()) {
+            return Ok(());
+        }
 
         diagnostics.report_error(
             format!(
@@ -803,9 +1321,72 @@ impl<'a> Analyzer<'a> {
     }
 
     /// Reconstructs a `Type` from its canonical type-name string (as stored in function-table
-    /// signatures), e.g. "int", "string", "Node", "int[]". Falls back to `void` if unparseable.
+    /// signatures), e.g. "int", "string", "Node", "int[]", "fun(int):bool". Falls back to `void` if
+    /// unparseable.
     pub(super) fn type_from_name(name: &str) -> Type {
+        if let Some(function) = Self::function_type_from_name(name) {
+            return function;
+        }
         let token = synthetic_token(TokenKind::IdentifierToken, name);
         Type::from_token(token).unwrap_or(Type::Void)
     }
+
+    /// Parses the `fun(p1,p2):ret` spelling of a function type (see `Type::get_type`), whose
+    /// parameter and return names may themselves be function types or generic instances.
+    fn function_type_from_name(name: &str) -> Option<Type> {
+        let rest = name.strip_prefix("fun(")?;
+        let mut params = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        for (i, c) in rest.char_indices() {
+            match c {
+                '(' | '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ')' if depth > 0 => depth -= 1,
+                ',' if depth == 0 => {
+                    params.push(Self::type_from_name(&rest[start..i]));
+                    start = i + 1;
+                }
+                ')' => {
+                    if start < i {
+                        params.push(Self::type_from_name(&rest[start..i]));
+                    }
+                    let ret = rest[i + 1..].strip_prefix(':')?;
+                    return Some(Type::Function(params, Box::new(Self::type_from_name(ret))));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// How an operator applies to a class operand (see [`Analyzer::resolve_operator`]).
+enum OperatorUse {
+    /// The operand is not a class (or `==`/`!=` has no `op_eq`): the built-in operator.
+    Builtin,
+    /// A call to the named operator method.
+    Method(&'static str),
+    /// The class cannot use the operator; the error has been reported.
+    Rejected,
+}
+
+/// The method a class defines to overload `kind` with `arity` operands besides itself:
+/// `a + b` calls `a.op_add(b)`, `-a` calls `a.op_neg()`.
+fn operator_method(kind: TokenKind, arity: usize) -> Option<&'static str> {
+    let method = match (kind, arity) {
+        (TokenKind::PlusToken, 1) => "op_add",
+        (TokenKind::MinusToken, 1) => "op_sub",
+        (TokenKind::StarToken, 1) => "op_mul",
+        (TokenKind::SlashToken, 1) => "op_div",
+        (TokenKind::ModulusToken, 1) => "op_mod",
+        (TokenKind::EqualEqualToken | TokenKind::NotEqualToken, 1) => "op_eq",
+        (TokenKind::SmallerThanToken, 1) => "op_lt",
+        (TokenKind::SmallerThanEqualToken, 1) => "op_le",
+        (TokenKind::GreaterThanToken, 1) => "op_gt",
+        (TokenKind::GreaterThanEqualToken, 1) => "op_ge",
+        (TokenKind::MinusToken, 0) => "op_neg",
+        _ => return None,
+    };
+    Some(method)
 }
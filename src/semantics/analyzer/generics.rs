@@ -1,10 +1,46 @@
 use super::*;
 use crate::diagnostics::DiagnosticBag;
+use crate::semantics::errors::SemanticError;
 use crate::syntax::nodes::function::ParameterNode;
-use crate::syntax::nodes::{FunctionNode, Type};
+use crate::syntax::nodes::types::{
+    is_unknown_type_name, mangle_generic, strip_nullable, UNKNOWN_TYPE_NAME,
+};
+use crate::syntax::nodes::{FunctionNode, GenericBound, Type};
 use crate::text::text_span::TextSpan;
+use crate::syntax::token::syntax_token::SyntaxToken;
 use crate::syntax::token::token_kind::TokenKind;
 
+/// Separates owner and parameter in an archetype name (`max::T`). It cannot occur in a source type
+/// name, so any type spelling containing it was built from an archetype.
+const ARCHETYPE_SEPARATOR: &str = "::";
+
+/// The archetype standing in for type parameter `param` of generic declaration `owner`.
+fn archetype_name(owner: &str, param: &str) -> String {
+    format!("{}{}{}", owner, ARCHETYPE_SEPARATOR, param)
+}
+
+/// True when the type or symbol spelled `name` mentions an archetype, i.e. it only exists to check
+/// a bounded generic body and must not reach the emitted program.
+pub(super) fn is_archetype_derived(name: &str) -> bool {
+    name.contains(ARCHETYPE_SEPARATOR)
+}
+
+/// Stands in for a type parameter in a mangled type name while matching it against a concrete
+/// one. It cannot occur in a source type name.
+const GENERIC_HOLE: &str = "<hole>";
+
+/// The text `GENERIC_HOLE` covers when `pattern` (which contains it) is laid over `name`, with
+/// every occurrence of the hole covering the same text. `None` when they do not match.
+fn match_hole(pattern: &str, name: &str) -> Option<String> {
+    let (prefix, rest) = pattern.split_once(GENERIC_HOLE)?;
+    let tail = name.strip_prefix(prefix)?;
+    (1..=tail.len())
+        .filter(|&len| tail.is_char_boundary(len))
+        .map(|len| &tail[..len])
+        .find(|candidate| format!("{}{}", candidate, rest.replace(GENERIC_HOLE, candidate)) == tail)
+        .map(str::to_string)
+}
+
 impl<'a> Analyzer<'a> {
     /// Substitutes every generic parameter appearing in a method's parameter or return types
     /// with its concrete type, according to the monomorphization bindings.
@@ -37,19 +73,88 @@ impl<'a> Analyzer<'a> {
                     Self::match_generic_type(inner, arg, param_name)
                 }
             }
+            // `fun(T): U` against a function value's type: match parameter-wise, then the result.
+            Type::Function(params, ret) => match Self::type_from_name(arg) {
+                Type::Function(arg_params, arg_ret) if arg_params.len() == params.len() => params
+                    .iter()
+                    .zip(arg_params.iter())
+                    .map(|(p, a)| (p, a.get_type()))
+                    .chain(std::iter::once((&**ret, arg_ret.get_type())))
+                    .find_map(|(formal, arg)| Self::match_generic_type(formal, &arg, param_name)),
+                _ => None,
+            },
+            // `Option<U>` against a generic instance's mangled name (`Option_int`): mangle the
+            // formal with a placeholder standing in for the parameter, then read the parameter off
+            // the span of `arg` the placeholder covers.
+            Type::Struct(_, Some(_)) => {
+                let hole = Type::Struct(synthetic_token(TokenKind::IdentifierToken, GENERIC_HOLE), None);
+                let bindings = GenericBindings::from([(param_name.to_string(), hole)]);
+                match_hole(&Self::monomorphize_type(formal, &bindings).get_type(), arg)
+                    .filter(|concrete| !concrete.contains(UNKNOWN_TYPE_NAME))
+            }
             _ => None,
         }
     }
 
+    /// The type `param` takes when `template`'s return type (with `outer` substituted) is matched
+    /// against `expected`, the type the call's context expects (a `let` annotation, say). Only
+    /// what that fully spells out counts.
+    fn match_expected_result(
+        template: &FunctionNode<'a>,
+        outer: &GenericBindings,
+        expected: Option<&Type>,
+        param: &str,
+    ) -> Option<String> {
+        let ret = Self::monomorphize_type(template.return_type.as_ref()?, outer);
+        Self::match_generic_type(&ret, &expected?.get_type(), param)
+            .filter(|concrete| !concrete.contains(UNKNOWN_TYPE_NAME))
+    }
+
+    /// The expected type of argument `index` of a call to generic `template`, given the types of
+    /// the arguments before it: the formal parameter type with each type parameter fixed by the
+    /// explicit type arguments, an earlier argument, or the call's `expected` result, and with
+    /// `outer` (the bindings of the generic class declaring a method) substituted; the rest are
+    /// left unknown. This is what lets the lambda in `Array.map(xs, (x) => x * 2)` take its
+    /// parameter type from `xs` and its result from its body.
+    pub(super) fn generic_arg_hint(
+        template: &FunctionNode<'a>,
+        outer: &GenericBindings,
+        generic_args: &Option<Vec<Type>>,
+        index: usize,
+        earlier: &[String],
+        expected: Option<&Type>,
+    ) -> Option<Type> {
+        let formal = &template.parameters.get(index)?.type_;
+        let gen_params = template.generic_parameters.as_deref().unwrap_or(&[]);
+        let explicit = generic_args.as_deref().filter(|g| g.len() == gen_params.len());
+        let mut bindings = outer.clone();
+        for (i, param) in gen_params.iter().enumerate() {
+            let concrete = match explicit {
+                Some(args) => Some(args[i].clone()),
+                None => template
+                    .parameters
+                    .iter()
+                    .zip(earlier)
+                    .find_map(|(formal, arg)| Self::match_generic_type(&formal.type_, arg, &param.text))
+                    .or_else(|| Self::match_expected_result(template, outer, expected, &param.text))
+                    .map(|concrete| Self::concrete_type_from_str(&concrete)),
+            };
+            bindings.insert(param.text.clone(), concrete.unwrap_or(Type::Unknown));
+        }
+        Some(Self::monomorphize_type(formal, &bindings))
+    }
+
     /// Determines the concrete type bound to each generic parameter of `template` for one call.
     /// Uses explicit type arguments when given (arity-checked); otherwise infers each parameter
-    /// from the actual argument passed to the first formal parameter that is exactly that
-    /// parameter. Parameters that cannot be inferred produce a diagnostic.
+    /// from the actual argument passed to the first formal parameter that mentions it, or failing
+    /// that from the call's `expected` result type. Parameters that cannot be inferred produce a
+    /// diagnostic.
     pub(super) fn infer_generic_bindings(
         &self,
         template: &FunctionNode<'a>,
         generic_args: &Option<Vec<Type>>,
         params_types: &[String],
+        expected: Option<&Type>,
         position: &TextSpan,
         diagnostics: &mut DiagnosticBag,
     ) -> GenericBindings {
@@ -76,6 +181,9 @@ impl<'a> Analyzer<'a> {
                 params_types.get(i).and_then(|arg| {
                     Self::match_generic_type(&formal.type_, arg, &param.text)
                 })
+            })
+            .or_else(|| {
+                Self::match_expected_result(template, &GenericBindings::new(), expected, &param.text)
             });
             match concrete {
                 Some(concrete) => (param.text.clone(), Self::concrete_type_from_str(&concrete)),
@@ -91,7 +199,8 @@ impl<'a> Analyzer<'a> {
     }
 
     /// Returns `ty` with any generic parameter substituted for its concrete type per the
-    /// monomorphization bindings, recursing through array and nullable wrappers (`T`, `T[]`, `T?`).
+    /// monomorphization bindings, recursing through array, nullable and function types (`T`, `T[]`,
+    /// `T?`, `fun(T): T`).
     pub(super) fn monomorphize_type(ty: &Type, bindings: &GenericBindings) -> Type {
         match ty {
             Type::Struct(token, None) => match lookup_binding(bindings, &token.text) {
@@ -112,6 +221,13 @@ impl<'a> Analyzer<'a> {
             Type::Nullable(inner) => {
                 Type::Nullable(Box::new(Self::monomorphize_type(inner, bindings)))
             }
+            Type::Function(params, ret) => Type::Function(
+                params
+                    .iter()
+                    .map(|p| Self::monomorphize_type(p, bindings))
+                    .collect(),
+                Box::new(Self::monomorphize_type(ret, bindings)),
+            ),
             _ => ty.clone(),
         }
     }
@@ -127,4 +243,360 @@ impl<'a> Analyzer<'a> {
             this_type,
         )
     }
+
+    /// True while analyzing a body instantiated with an archetype (see [`Self::check_bounded_generics`]),
+    /// whose HIR is never emitted.
+    pub(super) fn under_archetype(&self) -> bool {
+        self.current_generic_bindings
+            .values()
+            .any(|t| is_archetype_derived(&t.get_type()))
+    }
+
+    /// Resolves one bound, with the owner's type parameters substituted per `bindings`, to the
+    /// interface it names (instantiating a generic interface such as `Comparable<Box>`). `None`
+    /// when the bound is not an interface; [`Self::validate_generic_bounds`] reports that once.
+    fn bound_interface(
+        &mut self,
+        bound: &Type,
+        bindings: &GenericBindings,
+        position: &TextSpan,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Option<String> {
+        let bound = Self::monomorphize_type(bound, bindings);
+        let (base, args) = Self::resolve_struct_parts(&bound)?;
+        if !self.is_interface_name(&base) {
+            return None;
+        }
+        if args.is_empty() {
+            return Some(base);
+        }
+        self.ensure_interface_instantiated(&base, &args, position, diagnostics);
+        Some(mangle_generic(&base, &args))
+    }
+
+    /// Checks the type arguments in `bindings` against `bounds`, reporting each unsatisfied bound
+    /// at `position`. A type argument satisfies an interface bound when it is that interface or a
+    /// class (or archetype) implementing it.
+    fn generic_bounds_hold(
+        &mut self,
+        owner: &str,
+        bounds: &[GenericBound],
+        bindings: &GenericBindings,
+        position: &TextSpan,
+        diagnostics: &mut DiagnosticBag,
+    ) -> bool {
+        let mut hold = true;
+        for bound in bounds {
+            let Some(arg) = lookup_binding(bindings, &bound.parameter.text) else {
+                continue;
+            };
+            let arg_name = strip_nullable(&arg.get_type()).to_string();
+            if is_unknown_type_name(&arg_name) {
+                continue;
+            }
+            for required in &bound.bounds {
+                let Some(iface) = self.bound_interface(required, bindings, position, diagnostics) else {
+                    continue;
+                };
+                if arg_name != iface && !self.class_implements(&arg_name, &iface) {
+                    diagnostics.report_error(
+                        format!(
+                            "Type '{}' does not satisfy the bound '{}' on type parameter '{}' of '{}'",
+                            arg.display_name(),
+                            Self::monomorphize_type(required, bindings).display_name(),
+                            bound.parameter.text,
+                            owner
+                        ),
+                        Some(*position),
+                    );
+                    hold = false;
+                }
+            }
+        }
+        hold
+    }
+
+    /// Checks one instantiation (`mangled`) of generic declaration `owner` against its bounds.
+    /// Instantiations made while types are still being registered are checked later, once every
+    /// `implements` clause is known. An instance that violates a bound is recorded so its body is
+    /// not analyzed into a second, deeper error.
+    pub(super) fn check_instance_bounds(
+        &mut self,
+        owner: &str,
+        bounds: &'a [GenericBound],
+        bindings: &GenericBindings,
+        mangled: &str,
+        position: &TextSpan,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        if bounds.is_empty() {
+            return;
+        }
+        if !self.bounds_ready {
+            self.deferred_bound_checks.push((
+                owner.to_string(),
+                bounds,
+                bindings.clone(),
+                mangled.to_string(),
+                *position,
+                diagnostics.file_path.clone(),
+            ));
+            return;
+        }
+        if !self.generic_bounds_hold(owner, bounds, bindings, position, diagnostics) {
+            self.unsatisfied_instances.insert(mangled.to_string());
+        }
+    }
+
+    /// Reports bounds that name no type parameter of `owner` or are not interfaces.
+    fn validate_generic_bounds(
+        &self,
+        owner: &str,
+        params: &Option<Vec<SyntaxToken>>,
+        bounds: &[GenericBound],
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        let params = params.as_deref().unwrap_or(&[]);
+        for bound in bounds {
+            if !params.iter().any(|p| p.text == bound.parameter.text) {
+                diagnostics.report_error(
+                    format!("'{}' is not a type parameter of '{}'", bound.parameter.text, owner),
+                    Some(bound.parameter.position),
+                );
+            }
+            for required in &bound.bounds {
+                let base = Self::resolve_struct_parts(required).map(|(base, _)| base);
+                if !base.is_some_and(|b| self.is_interface_name(&b)) {
+                    diagnostics.report_error(
+                        format!(
+                            "Bound '{}' on type parameter '{}' is not an interface",
+                            required.display_name(),
+                            bound.parameter.text
+                        ),
+                        Some(required.get_span().unwrap_or(bound.parameter.position)),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Binds each type parameter of `owner` to its archetype: a synthetic interface offering the
+    /// methods of every bound on that parameter (an unbounded parameter offers none) and
+    /// convertible to each bound. Bounds may mention the parameters themselves
+    /// (`T: Comparable<T>`), so every archetype is registered before any bound is resolved.
+    fn archetype_bindings(
+        &mut self,
+        owner: &str,
+        params: &[SyntaxToken],
+        bounds: &[GenericBound],
+        diagnostics: &mut DiagnosticBag,
+    ) -> GenericBindings {
+        let bindings: GenericBindings = params
+            .iter()
+            .map(|param| {
+                let name = archetype_name(owner, &param.text);
+                self.type_ctx.register(DefKind::Interface, &name, Vec::new());
+                (param.text.clone(), Type::Struct(synthetic_token(TokenKind::IdentifierToken, &name), None))
+            })
+            .collect();
+        for param in params {
+            let mut interfaces = Vec::new();
+            let mut methods: Vec<&'a FunctionNode<'a>> = Vec::new();
+            for bound in bounds.iter().filter(|b| b.parameter.text == param.text) {
+                for required in &bound.bounds {
+                    let Some(iface) = self.bound_interface(required, &bindings, &param.position, diagnostics) else {
+                        continue;
+                    };
+                    for method in self.interface_methods.get(&iface).cloned().unwrap_or_default() {
+                        if !methods.iter().any(|m| m.name.text == method.name.text) {
+                            methods.push(method);
+                        }
+                    }
+                    interfaces.push(iface);
+                }
+            }
+            let name = archetype_name(owner, &param.text);
+            self.interface_methods.insert(name.clone(), methods);
+            self.archetypes.insert(name, interfaces);
+        }
+        bindings
+    }
+
+    /// Pass: validates every declared bound, settles the bound checks deferred during registration,
+    /// then type-checks each bounded generic function and class once, with every type parameter
+    /// bound to its archetype. A body that only uses what its bounds promise checks cleanly for
+    /// every type argument satisfying them; one that does not is reported here, once, and its
+    /// concrete instantiations are analyzed silently. Archetype instances produce no HIR.
+    pub(super) fn check_bounded_generics(
+        &mut self,
+        node: &'a ProgramNode<'a>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        for function in node.functions.iter() {
+            diagnostics.file_path = file_path_string(&function.file_path);
+            let owner = &function.name.text;
+            self.validate_generic_bounds(owner, &function.generic_parameters, &function.generic_bounds, diagnostics);
+        }
+        for class in node.structs.iter() {
+            diagnostics.file_path = file_path_string(&class.file_path);
+            let owner = &class.name.text;
+            self.validate_generic_bounds(owner, &class.generic_parameters, &class.generic_bounds, diagnostics);
+            for method in class.methods.iter() {
+                self.validate_generic_bounds(&method.name.text, &method.generic_parameters, &method.generic_bounds, diagnostics);
+            }
+        }
+        for iface in node.interfaces.iter() {
+            diagnostics.file_path = file_path_string(&iface.file_path);
+            self.validate_generic_bounds(&iface.name.text, &iface.generic_parameters, &iface.generic_bounds, diagnostics);
+        }
+
+        self.bounds_ready = true;
+        for (owner, bounds, bindings, mangled, position, file) in std::mem::take(&mut self.deferred_bound_checks) {
+            diagnostics.file_path = file;
+            if !self.generic_bounds_hold(&owner, bounds, &bindings, &position, diagnostics) {
+                self.unsatisfied_instances.insert(mangled);
+            }
+        }
+
+        for function in node.functions.iter() {
+            if function.generic_bounds.is_empty() || function.is_extern {
+                continue;
+            }
+            diagnostics.file_path = file_path_string(&function.file_path);
+            let params = function.generic_parameters.as_deref().unwrap_or(&[]);
+            let bindings = self.archetype_bindings(&function.name.text, params, &function.generic_bounds, diagnostics);
+            let mut checked = function.clone();
+            Self::substitute_generic_signature(&mut checked, &bindings);
+            let checked: &'a FunctionNode<'a> = self.arena.alloc(checked);
+            let errors = diagnostics.errors().count();
+            self.with_generic_bindings(bindings, |s| s.analyze_function(checked, diagnostics))?;
+            if diagnostics.errors().count() > errors && !function.body.is_empty() {
+                self.failed_templates.insert(function.body.as_ptr() as usize);
+            }
+        }
+
+        for class in node.structs.iter() {
+            if class.generic_bounds.is_empty() {
+                continue;
+            }
+            diagnostics.file_path = file_path_string(&class.file_path);
+            let params = class.generic_parameters.as_deref().unwrap_or(&[]);
+            let bindings = self.archetype_bindings(&class.name.text, params, &class.generic_bounds, diagnostics);
+            let args: Vec<Type> = bindings.values().cloned().collect();
+            // Analyze the archetype instance's methods here rather than in the instantiation fixpoint,
+            // so a failing method is known before any concrete instance of it is analyzed.
+            let start = self.struct_methods.len();
+            self.ensure_struct_instantiated(&class.name.text, &args, &class.name.position, diagnostics);
+            let methods: Vec<_> = self.struct_methods.drain(start..).collect();
+            for (method, method_bindings) in methods {
+                diagnostics.file_path = file_path_string(&method.file_path);
+                let errors = diagnostics.errors().count();
+                self.with_generic_bindings(method_bindings, |s| s.analyze_function(method, diagnostics))?;
+                let own = class.methods.iter().any(|m| std::ptr::eq(m.body, method.body));
+                if own && diagnostics.errors().count() > errors && !method.body.is_empty() {
+                    self.failed_templates.insert(method.body.as_ptr() as usize);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass: type-checks every interface default body once, with `this` bound to an archetype of
+    /// the interface (offering exactly its methods) and each interface type parameter bound to its
+    /// own archetype. A default body that reaches past the interface is reported here rather than
+    /// in every implementing class, whose copies are then analyzed silently. Default methods with
+    /// type parameters of their own are checked per instantiation instead.
+    pub(super) fn check_default_methods(
+        &mut self,
+        node: &'a ProgramNode<'a>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        for iface in node.interfaces.iter() {
+            let defaults: Vec<&'a FunctionNode<'a>> = iface
+                .methods
+                .iter()
+                .filter(|m| m.has_default_body && !m.is_static && m.generic_parameters.is_none())
+                .collect();
+            if defaults.is_empty() {
+                continue;
+            }
+            diagnostics.file_path = file_path_string(&iface.file_path);
+            // `this` is a keyword, so the receiver's parameter name can never collide with a type
+            // parameter or be spelled as a type in the body.
+            let receiver = synthetic_token(TokenKind::IdentifierToken, "this");
+            let own_params = iface.generic_parameters.as_deref().unwrap_or(&[]);
+            let own_type = Type::Struct(
+                iface.name.clone(),
+                iface.generic_parameters.as_ref().map(|params| {
+                    params.iter().map(|p| Type::Struct(p.clone(), None)).collect()
+                }),
+            );
+            let mut params = own_params.to_vec();
+            params.push(receiver.clone());
+            let mut bounds = iface.generic_bounds.clone();
+            bounds.push(GenericBound { parameter: receiver, bounds: vec![own_type] });
+            let bindings = self.archetype_bindings(&iface.name.text, &params, &bounds, diagnostics);
+            let receiver_type = archetype_name(&iface.name.text, "this");
+            for method in defaults {
+                let mut checked = method.clone();
+                Self::substitute_generic_signature(&mut checked, &bindings);
+                checked.parameters.insert(0, Self::make_this_param(&receiver_type));
+                let checked: &'a FunctionNode<'a> = self.arena.alloc(checked);
+                let errors = diagnostics.errors().count();
+                self.with_generic_bindings(bindings.clone(), |s| s.analyze_function(checked, diagnostics))?;
+                if diagnostics.errors().count() > errors && !method.body.is_empty() {
+                    self.failed_templates.insert(method.body.as_ptr() as usize);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// True when the concrete instance `mangled` of `template` should be analyzed without reporting:
+    /// its template failed its archetype check, or its type arguments violate a bound. Either way
+    /// the error was already reported once.
+    pub(super) fn instance_is_muted(&self, template: &FunctionNode<'a>, mangled: &str) -> bool {
+        self.failed_templates.contains(&(template.body.as_ptr() as usize))
+            || self.unsatisfied_instances.contains(mangled)
+    }
+
+    /// Records a call to the method or generic instance emitted as `name`, for
+    /// `report_unavailable_methods`.
+    pub(super) fn record_method_call(&mut self, name: &str, position: TextSpan, diagnostics: &DiagnosticBag) {
+        self.method_calls.push((
+            name.to_string(),
+            position,
+            diagnostics.file_path.clone(),
+            self.lenient_method.clone(),
+        ));
+    }
+
+    /// Reports every call to a method left out of its instance (see `unavailable_methods`). A
+    /// leniently analyzed method that calls one is left out too, so the error lands on a call the
+    /// program makes rather than inside the standard library.
+    pub(super) fn report_unavailable_methods(&mut self, diagnostics: &mut DiagnosticBag) {
+        let mut changed = !self.unavailable_methods.is_empty();
+        while changed {
+            changed = false;
+            for (callee, _, _, caller) in &self.method_calls {
+                let Some(caller) = caller else { continue };
+                if self.unavailable_methods.contains_key(caller) {
+                    continue;
+                }
+                if let Some(message) = self.unavailable_methods.get(callee).cloned() {
+                    self.unavailable_methods.insert(caller.clone(), message);
+                    changed = true;
+                }
+            }
+        }
+        for (callee, position, file, caller) in &self.method_calls {
+            if caller.is_some() {
+                continue;
+            }
+            if let Some(message) = self.unavailable_methods.get(callee) {
+                diagnostics.file_path = file.clone();
+                diagnostics.report_error(message.clone(), Some(*position));
+            }
+        }
+    }
 }
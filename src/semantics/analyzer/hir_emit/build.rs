@@ -50,16 +50,30 @@ impl<'a> Analyzer<'a> {
         // for lowering without aliasing the struct/union-table borrows.
         // Discriminated unions are also registered in the struct table (for tagging/release), but they
         // get a variant-aware layout + `to_string` from the union table below — so exclude them here to
-        // avoid a duplicate (empty) struct layout and a duplicate `$<Union>_to_string`.
-        let struct_snapshot: Vec<(String, Vec<(String, Type)>)> = self
+        // avoid a duplicate (empty) struct layout and a duplicate `$<Union>_to_string`. Archetype
+        // instances (see `check_bounded_generics`) are never emitted.
+        // A subclass snapshots only its own fields; the inherited prefix is copied from its base's
+        // layout, which is already built since bases are registered (and so listed) first.
+        type StructSnap = (String, Option<String>, Vec<(String, Type)>);
+        let struct_snapshot: Vec<StructSnap> = self
             .struct_table
             .structs
             .iter()
             .filter(|(name, _)| !self.union_table.contains_key(name.as_str()))
+            .filter(|(name, _)| !is_archetype_derived(name))
             .map(|(name, info)| {
-                let fields =
-                    info.fields.iter().map(|(fname, f)| (fname.clone(), f.type_.clone())).collect();
-                (name.clone(), fields)
+                let inherited = info
+                    .base
+                    .as_ref()
+                    .and_then(|b| self.struct_table.get_struct(b))
+                    .map_or(0, |b| b.fields.len());
+                let fields = info
+                    .fields
+                    .iter()
+                    .skip(inherited)
+                    .map(|(fname, f)| (fname.clone(), f.type_.clone()))
+                    .collect();
+                (name.clone(), info.base.clone(), fields)
             })
             .collect();
         // (union name, block size, [(variant name, discriminant, [(field name, offset, field type)])]).
@@ -67,6 +81,7 @@ impl<'a> Analyzer<'a> {
         let union_snapshot: Vec<(String, u32, Vec<VariantSnap>)> = self
             .union_table
             .iter()
+            .filter(|(name, _)| !is_archetype_derived(name))
             .map(|(name, info)| {
                 let variants = info
                     .variants
@@ -85,14 +100,26 @@ impl<'a> Analyzer<'a> {
             .collect();
 
         let mut layouts = LayoutTable::default();
-        for (name, fields) in struct_snapshot {
+        for (name, base, fields) in struct_snapshot {
             // Key by the struct's interned type id (`lower_str` canonicalizes both plain names and
             // mangled generic instances like `Box_int` to `struct_ty(def, args)`), so each
             // monomorphization gets its own layout.
             let ty = self.type_ctx.lower_str(&name);
             let defs: Vec<(String, TypeId)> =
                 fields.iter().map(|(fname, t)| (fname.clone(), self.type_ctx.lower(t))).collect();
-            layouts.insert(ty, TypeLayout::from_fields(&self.type_ctx.interner, name, defs));
+            let base_ty = base.map(|b| self.type_ctx.lower_str(&b));
+            let layout = match base_ty.and_then(|b| layouts.get(b).map(|l| (b, l))) {
+                Some((b, base_layout)) => {
+                    TypeLayout::extending(&self.type_ctx.interner, name, b, base_layout, defs)
+                }
+                None => TypeLayout::from_fields(&self.type_ctx.interner, name, defs),
+            };
+            layouts.insert(ty, layout);
+        }
+        // Closure environments `[__fn][captures...]` are plain heap structs to the backend: they get
+        // a type tag and a field-releasing `$release_*` like any class.
+        for (ty, name, fields) in std::mem::take(&mut self.hir.closure_envs) {
+            layouts.insert(ty, TypeLayout::from_fields(&self.type_ctx.interner, name, fields));
         }
         for (name, size, variants) in union_snapshot {
             let ty = self.type_ctx.lower_str(&name);
@@ -202,4 +229,20 @@ impl<'a> Analyzer<'a> {
         }
         out
     }
+
+    /// Collects the `@test` functions' defs in declaration order, the order `dream test` runs them
+    /// in. Tests take no parameters, so each resolves under its plain emitted name.
+    pub(in crate::semantics::analyzer) fn hir_build_tests(
+        &self,
+        node: &crate::syntax::nodes::ProgramNode,
+    ) -> Vec<crate::types::DefId> {
+        node.functions
+            .iter()
+            .filter(|func| crate::semantics::analyzer::is_test(func))
+            .filter_map(|func| {
+                let name = self.function_table.resolve_emitted_name(&func.name.text, &[]);
+                self.type_ctx.defs.lookup(DefKind::Function, &name)
+            })
+            .collect()
+    }
 }
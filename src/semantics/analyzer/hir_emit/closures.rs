@@ -0,0 +1,289 @@
+//! Closure emission. A lambda is emitted as its own [`HFunction`] (`__lambda_N`) whose hidden first
+//! parameter is its environment: a heap block laid out as the struct `__Closure_N` =
+//! `[__fn: table index][captured values...]`. The lambda body is analyzed *nested* inside the
+//! enclosing function, so the enclosing emission state is saved as a [`HirFrame`] for the duration
+//! and restored afterward. A name the lambda reads that belongs to an enclosing frame becomes a
+//! capture: a local of the lambda, initialized from its env field in the prologue, and fed at the
+//! creation site from the enclosing local (threaded through every intermediate lambda).
+
+use super::*;
+
+/// The emission state of one enclosing function, saved while a nested lambda is emitted.
+#[derive(Default)]
+pub(super) struct HirFrame {
+    collecting: bool,
+    ok: bool,
+    locals: IndexMap<String, (LocalId, TypeId)>,
+    next_local: u32,
+    local_decls: Vec<HLocal>,
+    params: Vec<HParam>,
+    blocks: Vec<Vec<HStmt>>,
+    def: Option<DefId>,
+    name: String,
+    instance: Vec<TypeId>,
+    ret: Option<TypeId>,
+    is_async: bool,
+    source: Option<SourceInfo>,
+    env: Option<TypeId>,
+    captures: Vec<HirCapture>,
+}
+
+impl HirFrame {
+    /// Adds a capture of `name` (the enclosing frame's `outer` local) to this lambda frame,
+    /// returning the lambda-side local that holds it.
+    fn capture(&mut self, name: &str, ty: TypeId, outer: LocalId) -> LocalId {
+        let inner = LocalId(self.next_local);
+        self.next_local += 1;
+        self.locals.insert(name.to_string(), (inner, ty));
+        self.local_decls.push(HLocal { id: inner, name: name.to_string(), ty });
+        self.captures.push(HirCapture { name: name.to_string(), inner, ty, outer });
+        inner
+    }
+}
+
+/// A closure environment layout to register: `(env type, struct name, [(field name, field type)])`.
+pub(super) type ClosureEnv = (TypeId, String, Vec<(String, TypeId)>);
+
+/// One variable captured by a lambda: the lambda's own local for it (`inner`) and the enclosing
+/// function's local it is copied from when the closure is created (`outer`).
+pub(super) struct HirCapture {
+    name: String,
+    inner: LocalId,
+    ty: TypeId,
+    outer: LocalId,
+}
+
+impl<'a> Analyzer<'a> {
+    fn hir_save_frame(&mut self) -> HirFrame {
+        let h = &mut self.hir;
+        HirFrame {
+            collecting: h.collecting,
+            ok: h.ok,
+            locals: std::mem::take(&mut h.locals),
+            next_local: h.next_local,
+            local_decls: std::mem::take(&mut h.local_decls),
+            params: std::mem::take(&mut h.params),
+            blocks: std::mem::take(&mut h.blocks),
+            def: h.def.take(),
+            name: std::mem::take(&mut h.name),
+            instance: std::mem::take(&mut h.instance),
+            ret: h.ret.take(),
+            is_async: h.is_async,
+            source: h.source.take(),
+            env: h.env.take(),
+            captures: std::mem::take(&mut h.captures),
+        }
+    }
+
+    fn hir_restore_frame(&mut self, frame: HirFrame) {
+        let h = &mut self.hir;
+        h.collecting = frame.collecting;
+        h.ok = frame.ok;
+        h.locals = frame.locals;
+        h.next_local = frame.next_local;
+        h.local_decls = frame.local_decls;
+        h.params = frame.params;
+        h.blocks = frame.blocks;
+        h.def = frame.def;
+        h.name = frame.name;
+        h.instance = frame.instance;
+        h.ret = frame.ret;
+        h.is_async = frame.is_async;
+        h.source = frame.source;
+        h.env = frame.env;
+        h.captures = frame.captures;
+    }
+
+    /// Starts emitting a lambda with the given (already typed) parameters, saving the enclosing
+    /// function's state. The lambda is collected only if its enclosing function is: a lambda in a
+    /// skipped function is analyzed for types alone. `line` is where the lambda is written; a trace
+    /// names it after its enclosing function. Paired with [`Self::hir_lambda_finish`].
+    pub(in crate::semantics::analyzer) fn hir_lambda_begin(
+        &mut self,
+        params: &[(String, Type)],
+        line: usize,
+    ) {
+        let outer_active = self.active();
+        let frame = self.hir_save_frame();
+        self.hir.source = frame.source.as_ref().map(|outer| SourceInfo {
+            name: format!("{}.<lambda>", outer.name),
+            file: outer.file.clone(),
+            line: line as u32,
+        });
+        self.hir.frames.push(frame);
+        self.hir.collecting = outer_active;
+        self.hir.ok = true;
+        self.hir.last = None;
+        self.hir.next_local = 0;
+        self.hir.is_async = false;
+        self.hir.blocks.push(Vec::new());
+        if !outer_active {
+            return;
+        }
+        let n = self.hir.next_closure;
+        self.hir.next_closure += 1;
+        let name = format!("__lambda_{}", n);
+        let def = self.type_ctx.defs.intern(DefKind::Function, &name, vec![]);
+        let env_def = self.type_ctx.defs.intern(DefKind::Struct, &format!("__Closure_{}", n), vec![]);
+        let env = self.type_ctx.interner.struct_ty(env_def, vec![]);
+        self.hir.def = Some(def);
+        self.hir.name = name;
+        self.hir.env = Some(env);
+        self.hir_add_param(crate::hir::CLOSURE_ENV_PARAM, env);
+        for (pname, pty) in params {
+            let ty = self.type_ctx.lower(pty);
+            self.hir_add_param(pname, ty);
+        }
+    }
+
+    fn hir_add_param(&mut self, name: &str, ty: TypeId) {
+        let local = LocalId(self.hir.next_local);
+        self.hir.next_local += 1;
+        self.hir.locals.insert(name.to_string(), (local, ty));
+        self.hir.params.push(HParam { local, name: name.to_string(), ty });
+    }
+
+    /// Finishes the current lambda: records its [`HFunction`] (captures are loaded from the env in a
+    /// prologue) and its env layout, restores the enclosing function, and leaves the closure value
+    /// `[fn][captures...]` of type `func_ty` in `last`. An unrepresentable lambda makes the enclosing
+    /// function unrepresentable too.
+    pub(in crate::semantics::analyzer) fn hir_lambda_finish(&mut self, func_ty: &Type, ret: &Type) {
+        let mut closure = None;
+        if self.hir.collecting && self.hir.ok && self.hir.blocks.len() == 1 {
+            if let (Some(def), Some(env)) = (self.hir.def, self.hir.env) {
+                let ret_ty = self.type_ctx.lower(ret);
+                let env_local = HExpr::new(env, HExprKind::Var(Binding::Local(LocalId(0))));
+                let mut body: Vec<HStmt> = self
+                    .hir
+                    .captures
+                    .iter()
+                    .enumerate()
+                    .map(|(k, c)| HStmt::Let {
+                        local: c.inner,
+                        ty: c.ty,
+                        value: HExpr::new(
+                            c.ty,
+                            HExprKind::Field { obj: Box::new(env_local.clone()), field: k + 1 },
+                        ),
+                    })
+                    .collect();
+                body.extend(self.hir.blocks.pop().unwrap_or_default());
+                let mut fields = vec![("__fn".to_string(), self.type_ctx.interner.int())];
+                fields.extend(self.hir.captures.iter().map(|c| (c.name.clone(), c.ty)));
+                let env_name = format!("__Closure_{}", self.hir.name.trim_start_matches("__lambda_"));
+                self.hir.closure_envs.push((env, env_name, fields));
+                self.hir.functions.push(HFunction {
+                    def,
+                    name: std::mem::take(&mut self.hir.name),
+                    instance: vec![],
+                    params: std::mem::take(&mut self.hir.params),
+                    ret: ret_ty,
+                    locals: std::mem::take(&mut self.hir.local_decls),
+                    body,
+                    is_async: false,
+                    source: self.hir.source.take(),
+                });
+                let outer: Vec<(LocalId, TypeId)> =
+                    self.hir.captures.iter().map(|c| (c.outer, c.ty)).collect();
+                closure = Some((Callee { def, instance: vec![], ret: ret_ty }, env, outer));
+            }
+        }
+        let frame = self.hir.frames.pop().unwrap_or_default();
+        self.hir_restore_frame(frame);
+        match closure {
+            Some((func, env, outer)) => {
+                let ty = self.type_ctx.lower(func_ty);
+                let captures = outer
+                    .into_iter()
+                    .map(|(local, ty)| HExpr::new(ty, HExprKind::Var(Binding::Local(local))))
+                    .collect();
+                self.hir.last = Some(HExpr::new(ty, HExprKind::Closure { func, env, captures }));
+            }
+            None => {
+                self.hir.last = None;
+                self.hir_fail();
+            }
+        }
+    }
+
+    /// Resolves `name` to a variable binding: a local of the current function, a variable captured
+    /// from an enclosing function (when inside a lambda), or a module global.
+    pub(in crate::semantics::analyzer) fn hir_resolve_binding(&mut self, name: &str) -> Option<(Binding, TypeId)> {
+        if let Some(&(local, ty)) = self.hir.locals.get(name) {
+            return Some((Binding::Local(local), ty));
+        }
+        if let Some((local, ty)) = self.hir_capture(name) {
+            return Some((Binding::Local(local), ty));
+        }
+        self.hir.globals.get(name).map(|&(global, ty)| (Binding::Global(global), ty))
+    }
+
+    /// Captures `name` from the innermost enclosing frame that declares it, threading the capture
+    /// through every lambda frame in between, and returns the current lambda's local for it.
+    fn hir_capture(&mut self, name: &str) -> Option<(LocalId, TypeId)> {
+        self.hir.env?;
+        let k = self.hir.frames.iter().rposition(|f| f.locals.contains_key(name))?;
+        let (mut outer, ty) = self.hir.frames[k].locals[name];
+        for frame in self.hir.frames[k + 1..].iter_mut() {
+            outer = frame.capture(name, ty, outer);
+        }
+        let inner = LocalId(self.hir.next_local);
+        self.hir.next_local += 1;
+        self.hir.locals.insert(name.to_string(), (inner, ty));
+        self.hir.local_decls.push(HLocal { id: inner, name: name.to_string(), ty });
+        self.hir.captures.push(HirCapture { name: name.to_string(), inner, ty, outer });
+        Some((inner, ty))
+    }
+
+    /// The shared env type of capture-free closures over a named function (`__FnRef`, holding only
+    /// the function's table index), registering its layout on first use.
+    pub(super) fn hir_fnref_env(&mut self) -> TypeId {
+        let def = self.type_ctx.defs.intern(DefKind::Struct, "__FnRef", vec![]);
+        let env = self.type_ctx.interner.struct_ty(def, vec![]);
+        if !self.hir.closure_envs.iter().any(|(ty, _, _)| *ty == env) {
+            let fields = vec![("__fn".to_string(), self.type_ctx.interner.int())];
+            self.hir.closure_envs.push((env, "__FnRef".to_string(), fields));
+        }
+        env
+    }
+
+    /// The thunk `__fnref_<name>(__env, params...)` that forwards to the function `target`, giving a
+    /// plain function the closure calling convention. Emitted once per function.
+    pub(super) fn hir_fnref_thunk(&mut self, name: &str, target: DefId, params: &[TypeId], ret: TypeId) -> DefId {
+        if let Some(&def) = self.hir.thunks.get(name) {
+            return def;
+        }
+        let thunk_name = format!("__fnref_{}", name);
+        let def = self.type_ctx.defs.intern(DefKind::Function, &thunk_name, vec![]);
+        let env = self.hir_fnref_env();
+        let mut hparams = vec![HParam { local: LocalId(0), name: crate::hir::CLOSURE_ENV_PARAM.to_string(), ty: env }];
+        let mut args = Vec::with_capacity(params.len());
+        for (i, &ty) in params.iter().enumerate() {
+            let local = LocalId(i as u32 + 1);
+            hparams.push(HParam { local, name: format!("p{}", i), ty });
+            args.push(HExpr::new(ty, HExprKind::Var(Binding::Local(local))));
+        }
+        let call = HExpr::new(
+            ret,
+            HExprKind::Call { callee: Callee { def: target, instance: vec![], ret }, args },
+        );
+        let body = if matches!(self.type_ctx.interner.kind(ret), TyKind::Void) {
+            vec![HStmt::Expr(call), HStmt::Return(None)]
+        } else {
+            vec![HStmt::Return(Some(call))]
+        };
+        self.hir.functions.push(HFunction {
+            def,
+            name: thunk_name,
+            instance: vec![],
+            params: hparams,
+            ret,
+            locals: vec![],
+            body,
+            is_async: false,
+            source: None,
+        });
+        self.hir.thunks.insert(name.to_string(), def);
+        def
+    }
+}
@@ -38,20 +38,16 @@ impl<'a> Analyzer<'a> {
         self.hir.last = kind.map(|k| HExpr::new(ty, k));
     }
 
-    /// Records the HIR for an identifier read: a local-variable reference if the name resolves to a
-    /// slot, otherwise `None` (globals and function values are later slices).
+    /// Records the HIR for an identifier read: a local, captured, or global variable reference if the
+    /// name resolves (see [`Self::hir_resolve_binding`]), otherwise `None`.
     pub(in crate::semantics::analyzer) fn hir_set_var(&mut self, name: &str) {
         if !self.active() {
             self.hir.last = None;
             return;
         }
-        if let Some(&(local, ty)) = self.hir.locals.get(name) {
-            self.hir.last = Some(HExpr::new(ty, HExprKind::Var(Binding::Local(local))));
-        } else if let Some(&(global, ty)) = self.hir.globals.get(name) {
-            self.hir.last = Some(HExpr::new(ty, HExprKind::Var(Binding::Global(global))));
-        } else {
-            self.hir.last = None;
-        }
+        self.hir.last = self
+            .hir_resolve_binding(name)
+            .map(|(binding, ty)| HExpr::new(ty, HExprKind::Var(binding)));
     }
 
     /// Records the HIR for a binary expression from its already-collected operands.
@@ -167,6 +163,68 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// Records the HIR for a class equality `lhs == rhs` that may involve `null` (see
+    /// `analyze_equality_call`): each operand is bound once to its hidden local, in order, and
+    /// `call` (the `op_eq` call on those locals) only runs when both are non-null. Two nulls are
+    /// equal; a null and a value are not. `negate` wraps the result for `!=`.
+    pub(in crate::semantics::analyzer) fn hir_set_equality(
+        &mut self,
+        lhs: (Option<LocalId>, Option<HExpr>, bool),
+        rhs: (Option<LocalId>, Option<HExpr>, bool),
+        call: Option<HExpr>,
+        negate: bool,
+    ) {
+        if !self.active() {
+            self.hir.last = None;
+            return;
+        }
+        let ((Some(lhs_local), Some(lhs_value), lhs_nullable), (Some(rhs_local), Some(rhs_value), rhs_nullable), Some(call)) =
+            (lhs, rhs, call)
+        else {
+            self.hir.last = None;
+            return;
+        };
+        let bool_ty = self.type_ctx.interner.bool();
+        let is_null = |local: LocalId, ty| {
+            HExpr::new(
+                bool_ty,
+                HExprKind::Binary {
+                    op: BinOp::Eq,
+                    lhs: Box::new(HExpr::new(ty, HExprKind::Var(Binding::Local(local)))),
+                    rhs: Box::new(HExpr::new(ty, HExprKind::Null)),
+                },
+            )
+        };
+        let ternary = |cond, then_expr, else_expr| {
+            HExpr::new(
+                bool_ty,
+                HExprKind::Ternary {
+                    cond: Box::new(cond),
+                    then_expr: Box::new(then_expr),
+                    else_expr: Box::new(else_expr),
+                },
+            )
+        };
+        let no = HExpr::new(bool_ty, HExprKind::BoolLit(false));
+        // rhs null: equal only to a null lhs, which the outer test has already ruled out
+        let mut body = call;
+        if rhs_nullable {
+            body = ternary(is_null(rhs_local, rhs_value.ty), no.clone(), body);
+        }
+        if lhs_nullable {
+            let both_null = if rhs_nullable { is_null(rhs_local, rhs_value.ty) } else { no };
+            body = ternary(is_null(lhs_local, lhs_value.ty), both_null, body);
+        }
+        if negate {
+            body = HExpr::new(bool_ty, HExprKind::Unary { op: UnOp::Not, operand: Box::new(body) });
+        }
+        let bind = |local, value: HExpr, body| {
+            HExpr::new(bool_ty, HExprKind::Let { local, value: Box::new(value), body: Box::new(body) })
+        };
+        body = bind(rhs_local, rhs_value, body);
+        self.hir.last = Some(bind(lhs_local, lhs_value, body));
+    }
+
     /// Records the HIR for `array[index]` (read position).
     pub(in crate::semantics::analyzer) fn hir_set_index(
         &mut self,
@@ -278,35 +336,47 @@ impl<'a> Analyzer<'a> {
     }
 
     /// Records a first-class function value: a bare function name used as a value (e.g. `let f = foo;`
-    /// or passing `foo` to a `fun(...)` parameter) becomes a `Binding::Func` carrying the resolved def
-    /// and signature, typed as the function type so the backend can materialize its table index. Drops
-    /// coverage if the name is not a registered function def.
+    /// or passing `foo` to a `fun(...)` parameter) becomes a capture-free closure over the function's
+    /// forwarding thunk (see [`Self::hir_fnref_thunk`]), typed as the function type. Drops coverage if
+    /// the name is not a registered function def.
     pub(in crate::semantics::analyzer) fn hir_set_func_value(&mut self, name: &str, func_ty: &Type, ret: &Type) {
         if !self.active() {
             self.hir.last = None;
             return;
         }
-        let Some(def) = self.type_ctx.defs.lookup(DefKind::Function, name) else {
+        let Some(target) = self.type_ctx.defs.lookup(DefKind::Function, name) else {
             self.hir.last = None;
             return;
         };
+        let Type::Function(params, _) = func_ty else {
+            self.hir.last = None;
+            return;
+        };
+        let params: Vec<TypeId> = params.iter().map(|p| self.type_ctx.lower(p)).collect();
         let tid = self.type_ctx.lower(func_ty);
         let ret_ty = self.type_ctx.lower(ret);
+        let thunk = self.hir_fnref_thunk(name, target, &params, ret_ty);
+        let env = self.hir_fnref_env();
         self.hir.last = Some(HExpr::new(
             tid,
-            HExprKind::Var(Binding::Func(Callee { def, instance: vec![], ret: ret_ty })),
+            HExprKind::Closure {
+                func: Callee { def: thunk, instance: vec![], ret: ret_ty },
+                env,
+                captures: vec![],
+            },
         ));
     }
 
-    /// Records an indirect call `f(args)` where `f` is a function-typed local: the target reads the
-    /// local (whose value is a function-table index) and the call dispatches through it. Drops coverage
-    /// if the name is not a known local or any argument is not representable.
+    /// Records an indirect call `f(args)` where `f` is a function-typed variable (a local, a capture,
+    /// or a global): the target reads the variable (whose value is a closure pointer) and the call
+    /// dispatches through it. Drops coverage if the name does not resolve or any argument is not
+    /// representable.
     pub(in crate::semantics::analyzer) fn hir_set_indirect_call(&mut self, name: &str, args: Vec<Option<HExpr>>, ret: &Type) {
         if !self.active() {
             self.hir.last = None;
             return;
         }
-        let Some(&(local, ty)) = self.hir.locals.get(name) else {
+        let Some((binding, ty)) = self.hir_resolve_binding(name) else {
             self.hir.last = None;
             return;
         };
@@ -321,7 +391,7 @@ impl<'a> Analyzer<'a> {
             }
         }
         let ret_ty = self.type_ctx.lower(ret);
-        let target = HExpr::new(ty, HExprKind::Var(Binding::Local(local)));
+        let target = HExpr::new(ty, HExprKind::Var(binding));
         self.hir.last = Some(HExpr::new(
             ret_ty,
             HExprKind::IndirectCall { target: Box::new(target), args: collected },
@@ -406,7 +476,8 @@ impl<'a> Analyzer<'a> {
     /// — the registered `DefId` for both plain and generic structs — and `result_ty` supplies the
     /// per-instance layout key. `ctor`, when `Some`, is the resolved user `constructor(){}` def (its
     /// `args` are the constructor's arguments); when `None`, the implicit zero-arg default
-    /// constructor takes no args and every field is zero-initialized.
+    /// constructor takes no args and every field is zero-initialized, except for a tuple literal
+    /// (see `hir_set_tuple`), whose args fill the fields in order.
     /// Unresolved names or a non-representable argument drop the call out of coverage.
     pub(in crate::semantics::analyzer) fn hir_set_new(
         &mut self,
@@ -445,6 +516,26 @@ impl<'a> Analyzer<'a> {
         ));
     }
 
+    /// Records a tuple literal: a constructor-less `New` of the `Tuple#N` class (`name`) whose
+    /// `args` initialize the positional fields in order, each coerced to its element type.
+    pub(in crate::semantics::analyzer) fn hir_set_tuple(
+        &mut self,
+        name: &str,
+        elements: Vec<Option<HExpr>>,
+        element_types: &[Type],
+        tuple_ty: &Type,
+    ) {
+        let coerced = elements
+            .into_iter()
+            .zip(element_types)
+            .map(|(e, t)| {
+                let target = self.type_ctx.lower(t);
+                e.map(|e| self.coerce_to(e, target))
+            })
+            .collect();
+        self.hir_set_new(name, None, coerced, tuple_ty);
+    }
+
     /// Records a resolved instance method call `receiver.method(args)`. `mangled` is the registered
     /// `{Type}_{method}` name; if it does not resolve to a `DefId`, or the receiver/any argument is
     /// not representable, the call drops out of coverage.
@@ -814,6 +905,65 @@ impl<'a> Analyzer<'a> {
             None => self.hir.last = None,
         }
     }
+    /// Records `operand?` (see [`Analyzer::analyze_propagate`]): binds the operand to a fresh local
+    /// named `slot_name`, returns the failure variant of `return_ty` unless the operand holds the
+    /// success variant, and leaves the success payload as the value. `variants` is the union's
+    /// `(def, success, failure)`; `return_error` is the error type of a `Result` return, whose `Err`
+    /// is rebuilt around the operand's error.
+    pub(in crate::semantics::analyzer) fn hir_set_propagate(
+        &mut self,
+        slot_name: &str,
+        operand: Option<HExpr>,
+        operand_ty: &Type,
+        variants: (DefId, usize, usize),
+        return_error: Option<&Type>,
+        return_ty: &Type,
+    ) {
+        let Type::Struct(_, Some(args)) = operand_ty else {
+            self.hir.last = None;
+            return;
+        };
+        let (Some(operand), Some(slot)) = (operand, self.alloc_local(slot_name, operand_ty)) else {
+            self.hir_fail();
+            self.hir.last = None;
+            return;
+        };
+        let (def, success, failure) = variants;
+        let union_ty = self.type_ctx.lower(operand_ty);
+        self.push_stmt(HStmt::Let { local: slot, ty: union_ty, value: operand });
+
+        let interner = &self.type_ctx.interner;
+        let (int, bool_ty) = (interner.int(), interner.bool());
+        let read = || HExpr::new(union_ty, HExprKind::Var(Binding::Local(slot)));
+        let payload = |ty: TypeId, variant: usize| {
+            HExpr::new(ty, HExprKind::UnionField { base: Box::new(read()), union_ty, variant, field: 0 })
+        };
+        let failed = HExpr::new(
+            bool_ty,
+            HExprKind::Binary {
+                op: BinOp::Ne,
+                lhs: Box::new(HExpr::new(int, HExprKind::Discriminant(Box::new(read())))),
+                rhs: Box::new(HExpr::new(int, HExprKind::IntLit(success as i64))),
+            },
+        );
+        let mut fields = Vec::new();
+        if let (Some(error), Some(return_error)) = (args.get(1), return_error) {
+            let error = payload(self.type_ctx.lower(error), failure);
+            let target = self.type_ctx.lower(return_error);
+            fields.push(self.coerce_to(error, target));
+        }
+        let returned = HExpr::new(
+            self.type_ctx.lower(return_ty),
+            HExprKind::UnionNew { def, variant: failure, args: fields },
+        );
+        self.push_stmt(HStmt::If {
+            cond: failed,
+            then_branch: vec![HStmt::Return(Some(returned))],
+            else_branch: vec![],
+        });
+        self.hir.last = Some(payload(self.type_ctx.lower(&args[0]), success));
+    }
+
     /// Sets `last` to a read of an already-allocated local (used by the match-expression desugar to
     /// yield the result temporary as the match's value).
     pub(in crate::semantics::analyzer) fn hir_set_local_read(&mut self, local: LocalId, ty: TypeId) {
@@ -8,21 +8,24 @@
 //! anything unrepresentable flips [`HirEmit::ok`] to `false` and the function is skipped (it then has
 //! no backend output). The HIR is the only input the backend consumes.
 
-use super::Analyzer;
+use super::{is_archetype_derived, Analyzer};
 use crate::hir::{
-    BinOp, Binding, Callee, GlobalId, HArm, HExpr, HExprKind, HFunction, HGlobal, HImport, HLocal,
-    HParam, HPattern, HPlace, HStmt, LocalId, UnOp,
+    BinOp, Binding, Callee, GlobalId, HArm, HCatch, HExpr, HExprKind, HFunction, HGlobal, HImport, HLocal,
+    HParam, HPattern, HPlace, HStmt, LocalId, SourceInfo, UnOp,
 };
 use crate::syntax::nodes::{FunctionNode, Type};
 use crate::syntax::token::syntax_token::SyntaxToken;
 use crate::syntax::token::token_kind::TokenKind;
-use crate::types::{DefId, DefKind, PrimTy, TyKind, TypeId};
+use crate::types::{display_name, DefId, DefKind, PrimTy, TyKind, TypeId};
 use indexmap::IndexMap;
 
 mod build;
+mod closures;
 mod exprs;
 mod stmts;
 
+use closures::{ClosureEnv, HirCapture, HirFrame};
+
 /// Per-analysis HIR-emission state, plus the accumulated [`HFunction`]s. Reset at the start of each
 /// candidate function (see [`Analyzer::hir_begin_function`]).
 #[derive(Default)]
@@ -56,6 +59,8 @@ pub(super) struct HirEmit {
     instance: Vec<TypeId>,
     ret: Option<TypeId>,
     is_async: bool,
+    /// Where the current function is declared, recorded on its [`HFunction`] for stack traces.
+    source: Option<SourceInfo>,
     /// Name -> (slot, type) for module-level variables, populated once after globals are analyzed
     /// (see [`Analyzer::hir_register_globals`]). Read by identifier/assignment lowering so a name
     /// that is not a local resolves to a [`Binding::Global`].
@@ -64,6 +69,22 @@ pub(super) struct HirEmit {
     /// [`HGlobal`] in [`Analyzer::hir_register_globals`]. Populated while top-level variables are
     /// analyzed (see [`Analyzer::hir_global_init_begin`]).
     pending_global_inits: IndexMap<String, HExpr>,
+    /// The emission state of each enclosing function while a lambda body is being emitted
+    /// (innermost last); see [`Analyzer::hir_lambda_begin`]. Empty outside lambdas.
+    frames: Vec<HirFrame>,
+    /// The closure environment type of the lambda currently being emitted (its hidden `__env`
+    /// parameter is local 0). `None` in an ordinary function.
+    env: Option<TypeId>,
+    /// The variables the current lambda captures from enclosing functions, in env-field order.
+    captures: Vec<HirCapture>,
+    /// Monotonic counter naming each lambda's function (`__lambda_N`) and env struct (`__Closure_N`).
+    next_closure: u32,
+    /// Closure environment layouts `(env type, name, fields)`, added to the struct layouts by
+    /// [`Analyzer::hir_build_layouts`]. Field 0 is always the function's table index.
+    closure_envs: Vec<ClosureEnv>,
+    /// Function name -> `DefId` of the capture-free thunk that lets it be used as a function value
+    /// (see [`Analyzer::hir_set_func_value`]), so each function gets at most one.
+    thunks: IndexMap<String, DefId>,
     /// All successfully emitted functions, surfaced via `SemanticInfo::hir`.
     pub functions: Vec<HFunction>,
     /// The module-global declarations, surfaced via `SemanticInfo::hir`.
@@ -134,9 +155,10 @@ impl<'a> Analyzer<'a> {
 
         // A generic template is emitted once per monomorphization: the initial (unbound) pass is
         // skipped, and each concrete instantiation is analyzed again under `current_generic_bindings`
-        // (see `analyze_pending_instantiations`). Anything with no registered def is skipped.
+        // (see `analyze_pending_instantiations`). Anything with no registered def is skipped, as is
+        // an archetype instance, which exists only to check a bounded generic body.
         let under_mono = !self.current_generic_bindings.is_empty();
-        if def.is_none() || (is_generic && !under_mono) {
+        if def.is_none() || (is_generic && !under_mono) || self.under_archetype() {
             self.hir.collecting = false;
             return;
         }
@@ -162,10 +184,17 @@ impl<'a> Analyzer<'a> {
         self.hir.params.clear();
         self.hir.blocks.clear();
         self.hir.blocks.push(Vec::new());
+        self.hir.env = None;
+        self.hir.captures.clear();
         self.hir.def = def;
         self.hir.instance = instance;
         self.hir.name = lookup_name;
         self.hir.is_async = function.is_async;
+        self.hir.source = function.file_path.as_ref().map(|file| SourceInfo {
+            name: self.source_name(function),
+            file: file.to_string(),
+            line: function.name.position.line_no as u32,
+        });
         self.hir.ret = Some(
             function
                 .return_type
@@ -189,6 +218,19 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// The name a stack trace shows for `function`. Methods are analyzed under their mangled name
+    /// and shown as `Type.method`, with generic instances spelled as in source (`Box<int>.get`).
+    pub(in crate::semantics::analyzer) fn source_name(&mut self, function: &FunctionNode<'a>) -> String {
+        match self.method_names.get(&function.name.text).cloned() {
+            Some((target, method)) => {
+                let ty = self.type_ctx.lower_str(&target);
+                let target = display_name(&self.type_ctx.interner, &self.type_ctx.defs, ty);
+                format!("{}.{}", target, method)
+            }
+            None => function.name.text.clone(),
+        }
+    }
+
     /// Finishes the current function: if it was a fully-supported candidate, builds and records its
     /// [`HFunction`]. Always turns collection back off.
     pub(in crate::semantics::analyzer) fn hir_finish_function(&mut self) {
@@ -206,6 +248,7 @@ impl<'a> Analyzer<'a> {
                     locals: std::mem::take(&mut self.hir.local_decls),
                     body,
                     is_async: self.hir.is_async,
+                    source: self.hir.source.take(),
                 });
             }
         }
@@ -243,6 +286,20 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// Marks the statements emitted next as coming from source `line` (see [`HStmt::Line`]). A
+    /// marker directly after another replaces it, so statements that emit nothing leave no trail.
+    pub(in crate::semantics::analyzer) fn hir_line(&mut self, line: usize) {
+        if !self.active() {
+            return;
+        }
+        if let Some(block) = self.hir.blocks.last_mut() {
+            match block.last_mut() {
+                Some(HStmt::Line(prev)) => *prev = line as u32,
+                _ => block.push(HStmt::Line(line as u32)),
+            }
+        }
+    }
+
     /// Appends a fully-built statement to the current block (used by callers that assemble their own
     /// `HStmt`, e.g. the `if`/`else if` chain folder). Gated on the active flag like [`Self::push_stmt`].
     pub(in crate::semantics::analyzer) fn hir_push_stmt(&mut self, stmt: HStmt) {
@@ -96,7 +96,7 @@ impl<'a> Analyzer<'a> {
     /// Inserts an implicit boxing cast when a primitive `value` is stored into an `object`-typed
     /// slot (`let o: object = 42`), so the backend boxes it rather than storing a raw scalar. All
     /// other conversions (reference→object, numeric widening) are left to the backend / call sites.
-    fn coerce_to(&self, value: HExpr, target: TypeId) -> HExpr {
+    pub(super) fn coerce_to(&self, value: HExpr, target: TypeId) -> HExpr {
         use crate::types::{PrimTy, TyKind};
         let interner = &self.type_ctx.interner;
         let target_k = interner.kind(interner.strip_nullable(target));
@@ -129,6 +129,18 @@ impl<'a> Analyzer<'a> {
         value
     }
 
+    /// Boxes each primitive argument passed to an `object` parameter, as [`Self::coerce_to`] does for
+    /// `let o: object = 42`. `params` are the callee's declared parameter type names, aligned with
+    /// `args`; a call through an unrepresentable argument is left as-is (it drops out of coverage).
+    pub(in crate::semantics::analyzer) fn hir_box_object_args(&self, params: &[String], args: &mut [Option<HExpr>]) {
+        let object = self.type_ctx.interner.object();
+        for (param, arg) in params.iter().zip(args.iter_mut()) {
+            if crate::syntax::nodes::types::strip_nullable(param) == "object" {
+                *arg = arg.take().map(|e| self.coerce_to(e, object));
+            }
+        }
+    }
+
     /// Appends an assignment to a local or module-global. Fails the function for an unresolved name
     /// or a non-representable value.
     pub(in crate::semantics::analyzer) fn hir_assign_local(&mut self, name: &str, value: Option<HExpr>) {
@@ -268,6 +280,54 @@ impl<'a> Analyzer<'a> {
         self.push_stmt(HStmt::Continue(label));
     }
 
+    /// Appends `throw value;`, boxing a primitive value into `object`. Fails the function if the
+    /// value was not representable.
+    pub(in crate::semantics::analyzer) fn hir_throw(&mut self, value: Option<HExpr>) {
+        if !self.active() {
+            return;
+        }
+        match value {
+            Some(value) => {
+                let object = self.type_ctx.interner.object();
+                let value = self.coerce_to(value, object);
+                self.push_stmt(HStmt::Throw(value))
+            }
+            None => self.hir.ok = false,
+        }
+    }
+
+    /// Appends a `try` statement. `exception` is the slot allocated for the caught value (see
+    /// [`Self::hir_alloc_local`]); each catch is its target type (`None` for an untyped clause) and
+    /// its body, which already begins with the clause's binding.
+    pub(in crate::semantics::analyzer) fn hir_try(
+        &mut self,
+        body: Vec<HStmt>,
+        exception: Option<LocalId>,
+        catches: Vec<(Option<Type>, Vec<HStmt>)>,
+        finally: Vec<HStmt>,
+    ) {
+        if !self.active() {
+            return;
+        }
+        let Some(exception) = exception else {
+            self.hir.ok = false;
+            return;
+        };
+        let catches = catches
+            .into_iter()
+            .map(|(ty, body)| HCatch {
+                ty: ty.map(|t| self.type_ctx.lower(&t)),
+                body,
+            })
+            .collect();
+        self.push_stmt(HStmt::Try {
+            body,
+            exception,
+            catches,
+            finally,
+        });
+    }
+
     /// Appends a `switch`/statement-`match` lowered to [`HStmt::Switch`]. `arms` are the already-built
     /// pattern/body pairs and `default` the fallthrough block. `ok` is the caller's verdict on
     /// whether every arm was representable (e.g. no multi-label case, scrutinee present); a `false`
@@ -6,7 +6,7 @@ use crate::semantics::symbol_table::SymbolTable;
 use crate::semantics::union_table::UnionTable;
 use crate::syntax::nodes::types::{mangle_with_suffixes, primitive_type, FUTURE_TYPE};
 use crate::syntax::nodes::{EnumDeclarationNode, ExtendNode};
-use crate::syntax::nodes::{FunctionNode, ProgramNode, Type};
+use crate::syntax::nodes::{FunctionNode, GenericBound, ProgramNode, Type};
 use crate::syntax::syntax_tree::SyntaxTree;
 use crate::types::{DefKind, TypeCtx};
 use crate::text::line_text::LineText;
@@ -16,14 +16,18 @@ use crate::syntax::token::token_kind::TokenKind;
 use bumpalo::Bump;
 use indexmap::IndexMap;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 mod await_rules;
 mod calls;
+mod closures;
+mod coverage;
 mod declarations;
+mod exceptions;
 mod expressions;
 mod generics;
+use generics::is_archetype_derived;
 mod hir_emit;
 mod statements;
 mod switch_unions;
@@ -35,6 +39,14 @@ fn file_path_string(file_path: &Option<Rc<str>>) -> Option<String> {
     file_path.as_ref().map(|p| p.to_string())
 }
 
+/// The attribute marking a free function as a test that `dream test` runs, e.g. `@test fun adds()`.
+const TEST_ATTR: &str = "test";
+
+/// True if `function` is marked `@test`.
+fn is_test(function: &FunctionNode) -> bool {
+    function.attributes.iter().any(|a| a.name.text == TEST_ATTR)
+}
+
 /// Reports `message` at `span` into the bag and returns the matching typed [`SemanticError`], so a
 /// failing analysis site can `return Err(report(diagnostics, msg, span))` in a single step. The
 /// pushed diagnostic is what the user sees; the returned error drives `?`-based short-circuiting of
@@ -174,12 +186,31 @@ pub(super) fn accessor_member_name(method: &FunctionNode) -> String {
     }
 }
 
+/// The receiver of base-class calls in a subclass: `super.method()` and `super(args)`.
+pub(super) const SUPER: &str = "super";
+
+/// True when `function`'s body begins with a `super(...)` base-constructor call.
+pub(super) fn begins_with_super_call(function: &FunctionNode) -> bool {
+    matches!(
+        function.body.first(),
+        Some(crate::syntax::nodes::StatementNode::FunctionInvocation(name, ..)) if name.text == SUPER
+    )
+}
+
 /// Maps each generic parameter name to the concrete `Type` bound to it for one monomorphization.
 /// Insertion-ordered so the mangled instance symbol (built from the values in order) is
 /// deterministic. Stores the structured AST `Type` (not a stringified name), so the monomorphizer
 /// substitutes and lowers it directly rather than round-tripping through `get_type()`/reparse.
 pub type GenericBindings = IndexMap<String, Type>;
 
+/// A bound check postponed until every declaration is registered (see `deferred_bound_checks`).
+type DeferredBoundCheck<'a> =
+    (String, &'a [GenericBound], GenericBindings, String, TextSpan, Option<String>);
+
+/// A method call recorded for `report_unavailable_methods`: the callee's emitted name, the
+/// call site and its file, and the leniently analyzed method making the call (if any).
+type MethodCall = (String, TextSpan, Option<String>, Option<String>);
+
 /// Enum name -> (member name -> integer value). Insertion-ordered at both levels so the enum
 /// variant-name interning that feeds emitted output happens in a deterministic (declaration) order.
 pub type EnumTable = IndexMap<String, IndexMap<String, i32>>;
@@ -230,6 +261,9 @@ pub struct Analyzer<'a> {
     generic_structs:
         HashMap<String, &'a crate::syntax::nodes::struct_node::StructDeclarationNode<'a>>,
     struct_methods: Vec<(&'a FunctionNode<'a>, GenericBindings)>,
+    /// Mangled method name (`Box_int_get`) -> its target type and source method name, from which
+    /// stack traces name it `Box<int>.get`.
+    method_names: HashMap<String, (String, String)>,
     /// Registered enums: name -> (member -> value). Enum values are plain `i32`s at runtime.
     enum_table: EnumTable,
     /// Layout of every registered (monomorphized) discriminated union.
@@ -251,6 +285,47 @@ pub struct Analyzer<'a> {
     /// implements clause is validated. Names are mangled for generic instances (e.g. `Box_int` ->
     /// `Container_int`). Drives interface-typed assignability and itable emission.
     implements: HashMap<String, Vec<String>>,
+    /// Subclass name -> its base class (`class Dog : Animal` records `Dog -> Animal`). Drives
+    /// upcast assignability, hierarchy-aware `is`/casts and `super` resolution.
+    base_classes: IndexMap<String, String>,
+    /// Class name -> the instance methods registered on it (own, inherited and interface
+    /// defaults), as the original declarations with their generic bindings. A subclass copies the
+    /// entries it does not redefine.
+    class_methods: HashMap<String, Vec<(&'a FunctionNode<'a>, GenericBindings)>>,
+    /// Class name -> its virtual method slots, inherited slots first, for every class with at least
+    /// one. Vtables take the leading dispatch ids (ahead of every interface), in this order.
+    vtables: IndexMap<String, Vec<&'a FunctionNode<'a>>>,
+    /// Set by `super.m()` so the next instance-method resolution calls the base implementation
+    /// directly instead of dispatching through the vtable.
+    dispatch_statically: bool,
+    /// Archetype name (`max::T`) -> the interfaces bounding that type parameter. An archetype is the
+    /// synthetic interface a bounded generic body is checked against (see `check_bounded_generics`);
+    /// it offers exactly its bounds' methods and converts to each bound.
+    archetypes: HashMap<String, Vec<String>>,
+    /// Bodies (keyed by their statement slice address, shared by every clone of a template) whose
+    /// archetype check failed. Their concrete instantiations are analyzed without reporting, so an
+    /// error in a bounded generic body is reported once rather than once per instantiation.
+    failed_templates: HashSet<usize>,
+    /// Mangled generic function and class instances whose type arguments violate a bound. The
+    /// violation is reported at the instantiation site; the instance body is analyzed silently.
+    unsatisfied_instances: HashSet<String>,
+    /// Class and interface instantiations made while types are still being registered, whose bound
+    /// checks wait for every `implements` clause to be validated: (owner, bounds, bindings, mangled
+    /// instance, site, file).
+    deferred_bound_checks: Vec<DeferredBoundCheck<'a>>,
+    /// Set once every declaration is registered; bound checks from then on run immediately.
+    bounds_ready: bool,
+    /// Emitted name -> the error a call to it reports, for the methods of standard-library generic
+    /// class instances whose bodies do not type-check with the instance's type arguments (`sort` on
+    /// a `List` of an unordered type). Such a method is left out of the instance, and only a call to
+    /// it is an error.
+    unavailable_methods: HashMap<String, String>,
+    /// Every method and generic-method call analyzed so far, checked against `unavailable_methods`
+    /// once all instances are analyzed.
+    method_calls: Vec<MethodCall>,
+    /// The emitted name of the standard-library instance method being analyzed leniently (see
+    /// `unavailable_methods`), if any.
+    lenient_method: Option<String>,
     /// An optional expected type for the expression currently being analyzed (from a `let`
     /// annotation or `return` type). Used to resolve the type arguments of a generic union's
     /// nullary variant (`let o: Option<int> = Option.None;`), where they cannot be inferred from
@@ -266,8 +341,17 @@ pub struct Analyzer<'a> {
     /// Label attached to the immediately-following loop (`outer: for ...`), consumed by that loop's
     /// analyzer so it can be threaded into the loop's HIR node. `None` for unlabeled loops.
     pending_loop_label: Option<String>,
+    /// The functions enclosing the lambdas currently being analyzed, outermost first. A lambda body
+    /// is analyzed against a synthesized function node, so member-privacy checks consult the
+    /// outermost real function instead (a lambda inside a method may use that type's private members).
+    closure_hosts: Vec<FunctionNode<'a>>,
     /// True while analyzing the body of an `async fun`. Gates the use of `await`.
     current_function_is_async: bool,
+    /// Where a `?` cannot be used, named for its diagnostic: set while analyzing code that runs
+    /// conditionally or repeatedly within its statement (a loop condition, an `else if` condition,
+    /// a ternary branch, the right operand of `&&`/`||`/`??`) or a `finally` body. A `?` hoists its
+    /// early return in front of the enclosing statement, which would be wrong in such a context.
+    propagate_barrier: Option<&'static str>,
     /// Resolved top-level variables, in declaration order. Surfaced to codegen via [`SemanticInfo`].
     globals: Vec<GlobalSymbol>,
     /// The module-level symbol scope holding every top-level variable. It is the root parent of
@@ -292,6 +376,7 @@ impl<'a> Analyzer<'a> {
             instantiated_generics: IndexMap::new(),
             generic_structs: HashMap::new(),
             struct_methods: Vec::new(),
+            method_names: HashMap::new(),
             enum_table: IndexMap::new(),
             union_table: IndexMap::new(),
             generic_unions: HashMap::new(),
@@ -299,11 +384,25 @@ impl<'a> Analyzer<'a> {
             interface_methods: IndexMap::new(),
             generic_interfaces: HashMap::new(),
             implements: HashMap::new(),
+            base_classes: IndexMap::new(),
+            class_methods: HashMap::new(),
+            vtables: IndexMap::new(),
+            dispatch_statically: false,
+            archetypes: HashMap::new(),
+            failed_templates: HashSet::new(),
+            unsatisfied_instances: HashSet::new(),
+            deferred_bound_checks: Vec::new(),
+            bounds_ready: false,
+            unavailable_methods: HashMap::new(),
+            method_calls: Vec::new(),
+            lenient_method: None,
             current_expected_type: None,
             current_generic_bindings: GenericBindings::new(),
             loop_labels: Vec::new(),
             pending_loop_label: None,
+            closure_hosts: Vec::new(),
             current_function_is_async: false,
+            propagate_barrier: None,
             globals: Vec::new(),
             global_symbol_table: Rc::new(RefCell::new(SymbolTable::new(None))),
             type_ctx: TypeCtx::new(),
@@ -370,9 +469,23 @@ impl<'a> Analyzer<'a> {
         result
     }
 
+    /// Runs `f` with `propagate_barrier` set to `barrier`, restoring the previous value afterward.
+    pub(super) fn with_propagate_barrier<F, R>(&mut self, barrier: Option<&'static str>, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let saved = std::mem::replace(&mut self.propagate_barrier, barrier);
+        let result = f(self);
+        self.propagate_barrier = saved;
+        result
+    }
+
     /// Builds a concrete `Type` from a type name, used when substituting a generic
     /// parameter `T` with the concrete type chosen at the call/instantiation site.
     fn concrete_type_from_str(name: &str) -> Type {
+        if name.starts_with("fun(") {
+            return Self::type_from_name(name);
+        }
         let token = synthetic_token(TokenKind::DataTypeToken, name);
         primitive_type(name, token.clone()).unwrap_or(Type::Struct(token, None))
     }
@@ -415,6 +528,7 @@ impl<'a> Analyzer<'a> {
         // union/struct field may instantiate a generic union during `register_enums`), so the
         // extension methods are always available to attach at the first instantiation.
         self.stash_generic_extensions(node);
+        self.register_tuple_templates();
         self.register_enums(node, diagnostics);
         // Interfaces are registered before structs so a class's implements clause can be validated
         // against the interface method signatures during struct registration.
@@ -427,8 +541,11 @@ impl<'a> Analyzer<'a> {
         // HIR global slots are assigned incrementally inside `register_globals` (in declaration
         // order) so both later initializers and function bodies can resolve global identifiers.
         self.register_globals(node, diagnostics);
+        self.check_bounded_generics(node, diagnostics)?;
+        self.check_default_methods(node, diagnostics)?;
         self.analyze_function_bodies(node, &mut symbol_table_map, diagnostics)?;
         self.analyze_pending_instantiations(&mut symbol_table_map, diagnostics)?;
+        self.report_unavailable_methods(diagnostics);
 
         // Per-statement/expression analysis recovers locally (reporting into the bag and poisoning
         // with `Type::Unknown`) so every independent error in the program is surfaced. The typed
@@ -443,6 +560,7 @@ impl<'a> Analyzer<'a> {
         let layouts = self.hir_build_layouts();
         let imports = self.hir_build_imports(node);
         let intrinsics = self.hir_build_intrinsics(node);
+        let tests = self.hir_build_tests(node);
         let interfaces = self.hir_build_interfaces();
         let hir_functions = std::mem::take(&mut self.hir.functions);
         let hir_globals = std::mem::take(&mut self.hir.global_decls);
@@ -464,6 +582,7 @@ impl<'a> Analyzer<'a> {
                 imports,
                 intrinsics,
                 interfaces,
+                tests,
             },
         })
     }
@@ -7,9 +7,9 @@ use crate::hir::{HExpr, HStmt};
 use crate::intrinsics;
 use crate::semantics::errors::SemanticError;
 use crate::semantics::symbol_table::SymbolTable;
-use crate::syntax::nodes::types::{mangle_generic, strip_nullable};
+use crate::syntax::nodes::types::{is_tuple_type_name, mangle_generic, strip_nullable};
 use crate::types::method_fn;
-use crate::syntax::nodes::{ExpressionNode, FunctionNode, StatementNode, Type};
+use crate::syntax::nodes::{ExpressionNode, FunctionNode, PatternNode, StatementNode, Type};
 use crate::syntax::token::syntax_token::SyntaxToken;
 use crate::syntax::token::token_kind::TokenKind;
 use std::cell::RefCell;
@@ -262,7 +262,9 @@ impl<'a> Analyzer<'a> {
     ) -> Result<(), SemanticError> {
         let label = self.pending_loop_label.take();
         let cond_type = self
-            .analyze_expression(condition, parent_function, symbol_table, diagnostics)
+            .with_propagate_barrier(Some("a loop condition"), |s| {
+                s.analyze_expression(condition, parent_function, symbol_table, diagnostics)
+            })
             .unwrap_or(Type::Unknown);
         let cond_hir = self.hir_take();
         if !cond_type.is_unknown() && !cond_type.is_bool() {
@@ -287,7 +289,9 @@ impl<'a> Analyzer<'a> {
     ) -> Result<(), SemanticError> {
         let label = self.pending_loop_label.take();
         let cond_type = self
-            .analyze_expression(condition, parent_function, symbol_table, diagnostics)
+            .with_propagate_barrier(Some("a loop condition"), |s| {
+                s.analyze_expression(condition, parent_function, symbol_table, diagnostics)
+            })
             .unwrap_or(Type::Unknown);
         let cond_hir = self.hir_take();
         if !cond_type.is_unknown() && !cond_type.is_bool() {
@@ -334,7 +338,9 @@ impl<'a> Analyzer<'a> {
         let mut cond_hir = None;
         if let Some(cond_expr) = condition {
             let cond_type = self
-                .analyze_expression(cond_expr, ctx.parent_function, &for_scope, diagnostics)
+                .with_propagate_barrier(Some("a loop condition"), |s| {
+                    s.analyze_expression(cond_expr, ctx.parent_function, &for_scope, diagnostics)
+                })
                 .unwrap_or(Type::Unknown);
             cond_hir = self.hir_take();
             if !cond_type.is_unknown() && !cond_type.is_bool() {
@@ -463,6 +469,100 @@ impl<'a> Analyzer<'a> {
         }
         Ok(())
     }
+    /// `let (a, b) = expr;` (and the element binding of `for (let (k, v) in pairs)`): evaluates
+    /// `expr` once into a hidden local, then declares each binding of the tuple pattern as a read
+    /// of its element. The pattern must be irrefutable, so only bindings, `_` and nested tuple
+    /// patterns are allowed; literals and variants belong in a `switch`.
+    pub(super) fn analyze_destructuring_declaration(
+        &mut self,
+        pattern: &PatternNode,
+        right: &ExpressionNode<'a>,
+        is_const: bool,
+        ctx: &super::AnalyzerContext<'a, '_>,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Result<(), SemanticError> {
+        let mut bindings = Vec::new();
+        Self::collect_pattern_bindings(pattern, &mut bindings);
+        for name in &bindings {
+            self.check_reserved_name(name, "variable", diagnostics);
+        }
+
+        let right_type = self
+            .analyze_expression(right, ctx.parent_function, ctx.symbol_table, diagnostics)
+            .unwrap_or(Type::Unknown);
+        let value = self.hir_take();
+
+        let errors_before = diagnostics.errors().count();
+        let info = self.check_pattern(
+            pattern,
+            &right_type,
+            ctx.symbol_table,
+            ctx.parent_function,
+            diagnostics,
+        )?;
+        if !info.irrefutable && diagnostics.errors().count() == errors_before {
+            diagnostics.report_error(
+                "A destructuring 'let' must always match; use a 'switch' to test literals or variants"
+                    .to_string(),
+                pattern.position(),
+            );
+        }
+        if is_const {
+            for name in &bindings {
+                (*ctx.symbol_table)
+                    .as_ref()
+                    .borrow_mut()
+                    .mark_const(name.text.clone());
+            }
+        }
+
+        let Some(local) = self.hir_alloc_local("__destructure", &right_type) else {
+            return Ok(());
+        };
+        let ty = self.type_ctx.lower(&right_type);
+        match value {
+            Some(value) => self.hir_push_stmt(HStmt::Let { local, ty, value }),
+            None => {
+                self.hir_fail();
+                return Ok(());
+            }
+        }
+        let read = self.hx_local(local, ty);
+        match self.compile_pattern(&read, &right_type, pattern) {
+            Some((_, binds)) => {
+                for (name, ty, expr) in binds {
+                    self.hir_declare_local(&name, &ty, Some(expr));
+                }
+            }
+            None => self.hir_fail(),
+        }
+        Ok(())
+    }
+
+    /// The names a pattern binds, in source order.
+    fn collect_pattern_bindings<'p>(pattern: &'p PatternNode, out: &mut Vec<&'p SyntaxToken>) {
+        match pattern {
+            PatternNode::Binding(name) => out.push(name),
+            PatternNode::TypeTest(_, name) if name.text != "_" => out.push(name),
+            PatternNode::Variant(_, _, subs)
+            | PatternNode::Tuple(_, subs)
+            | PatternNode::Or(subs) => {
+                for sub in subs {
+                    Self::collect_pattern_bindings(sub, out);
+                }
+            }
+            PatternNode::Class(_, fields) => {
+                for (_, sub) in fields {
+                    Self::collect_pattern_bindings(sub, out);
+                }
+            }
+            PatternNode::Wildcard(_)
+            | PatternNode::Literal(_)
+            | PatternNode::Range(..)
+            | PatternNode::TypeTest(..) => {}
+        }
+    }
+
     pub(super) fn analyze_assignment(
         &mut self,
         left: &SyntaxToken,
@@ -480,6 +580,20 @@ impl<'a> Analyzer<'a> {
                 Some(left.position),
             );
         }
+        // Captures are copied into the closure when it is created, so an assignment inside the
+        // lambda would silently update only its private copy.
+        if (*symbol_table).as_ref().borrow().is_captured(&left.text) {
+            self.hir_fail();
+            diagnostics.report_error(
+                format!(
+                    "Cannot assign to '{}' because it is captured by a lambda; captured variables are read-only, \
+                     since the lambda holds a snapshot of '{}' taken when it was created. To share a changing value, \
+                     keep it in a field of an object both sides reference",
+                    left.text, left.text
+                ),
+                Some(left.position),
+            );
+        }
         let r = self
             .analyze_expression(right, parent_function, symbol_table, diagnostics)
             .unwrap_or(Type::Unknown);
@@ -565,11 +679,12 @@ impl<'a> Analyzer<'a> {
         Ok(())
     }
 
-    /// Desugars a class index-assignment `obj[index] = value` to `obj.set(index, value)` when
-    /// `obj_type` exposes an eligible `set` (accessible instance, non-async method taking two
-    /// arguments; its return value is discarded). A same-named `set` that is static/async/wrong
-    /// arity is left as an ordinary method and this site reports why the value is not
-    /// index-assignable.
+    /// Desugars a class index-assignment `obj[index] = value` to `obj.op_index_set(index, value)`,
+    /// or to `obj.set(index, value)` when there is no `op_index_set`. The method must be an
+    /// accessible instance, non-async method taking two arguments; its return value is discarded.
+    /// A same-named method that is static/async/wrong arity is left as an ordinary method and this
+    /// site reports why the value is not index-assignable.
+    #[allow(clippy::too_many_arguments)]
     fn analyze_index_set(
         &mut self,
         arr: &'a ExpressionNode<'a>,
@@ -580,9 +695,14 @@ impl<'a> Analyzer<'a> {
         symbol_table: &Rc<RefCell<SymbolTable>>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<(), SemanticError> {
-        match self.resolve_hook_method(obj_type, "set", 2, diagnostics) {
+        // `op_index_set` pairs with `op_index` and takes precedence over `set`.
+        let hook = match self.resolve_hook_method(obj_type, "op_index_set", 2, diagnostics) {
+            super::calls::HookResolution::Absent => "set",
+            _ => "op_index_set",
+        };
+        match self.resolve_hook_method(obj_type, hook, 2, diagnostics) {
             super::calls::HookResolution::Eligible(_) => {
-                let set_tok = synthetic_token(TokenKind::IdentifierToken, "set");
+                let set_tok = synthetic_token(TokenKind::IdentifierToken, hook);
                 let call = ExpressionNode::MethodCall(
                     arr,
                     set_tok,
@@ -680,6 +800,20 @@ impl<'a> Analyzer<'a> {
             }
         };
 
+        // Tuples are values: build a new one instead of updating an element in place.
+        if is_tuple_type_name(&base_name) {
+            self.hir_fail();
+            diagnostics.report_error(
+                format!(
+                    "Cannot assign to element {} of '{}': tuple elements are read-only",
+                    member.text,
+                    obj_type.display_name()
+                ),
+                Some(member.position),
+            );
+            return Ok(());
+        }
+
         self.ensure_struct_instantiated(&base_name, &generic_args, &member.position, diagnostics);
         let struct_name = mangle_generic(&base_name, &generic_args);
 
@@ -776,7 +910,7 @@ impl<'a> Analyzer<'a> {
         let branches = std::iter::once((condition, condition.position(), if_body))
             .chain(else_if.iter().map(|i| (&i.0, i.0.position(), &i.1)));
 
-        for (cond_expr, cond_pos, body) in branches {
+        for (i, (cond_expr, cond_pos, body)) in branches.enumerate() {
             // An `is`-with-binding condition (`if (x is T name)`) declares a narrowed local `name: T`
             // scoped to the taken branch only. Capture the binding parts so both the compile-time fold
             // and the runtime path can introduce it into that branch's scope.
@@ -796,11 +930,16 @@ impl<'a> Analyzer<'a> {
                     .analyze_expression(left, ctx.parent_function, ctx.symbol_table, diagnostics)
                     .unwrap_or(Type::Unknown);
                 let left_name = strip_nullable(&left_t.get_type()).to_string();
+                let right_name = right_type.get_type();
                 let runtime = left_t.is_object()
                     || left_t.is_unknown()
-                    || self.is_interface_name(&left_name);
+                    || self.is_interface_name(&left_name)
+                    || self.is_subclass_of(strip_nullable(&right_name), &left_name);
                 if !runtime {
-                    if left_t.get_type() == right_type.get_type() {
+                    // A non-null class operand is an instance of each of its base classes.
+                    let upcast = !left_t.get_type().ends_with('?')
+                        && self.is_subclass_of(&left_name, strip_nullable(&right_name));
+                    if left_t.get_type() == right_name || upcast {
                         let branch_scope = self.branch_scope(ctx.symbol_table);
                         self.hir_open_block();
                         self.declare_is_binding(&is_binding, &branch_scope, ctx, diagnostics)?;
@@ -821,8 +960,13 @@ impl<'a> Analyzer<'a> {
                 }
             }
 
+            // Anything a condition hoists lands in front of the whole chain, which is only right for
+            // the first condition.
+            let barrier = if i == 0 { self.propagate_barrier } else { Some("an 'else if' condition") };
             let cond_type = self
-                .analyze_expression(cond_expr, ctx.parent_function, ctx.symbol_table, diagnostics)
+                .with_propagate_barrier(barrier, |s| {
+                    s.analyze_expression(cond_expr, ctx.parent_function, ctx.symbol_table, diagnostics)
+                })
                 .unwrap_or(Type::Unknown);
             let cond_hir = self.hir_take();
             if !cond_type.is_unknown() && !cond_type.is_bool() {
@@ -894,7 +1038,7 @@ impl<'a> Analyzer<'a> {
     /// cast path means reference/interface targets alias the same pointer (identity) while value-type
     /// targets (`int`, `bool`, …) unbox the operand — exactly the narrowing `is` implies. A no-op when
     /// the condition has no binding. Must be called inside the branch's open HIR block, before its body.
-    fn declare_is_binding(
+    pub(super) fn declare_is_binding(
         &mut self,
         binding: &Option<(&SyntaxToken, &Type, &ExpressionNode<'a>)>,
         branch_scope: &Rc<RefCell<SymbolTable>>,
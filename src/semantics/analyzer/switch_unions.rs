@@ -2,12 +2,15 @@
 //! of pattern-matching `switch` expressions/statements: pattern typing, binding scopes, guards,
 //! arm-type unification, exhaustiveness, and unreachable-arm detection.
 
+use super::coverage::{self, CtorKind, Family, IntDomain, Member, Space};
 use super::*;
 use crate::diagnostics::DiagnosticBag;
 use crate::semantics::errors::SemanticError;
 use crate::semantics::symbol_table::SymbolTable;
 use crate::semantics::union_table::UnionInfo;
-use crate::syntax::nodes::types::strip_nullable;
+use crate::syntax::nodes::types::{
+    is_tuple_type_name, strip_nullable, tuple_type_name, UNKNOWN_TYPE_NAME,
+};
 use crate::syntax::nodes::{
     ExpressionNode, FunctionNode, PatternNode, StatementNode, SwitchArm, SwitchArmBody, Type,
 };
@@ -35,11 +38,35 @@ enum HirArmShape {
 
 /// What checking a single pattern told us, used to drive exhaustiveness and unreachable-arm
 /// analysis.
-struct PatternInfo {
-    /// True when the pattern matches every value of its type (a bare binding or `_`).
-    irrefutable: bool,
-    /// The union variant fully covered by this pattern (all sub-patterns irrefutable), if any.
-    covered_variant: Option<String>,
+pub(super) struct PatternInfo {
+    /// True when the pattern matches every value of its type (a bare binding, `_`, or a tuple of
+    /// irrefutable patterns).
+    pub(super) irrefutable: bool,
+    /// The values the pattern matches, for coverage analysis.
+    space: Space,
+}
+
+impl PatternInfo {
+    /// A pattern that matches everything.
+    fn catch_all() -> Self {
+        PatternInfo {
+            irrefutable: true,
+            space: Space::Wild,
+        }
+    }
+
+    fn refutable(space: Space) -> Self {
+        PatternInfo {
+            irrefutable: false,
+            space,
+        }
+    }
+
+    /// A pattern that failed to check. It covers nothing, so it cannot make a sibling pattern
+    /// look unreachable.
+    fn poisoned() -> Self {
+        PatternInfo::refutable(Space::Or(vec![]))
+    }
 }
 
 impl<'a> Analyzer<'a> {
@@ -151,7 +178,9 @@ impl<'a> Analyzer<'a> {
         }
 
         // Generic union: resolve the concrete type arguments, preferring an explicit expected type
-        // (e.g. a `let`/`return` annotation) and otherwise inferring from the arguments.
+        // (e.g. a `let`/`return` annotation) and otherwise inferring from the arguments. An
+        // expected type only partly known (`Option<U>` in a lambda passed to `and_then` before `U`
+        // is inferred) fixes the arguments it knows, and the payload fixes the rest.
         let template = *self.generic_unions.get(enum_name).unwrap();
         let params: Vec<String> = template
             .generic_parameters
@@ -159,28 +188,33 @@ impl<'a> Analyzer<'a> {
             .map(|ps| ps.iter().map(|p| p.text.clone()).collect())
             .unwrap_or_default();
 
-        let mut concrete_args: Option<Vec<Type>> = None;
-        if let Some(Type::Struct(b, Some(eargs))) = &self.current_expected_type {
-            if b.text == enum_name && eargs.len() == params.len() {
-                concrete_args = Some(eargs.clone());
-            }
-        }
-        if concrete_args.is_none() {
-            let mut binding: HashMap<String, Type> = HashMap::new();
-            for (ft, at) in field_types.iter().zip(arg_types.iter()) {
-                let name = ft.get_type();
-                if params.contains(&name) {
-                    binding.entry(name).or_insert_with(|| at.clone());
-                }
+        let expected_args = match &self.current_expected_type {
+            Some(Type::Struct(b, Some(eargs)))
+                if b.text == enum_name && eargs.len() == params.len() =>
+            {
+                eargs.clone()
             }
-            let resolved: Vec<Type> = params
-                .iter()
-                .filter_map(|p| binding.get(p).cloned())
-                .collect();
-            if resolved.len() == params.len() {
-                concrete_args = Some(resolved);
+            _ => vec![Type::Unknown; params.len()],
+        };
+        let mut binding: HashMap<String, Type> = HashMap::new();
+        for (ft, at) in field_types.iter().zip(arg_types.iter()) {
+            let name = ft.get_type();
+            if params.contains(&name) {
+                binding.entry(name).or_insert_with(|| at.clone());
             }
         }
+        let resolved: Vec<Type> = params
+            .iter()
+            .zip(expected_args)
+            .filter_map(|(p, expected)| {
+                if expected.get_type().contains(UNKNOWN_TYPE_NAME) {
+                    binding.get(p).cloned()
+                } else {
+                    Some(expected)
+                }
+            })
+            .collect();
+        let concrete_args = (resolved.len() == params.len()).then_some(resolved);
 
         let concrete_args = match concrete_args {
             Some(a) => a,
@@ -317,6 +351,12 @@ impl<'a> Analyzer<'a> {
                     bindings,
                 }
             }
+            // These always take the if-chain lowering (see `pattern_is_nested`).
+            PatternNode::Tuple(..)
+            | PatternNode::Range(..)
+            | PatternNode::Or(..)
+            | PatternNode::TypeTest(..)
+            | PatternNode::Class(..) => HirArmShape::Unsupported,
         }
     }
 
@@ -326,10 +366,21 @@ impl<'a> Analyzer<'a> {
         arms.iter().any(|a| a.guard.is_some() || Self::pattern_is_nested(&a.pattern))
     }
 
-    /// True for a variant pattern with at least one sub-pattern that isn't a flat binding/wildcard.
+    /// True for a variant pattern with at least one sub-pattern that isn't a flat binding/wildcard,
+    /// and for every pattern a `Switch` has no single constant or discriminant to dispatch on:
+    /// tuples, ranges, or-patterns, and type and class patterns.
     fn pattern_is_nested(p: &PatternNode) -> bool {
-        matches!(p, PatternNode::Variant(_, _, subs)
-            if subs.iter().any(|s| !matches!(s, PatternNode::Binding(_) | PatternNode::Wildcard(_))))
+        match p {
+            PatternNode::Variant(_, _, subs) => subs
+                .iter()
+                .any(|s| !matches!(s, PatternNode::Binding(_) | PatternNode::Wildcard(_))),
+            PatternNode::Tuple(..)
+            | PatternNode::Range(..)
+            | PatternNode::Or(..)
+            | PatternNode::TypeTest(..)
+            | PatternNode::Class(..) => true,
+            PatternNode::Wildcard(_) | PatternNode::Binding(_) | PatternNode::Literal(_) => false,
+        }
     }
 
     // -- small typed-HExpr builders used by the if-chain switch lowering --
@@ -339,7 +390,7 @@ impl<'a> Analyzer<'a> {
     fn hx_int(&self, v: i64) -> crate::hir::HExpr {
         crate::hir::HExpr::new(self.type_ctx.interner.int(), crate::hir::HExprKind::IntLit(v))
     }
-    fn hx_local(&self, local: crate::hir::LocalId, ty: crate::types::TypeId) -> crate::hir::HExpr {
+    pub(super) fn hx_local(&self, local: crate::hir::LocalId, ty: crate::types::TypeId) -> crate::hir::HExpr {
         crate::hir::HExpr::new(ty, crate::hir::HExprKind::Var(crate::hir::Binding::Local(local)))
     }
     fn hx_disc(&self, v: crate::hir::HExpr) -> crate::hir::HExpr {
@@ -395,7 +446,7 @@ impl<'a> Analyzer<'a> {
         // 1. `iterator()`: an eligible 0-arg instance method returning an enumerator object.
         let iterator_info = match self.resolve_hook_method(iterable_type, "iterator", 0, diagnostics)
         {
-            HookResolution::Eligible(info) => info,
+            HookResolution::Eligible(info) => *info,
             HookResolution::Ineligible(reason) => {
                 self.hir_fail();
                 diagnostics.report_error(
@@ -437,7 +488,7 @@ impl<'a> Analyzer<'a> {
 
         // 2. `next()` on the enumerator: an eligible 0-arg instance method returning `Option<T>`.
         let next_info = match self.resolve_hook_method(&enumerator_type, "next", 0, diagnostics) {
-            HookResolution::Eligible(info) => info,
+            HookResolution::Eligible(info) => *info,
             HookResolution::Ineligible(reason) => {
                 self.hir_fail();
                 diagnostics.report_error(
@@ -586,7 +637,7 @@ impl<'a> Analyzer<'a> {
     /// representable (so the caller drops the function). All field reads are inlined into the returned
     /// expressions, so the conditions/bindings are self-contained (no reliance on prior bindings).
     #[allow(clippy::type_complexity)]
-    fn compile_pattern(
+    pub(super) fn compile_pattern(
         &mut self,
         value: &crate::hir::HExpr,
         value_type: &Type,
@@ -650,9 +701,119 @@ impl<'a> Analyzer<'a> {
                 }
                 Some((conds, binds))
             }
+            PatternNode::Tuple(_, subs) => {
+                // `check_pattern` only accepts a non-nullable tuple of the same arity, so each
+                // element is a plain field read of the value.
+                let elements = value_type.tuple_elements()?.to_vec();
+                if subs.len() != elements.len() {
+                    return None;
+                }
+                let mut conds = Vec::new();
+                let mut binds = Vec::new();
+                for (i, (sub, ety)) in subs.iter().zip(&elements).enumerate() {
+                    let field = self.struct_field_index(&base, &i.to_string())?;
+                    let field_expr = HExpr::new(
+                        self.type_ctx.lower(ety),
+                        HExprKind::Field {
+                            obj: Box::new(value.clone()),
+                            field,
+                        },
+                    );
+                    let (mut c, mut b) = self.compile_pattern(&field_expr, ety, sub)?;
+                    conds.append(&mut c);
+                    binds.append(&mut b);
+                }
+                Some((conds, binds))
+            }
+            PatternNode::Range(lo, hi) => {
+                self.hir_set_literal(lo);
+                let lo = self.hir_take()?;
+                self.hir_set_literal(hi);
+                let hi = self.hir_take()?;
+                let conds = vec![
+                    self.hx_bin(BinOp::Ge, value.clone(), lo),
+                    self.hx_bin(BinOp::Le, value.clone(), hi),
+                ];
+                Some((conds, vec![]))
+            }
+            PatternNode::Or(alternatives) => {
+                // `check_pattern` rejects bindings in alternatives, so only the tests matter.
+                let mut any: Option<HExpr> = None;
+                for alternative in alternatives {
+                    let (conds, _) = self.compile_pattern(value, value_type, alternative)?;
+                    let all = conds
+                        .into_iter()
+                        .reduce(|a, b| self.hx_bin(BinOp::And, a, b))
+                        .unwrap_or_else(|| self.hx_bool(true));
+                    any = Some(match any {
+                        Some(prev) => self.hx_bin(BinOp::Or, prev, all),
+                        None => all,
+                    });
+                }
+                Some((any.into_iter().collect(), vec![]))
+            }
+            PatternNode::TypeTest(target, binding) => {
+                let (conds, narrowed) = self.compile_type_test(value, value_type, target);
+                let binds = if binding.text == "_" {
+                    vec![]
+                } else {
+                    vec![(binding.text.clone(), target.clone(), narrowed)]
+                };
+                Some((conds, binds))
+            }
+            PatternNode::Class(target, fields) => {
+                // The type test comes first, so the `&&` chain only reads fields of a `target`.
+                let (mut conds, narrowed) = self.compile_type_test(value, value_type, target);
+                let struct_name = target.get_type();
+                let info = self.struct_table.get_struct(&struct_name)?.clone();
+                let mut binds = Vec::new();
+                for (field, sub) in fields {
+                    let fty = info.fields.get(&field.text)?.type_.clone();
+                    let index = self.struct_field_index(&struct_name, &field.text)?;
+                    let field_expr = HExpr::new(
+                        self.type_ctx.lower(&fty),
+                        HExprKind::Field {
+                            obj: Box::new(narrowed.clone()),
+                            field: index,
+                        },
+                    );
+                    let (mut c, mut b) = self.compile_pattern(&field_expr, &fty, sub)?;
+                    conds.append(&mut c);
+                    binds.append(&mut b);
+                }
+                Some((conds, binds))
+            }
         }
     }
 
+    /// The runtime test for a type or class pattern naming `target`, plus the value narrowed to
+    /// `target`. A subject statically of type `target` needs neither; otherwise the test compares
+    /// runtime tags (false for `null`) and the narrowing is a cast, which unboxes a primitive.
+    fn compile_type_test(
+        &mut self,
+        value: &crate::hir::HExpr,
+        value_type: &Type,
+        target: &Type,
+    ) -> (Vec<crate::hir::HExpr>, crate::hir::HExpr) {
+        use crate::hir::{HExpr, HExprKind};
+        if value_type.get_type() == target.get_type() {
+            return (vec![], value.clone());
+        }
+        let target_ty = self.type_ctx.lower(target);
+        if self.non_null_subclass(value_type, target) {
+            return (vec![], HExpr::new(target_ty, HExprKind::Cast(Box::new(value.clone()))));
+        }
+        let test = HExpr::new(
+            self.type_ctx.interner.bool(),
+            HExprKind::IsType {
+                value: Box::new(value.clone()),
+                target: target_ty,
+            },
+        );
+        let narrowed = HExpr::new(target_ty, HExprKind::Cast(Box::new(value.clone())));
+        (vec![test], narrowed)
+    }
+
     /// General pattern-`switch` lowering (guards + nested/literal sub-patterns) as a flag-gated
     /// if-chain: evaluates the subject once, then for each arm emits `if (!done && <tests>) {
     /// <binds>; [if (<guard>)] { done = true; <body> } }`. A failed guard leaves `done` false so the
@@ -703,24 +864,31 @@ impl<'a> Analyzer<'a> {
         let subj_read = |s: &Self| s.hx_local(subj_local.unwrap_or(crate::hir::LocalId(0)), subj_ty_id);
 
         let mut arm_value_type: Option<Type> = None;
-        let mut covered: HashSet<String> = HashSet::new();
-        let mut has_catch_all = false;
-        let mut catch_all_index: Option<usize> = None;
+        // The coverage of each unguarded arm so far, one single-column row per arm. A pattern
+        // that failed to check says nothing reliable about coverage, so it is left out and the
+        // exhaustiveness check skipped.
+        let mut rows: Vec<Vec<Space>> = Vec::new();
+        let mut patterns_ok = true;
         let mut result_temp: Option<crate::hir::LocalId> = None;
         let mut result_ty_id: Option<crate::types::TypeId> = None;
 
-        for (i, arm) in arms.iter().enumerate() {
-            if catch_all_index.is_some() {
-                diagnostics.report_error(
-                    "Unreachable switch arm: a previous arm already matches everything".to_string(),
-                    arm.pattern.position(),
-                );
-            }
-
+        for arm in arms {
             let arm_scope = Rc::new(RefCell::new(SymbolTable::new(Some(symbol_table.clone()))));
             (*symbol_table).borrow_mut().add_child(arm_scope.clone());
 
-            let info = self.check_pattern(&arm.pattern, &subject_type, &arm_scope, diagnostics)?;
+            let errors_before = diagnostics.errors().count();
+            let info = self.check_pattern(
+                &arm.pattern,
+                &subject_type,
+                &arm_scope,
+                parent_function,
+                diagnostics,
+            )?;
+            let pattern_ok = diagnostics.errors().count() == errors_before;
+            patterns_ok &= pattern_ok;
+            if pattern_ok && !subject_type.is_unknown() {
+                self.report_unreachable_pattern(&rows, &arm.pattern, &info.space, diagnostics);
+            }
 
             // Build the arm's tests + payload bindings from the resolved subject value.
             let sr = subj_read(self);
@@ -810,13 +978,8 @@ impl<'a> Analyzer<'a> {
             self.hir_push_stmt(HStmt::If { cond, then_branch, else_branch: vec![] });
 
             // Exhaustiveness bookkeeping (guarded arms never contribute — a guard may fail).
-            if arm.guard.is_none() {
-                if info.irrefutable {
-                    has_catch_all = true;
-                    catch_all_index = Some(i);
-                } else if let Some(v) = info.covered_variant {
-                    covered.insert(v);
-                }
+            if arm.guard.is_none() && pattern_ok {
+                rows.push(vec![info.space]);
             }
         }
 
@@ -832,34 +995,9 @@ impl<'a> Analyzer<'a> {
             self.hir_fail();
         }
 
-        // Exhaustiveness: a guarded catch-all doesn't count, so require full variant coverage or `_`.
-        if !has_catch_all {
-            if let Some(info) = &union_info {
-                let missing: Vec<String> = info
-                    .variants
-                    .iter()
-                    .filter(|v| !covered.contains(&v.name))
-                    .map(|v| v.name.clone())
-                    .collect();
-                if !missing.is_empty() {
-                    diagnostics.report_error(
-                        format!(
-                            "Non-exhaustive switch on '{}': missing variant(s) {}. Add the missing arm(s) or a `_` arm",
-                            subject_base,
-                            missing.join(", ")
-                        ),
-                        subject.position(),
-                    );
-                }
-            } else if !subject_type.is_unknown() {
-                diagnostics.report_error(
-                    format!(
-                        "Non-exhaustive switch on '{}': add a `_` arm to cover all cases",
-                        subject_base
-                    ),
-                    subject.position(),
-                );
-            }
+        // Exhaustiveness: a guarded catch-all doesn't count, so require full coverage or `_`.
+        if patterns_ok {
+            self.report_missing_arms(&rows, subject, &subject_type, &union_info, diagnostics);
         }
 
         if is_expression {
@@ -911,9 +1049,11 @@ impl<'a> Analyzer<'a> {
             .lookup(crate::types::DefKind::Union, &subject_base);
 
         let mut arm_value_type: Option<Type> = None;
-        let mut covered: HashSet<String> = HashSet::new();
-        let mut has_catch_all = false;
-        let mut catch_all_index: Option<usize> = None;
+        // The coverage of each unguarded arm so far, one single-column row per arm. A pattern
+        // that failed to check says nothing reliable about coverage, so it is left out and the
+        // exhaustiveness check skipped.
+        let mut rows: Vec<Vec<Space>> = Vec::new();
+        let mut patterns_ok = true;
 
         // HIR: build `Switch` arms + a default block. A statement-position switch lowers directly; a
         // value-position switch desugars to `<result temp> = arm; … ; <result temp read>`, with each
@@ -924,19 +1064,24 @@ impl<'a> Analyzer<'a> {
         let mut result_temp: Option<crate::hir::LocalId> = None;
         let mut result_ty_id: Option<crate::types::TypeId> = None;
 
-        for (i, arm) in arms.iter().enumerate() {
-            if catch_all_index.is_some() {
-                diagnostics.report_error(
-                    "Unreachable switch arm: a previous arm already matches everything".to_string(),
-                    arm.pattern.position(),
-                );
-            }
-
+        for arm in arms {
             // Each arm introduces its pattern bindings into a fresh child scope.
             let arm_scope = Rc::new(RefCell::new(SymbolTable::new(Some(symbol_table.clone()))));
             (*symbol_table).borrow_mut().add_child(arm_scope.clone());
 
-            let info = self.check_pattern(&arm.pattern, &subject_type, &arm_scope, diagnostics)?;
+            let errors_before = diagnostics.errors().count();
+            let info = self.check_pattern(
+                &arm.pattern,
+                &subject_type,
+                &arm_scope,
+                parent_function,
+                diagnostics,
+            )?;
+            let pattern_ok = diagnostics.errors().count() == errors_before;
+            patterns_ok &= pattern_ok;
+            if pattern_ok && !subject_type.is_unknown() {
+                self.report_unreachable_pattern(&rows, &arm.pattern, &info.space, diagnostics);
+            }
 
             if let Some(guard) = &arm.guard {
                 // HIR `Switch` arms have no guard, so a guarded arm drops the function out of coverage.
@@ -1014,13 +1159,8 @@ impl<'a> Analyzer<'a> {
             }
 
             // An arm only contributes to exhaustiveness when it has no guard (a guard may fail).
-            if arm.guard.is_none() {
-                if info.irrefutable {
-                    has_catch_all = true;
-                    catch_all_index = Some(i);
-                } else if let Some(v) = info.covered_variant {
-                    covered.insert(v);
-                }
+            if arm.guard.is_none() && pattern_ok {
+                rows.push(vec![info.space]);
             }
         }
 
@@ -1041,33 +1181,8 @@ impl<'a> Analyzer<'a> {
         }
 
         // Exhaustiveness: every union variant must be covered, or a catch-all arm present.
-        if !has_catch_all {
-            if let Some(info) = &union_info {
-                let missing: Vec<String> = info
-                    .variants
-                    .iter()
-                    .filter(|v| !covered.contains(&v.name))
-                    .map(|v| v.name.clone())
-                    .collect();
-                if !missing.is_empty() {
-                    diagnostics.report_error(
-                        format!(
-                            "Non-exhaustive switch on '{}': missing variant(s) {}. Add the missing arm(s) or a `_` arm",
-                            subject_base,
-                            missing.join(", ")
-                        ),
-                        subject.position(),
-                    );
-                }
-            } else if !subject_type.is_unknown() {
-                diagnostics.report_error(
-                    format!(
-                        "Non-exhaustive switch on '{}': add a `_` arm to cover all cases",
-                        subject_base
-                    ),
-                    subject.position(),
-                );
-            }
+        if patterns_ok {
+            self.report_missing_arms(&rows, subject, &subject_type, &union_info, diagnostics);
         }
 
         if is_expression {
@@ -1078,31 +1193,29 @@ impl<'a> Analyzer<'a> {
     }
 
     /// Type-checks `pattern` against `expected`, introducing any bindings into `scope`.
-    fn check_pattern(
+    /// `parent_function` is the function the pattern appears in; class patterns may only name its
+    /// private fields from inside the class's own methods.
+    pub(super) fn check_pattern(
         &mut self,
         pattern: &PatternNode,
         expected: &Type,
         scope: &Rc<RefCell<SymbolTable>>,
+        parent_function: &FunctionNode<'a>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<PatternInfo, SemanticError> {
         let expected_base = strip_nullable(&expected.get_type()).to_string();
         let union_info: Option<UnionInfo> = self.union_table.get(&expected_base).cloned();
 
         match pattern {
-            PatternNode::Wildcard(_) => Ok(PatternInfo {
-                irrefutable: true,
-                covered_variant: None,
-            }),
+            PatternNode::Wildcard(_) => Ok(PatternInfo::catch_all()),
             PatternNode::Binding(name) => {
                 // A bare identifier that names a unit variant of the matched union is a
                 // unit-variant pattern; otherwise it binds the whole value.
                 if let Some(info) = &union_info {
                     if let Some(v) = info.variant(&name.text) {
                         if v.fields.is_empty() {
-                            return Ok(PatternInfo {
-                                irrefutable: false,
-                                covered_variant: Some(name.text.clone()),
-                            });
+                            let space = self.ctor_space(expected, &name.text, vec![]);
+                            return Ok(PatternInfo::refutable(space));
                         }
                     }
                 }
@@ -1112,10 +1225,7 @@ impl<'a> Analyzer<'a> {
                 {
                     diagnostics.report_error(e.to_string(), Some(name.position));
                 }
-                Ok(PatternInfo {
-                    irrefutable: true,
-                    covered_variant: None,
-                })
+                Ok(PatternInfo::catch_all())
             }
             PatternNode::Literal(lit) => {
                 if !lit.is_unknown()
@@ -1130,12 +1240,165 @@ impl<'a> Analyzer<'a> {
                         ),
                         lit.get_span(),
                     );
+                    return Ok(PatternInfo::poisoned());
+                }
+                Ok(PatternInfo::refutable(self.literal_space(lit, expected)))
+            }
+            PatternNode::Range(lo, hi) => Ok(self.check_range_pattern(lo, hi, expected, diagnostics)),
+            PatternNode::Or(alternatives) => {
+                let mut irrefutable = false;
+                let mut spaces = Vec::with_capacity(alternatives.len());
+                for alternative in alternatives {
+                    // Each alternative binds into a scratch scope: a name bound by only some of
+                    // them would be unset whenever another one matched.
+                    let alt_scope = Rc::new(RefCell::new(SymbolTable::new(Some(scope.clone()))));
+                    let info = self.check_pattern(
+                        alternative,
+                        expected,
+                        &alt_scope,
+                        parent_function,
+                        diagnostics,
+                    )?;
+                    if !alt_scope.borrow().get_all().is_empty() {
+                        diagnostics.report_error(
+                            "Or-pattern alternatives cannot bind names; use a separate arm for each binding"
+                                .to_string(),
+                            alternative.position(),
+                        );
+                    }
+                    irrefutable |= info.irrefutable;
+                    spaces.push(info.space);
                 }
                 Ok(PatternInfo {
-                    irrefutable: false,
-                    covered_variant: None,
+                    irrefutable,
+                    space: Space::Or(spaces),
                 })
             }
+            PatternNode::TypeTest(target, binding) => {
+                let valid = self.check_type_pattern_target(target, expected, diagnostics);
+                if binding.text != "_" {
+                    if let Err(e) = (*scope)
+                        .borrow_mut()
+                        .add_symbol(binding.text.clone(), target.clone())
+                    {
+                        diagnostics.report_error(e.to_string(), Some(binding.position));
+                    }
+                }
+                if !valid {
+                    return Ok(PatternInfo::poisoned());
+                }
+                let arity = self.struct_field_names(&target.get_type()).len();
+                Ok(self.type_pattern_info(expected, target, vec![Space::Wild; arity], true))
+            }
+            PatternNode::Class(target, fields) => {
+                let valid = self.check_type_pattern_target(target, expected, diagnostics);
+                let target_name = target.get_type();
+                let info = match self.struct_table.get_struct(&target_name) {
+                    Some(info) if valid => Some(info.clone()),
+                    _ => {
+                        if valid {
+                            diagnostics.report_error(
+                                format!(
+                                    "Class pattern needs a class type, not '{}'",
+                                    target.display_name()
+                                ),
+                                target.get_span(),
+                            );
+                        }
+                        None
+                    }
+                };
+                let Some(info) = info else {
+                    for (_, sub) in fields {
+                        self.check_pattern(sub, &Type::Unknown, scope, parent_function, diagnostics)?;
+                    }
+                    return Ok(PatternInfo::poisoned());
+                };
+                let base_name = match target {
+                    Type::Struct(token, _) => token.text.clone(),
+                    _ => target_name.clone(),
+                };
+                let layout = self.struct_field_names(&target_name);
+                let mut args = vec![Space::Wild; layout.len()];
+                let mut all_irrefutable = true;
+                let mut seen = HashSet::new();
+                for (field, sub) in fields {
+                    let Some(field_info) = info.fields.get(&field.text) else {
+                        diagnostics.report_error(
+                            format!(
+                                "Field '{}' not found in class '{}'",
+                                field.text,
+                                target.display_name()
+                            ),
+                            Some(field.position),
+                        );
+                        self.check_pattern(sub, &Type::Unknown, scope, parent_function, diagnostics)?;
+                        all_irrefutable = false;
+                        continue;
+                    };
+                    if !seen.insert(field.text.clone()) {
+                        diagnostics.report_error(
+                            format!("Field '{}' is matched more than once", field.text),
+                            Some(field.position),
+                        );
+                    }
+                    if !field_info.is_public && !self.in_methods_of(parent_function, &base_name) {
+                        diagnostics.report_error(
+                            format!("'{}' is private to '{}'", field.text, base_name),
+                            Some(field.position),
+                        );
+                    }
+                    let field_type = field_info.type_.clone();
+                    let sub_info =
+                        self.check_pattern(sub, &field_type, scope, parent_function, diagnostics)?;
+                    all_irrefutable &= sub_info.irrefutable;
+                    if let Some(i) = layout.iter().position(|name| *name == field.text) {
+                        args[i] = sub_info.space;
+                    }
+                }
+                Ok(self.type_pattern_info(expected, target, args, all_irrefutable))
+            }
+            PatternNode::Tuple(open, subs) => {
+                let elements = expected
+                    .tuple_elements()
+                    .filter(|elems| elems.len() == subs.len())
+                    .map(|elems| elems.to_vec());
+                let Some(elements) = elements else {
+                    if !expected.is_unknown() {
+                        diagnostics.report_error(
+                            format!(
+                                "Tuple pattern with {} elements cannot match a value of type '{}'",
+                                subs.len(),
+                                expected.display_name()
+                            ),
+                            Some(open.position),
+                        );
+                    }
+                    for sub in subs {
+                        self.check_pattern(sub, &Type::Unknown, scope, parent_function, diagnostics)?;
+                    }
+                    return Ok(PatternInfo::poisoned());
+                };
+                // The value's type may come from a generic signature nothing has instantiated yet.
+                self.ensure_struct_instantiated(
+                    &tuple_type_name(elements.len()),
+                    &elements,
+                    &open.position,
+                    diagnostics,
+                );
+                // A tuple always has every element, so it matches whenever all of its parts do.
+                let mut irrefutable = true;
+                let mut args = Vec::with_capacity(subs.len());
+                for (sub, element) in subs.iter().zip(&elements) {
+                    let info = self.check_pattern(sub, element, scope, parent_function, diagnostics)?;
+                    irrefutable &= info.irrefutable;
+                    args.push(info.space);
+                }
+                if irrefutable {
+                    return Ok(PatternInfo::catch_all());
+                }
+                Ok(PatternInfo::refutable(self.ctor_space(expected, &expected_base, args)))
+            }
             PatternNode::Variant(qualifier, variant, subs) => {
                 let info = match &union_info {
                     Some(info) => info.clone(),
@@ -1149,12 +1412,9 @@ impl<'a> Analyzer<'a> {
                         );
                         // Still walk sub-patterns so their bindings/errors surface.
                         for sub in subs {
-                            self.check_pattern(sub, &Type::Unknown, scope, diagnostics)?;
+                            self.check_pattern(sub, &Type::Unknown, scope, parent_function, diagnostics)?;
                         }
-                        return Ok(PatternInfo {
-                            irrefutable: false,
-                            covered_variant: None,
-                        });
+                        return Ok(PatternInfo::poisoned());
                     }
                 };
 
@@ -1178,12 +1438,9 @@ impl<'a> Analyzer<'a> {
                             Some(variant.position),
                         );
                         for sub in subs {
-                            self.check_pattern(sub, &Type::Unknown, scope, diagnostics)?;
+                            self.check_pattern(sub, &Type::Unknown, scope, parent_function, diagnostics)?;
                         }
-                        return Ok(PatternInfo {
-                            irrefutable: false,
-                            covered_variant: None,
-                        });
+                        return Ok(PatternInfo::poisoned());
                     }
                 };
 
@@ -1200,29 +1457,410 @@ impl<'a> Analyzer<'a> {
                     );
                 }
 
-                let mut all_irrefutable = true;
+                let mut args = Vec::with_capacity(var_info.fields.len());
                 for (i, sub) in subs.iter().enumerate() {
                     let field_type = var_info
                         .fields
                         .get(i)
                         .map(|f| f.type_.clone())
                         .unwrap_or(Type::Unknown);
-                    let sub_info = self.check_pattern(sub, &field_type, scope, diagnostics)?;
-                    if !sub_info.irrefutable {
-                        all_irrefutable = false;
-                    }
+                    let sub_info =
+                        self.check_pattern(sub, &field_type, scope, parent_function, diagnostics)?;
+                    args.push(sub_info.space);
                 }
+                args.resize(var_info.fields.len(), Space::Wild);
+                Ok(PatternInfo::refutable(self.ctor_space(expected, &variant.text, args)))
+            }
+        }
+    }
 
-                // The variant is fully covered only when every sub-pattern is irrefutable.
-                Ok(PatternInfo {
-                    irrefutable: false,
-                    covered_variant: if all_irrefutable {
-                        Some(variant.text.clone())
-                    } else {
-                        None
-                    },
+    /// Checks a range pattern `lo..=hi`: both bounds must be integer or char literals the subject
+    /// type accepts, in ascending order.
+    fn check_range_pattern(
+        &mut self,
+        lo: &Type,
+        hi: &Type,
+        expected: &Type,
+        diagnostics: &mut DiagnosticBag,
+    ) -> PatternInfo {
+        if expected.is_unknown() || lo.is_unknown() || hi.is_unknown() {
+            return PatternInfo::poisoned();
+        }
+        let expected_base = strip_nullable(&expected.get_type()).to_string();
+        let Some(domain) = int_domain(&expected_base) else {
+            diagnostics.report_error(
+                format!(
+                    "Range patterns only match integers and chars, not '{}'",
+                    expected.display_name()
+                ),
+                lo.get_span(),
+            );
+            return PatternInfo::poisoned();
+        };
+        let mut bounds = [0i128; 2];
+        for (slot, bound) in bounds.iter_mut().zip([lo, hi]) {
+            let value = literal_int_value(bound)
+                .filter(|_| self.type_str_assignable(&expected_base, &bound.get_type()));
+            let Some(value) = value else {
+                diagnostics.report_error(
+                    format!(
+                        "Range bound of type '{}' cannot match a value of type '{}'",
+                        bound.get_type(),
+                        expected_base
+                    ),
+                    bound.get_span(),
+                );
+                return PatternInfo::poisoned();
+            };
+            *slot = value;
+        }
+        if bounds[0] > bounds[1] {
+            diagnostics.report_error(
+                format!(
+                    "Range pattern is empty: its lower bound is greater than its upper bound ({} > {})",
+                    bounds[0], bounds[1]
+                ),
+                lo.get_span(),
+            );
+            return PatternInfo::poisoned();
+        }
+        PatternInfo::refutable(Space::Range {
+            lo: bounds[0].max(domain.min),
+            hi: bounds[1].min(domain.max),
+            domain,
+        })
+    }
+
+    /// True when `value` is a non-null class type extending class `target`, so every value of it
+    /// is a `target`.
+    fn non_null_subclass(&self, value: &Type, target: &Type) -> bool {
+        let value = value.get_type();
+        !value.ends_with('?') && self.is_subclass_of(&value, &target.get_type())
+    }
+
+    /// Validates the type named by a type or class pattern against the subject type `expected`:
+    /// the subject's own type always matches; a class can also be tested for on an `object`, an
+    /// interface it implements, or one of its base classes (and a primitive on an `object`).
+    /// Returns false after reporting, or silently for a poisoned operand.
+    fn check_type_pattern_target(
+        &mut self,
+        target: &Type,
+        expected: &Type,
+        diagnostics: &mut DiagnosticBag,
+    ) -> bool {
+        if expected.is_unknown() || target.is_unknown() {
+            return false;
+        }
+        if let Type::Struct(base, Some(args)) = target {
+            self.ensure_struct_instantiated(&base.text, args, &base.position, diagnostics);
+        }
+        let target_name = target.get_type();
+        let subject = strip_nullable(&expected.get_type()).to_string();
+        if target_name == subject {
+            return true;
+        }
+        if self.is_interface_name(&target_name) {
+            diagnostics.report_error(
+                format!(
+                    "Type pattern '{}' names an interface; match the classes that implement it instead",
+                    target.display_name()
+                ),
+                target.get_span(),
+            );
+            return false;
+        }
+        if matches!(target, Type::Struct(..)) && self.struct_table.get_struct(&target_name).is_none()
+        {
+            diagnostics.report_error(
+                format!("Type pattern '{}' does not name a class", target.display_name()),
+                target.get_span(),
+            );
+            return false;
+        }
+        let reachable = subject == "object"
+            || (self.is_interface_name(&subject) && self.class_implements(&target_name, &subject))
+            || self.is_subclass_of(&target_name, &subject)
+            || self.is_subclass_of(&subject, &target_name);
+        if !reachable {
+            diagnostics.report_error(
+                format!(
+                    "Type pattern '{}' can never match a value of type '{}'",
+                    target.display_name(),
+                    expected.display_name()
+                ),
+                target.get_span(),
+            );
+        }
+        reachable
+    }
+
+    /// The outcome of a (valid) type or class pattern for `target`, whose fields match `args` in
+    /// layout order. It needs no runtime test, and covers everything, when the subject is already
+    /// a non-null `target` (or subclass of it) and every field pattern is irrefutable. A class
+    /// with subclasses also matches each of them, whose layouts extend `target`'s with fields the
+    /// pattern leaves unconstrained.
+    fn type_pattern_info(
+        &self,
+        expected: &Type,
+        target: &Type,
+        args: Vec<Space>,
+        fields_irrefutable: bool,
+    ) -> PatternInfo {
+        if fields_irrefutable
+            && (expected.get_type() == target.get_type() || self.non_null_subclass(expected, target))
+        {
+            return PatternInfo::catch_all();
+        }
+        let target_name = target.get_type();
+        let subclasses = self.subclasses_of(&target_name);
+        if subclasses.is_empty() {
+            return PatternInfo::refutable(self.ctor_space(expected, &target_name, args));
+        }
+        let mut spaces = vec![self.ctor_space(expected, &target_name, args.clone())];
+        for class in subclasses {
+            let mut class_args = args.clone();
+            class_args.resize(self.struct_field_names(&class).len(), Space::Wild);
+            spaces.push(self.ctor_space(expected, &class, class_args));
+        }
+        PatternInfo::refutable(Space::Or(spaces))
+    }
+
+    /// The coverage space of a constant literal matched against `expected`. Integer and char
+    /// literals become one-value ranges so that range arms can cover them.
+    fn literal_space(&self, lit: &Type, expected: &Type) -> Space {
+        let expected_name = expected.get_type();
+        if let (Some(domain), Some(value)) =
+            (int_domain(strip_nullable(&expected_name)), literal_int_value(lit))
+        {
+            return Space::Range {
+                lo: value,
+                hi: value,
+                domain,
+            };
+        }
+        let name = match lit {
+            Type::Nullable(_) => "null".to_string(),
+            Type::Boolean(token) => token.text.clone(),
+            Type::String(token)
+            | Type::Char(token)
+            | Type::Float(token)
+            | Type::Double(token)
+            | Type::Integer(token)
+            | Type::Long(token)
+            | Type::UInt(token)
+            | Type::ULong(token)
+            | Type::Byte(token) => format!("{} {}", lit.get_type(), token.text),
+            _ => lit.get_type(),
+        };
+        self.ctor_space(expected, &name, vec![])
+    }
+
+    /// A constructor space for `name`, one of the constructors of `expected`'s type.
+    fn ctor_space(&self, expected: &Type, name: &str, args: Vec<Space>) -> Space {
+        Space::Ctor {
+            name: name.to_string(),
+            args,
+            family: Rc::new(self.coverage_family(expected)),
+        }
+    }
+
+    /// The constructors of `ty` for coverage purposes: a union's variants, `false`/`true`, the one
+    /// shape of a tuple, a class and each of its subclasses, or the classes implementing an
+    /// interface (in name order). A nullable type also has `null`. Every other type is open.
+    fn coverage_family(&self, ty: &Type) -> Family {
+        let class_member = |s: &Self, name: &str, kind: CtorKind| Member {
+            name: name.to_string(),
+            kind,
+            fields: s.struct_field_names(name),
+        };
+        let literal_member = |name: &str| Member {
+            name: name.to_string(),
+            kind: CtorKind::Literal,
+            fields: vec![],
+        };
+        let name = ty.get_type();
+        let base = strip_nullable(&name);
+        let (mut members, complete) = if let Some(info) = self.union_table.get(base) {
+            let variants = info
+                .variants
+                .iter()
+                .map(|v| Member {
+                    name: v.name.clone(),
+                    kind: CtorKind::Variant,
+                    fields: v.fields.iter().map(|f| f.name.clone()).collect(),
                 })
+                .collect();
+            (variants, true)
+        } else if base == "bool" {
+            (vec![literal_member("false"), literal_member("true")], true)
+        } else if is_tuple_type_name(base) {
+            (vec![class_member(self, base, CtorKind::Tuple)], true)
+        } else if self.is_interface_name(base) {
+            let mut classes: Vec<&String> = self
+                .implements
+                .iter()
+                .filter(|(_, interfaces)| interfaces.iter().any(|i| i == base))
+                .map(|(class, _)| class)
+                .collect();
+            classes.sort();
+            let members: Vec<Member> = classes
+                .into_iter()
+                .map(|class| class_member(self, class, CtorKind::Class))
+                .collect();
+            let complete = !members.is_empty();
+            (members, complete)
+        } else if self.struct_table.get_struct(base).is_some() {
+            let members = std::iter::once(base.to_string())
+                .chain(self.subclasses_of(base))
+                .map(|class| class_member(self, &class, CtorKind::Class))
+                .collect();
+            (members, true)
+        } else {
+            (vec![], false)
+        };
+        if complete && name != base {
+            members.push(literal_member("null"));
+        }
+        Family { members, complete }
+    }
+
+    /// The field names of class `name` in layout order (the order of `struct_field_index`), or
+    /// none for a type that is not a class.
+    fn struct_field_names(&self, name: &str) -> Vec<String> {
+        let Some(info) = self.struct_table.get_struct(name) else {
+            return vec![];
+        };
+        let mut ordered: Vec<(&String, usize)> =
+            info.fields.iter().map(|(n, f)| (n, f.offset)).collect();
+        ordered.sort_by_key(|(_, offset)| *offset);
+        ordered.into_iter().map(|(n, _)| n.clone()).collect()
+    }
+
+    /// Reports `pattern` (covering `space`) when the unguarded arms before it (`rows`) already
+    /// match every value it matches; otherwise reports each of its or-alternatives that the
+    /// earlier arms and alternatives already match.
+    fn report_unreachable_pattern(
+        &self,
+        rows: &[Vec<Space>],
+        pattern: &PatternNode,
+        space: &Space,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        if !coverage::is_useful(rows, std::slice::from_ref(space)) {
+            let message = if rows.iter().any(|row| matches!(row[0], Space::Wild)) {
+                "Unreachable switch arm: a previous arm already matches everything"
+            } else {
+                "Unreachable switch arm: earlier arms already match every value this pattern matches"
+            };
+            diagnostics.report_error(message.to_string(), pattern.position());
+            return;
+        }
+        if let (PatternNode::Or(alternatives), Space::Or(spaces)) = (pattern, space) {
+            let mut seen = rows.to_vec();
+            for (alternative, alt_space) in alternatives.iter().zip(spaces) {
+                if !coverage::is_useful(&seen, std::slice::from_ref(alt_space)) {
+                    diagnostics.report_error(
+                        "Unreachable pattern alternative: earlier patterns already match every value it matches"
+                            .to_string(),
+                        alternative.position(),
+                    );
+                }
+                seen.push(vec![alt_space.clone()]);
             }
         }
     }
+
+    /// Reports a switch whose unguarded arms (`rows`) leave some value of `subject_type`
+    /// unmatched, naming the missing variants or patterns when they can be enumerated.
+    fn report_missing_arms(
+        &self,
+        rows: &[Vec<Space>],
+        subject: &ExpressionNode<'a>,
+        subject_type: &Type,
+        union_info: &Option<UnionInfo>,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        if subject_type.is_unknown() {
+            return;
+        }
+        let subject_base = match subject_type {
+            Type::Nullable(inner) => inner.display_name(),
+            other => other.display_name(),
+        };
+        let mut missing: Vec<String> = coverage::missing_patterns(rows, 1)
+            .into_iter()
+            .map(|mut witness| witness.remove(0))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        // With no usable arm at all, a union is still best described by its variants.
+        if let (Some(info), true) = (union_info, rows.is_empty()) {
+            missing = info.variants.iter().map(|v| v.name.clone()).collect();
+        }
+        let variant_names: Option<Vec<String>> = union_info.as_ref().and_then(|info| {
+            missing
+                .iter()
+                .map(|m| {
+                    info.variants
+                        .iter()
+                        .find(|v| {
+                            *m == v.name
+                                || *m == format!("{}({})", v.name, vec!["_"; v.fields.len()].join(", "))
+                        })
+                        .map(|v| v.name.clone())
+                })
+                .collect()
+        });
+        let message = if let Some(names) = variant_names {
+            format!(
+                "Non-exhaustive switch on '{}': missing variant(s) {}. Add the missing arm(s) or a `_` arm",
+                subject_base,
+                names.join(", ")
+            )
+        } else if missing.iter().all(|m| m == "_") {
+            format!(
+                "Non-exhaustive switch on '{}': add a `_` arm to cover all cases",
+                subject_base
+            )
+        } else {
+            format!(
+                "Non-exhaustive switch on '{}': pattern(s) {} not covered. Add the missing arm(s) or a `_` arm",
+                subject_base,
+                missing.join(", ")
+            )
+        };
+        diagnostics.report_error(message, subject.position());
+    }
+}
+
+/// The values of the integer-like type `name`, or `None` for any other type.
+fn int_domain(name: &str) -> Option<IntDomain> {
+    let (min, max) = match name {
+        "int" => (i32::MIN as i128, i32::MAX as i128),
+        "uint" => (0, u32::MAX as i128),
+        "long" => (i64::MIN as i128, i64::MAX as i128),
+        "ulong" => (0, u64::MAX as i128),
+        "byte" => (0, u8::MAX as i128),
+        "char" => (0, char::MAX as i128),
+        _ => return None,
+    };
+    Some(IntDomain {
+        min,
+        max,
+        is_char: name == "char",
+    })
+}
+
+/// The value of an integer or char literal (the parser spells a char as its code point).
+fn literal_int_value(lit: &Type) -> Option<i128> {
+    match lit {
+        Type::Integer(token)
+        | Type::Long(token)
+        | Type::UInt(token)
+        | Type::ULong(token)
+        | Type::Byte(token)
+        | Type::Char(token) => token.text.parse().ok(),
+        _ => None,
+    }
 }
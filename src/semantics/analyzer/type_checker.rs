@@ -4,6 +4,8 @@ use crate::semantics::errors::SemanticError;
 use crate::semantics::function_control_flow::FunctionControlGraph;
 use crate::semantics::symbol_table::SymbolTable;
 use crate::syntax::nodes::{FunctionNode, StatementNode};
+use crate::syntax::token::syntax_token::SyntaxToken;
+use crate::syntax::token::token_kind::TokenKind;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -13,6 +15,12 @@ impl<'a> Analyzer<'a> {
         function: &FunctionNode<'a>,
         diagnostics: &mut DiagnosticBag,
     ) -> Result<Rc<RefCell<SymbolTable>>, SemanticError> {
+        if let Some(body) = self.implicit_super_body(function) {
+            let mut chained = function.clone();
+            chained.body = body;
+            let chained: &'a FunctionNode<'a> = self.arena.alloc(chained);
+            return self.analyze_function(chained, diagnostics);
+        }
         let param_table = Rc::new(RefCell::new(
             self.add_function_param_table(function, diagnostics)?,
         ));
@@ -39,6 +47,38 @@ impl<'a> Analyzer<'a> {
         Ok(param_table.clone())
     }
 
+    /// The body of a subclass constructor that does not begin with `super(...)`, prefixed with an
+    /// implicit `super()` so the base class's constructor still runs first. `None` when there is
+    /// nothing to chain to, or when the base constructor needs arguments (reported at
+    /// registration by `check_constructor_chaining`).
+    fn implicit_super_body(&self, function: &FunctionNode<'a>) -> Option<&'a [StatementNode<'a>]> {
+        let (class, member) = self.method_names.get(&function.name.text)?;
+        if member != crate::syntax::nodes::types::CONSTRUCTOR_NAME || begins_with_super_call(function) {
+            return None;
+        }
+        let base = self.base_classes.get(class)?;
+        let base_ctor = self
+            .function_table
+            .get_function(&crate::types::constructor_fn(base))
+            .ok()?;
+        // Parameter 0 of the registered constructor is the implicit `this`.
+        if base_ctor.defaults.iter().skip(1).any(|d| d.is_none()) {
+            return None;
+        }
+        let call = StatementNode::FunctionInvocation(
+            SyntaxToken::new(
+                TokenKind::IdentifierToken,
+                function.name.position,
+                SUPER.to_string(),
+            ),
+            None,
+            Vec::new(),
+        );
+        let body: Vec<StatementNode<'a>> =
+            std::iter::once(call).chain(function.body.iter().cloned()).collect();
+        Some(self.arena.alloc_slice_fill_iter(body))
+    }
+
     pub(super) fn add_function_param_table(
         &mut self,
         function: &FunctionNode<'a>,
@@ -73,6 +113,9 @@ impl<'a> Analyzer<'a> {
             (*parent_table).borrow_mut().add_child(symbol_table.clone());
         }
         for statement in body.iter() {
+            if let Some(span) = statement.start_position() {
+                self.hir_line(span.line_no);
+            }
             let clone = &symbol_table.clone();
             // Recover at the statement boundary: a short-circuited statement leaves its diagnostic
             // in the bag, and we move on to the next sibling so every independent error in the
@@ -103,6 +146,7 @@ impl<'a> Analyzer<'a> {
         // *before* recursing into any nested body, so a function containing one is skipped cleanly.
         match statement {
             StatementNode::Declaration(..)
+            | StatementNode::DestructuringDeclaration(..)
             | StatementNode::Assignment(..)
             | StatementNode::IndexAssignment(..)
             | StatementNode::MemberAssignment(..)
@@ -119,24 +163,28 @@ impl<'a> Analyzer<'a> {
             | StatementNode::Labeled(..)
             | StatementNode::FunctionInvocation(..)
             | StatementNode::MethodInvocation(..)
-            | StatementNode::AwaitStmt(..) => {}
+            | StatementNode::AwaitStmt(..)
+            | StatementNode::Throw(..)
+            | StatementNode::Try(..) => {}
         }
         match statement {
             StatementNode::Declaration(left, type_annotation, right, is_const) => self
                 .analyze_declaration(left, type_annotation, right, *is_const, &ctx, diagnostics)?,
+            StatementNode::DestructuringDeclaration(pattern, right, is_const) => self
+                .analyze_destructuring_declaration(pattern, right, *is_const, &ctx, diagnostics)?,
             StatementNode::Assignment(left, right) => {
                 self.analyze_assignment(left, right, parent_function, symbol_table, diagnostics)?
             }
             StatementNode::IndexAssignment(left, index, right) => self.analyze_index_assignment(
-                *left,
-                *index,
+                left,
+                index,
                 right,
                 parent_function,
                 symbol_table,
                 diagnostics,
             )?,
             StatementNode::MemberAssignment(obj, member, right) => self.analyze_member_assignment(
-                *obj,
+                obj,
                 member,
                 right,
                 parent_function,
@@ -226,6 +274,18 @@ impl<'a> Analyzer<'a> {
                 let value = self.hir_take();
                 self.hir_expr_stmt(value);
             }
+            StatementNode::Throw(keyword, value) => {
+                self.analyze_throw(keyword, value, &ctx, diagnostics)?
+            }
+            StatementNode::Try(keyword, body, catches, finally) => self.analyze_try(
+                keyword,
+                body,
+                catches,
+                finally,
+                &ctx,
+                has_parent_while,
+                diagnostics,
+            )?,
             StatementNode::AwaitStmt(future_expr) => {
                 let fut = self
                     .analyze_expression(future_expr, parent_function, symbol_table, diagnostics)
@@ -1,4 +1,4 @@
-use crate::syntax::nodes::{ExpressionNode, FunctionNode, StatementNode, Type};
+use crate::syntax::nodes::{CatchClause, ExpressionNode, FunctionNode, StatementNode, Type};
 use std::cell::RefCell;
 use std::io::Error;
 use std::rc::Rc;
@@ -112,12 +112,15 @@ impl<'a> FunctionControlGraph<'a> {
             // only valid in void functions (checked by the analyzer); handling it here without a
             // value avoids panicking on malformed `return;` inside a non-void function.
             StatementNode::Return(_) => self.visit_return(parent)?,
+            // A `throw` ends the path without falling through, just like a `return`.
+            StatementNode::Throw(..) => self.visit_return(parent)?,
             StatementNode::IfElse(_, if_body, else_pair, else_body) => {
                 self.visit_if_else(if_body, else_pair, else_body, parent)?
             }
             StatementNode::Switch(_, cases, default) => {
                 self.visit_switch(cases, default, parent)?
             }
+            StatementNode::Try(_, body, catches, _) => self.visit_try(body, catches, parent)?,
             _ => {}
         };
         Ok(())
@@ -211,6 +214,28 @@ impl<'a> FunctionControlGraph<'a> {
         Ok(())
     }
 
+    // A `try` returns on all paths when its body and every `catch` body do: control reaches the end
+    // of the statement either by finishing the body or by finishing a clause. (`finally` cannot
+    // return, so it never decides the matter.)
+    fn visit_try(
+        &mut self,
+        body: &'a [StatementNode<'a>],
+        catches: &[CatchClause<'a>],
+        parent: &Rc<RefCell<FlowNode>>,
+    ) -> Result<(), Error> {
+        let bodies = std::iter::once(body).chain(catches.iter().map(|c| c.body));
+        for body in bodies {
+            let node = Rc::new(RefCell::new(FlowNode::new()));
+            (*parent)
+                .as_ref()
+                .borrow_mut()
+                .child_nodes
+                .push(node.clone());
+            self.visit_block(body, &node)?;
+        }
+        Ok(())
+    }
+
     //add return node to parent block and mark: has return
     fn visit_return(&mut self, parent: &Rc<RefCell<FlowNode>>) -> Result<(), Error> {
         let return_flow = Rc::new(RefCell::new(FlowNode::from(true)));
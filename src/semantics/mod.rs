@@ -2,6 +2,7 @@ pub mod analyzer;
 pub mod errors;
 mod function_control_flow;
 pub mod function_table;
+pub mod modules;
 pub mod struct_table;
 pub mod symbol_table;
 pub mod union_table;
@@ -0,0 +1,897 @@
+//! Module namespaces. Every user `.dream` file is a module: its top-level declarations are visible
+//! in the file itself, and other modules see only its `public` ones, and only after importing it:
+//!
+//! - `import geometry.shapes;` brings the public declarations into scope unqualified and also
+//!   allows `shapes.Circle`;
+//! - `import geometry.shapes as gs;` only allows the qualified `gs.Circle`;
+//! - `import { Circle, area } from geometry.shapes;` brings just the listed names into scope.
+//!
+//! The analyzer works on one flat program, so resolution happens on the merged declarations before
+//! analysis: each reference to a top-level declaration is rewritten to the declaration's internal
+//! name, and qualified references (`shapes.area(c)`, `shapes.Circle` as a type) become plain ones.
+//! A name declared by several modules keeps its spelling in the first of them (the entry file wins)
+//! and is renamed `{module}__{Name}` in the others, so same-named classes no longer collide.
+//! Extension methods and the standard prelude stay program-wide.
+
+use bumpalo::Bump;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::diagnostics::DiagnosticBag;
+use crate::syntax::nodes::struct_node::{StructDeclarationNode, StructFieldNode};
+use crate::syntax::nodes::{
+    CatchClause, EnumDeclarationNode, ExpressionNode, ExtendNode, FunctionNode, GenericBound,
+    GlobalVariableNode, ImportNode, InterfaceDeclarationNode, LambdaBody, LambdaNode, PatternNode,
+    StatementNode, SwitchArm, SwitchArmBody, Type,
+};
+use crate::syntax::token::syntax_token::SyntaxToken;
+
+/// The program's user modules, keyed by file tag in load order (the entry file first).
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    modules: IndexMap<String, ModuleInfo>,
+}
+
+/// One user module: its dotted name (the file path relative to the entry file's directory, e.g.
+/// `geometry.shapes`) and its imports.
+#[derive(Debug)]
+pub struct ModuleInfo {
+    pub name: String,
+    pub imports: Vec<ModuleImport>,
+}
+
+/// An `import` resolved to the file tag of the module it names.
+#[derive(Debug)]
+pub struct ModuleImport {
+    pub node: ImportNode,
+    pub file: String,
+}
+
+impl ModuleGraph {
+    /// Registers the module loaded from `file`. The first module registered is the entry file.
+    pub fn add_module(&mut self, file: &str, name: String, imports: Vec<ModuleImport>) {
+        self.modules
+            .entry(file.to_string())
+            .or_insert(ModuleInfo { name, imports });
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Every module with its file tag, in load order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ModuleInfo)> {
+        self.modules.iter()
+    }
+}
+
+/// A module's dotted name from its path relative to `root` (`geometry/shapes.dream` ->
+/// `geometry.shapes`). A file outside `root` is named after its stem.
+pub fn module_name(root: &std::path::Path, file: &std::path::Path) -> String {
+    let relative = file
+        .strip_prefix(root)
+        .ok()
+        .map(|p| p.with_extension(""))
+        .unwrap_or_else(|| file.file_stem().map(Into::into).unwrap_or_default());
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// One top-level declaration as seen from other modules.
+struct Export {
+    is_public: bool,
+    /// The name the analyzer sees (the source name, or the `{module}__{Name}` spelling when
+    /// another module declares the same name).
+    internal: String,
+}
+
+/// Rewrites the user modules' declarations so every cross-module reference names the declaration
+/// it resolves to, reporting references that are not imported or not `public`. Declarations from
+/// files outside `graph` (the prelude, generated code) are left untouched.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_modules<'a>(
+    graph: &ModuleGraph,
+    arena: &'a Bump,
+    functions: &mut [FunctionNode<'a>],
+    structs: &mut [StructDeclarationNode<'a>],
+    interfaces: &mut [InterfaceDeclarationNode<'a>],
+    enums: &mut [EnumDeclarationNode],
+    extends: &mut [ExtendNode<'a>],
+    globals: &mut [GlobalVariableNode<'a>],
+    diagnostics: &mut DiagnosticBag,
+) {
+    // A single file has nobody to share names with.
+    if graph.len() < 2 {
+        return;
+    }
+
+    // Every module's declarations, as `(file, name, is_public, renamable)`. Externs bind to a host
+    // import by name, so they are never renamed.
+    let mut declared: Vec<(Rc<str>, String, bool, bool)> = Vec::new();
+    for f in functions.iter() {
+        if let Some(file) = module_file(graph, &f.file_path) {
+            declared.push((file, f.name.text.clone(), f.is_public, !f.is_extern));
+        }
+    }
+    for s in structs.iter() {
+        if let Some(file) = module_file(graph, &s.file_path) {
+            declared.push((file, s.name.text.clone(), s.is_public, true));
+        }
+    }
+    for i in interfaces.iter() {
+        if let Some(file) = module_file(graph, &i.file_path) {
+            declared.push((file, i.name.text.clone(), i.is_public, true));
+        }
+    }
+    for e in enums.iter() {
+        if let Some(file) = module_file(graph, &e.file_path) {
+            declared.push((file, e.name.text.clone(), e.is_public, true));
+        }
+    }
+    for g in globals.iter() {
+        if let Some(file) = module_file(graph, &g.file_path) {
+            declared.push((file, g.name.text.clone(), g.is_public, true));
+        }
+    }
+
+    // The module owning each name's plain spelling: the first (in load order) to declare it.
+    let mut owners: HashMap<&str, usize> = HashMap::new();
+    for (file, name, _, renamable) in &declared {
+        if *renamable {
+            let index = graph.modules.get_index_of(&**file).unwrap_or(usize::MAX);
+            let owner = owners.entry(name.as_str()).or_insert(index);
+            *owner = (*owner).min(index);
+        }
+    }
+    let mut exports: HashMap<String, IndexMap<String, Export>> = HashMap::new();
+    for (file, name, is_public, renamable) in &declared {
+        let index = graph.modules.get_index_of(&**file).unwrap_or(usize::MAX);
+        let internal = if *renamable && owners.get(name.as_str()) != Some(&index) {
+            let module = &graph.modules[index].name;
+            format!("{}__{}", module.replace('.', "_"), name)
+        } else {
+            name.clone()
+        };
+        let entry = exports
+            .entry(file.to_string())
+            .or_default()
+            .entry(name.clone())
+            .or_insert(Export { is_public: false, internal });
+        // Overloads share a name; any public overload exports it.
+        entry.is_public |= *is_public;
+    }
+
+    let mut resolvers: HashMap<&str, Resolver<'a, '_>> = graph
+        .modules
+        .iter()
+        .map(|(file, module)| {
+            (file.as_str(), Resolver::new(graph, &exports, file, module, arena))
+        })
+        .collect();
+
+    for function in functions.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &function.file_path) {
+            function.name.text = r.own_name(&function.name.text);
+            r.function(function, &[]);
+        }
+    }
+    for decl in structs.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &decl.file_path) {
+            r.struct_decl(decl);
+        }
+    }
+    for decl in interfaces.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &decl.file_path) {
+            decl.name.text = r.own_name(&decl.name.text);
+            let generics = generic_names(&decl.generic_parameters);
+            r.bounds(&mut decl.generic_bounds, &generics);
+            for method in decl.methods.iter_mut() {
+                r.function(method, &generics);
+            }
+        }
+    }
+    for decl in enums.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &decl.file_path) {
+            decl.name.text = r.own_name(&decl.name.text);
+            let generics = generic_names(&decl.generic_parameters);
+            for variant in decl.variants.iter_mut() {
+                for field in variant.fields.iter_mut() {
+                    r.field(field, &generics);
+                }
+            }
+        }
+    }
+    for decl in extends.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &decl.file_path) {
+            let generics = generic_names(&decl.generic_parameters);
+            if !generics.contains(&decl.target.text) {
+                if let Some(internal) = r.resolve(&decl.target) {
+                    decl.target.text = internal;
+                }
+            }
+            for method in decl.methods.iter_mut() {
+                r.function(method, &generics);
+            }
+        }
+    }
+    for global in globals.iter_mut() {
+        if let Some(r) = resolver(&mut resolvers, &global.file_path) {
+            global.name.text = r.own_name(&global.name.text);
+            if let Some(t) = global.declared_type.as_mut() {
+                r.type_(t);
+            }
+            global.initializer = r.expr(&global.initializer);
+        }
+    }
+
+    for file in graph.modules.keys() {
+        diagnostics.extend(&resolvers[file.as_str()].diagnostics);
+    }
+}
+
+/// The resolver of the module a declaration was loaded from, if it is a user module.
+fn resolver<'r, 'a, 'g>(
+    resolvers: &'r mut HashMap<&str, Resolver<'a, 'g>>,
+    path: &Option<Rc<str>>,
+) -> Option<&'r mut Resolver<'a, 'g>> {
+    resolvers.get_mut(path.as_deref()?)
+}
+
+/// The file tag of a declaration that belongs to one of `graph`'s modules.
+fn module_file(graph: &ModuleGraph, path: &Option<Rc<str>>) -> Option<Rc<str>> {
+    path.clone().filter(|p| graph.modules.contains_key(&**p))
+}
+
+fn generic_names(params: &Option<Vec<SyntaxToken>>) -> Vec<String> {
+    params
+        .iter()
+        .flatten()
+        .map(|p| p.text.clone())
+        .collect()
+}
+
+/// Name resolution for one module.
+struct Resolver<'a, 'g> {
+    graph: &'g ModuleGraph,
+    exports: &'g HashMap<String, IndexMap<String, Export>>,
+    file: &'g str,
+    arena: &'a Bump,
+    /// Source name -> internal name for the module's own declarations and its selective imports.
+    scope: HashMap<String, String>,
+    /// Files whose public declarations are in scope unqualified (plain `import a.b;`).
+    wildcards: Vec<&'g str>,
+    /// Qualifier -> the file of the module it names.
+    qualifiers: HashMap<String, &'g str>,
+    /// Local variables, parameters and pattern bindings, innermost block last. They shadow
+    /// top-level names and qualifiers.
+    locals: Vec<HashSet<String>>,
+    /// Generic parameters in scope; they shadow top-level type names.
+    generics: Vec<String>,
+    diagnostics: DiagnosticBag,
+}
+
+impl<'a, 'g> Resolver<'a, 'g> {
+    fn new(
+        graph: &'g ModuleGraph,
+        exports: &'g HashMap<String, IndexMap<String, Export>>,
+        file: &'g str,
+        module: &'g ModuleInfo,
+        arena: &'a Bump,
+    ) -> Self {
+        let mut diagnostics = DiagnosticBag::new(Some(file.to_string()));
+        let mut scope: HashMap<String, String> = exports
+            .get(file)
+            .map(|own| {
+                own.iter()
+                    .map(|(name, e)| (name.clone(), e.internal.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut wildcards = Vec::new();
+        let mut qualifiers: HashMap<String, &'g str> = HashMap::new();
+
+        for import in &module.imports {
+            let target = import.file.as_str();
+            let target_name = &graph.modules[target].name;
+            if let Some(names) = &import.node.names {
+                for name in names {
+                    match exports.get(target).and_then(|e| e.get(&name.text)) {
+                        Some(export) if export.is_public => {
+                            if scope.contains_key(&name.text) {
+                                diagnostics.report_error(
+                                    format!(
+                                        "Imported name '{}' conflicts with a declaration of the same name in this module",
+                                        name.text
+                                    ),
+                                    Some(name.position),
+                                );
+                            } else {
+                                scope.insert(name.text.clone(), export.internal.clone());
+                            }
+                        }
+                        Some(_) => diagnostics.report_error(
+                            format!("'{}' is private to module '{}'", name.text, target_name),
+                            Some(name.position),
+                        ),
+                        None => diagnostics.report_error(
+                            format!("Module '{}' has no declaration named '{}'", target_name, name.text),
+                            Some(name.position),
+                        ),
+                    }
+                }
+                continue;
+            }
+            if import.node.alias.is_none() && !wildcards.contains(&target) {
+                wildcards.push(target);
+            }
+            if let Some(qualifier) = import.node.qualifier() {
+                match qualifiers.get(qualifier) {
+                    Some(bound) if *bound != target => {
+                        let position = import.node.alias.as_ref().unwrap_or(&import.node.module_name).position;
+                        diagnostics.report_error(
+                            format!(
+                                "Module qualifier '{}' is already bound to module '{}'; import one of them with 'as'",
+                                qualifier, graph.modules[*bound].name
+                            ),
+                            Some(position),
+                        );
+                    }
+                    _ => {
+                        qualifiers.insert(qualifier.to_string(), target);
+                    }
+                }
+            }
+        }
+
+        Resolver {
+            graph,
+            exports,
+            file,
+            arena,
+            scope,
+            wildcards,
+            qualifiers,
+            locals: Vec::new(),
+            generics: Vec::new(),
+            diagnostics,
+        }
+    }
+
+    /// The internal name of one of this module's own declarations.
+    fn own_name(&self, name: &str) -> String {
+        self.exports
+            .get(self.file)
+            .and_then(|own| own.get(name))
+            .map_or_else(|| name.to_string(), |e| e.internal.clone())
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals.iter().rev().any(|scope| scope.contains(name))
+    }
+
+    fn declare_local(&mut self, name: &str) {
+        if let Some(scope) = self.locals.last_mut() {
+            scope.insert(name.to_string());
+        }
+    }
+
+    /// The module a (non-shadowed) qualifier names.
+    fn qualifier(&self, name: &str) -> Option<&'g str> {
+        if self.is_local(name) {
+            return None;
+        }
+        self.qualifiers.get(name).copied()
+    }
+
+    /// Resolves an unqualified reference to a top-level declaration. Returns the internal name, or
+    /// `None` for names no user module declares (locals, prelude types, builtins) and for
+    /// references reported as not visible here.
+    fn resolve(&mut self, token: &SyntaxToken) -> Option<String> {
+        let name = token.text.as_str();
+        if let Some(internal) = self.scope.get(name) {
+            return Some(internal.clone());
+        }
+        let (graph, exports) = (self.graph, self.exports);
+        let display = |file: &str| graph.modules[file].name.as_str();
+        let mut found: Vec<(&str, &Export)> = Vec::new();
+        let mut private_in = None;
+        for &file in &self.wildcards {
+            if let Some(export) = exports.get(file).and_then(|e| e.get(name)) {
+                if !export.is_public {
+                    private_in.get_or_insert(file);
+                } else if !found.iter().any(|(_, e)| e.internal == export.internal) {
+                    found.push((file, export));
+                }
+            }
+        }
+        let message = match found.as_slice() {
+            [(_, export)] => return Some(export.internal.clone()),
+            [(a, _), (b, _), ..] => format!(
+                "'{}' is ambiguous: it is declared in both module '{}' and module '{}'; qualify it or import it by name",
+                name,
+                display(a),
+                display(b)
+            ),
+            [] => match private_in {
+                Some(file) => format!("'{}' is private to module '{}'", name, display(file)),
+                None => {
+                    let declaring = graph
+                        .modules
+                        .keys()
+                        .find(|file| exports.get(*file).is_some_and(|e| e.contains_key(name)));
+                    match declaring {
+                        Some(file) => format!(
+                            "'{}' is declared in module '{}', which is not imported here",
+                            name,
+                            display(file)
+                        ),
+                        None => return None,
+                    }
+                }
+            },
+        };
+        self.diagnostics.report_error(message, Some(token.position));
+        None
+    }
+
+    /// Resolves `qualifier.name` against the module the qualifier names.
+    fn resolve_qualified(&mut self, file: &str, name: &SyntaxToken) -> Option<String> {
+        let module = &self.graph.modules[file].name;
+        let message = match self.exports.get(file).and_then(|e| e.get(&name.text)) {
+            Some(export) if export.is_public => return Some(export.internal.clone()),
+            Some(_) => format!("'{}' is private to module '{}'", name.text, module),
+            None => format!("Module '{}' has no declaration named '{}'", module, name.text),
+        };
+        self.diagnostics.report_error(message, Some(name.position));
+        None
+    }
+
+    /// A `qualifier.Name` expression collapsed to a plain identifier token naming the declaration.
+    fn qualified_token(&mut self, expr: &ExpressionNode<'a>) -> Option<SyntaxToken> {
+        let ExpressionNode::MemberAccess(receiver, name) = expr else {
+            return None;
+        };
+        let ExpressionNode::Identifier(qualifier) = receiver else {
+            return None;
+        };
+        let file = self.qualifier(&qualifier.text)?;
+        let mut token = name.clone();
+        if let Some(internal) = self.resolve_qualified(file, name) {
+            token.text = internal;
+        }
+        Some(token)
+    }
+
+    /// Resolves a callee / variable reference unless a local shadows it.
+    fn value_token(&mut self, token: &SyntaxToken) -> SyntaxToken {
+        let mut token = token.clone();
+        if !self.is_local(&token.text) {
+            if let Some(internal) = self.resolve(&token) {
+                token.text = internal;
+            }
+        }
+        token
+    }
+
+    fn type_(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Struct(token, args) => {
+                if let Some(args) = args {
+                    for arg in args.iter_mut() {
+                        self.type_(arg);
+                    }
+                }
+                if let Some((qualifier, name)) = token.text.rsplit_once('.') {
+                    let mut name_token = token.clone();
+                    name_token.text = name.to_string();
+                    match self.qualifiers.get(qualifier).copied() {
+                        Some(file) => {
+                            token.text = self
+                                .resolve_qualified(file, &name_token)
+                                .unwrap_or(name_token.text);
+                        }
+                        None => {
+                            let message = format!("Unknown module qualifier '{}'", qualifier);
+                            self.diagnostics.report_error(message, Some(token.position));
+                            token.text = name_token.text;
+                        }
+                    }
+                } else if !self.generics.contains(&token.text) {
+                    if let Some(internal) = self.resolve(token) {
+                        token.text = internal;
+                    }
+                }
+            }
+            Type::Array(inner) | Type::Nullable(inner) => self.type_(inner),
+            Type::Function(params, ret) => {
+                for p in params.iter_mut() {
+                    self.type_(p);
+                }
+                self.type_(ret);
+            }
+            _ => {}
+        }
+    }
+
+    fn bounds(&mut self, bounds: &mut [GenericBound], generics: &[String]) {
+        let saved = self.generics.len();
+        self.generics.extend(generics.iter().cloned());
+        for bound in bounds.iter_mut() {
+            for t in bound.bounds.iter_mut() {
+                self.type_(t);
+            }
+        }
+        self.generics.truncate(saved);
+    }
+
+    fn field(&mut self, field: &mut StructFieldNode, generics: &[String]) {
+        let saved = self.generics.len();
+        self.generics.extend(generics.iter().cloned());
+        self.type_(&mut field.field_type);
+        field.type_token.text = field.field_type.get_type();
+        self.generics.truncate(saved);
+    }
+
+    fn struct_decl(&mut self, decl: &mut StructDeclarationNode<'a>) {
+        decl.name.text = self.own_name(&decl.name.text);
+        let generics = generic_names(&decl.generic_parameters);
+        self.bounds(&mut decl.generic_bounds, &generics);
+        let saved = self.generics.len();
+        self.generics.extend(generics.iter().cloned());
+        for t in decl.implements.iter_mut() {
+            self.type_(t);
+        }
+        self.generics.truncate(saved);
+        for field in decl.fields.iter_mut() {
+            self.field(field, &generics);
+        }
+        for method in decl.methods.iter_mut() {
+            self.function(method, &generics);
+        }
+    }
+
+    /// Resolves a function's signature and body. `outer_generics` are the enclosing type's generic
+    /// parameters.
+    fn function(&mut self, function: &mut FunctionNode<'a>, outer_generics: &[String]) {
+        let saved = self.generics.len();
+        self.generics.extend(outer_generics.iter().cloned());
+        self.generics.extend(generic_names(&function.generic_parameters));
+        let generics = self.generics.clone();
+        self.bounds(&mut function.generic_bounds, &generics);
+        for param in function.parameters.iter_mut() {
+            self.type_(&mut param.type_);
+        }
+        if let Some(ret) = function.return_type.as_mut() {
+            self.type_(ret);
+        }
+        let mut scope: HashSet<String> =
+            function.parameters.iter().map(|p| p.name.text.clone()).collect();
+        scope.insert("this".to_string());
+        self.locals.push(scope);
+        function.body = self.statements(function.body);
+        self.locals.pop();
+        self.generics.truncate(saved);
+    }
+
+    fn block(&mut self, statements: &'a [StatementNode<'a>]) -> &'a [StatementNode<'a>] {
+        self.locals.push(HashSet::new());
+        let block = self.statements(statements);
+        self.locals.pop();
+        block
+    }
+
+    fn statements(&mut self, statements: &'a [StatementNode<'a>]) -> &'a [StatementNode<'a>] {
+        let resolved: Vec<StatementNode<'a>> = statements.iter().map(|s| self.statement(s)).collect();
+        self.arena.alloc_slice_fill_iter(resolved)
+    }
+
+    fn exprs(&mut self, exprs: &[ExpressionNode<'a>]) -> Vec<ExpressionNode<'a>> {
+        exprs.iter().map(|e| self.expr(e)).collect()
+    }
+
+    fn alloc_expr(&mut self, expr: &ExpressionNode<'a>) -> &'a ExpressionNode<'a> {
+        let resolved = self.expr(expr);
+        self.arena.alloc(resolved)
+    }
+
+    fn generic_args(&mut self, args: &Option<Vec<Type>>) -> Option<Vec<Type>> {
+        args.clone().map(|mut args| {
+            for t in args.iter_mut() {
+                self.type_(t);
+            }
+            args
+        })
+    }
+
+    fn statement(&mut self, statement: &StatementNode<'a>) -> StatementNode<'a> {
+        match statement {
+            StatementNode::Assignment(name, value) => {
+                let value = self.expr(value);
+                StatementNode::Assignment(self.value_token(name), value)
+            }
+            StatementNode::IndexAssignment(target, index, value) => StatementNode::IndexAssignment(
+                self.alloc_expr(target),
+                self.alloc_expr(index),
+                self.expr(value),
+            ),
+            StatementNode::MemberAssignment(target, member, value) => {
+                let value = self.expr(value);
+                if let ExpressionNode::Identifier(q) = target {
+                    if let Some(file) = self.qualifier(&q.text) {
+                        let mut name = member.clone();
+                        if let Some(internal) = self.resolve_qualified(file, member) {
+                            name.text = internal;
+                        }
+                        return StatementNode::Assignment(name, value);
+                    }
+                }
+                StatementNode::MemberAssignment(self.alloc_expr(target), member.clone(), value)
+            }
+            StatementNode::Declaration(name, annotation, value, is_const) => {
+                let value = self.expr(value);
+                let mut annotation = annotation.clone();
+                if let Some(t) = annotation.as_mut() {
+                    self.type_(t);
+                }
+                self.declare_local(&name.text);
+                StatementNode::Declaration(name.clone(), annotation, value, *is_const)
+            }
+            StatementNode::DestructuringDeclaration(pattern, value, is_const) => {
+                let value = self.expr(value);
+                let pattern = self.pattern(pattern);
+                StatementNode::DestructuringDeclaration(pattern, value, *is_const)
+            }
+            StatementNode::FunctionInvocation(name, generics, args) => {
+                StatementNode::FunctionInvocation(
+                    self.value_token(name),
+                    self.generic_args(generics),
+                    self.exprs(args),
+                )
+            }
+            StatementNode::MethodInvocation(receiver, name, generics, args) => {
+                let generics = self.generic_args(generics);
+                let args = self.exprs(args);
+                if let ExpressionNode::Identifier(q) = receiver {
+                    if let Some(file) = self.qualifier(&q.text) {
+                        let mut callee = name.clone();
+                        if let Some(internal) = self.resolve_qualified(file, name) {
+                            callee.text = internal;
+                        }
+                        return StatementNode::FunctionInvocation(callee, generics, args);
+                    }
+                }
+                StatementNode::MethodInvocation(self.alloc_expr(receiver), name.clone(), generics, args)
+            }
+            StatementNode::Return(value) => StatementNode::Return(value.as_ref().map(|v| self.expr(v))),
+            StatementNode::IfElse(cond, then, elifs, otherwise) => {
+                let cond = self.expr(cond);
+                let then = self.block(then);
+                let elifs = elifs
+                    .iter()
+                    .map(|(c, body)| (self.expr(c), self.block(body)))
+                    .collect();
+                let otherwise = otherwise.map(|body| self.block(body));
+                StatementNode::IfElse(cond, then, elifs, otherwise)
+            }
+            StatementNode::While(cond, body) => {
+                StatementNode::While(self.expr(cond), self.block(body))
+            }
+            StatementNode::DoWhile(body, cond) => {
+                let body = self.block(body);
+                StatementNode::DoWhile(body, self.expr(cond))
+            }
+            StatementNode::For(init, cond, step, body) => {
+                self.locals.push(HashSet::new());
+                let init = init.map(|s| &*self.arena.alloc(self.statement(s)));
+                let cond = cond.as_ref().map(|c| self.expr(c));
+                let step = step.map(|s| &*self.arena.alloc(self.statement(s)));
+                let body = self.block(body);
+                self.locals.pop();
+                StatementNode::For(init, cond, step, body)
+            }
+            StatementNode::Labeled(label, inner) => {
+                let inner = self.statement(inner);
+                StatementNode::Labeled(label.clone(), self.arena.alloc(inner))
+            }
+            StatementNode::Break(_) | StatementNode::Continue(_) => statement.clone(),
+            StatementNode::ExpressionStatement(e) => StatementNode::ExpressionStatement(self.expr(e)),
+            StatementNode::AwaitStmt(e) => StatementNode::AwaitStmt(self.expr(e)),
+            StatementNode::ForEach(var, iterable, index, length, body) => {
+                let iterable = self.expr(iterable);
+                self.locals.push(HashSet::from([
+                    var.text.clone(),
+                    index.clone(),
+                    length.clone(),
+                ]));
+                let body = self.block(body);
+                self.locals.pop();
+                StatementNode::ForEach(var.clone(), iterable, index.clone(), length.clone(), body)
+            }
+            StatementNode::Switch(subject, cases, default) => {
+                let subject = self.expr(subject);
+                let cases = cases
+                    .iter()
+                    .map(|(values, body)| (self.exprs(values), self.block(body)))
+                    .collect();
+                let default = default.map(|body| self.block(body));
+                StatementNode::Switch(subject, cases, default)
+            }
+            StatementNode::Throw(keyword, value) => StatementNode::Throw(keyword.clone(), self.expr(value)),
+            StatementNode::Try(keyword, body, catches, finally) => {
+                let body = self.block(body);
+                let catches = catches
+                    .iter()
+                    .map(|c| {
+                        let mut type_ = c.type_.clone();
+                        if let Some(t) = type_.as_mut() {
+                            self.type_(t);
+                        }
+                        self.locals.push(HashSet::from([c.name.text.clone()]));
+                        let body = self.block(c.body);
+                        self.locals.pop();
+                        CatchClause { name: c.name.clone(), type_, body }
+                    })
+                    .collect();
+                let finally = finally.map(|body| self.block(body));
+                StatementNode::Try(keyword.clone(), body, catches, finally)
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &ExpressionNode<'a>) -> ExpressionNode<'a> {
+        match expr {
+            ExpressionNode::Literal(_) => expr.clone(),
+            ExpressionNode::ArrayLiteral(elements) => ExpressionNode::ArrayLiteral(self.exprs(elements)),
+            ExpressionNode::Tuple(open, elements) => ExpressionNode::Tuple(open.clone(), self.exprs(elements)),
+            ExpressionNode::Binary(left, op, right) => {
+                ExpressionNode::Binary(self.alloc_expr(left), op.clone(), self.alloc_expr(right))
+            }
+            ExpressionNode::Unary(op, operand) => ExpressionNode::Unary(op.clone(), self.alloc_expr(operand)),
+            ExpressionNode::Identifier(name) => ExpressionNode::Identifier(self.value_token(name)),
+            ExpressionNode::Parenthesized(inner) => ExpressionNode::Parenthesized(self.alloc_expr(inner)),
+            ExpressionNode::FunctionCall(name, generics, args) => ExpressionNode::FunctionCall(
+                self.value_token(name),
+                self.generic_args(generics),
+                self.exprs(args),
+            ),
+            ExpressionNode::IndexAccess(target, index) => {
+                ExpressionNode::IndexAccess(self.alloc_expr(target), self.alloc_expr(index))
+            }
+            ExpressionNode::Cast(ty, value) => {
+                let mut ty = ty.clone();
+                self.type_(&mut ty);
+                ExpressionNode::Cast(ty, self.alloc_expr(value))
+            }
+            ExpressionNode::MemberAccess(receiver, member) => {
+                if let Some(token) = self.qualified_token(expr) {
+                    return ExpressionNode::Identifier(token);
+                }
+                ExpressionNode::MemberAccess(self.alloc_expr(receiver), member.clone())
+            }
+            ExpressionNode::IsExpression(value, ty, binding) => {
+                let value = self.alloc_expr(value);
+                let mut ty = ty.clone();
+                self.type_(&mut ty);
+                if let Some(binding) = binding {
+                    self.declare_local(&binding.text);
+                }
+                ExpressionNode::IsExpression(value, ty, binding.clone())
+            }
+            ExpressionNode::MethodCall(receiver, name, generics, args) => {
+                let generics = self.generic_args(generics);
+                let args = self.exprs(args);
+                if let ExpressionNode::Identifier(q) = receiver {
+                    if let Some(file) = self.qualifier(&q.text) {
+                        let mut callee = name.clone();
+                        if let Some(internal) = self.resolve_qualified(file, name) {
+                            callee.text = internal;
+                        }
+                        return ExpressionNode::FunctionCall(callee, generics, args);
+                    }
+                }
+                ExpressionNode::MethodCall(self.alloc_expr(receiver), name.clone(), generics, args)
+            }
+            ExpressionNode::Ternary(cond, then, otherwise) => ExpressionNode::Ternary(
+                self.alloc_expr(cond),
+                self.alloc_expr(then),
+                self.alloc_expr(otherwise),
+            ),
+            ExpressionNode::Await(inner) => ExpressionNode::Await(self.alloc_expr(inner)),
+            ExpressionNode::Propagate(inner, op) => ExpressionNode::Propagate(self.alloc_expr(inner), op.clone()),
+            ExpressionNode::Switch(subject, arms) => {
+                let subject = self.alloc_expr(subject);
+                let arms = arms.iter().map(|arm| self.switch_arm(arm)).collect();
+                ExpressionNode::Switch(subject, arms)
+            }
+            ExpressionNode::Lambda(lambda) => {
+                let mut params = lambda.parameters.clone();
+                for p in params.iter_mut() {
+                    if let Some(t) = p.type_.as_mut() {
+                        self.type_(t);
+                    }
+                }
+                let mut return_type = lambda.return_type.clone();
+                if let Some(t) = return_type.as_mut() {
+                    self.type_(t);
+                }
+                self.locals.push(params.iter().map(|p| p.name.text.clone()).collect());
+                let body = match &lambda.body {
+                    LambdaBody::Expr(e) => LambdaBody::Expr(self.alloc_expr(e)),
+                    LambdaBody::Block(b) => LambdaBody::Block(self.block(b)),
+                };
+                self.locals.pop();
+                ExpressionNode::Lambda(self.arena.alloc(LambdaNode {
+                    arrow: lambda.arrow.clone(),
+                    parameters: params,
+                    return_type,
+                    body,
+                }))
+            }
+        }
+    }
+
+    fn switch_arm(&mut self, arm: &SwitchArm<'a>) -> SwitchArm<'a> {
+        self.locals.push(HashSet::new());
+        let pattern = self.pattern(&arm.pattern);
+        let guard = arm.guard.as_ref().map(|g| self.expr(g));
+        let body = match &arm.body {
+            SwitchArmBody::Expr(e) => SwitchArmBody::Expr(self.alloc_expr(e)),
+            SwitchArmBody::Block(b) => SwitchArmBody::Block(self.block(b)),
+        };
+        self.locals.pop();
+        SwitchArm { pattern, guard, body }
+    }
+
+    /// Resolves the types a pattern names and declares its bindings in the current scope.
+    fn pattern(&mut self, pattern: &PatternNode) -> PatternNode {
+        match pattern {
+            PatternNode::Wildcard(_) | PatternNode::Literal(_) | PatternNode::Range(_, _) => {
+                pattern.clone()
+            }
+            PatternNode::Binding(name) => {
+                self.declare_local(&name.text);
+                pattern.clone()
+            }
+            PatternNode::Variant(owner, variant, subs) => {
+                let owner = owner.as_ref().map(|o| {
+                    let mut o = o.clone();
+                    if let Some(internal) = self.resolve(&o) {
+                        o.text = internal;
+                    }
+                    o
+                });
+                let subs = subs.iter().map(|p| self.pattern(p)).collect();
+                PatternNode::Variant(owner, variant.clone(), subs)
+            }
+            PatternNode::Tuple(open, subs) => {
+                PatternNode::Tuple(open.clone(), subs.iter().map(|p| self.pattern(p)).collect())
+            }
+            PatternNode::Or(alternatives) => {
+                PatternNode::Or(alternatives.iter().map(|p| self.pattern(p)).collect())
+            }
+            PatternNode::TypeTest(ty, binding) => {
+                let mut ty = ty.clone();
+                self.type_(&mut ty);
+                self.declare_local(&binding.text);
+                PatternNode::TypeTest(ty, binding.clone())
+            }
+            PatternNode::Class(ty, fields) => {
+                let mut ty = ty.clone();
+                self.type_(&mut ty);
+                let fields = fields
+                    .iter()
+                    .map(|(name, sub)| (name.clone(), self.pattern(sub)))
+                    .collect();
+                PatternNode::Class(ty, fields)
+            }
+        }
+    }
+}
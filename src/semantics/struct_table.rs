@@ -20,6 +20,8 @@ pub struct StructInfo {
     pub fields: IndexMap<String, StructFieldInfo>,
     pub size: usize,
     pub is_public: bool,
+    /// The class this one extends (`class Dog : Animal`), whose fields lead `fields`.
+    pub base: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,21 +44,50 @@ impl StructTable {
     }
 
     pub fn add_struct(&mut self, struct_decl: &StructDeclarationNode<'_>) -> Result<(), String> {
+        self.insert_struct(struct_decl, None)
+    }
+
+    /// Registers a class extending the already-registered class `base`: the base's fields come
+    /// first at their own offsets, followed by the class's own fields from the end of the base.
+    pub fn add_subclass(
+        &mut self,
+        struct_decl: &StructDeclarationNode<'_>,
+        base: &str,
+    ) -> Result<(), String> {
+        let base = self
+            .structs
+            .get(base)
+            .cloned()
+            .ok_or_else(|| format!("Base class '{}' is not defined", base))?;
+        self.insert_struct(struct_decl, Some(base))
+    }
+
+    fn insert_struct(
+        &mut self,
+        struct_decl: &StructDeclarationNode<'_>,
+        base: Option<StructInfo>,
+    ) -> Result<(), String> {
         let name = struct_decl.name.text.clone();
         if self.structs.contains_key(&name) {
             return Err(format!("Struct '{}' is already defined", name));
         }
 
-        let mut fields = IndexMap::new();
-        let mut current_offset = 0;
+        let mut fields = base.as_ref().map(|b| b.fields.clone()).unwrap_or_default();
+        let mut current_offset = base.as_ref().map_or(0, |b| b.size);
 
         for field in &struct_decl.fields {
             let field_name = field.name.text.clone();
             if fields.contains_key(&field_name) {
-                return Err(format!(
-                    "Field '{}' is already defined in class '{}'",
-                    field_name, name
-                ));
+                return Err(match &base {
+                    Some(b) if b.fields.contains_key(&field_name) => format!(
+                        "Field '{}' of class '{}' is already defined in base class '{}'",
+                        field_name, name, b.name
+                    ),
+                    _ => format!(
+                        "Field '{}' is already defined in class '{}'",
+                        field_name, name
+                    ),
+                });
             }
 
             // Use the structured type parsed by the parser, which preserves generic arguments
@@ -101,6 +132,7 @@ impl StructTable {
                 fields,
                 size: current_offset,
                 is_public: struct_decl.is_public,
+                base: base.map(|b| b.name),
             },
         );
 
@@ -122,6 +154,7 @@ impl StructTable {
                 fields: IndexMap::new(),
                 size,
                 is_public,
+                base: None,
             },
         );
         Ok(())
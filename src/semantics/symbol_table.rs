@@ -15,6 +15,9 @@ pub struct SymbolTable {
     const_symbols: HashSet<String>,
     parent: Option<Rc<RefCell<SymbolTable>>>,
     pub children: Vec<Rc<RefCell<SymbolTable>>>,
+    /// True for a lambda's parameter scope: names resolved past it belong to an enclosing function
+    /// and are captured by the lambda.
+    closure_boundary: bool,
 }
 
 impl SymbolTable {
@@ -24,6 +27,38 @@ impl SymbolTable {
             const_symbols: HashSet::new(),
             parent,
             children: Vec::new(),
+            closure_boundary: false,
+        }
+    }
+
+    /// Creates a lambda's parameter scope, nested in the scope the lambda appears in.
+    pub fn new_closure(parent: Rc<RefCell<SymbolTable>>) -> SymbolTable {
+        SymbolTable {
+            closure_boundary: true,
+            ..SymbolTable::new(Some(parent))
+        }
+    }
+
+    /// Returns true if `name` resolves, from this scope, to a local of an enclosing function across a
+    /// lambda boundary (i.e. a variable the lambda captures). Module globals are never captured.
+    pub fn is_captured(&self, name: &str) -> bool {
+        if self.symbols.contains_key(name) {
+            return false;
+        }
+        match self.parent {
+            Some(ref parent) if self.closure_boundary => parent.as_ref().borrow().resolves_local(name),
+            Some(ref parent) => parent.as_ref().borrow().is_captured(name),
+            None => false,
+        }
+    }
+
+    /// Returns true if `name` resolves to a function-local (non-global) binding from this scope.
+    fn resolves_local(&self, name: &str) -> bool {
+        match self.parent {
+            Some(ref parent) => {
+                self.symbols.contains_key(name) || parent.as_ref().borrow().resolves_local(name)
+            }
+            None => false,
         }
     }
 
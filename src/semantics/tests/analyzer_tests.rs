@@ -77,6 +77,7 @@ fn emit_hir_to_module_rc(code: &str) -> String {
     assert!(!diagnostics.has_errors(), "unexpected analysis errors");
     let interner = &analyzer.type_ctx.interner;
     let mut mir = crate::mir::lower::lower_program(&hir, interner);
+    crate::mir::unwind::insert_unwind_cleanup(&mut mir, interner);
     use crate::mir::passes::MirPass;
     for f in &mut mir.functions {
         crate::mir::passes::RcInsertion.run(f, interner);
@@ -95,63 +96,74 @@ fn run_and_capture_rc(code: &str, entry: &str) -> String {
 /// — allocator, string ABI, `*_to_string`, and deep release — for real, not just that it assembles.
 #[cfg(feature = "native")]
 fn run_wat(wat: &str, entry: &str) -> String {
+    run_wat_with_max_heap(wat, entry, None).expect("entry should run without trapping")
+}
+
+/// Like [`run_wat`], with the heap capped at `max_heap_bytes` (see `wasm_runner::set_max_heap`).
+/// A trap is returned as the error (out-of-memory and failed-check traps as their descriptive error).
+#[cfg(feature = "native")]
+fn run_wat_with_max_heap(wat: &str, entry: &str, max_heap_bytes: Option<u64>) -> Result<String, wasmtime::Error> {
+    use crate::execution::wasm_runner::{runtime_error, set_max_heap};
     use std::sync::{Arc, Mutex};
     use wasmtime::*;
 
     let wasm = wat::parse_str(wat).expect("module should assemble");
-    let engine = Engine::default();
+    let engine = crate::execution::wasm_runner::new_engine().unwrap();
     let module = Module::new(&engine, &wasm).expect("module should compile");
 
-    let out = Arc::new(Mutex::new(String::new()));
+    let out = Arc::new(Mutex::new(Vec::<u8>::new()));
     let mut store = Store::new(&engine, out.clone());
     let mut linker = Linker::new(&engine);
 
     linker
-        .func_wrap("env", "print_int", |c: Caller<'_, Arc<Mutex<String>>>, v: i32| {
-            c.data().lock().unwrap().push_str(&v.to_string());
+        .func_wrap("env", "print_int", |c: Caller<'_, Arc<Mutex<Vec<u8>>>>, v: i32| {
+            c.data().lock().unwrap().extend_from_slice(v.to_string().as_bytes());
         })
         .unwrap();
     linker
-        .func_wrap("env", "print_char", |c: Caller<'_, Arc<Mutex<String>>>, v: i32| {
-            if let Some(ch) = char::from_u32(v as u32) {
-                c.data().lock().unwrap().push(ch);
-            }
+        .func_wrap("env", "print_char", |c: Caller<'_, Arc<Mutex<Vec<u8>>>>, v: i32| {
+            c.data().lock().unwrap().push(v as u8);
         })
         .unwrap();
     linker
-        .func_wrap("env", "print_float", |c: Caller<'_, Arc<Mutex<String>>>, v: f32| {
-            c.data().lock().unwrap().push_str(&v.to_string());
+        .func_wrap("env", "print_float", |c: Caller<'_, Arc<Mutex<Vec<u8>>>>, v: f32| {
+            c.data().lock().unwrap().extend_from_slice(v.to_string().as_bytes());
         })
         .unwrap();
     linker
-        .func_wrap("env", "print_double", |c: Caller<'_, Arc<Mutex<String>>>, v: f64| {
-            c.data().lock().unwrap().push_str(&v.to_string());
+        .func_wrap("env", "print_double", |c: Caller<'_, Arc<Mutex<Vec<u8>>>>, v: f64| {
+            c.data().lock().unwrap().extend_from_slice(v.to_string().as_bytes());
         })
         .unwrap();
     linker
         .func_wrap(
             "env",
             "print_string",
-            |mut c: Caller<'_, Arc<Mutex<String>>>, ptr: i32| {
+            |mut c: Caller<'_, Arc<Mutex<Vec<u8>>>>, ptr: i32| {
                 let mem = c.get_export("memory").unwrap().into_memory().unwrap();
                 let data = mem.data(&c);
                 let mut end = ptr as usize;
                 while end < data.len() && data[end] != 0 {
                     end += 1;
                 }
-                let s = String::from_utf8_lossy(&data[ptr as usize..end]).into_owned();
-                c.data().lock().unwrap().push_str(&s);
+                let bytes = data[ptr as usize..end].to_vec();
+                c.data().lock().unwrap().extend_from_slice(&bytes);
             },
         )
         .unwrap();
 
     let instance = linker.instantiate(&mut store, &module).expect("module should instantiate");
+    if let Some(max) = max_heap_bytes {
+        set_max_heap(&instance, &mut store, max)?;
+    }
     let func = instance
         .get_typed_func::<(), ()>(&mut store, entry)
         .unwrap_or_else(|_| panic!("module should export `{}`", entry));
-    func.call(&mut store, ()).expect("entry should run without trapping");
-    let captured = out.lock().unwrap().clone();
-    captured
+    if let Err(trap) = func.call(&mut store, ()) {
+        return Err(runtime_error(&instance, &mut store).unwrap_or(trap));
+    }
+    let captured = String::from_utf8_lossy(&out.lock().unwrap()).into_owned();
+    Ok(captured)
 }
 
 /// Like [`emit_hir_to_wat`] but emits the full self-contained module (imports, memory, runtime,
@@ -861,10 +873,10 @@ fn exec_returned_value_transfers_ownership() {
     assert_eq!(run_and_capture_rc(&code, "main"), "57");
 }
 
-/// Hand-builds a two-function MIR that takes `add` as a first-class value and calls it indirectly:
-/// `fun main() { let f = add; print(f(2, 3)); }`. The analyzer does not yet emit function values, so
-/// this exercises the backend (FuncRef -> table index, function table + signature, `call_indirect`)
-/// directly. Returns the interner alongside so its `TypeId`s stay valid.
+/// Hand-builds a two-function MIR that wraps `add` in a capture-free closure and calls it
+/// indirectly: `fun main() { let f = add; print(f(2, 3)); }`. This exercises the backend directly
+/// (closure block holding the table index, function table + env-taking signature, `call_indirect`).
+/// Returns the interner alongside so its `TypeId`s stay valid.
 fn indirect_call_demo() -> (crate::mir::Mir, crate::types::TypeInterner) {
     use crate::mir::build::FunctionBuilder;
     use crate::mir::{BinOp, Callee, Const, Mir, Operand, Place, Rvalue, Statement, Terminator};
@@ -874,10 +886,12 @@ fn indirect_call_demo() -> (crate::mir::Mir, crate::types::TypeInterner) {
     let int = i.int();
     let void = i.void();
     let functy = i.func(vec![int, int], int);
+    let env = i.struct_ty(DefId(12), vec![]);
     let add_def = DefId(10);
 
     let mut ab = FunctionBuilder::new("add", int);
     ab.set_def(add_def, vec![]);
+    ab.new_param(env, Some("__env".into()));
     let a = ab.new_param(int, Some("a".into()));
     let b = ab.new_param(int, Some("b".into()));
     let t = ab.new_temp(int);
@@ -891,7 +905,14 @@ fn indirect_call_demo() -> (crate::mir::Mir, crate::types::TypeInterner) {
     mb.set_def(DefId(11), vec![]);
     let f = mb.new_local(functy, Some("f".into()));
     let r = mb.new_local(int, Some("r".into()));
-    mb.assign(Place::Local(f), Rvalue::FuncRef(Callee { def: add_def, args: vec![], ret: int }));
+    mb.assign(
+        Place::Local(f),
+        Rvalue::Closure {
+            func: Callee { def: add_def, args: vec![], ret: int },
+            env,
+            captures: vec![],
+        },
+    );
     mb.assign(
         Place::Local(r),
         Rvalue::IndirectCall {
@@ -911,8 +932,8 @@ fn test_indirect_call_emits_table_and_signature() {
     let wat = crate::mir::emit::emit_module(&mir, &interner, false);
     assert!(wat.contains("(table $__ft 2 funcref)"), "function table missing:\n{}", wat);
     assert!(wat.contains("(elem (i32.const 0) $add $main)"), "elem section missing:\n{}", wat);
-    assert!(wat.contains("(type $sig_i32_i32__i32"), "call_indirect signature missing:\n{}", wat);
-    assert!(wat.contains("(call_indirect $__ft (type $sig_i32_i32__i32))"), "indirect call missing:\n{}", wat);
+    assert!(wat.contains("(type $sig_i32_i32_i32__i32"), "call_indirect signature missing:\n{}", wat);
+    assert!(wat.contains("(call_indirect $__ft (type $sig_i32_i32_i32__i32))"), "indirect call missing:\n{}", wat);
     assert!(
         wat.contains("(export \"__indirect_function_table\" (table $__ft))"),
         "table export missing:\n{}",
@@ -931,8 +952,9 @@ fn exec_indirect_call_through_function_table() {
 
 #[test]
 fn test_hir_emission_first_class_function() {
-    // A bare function name is a value (`Binding::Func`), and calling a function-typed local emits an
-    // `IndirectCall` — both are now HIR-representable, so `main` stays in coverage.
+    // A bare function name is a value (a closure over its `__fnref_` thunk), and calling a
+    // function-typed local emits an `IndirectCall` — both are HIR-representable, so `main` stays in
+    // coverage.
     let code = format!(
         "{SYSTEM_STUB}
         fun add(a: int, b: int): int {{ return a + b; }}
@@ -940,7 +962,8 @@ fn test_hir_emission_first_class_function() {
     );
     let wat = emit_hir_to_module(&code);
     assert!(wat.contains("(call_indirect $__ft"), "indirect call not emitted:\n{}", wat);
-    assert!(wat.contains("funcref"), "function value not emitted:\n{}", wat);
+    assert!(wat.contains(";; closure fn def"), "function value not emitted:\n{}", wat);
+    assert!(wat.contains("(func $__fnref_add"), "function-value thunk not emitted:\n{}", wat);
 }
 
 #[cfg(feature = "native")]
@@ -956,11 +979,10 @@ fn exec_first_class_function_from_source() {
 }
 
 #[test]
-fn func_value_argument_is_not_reference_counted() {
-    // Documents how memory is managed when a function is passed as an argument: it isn't. A
-    // `fun(...)` value is a plain `i32` table index, not a heap reference, so the RC pass never
-    // retains or releases it. A `string` bound alongside it in the same scope still gets its normal
-    // reference-counting treatment — proving the distinction is real, not that RC is globally off.
+fn func_value_argument_is_reference_counted() {
+    // Documents how memory is managed when a function is passed as an argument: a `fun(...)` value
+    // is a pointer to a heap closure block (`[fn index][captures...]`), so the RC pass owns and
+    // releases it like any other reference, alongside the `string` bound in the same scope.
     let code = format!(
         "{SYSTEM_STUB}
         fun twice(x: int): int {{ return x * 2; }}
@@ -1020,9 +1042,9 @@ fn func_value_argument_is_not_reference_counted() {
         }
     }
 
-    assert_eq!(
-        func_value_rc, 0,
-        "a function value is a scalar table index and must never be retained/released:\n{:#?}",
+    assert!(
+        func_value_rc > 0,
+        "a function value is a heap closure and must be released:\n{:#?}",
         main
     );
     assert!(
@@ -1032,6 +1054,228 @@ fn func_value_argument_is_not_reference_counted() {
     );
 }
 
+#[test]
+fn test_hir_emission_lambda_closure() {
+    // A capturing lambda is emitted as its own `__lambda_N` function (taking its env first) and the
+    // creation site allocates the closure block holding the table index and the captured `n`.
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun main(): void {{
+            let n: int = 40;
+            let f: fun(int): int = (x) => x + n;
+            System.print(f(2));
+        }}"
+    );
+    let wat = emit_hir_to_module(&code);
+    assert!(wat.contains("(func $__lambda_0 (param $0 i32) (param $1 i32) (result i32)"), "lambda not emitted:\n{}", wat);
+    assert!(wat.contains(";; closure fn def"), "closure not allocated:\n{}", wat);
+    assert!(wat.contains("(call_indirect $__ft (type $sig_i32_i32__i32))"), "indirect call missing:\n{}", wat);
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_lambda_captures_by_value() {
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun apply(f: fun(int): int, v: int): int {{ return f(v); }}
+        fun main(): void {{
+            let n: int = 40;
+            let f: fun(int): int = (x) => x + n;
+            n = 0;
+            System.print(apply(f, 2));
+            System.print(apply((x) => x * n, 5));
+        }}"
+    );
+    assert_eq!(run_and_capture_rc(&code, "main"), "420");
+}
+
+#[test]
+fn test_lambda_parameter_type_inferred_from_context() {
+    let code = "fun apply(f: fun(int): int, v: int): int { return f(v); }
+        fun main(): void {
+            let f: fun(int): int = (x) => x + 1;
+            let r: int = apply((y) => y * 2, 3);
+        }";
+    let diagnostics = analyze_code(code);
+    assert_eq!(diagnostics.has_errors(), false);
+}
+
+#[test]
+fn test_lambda_parameter_type_must_be_inferable() {
+    let code = "fun main(): void { let f = (x) => x + 1; }";
+    let diagnostics = analyze_code(code);
+    assert!(diagnostics
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("cannot infer the type of lambda parameter 'x'")));
+}
+
+#[test]
+fn test_lambda_cannot_assign_captured_variable() {
+    let code = "fun main(): void {
+            let count: int = 0;
+            let bump: fun(): void = () => { count = count + 1; };
+        }";
+    let diagnostics = analyze_code(code);
+    assert!(diagnostics
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("Cannot assign to 'count' because it is captured by a lambda")
+            && d.message.contains("snapshot of 'count'")));
+}
+
+#[test]
+fn test_lambda_rejects_await() {
+    let code = "async fun work(): int { return 1; }
+        async fun main(): void {
+            let f: fun(): void = () => { let x = await work(); };
+        }";
+    let diagnostics = analyze_code(code);
+    assert!(diagnostics
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("'await' cannot be used inside a lambda")));
+}
+
+#[test]
+fn test_try_catch_typed_binding_and_all_paths_return() {
+    let code = "class Failure { public code: int; constructor(code: int) { this.code = code; } }
+        fun check(n: int): int {
+            try {
+                if (n < 0) { throw Failure(n); }
+                return n;
+            } catch (f: Failure) {
+                return f.code;
+            } catch (other) {
+                throw other;
+            } finally {
+                let done: int = 1;
+            }
+        }";
+    let diagnostics = analyze_code(code);
+    assert!(!diagnostics.has_errors(), "{:?}", diagnostics.diagnostics);
+}
+
+#[test]
+fn test_finally_rejects_return_and_escaping_break() {
+    let code = "fun main(): void {
+            while (true) {
+                try { } finally { break; }
+            }
+            try { } finally { return; }
+            try { } finally { while (true) { break; } }
+        }";
+    let diagnostics = analyze_code(code);
+    let count = |m: &str| diagnostics.diagnostics.iter().filter(|d| d.message == m).count();
+    // The `break` of a loop nested in the `finally` is fine.
+    assert_eq!(count("'break' and 'continue' cannot leave a 'finally' block"), 1);
+    assert_eq!(count("'return' cannot be used inside a 'finally' block"), 1);
+}
+
+#[test]
+fn test_throw_rejects_void_and_async() {
+    let code = "fun nothing(): void { }
+        fun main(): void { throw nothing(); }
+        async fun work(): int { try { throw 1; } catch (e) { } return 1; }";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("cannot throw a value of type void"));
+    assert!(has("'throw' and 'try' are not supported inside an 'async' function yet"));
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_uncaught_exception_is_runtime_error() {
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun fail(n: int): int {{
+            throw \"bad input \" + n;
+        }}
+        fun main(): void {{
+            try {{
+                fail(1);
+            }} catch (e: int) {{
+                System.print(0);
+            }}
+        }}"
+    );
+    let err = run_wat_with_max_heap(&emit_hir_to_module_rc(&code), "main", None)
+        .expect_err("an exception no clause catches should escape main");
+    assert_eq!(err.to_string(), "uncaught exception: bad input 1");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_string_index_out_of_range_is_runtime_error() {
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun main(): void {{
+            let s: string = \"abc\";
+            System.print(s[2]);
+            let i: int = 3;
+            System.print(s[i]);
+        }}"
+    );
+    let err = run_wat_with_max_heap(&emit_hir_to_module(&code), "main", None)
+        .expect_err("reading past the end of the string should trap");
+    assert_eq!(err.to_string(), "index out of bounds: index 3 is out of range for string of length 3");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_store_through_null_array_is_runtime_error() {
+    let code = format!(
+        "{SYSTEM_STUB}
+        class Bag {{
+            public items: int[];
+        }}
+        fun main(): void {{
+            let b: Bag = Bag();
+            b.items[0] = 1;
+        }}"
+    );
+    let err = run_wat_with_max_heap(&emit_hir_to_module(&code), "main", None)
+        .expect_err("indexing a zero-initialized (null) array field should trap");
+    assert_eq!(err.to_string(), "null dereference: indexing a null int[]");
+}
+
+/// Builds ever-longer strings without releasing any (no RC pass), so the heap must grow well past
+/// the initial 1 MiB of linear memory.
+#[cfg(feature = "native")]
+fn heap_hungry_program() -> String {
+    format!(
+        "{SYSTEM_STUB}
+        fun main(): void {{
+            let s: string = \"\";
+            let i: int = 0;
+            while (i < 1000) {{
+                s = s + \"abcdefgh\";
+                i = i + 1;
+            }}
+            System.print(i);
+        }}"
+    )
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_allocator_grows_linear_memory() {
+    assert_eq!(run_and_capture(&heap_hungry_program(), "main"), "1000");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_allocation_past_max_heap_is_out_of_memory() {
+    let wat = emit_hir_to_module(&heap_hungry_program());
+    let err = run_wat_with_max_heap(&wat, "main", Some(2 << 20)).expect_err("heap limit should be hit");
+    assert!(
+        err.to_string().contains("out of memory: could not allocate")
+            && err.to_string().contains("heap limit 2097152 bytes"),
+        "unexpected error: {}",
+        err
+    );
+}
+
 #[cfg(feature = "native")]
 #[test]
 fn exec_print_escapes_in_string_literal() {
@@ -1993,6 +2237,64 @@ fn test_interface_call_emits_dynamic_dispatch() {
     );
 }
 
+#[test]
+fn test_interface_default_method_inherited() {
+    // A class that omits a defaulted method inherits its own copy, callable directly and through
+    // the interface; an override replaces it.
+    let code = "
+        interface Shape {
+            fun area(): int;
+            fun describe(): string { return \"area \" + this.area().to_string(); }
+        }
+        class Square : Shape {
+            public fun area(): int { return 4; }
+        }
+        class Circle : Shape {
+            public fun area(): int { return 3; }
+            public fun describe(): string { return \"round\"; }
+        }
+        fun show(s: Shape): string { return s.describe(); }
+        fun run(): string { return show(Square()) + Square().describe() + show(Circle()); }
+    ";
+    let diagnostics = analyze_code(code);
+    assert_eq!(diagnostics.has_errors(), false, "{:?}", diagnostics.diagnostics);
+    let (wat, _) = emit_hir_to_wat(code);
+    assert!(wat.contains("(func $Square_describe"), "inherited copy should be emitted:\n{}", wat);
+}
+
+#[test]
+fn test_interface_default_methods_conflict() {
+    let code = "
+        interface A { fun label(): string { return \"a\"; } }
+        interface B { fun label(): string { return \"b\"; } }
+        class C : A, B { }
+    ";
+    let diagnostics = analyze_code(code);
+    assert!(diagnostics.diagnostics.iter().any(|d| d.message
+        == "class 'C' inherits default method 'label' from both 'A' and 'B'; define it in the class"));
+}
+
+#[test]
+fn test_interface_default_body_errors_reported_once() {
+    // A default body may only use the interface's own methods on `this`; the misuse is reported
+    // at the interface, not once per implementing class.
+    let code = "
+        interface Sized {
+            fun count(): int;
+            fun doubled(): int { return this.size * 2; }
+        }
+        class Bag : Sized { public size: int; public fun count(): int { return 0; } }
+        class Pile : Sized { public size: int; public fun count(): int { return 1; } }
+    ";
+    let diagnostics = analyze_code(code);
+    let errors: Vec<_> = diagnostics
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("has no field 'size'"))
+        .collect();
+    assert_eq!(errors.len(), 1, "{:?}", diagnostics.diagnostics);
+}
+
 #[test]
 fn test_generic_interface_monomorphized_ok() {
     // A generic class implementing a generic interface analyzes cleanly, and a call on the
@@ -2043,6 +2345,100 @@ fn test_generic_interface_signature_mismatch_errors() {
             || d.message.contains("does not implement method")));
 }
 
+#[test]
+fn test_generic_bounds_satisfied_ok() {
+    // Inline and `where` bounds let the template call interface methods on `T`, and a class
+    // implementing every bound satisfies them at the call site.
+    let code = "
+        interface Comparable<T> { fun compare_to(other: T): int; }
+        interface Named { fun name(): string; }
+        class Version : Comparable<Version>, Named {
+            public major: int;
+            constructor(major: int) { this.major = major; }
+            public fun compare_to(other: Version): int { return this.major - other.major; }
+            public fun name(): string { return this.major.to_string(); }
+        }
+        fun max<T: Comparable<T>>(a: T, b: T): T {
+            if (a.compare_to(b) >= 0) { return a; }
+            return b;
+        }
+        fun label<T>(a: T, b: T): string where T: Comparable<T> + Named {
+            return max(a, b).name();
+        }
+        fun run(): string { return label(Version(1), Version(2)); }
+    ";
+    let diagnostics = analyze_code(code);
+    assert_eq!(diagnostics.has_errors(), false);
+}
+
+#[test]
+fn test_generic_bound_unsatisfied_errors_at_call_site() {
+    let code = "
+        interface Comparable<T> { fun compare_to(other: T): int; }
+        fun max<T: Comparable<T>>(a: T, b: T): T {
+            if (a.compare_to(b) >= 0) { return a; }
+            return b;
+        }
+        fun run(): int { return max(1, 2); }
+    ";
+    let diagnostics = analyze_code(code);
+    assert_eq!(diagnostics.has_errors(), true);
+    assert!(diagnostics.diagnostics.iter().any(|d| d.message
+        == "Type 'int' does not satisfy the bound 'Comparable<int>' on type parameter 'T' of 'max'"));
+}
+
+#[test]
+fn test_generic_bound_body_errors_reported_once() {
+    // A bounded template is checked once against its bounds, so a misuse of `T` in the body is
+    // reported a single time no matter how many instantiations exist.
+    let code = "
+        interface Sized { fun count(): int; }
+        class Bag : Sized { public fun count(): int { return 0; } }
+        class Pile : Sized { public fun count(): int { return 1; } }
+        fun bad<T: Sized>(value: T): int { return value.size; }
+        fun run(): int { return bad(Bag()) + bad(Pile()); }
+    ";
+    let diagnostics = analyze_code(code);
+    let errors: Vec<_> = diagnostics
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("has no field 'size'"))
+        .collect();
+    assert_eq!(errors.len(), 1, "{:?}", diagnostics.diagnostics);
+}
+
+#[test]
+fn test_generic_bound_must_be_interface() {
+    let code = "
+        class Plain { }
+        fun wrap<T>(value: T): T where T: Plain, U: Plain { return value; }
+    ";
+    let diagnostics = analyze_code(code);
+    let messages: Vec<_> = diagnostics.diagnostics.iter().map(|d| d.message.clone()).collect();
+    assert!(messages.contains(&"Bound 'Plain' on type parameter 'T' is not an interface".to_string()));
+    assert!(messages.contains(&"'U' is not a type parameter of 'wrap'".to_string()));
+}
+
+#[test]
+fn test_async_method_on_generic_class_ok() {
+    // Each instantiation of a generic class gets its own async method, typed over the concrete
+    // argument.
+    let code = "
+        class Box<T> {
+            value: T;
+            constructor(value: T) { this.value = value; }
+            public async fun get(): T { return this.value; }
+        }
+        async fun run(): string {
+            let n = await Box<int>(1).get();
+            let s = await Box<string>(\"x\").get();
+            return s + n.to_string();
+        }
+    ";
+    let diagnostics = analyze_code(code);
+    assert_eq!(diagnostics.has_errors(), false, "{:?}", diagnostics.diagnostics);
+}
+
 #[test]
 fn test_async_interface_method_ok() {
     // An async interface method implemented by an async class method analyzes cleanly; calling it
@@ -2166,3 +2562,555 @@ fn test_default_param_rejected_after_required_at_analysis() {
     let diagnostics = analyze_code(code);
     assert_eq!(diagnostics.has_errors(), true);
 }
+
+#[test]
+fn test_propagate_operator_accepts_option_and_result() {
+    let code = "enum Option<T> { Some(value: T), None }
+        enum Result<T, E> { Ok(value: T), Err(error: E) }
+        fun half(n: int): Result<int, string> {
+            if (n % 2 == 1) { return Result.Err(\"odd\"); }
+            return Result.Ok(n / 2);
+        }
+        fun quarter(n: int): Result<int, string> {
+            half(n)?;
+            return Result.Ok(half(half(n)?)?);
+        }
+        fun head(items: int[]): Option<int> {
+            if (items.size() == 0) { return Option.None; }
+            return Option.Some(items[0]);
+        }
+        fun head_plus_one(items: int[]): Option<int> {
+            return Option.Some(head(items)? + 1);
+        }";
+    let diagnostics = analyze_code(code);
+    assert!(!diagnostics.has_errors(), "{:?}", diagnostics.diagnostics);
+}
+
+#[test]
+fn test_propagate_operator_rejects_mismatched_contexts() {
+    let code = "enum Option<T> { Some(value: T), None }
+        enum Result<T, E> { Ok(value: T), Err(error: E) }
+        fun half(n: int): Result<int, string> { return Result.Ok(n / 2); }
+        fun plain(n: int): int { return half(n)?; }
+        fun other(n: int): Result<int, int> { return Result.Ok(half(n)?); }
+        fun number(n: int): Option<int> { return Option.Some(n?); }
+        fun looped(n: int): Result<int, string> {
+            while (half(n)? > 0) { }
+            let ok = n > 0 && half(n)? > 0;
+            return Result.Ok(n);
+        }";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("can only be used in a function that returns a Result, but 'plain' returns int"));
+    assert!(has("cannot return the string error of Result<int, string> from 'other'"));
+    assert!(has("needs an Option or Result value, got int"));
+    assert!(has("cannot be used in a loop condition"));
+    assert!(has("cannot be used in the right operand of '&&'"));
+}
+
+#[test]
+fn test_test_attribute_requires_a_plain_free_function() {
+    let code = "
+        @test fun takes(x: int) {}
+        @test fun returns(): int { return 1; }
+        @test fun generic<T>() {}
+        @test fun ok() {}
+        class C {
+            @test fun method() {}
+        }
+    ";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("test function 'takes' must not declare parameters"));
+    assert!(has("test function 'returns' must not return a value"));
+    assert!(has("test function 'generic' cannot be generic"));
+    assert!(has("'@test' can only be applied to free functions, not method 'method'"));
+    assert!(!has("'ok'"));
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_test_functions_are_exported_in_declaration_order() {
+    let code = format!(
+        "{SYSTEM_STUB}
+        @test fun passes(): void {{ System.print(1); }}
+        @test fun fails(): void {{
+            let xs = [1];
+            System.print(xs[3]);
+        }}"
+    );
+    let wat = emit_hir_to_module(&code);
+    let passes = wat.find("(export \"__dream_test_passes\")").expect("missing test export");
+    let fails = wat.find("(export \"__dream_test_fails\")").expect("missing test export");
+    assert!(passes < fails, "tests must be exported in declaration order");
+    assert_eq!(run_wat(&wat, "__dream_test_passes"), "1");
+    let err = run_wat_with_max_heap(&wat, "__dream_test_fails", None)
+        .expect_err("a failing check should trap the test");
+    assert!(err.to_string().starts_with("index out of bounds"), "{}", err);
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_generic_static_method_call() {
+    // A generic static method monomorphizes per call like a generic free function, so the caller
+    // stays in HIR and each instance is emitted.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class K {{
+            public static fun id<T>(x: T): T {{ return x; }}
+        }}
+        fun main(): void {{
+            System.print(K.id(3));
+            System.print(K.id<bool>(true));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "3true");
+}
+
+#[test]
+fn test_tuple_misuse_is_reported() {
+    let code = "
+        fun main(): void {
+            let p = (1, \"a\");
+            p.0 = 2;
+            let (1, x) = p;
+            let (a, b, c) = p;
+            let d = p.2;
+            let n = (1, null);
+        }
+    ";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("Cannot assign to element 0 of '(int, string)': tuple elements are read-only"));
+    assert!(has("A destructuring 'let' must always match"));
+    assert!(has("Tuple pattern with 3 elements cannot match a value of type '(int, string)'"));
+    assert!(has("Tuple type '(int, string)' has no element 2"));
+    assert!(has("Cannot infer the type of a 'null' tuple element"));
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_tuple_destructuring_switch_and_equality() {
+    // Tuples compare by value (`==` on their elements, strings by content), destructure in `let`
+    // and `for`, and match positionally in a pattern `switch`.
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun divmod(a: int, b: int): (int, int) {{ return (a / b, a % b); }}
+        fun main(): void {{
+            let (q, r) = divmod(17, 5);
+            System.print(q);
+            System.print(r);
+            System.print((1, \"a\") == (1, \"a\" + \"\"));
+            for (let (x, (y, _)) in [(1, (2, 3)), (4, (4, 0))]) {{
+                let s = switch ((x, y)) {{
+                    (1, v) => v,
+                    (u, v) if u == v => 0,
+                    _ => -1,
+                }};
+                System.print(s);
+            }}
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "32true20");
+}
+
+#[test]
+fn test_switch_pattern_coverage_is_reported() {
+    let code = "
+        interface Shape { fun area(): int; }
+        class Circle : Shape { public r: int; fun area(): int { return this.r; } }
+        class Rect : Shape { public w: int; _h: int; fun area(): int { return this.w; } }
+        enum Tok { Num(value: int), Plus, Minus }
+        fun a(s: Shape): int { return switch (s) { Circle c => c.r }; }
+        fun b(t: Tok): int { return switch (t) { Plus | Minus => 1, Num(0..=9) => 2 }; }
+        fun c(n: int): int { return switch (n) { 1..=9 => 1, 3 => 2, 5 | 11 => 3, _ => 4 }; }
+        fun d(p: (bool, bool)): int { return switch (p) { (true, _) => 1, (false, true) => 2 }; }
+        fun e(s: Shape): int { return switch (s) { Rect { _h: 0 } => 1, _ => 2 }; }
+        fun f(n: int): int { return switch (n) { 9..=1 => 1, x | 2 => 2, _ => 3 }; }
+        fun g(o: object): int { return switch (o) { Shape s => 1, _ => 2 }; }
+    ";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("Non-exhaustive switch on 'Shape': pattern(s) Rect _ not covered"));
+    assert!(has("Non-exhaustive switch on 'Tok': pattern(s) Num(-2147483648..=-1), Num(10..=2147483647)"));
+    assert!(has("Non-exhaustive switch on '(bool, bool)': pattern(s) (false, false) not covered"));
+    assert!(has("Unreachable switch arm: earlier arms already match every value this pattern matches"));
+    assert!(has("Unreachable pattern alternative"));
+    assert!(has("'_h' is private to 'Rect'"));
+    assert!(has("Range pattern is empty"));
+    assert!(has("Or-pattern alternatives cannot bind names"));
+    assert!(has("Type pattern 'Shape' names an interface"));
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_switch_range_or_type_and_class_patterns() {
+    // Ranges and or-patterns test integers and chars; type and class patterns narrow an
+    // interface-typed subject to its implementing classes and match their fields.
+    let code = format!(
+        "{SYSTEM_STUB}
+        interface Shape {{ fun size(): int; }}
+        class Circle : Shape {{
+            public r: int;
+            constructor(r: int) {{ this.r = r; }}
+            fun size(): int {{ return this.r; }}
+        }}
+        class Rect : Shape {{
+            public w: int;
+            public h: int;
+            constructor(w: int, h: int) {{ this.w = w; this.h = h; }}
+            fun size(): int {{ return this.w * this.h; }}
+        }}
+        fun grade(n: int): int {{
+            return switch (n) {{ 0 => 0, 1..=9 => 1, 10 | 20 | 30 => 2, _ => 3 }};
+        }}
+        fun letter(c: char): bool {{
+            return switch (c) {{ 'a'..='z' | 'A'..='Z' => true, _ => false }};
+        }}
+        fun measure(s: Shape): int {{
+            return switch (s) {{
+                Rect {{ w: 0 }} | Rect {{ h: 0 }} => 0,
+                Rect {{ w, h }} if w == h => w,
+                Rect r => r.w + r.h,
+                Circle c => c.r * 100,
+            }};
+        }}
+        fun main(): void {{
+            System.print(grade(0));
+            System.print(grade(7));
+            System.print(grade(20));
+            System.print(grade(11));
+            System.print(letter('q'));
+            System.print(letter('4'));
+            System.print(measure(Rect(0, 3)));
+            System.print(measure(Rect(2, 2)));
+            System.print(measure(Rect(2, 3)));
+            System.print(measure(Circle(4)));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "0123truefalse025400");
+}
+
+#[test]
+fn test_operator_overloading_errors() {
+    let code = "
+        class P {
+            public x: int;
+            public fun op_lt(o: P): int { return 0; }
+            public fun op_add(a: P, b: P): P { return a; }
+            public static fun op_mul(o: P): P { return o; }
+        }
+        fun f(a: P): void {
+            let lt = a < a;
+            let add = a + a;
+            let mul = a * a;
+            let sub = a - a;
+            let neg = -a;
+            let same = a == a;
+        }
+    ";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("'op_lt' must return bool to overload '<', not 'int'"));
+    assert!(has("Operator '+' on 'P' cannot use its 'op_add': 'op_add' must take 1 argument(s)"));
+    assert!(has("'op_mul' must be a non-static instance method"));
+    assert!(has("Operator '-' is not defined for 'P' (define 'op_sub' to overload it)"));
+    assert!(has("Operator '-' is not defined for 'P' (define 'op_neg' to overload it)"));
+    assert_eq!(diagnostics.diagnostics.len(), 5);
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_operator_overloading() {
+    // Operators on a class call its `op_*` methods; `!=` negates `op_eq`, compound assignment
+    // reuses `op_add`, and a class without `op_eq` keeps reference equality.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class V {{
+            public x: int;
+            public y: int;
+            constructor(x: int, y: int) {{ this.x = x; this.y = y; }}
+            public fun op_add(o: V): V {{ return V(this.x + o.x, this.y + o.y); }}
+            public fun op_mul(k: int): V {{ return V(this.x * k, this.y * k); }}
+            public fun op_neg(): V {{ return V(-this.x, -this.y); }}
+            public fun op_eq(o: V): bool {{ return (this.x == o.x) && (this.y == o.y); }}
+            public fun op_lt(o: V): bool {{ return this.x + this.y < o.x + o.y; }}
+            public fun op_index(i: int): int {{ return i == 0 ? this.x : this.y; }}
+        }}
+        class Plain {{
+            public n: int;
+            constructor(n: int) {{ this.n = n; }}
+        }}
+        fun main(): void {{
+            let a = V(1, 2);
+            let b = a + V(3, 4) * 2;
+            System.print(b.x);
+            System.print(b.y);
+            b += a;
+            System.print(b[0]);
+            System.print((-a)[1]);
+            System.print(a == V(1, 2));
+            System.print(a != V(1, 2));
+            System.print(a < b);
+            let p = Plain(1);
+            System.print(p == Plain(1));
+            System.print(p == p);
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "7108-2truefalsetruefalsetrue");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_operator_eq_with_nullable_operands() {
+    // `==`/`!=` reach `op_eq` through a nullable operand on either side: both orders agree, two
+    // nulls are equal, and a null never equals a value.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class V {{
+            public x: int;
+            constructor(x: int) {{ this.x = x; }}
+            public fun op_eq(o: V): bool {{ return this.x == o.x; }}
+        }}
+        fun main(): void {{
+            let b = V(1);
+            let c: V? = V(1);
+            let n: V? = null;
+            let m: V? = null;
+            System.print(b == c);
+            System.print(c == b);
+            System.print(c != b);
+            System.print(n == b);
+            System.print(b == n);
+            System.print(n == m);
+            System.print(n != m);
+            System.print(c == n);
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "truetruefalsefalsefalsetruefalsefalse");
+}
+
+#[test]
+fn test_inheritance_errors() {
+    let code = "
+        class A {
+            constructor(n: int) {}
+            public virtual fun speak(): string { return \"a\"; }
+            public fun plain(): int { return 0; }
+        }
+        class NoCtor : A {}
+        class Missing : A {
+            constructor() {}
+        }
+        class B : A {
+            constructor() { super(1); }
+            public fun speak(): string { return \"b\"; }
+            public fun plain(): int { return 1; }
+            @override
+            public fun other(): int { return 2; }
+        }
+        class C : A {
+            constructor() { super(1); }
+            @override
+            public fun speak(): int { return 3; }
+        }
+        class Loop1 : Loop2 {}
+        class Loop2 : Loop1 {}
+        virtual fun f(): void {
+            super(1);
+        }
+    ";
+    let diagnostics = analyze_code(code);
+    let has = |m: &str| diagnostics.diagnostics.iter().any(|d| d.message.contains(m));
+    assert!(has("class 'NoCtor' must declare a constructor that calls 'super(...)'"));
+    assert!(has("constructor of 'Missing' must begin with 'super(...)'"));
+    assert!(has("method 'speak' overrides a virtual method of 'A'; mark it with '@override'"));
+    assert!(has("method 'plain' hides non-virtual method 'plain' of base class 'A'"));
+    assert!(has("method 'other' is marked '@override' but 'A' has no virtual method 'other'"));
+    assert!(has("method 'speak' does not match the signature of the virtual method it overrides in 'A'"));
+    assert!(has("the inheritance chain cycles back to"));
+    assert!(has("'virtual' can only be applied to class methods, not function 'f'"));
+    assert!(has("'super(...)' can only be called as the first statement of a subclass constructor"));
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_class_inheritance() {
+    // Virtual calls dispatch on the runtime class, `super.m()` calls the base version statically,
+    // and `is` / type patterns match subclasses.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class Animal {{
+            public legs: int;
+            constructor(legs: int) {{ this.legs = legs; }}
+            public virtual fun sound(): int {{ return 0; }}
+            public fun total(): int {{ return this.legs * 10 + this.sound(); }}
+        }}
+        class Dog : Animal {{
+            constructor() {{ super(4); }}
+            @override
+            public fun sound(): int {{ return 1; }}
+        }}
+        class Puppy : Dog {{
+            @override
+            public fun sound(): int {{ return super.sound() + 1; }}
+        }}
+        fun kind(a: Animal): int {{
+            return switch (a) {{
+                Puppy _ => 3,
+                Dog _ => 2,
+                Animal _ => 1,
+            }};
+        }}
+        fun main(): void {{
+            let a: Animal = Animal(2);
+            let d: Animal = Dog();
+            let p: Animal = Puppy();
+            System.print(a.total());
+            System.print(d.total());
+            System.print(p.total());
+            System.print(p is Dog);
+            System.print(d is Puppy);
+            System.print(kind(a));
+            System.print(kind(d));
+            System.print(kind(p));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "204142truefalse123");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_generic_method_infers_lambda_types() {
+    // A method's own type parameters are inferred like a generic function's, and a lambda argument
+    // takes its parameter types from the instantiated signature, so none needs annotations.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class Box<T> {{
+            public value: T;
+            constructor(value: T) {{ this.value = value; }}
+            public fun map<U>(f: fun(T): U): Box<U> {{ return Box<U>(f(this.value)); }}
+        }}
+        class Util {{
+            public static fun apply<A>(a: A, f: fun(A): A): A {{ return f(a); }}
+        }}
+        fun twice<T>(x: T, f: fun(T): T): T {{ return f(f(x)); }}
+        fun main(): void {{
+            let b = Box<int>(20);
+            let s = b.map((x) => x + 1).map((x) => x * 2).map((x) => x > 40);
+            System.print(s.value);
+            System.print(twice(3, (x) => x * x));
+            System.print(Util.apply(5, (x) => x - 1));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "true814");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_constructor_lambda_args_infer_types() {
+    // A lambda passed to a constructor takes its parameter types from the constructor's signature,
+    // with a generic class's type arguments substituted.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class Ranker<T> {{
+            compare: fun(T, T): int;
+            constructor(compare: fun(T, T): int) {{ this.compare = compare; }}
+            public fun first(a: T, b: T): T {{
+                let compare = this.compare;
+                if (compare(a, b) <= 0) {{ return a; }}
+                return b;
+            }}
+        }}
+        class Scaler {{
+            public f: fun(int): int;
+            constructor(f: fun(int): int) {{ this.f = f; }}
+        }}
+        fun main(): void {{
+            let low = Ranker<int>((a, b) => a - b);
+            let high = Ranker<int>((a, b) => b - a);
+            System.print(low.first(3, 8));
+            System.print(high.first(3, 8));
+            let s = Scaler((x) => x * 10);
+            let f = s.f;
+            System.print(f(4));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "3840");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_generic_method_infers_from_lambda_returning_instance() {
+    // A method type parameter that only appears inside a generic type the lambda returns
+    // (`fun(T): Pair<U, E>`) is read off the lambda's result, with the class's own `E` in place.
+    let code = format!(
+        "{SYSTEM_STUB}
+        class Pair<A, B> {{
+            public first: A;
+            public second: B;
+            constructor(first: A, second: B) {{ this.first = first; this.second = second; }}
+        }}
+        class Tagged<T, E> {{
+            public value: T;
+            public tag: E;
+            constructor(value: T, tag: E) {{ this.value = value; this.tag = tag; }}
+            public fun then<U>(f: fun(T): Pair<U, E>): Pair<U, E> {{ return f(this.value); }}
+        }}
+        fun main(): void {{
+            let t = Tagged<int, string>(6, \"a\");
+            let p = t.then((x) => Pair<bool, string>(x > 5, \"b\"));
+            System.print(p.first);
+            System.print(p.second);
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "trueb");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_primitive_argument_boxes_into_object_parameter() {
+    // A primitive passed to an `object` parameter is boxed at the call site — for free functions,
+    // static methods (overloaded ones included), instance methods, and constructors alike — so the
+    // callee can dispatch on its runtime type.
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun show(o: object): string {{ return o.to_string(); }}
+        class Fmt {{
+            public label: string;
+            constructor(o: object) {{ this.label = o.to_string(); }}
+            public static fun one(o: object): string {{ return o.to_string(); }}
+            public static fun pick(o: object, b: object): string {{ return b.to_string(); }}
+            public static fun pick(o: object): string {{ return o.to_string(); }}
+            public fun with(o: object): string {{ return this.label + o.to_string(); }}
+        }}
+        fun main(): void {{
+            System.print(show(1));
+            System.print(Fmt.one(2.5d));
+            System.print(Fmt.pick('c'));
+            System.print(Fmt.pick(1, true));
+            System.print(Fmt(3).with(4L));
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "12.5ctrue34");
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn exec_string_literal_as_object_survives_loop() {
+    // A string literal viewed as `object`, and the string its `to_string()` returns, each hold their
+    // own reference: rebinding them every iteration must not release the shared literal block.
+    let code = format!(
+        "{SYSTEM_STUB}
+        fun main(): void {{
+            let i = 0;
+            while (i < 3) {{
+                let o: object = \"x\";
+                let s = o.to_string();
+                System.print(s);
+                i = i + 1;
+            }}
+        }}"
+    );
+    assert_eq!(run_and_capture(&code, "main"), "xxx");
+}
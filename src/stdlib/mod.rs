@@ -5,14 +5,17 @@ use crate::syntax::nodes::Type;
 /// language service, so the two can never drift. The primitive files (int/char/string/...)
 /// only attach methods to built-in types, so their relative order does not matter.
 pub const PRELUDE_FILES: &[(&str, &str)] = &[
-    // Core intrinsic-backed types: raw arrays, `Option`/`Result`, futures, JS interop, math.
+    // Core intrinsic-backed types: raw arrays, `Option`/`Result`, futures, JS interop, math, plus the
+    // `Exception` class `throw` conventionally raises.
     ("<std>/core/array.dream", include_str!("core/array.dream")),
     ("<std>/core/option.dream", include_str!("core/option.dream")),
     ("<std>/core/result.dream", include_str!("core/result.dream")),
     ("<std>/core/promise.dream", include_str!("core/promise.dream")),
     ("<std>/core/jsref.dream", include_str!("core/jsref.dream")),
     ("<std>/core/math.dream", include_str!("core/math.dream")),
-    // Collections (`List`/`Map` and their cursors), one class per file under `collections/`.
+    ("<std>/core/exception.dream", include_str!("core/exception.dream")),
+    // Collections (`List`/`Map`/`Set`/`Deque`/`PriorityQueue` and their cursors), one class per
+    // file under `collections/`.
     ("<std>/collections/list.dream", include_str!("collections/list.dream")),
     (
         "<std>/collections/list_iterator.dream",
@@ -27,6 +30,24 @@ pub const PRELUDE_FILES: &[(&str, &str)] = &[
         "<std>/collections/map_iterator.dream",
         include_str!("collections/map_iterator.dream"),
     ),
+    ("<std>/collections/set.dream", include_str!("collections/set.dream")),
+    (
+        "<std>/collections/set_iterator.dream",
+        include_str!("collections/set_iterator.dream"),
+    ),
+    ("<std>/collections/deque.dream", include_str!("collections/deque.dream")),
+    (
+        "<std>/collections/deque_iterator.dream",
+        include_str!("collections/deque_iterator.dream"),
+    ),
+    (
+        "<std>/collections/priority_queue.dream",
+        include_str!("collections/priority_queue.dream"),
+    ),
+    (
+        "<std>/collections/priority_queue_iterator.dream",
+        include_str!("collections/priority_queue_iterator.dream"),
+    ),
     // Scalar primitives: each attaches methods to a built-in numeric/bool/char type.
     ("<std>/primitives/int.dream", include_str!("primitives/int.dream")),
     ("<std>/primitives/long.dream", include_str!("primitives/long.dream")),
@@ -37,13 +58,23 @@ pub const PRELUDE_FILES: &[(&str, &str)] = &[
     ("<std>/primitives/bool.dream", include_str!("primitives/bool.dream")),
     ("<std>/primitives/float.dream", include_str!("primitives/float.dream")),
     ("<std>/primitives/double.dream", include_str!("primitives/double.dream")),
-    // Text: the `string` type, its character cursor, and regular expressions.
+    // Text: the `string` type, its byte cursor, regular expressions, `StringBuilder`, and the
+    // Unicode code-point services (and their generated tables) behind `string`'s UTF-8 API.
     ("<std>/text/string.dream", include_str!("text/string.dream")),
     (
         "<std>/text/string_iterator.dream",
         include_str!("text/string_iterator.dream"),
     ),
     ("<std>/text/regex.dream", include_str!("text/regex.dream")),
+    (
+        "<std>/text/string_builder.dream",
+        include_str!("text/string_builder.dream"),
+    ),
+    ("<std>/text/unicode.dream", include_str!("text/unicode.dream")),
+    (
+        "<std>/text/unicode_data.dream",
+        include_str!("text/unicode_data.dream"),
+    ),
     // JSON: value tree, parser, and the public `JSON` API (one class per file).
     ("<std>/json/json_value.dream", include_str!("json/json_value.dream")),
     ("<std>/json/json_parser.dream", include_str!("json/json_parser.dream")),
@@ -60,6 +91,8 @@ pub const PRELUDE_FILES: &[(&str, &str)] = &[
     ("<std>/system/time.dream", include_str!("system/time.dream")),
     ("<std>/system/datetime.dream", include_str!("system/datetime.dream")),
     ("<std>/system/debug.dream", include_str!("system/debug.dream")),
+    // Testing: the `Assert` checks used by `@test` functions.
+    ("<std>/testing/assert.dream", include_str!("testing/assert.dream")),
 ];
 
 pub struct StdlibFunction {
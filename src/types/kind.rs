@@ -104,13 +104,14 @@ pub enum TyKind {
     Interface(DefId, Vec<TypeId>),
     /// A C-style enum definition (no type arguments; values are `int` at runtime).
     Enum(DefId),
-    /// A first-class function value `fun(params...): ret`, an `i32` table index at runtime.
+    /// A first-class function value `fun(params...): ret`: an `i32` pointer to a refcounted closure
+    /// block `[fn: table index][captures...]` at runtime.
     Func(Vec<TypeId>, TypeId),
 }
 
 impl TyKind {
     /// True if a value of this type is a heap-allocated, reference-counted object (strings, arrays,
-    /// objects, structs, and unions). Nullable wrappers defer to their inner type via the interner;
+    /// objects, structs, unions, and closures). Nullable wrappers defer to their inner type via the interner;
     /// this method only inspects the immediate kind, so callers strip `Nullable` first when needed.
     pub fn is_reference(&self) -> bool {
         matches!(
@@ -121,6 +122,7 @@ impl TyKind {
                 | TyKind::Struct(_, _)
                 | TyKind::Union(_, _)
                 | TyKind::Interface(_, _)
+                | TyKind::Func(_, _)
         )
     }
 }
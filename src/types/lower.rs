@@ -167,6 +167,11 @@ impl TypeCtx {
         if let Some(&bound) = bindings.get(name) {
             return bound;
         }
+        // A registered instance first: a mangled name can itself end in a suffix when its last type
+        // argument does (`Box_string?`, `Tuple#2_int_string?`), which is not a nullable `Box_string`.
+        if let Some(&id) = self.instances.get(name) {
+            return id;
+        }
         if let Some(base) = name.strip_suffix("[]") {
             let inner = self.lower_name(base, bindings);
             return self.interner.array(inner);
@@ -183,9 +188,6 @@ impl TypeCtx {
             "void" => return self.interner.void(),
             _ => {}
         }
-        if let Some(&id) = self.instances.get(name) {
-            return id;
-        }
         match self.nominal_kind(name) {
             Some(DefKind::Enum) => {
                 let def = self.defs.intern(DefKind::Enum, name, vec![]);
@@ -0,0 +1,597 @@
+//! Drives the `dream` binary end to end: subcommands, their artifacts, and exit statuses.
+
+use dream::mir::srcmap::{strip_line_markers, SourceMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh scratch directory for one test.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dream_cli_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn dream(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dream")).args(args).output().unwrap()
+}
+
+fn write(dir: &Path, name: &str, source: &str) -> String {
+    let path = dir.join(name);
+    fs::write(&path, source).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+const ECHO_ARGS: &str = "fun main(args: string[]) {
+    System.println(args.size());
+    for (let a in args) {
+        System.println(a);
+    }
+    if (args.size() > 2) {
+        System.exit(3);
+    }
+}
+";
+
+#[test]
+fn run_passes_arguments_and_propagates_exit_status() {
+    let dir = scratch("run");
+    let src = write(&dir, "echo.dream", ECHO_ARGS);
+
+    let out = dream(&["run", &src, "--", "one", "two words"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2\none\ntwo words\n");
+
+    let out = dream(&["run", &src, "--", "a", "b", "c"]);
+    assert_eq!(out.status.code(), Some(3), "System.exit(3) sets the process status");
+
+    let out = dream(&["run", &src]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "0\n");
+}
+
+#[test]
+fn check_stops_after_analysis_and_fails_on_errors() {
+    let dir = scratch("check");
+    let good = write(&dir, "good.dream", ECHO_ARGS);
+    let bad = write(&dir, "bad.dream", "fun main() {\n    let x: int = \"s\";\n}\n");
+
+    assert_eq!(dream(&["check", &good]).status.code(), Some(0));
+    assert_eq!(dream(&["check", &bad]).status.code(), Some(1));
+    assert_eq!(dream(&["build", &bad]).status.code(), Some(1));
+    let written: Vec<_> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(written.len(), 2, "check and a failed build write no artifacts: {:?}", written);
+}
+
+#[test]
+fn build_writes_the_requested_artifact() {
+    let dir = scratch("build");
+    let src = write(&dir, "app.dream", ECHO_ARGS);
+    let wasm = dir.join("out.wasm");
+
+    let out = dream(&["build", &src, "--emit=wasm", "--no-abi", "-o", wasm.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(0));
+    let bytes = fs::read(&wasm).unwrap();
+    assert!(bytes.starts_with(b"\0asm"));
+    // Line markers are stripped from the binary; their lines survive in its source map.
+    assert_eq!(strip_line_markers(&bytes), bytes);
+    assert!(SourceMap::from_wasm(&bytes).functions().any(|(_, f)| f.name == "main" && !f.lines.is_empty()));
+    assert!(!dir.join("app.wat").exists() && !dir.join("out.abi.json").exists());
+
+    // The default build writes the `.wat`, its `.wasm`, and the ABI sidecar next to the source.
+    assert_eq!(dream(&[&src]).status.code(), Some(0));
+    for name in ["app.wat", "app.wasm", "app.abi.json"] {
+        assert!(dir.join(name).exists(), "missing {}", name);
+    }
+
+    let out = dream(&["emit-mir", &src]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("fn main(_0) {"));
+}
+
+#[test]
+fn fmt_rewrites_in_place_and_check_reports() {
+    let dir = scratch("fmt");
+    let src = write(&dir, "messy.dream", "fun main() {\nlet x = 1;\n      System.println(x);\n}\n");
+
+    let out = dream(&["fmt", "--check", &src]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("messy.dream"));
+
+    assert_eq!(dream(&["fmt", &src]).status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(&src).unwrap(),
+        "fun main() {\n    let x = 1;\n    System.println(x);\n}\n"
+    );
+    assert_eq!(dream(&["fmt", "--check", &src]).status.code(), Some(0));
+}
+
+const TESTS: &str = "let runs = 0;
+
+@test
+fun adds() {
+    runs = runs + 1;
+    Assert.equal(4, 2 + 2);
+}
+
+@test
+fun isolated() {
+    runs = runs + 1;
+    Assert.equal(1, runs);
+    Assert.throws(() => { throw Exception(\"boom\"); });
+}
+
+@test
+fun wrong() {
+    Assert.equal(\"a\", \"b\");
+}
+";
+
+#[test]
+fn test_runs_each_test_and_reports_failures() {
+    let dir = scratch("test");
+    let src = write(&dir, "math.dream", TESTS);
+
+    let out = dream(&["test", &src]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(1), "a failing test fails the run:\n{}", stdout);
+    assert!(stdout.contains("test adds ... ok\ntest isolated ... ok\n"), "{}", stdout);
+    assert!(stdout.contains(&format!("test wrong ... FAILED at {}:18", src)), "{}", stdout);
+    assert!(stdout.contains("assertion failed: expected a, got b"), "{}", stdout);
+    assert!(stdout.ends_with("test result: FAILED. 2 passed; 1 failed\n"), "{}", stdout);
+
+    let passing = write(&dir, "ok.dream", &TESTS.replace("\"a\", \"b\"", "\"b\", \"b\""));
+    let out = dream(&["test", &passing]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).ends_with("test result: ok. 3 passed; 0 failed\n"));
+}
+
+#[test]
+fn test_reports_system_exit_as_a_failure_and_keeps_running() {
+    let dir = scratch("test_exit");
+    let src = write(
+        &dir,
+        "exit.dream",
+        "@test\nfun before() {\n    Assert.equal(1, 1);\n}\n\n@test\nfun quits() {\n    System.exit(4);\n}\n\n@test\nfun after() {\n    Assert.equal(2, 2);\n}\n",
+    );
+
+    let out = dream(&["test", &src]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(1), "the exit fails the run, not with its code:\n{}", stdout);
+    assert!(stdout.contains(&format!("test quits ... FAILED at {}:8", src)), "{}", stdout);
+    assert!(stdout.contains("test after ... ok"), "later tests still run:\n{}", stdout);
+    assert!(stdout.contains("System.exit(4) called"), "{}", stdout);
+    assert!(stdout.ends_with("test result: FAILED. 2 passed; 1 failed\n"), "{}", stdout);
+}
+
+#[test]
+fn help_prints_usage_and_exits_with_status_zero() {
+    for args in [&["--help"][..], &["-h"], &["help"], &["run", "x.dream", "--help"]] {
+        let out = dream(args);
+        assert_eq!(out.status.code(), Some(0), "{:?}", args);
+        assert!(String::from_utf8_lossy(&out.stdout).starts_with("Usage: dream"), "{:?}", args);
+    }
+}
+
+#[test]
+fn invalid_usage_exits_with_status_two() {
+    assert_eq!(dream(&[]).status.code(), Some(2));
+    assert_eq!(dream(&["build", "x.dream", "--frob"]).status.code(), Some(2));
+    assert_eq!(dream(&["build", "x.dream", "--", "arg"]).status.code(), Some(2));
+    assert_eq!(dream(&["run", "--target=wasi", "x.dream"]).status.code(), Some(2));
+    assert_eq!(dream(&["test", "--emit=wat", "x.dream"]).status.code(), Some(2));
+
+    // A misspelled command is named as one, not counted as a second source file.
+    let out = dream(&["biuld", "x.dream"]);
+    assert_eq!(out.status.code(), Some(2));
+    let log = String::from_utf8_lossy(&out.stdout).to_string() + &String::from_utf8_lossy(&out.stderr);
+    assert!(log.contains("Unknown command 'biuld'") && !log.contains("Expected one source file"), "{}", log);
+}
+
+const CYCLES: &str = "class Node {
+    public next: Node?;
+    public items: Node[];
+
+    constructor() {
+        this.next = null;
+        this.items = Array.new<Node>(0);
+    }
+}
+
+fun leak(): void {
+    let a = Node();
+    let b = Node();
+    a.next = b;
+    b.items = [a];
+}
+
+fun main() {
+    let base = Debug.live_objects();
+    for (let i = 0; i < 3; i = i + 1) {
+        leak();
+    }
+    let freed = Debug.collect_cycles();
+    System.println(freed > 0);
+    System.println(Debug.live_objects() - base);
+}
+";
+
+#[test]
+fn collect_cycles_reclaims_unreachable_cycles() {
+    let dir = scratch("cycles");
+    let src = write(&dir, "cycles.dream", CYCLES);
+
+    let out = dream(&["run", "--debug", "--collect-cycles", &src]);
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "true\n0\n");
+
+    let out = dream(&["run", "--debug", &src]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout).lines().next(), Some("false"), "plain ARC collects nothing");
+}
+
+/// Runs `program` under `--debug`, with the cycle collector when `collect`, and returns its stdout.
+fn run_cycles(name: &str, program: &str, collect: bool) -> String {
+    let dir = scratch(name);
+    let src = write(&dir, "cycles.dream", program);
+    let out = if collect {
+        dream(&["run", "--debug", "--collect-cycles", &src])
+    } else {
+        dream(&["run", "--debug", &src])
+    };
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn collect_cycles_keeps_a_cycle_reachable_from_a_root() {
+    let program = "class Node {
+    public value: int;
+    public next: Node?;
+
+    constructor(value: int) {
+        this.value = value;
+        this.next = null;
+    }
+}
+
+fun make_cycle(): Node {
+    let a = Node(1);
+    let b = Node(2);
+    a.next = b;
+    b.next = a;
+    return a;
+}
+
+fun main() {
+    let base = Debug.live_objects();
+    let kept = make_cycle();
+    System.println(Debug.collect_cycles());
+    System.println(kept.next.next.value + kept.next.value);
+    System.println(Debug.live_objects() - base);
+}
+";
+    assert_eq!(run_cycles("cycles_live", program, true), "0\n3\n2\n");
+}
+
+#[test]
+fn collect_cycles_runs_once_enough_allocations_pass() {
+    // No explicit `Debug.collect_cycles()`: the 10,000th allocation after the leaks collects them.
+    let program = "class Node {
+    public next: Node?;
+
+    constructor() {
+        this.next = null;
+    }
+}
+
+class Leaf {
+    public value: int;
+
+    constructor(value: int) {
+        this.value = value;
+    }
+}
+
+fun leak(): void {
+    let a = Node();
+    let b = Node();
+    a.next = b;
+    b.next = a;
+}
+
+fun churn(count: int): void {
+    for (let i = 0; i < count; i = i + 1) {
+        let leaf = Leaf(i);
+    }
+}
+
+fun main() {
+    let base = Debug.live_objects();
+    for (let i = 0; i < 3; i = i + 1) {
+        leak();
+    }
+    churn(9000);
+    System.println(Debug.live_objects() - base);
+    churn(1000);
+    System.println(Debug.live_objects() - base);
+}
+";
+    assert_eq!(run_cycles("cycles_threshold", program, true), "6\n0\n");
+    assert_eq!(run_cycles("cycles_threshold", program, false), "6\n6\n");
+}
+
+#[test]
+fn collect_cycles_traces_interface_fields_and_closures() {
+    let program = "interface Shape {
+    fun area(): int;
+}
+
+class Square : Shape {
+    public side: int;
+    public neighbour: Shape?;
+
+    constructor(side: int) {
+        this.side = side;
+        this.neighbour = null;
+    }
+
+    public fun area(): int { return this.side * this.side; }
+}
+
+class Button {
+    public clicks: int;
+    public on_click: fun(): int;
+
+    constructor() {
+        this.clicks = 0;
+        this.on_click = () => 0;
+    }
+}
+
+fun leak_through_interfaces(): void {
+    let a = Square(1);
+    let b = Square(2);
+    a.neighbour = b;
+    b.neighbour = a;
+}
+
+fun leak_through_closure(): void {
+    let button = Button();
+    button.on_click = () => button.clicks;
+}
+
+fun main() {
+    let base = Debug.live_objects();
+    leak_through_interfaces();
+    System.println(Debug.collect_cycles());
+    System.println(Debug.live_objects() - base);
+    leak_through_closure();
+    System.println(Debug.collect_cycles());
+    System.println(Debug.live_objects() - base);
+}
+";
+    assert_eq!(run_cycles("cycles_traced", program, true), "2\n0\n2\n0\n");
+    assert_eq!(run_cycles("cycles_traced", program, false), "0\n2\n0\n4\n");
+}
+
+#[test]
+fn collected_objects_skip_del_and_are_not_released_again() {
+    // The collector frees `a` and `b` without running `del()` (a documented limitation), and
+    // releases the `shared` node they referenced exactly once: it stays alive for `main` and is
+    // destroyed normally when replaced. Blocks the collector freed are reused afterwards.
+    let program = "class Node {
+    public name: string;
+    public next: Node?;
+    public other: Node?;
+
+    constructor(name: string) {
+        this.name = name;
+        this.next = null;
+        this.other = null;
+    }
+
+    del() {
+        System.println(\"del \" + this.name);
+    }
+}
+
+fun leak(shared: Node): void {
+    let a = Node(\"a\");
+    let b = Node(\"b\");
+    a.next = b;
+    b.next = a;
+    a.other = shared;
+}
+
+fun reuse(): void {
+    let n = Node(\"fresh\");
+    n.next = Node(\"child\");
+    let keep = n.next;
+}
+
+fun main() {
+    let base = Debug.live_objects();
+    let shared = Node(\"shared\");
+    leak(shared);
+    System.println(Debug.collect_cycles());
+    System.println(Debug.live_objects() - base);
+    System.println(shared.name);
+    reuse();
+    System.println(Debug.live_objects() - base);
+    shared = Node(\"replacement\");
+    System.println(Debug.collect_cycles());
+    System.println(Debug.live_objects() - base);
+}
+";
+    assert_eq!(
+        run_cycles("cycles_del", program, true),
+        "2\n1\nshared\ndel fresh\ndel child\n1\ndel shared\n0\n1\ndel replacement\n"
+    );
+}
+
+#[test]
+fn unavailable_std_method_is_reported_at_the_call() {
+    let dir = scratch("unavailable");
+    let body = "class P {\n    public x: int;\n}\n\nfun main() {\n    let ps = List<P>();\n    ps.sort();\n}\n";
+    let src = write(&dir, "app.dream", body);
+
+    let out = dream(&["check", &src]);
+    assert_eq!(out.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("app.dream: line 7 column 8 'List<P>.sort' is unavailable"), "{}", stdout);
+    assert!(!stdout.contains("<std>"), "{}", stdout);
+
+    // Without the call, the list is usable and the program compiles.
+    write(&dir, "app.dream", &body.replace("ps.sort();", "ps.sort_by((a, b) => a.x - b.x);"));
+    assert_eq!(dream(&["check", &src]).status.code(), Some(0));
+}
+
+/// Lays out a package `app` with a path dependency `geometry` (which has its own dependency
+/// `units`) and a git dependency `banner` vendored at `vendor/banner`, checked out at `commit`.
+fn package(name: &str, commit: &str) -> PathBuf {
+    let dir = scratch(name);
+    for sub in ["app/vendor/banner/.git", "geometry/shapes", "units"] {
+        fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    let app = dir.join("app");
+    write(
+        &app,
+        "dream.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\nbanner = { git = \"https://example.com/banner.git\" }\n",
+    );
+    write(
+        &app,
+        "main.dream",
+        "import geometry.shapes.square;\nimport geometry as geo;\nimport banner;\n\nfun main() {\n    System.println(square.area(3));\n    System.println(geo.describe());\n    System.println(banner.banner(\"hi\"));\n}\n",
+    );
+    let geometry = dir.join("geometry");
+    write(
+        &geometry,
+        "dream.toml",
+        "[package]\nname = \"geometry\"\nversion = \"0.2.0\"\nentry = \"lib.dream\"\n\n[dependencies]\nunits = { path = \"../units\" }\n",
+    );
+    write(&geometry, "lib.dream", "import units;\n\npublic fun describe(): string {\n    return \"geometry in \" + units.unit();\n}\n");
+    write(&geometry.join("shapes"), "square.dream", "public fun area(side: int): int {\n    return side * side;\n}\n");
+    write(&dir.join("units"), "dream.toml", "[package]\nname = \"units\"\n");
+    write(&dir.join("units"), "main.dream", "public fun unit(): string {\n    return \"cm\";\n}\n");
+    let banner = app.join("vendor/banner");
+    write(&banner, "dream.toml", "[package]\nname = \"banner\"\nentry = \"banner.dream\"\n");
+    write(&banner, "banner.dream", "public fun banner(s: string): string {\n    return \"== \" + s + \" ==\";\n}\n");
+    write(&banner.join(".git"), "HEAD", &format!("{}\n", commit));
+    app
+}
+
+#[test]
+fn package_imports_resolve_against_dependencies() {
+    let app = package("package", "1111111111111111111111111111111111111111");
+
+    // Without a file, `run` builds the entry the manifest names.
+    let out = Command::new(env!("CARGO_BIN_EXE_dream")).arg("run").current_dir(&app).output().unwrap();
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "9\ngeometry in cm\n== hi ==\n");
+
+    let lock = fs::read_to_string(app.join("dream.lock")).unwrap();
+    assert!(lock.contains("name = \"geometry\"\nversion = \"0.2.0\"\nsource = \"path+../geometry\"\ndependencies = [\"units\"]"), "{}", lock);
+    assert!(lock.contains("source = \"git+https://example.com/banner.git#1111111111111111111111111111111111111111\""), "{}", lock);
+
+    // Dependencies are not transitive: `units` is only a dependency of `geometry`.
+    let src = write(&app, "transitive.dream", "import units;\n\nfun main() {}\n");
+    assert_eq!(dream(&["check", &src]).status.code(), Some(1));
+}
+
+#[test]
+fn lockfile_pins_git_checkouts_until_update() {
+    let app = package("lock", "1111111111111111111111111111111111111111");
+    let main = app.join("main.dream");
+    let main = main.to_str().unwrap();
+    assert_eq!(dream(&["check", main]).status.code(), Some(0));
+
+    write(&app.join("vendor/banner/.git"), "HEAD", "2222222222222222222222222222222222222222\n");
+    let out = dream(&["check", main]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("run `dream update`"));
+
+    assert_eq!(dream(&["update", main]).status.code(), Some(0));
+    assert!(fs::read_to_string(app.join("dream.lock")).unwrap().contains("#2222222222222222222222222222222222222222"));
+    assert_eq!(dream(&["check", main]).status.code(), Some(0));
+}
+
+#[test]
+fn git_worktree_checkouts_are_read_through_the_common_dir() {
+    // `vendor/banner` is a worktree of a repository elsewhere, on a branch whose ref is packed in
+    // the repository's git directory rather than the worktree's.
+    let app = package("worktree", "unused");
+    let main = app.join("main.dream");
+    let main = main.to_str().unwrap();
+    let banner = app.join("vendor/banner");
+    fs::remove_dir_all(banner.join(".git")).unwrap();
+    let repo = app.parent().unwrap().join("banner-repo/.git");
+    let worktree = repo.join("worktrees/banner");
+    fs::create_dir_all(&worktree).unwrap();
+    write(&banner, ".git", &format!("gitdir: {}\n", worktree.display()));
+    write(&worktree, "HEAD", "ref: refs/heads/feature\n");
+    write(&worktree, "commondir", "../..\n");
+    write(&repo, "packed-refs", "# pack-refs with: peeled fully-peeled sorted\n3333333333333333333333333333333333333333 refs/heads/feature\n");
+
+    let manifest = |banner: &str| {
+        write(
+            &app,
+            "dream.toml",
+            &format!("[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ngeometry = {{ path = \"../geometry\" }}\nbanner = {}\n", banner),
+        );
+    };
+    manifest("{ git = \"https://example.com/banner.git\", rev = \"deadbeef\" }");
+    let out = dream(&["check", main]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("is at commit 3333333333333333333333333333333333333333, not at rev 'deadbeef'"));
+
+    manifest("{ git = \"https://example.com/banner.git\", rev = \"3333333\" }");
+    assert_eq!(dream(&["check", main]).status.code(), Some(0));
+    assert!(fs::read_to_string(app.join("dream.lock")).unwrap().contains("#3333333333333333333333333333333333333333"));
+
+    // A branch that cannot be resolved fails the rev check, and without a rev still keeps the pin.
+    write(&worktree, "HEAD", "ref: refs/heads/gone\n");
+    let out = dream(&["check", main]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("so rev '3333333' cannot be checked"));
+    manifest("{ git = \"https://example.com/banner.git\" }");
+    let out = dream(&["update", main]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("pin 3333333333333333333333333333333333333333 cannot be checked or kept"));
+    assert!(fs::read_to_string(app.join("dream.lock")).unwrap().contains("#3333333333333333333333333333333333333333"));
+}
+
+#[test]
+fn build_cache_reuses_output_until_an_imported_module_changes() {
+    let dir = scratch("cache");
+    write(&dir, "util.dream", "public fun greeting(): string {\n    return \"hello\";\n}\n");
+    let src = write(&dir, "app.dream", "import util;\n\nfun main() {\n    System.println(greeting());\n}\n");
+    let reused = |out: &Output| String::from_utf8_lossy(&out.stdout).contains("reusing its output");
+
+    // The cache is opt-in.
+    let out = dream(&["run", "-v", &src]);
+    assert!(String::from_utf8_lossy(&out.stdout).ends_with("hello\n"));
+    assert!(!dir.join(".dream-cache").exists());
+
+    let out = dream(&["run", "-v", "--cache", &src]);
+    assert!(!reused(&out));
+    assert!(String::from_utf8_lossy(&out.stdout).ends_with("hello\n"));
+    assert!(dir.join(".dream-cache").is_dir());
+
+    // Nothing changed: the artifacts are replayed, even over a deleted output.
+    fs::remove_file(dir.join("app.wasm")).unwrap();
+    let out = dream(&["run", "-v", "--cache", &src]);
+    assert!(reused(&out));
+    assert!(String::from_utf8_lossy(&out.stdout).ends_with("hello\n"));
+
+    // Editing an imported module invalidates it and its importers, which are compiled again from
+    // the other files' parsed trees and the other functions' lowered bodies.
+    write(&dir, "util.dream", "public fun greeting(): string {\n    return \"bye\";\n}\n");
+    let out = dream(&["run", "-v", "--cache", &src]);
+    let log = String::from_utf8_lossy(&out.stdout);
+    assert!(!reused(&out));
+    assert!(log.contains("invalidated:") && log.contains("util.dream") && log.contains("app.dream"), "{}", log);
+    let summary = log.lines().find(|line| line.contains("parsed files and")).expect("no reuse summary");
+    let counts: Vec<usize> = summary.split_whitespace().filter_map(|word| word.parse().ok()).collect();
+    assert!(matches!(counts[..], [parsed, files, lowered, functions] if parsed + 1 == files && 0 < lowered && lowered < functions), "{}", summary);
+    assert!(log.ends_with("bye\n"));
+}
@@ -1,8 +1,10 @@
 use dream::driver::compiler::{Compiler, Target};
 use dream::execution::host::{
     link_console_functions, link_datetime_functions, link_file_functions, link_http_functions,
-    link_math_functions, link_regex_functions, read_string_from_memory,
+    link_math_functions, link_regex_functions, read_string_bytes_from_memory,
 };
+use dream::execution::wasm_runner::{new_engine, traced_runtime_error};
+use dream::mir::srcmap::{strip_line_markers, SourceMap};
 use pretty_assertions::assert_eq;
 use std::fs;
 use std::path::Path;
@@ -11,18 +13,24 @@ use wasmtime::*;
 
 #[derive(Clone)]
 struct TestEnv {
-    output: Arc<Mutex<String>>,
+    output: Arc<Mutex<Vec<u8>>>,
 }
 
 impl TestEnv {
     fn new() -> Self {
         Self {
-            output: Arc::new(Mutex::new(String::new())),
+            output: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
     fn print(&self, s: &str) {
-        self.output.lock().unwrap().push_str(s);
+        self.print_bytes(s.as_bytes());
+    }
+
+    /// Appends raw output bytes; the captured text is UTF-8 decoded only once the run is over, so a
+    /// multi-byte character printed one `char` (byte) at a time comes out whole.
+    fn print_bytes(&self, bytes: &[u8]) {
+        self.output.lock().unwrap().extend_from_slice(bytes);
     }
 }
 
@@ -61,10 +69,10 @@ fn run_test_case(dream_file: &Path) {
     let wat_content = fs::read_to_string(&wat_path).unwrap();
 
     // 2. Parse WAT to Wasm binary
-    let wasm_bytes = wat::parse_str(&wat_content).expect("Failed to parse WAT");
+    let wasm_bytes = strip_line_markers(&wat::parse_str(&wat_content).expect("Failed to parse WAT"));
 
     // 3. Setup Wasmtime
-    let engine = Engine::default();
+    let engine = new_engine().unwrap();
     let module = Module::new(&engine, &wasm_bytes).expect("Failed to create module");
 
     let mut store = Store::new(&engine, ());
@@ -100,9 +108,7 @@ fn run_test_case(dream_file: &Path) {
     let env_clone = env.clone();
     linker
         .func_wrap("env", "print_char", move |v: i32| {
-            if let Some(c) = char::from_u32(v as u32) {
-                env_clone.print(&c.to_string());
-            }
+            env_clone.print_bytes(&[v as u8]);
         })
         .unwrap();
 
@@ -113,8 +119,8 @@ fn run_test_case(dream_file: &Path) {
             "print_string",
             move |mut caller: Caller<'_, ()>, ptr: i32| {
                 let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
-                let s = read_string_from_memory(&memory, &caller, ptr);
-                env_clone.print(&s);
+                let bytes = read_string_bytes_from_memory(&memory, &caller, ptr);
+                env_clone.print_bytes(&bytes);
             },
         )
         .unwrap();
@@ -163,10 +169,28 @@ fn run_test_case(dream_file: &Path) {
         .get_typed_func::<(), ()>(&mut store, "main")
         .expect("Failed to get main function");
 
-    main_func.call(&mut store, ()).expect("Execution failed");
+    // A case with an `.expected_runtime_error` must trap with that Dream-level error (a failed
+    // runtime check or out-of-memory) and its Dream stack trace; its `.expected` holds the output
+    // printed before the trap.
+    let run_result = main_func.call(&mut store, ());
+    let expected_runtime_error_file = dream_file.with_extension("expected_runtime_error");
+    if expected_runtime_error_file.exists() {
+        let expected_error = fs::read_to_string(&expected_runtime_error_file).unwrap();
+        let trap = run_result.expect_err("Expected execution to trap");
+        let map = SourceMap::from_wasm(&wasm_bytes);
+        let error = traced_runtime_error(&instance, &mut store, &trap, &map).unwrap_or(trap);
+        assert_eq!(
+            relative_paths(&error.to_string()).trim(),
+            expected_error.trim(),
+            "Runtime error mismatch for {:?}",
+            dream_file
+        );
+    } else {
+        run_result.expect("Execution failed");
+    }
 
     // 6. Assert Output
-    let actual_output = env.output.lock().unwrap().clone();
+    let actual_output = String::from_utf8_lossy(&env.output.lock().unwrap()).into_owned();
     assert_eq!(
         actual_output.trim(),
         expected_output.trim(),
@@ -178,6 +202,12 @@ fn run_test_case(dream_file: &Path) {
     let _ = fs::remove_file(wat_path);
 }
 
+/// Stack-trace frames name the canonical source path; golden files spell it relative to the crate.
+fn relative_paths(text: &str) -> String {
+    let root = std::env::current_dir().unwrap();
+    text.replace(&format!("{}/", root.display()), "")
+}
+
 #[test]
 fn run_all_e2e_cases() {
     let cases_dir = Path::new("tests/cases");
@@ -247,3 +277,164 @@ fn codegen_is_deterministic() {
         }
     }
 }
+
+/// State behind the minimal `wasi_snapshot_preview1` host below: no preopened directories, a fixed
+/// clock, and captured stdout/stderr.
+#[derive(Default)]
+struct FakeWasi {
+    args: Vec<String>,
+    stdout: String,
+    stderr: String,
+    exit_code: Option<i32>,
+}
+
+fn wasi_memory(caller: &mut Caller<'_, FakeWasi>) -> Memory {
+    caller.get_export("memory").unwrap().into_memory().unwrap()
+}
+
+fn wasi_write_u32(caller: &mut Caller<'_, FakeWasi>, addr: i32, value: u32) {
+    let memory = wasi_memory(caller);
+    memory
+        .write(caller, addr as usize, &value.to_le_bytes())
+        .unwrap();
+}
+
+/// Links the `wasi_snapshot_preview1` functions the cases below reach: output, arguments, the clock,
+/// and `proc_exit`.
+fn link_fake_wasi(linker: &mut Linker<FakeWasi>) {
+    const EBADF: i32 = 8;
+    linker
+        .func_wrap(
+            "wasi_snapshot_preview1",
+            "fd_write",
+            |mut caller: Caller<'_, FakeWasi>, fd: i32, iovs: i32, iovs_len: i32, nwritten: i32| -> i32 {
+                let memory = wasi_memory(&mut caller);
+                let mut text = Vec::new();
+                for i in 0..iovs_len as usize {
+                    let (mut ptr, mut len) = ([0u8; 4], [0u8; 4]);
+                    memory.read(&caller, iovs as usize + i * 8, &mut ptr).unwrap();
+                    memory.read(&caller, iovs as usize + i * 8 + 4, &mut len).unwrap();
+                    let (ptr, len) = (u32::from_le_bytes(ptr) as usize, u32::from_le_bytes(len) as usize);
+                    text.extend_from_slice(&memory.data(&caller)[ptr..ptr + len]);
+                }
+                let text = String::from_utf8(text).unwrap();
+                match fd {
+                    1 => caller.data_mut().stdout.push_str(&text),
+                    2 => caller.data_mut().stderr.push_str(&text),
+                    _ => return EBADF,
+                }
+                let len = text.len() as u32;
+                wasi_write_u32(&mut caller, nwritten, len);
+                0
+            },
+        )
+        .unwrap();
+    linker
+        .func_wrap(
+            "wasi_snapshot_preview1",
+            "args_sizes_get",
+            |mut caller: Caller<'_, FakeWasi>, argc: i32, buf_size: i32| -> i32 {
+                let args = &caller.data().args;
+                let (count, size) = (args.len() as u32, args.iter().map(|a| a.len() as u32 + 1).sum());
+                wasi_write_u32(&mut caller, argc, count);
+                wasi_write_u32(&mut caller, buf_size, size);
+                0
+            },
+        )
+        .unwrap();
+    linker
+        .func_wrap(
+            "wasi_snapshot_preview1",
+            "args_get",
+            |mut caller: Caller<'_, FakeWasi>, argv: i32, buf: i32| -> i32 {
+                let memory = wasi_memory(&mut caller);
+                let args = caller.data().args.clone();
+                let mut at = buf;
+                for (i, arg) in args.iter().enumerate() {
+                    wasi_write_u32(&mut caller, argv + 4 * i as i32, at as u32);
+                    memory.write(&mut caller, at as usize, arg.as_bytes()).unwrap();
+                    memory.write(&mut caller, at as usize + arg.len(), &[0]).unwrap();
+                    at += arg.len() as i32 + 1;
+                }
+                0
+            },
+        )
+        .unwrap();
+    linker
+        .func_wrap(
+            "wasi_snapshot_preview1",
+            "clock_time_get",
+            |mut caller: Caller<'_, FakeWasi>, _id: i32, _precision: i64, out: i32| -> i32 {
+                let memory = wasi_memory(&mut caller);
+                let nanos: u64 = 1_700_000_000_000_000_000;
+                memory.write(&mut caller, out as usize, &nanos.to_le_bytes()).unwrap();
+                0
+            },
+        )
+        .unwrap();
+    linker
+        .func_wrap(
+            "wasi_snapshot_preview1",
+            "proc_exit",
+            |mut caller: Caller<'_, FakeWasi>, code: i32| -> Result<()> {
+                caller.data_mut().exit_code = Some(code);
+                Err(Error::msg(format!("exit {}", code)))
+            },
+        )
+        .unwrap();
+    // No preopens: every path fails to resolve, so the file functions past it are never reached
+    // (the caller stubs them as traps).
+    linker
+        .func_wrap("wasi_snapshot_preview1", "fd_prestat_get", |_: i32, _: i32| -> i32 { EBADF })
+        .unwrap();
+}
+
+/// A `--target=wasi` build imports nothing but `wasi_snapshot_preview1`, exports `_start`, and
+/// produces the same output as the default target, with runtime errors reported on stderr.
+#[test]
+fn wasi_target_runs_on_preview1() {
+    let cases_dir = Path::new("tests/cases");
+    let engine = new_engine().unwrap();
+    // `main_args` sees an empty array: argv[0], the program name, is not passed to `main`.
+    for name in ["main_args", "strings", "async_basic", "exceptions", "index_out_of_bounds", "propagate"] {
+        let src = cases_dir.join(format!("{}.dream", name));
+        let out = std::env::temp_dir().join(format!("dream_wasi_{}.wat", name));
+        Compiler::new(Target::Wasi)
+            .compile(&src.to_str().unwrap().to_string(), &out.to_str().unwrap().to_string())
+            .unwrap_or_else(|_| panic!("Compilation failed for {}", name));
+        let wat = fs::read_to_string(&out).unwrap();
+        let _ = fs::remove_file(&out);
+        let _ = fs::remove_file(out.with_extension("wasm"));
+        let _ = fs::remove_file(out.with_extension("abi.json"));
+
+        let module = Module::new(&engine, wat::parse_str(&wat).unwrap()).unwrap();
+        for import in module.imports() {
+            assert_eq!(import.module(), "wasi_snapshot_preview1", "{}: imports {}", name, import.name());
+        }
+        let mut linker = Linker::new(&engine);
+        link_fake_wasi(&mut linker);
+        linker.define_unknown_imports_as_traps(&module).unwrap();
+        let state = FakeWasi { args: vec!["prog".to_string()], ..Default::default() };
+        let mut store = Store::new(&engine, state);
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let start = instance.get_typed_func::<(), ()>(&mut store, "_start").unwrap();
+        let result = start.call(&mut store, ());
+
+        let expected = fs::read_to_string(src.with_extension("expected")).unwrap();
+        assert_eq!(store.data().stdout.trim(), expected.trim(), "Output mismatch for {}", name);
+        let runtime_error = src.with_extension("expected_runtime_error");
+        if runtime_error.exists() {
+            assert!(result.is_err(), "{} should trap", name);
+            let message = fs::read_to_string(runtime_error).unwrap();
+            assert_eq!(
+                store.data().stderr.trim(),
+                message.lines().next().unwrap(),
+                "stderr mismatch for {}",
+                name
+            );
+        } else {
+            result.unwrap_or_else(|e| panic!("{} failed: {:?}", name, e));
+            assert_eq!(store.data().exit_code, None);
+        }
+    }
+}
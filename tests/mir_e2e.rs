@@ -11,8 +11,10 @@
 use dream::driver::compiler::{Compiler, Target};
 use dream::execution::host::{
     link_console_functions, link_file_functions, link_http_functions, link_math_functions,
-    link_regex_functions, read_string_from_memory,
+    link_regex_functions, read_string_bytes_from_memory,
 };
+use dream::execution::wasm_runner::{new_engine, traced_runtime_error};
+use dream::mir::srcmap::{strip_line_markers, SourceMap};
 use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
@@ -26,23 +28,31 @@ const XFAIL: &[(&str, &str)] = &[];
 
 #[derive(Clone)]
 struct TestEnv {
-    output: Arc<Mutex<String>>,
+    output: Arc<Mutex<Vec<u8>>>,
 }
 
 impl TestEnv {
     fn new() -> Self {
         Self {
-            output: Arc::new(Mutex::new(String::new())),
+            output: Arc::new(Mutex::new(Vec::new())),
         }
     }
     fn print(&self, s: &str) {
-        self.output.lock().unwrap().push_str(s);
+        self.print_bytes(s.as_bytes());
+    }
+
+    /// Appends raw output bytes; the captured text is UTF-8 decoded only once the run is over, so a
+    /// multi-byte character printed one `char` (byte) at a time comes out whole.
+    fn print_bytes(&self, bytes: &[u8]) {
+        self.output.lock().unwrap().extend_from_slice(bytes);
     }
 }
 
-/// Compiles one case through the MIR backend and runs it, returning `Ok(actual_output)` or an error
-/// describing the failure stage (compile / assemble / instantiate / execute).
-fn compile_and_run_mir(dream_file: &Path) -> Result<String, String> {
+/// Compiles one case through the MIR backend and runs it, returning `Ok((actual_output, runtime_error))`
+/// or an error describing the failure stage (compile / assemble / instantiate / execute). A trap the
+/// program reports itself (a failed runtime check) is returned as `runtime_error`, with its Dream
+/// stack trace, alongside the output printed before it.
+fn compile_and_run_mir(dream_file: &Path) -> Result<(String, Option<String>), String> {
     let wat_path = dream_file.with_extension("mir.wat");
     let dream_str = dream_file.to_str().unwrap().to_string();
     let wat_str = wat_path.to_str().unwrap().to_string();
@@ -57,8 +67,8 @@ fn compile_and_run_mir(dream_file: &Path) -> Result<String, String> {
     let _ = fs::remove_file(wat_path.with_extension("wasm"));
     let _ = fs::remove_file(wat_path.with_extension("abi.json"));
 
-    let wasm = wat::parse_str(&wat).map_err(|e| format!("assemble: {e}"))?;
-    let engine = Engine::default();
+    let wasm = strip_line_markers(&wat::parse_str(&wat).map_err(|e| format!("assemble: {e}"))?);
+    let engine = new_engine().unwrap();
     let module = Module::new(&engine, &wasm).map_err(|e| format!("module: {e:#}"))?;
     let mut store = Store::new(&engine, ());
     let mut linker = Linker::new(&engine);
@@ -79,9 +89,7 @@ fn compile_and_run_mir(dream_file: &Path) -> Result<String, String> {
     let e = env.clone();
     linker
         .func_wrap("env", "print_char", move |v: i32| {
-            if let Some(c) = char::from_u32(v as u32) {
-                e.print(&c.to_string());
-            }
+            e.print_bytes(&[v as u8]);
         })
         .unwrap();
     let e = env.clone();
@@ -91,8 +99,8 @@ fn compile_and_run_mir(dream_file: &Path) -> Result<String, String> {
             "print_string",
             move |mut caller: Caller<'_, ()>, ptr: i32| {
                 let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
-                let s = read_string_from_memory(&memory, &caller, ptr);
-                e.print(&s);
+                let bytes = read_string_bytes_from_memory(&memory, &caller, ptr);
+                e.print_bytes(&bytes);
             },
         )
         .unwrap();
@@ -112,11 +120,22 @@ fn compile_and_run_mir(dream_file: &Path) -> Result<String, String> {
     let main = instance
         .get_typed_func::<(), ()>(&mut store, "main")
         .map_err(|e| format!("no main: {e}"))?;
-    main.call(&mut store, ())
-        .map_err(|e| format!("execute: {e}"))?;
+    let trapped = match main.call(&mut store, ()) {
+        Ok(()) => None,
+        Err(e) => match traced_runtime_error(&instance, &mut store, &e, &SourceMap::from_wasm(&wasm)) {
+            Some(err) => Some(relative_paths(&err.to_string())),
+            None => return Err(format!("execute: {e}")),
+        },
+    };
+
+    let out = String::from_utf8_lossy(&env.output.lock().unwrap()).into_owned();
+    Ok((out, trapped))
+}
 
-    let out = env.output.lock().unwrap().clone();
-    Ok(out)
+/// Stack-trace frames name the canonical source path; golden files spell it relative to the crate.
+fn relative_paths(text: &str) -> String {
+    let root = std::env::current_dir().unwrap();
+    text.replace(&format!("{}/", root.display()), "")
 }
 
 #[test]
@@ -150,18 +169,25 @@ fn mir_backend_e2e_coverage() {
             Err(_) => continue, // no golden output to compare against
         };
 
+        let expected_trap = fs::read_to_string(path.with_extension("expected_runtime_error"))
+            .ok()
+            .map(|s| s.trim().to_string());
+
         let is_xfail = xfail.contains(stem.as_str());
         match compile_and_run_mir(&path) {
-            Ok(actual) if actual.trim() == expected.trim() => {
+            Ok((actual, trap)) if actual.trim() == expected.trim() && trap == expected_trap => {
                 if is_xfail {
                     unexpected_pass.push(stem);
                 } else {
                     passed.push(stem);
                 }
             }
-            Ok(actual) => {
+            Ok((actual, trap)) => {
                 if !is_xfail {
-                    failed.push((stem, format!("output mismatch: got {:?}", actual.trim())));
+                    failed.push((
+                        stem,
+                        format!("output mismatch: got {:?}, runtime error {trap:?}", actual.trim()),
+                    ));
                 }
             }
             Err(e) => {
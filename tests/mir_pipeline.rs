@@ -49,6 +49,7 @@ fn compile_sum_to() -> String {
             dream::hir::HLocal { id: acc, name: "acc".into(), ty: int },
         ],
         is_async: false,
+        source: None,
         body: vec![
             HStmt::Let { local: i, ty: int, value: HExpr::new(int, HExprKind::IntLit(0)) },
             HStmt::Let { local: acc, ty: int, value: HExpr::new(int, HExprKind::IntLit(0)) },
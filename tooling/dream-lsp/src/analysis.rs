@@ -4,6 +4,7 @@
 
 use bumpalo::Bump;
 use dream::diagnostics::{DiagnosticBag, Severity};
+use dream::driver::package::PackageGraph;
 use dream::driver::source_loader::collect_declarations;
 use dream::semantics::analyzer::Analyzer;
 use dream::syntax::lexer::Lexer;
@@ -30,6 +31,14 @@ pub const MAIN_FILE: &str = "main.dream";
 /// service and the compiler can never drift (see `dream::stdlib::PRELUDE_FILES`).
 use dream::stdlib::PRELUDE_FILES;
 
+/// The package graph of the `dream.toml` above `file_path`, so imports of dependencies resolve the
+/// way the compiler resolves them. A manifest that fails to load leaves only relative imports; the
+/// lockfile is the compiler's to write, never the editor's.
+pub fn load_packages(file_path: &str) -> Option<PackageGraph> {
+    let dir = std::path::Path::new(file_path).parent()?;
+    PackageGraph::load(&PackageGraph::find_root(dir)?).ok()
+}
+
 /// Runs the full front-end over `text` and returns the diagnostics that belong to the user's
 /// document, with byte spans converted to LSP ranges.
 pub fn collect_diagnostics(file_path: Option<&str>, text: &str) -> Vec<DiagnosticOut> {
@@ -68,23 +77,43 @@ pub fn collect_diagnostics(file_path: Option<&str>, text: &str) -> Vec<Diagnosti
                 .unwrap_or_else(|| std::path::Path::new(""));
             acc.visited.insert(path_str.to_string());
             acc.visited.insert(MAIN_FILE.to_string());
-
-            for import in &program.imports {
-                let module_name = import.module_name.text.as_str();
-                let import_path =
-                    dream::driver::source_loader::resolve_import_path(parent_dir, module_name);
-
-                if let Some(import_path_str) = import_path.to_str() {
-                    if import_path.exists() {
-                        let _ = dream::driver::source_loader::parse_file_recursive(
-                            &import_path_str.to_string(),
-                            &mut acc,
-                            &arena,
-                            &mut diagnostics,
-                        );
-                    }
-                }
+            acc.root_dir = Some(parent_dir.to_path_buf());
+            acc.packages = load_packages(path_str);
+
+            // The document is the entry module; its imports load (and register) the others.
+            let path = std::path::Path::new(path_str);
+            let imports = dream::driver::source_loader::resolve_imports(
+                program,
+                path,
+                acc.packages.as_ref(),
+                &mut diagnostics,
+            );
+            let files: Vec<String> = imports.iter().map(|i| i.file.clone()).collect();
+            let name = acc
+                .packages
+                .as_ref()
+                .and_then(|packages| packages.module_name(path))
+                .unwrap_or_else(|| dream::semantics::modules::module_name(parent_dir, path));
+            acc.modules.add_module(MAIN_FILE, name, imports);
+            for import_path_str in &files {
+                let _ = dream::driver::source_loader::parse_file_recursive(
+                    import_path_str,
+                    &mut acc,
+                    &arena,
+                    &mut diagnostics,
+                );
             }
+            dream::semantics::modules::resolve_modules(
+                &acc.modules,
+                &arena,
+                &mut acc.all_functions,
+                &mut acc.all_structs,
+                &mut acc.all_interfaces,
+                &mut acc.all_enums,
+                &mut acc.all_extends,
+                &mut acc.all_globals,
+                &mut diagnostics,
+            );
         }
     }
 
@@ -152,6 +181,7 @@ pub fn collect_diagnostics(file_path: Option<&str>, text: &str) -> Vec<Diagnosti
 
 /// Parses each embedded prelude file and merges its declarations, tagging them with their
 /// `<std>` path so their diagnostics can be filtered out of the user-facing list.
+#[allow(clippy::too_many_arguments)]
 fn merge_prelude<'a>(
     arena: &'a Bump,
     file_path: Option<&str>,
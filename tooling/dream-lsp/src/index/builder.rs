@@ -6,7 +6,7 @@ use std::collections::HashMap;
 
 use dream::syntax::nodes::struct_node::StructDeclarationNode;
 use dream::syntax::nodes::{
-    ExpressionNode, FunctionNode, PatternNode, ProgramNode, StatementNode, SwitchArmBody, Type,
+    ExpressionNode, FunctionNode, LambdaBody, PatternNode, ProgramNode, StatementNode, SwitchArmBody, Type,
 };
 use dream::syntax::token::syntax_token::SyntaxToken;
 
@@ -143,6 +143,27 @@ impl Builder {
                     .unwrap_or(inner_ty);
                 Some(unwrapped)
             }
+            ExpressionNode::Propagate(inner, _) => {
+                // `?` unwraps `Option<T>` and `Result<T, E>` to their first type argument.
+                let inner_ty = self.infer_type(inner, scope)?;
+                let args = inner_ty
+                    .strip_prefix("Option<")
+                    .or_else(|| inner_ty.strip_prefix("Result<"))?
+                    .strip_suffix('>')?;
+                let mut depth = 0;
+                let end = args
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        match c {
+                            '<' => depth += 1,
+                            '>' => depth -= 1,
+                            _ => {}
+                        }
+                        c == ',' && depth == 0
+                    })
+                    .map_or(args.len(), |(i, _)| i);
+                Some(args[..end].trim().to_string())
+            }
             _ => None,
         }
     }
@@ -381,6 +402,10 @@ impl Builder {
                 }
                 self.walk_expr(expr, scope);
             }
+            StatementNode::DestructuringDeclaration(pattern, expr, _is_const) => {
+                self.walk_expr(expr, scope);
+                self.walk_pattern(pattern, scope);
+            }
             StatementNode::Assignment(name, expr) => {
                 self.add_ref(name, SymKind::Variable, scope);
                 self.walk_expr(expr, scope);
@@ -466,7 +491,25 @@ impl Builder {
                 self.walk_block(body, scope);
             }
             StatementNode::Labeled(_, inner) => self.walk_stmt(inner, scope),
-            StatementNode::AwaitStmt(expr) => self.walk_expr(expr, scope),
+            StatementNode::AwaitStmt(expr) | StatementNode::Throw(_, expr) => self.walk_expr(expr, scope),
+            StatementNode::Try(_, body, catches, finally) => {
+                self.walk_block(body, scope);
+                for clause in catches {
+                    let ty = clause
+                        .type_
+                        .as_ref()
+                        .map(|t| t.display_name())
+                        .unwrap_or_else(|| "object".to_string());
+                    self.push_decl(&clause.name, SymKind::Variable, ty.clone(), scope, Some(ty));
+                    if let Some(t) = &clause.type_ {
+                        self.add_type_ref(t, scope);
+                    }
+                    self.walk_block(clause.body, scope);
+                }
+                if let Some(body) = finally {
+                    self.walk_block(body, scope);
+                }
+            }
             StatementNode::Break(_) | StatementNode::Continue(_) => {}
             StatementNode::Switch(subject, cases, default) => {
                 self.walk_expr(subject, scope);
@@ -577,12 +620,12 @@ impl Builder {
                 self.walk_expr(t, scope);
                 self.walk_expr(e, scope);
             }
-            ExpressionNode::ArrayLiteral(elems) => {
+            ExpressionNode::ArrayLiteral(elems) | ExpressionNode::Tuple(_, elems) => {
                 for elem in elems {
                     self.walk_expr(elem, scope);
                 }
             }
-            ExpressionNode::Await(e) => self.walk_expr(e, scope),
+            ExpressionNode::Await(e) | ExpressionNode::Propagate(e, _) => self.walk_expr(e, scope),
             ExpressionNode::Switch(subject, arms) => {
                 self.walk_expr(subject, scope);
                 for arm in arms {
@@ -596,19 +639,63 @@ impl Builder {
                     }
                 }
             }
+            ExpressionNode::Lambda(lambda) => {
+                // Lambda parameters are indexed in the enclosing scope, like block-local variables.
+                for param in &lambda.parameters {
+                    let ty = param.type_.as_ref().map(|t| t.display_name());
+                    let detail = match &ty {
+                        Some(ty) => format!("(parameter) {}: {}", param.name.text, ty),
+                        None => format!("(parameter) {}", param.name.text),
+                    };
+                    self.push_decl(&param.name, SymKind::Param, detail, scope, ty);
+                    if let Some(t) = &param.type_ {
+                        self.add_type_ref(t, scope);
+                    }
+                }
+                if let Some(rt) = &lambda.return_type {
+                    self.add_type_ref(rt, scope);
+                }
+                match &lambda.body {
+                    LambdaBody::Expr(e) => self.walk_expr(e, scope),
+                    LambdaBody::Block(stmts) => self.walk_block(stmts, scope),
+                }
+            }
             ExpressionNode::Literal(_) => {}
         }
     }
 
     /// Indexes the bindings and variant references introduced by a match pattern so hover, rename,
     /// and go-to work for them. Binding identifiers become local variables; variant names (and an
-    /// optional `Enum.` qualifier) become references.
+    /// optional `Enum.` qualifier), the types of type and class patterns, and the fields a class
+    /// pattern names become references.
     fn walk_pattern(&mut self, pattern: &PatternNode, scope: usize) {
         match pattern {
-            PatternNode::Wildcard(_) | PatternNode::Literal(_) => {}
+            PatternNode::Wildcard(_) | PatternNode::Literal(_) | PatternNode::Range(..) => {}
             PatternNode::Binding(name) => {
                 self.push_decl(name, SymKind::Variable, "binding".to_string(), scope, None);
             }
+            PatternNode::TypeTest(ty, name) => {
+                self.add_type_ref(ty, scope);
+                if name.text != "_" {
+                    self.push_decl(name, SymKind::Variable, "binding".to_string(), scope, None);
+                }
+            }
+            PatternNode::Class(ty, fields) => {
+                self.add_type_ref(ty, scope);
+                for (field, sub) in fields {
+                    // The shorthand `Point { x }` reuses the field token as the binding.
+                    let shorthand = matches!(sub, PatternNode::Binding(b) if b.position == field.position);
+                    if !shorthand {
+                        self.add_ref(field, SymKind::Field, scope);
+                    }
+                    self.walk_pattern(sub, scope);
+                }
+            }
+            PatternNode::Or(alternatives) => {
+                for alternative in alternatives {
+                    self.walk_pattern(alternative, scope);
+                }
+            }
             PatternNode::Variant(qualifier, variant, subs) => {
                 if let Some(q) = qualifier {
                     self.add_ref(q, SymKind::Type, scope);
@@ -618,11 +705,22 @@ impl Builder {
                     self.walk_pattern(sub, scope);
                 }
             }
+            PatternNode::Tuple(_, subs) => {
+                for sub in subs {
+                    self.walk_pattern(sub, scope);
+                }
+            }
         }
     }
 
     fn add_type_ref(&mut self, ty: &Type, scope: usize) {
-        if let Type::Struct(token, _) = base_struct(ty) {
+        let base = base_struct(ty);
+        // A tuple type has no declaration of its own; reference its element types instead.
+        if let Some(elements) = base.tuple_elements() {
+            for element in elements {
+                self.add_type_ref(element, scope);
+            }
+        } else if let Type::Struct(token, _) = base {
             self.add_ref(token, SymKind::Type, scope);
         }
     }
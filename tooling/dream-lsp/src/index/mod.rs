@@ -65,19 +65,20 @@ impl Index {
                 &mut acc.all_extends,
                 &mut scratch,
                 &mut file_contents,
+                None,
             );
 
             if let Some(path_str) = file_path {
-                let parent_dir = std::path::Path::new(path_str)
-                    .parent()
-                    .unwrap_or_else(|| std::path::Path::new(""));
-
                 acc.visited.insert(path_str.to_string());
+                acc.packages = crate::analysis::load_packages(path_str);
 
                 for import in &program.imports {
                     let module_name = import.module_name.text.as_str();
-                    let import_path =
-                        dream::driver::source_loader::resolve_import_path(parent_dir, module_name);
+                    let import_path = dream::driver::source_loader::locate_import(
+                        std::path::Path::new(path_str),
+                        module_name,
+                        acc.packages.as_ref(),
+                    );
 
                     if let Some(import_path_str) = import_path.to_str() {
                         if import_path.exists() {
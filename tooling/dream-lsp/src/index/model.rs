@@ -131,7 +131,7 @@ pub(crate) fn is_ident_byte(b: u8) -> bool {
 }
 
 /// Language keywords offered as completion proposals.
-pub const KEYWORDS: [&str; 37] = [
+pub const KEYWORDS: [&str; 42] = [
     "if",
     "else",
     "for",
@@ -144,6 +144,7 @@ pub const KEYWORDS: [&str; 37] = [
     "const",
     "fun",
     "static",
+    "virtual",
     "import",
     "public",
     "extern",
@@ -154,6 +155,10 @@ pub const KEYWORDS: [&str; 37] = [
     "switch",
     "case",
     "default",
+    "throw",
+    "try",
+    "catch",
+    "finally",
     "is",
     "in",
     "true",
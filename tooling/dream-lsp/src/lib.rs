@@ -1,7 +1,8 @@
 pub mod analysis;
 pub mod backend;
 pub mod conversions;
-pub mod format;
+/// The source formatter behind `textDocument/formatting`, shared with the `dream fmt` command.
+pub use dream::syntax::format;
 pub mod index;
 pub mod position;
 pub mod semantic_tokens;
@@ -89,6 +89,7 @@ pub fn compute(file_path: Option<&str>, text: &str) -> Vec<SemanticToken> {
                 | TokenKind::ConstToken
                 | TokenKind::FunToken
                 | TokenKind::StaticToken
+                | TokenKind::VirtualToken
                 | TokenKind::ImportToken
                 | TokenKind::PublicToken
                 | TokenKind::ExternToken
@@ -101,6 +102,10 @@ pub fn compute(file_path: Option<&str>, text: &str) -> Vec<SemanticToken> {
                 | TokenKind::SwitchToken
                 | TokenKind::CaseToken
                 | TokenKind::DefaultToken
+                | TokenKind::ThrowToken
+                | TokenKind::TryToken
+                | TokenKind::CatchToken
+                | TokenKind::FinallyToken
                 | TokenKind::AsyncToken
                 | TokenKind::AwaitToken => Some(0),
                 TokenKind::DataTypeToken => Some(9),
@@ -32,11 +32,11 @@ pub fn classify(text: &str) -> Vec<TokenOut> {
         // `this` lexes as an identifier but reads as a keyword inside methods; `get`/`set` are
         // contextual accessor keywords, highlighted only in `get <name>(` / `set <name>(` position
         // (so ordinary method calls like `list.get(0)` stay classified as identifiers).
-        let kind = if token.kind == TokenKind::IdentifierToken && token.text == "this" {
-            "keyword"
-        } else if token.kind == TokenKind::IdentifierToken
-            && (token.text == "get" || token.text == "set")
-            && is_accessor_position(&tokens, i)
+        let is_ident = token.kind == TokenKind::IdentifierToken;
+        let kind = if is_ident
+            && (token.text == "this"
+                || ((token.text == "get" || token.text == "set")
+                    && is_accessor_position(&tokens, i)))
         {
             "keyword"
         } else {
@@ -76,9 +76,10 @@ fn category(kind: TokenKind) -> Option<&'static str> {
         StringToken | InterpolatedStringToken | CharToken => "string",
         BooleanToken | NullToken => "keyword",
         IfToken | ElseToken | ForToken | WhileToken | DoToken | ReturnToken | BreakToken
-        | ContinueToken | LetToken | ConstToken | FunToken | StaticToken | ImportToken
+        | ContinueToken | LetToken | ConstToken | FunToken | StaticToken | VirtualToken | ImportToken
         | PublicToken | ExternToken | ClassToken | ExtendToken | IsToken | InToken | EnumToken
-        | TypeToken | SwitchToken | CaseToken | DefaultToken => "keyword",
+        | TypeToken | SwitchToken | CaseToken | DefaultToken | ThrowToken | TryToken | CatchToken
+        | FinallyToken => "keyword",
         PlusToken
         | MinusToken
         | SlashToken
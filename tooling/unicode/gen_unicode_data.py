@@ -0,0 +1,230 @@
+#!/usr/bin/env python3
+"""Generates src/stdlib/text/unicode_data.dream, the Unicode property tables behind the
+stdlib's code-point, case-mapping, normalization and grapheme APIs (src/stdlib/text/unicode.dream).
+
+The tables come from the running Python's `unicodedata` module, so the Unicode version is
+whatever that module reports (it is written into the generated header). Re-run after a Python
+upgrade to move to a newer Unicode version:
+
+    python3 tooling/unicode/gen_unicode_data.py > src/stdlib/text/unicode_data.dream
+
+Each table is one string literal of fixed-width, lowercase-hex records sorted by their first
+field, so the Dream side binary-searches it in place without decoding anything up front.
+"""
+
+import unicodedata
+
+MAX = 0x110000
+
+# Extended_Pictographic (emoji-data.txt) is not exposed by `unicodedata`, so its ranges are kept
+# here by hand. Regional indicators (1F1E6..1F1FF) and skin-tone modifiers (1F3FB..1F3FF) are
+# deliberately absent: they have their own grapheme classes.
+EXTENDED_PICTOGRAPHIC = [
+    (0x00A9, 0x00A9), (0x00AE, 0x00AE), (0x203C, 0x203C), (0x2049, 0x2049),
+    (0x2122, 0x2122), (0x2139, 0x2139), (0x2194, 0x2199), (0x21A9, 0x21AA),
+    (0x231A, 0x231B), (0x2328, 0x2328), (0x2388, 0x2388), (0x23CF, 0x23CF),
+    (0x23E9, 0x23F3), (0x23F8, 0x23FA), (0x24C2, 0x24C2), (0x25AA, 0x25AB),
+    (0x25B6, 0x25B6), (0x25C0, 0x25C0), (0x25FB, 0x25FE), (0x2600, 0x2605),
+    (0x2607, 0x2612), (0x2614, 0x2685), (0x2690, 0x2705), (0x2708, 0x2712),
+    (0x2714, 0x2714), (0x2716, 0x2716), (0x271D, 0x271D), (0x2721, 0x2721),
+    (0x2728, 0x2728), (0x2733, 0x2734), (0x2744, 0x2744), (0x2747, 0x2747),
+    (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755), (0x2757, 0x2757),
+    (0x2763, 0x2767), (0x2795, 0x2797), (0x27A1, 0x27A1), (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF), (0x2934, 0x2935), (0x2B05, 0x2B07), (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x3030, 0x3030), (0x303D, 0x303D),
+    (0x3297, 0x3297), (0x3299, 0x3299), (0x1F000, 0x1F0FF), (0x1F10D, 0x1F10F),
+    (0x1F12F, 0x1F12F), (0x1F16C, 0x1F171), (0x1F17E, 0x1F17F), (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A), (0x1F1AD, 0x1F1E5), (0x1F201, 0x1F20F), (0x1F21A, 0x1F21A),
+    (0x1F22F, 0x1F22F), (0x1F232, 0x1F23A), (0x1F23C, 0x1F23F), (0x1F249, 0x1F3FA),
+    (0x1F400, 0x1F53D), (0x1F546, 0x1F64F), (0x1F680, 0x1F6FF), (0x1F774, 0x1F77F),
+    (0x1F7D5, 0x1F7FF), (0x1F80C, 0x1F80F), (0x1F848, 0x1F84F), (0x1F85A, 0x1F85F),
+    (0x1F888, 0x1F88F), (0x1F8AE, 0x1F8FF), (0x1F90C, 0x1F93A), (0x1F93C, 0x1F945),
+    (0x1F947, 0x1FAFF), (0x1FC00, 0x1FFFD),
+]
+
+# Grapheme_Cluster_Break classes, numbered as `Unicode._grapheme_class` documents them. CR, LF and
+# the Hangul LV/LVT syllables are classified by value in the Dream code, not tabled.
+CONTROL, EXTEND, REGIONAL, PICTOGRAPHIC, ZWJ, L, V, T, PREPEND = range(1, 10)
+
+PREPEND_POINTS = {0x0600, 0x0601, 0x0602, 0x0603, 0x0604, 0x0605, 0x06DD, 0x070F, 0x0890,
+                  0x0891, 0x08E2, 0x110BD, 0x110CD}
+
+
+def case_runs(fn):
+    """Simple (single code point) case mappings as runs of (start, count, stride, delta)."""
+    mapping = {}
+    for cp in range(MAX):
+        mapped = fn(chr(cp))
+        if len(mapped) == 1 and ord(mapped) != cp:
+            mapping[cp] = ord(mapped)
+    runs = []
+    points = sorted(mapping)
+    i = 0
+    while i < len(points):
+        start = points[i]
+        delta = mapping[start] - start
+        contiguous = 1
+        while mapping.get(start + contiguous, None) == start + contiguous + delta:
+            contiguous += 1
+        # Alternating upper/lower pairs (Latin Extended-A and friends) step by two; only when the
+        # code points in between are unmapped, so runs never overlap.
+        alternating = 1
+        while (mapping.get(start + 2 * alternating, None) == start + 2 * alternating + delta
+               and start + 2 * alternating - 1 not in mapping):
+            alternating += 1
+        stride, count = (2, alternating) if alternating > contiguous else (1, contiguous)
+        count = min(count, 0xFF)
+        runs.append((start, count, stride, delta))
+        covered = {start + k * stride for k in range(count)}
+        while i < len(points) and points[i] in covered:
+            i += 1
+    return runs
+
+
+def combining_runs():
+    runs = []
+    for cp in range(MAX):
+        ccc = unicodedata.combining(chr(cp))
+        if ccc == 0:
+            continue
+        if runs and runs[-1][1] == cp - 1 and runs[-1][2] == ccc:
+            runs[-1][1] = cp
+        else:
+            runs.append([cp, cp, ccc])
+    return runs
+
+
+def decompositions():
+    table = []
+    for cp in range(MAX):
+        decomposition = unicodedata.decomposition(chr(cp))
+        if decomposition and not decomposition.startswith("<"):
+            parts = [int(part, 16) for part in decomposition.split()]
+            assert len(parts) <= 2
+            table.append((cp, parts[0], parts[1] if len(parts) == 2 else 0))
+    return table
+
+
+def compositions(decomposed):
+    # Primary composites: pairs whose NFC is the composite (this drops composition exclusions).
+    table = []
+    for cp, a, b in decomposed:
+        if b and unicodedata.normalize("NFC", chr(a) + chr(b)) == chr(cp):
+            table.append((a, b, cp))
+    return sorted(table)
+
+
+def grapheme_class(cp):
+    category = unicodedata.category(chr(cp))
+    if cp == 0x200D:
+        return ZWJ
+    if cp == 0x200C or 0x1F3FB <= cp <= 0x1F3FF or 0xE0020 <= cp <= 0xE007F:
+        return EXTEND
+    if category in ("Mn", "Me", "Mc"):
+        return EXTEND
+    if cp in PREPEND_POINTS:
+        return PREPEND
+    if category in ("Cc", "Zl", "Zp", "Cf"):
+        return CONTROL
+    if 0x1F1E6 <= cp <= 0x1F1FF:
+        return REGIONAL
+    if 0x1100 <= cp <= 0x115F or 0xA960 <= cp <= 0xA97C:
+        return L
+    if 0x1160 <= cp <= 0x11A7 or 0xD7B0 <= cp <= 0xD7C6:
+        return V
+    if 0x11A8 <= cp <= 0x11FF or 0xD7CB <= cp <= 0xD7FB:
+        return T
+    for low, high in EXTENDED_PICTOGRAPHIC:
+        if low <= cp <= high:
+            return PICTOGRAPHIC
+    return 0
+
+
+def grapheme_runs():
+    runs = []
+    for cp in range(MAX):
+        cls = grapheme_class(cp)
+        if cls == 0:
+            continue
+        if runs and runs[-1][1] == cp - 1 and runs[-1][2] == cls:
+            runs[-1][1] = cp
+        else:
+            runs.append([cp, cp, cls])
+    return runs
+
+
+def hex_field(value, width):
+    text = format(value, "0{}x".format(width))
+    assert len(text) == width, (value, width)
+    return text
+
+
+def emit_table(name, doc, records):
+    lines = ["    // {}".format(line) for line in doc]
+    lines.append("    public static fun {}(): string {{".format(name))
+    lines.append('        return "{}";'.format("".join(records)))
+    lines.append("    }")
+    return "\n".join(lines)
+
+
+def main():
+    upper = case_runs(str.upper)
+    lower = case_runs(str.lower)
+    decomposed = decompositions()
+    composed = compositions(decomposed)
+
+    def case_record(run):
+        start, count, stride, delta = run
+        return hex_field(start, 5) + hex_field(count, 2) + hex_field(stride, 1) + hex_field(delta + 0x800000, 6)
+
+    tables = [
+        emit_table(
+            "uppercase",
+            ["Simple uppercase mappings as 14-char runs `start(5) count(2) stride(1) delta(6)`: the",
+             "`count` code points `start + k * stride` map to themselves plus `delta - 0x800000`."],
+            [case_record(run) for run in upper],
+        ),
+        emit_table(
+            "lowercase",
+            ["Simple lowercase mappings, in the same run format as `uppercase()`."],
+            [case_record(run) for run in lower],
+        ),
+        emit_table(
+            "combining_classes",
+            ["Nonzero canonical combining classes as 12-char runs `first(5) last(5) class(2)`."],
+            [hex_field(a, 5) + hex_field(b, 5) + hex_field(c, 2) for a, b, c in combining_runs()],
+        ),
+        emit_table(
+            "decompositions",
+            ["Canonical decompositions (one level) as 15-char records `code point(5) first(5)",
+             "second(5)`, the second `00000` for a singleton. Hangul syllables are computed instead."],
+            [hex_field(cp, 5) + hex_field(a, 5) + hex_field(b, 5) for cp, a, b in decomposed],
+        ),
+        emit_table(
+            "compositions",
+            ["Primary composites as 15-char records `first(5) second(5) composite(5)`, sorted by",
+             "the pair. Excluded compositions are absent."],
+            [hex_field(a, 5) + hex_field(b, 5) + hex_field(c, 5) for a, b, c in composed],
+        ),
+        emit_table(
+            "grapheme_breaks",
+            ["Grapheme_Cluster_Break classes as 11-char runs `first(5) last(5) class(1)`, numbered as",
+             "`Unicode._grapheme_class` documents. Code points in no run are `Other` (0)."],
+            [hex_field(a, 5) + hex_field(b, 5) + hex_field(c, 1) for a, b, c in grapheme_runs()],
+        ),
+    ]
+
+    print("// GENERATED by tooling/unicode/gen_unicode_data.py from Unicode {}; do not edit.".format(
+        unicodedata.unidata_version))
+    print("//")
+    print("// The Unicode property tables behind `Unicode` (unicode.dream). Each is one string of")
+    print("// fixed-width lowercase-hex records sorted by their first field, binary-searched in place.")
+    print("// Only the tables a program reaches are linked into it. Auto-imported into every program.")
+    print()
+    print("class UnicodeData {")
+    print("\n\n".join(tables))
+    print("}")
+
+
+if __name__ == "__main__":
+    main()